@@ -1,42 +1,139 @@
 use std::borrow::Borrow;
 use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
 
 use chia_sha2::Sha256;
 use linked_hash_map::LinkedHashMap;
 use std::sync::Mutex;
 
 use crate::{aggregate_verify_gt, hash_to_g2};
-use crate::{GTElement, PublicKey, Signature};
+use crate::{Error, GTElement, PublicKey, Result, Signature};
+
+/// The maximum size of a [`BlsCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlsCacheCapacity {
+    /// The maximum number of pairings to keep.
+    Entries(NonZeroUsize),
+    /// The approximate maximum amount of memory to use, in bytes. Each entry
+    /// takes [`BlsCache::ENTRY_SIZE`] bytes, and the cache always has room for
+    /// at least one.
+    Bytes(NonZeroUsize),
+}
 
-/// This is a cache of pairings of public keys and their corresponding message.
-/// It accelerates aggregate verification when some public keys have already
-/// been paired, and found in the cache.
-/// We use it to cache pairings when validating transactions inserted into the
-/// mempool, as many of those transactions are likely to show up in a full block
-/// later. This makes it a lot cheaper to validate the full block.
-/// However, validating a signature where we have no cached GT elements, the
-/// aggregate_verify() primitive is faster. When long-syncing, that's
-/// preferable.
+impl Default for BlsCacheCapacity {
+    fn default() -> Self {
+        Self::Entries(NonZeroUsize::new(50_000).unwrap())
+    }
+}
+
+impl BlsCacheCapacity {
+    fn entries(self) -> NonZeroUsize {
+        match self {
+            Self::Entries(entries) => entries,
+            Self::Bytes(bytes) => {
+                NonZeroUsize::new(bytes.get() / BlsCache::ENTRY_SIZE).unwrap_or(NonZeroUsize::MIN)
+            }
+        }
+    }
+}
+
+/// Counters of how a [`BlsCache`] has been used, for monitoring.
+#[cfg_attr(
+    feature = "py-bindings",
+    pyo3::pyclass(name = "BLSCacheStats", get_all, frozen)
+)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlsCacheStats {
+    /// The number of pairings that were found in the cache.
+    pub hits: u64,
+    /// The number of pairings that had to be computed.
+    pub misses: u64,
+    /// The number of entries that were dropped to make room for new ones, or
+    /// because they expired. Entries removed by `evict()` aren't counted.
+    pub evictions: u64,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    pairing: GTElement,
+    inserted: Instant,
+}
 
 #[derive(Debug, Clone)]
 struct BlsCacheData {
-    // sha256(pubkey + message) -> GTElement
-    items: LinkedHashMap<[u8; 32], GTElement>,
+    // sha256(pubkey + message) -> GTElement, in insertion order
+    items: LinkedHashMap<[u8; 32], CacheEntry>,
     capacity: NonZeroUsize,
+    ttl: Option<Duration>,
+    stats: BlsCacheStats,
 }
 
 impl BlsCacheData {
+    fn is_expired(&self, entry: &CacheEntry, now: Instant) -> bool {
+        self.ttl
+            .is_some_and(|ttl| now.saturating_duration_since(entry.inserted) >= ttl)
+    }
+
+    pub fn get(&mut self, hash: &[u8; 32]) -> Option<GTElement> {
+        let now = Instant::now();
+        let expired = match self.items.get(hash) {
+            Some(entry) if !self.is_expired(entry, now) => {
+                self.stats.hits += 1;
+                return Some(entry.pairing.clone());
+            }
+            Some(_) => true,
+            None => false,
+        };
+        if expired {
+            self.items.remove(hash);
+            self.stats.evictions += 1;
+        }
+        self.stats.misses += 1;
+        None
+    }
+
     pub fn put(&mut self, hash: [u8; 32], pairing: GTElement) {
-        // If the cache is full, remove the oldest item.
-        if self.items.len() == self.capacity.get() {
-            if let Some((oldest_key, _)) = self.items.pop_front() {
-                self.items.remove(&oldest_key);
+        let now = Instant::now();
+
+        // Every entry has the same time to live, and updating an entry moves
+        // it to the back, so the expired entries are all at the front.
+        while let Some((_, oldest)) = self.items.front() {
+            if !self.is_expired(oldest, now) {
+                break;
             }
+            self.items.pop_front();
+            self.stats.evictions += 1;
+        }
+
+        self.items.insert(
+            hash,
+            CacheEntry {
+                pairing,
+                inserted: now,
+            },
+        );
+
+        // If the cache is full, remove the oldest items.
+        while self.items.len() > self.capacity.get() {
+            self.items.pop_front();
+            self.stats.evictions += 1;
         }
-        self.items.insert(hash, pairing);
     }
 }
 
+/// This is a cache of pairings of public keys and their corresponding message.
+/// It accelerates aggregate verification when some public keys have already
+/// been paired, and found in the cache.
+/// We use it to cache pairings when validating transactions inserted into the
+/// mempool, as many of those transactions are likely to show up in a full block
+/// later. This makes it a lot cheaper to validate the full block.
+/// However, validating a signature where we have no cached GT elements, the
+/// aggregate_verify() primitive is faster. When long-syncing, that's
+/// preferable.
+///
+/// When the cache is full, the entry that was inserted (or updated) the
+/// longest time ago is evicted first. Entries can also be given a time to
+/// live, after which they're no longer used.
 #[cfg_attr(feature = "py-bindings", pyo3::pyclass(name = "BLSCache"))]
 #[derive(Debug)]
 pub struct BlsCache {
@@ -45,7 +142,7 @@ pub struct BlsCache {
 
 impl Default for BlsCache {
     fn default() -> Self {
-        Self::new(NonZeroUsize::new(50_000).unwrap())
+        Self::with_policy(BlsCacheCapacity::default(), None)
     }
 }
 
@@ -58,15 +155,38 @@ impl Clone for BlsCache {
 }
 
 impl BlsCache {
+    /// The approximate amount of memory used by each entry: the key, the
+    /// pairing, its timestamp and the bookkeeping of the map.
+    pub const ENTRY_SIZE: usize =
+        std::mem::size_of::<([u8; 32], CacheEntry)>() + 4 * std::mem::size_of::<usize>();
+
+    /// The version of the format written by `serialize()`.
+    pub const SERIALIZATION_VERSION: u8 = 1;
+
+    const SERIALIZED_ENTRY_SIZE: usize = 32 + GTElement::SIZE;
+
     pub fn new(capacity: NonZeroUsize) -> Self {
+        Self::with_policy(BlsCacheCapacity::Entries(capacity), None)
+    }
+
+    /// Creates a cache of the given capacity. If `ttl` is set, entries that
+    /// were inserted longer ago than that are treated as missing, and are
+    /// removed.
+    pub fn with_policy(capacity: BlsCacheCapacity, ttl: Option<Duration>) -> Self {
         Self {
             cache: Mutex::new(BlsCacheData {
                 items: LinkedHashMap::new(),
-                capacity,
+                capacity: capacity.entries(),
+                ttl,
+                stats: BlsCacheStats::default(),
             }),
         }
     }
 
+    pub fn stats(&self) -> BlsCacheStats {
+        self.cache.lock().expect("cache").stats
+    }
+
     pub fn len(&self) -> usize {
         self.cache.lock().expect("cache").items.len()
     }
@@ -89,7 +209,7 @@ impl BlsCache {
             let hash: [u8; 32] = hasher.finalize();
 
             // If the pairing is in the cache, we don't need to recalculate it.
-            if let Some(pairing) = self.cache.lock().expect("cache").items.get(&hash).cloned() {
+            if let Some(pairing) = self.cache.lock().expect("cache").get(&hash) {
                 return pairing;
             }
 
@@ -126,6 +246,68 @@ impl BlsCache {
             c.items.remove(&hash);
         }
     }
+
+    /// Saves the entries of the cache, to be restored with `deserialize()`,
+    /// e.g. after a restart. The format is a version byte, followed by the
+    /// entries from the oldest to the newest, each as the 32 byte key and the
+    /// pairing in the form of `GTElement::to_canonical_bytes()`. The time the
+    /// entries were inserted and the stats are not included.
+    pub fn serialize(&self) -> Vec<u8> {
+        let c = self.cache.lock().expect("cache");
+        let mut ret = Vec::with_capacity(1 + c.items.len() * Self::SERIALIZED_ENTRY_SIZE);
+        ret.push(Self::SERIALIZATION_VERSION);
+        for (key, entry) in &c.items {
+            ret.extend_from_slice(key);
+            ret.extend_from_slice(&entry.pairing.to_canonical_bytes());
+        }
+        ret
+    }
+
+    /// Restores a cache from the output of `serialize()`. The entries are
+    /// treated as freshly inserted. If there are more than fit in `capacity`,
+    /// the newest ones are kept.
+    ///
+    /// The input must come from a trusted source, e.g. a file written by this
+    /// node. Each pairing is checked to be a valid `GTElement`, but it can't
+    /// be checked against its key, since that's a hash of the public key and
+    /// message. A tampered cache can make `aggregate_verify()` accept invalid
+    /// signatures.
+    pub fn deserialize(bytes: &[u8], capacity: BlsCacheCapacity) -> Result<Self> {
+        let Some((&version, entries)) = bytes.split_first() else {
+            return Err(Error::InvalidCacheData);
+        };
+        if version != Self::SERIALIZATION_VERSION {
+            return Err(Error::UnsupportedCacheVersion(version));
+        }
+        if entries.len() % Self::SERIALIZED_ENTRY_SIZE != 0 {
+            return Err(Error::InvalidCacheData);
+        }
+
+        let cache = Self::with_policy(capacity, None);
+        let mut c = cache.cache.lock().expect("cache");
+
+        // there's no need to validate the entries that would be evicted
+        let count = entries.len() / Self::SERIALIZED_ENTRY_SIZE;
+        let skip = count.saturating_sub(c.capacity.get());
+        for entry in entries.chunks_exact(Self::SERIALIZED_ENTRY_SIZE).skip(skip) {
+            let (key, pairing) = entry.split_at(32);
+            let pairing = GTElement::from_canonical_bytes(pairing.try_into().unwrap())?;
+            c.put(key.try_into().unwrap(), pairing);
+        }
+        c.stats = BlsCacheStats::default();
+
+        drop(c);
+        Ok(cache)
+    }
+
+    /// Like `evict()`, but takes the keys of the entries, i.e. the sha256 of
+    /// the public key and message.
+    pub fn evict_hashes<H: Borrow<[u8; 32]>>(&self, hashes: impl IntoIterator<Item = H>) {
+        let mut c = self.cache.lock().expect("cache");
+        for hash in hashes {
+            c.items.remove(hash.borrow());
+        }
+    }
 }
 
 #[cfg(feature = "py-bindings")]
@@ -136,23 +318,52 @@ use pyo3::{
     Bound, PyObject, PyResult,
 };
 
+#[cfg(feature = "py-bindings")]
+fn py_capacity(size: Option<u32>, size_bytes: Option<u64>) -> PyResult<BlsCacheCapacity> {
+    let capacity = match (size, size_bytes) {
+        (Some(_), Some(_)) => {
+            return Err(PyValueError::new_err(
+                "Cannot specify both size and size_bytes.",
+            ));
+        }
+        (Some(size), None) => {
+            let Some(size) = NonZeroUsize::new(size as usize) else {
+                return Err(PyValueError::new_err(
+                    "Cannot have a cache size less than one.",
+                ));
+            };
+            BlsCacheCapacity::Entries(size)
+        }
+        (None, Some(size_bytes)) => {
+            let Some(size_bytes) = usize::try_from(size_bytes).ok().and_then(NonZeroUsize::new)
+            else {
+                return Err(PyValueError::new_err("Invalid cache size in bytes."));
+            };
+            BlsCacheCapacity::Bytes(size_bytes)
+        }
+        (None, None) => BlsCacheCapacity::default(),
+    };
+    Ok(capacity)
+}
+
 #[cfg(feature = "py-bindings")]
 #[pyo3::pymethods]
 impl BlsCache {
     #[new]
-    #[pyo3(signature = (size=None))]
-    pub fn init(size: Option<u32>) -> PyResult<Self> {
-        let Some(size) = size else {
-            return Ok(Self::default());
-        };
-
-        let Some(size) = NonZeroUsize::new(size as usize) else {
-            return Err(PyValueError::new_err(
-                "Cannot have a cache size less than one.",
-            ));
+    #[pyo3(signature = (size=None, *, size_bytes=None, ttl=None))]
+    pub fn init(size: Option<u32>, size_bytes: Option<u64>, ttl: Option<f64>) -> PyResult<Self> {
+        let capacity = py_capacity(size, size_bytes)?;
+
+        let ttl = match ttl {
+            Some(ttl) if ttl > 0.0 => Some(
+                Duration::try_from_secs_f64(ttl)
+                    .map_err(|_| PyValueError::new_err("Invalid ttl."))?,
+            ),
+            Some(_) => return Err(PyValueError::new_err("The ttl must be positive.")),
+            None => None,
         };
 
-        Ok(Self::new(size))
+        Ok(Self::with_policy(capacity, ttl))
     }
 
     #[pyo3(name = "aggregate_verify")]
@@ -189,7 +400,7 @@ impl BlsCache {
         for (key, value) in &c.items {
             ret.append((
                 PyBytes::new(py, key),
-                value.clone().into_pyobject(py)?.into_any(),
+                value.pairing.clone().into_pyobject(py)?.into_any(),
             ))?;
         }
         Ok(ret.into())
@@ -222,6 +433,40 @@ impl BlsCache {
         self.evict(pks.into_iter().zip(msgs));
         Ok(())
     }
+
+    #[pyo3(name = "evict_hashes")]
+    pub fn py_evict_hashes(&self, hashes: &Bound<'_, PySequence>) -> PyResult<()> {
+        let hashes = hashes
+            .try_iter()?
+            .map(|item| {
+                let hash: Vec<u8> = item?.extract()?;
+                <[u8; 32]>::try_from(hash).map_err(|_| PyValueError::new_err("invalid key"))
+            })
+            .collect::<PyResult<Vec<[u8; 32]>>>()?;
+        self.evict_hashes(hashes);
+        Ok(())
+    }
+
+    #[pyo3(name = "stats")]
+    pub fn py_stats(&self) -> BlsCacheStats {
+        self.stats()
+    }
+
+    #[pyo3(name = "serialize")]
+    pub fn py_serialize<'p>(&self, py: pyo3::Python<'p>) -> Bound<'p, pyo3::types::PyBytes> {
+        pyo3::types::PyBytes::new(py, &self.serialize())
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "deserialize", signature = (data, size=None, *, size_bytes=None))]
+    pub fn py_deserialize(
+        data: &[u8],
+        size: Option<u32>,
+        size_bytes: Option<u64>,
+    ) -> PyResult<Self> {
+        let capacity = py_capacity(size, size_bytes)?;
+        Ok(Self::deserialize(data, capacity)?)
+    }
 }
 
 #[cfg(test)]
@@ -391,4 +636,279 @@ pub mod tests {
                 .contains_key(&hash));
         }
     }
+
+    fn pk_msg_hash(pk: &PublicKey, msg: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(pk.to_bytes());
+        hasher.update(msg);
+        hasher.finalize()
+    }
+
+    #[test]
+    fn test_evict_hashes() {
+        let bls_cache = BlsCache::default();
+        let sk = SecretKey::from_seed(&[1; 32]);
+        let pk = sk.public_key();
+        let msgs = [[1; 32], [2; 32]];
+        for msg in msgs {
+            assert!(bls_cache.aggregate_verify([(pk, msg)], &sign(&sk, msg)));
+        }
+        assert_eq!(bls_cache.len(), 2);
+
+        bls_cache.evict_hashes([pk_msg_hash(&pk, &msgs[0])]);
+        assert_eq!(bls_cache.len(), 1);
+        let c = bls_cache.cache.lock().expect("cache");
+        assert!(c.items.contains_key(&pk_msg_hash(&pk, &msgs[1])));
+
+        // explicit evictions aren't counted in the stats
+        assert_eq!(c.stats.evictions, 0);
+    }
+
+    #[test]
+    fn test_capacity_bytes() {
+        let capacity = NonZeroUsize::new(BlsCache::ENTRY_SIZE * 3 + 1).unwrap();
+        let bls_cache = BlsCache::with_policy(BlsCacheCapacity::Bytes(capacity), None);
+        for i in 1..=5 {
+            let sk = SecretKey::from_seed(&[i; 32]);
+            let msg = [106; 32];
+            assert!(bls_cache.aggregate_verify([(sk.public_key(), msg)], &sign(&sk, msg)));
+        }
+        assert_eq!(bls_cache.len(), 3);
+        assert_eq!(
+            bls_cache.stats(),
+            BlsCacheStats {
+                hits: 0,
+                misses: 5,
+                evictions: 2,
+            }
+        );
+
+        // there's always room for at least one entry
+        let capacity = NonZeroUsize::new(1).unwrap();
+        let bls_cache = BlsCache::with_policy(BlsCacheCapacity::Bytes(capacity), None);
+        let sk = SecretKey::from_seed(&[1; 32]);
+        let msg = [106; 32];
+        assert!(bls_cache.aggregate_verify([(sk.public_key(), msg)], &sign(&sk, msg)));
+        assert_eq!(bls_cache.len(), 1);
+    }
+
+    #[test]
+    fn test_stats() {
+        let bls_cache = BlsCache::default();
+        let sk = SecretKey::from_seed(&[1; 32]);
+        let pk = sk.public_key();
+        let msg1 = [1; 32];
+        let msg2 = [2; 32];
+        let sig = sign(&sk, msg1) + &sign(&sk, msg2);
+
+        assert!(bls_cache.aggregate_verify([(pk, msg1)], &sign(&sk, msg1)));
+        assert!(bls_cache.aggregate_verify([(pk, msg1), (pk, msg2)], &sig));
+        assert!(bls_cache.aggregate_verify([(pk, msg1), (pk, msg2)], &sig));
+        assert_eq!(
+            bls_cache.stats(),
+            BlsCacheStats {
+                hits: 3,
+                misses: 2,
+                evictions: 0,
+            }
+        );
+
+        // updating an existing entry doesn't evict anything
+        let bls_cache = BlsCache::new(NonZeroUsize::new(1).unwrap());
+        let aug_msg = [pk.to_bytes().as_slice(), &msg1].concat();
+        let pairing = hash_to_g2(&aug_msg).pair(&pk);
+        bls_cache.update(&aug_msg, pairing.clone());
+        bls_cache.update(&aug_msg, pairing);
+        assert_eq!(bls_cache.len(), 1);
+        assert_eq!(bls_cache.stats().evictions, 0);
+    }
+
+    #[test]
+    fn test_ttl() {
+        let ttl = Duration::from_millis(100);
+        let bls_cache = BlsCache::with_policy(BlsCacheCapacity::default(), Some(ttl));
+        let sk = SecretKey::from_seed(&[1; 32]);
+        let pk = sk.public_key();
+        let msg1 = [1; 32];
+        let msg2 = [2; 32];
+
+        assert!(bls_cache.aggregate_verify([(pk, msg1)], &sign(&sk, msg1)));
+        assert!(bls_cache.aggregate_verify([(pk, msg1)], &sign(&sk, msg1)));
+        assert_eq!(bls_cache.stats().hits, 1);
+
+        std::thread::sleep(ttl);
+
+        // the expired entry is a miss, and is replaced by a fresh one
+        assert!(bls_cache.aggregate_verify([(pk, msg1)], &sign(&sk, msg1)));
+        assert_eq!(
+            bls_cache.stats(),
+            BlsCacheStats {
+                hits: 1,
+                misses: 2,
+                evictions: 1,
+            }
+        );
+        assert_eq!(bls_cache.len(), 1);
+
+        std::thread::sleep(ttl);
+
+        // expired entries are also removed when new ones are inserted
+        assert!(bls_cache.aggregate_verify([(pk, msg2)], &sign(&sk, msg2)));
+        assert_eq!(bls_cache.len(), 1);
+        assert_eq!(bls_cache.stats().evictions, 2);
+        assert!(bls_cache
+            .cache
+            .lock()
+            .expect("cache")
+            .items
+            .contains_key(&pk_msg_hash(&pk, &msg2)));
+    }
+
+    #[test]
+    fn test_serialize() {
+        let bls_cache = BlsCache::default();
+        let keys: Vec<(SecretKey, [u8; 32])> = (1..=3)
+            .map(|i| (SecretKey::from_seed(&[i; 32]), [i; 32]))
+            .collect();
+        let mut sig = Signature::default();
+        for (sk, msg) in &keys {
+            sig += &sign(sk, msg);
+        }
+        let pks_msgs: Vec<(PublicKey, [u8; 32])> = keys
+            .iter()
+            .map(|(sk, msg)| (sk.public_key(), *msg))
+            .collect();
+        assert!(bls_cache.aggregate_verify(pks_msgs.iter().copied(), &sig));
+
+        let bytes = bls_cache.serialize();
+        assert_eq!(bytes.len(), 1 + 3 * (32 + GTElement::SIZE));
+        assert_eq!(bytes[0], BlsCache::SERIALIZATION_VERSION);
+
+        let restored = BlsCache::deserialize(&bytes, BlsCacheCapacity::default()).unwrap();
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored.stats(), BlsCacheStats::default());
+        assert_eq!(restored.serialize(), bytes);
+
+        // all pairings are found in the restored cache
+        assert!(restored.aggregate_verify(pks_msgs.iter().copied(), &sig));
+        assert_eq!(
+            restored.stats(),
+            BlsCacheStats {
+                hits: 3,
+                misses: 0,
+                evictions: 0,
+            }
+        );
+
+        // only the newest entries are kept if they don't all fit
+        let restored = BlsCache::deserialize(
+            &bytes,
+            BlsCacheCapacity::Entries(NonZeroUsize::new(2).unwrap()),
+        )
+        .unwrap();
+        assert_eq!(restored.len(), 2);
+        // the missing pairing is looked up last, since inserting it evicts the
+        // oldest entry
+        assert!(restored.aggregate_verify(pks_msgs.iter().rev().copied(), &sig));
+        assert_eq!(restored.stats().hits, 2);
+        assert_eq!(restored.stats().misses, 1);
+
+        // an empty cache
+        let bytes = BlsCache::default().serialize();
+        assert_eq!(bytes, [BlsCache::SERIALIZATION_VERSION]);
+        let restored = BlsCache::deserialize(&bytes, BlsCacheCapacity::default()).unwrap();
+        assert!(restored.is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_invalid() {
+        let bls_cache = BlsCache::default();
+        let sk = SecretKey::from_seed(&[1; 32]);
+        let msg = [1; 32];
+        assert!(bls_cache.aggregate_verify([(sk.public_key(), msg)], &sign(&sk, msg)));
+        let bytes = bls_cache.serialize();
+        let capacity = BlsCacheCapacity::default();
+
+        assert_eq!(
+            BlsCache::deserialize(&[], capacity).unwrap_err(),
+            Error::InvalidCacheData
+        );
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[0] = 2;
+        assert_eq!(
+            BlsCache::deserialize(&wrong_version, capacity).unwrap_err(),
+            Error::UnsupportedCacheVersion(2)
+        );
+
+        assert_eq!(
+            BlsCache::deserialize(&bytes[..bytes.len() - 1], capacity).unwrap_err(),
+            Error::InvalidCacheData
+        );
+
+        let mut corrupt = bytes.clone();
+        *corrupt.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            BlsCache::deserialize(&corrupt, capacity).unwrap_err(),
+            Error::InvalidGTElement
+        );
+    }
+
+    #[test]
+    fn test_concurrent_evict() {
+        const THREADS: usize = 4;
+        const ROUNDS: usize = 25;
+
+        let bls_cache = BlsCache::new(NonZeroUsize::new(6).unwrap());
+        let keys: Vec<(SecretKey, PublicKey, [u8; 32])> = (0..10_u8)
+            .map(|i| {
+                let sk = SecretKey::from_seed(&[i; 32]);
+                let pk = sk.public_key();
+                (sk, pk, [i; 32])
+            })
+            .collect();
+
+        std::thread::scope(|s| {
+            for t in 0..THREADS {
+                let bls_cache = &bls_cache;
+                let keys = &keys;
+                s.spawn(move || {
+                    for round in 0..ROUNDS {
+                        let first = (t + round) % keys.len();
+                        let second = (t + round * 3 + 1) % keys.len();
+                        let mut sig = Signature::default();
+                        let mut pks_msgs = Vec::new();
+                        for i in [first, second] {
+                            let (sk, pk, msg) = &keys[i];
+                            sig += &sign(sk, msg);
+                            pks_msgs.push((pk, msg));
+                        }
+                        assert!(bls_cache.aggregate_verify(pks_msgs.iter().copied(), &sig));
+
+                        // a wrong signature must never verify, cached or not
+                        let (sk, _, msg) = &keys[(first + 5) % keys.len()];
+                        let bad_sig = sign(sk, msg);
+                        assert!(!bls_cache.aggregate_verify(pks_msgs.iter().copied(), &bad_sig));
+                    }
+                });
+            }
+
+            s.spawn(|| {
+                for round in 0..(THREADS * ROUNDS) {
+                    let (_, pk, msg) = &keys[round % keys.len()];
+                    if round % 2 == 0 {
+                        bls_cache.evict([(pk, msg)]);
+                    } else {
+                        bls_cache.evict_hashes([pk_msg_hash(pk, msg)]);
+                    }
+                    std::thread::yield_now();
+                }
+            });
+        });
+
+        assert!(bls_cache.len() <= 6);
+        let stats = bls_cache.stats();
+        let lookups = u64::try_from(THREADS * ROUNDS * 2 * 2).unwrap();
+        assert_eq!(stats.hits + stats.misses, lookups);
+    }
 }
@@ -15,6 +15,20 @@ pub enum Error {
     InvalidPublicKey(BLST_ERROR),
     #[error("Signature is invalid (BLST ERROR: {0:?})")]
     InvalidSignature(BLST_ERROR),
+    #[error("GTElement is not a valid element of the target group")]
+    InvalidGTElement,
+    #[error("Unsupported BLSCache serialization version {0}")]
+    UnsupportedCacheVersion(u8),
+    #[error("BLSCache data has an invalid length")]
+    InvalidCacheData,
+    #[error("Mnemonic must have 12, 15, 18, 21 or 24 words, got {0}")]
+    InvalidMnemonicLength(usize),
+    #[error("Mnemonic contains a word that's not in the wordlist: {0}")]
+    UnknownMnemonicWord(String),
+    #[error("Mnemonic has an invalid checksum")]
+    InvalidMnemonicChecksum,
+    #[error("Mnemonic entropy must be 16, 20, 24, 28 or 32 bytes, got {0}")]
+    InvalidEntropyLength(usize),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -1,7 +1,7 @@
+use crate::{Error, Result};
 use blst::*;
 use chia_sha2::Sha256;
-use chia_traits::chia_error::Result;
-use chia_traits::{read_bytes, Streamable};
+use chia_traits::{bytes_summary, diff_leaf, read_bytes, FieldDiff, Streamable, StreamableDiff};
 #[cfg(feature = "py-bindings")]
 use pyo3::exceptions::PyNotImplementedError;
 #[cfg(feature = "py-bindings")]
@@ -11,6 +11,7 @@ use pyo3::types::PyType;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::io::Cursor;
+use std::iter::Product;
 use std::mem::MaybeUninit;
 use std::ops::{Mul, MulAssign};
 
@@ -23,8 +24,12 @@ use std::ops::{Mul, MulAssign};
 pub struct GTElement(pub(crate) blst_fp12);
 
 impl GTElement {
-    const SIZE: usize = std::mem::size_of::<blst_fp12>();
+    /// The size of both the raw and the canonical serialization: 12
+    /// coefficients in Fp of 48 bytes each.
+    pub const SIZE: usize = std::mem::size_of::<blst_fp12>();
 
+    /// Creates an element from the output of `to_bytes()`. The bytes are not
+    /// validated, so they must come from a trusted source.
     pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Self {
         let gt = unsafe {
             let mut gt = MaybeUninit::<blst_fp12>::uninit();
@@ -34,6 +39,11 @@ impl GTElement {
         Self(gt)
     }
 
+    /// The in-memory representation used by blst: each coefficient is stored
+    /// in Montgomery form, as 6 64-bit limbs in the byte order of the host
+    /// (little-endian on all supported platforms). This is compatible with
+    /// previous versions, but it's not portable. Use `to_canonical_bytes()`
+    /// for data that's persisted or sent to other machines.
     pub fn to_bytes(&self) -> [u8; Self::SIZE] {
         unsafe {
             let mut bytes = MaybeUninit::<[u8; Self::SIZE]>::uninit();
@@ -46,6 +56,60 @@ impl GTElement {
             bytes.assume_init()
         }
     }
+
+    /// Serializes the 12 coefficients in Fp as 48 bytes big-endian each, in
+    /// the order c0.c0.c0, c0.c0.c1, c0.c1.c0, ..., c1.c2.c1 (Fp12 over Fp6
+    /// over Fp2 over Fp).
+    pub fn to_canonical_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        let coefficients = self
+            .0
+            .fp6
+            .iter()
+            .flat_map(|fp6| fp6.fp2.iter())
+            .flat_map(|fp2| fp2.fp.iter());
+        for (fp, out) in coefficients.zip(bytes.chunks_exact_mut(48)) {
+            unsafe {
+                blst_bendian_from_fp(out.as_mut_ptr(), fp);
+            }
+        }
+        bytes
+    }
+
+    /// Parses the output of `to_canonical_bytes()`. Fails if a coefficient
+    /// isn't reduced modulo p, or if the element is not in the target group.
+    pub fn from_canonical_bytes(bytes: &[u8; Self::SIZE]) -> Result<Self> {
+        let mut gt = blst_fp12::default();
+        let coefficients = gt
+            .fp6
+            .iter_mut()
+            .flat_map(|fp6| fp6.fp2.iter_mut())
+            .flat_map(|fp2| fp2.fp.iter_mut());
+        for (fp, chunk) in coefficients.zip(bytes.chunks_exact(48)) {
+            // values that aren't reduced don't survive the round trip
+            let mut reduced = [0_u8; 48];
+            unsafe {
+                blst_fp_from_bendian(fp, chunk.as_ptr());
+                blst_bendian_from_fp(reduced.as_mut_ptr(), fp);
+            }
+            if reduced != chunk {
+                return Err(Error::InvalidGTElement);
+            }
+        }
+        // zero is not invertible, and the group check alone may accept it
+        let is_zero = bytes.iter().all(|b| *b == 0);
+        if is_zero || !unsafe { blst_fp12_in_group(&gt) } {
+            return Err(Error::InvalidGTElement);
+        }
+        Ok(Self(gt))
+    }
+}
+
+/// The identity element.
+impl Default for GTElement {
+    fn default() -> Self {
+        Self(unsafe { *blst_fp12_one() })
+    }
 }
 
 impl PartialEq for GTElement {
@@ -81,6 +145,24 @@ impl Mul<&GTElement> for &GTElement {
     }
 }
 
+impl<'a> Product<&'a GTElement> for GTElement {
+    fn product<I: Iterator<Item = &'a GTElement>>(iter: I) -> Self {
+        iter.fold(Self::default(), |mut acc, gt| {
+            acc *= gt;
+            acc
+        })
+    }
+}
+
+impl Product for GTElement {
+    fn product<I: Iterator<Item = GTElement>>(iter: I) -> Self {
+        iter.fold(Self::default(), |mut acc, gt| {
+            acc *= &gt;
+            acc
+        })
+    }
+}
+
 impl fmt::Debug for GTElement {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         formatter.write_fmt(format_args!(
@@ -95,18 +177,28 @@ impl Streamable for GTElement {
         digest.update(self.to_bytes());
     }
 
-    fn stream(&self, out: &mut Vec<u8>) -> Result<()> {
+    fn stream(&self, out: &mut Vec<u8>) -> chia_traits::Result<()> {
         out.extend_from_slice(&self.to_bytes());
         Ok(())
     }
 
-    fn parse<const TRUSTED: bool>(input: &mut Cursor<&[u8]>) -> Result<Self> {
+    fn parse<const TRUSTED: bool>(input: &mut Cursor<&[u8]>) -> chia_traits::Result<Self> {
         Ok(GTElement::from_bytes(
             read_bytes(input, Self::SIZE)?.try_into().unwrap(),
         ))
     }
 }
 
+impl StreamableDiff for GTElement {
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+        diff_leaf(self, other, path, out);
+    }
+
+    fn summary(&self) -> String {
+        bytes_summary(&self.to_bytes())
+    }
+}
+
 #[cfg(feature = "py-bindings")]
 #[pyo3::pymethods]
 impl GTElement {
@@ -136,14 +228,31 @@ impl GTElement {
     pub fn __imul__(&mut self, rhs: &Self) {
         *self *= rhs;
     }
+
+    #[pyo3(name = "to_canonical_bytes")]
+    pub fn py_to_canonical_bytes<'p>(&self, py: Python<'p>) -> Bound<'p, pyo3::types::PyBytes> {
+        pyo3::types::PyBytes::new(py, &self.to_canonical_bytes())
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_canonical_bytes")]
+    pub fn py_from_canonical_bytes(data: &[u8]) -> PyResult<Self> {
+        let bytes = data.try_into().map_err(|_| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "GTElement::from_canonical_bytes() expects {} bytes",
+                Self::SIZE
+            ))
+        })?;
+        Ok(Self::from_canonical_bytes(bytes)?)
+    }
 }
 
 #[cfg(feature = "py-bindings")]
 mod pybindings {
     use super::*;
 
-    use crate::parse_hex::parse_hex_string;
-    use chia_traits::{FromJsonDict, ToJsonDict};
+    use crate::parse_hex::{parse_hex_string, parse_hex_value};
+    use chia_traits::{FromJsonDict, JsonValue, ToJsonDict};
 
     impl ToJsonDict for GTElement {
         fn to_json_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
@@ -164,5 +273,98 @@ mod pybindings {
                     .unwrap(),
             ))
         }
+
+        fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+            Ok(Self::from_bytes(
+                parse_hex_value(v, Self::SIZE, "GTElement")?
+                    .as_slice()
+                    .try_into()
+                    .unwrap(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hash_to_g2, sign, PublicKey, SecretKey, Signature};
+
+    fn pairing(i: u8) -> GTElement {
+        let sk = SecretKey::from_seed(&[i; 32]);
+        let pk = sk.public_key();
+        let aug_msg = [pk.to_bytes().as_slice(), &[i; 32]].concat();
+        hash_to_g2(&aug_msg).pair(&pk)
+    }
+
+    #[test]
+    fn test_canonical_round_trip() {
+        for i in 0..4 {
+            let gt = pairing(i);
+            let bytes = gt.to_canonical_bytes();
+            assert_eq!(GTElement::from_canonical_bytes(&bytes).unwrap(), gt);
+        }
+
+        let identity = GTElement::default();
+        let bytes = identity.to_canonical_bytes();
+        // the identity is 1 in the first coefficient, and 0 everywhere else
+        assert_eq!(bytes[47], 1);
+        assert_eq!(bytes.iter().filter(|b| **b != 0).count(), 1);
+        assert_eq!(GTElement::from_canonical_bytes(&bytes).unwrap(), identity);
+    }
+
+    #[test]
+    fn test_canonical_invalid() {
+        // a coefficient that's not reduced modulo p
+        let mut bytes = pairing(1).to_canonical_bytes();
+        bytes[..48].fill(0xff);
+        assert_eq!(
+            GTElement::from_canonical_bytes(&bytes),
+            Err(Error::InvalidGTElement)
+        );
+
+        // a valid element of Fp12, but not in the target group
+        let mut bytes = pairing(1).to_canonical_bytes();
+        bytes[GTElement::SIZE - 1] ^= 1;
+        assert_eq!(
+            GTElement::from_canonical_bytes(&bytes),
+            Err(Error::InvalidGTElement)
+        );
+
+        assert_eq!(
+            GTElement::from_canonical_bytes(&[0; GTElement::SIZE]),
+            Err(Error::InvalidGTElement)
+        );
+    }
+
+    #[test]
+    fn test_product() {
+        let pairings: Vec<GTElement> = (1..=3).map(pairing).collect();
+        assert_eq!(
+            pairings.iter().product::<GTElement>(),
+            &(&pairings[0] * &pairings[1]) * &pairings[2]
+        );
+        assert_eq!(pairings.into_iter().product::<GTElement>(), {
+            let mut gt = pairing(1);
+            gt *= &pairing(2);
+            gt *= &pairing(3);
+            gt
+        });
+        assert_eq!(
+            std::iter::empty::<GTElement>().product::<GTElement>(),
+            GTElement::default()
+        );
+
+        // the product of the pairings of an aggregate signature's public keys
+        // and messages is the pairing of the signature
+        let mut sig = Signature::default();
+        for i in 1..=3 {
+            let sk = SecretKey::from_seed(&[i; 32]);
+            sig += &sign(&sk, [i; 32]);
+        }
+        assert_eq!(
+            (1..=3).map(pairing).product::<GTElement>(),
+            sig.pair(&PublicKey::generator())
+        );
     }
 }
@@ -4,6 +4,7 @@ mod bls_cache;
 mod derive_keys;
 mod error;
 mod gtelement;
+mod mnemonic;
 mod public_key;
 mod secret_key;
 mod signature;
@@ -11,15 +12,20 @@ mod signature;
 #[cfg(feature = "py-bindings")]
 mod parse_hex;
 
-pub use bls_cache::BlsCache;
+pub use bls_cache::{BlsCache, BlsCacheCapacity, BlsCacheStats};
 pub use derive_keys::*;
 pub use error::{Error, Result};
 pub use gtelement::GTElement;
+pub use mnemonic::{
+    generate_mnemonic, mnemonic_from_entropy, mnemonic_to_entropy, mnemonic_to_seed,
+    validate_mnemonic,
+};
 pub use public_key::{hash_to_g1, hash_to_g1_with_dst, PublicKey};
 pub use secret_key::SecretKey;
 pub use signature::{
     aggregate, aggregate_pairing, aggregate_verify, aggregate_verify_gt, hash_to_g2,
-    hash_to_g2_with_dst, sign, sign_raw, verify, Signature,
+    hash_to_g2_with_dst, pop_fast_aggregate_verify, pop_prove, pop_sign, pop_verify, sign,
+    sign_raw, verify, Signature,
 };
 
 pub type G1Element = PublicKey;
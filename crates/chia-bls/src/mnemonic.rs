@@ -0,0 +1,256 @@
+use crate::{Error, Result};
+use chia_sha2::Sha256;
+use rand::RngCore;
+use std::sync::OnceLock;
+
+// the BIP-39 english wordlist, which is the only one chia supports
+static WORDLIST: OnceLock<Vec<&'static str>> = OnceLock::new();
+
+fn wordlist() -> &'static [&'static str] {
+    WORDLIST.get_or_init(|| include_str!("bip39-english.txt").lines().collect())
+}
+
+fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    hasher.finalize()
+}
+
+/// Returns a new random 24 word mnemonic, like the ones chia's keychain
+/// generates.
+pub fn generate_mnemonic<R: RngCore + ?Sized>(rng: &mut R) -> String {
+    let mut entropy = [0_u8; 32];
+    rng.fill_bytes(&mut entropy);
+    mnemonic_from_entropy(&entropy).expect("32 bytes is a valid entropy length")
+}
+
+/// Encodes 16, 20, 24, 28 or 32 bytes of entropy as a mnemonic of 12, 15, 18,
+/// 21 or 24 words respectively. This mirrors `bytes_to_mnemonic()` in
+/// chia-blockchain.
+pub fn mnemonic_from_entropy(entropy: &[u8]) -> Result<String> {
+    if !matches!(entropy.len(), 16 | 20 | 24 | 28 | 32) {
+        return Err(Error::InvalidEntropyLength(entropy.len()));
+    }
+
+    // the checksum is the first len / 4 bits of the hash, at most 8
+    let mut bits = entropy.to_vec();
+    bits.push(sha256(entropy)[0]);
+    let num_words = entropy.len() * 3 / 4;
+
+    let words = wordlist();
+    let mut ret = Vec::<&str>::with_capacity(num_words);
+    for word in 0..num_words {
+        let mut idx = 0_usize;
+        for bit in word * 11..(word + 1) * 11 {
+            idx = (idx << 1) | usize::from((bits[bit / 8] >> (7 - bit % 8)) & 1);
+        }
+        ret.push(words[idx]);
+    }
+    Ok(ret.join(" "))
+}
+
+/// Decodes a mnemonic back into its entropy, validating the words and the
+/// checksum. This mirrors `bytes_from_mnemonic()` in chia-blockchain.
+pub fn mnemonic_to_entropy(phrase: &str) -> Result<Vec<u8>> {
+    let phrase: Vec<&str> = phrase.split_whitespace().collect();
+    if !matches!(phrase.len(), 12 | 15 | 18 | 21 | 24) {
+        return Err(Error::InvalidMnemonicLength(phrase.len()));
+    }
+
+    let words = wordlist();
+    // 11 bits per word, the last byte holds the checksum
+    let mut bits = vec![0_u8; phrase.len() * 11 / 8 + 1];
+    for (i, word) in phrase.iter().enumerate() {
+        let idx = words
+            .binary_search(word)
+            .map_err(|_| Error::UnknownMnemonicWord((*word).to_string()))?;
+        for b in 0..11 {
+            if (idx >> (10 - b)) & 1 == 1 {
+                let bit = i * 11 + b;
+                bits[bit / 8] |= 0x80 >> (bit % 8);
+            }
+        }
+    }
+
+    let entropy_len = phrase.len() * 4 / 3;
+    let checksum_bits = phrase.len() / 3;
+    let entropy = &bits[..entropy_len];
+    let mask = 0xff_u8 << (8 - checksum_bits);
+    if bits[entropy_len] & mask != sha256(entropy)[0] & mask {
+        return Err(Error::InvalidMnemonicChecksum);
+    }
+    Ok(entropy.to_vec())
+}
+
+/// Checks that the phrase is a valid BIP-39 mnemonic, i.e. that it has 12, 15,
+/// 18, 21 or 24 words from the english wordlist and a matching checksum.
+pub fn validate_mnemonic(phrase: &str) -> Result<()> {
+    mnemonic_to_entropy(phrase).map(|_| ())
+}
+
+/// The BIP-39 seed of a mnemonic, PBKDF2-HMAC-SHA512 with 2048 rounds and
+/// "mnemonic" + passphrase as the salt. chia's keychain always uses an empty
+/// passphrase. The mnemonic is not validated and both strings are used as-is,
+/// so non-ASCII passphrases must already be NFKD normalized.
+pub fn mnemonic_to_seed(phrase: &str, passphrase: &str) -> [u8; 64] {
+    let salt = format!("mnemonic{passphrase}");
+    let mut seed = [0_u8; 64];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha512>(phrase.as_bytes(), salt.as_bytes(), 2048, &mut seed);
+    seed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretKey;
+    use hex::FromHex;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rstest::rstest;
+
+    #[test]
+    fn test_wordlist() {
+        let words = wordlist();
+        assert_eq!(words.len(), 2048);
+        assert!(words.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(words[0], "abandon");
+        assert_eq!(words[2047], "zoo");
+    }
+
+    // test vectors from:
+    // https://github.com/trezor/python-mnemonic/blob/master/vectors.json
+    // (entropy, mnemonic, seed with the passphrase "TREZOR")
+    #[rstest]
+    #[case(
+        "00000000000000000000000000000000",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+    )]
+    #[case(
+        "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+        "legal winner thank year wave sausage worth useful legal winner thank yellow",
+        "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607"
+    )]
+    #[case(
+        "80808080808080808080808080808080",
+        "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+        "d71de856f81a8acc65e6fc851a38d4d7ec216fd0796d0a6827a3ad6ed5511a30fa280f12eb2e47ed2ac03b5c462a0358d18d69fe4f985ec81778c1b370b652a8"
+    )]
+    #[case(
+        "ffffffffffffffffffffffffffffffff",
+        "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+        "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069"
+    )]
+    #[case(
+        "9e885d952ad362caeb4efe34a8e91bd2",
+        "ozone drill grab fiber curtain grace pudding thank cruise elder eight picnic",
+        "274ddc525802f7c828d8ef7ddbcdc5304e87ac3535913611fbbfa986d0c9e5476c91689f9c8a54fd55bd38606aa6a8595ad213d4c9c9f9aca3fb217069a41028"
+    )]
+    #[case(
+        "f30f8c1da665478f49b001d94c5fc452",
+        "vessel ladder alter error federal sibling chat ability sun glass valve picture",
+        "2aaa9242daafcee6aa9d7269f17d4efe271e1b9a529178d7dc139cd18747090bf9d60295d0ce74309a78852a9caadf0af48aae1c6253839624076224374bc63f"
+    )]
+    #[case(
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+        "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd3097170af7a4d73245cafa9c3cca8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8"
+    )]
+    #[case(
+        "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+        "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad"
+    )]
+    #[case(
+        "68a79eaca2324873eacc50cb9c6eca8cc68ea5d936f98787c60c7ebc74e6ce7c",
+        "hamster diagram private dutch cause delay private meat slide toddler razor book happy fancy gospel tennis maple dilemma loan word shrug inflict delay length",
+        "64c87cde7e12ecf6704ab95bb1408bef047c22db4cc7491c4271d170a1b213d20b385bc1588d9c7b38f1b39d415665b8a9030c9ec653d75e65f847d8fc1fc440"
+    )]
+    fn test_bip39_vectors(#[case] entropy: &str, #[case] phrase: &str, #[case] seed: &str) {
+        let entropy = Vec::<u8>::from_hex(entropy).unwrap();
+        assert_eq!(mnemonic_from_entropy(&entropy).unwrap(), phrase);
+        assert_eq!(mnemonic_to_entropy(phrase).unwrap(), entropy);
+        assert_eq!(validate_mnemonic(phrase), Ok(()));
+        assert_eq!(
+            mnemonic_to_seed(phrase, "TREZOR"),
+            <[u8; 64]>::from_hex(seed).unwrap()
+        );
+    }
+
+    // the seeds are the ones in secret_key::tests::test_make_key(), the
+    // fingerprint is the one chia's keychain shows for the mnemonic
+    // (phrase, seed, fingerprint)
+    #[rstest]
+    #[case(
+        "all hour make first leader extend hole alien behind guard gospel lava path output census museum junior mass reopen famous sing advance salt reform",
+        "fc795be0c3f18c50dddb34e72179dc597d64055497ecc1e69e2e56a5409651bc139aae8070d4df0ea14d8d2a518a9a00bb1cc6e92e053fe34051f6821df9164c",
+        651_010_559
+    )]
+    #[case(
+        "panda eyebrow bullet gorilla call smoke muffin taste mesh discover soft ostrich alcohol speed nation flash devote level hobby quick inner drive ghost inside",
+        "3e066d7dee2dbf8fcd3fe240a3975658ca118a8f6f4ca81cf99104944604b05a5090a79d99e545704b914ca0397fedb82fd00fd6a72098703709c891a065ee49",
+        3_061_729_350
+    )]
+    #[case(
+        "void come effort suffer camp survey warrior heavy shoot primary clutch crush open amazing screen patrol group space point ten exist slush involve unfold",
+        "b873212f885ccffbf4692afcb84bc2e55886de2dfa07d90f5c3c239abc31c0a6ce047e30fd8bf6a281e71389aa82d73df74c7bbfb3b06b4639a5cee775cccd3c",
+        3_823_090_673
+    )]
+    fn test_chia_keychain(#[case] phrase: &str, #[case] seed: &str, #[case] fingerprint: u32) {
+        let seed = <[u8; 64]>::from_hex(seed).unwrap();
+        assert_eq!(mnemonic_to_seed(phrase, ""), seed);
+        let sk = SecretKey::from_mnemonic(phrase).unwrap();
+        assert_eq!(sk, SecretKey::from_seed(&seed));
+        assert_eq!(sk.public_key().get_fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut rng = StdRng::seed_from_u64(1337);
+        for _ in 0..50 {
+            let phrase = generate_mnemonic(&mut rng);
+            assert_eq!(phrase.split(' ').count(), 24);
+            assert_eq!(validate_mnemonic(&phrase), Ok(()));
+            let entropy = mnemonic_to_entropy(&phrase).unwrap();
+            assert_eq!(mnemonic_from_entropy(&entropy).unwrap(), phrase);
+        }
+    }
+
+    #[rstest]
+    #[case(15)]
+    #[case(33)]
+    #[case(0)]
+    fn test_invalid_entropy_length(#[case] len: usize) {
+        assert_eq!(
+            mnemonic_from_entropy(&vec![0; len]),
+            Err(Error::InvalidEntropyLength(len))
+        );
+    }
+
+    #[rstest]
+    #[case("", Error::InvalidMnemonicLength(0))]
+    #[case(
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        Error::InvalidMnemonicLength(11)
+    )]
+    #[case(
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+        Error::InvalidMnemonicChecksum
+    )]
+    #[case(
+        "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo",
+        Error::InvalidMnemonicChecksum
+    )]
+    #[case(
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abou",
+        Error::UnknownMnemonicWord("abou".to_string())
+    )]
+    #[case(
+        "Abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        Error::UnknownMnemonicWord("Abandon".to_string())
+    )]
+    fn test_invalid_mnemonic(#[case] phrase: &str, #[case] error: Error) {
+        assert_eq!(validate_mnemonic(phrase), Err(error.clone()));
+        assert_eq!(SecretKey::from_mnemonic(phrase), Err(error));
+    }
+}
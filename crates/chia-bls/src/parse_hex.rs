@@ -1,45 +1,46 @@
+use chia_traits::JsonValue;
 use pyo3::{
     exceptions::{PyTypeError, PyValueError},
     prelude::*,
 };
 
+fn check_length(buf: Vec<u8>, len: usize, name: &str) -> PyResult<Vec<u8>> {
+    if buf.len() == len {
+        Ok(buf)
+    } else {
+        Err(PyValueError::new_err(format!(
+            "{}, invalid length {} expected {}",
+            name,
+            buf.len(),
+            len
+        )))
+    }
+}
+
+fn parse_hex_str(s: &str, len: usize, name: &str) -> PyResult<Vec<u8>> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    let buf = hex::decode(s).map_err(|_| PyValueError::new_err("invalid hex"))?;
+    check_length(buf, len, name)
+}
+
 pub fn parse_hex_string(o: &Bound<'_, PyAny>, len: usize, name: &str) -> PyResult<Vec<u8>> {
     if let Ok(s) = o.extract::<String>() {
-        let s = if let Some(st) = s.strip_prefix("0x") {
-            st
-        } else {
-            &s[..]
-        };
-        let buf = match hex::decode(s) {
-            Err(_) => {
-                return Err(PyValueError::new_err("invalid hex"));
-            }
-            Ok(v) => v,
-        };
-        if buf.len() == len {
-            Ok(buf)
-        } else {
-            Err(PyValueError::new_err(format!(
-                "{}, invalid length {} expected {}",
-                name,
-                buf.len(),
-                len
-            )))
-        }
+        parse_hex_str(&s, len, name)
     } else if let Ok(buf) = o.extract::<Vec<u8>>() {
-        if buf.len() == len {
-            Ok(buf)
-        } else {
-            Err(PyValueError::new_err(format!(
-                "{}, invalid length {} expected {}",
-                name,
-                buf.len(),
-                len
-            )))
-        }
+        check_length(buf, len, name)
     } else {
         Err(PyTypeError::new_err(format!(
             "invalid input type for {name}"
         )))
     }
 }
+
+/// The `from_json_value()` counterpart of `parse_hex_string()`.
+pub fn parse_hex_value(v: &JsonValue, len: usize, name: &str) -> PyResult<Vec<u8>> {
+    match v {
+        JsonValue::String(s) => parse_hex_str(s, len, name),
+        _ => Err(PyTypeError::new_err(format!(
+            "invalid input type for {name}"
+        ))),
+    }
+}
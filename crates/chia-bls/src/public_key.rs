@@ -3,7 +3,7 @@ use crate::{DerivableKey, Error, Result};
 
 use blst::*;
 use chia_sha2::Sha256;
-use chia_traits::{read_bytes, Streamable};
+use chia_traits::{bytes_summary, diff_leaf, read_bytes, FieldDiff, Streamable, StreamableDiff};
 #[cfg(feature = "py-bindings")]
 use pyo3::exceptions::PyNotImplementedError;
 #[cfg(feature = "py-bindings")]
@@ -181,6 +181,37 @@ impl Streamable for PublicKey {
     }
 }
 
+impl StreamableDiff for PublicKey {
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+        diff_leaf(self, other, path, out);
+    }
+
+    fn summary(&self) -> String {
+        bytes_summary(&self.to_bytes())
+    }
+}
+
+/// Serialized as the hex encoded compressed bytes, see [`WireFormat`](chia_traits::WireFormat).
+#[cfg(feature = "serde")]
+impl serde::Serialize for PublicKey {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&chia_traits::WireFormat(*self), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PublicKey {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        <chia_traits::WireFormat<Self> as serde::Deserialize<'de>>::deserialize(deserializer)
+            .map(chia_traits::WireFormat::into_inner)
+    }
+}
+
 impl Hash for PublicKey {
     fn hash<H: Hasher>(&self, state: &mut H) {
         state.write(&self.to_bytes());
@@ -364,9 +395,9 @@ impl PublicKey {
 mod pybindings {
     use super::*;
 
-    use crate::parse_hex::parse_hex_string;
+    use crate::parse_hex::{parse_hex_string, parse_hex_value};
 
-    use chia_traits::{FromJsonDict, ToJsonDict};
+    use chia_traits::{FromJsonDict, JsonValue, ToJsonDict};
 
     impl ToJsonDict for PublicKey {
         fn to_json_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
@@ -387,6 +418,15 @@ mod pybindings {
                     .unwrap(),
             )?)
         }
+
+        fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+            Ok(Self::from_bytes(
+                parse_hex_value(v, 48, "PublicKey")?
+                    .as_slice()
+                    .try_into()
+                    .unwrap(),
+            )?)
+        }
     }
 }
 
@@ -592,6 +632,23 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let pk = PublicKey::generator();
+        let json = serde_json::to_string(&pk).unwrap();
+        assert_eq!(
+            json,
+            "\"0x97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\""
+        );
+        assert_eq!(serde_json::from_str::<PublicKey>(&json).unwrap(), pk);
+
+        // the point must be on the curve
+        let mut invalid = json.clone();
+        invalid.replace_range(3..5, "00");
+        assert!(serde_json::from_str::<PublicKey>(&invalid).is_err());
+    }
+
     #[test]
     fn test_generator() {
         assert_eq!(
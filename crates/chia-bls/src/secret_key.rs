@@ -1,7 +1,7 @@
-use crate::{DerivableKey, Error, PublicKey, Result};
+use crate::{mnemonic_to_seed, validate_mnemonic, DerivableKey, Error, PublicKey, Result};
 use blst::*;
 use chia_sha2::Sha256;
-use chia_traits::{read_bytes, Streamable};
+use chia_traits::{bytes_summary, diff_leaf, read_bytes, FieldDiff, Streamable, StreamableDiff};
 use hkdf::HkdfExtract;
 #[cfg(feature = "py-bindings")]
 use pyo3::exceptions::PyNotImplementedError;
@@ -112,6 +112,13 @@ impl SecretKey {
         Self::from_bytes(&bytes).expect("from_seed")
     }
 
+    /// The master key of a BIP-39 mnemonic, the way chia's keychain derives
+    /// it, i.e. `from_seed()` of the seed with an empty passphrase.
+    pub fn from_mnemonic(phrase: &str) -> Result<Self> {
+        validate_mnemonic(phrase)?;
+        Ok(Self::from_seed(&mnemonic_to_seed(phrase, "")))
+    }
+
     pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self> {
         let pk = unsafe {
             let mut pk = MaybeUninit::<blst_scalar>::uninit();
@@ -175,6 +182,20 @@ impl Streamable for SecretKey {
     }
 }
 
+impl StreamableDiff for SecretKey {
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+        diff_leaf(self, other, path, out);
+    }
+
+    fn summary(&self) -> String {
+        // don't reveal the secret key itself
+        format!(
+            "<PrivateKey for {}>",
+            bytes_summary(&self.public_key().to_bytes())
+        )
+    }
+}
+
 impl Hash for SecretKey {
     fn hash<H: Hasher>(&self, state: &mut H) {
         state.write(&self.to_bytes());
@@ -298,15 +319,21 @@ impl SecretKey {
     pub fn py_from_seed(seed: &[u8]) -> Self {
         Self::from_seed(seed)
     }
+
+    #[pyo3(name = "from_mnemonic")]
+    #[staticmethod]
+    pub fn py_from_mnemonic(phrase: &str) -> PyResult<Self> {
+        Ok(Self::from_mnemonic(phrase)?)
+    }
 }
 
 #[cfg(feature = "py-bindings")]
 mod pybindings {
     use super::*;
 
-    use crate::parse_hex::parse_hex_string;
+    use crate::parse_hex::{parse_hex_string, parse_hex_value};
 
-    use chia_traits::{FromJsonDict, ToJsonDict};
+    use chia_traits::{FromJsonDict, JsonValue, ToJsonDict};
 
     impl ToJsonDict for SecretKey {
         fn to_json_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
@@ -327,6 +354,15 @@ mod pybindings {
                     .unwrap(),
             )?)
         }
+
+        fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+            Ok(Self::from_bytes(
+                parse_hex_value(v, 32, "PrivateKey")?
+                    .as_slice()
+                    .try_into()
+                    .unwrap(),
+            )?)
+        }
     }
 }
 
@@ -1,7 +1,7 @@
 use crate::{Error, GTElement, PublicKey, Result, SecretKey};
 use blst::*;
 use chia_sha2::Sha256;
-use chia_traits::{read_bytes, Streamable};
+use chia_traits::{bytes_summary, diff_leaf, read_bytes, FieldDiff, Streamable, StreamableDiff};
 #[cfg(feature = "py-bindings")]
 use pyo3::exceptions::PyNotImplementedError;
 #[cfg(feature = "py-bindings")]
@@ -149,6 +149,16 @@ impl Streamable for Signature {
     }
 }
 
+impl StreamableDiff for Signature {
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+        diff_leaf(self, other, path, out);
+    }
+
+    fn summary(&self) -> String {
+        bytes_summary(&self.to_bytes())
+    }
+}
+
 impl PartialEq for Signature {
     fn eq(&self, other: &Self) -> bool {
         unsafe { blst_p2_is_equal(&self.0, &other.0) }
@@ -481,6 +491,97 @@ pub fn sign<Msg: AsRef<[u8]>>(sk: &SecretKey, msg: Msg) -> Signature {
     sign_raw(sk, aug_msg)
 }
 
+// The proof of possession scheme signs messages as-is. Each public key must
+// come with a proof of possession (see pop_prove()), which is what makes it
+// safe to aggregate signatures over the same message.
+pub(crate) const POP_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+// proofs of possession are signatures over the public key, using their own DST
+pub(crate) const POP_PROOF_DST: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+fn sign_with_dst(sk: &SecretKey, msg: &[u8], dst: &[u8]) -> Signature {
+    let mut p2 = hash_to_g2_with_dst(msg, dst).0;
+    let p2_ptr: *mut blst_p2 = &mut p2;
+    unsafe {
+        blst_sign_pk_in_g1(p2_ptr, p2_ptr, &sk.0);
+    }
+    Signature(p2)
+}
+
+fn core_verify(sig: &Signature, key: &PublicKey, msg: &[u8], dst: &[u8]) -> bool {
+    // unlike the augmented scheme, nothing ties the message to the key here,
+    // so the key and signature have to be checked explicitly
+    if !sig.is_valid() || !key.is_valid() || key.is_inf() {
+        return false;
+    }
+
+    unsafe {
+        let mut pubkey_affine = MaybeUninit::<blst_p1_affine>::uninit();
+        let mut sig_affine = MaybeUninit::<blst_p2_affine>::uninit();
+
+        blst_p1_to_affine(pubkey_affine.as_mut_ptr(), &key.0);
+        blst_p2_to_affine(sig_affine.as_mut_ptr(), &sig.0);
+
+        let err = blst_core_verify_pk_in_g1(
+            &pubkey_affine.assume_init(),
+            &sig_affine.assume_init(),
+            true, // hash
+            msg.as_ptr(),
+            msg.len(),
+            dst.as_ptr(),
+            dst.len(),
+            std::ptr::null(),
+            0,
+        );
+
+        err == BLST_ERROR::BLST_SUCCESS
+    }
+}
+
+// Signs msg using sk using the proof of possession scheme. The signature can
+// be verified with pop_fast_aggregate_verify(), passing just the one key.
+pub fn pop_sign<Msg: AsRef<[u8]>>(sk: &SecretKey, msg: Msg) -> Signature {
+    sign_with_dst(sk, msg.as_ref(), POP_DST)
+}
+
+// Proves possession of sk by signing its public key.
+pub fn pop_prove(sk: &SecretKey) -> Signature {
+    sign_with_dst(sk, &sk.public_key().to_bytes(), POP_PROOF_DST)
+}
+
+// Verifies a proof of possession made by pop_prove().
+pub fn pop_verify(key: &PublicKey, proof: &Signature) -> bool {
+    core_verify(proof, key, &key.to_bytes(), POP_PROOF_DST)
+}
+
+// Verifies an aggregate signature of keys that all signed the same message
+// with pop_sign(). This is only secure if every key's proof of possession has
+// been checked with pop_verify() beforehand.
+pub fn pop_fast_aggregate_verify<Pk: Borrow<PublicKey>, I, Msg: AsRef<[u8]>>(
+    keys: I,
+    msg: Msg,
+    sig: &Signature,
+) -> bool
+where
+    I: IntoIterator<Item = Pk>,
+{
+    let mut keys = keys.into_iter().peekable();
+    if keys.peek().is_none() {
+        return false;
+    }
+
+    let mut agg_key = PublicKey::default();
+    for key in keys {
+        let key = key.borrow();
+        if !key.is_valid() {
+            return false;
+        }
+        agg_key += key;
+    }
+
+    core_verify(sig, &agg_key, msg.as_ref(), POP_DST)
+}
+
 #[cfg(feature = "py-bindings")]
 #[pyo3::pymethods]
 impl Signature {
@@ -529,9 +630,9 @@ impl Signature {
 mod pybindings {
     use super::*;
 
-    use crate::parse_hex::parse_hex_string;
+    use crate::parse_hex::{parse_hex_string, parse_hex_value};
 
-    use chia_traits::{FromJsonDict, ToJsonDict};
+    use chia_traits::{FromJsonDict, JsonValue, ToJsonDict};
 
     impl ToJsonDict for Signature {
         fn to_json_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
@@ -552,6 +653,15 @@ mod pybindings {
                     .unwrap(),
             )?)
         }
+
+        fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+            Ok(Self::from_bytes(
+                parse_hex_value(v, 96, "Signature")?
+                    .as_slice()
+                    .try_into()
+                    .unwrap(),
+            )?)
+        }
     }
 }
 
@@ -1098,6 +1208,109 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_pop_scheme_vector() {
+        // test case from the Ethereum consensus spec BLS tests (sign_case_8cd3d4d0d9a5b265),
+        // which use the same BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_ ciphersuite
+        let sk = SecretKey::from_bytes(
+            &<[u8; 32]>::from_hex(
+                "263dbd792f5b1be47ed85f8938c0f29586af0d3ac7b977f21c278fe1462040e3",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let pk = sk.public_key();
+        assert_eq!(
+            hex::encode(pk.to_bytes()),
+            "a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a"
+        );
+
+        let msg = [0_u8; 32];
+        let sig = pop_sign(&sk, msg);
+        assert_eq!(
+            hex::encode(sig.to_bytes()),
+            "b6ed936746e01f8ecf281f020953fbf1f01debd5657c4a383940b020b26507f6076334f91e2366c96e9ab279fb5158090352ea1c5b0c9274504f4f0e7053af24802e51e4568d164fe986834f41e55c8e850ce1f98458c0cfc9ab380b55285a55"
+        );
+        assert!(pop_fast_aggregate_verify([&pk], msg, &sig));
+    }
+
+    #[test]
+    fn test_pop_prove() {
+        let sk1 = SecretKey::from_seed(&[4_u8; 32]);
+        let pk1 = sk1.public_key();
+        let sk2 = SecretKey::from_seed(&[5_u8; 32]);
+        let pk2 = sk2.public_key();
+
+        let proof1 = pop_prove(&sk1);
+        let proof2 = pop_prove(&sk2);
+        assert!(pop_verify(&pk1, &proof1));
+        assert!(pop_verify(&pk2, &proof2));
+        assert!(pop_verify(
+            &PublicKey::from_bytes(&pk1.to_bytes()).unwrap(),
+            &Signature::from_bytes(&proof1.to_bytes()).unwrap()
+        ));
+
+        // a proof only works for its own key
+        assert!(!pop_verify(&pk1, &proof2));
+        assert!(!pop_verify(&pk2, &proof1));
+
+        // the proof is a signature of the public key, but under its own DST,
+        // so signatures of the key's bytes in either scheme aren't proofs
+        assert_eq!(proof1, sign_with_dst(&sk1, &pk1.to_bytes(), POP_PROOF_DST));
+        assert!(!pop_verify(&pk1, &pop_sign(&sk1, pk1.to_bytes())));
+        assert!(!pop_verify(&pk1, &sign_raw(&sk1, pk1.to_bytes())));
+        assert!(!pop_verify(&pk1, &sign(&sk1, pk1.to_bytes())));
+
+        // and a proof isn't a signature of the key's bytes
+        assert!(!pop_fast_aggregate_verify([&pk1], pk1.to_bytes(), &proof1));
+
+        // the identity can't prove possession of anything
+        assert!(!pop_verify(&PublicKey::default(), &Signature::default()));
+    }
+
+    #[rstest]
+    fn test_pop_fast_aggregate_verify(#[values(1, 2, 3, 10)] num_keys: usize) {
+        let msg = b"pool partial";
+        let sks: Vec<SecretKey> = (0..num_keys)
+            .map(|i| SecretKey::from_seed(&[u8::try_from(i).unwrap(); 32]))
+            .collect();
+        let pks: Vec<PublicKey> = sks.iter().map(SecretKey::public_key).collect();
+        let sig = aggregate(sks.iter().map(|sk| pop_sign(sk, msg)));
+
+        assert!(pop_fast_aggregate_verify(&pks, msg, &sig));
+        assert!(pop_fast_aggregate_verify(
+            pks.iter().rev(),
+            msg,
+            &Signature::from_bytes(&sig.to_bytes()).unwrap()
+        ));
+
+        // wrong message
+        assert!(!pop_fast_aggregate_verify(&pks, b"other partial", &sig));
+
+        // missing or extra key
+        assert!(!pop_fast_aggregate_verify(&pks[1..], msg, &sig));
+        let extra = SecretKey::from_seed(&[0xff; 32]).public_key();
+        assert!(!pop_fast_aggregate_verify(
+            pks.iter().chain([&extra]),
+            msg,
+            &sig
+        ));
+
+        // augmented signatures don't verify in the proof of possession scheme
+        let aug_sig = aggregate(sks.iter().map(|sk| sign(sk, msg)));
+        assert!(!pop_fast_aggregate_verify(&pks, msg, &aug_sig));
+    }
+
+    #[test]
+    fn test_pop_fast_aggregate_verify_no_keys() {
+        let pks: [&PublicKey; 0] = [];
+        assert!(!pop_fast_aggregate_verify(
+            pks,
+            b"foobar",
+            &Signature::default()
+        ));
+    }
+
     #[test]
     fn test_hash() {
         fn hash<T: Hash>(v: T) -> u64 {
@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use chia_protocol::TimestampedPeerInfo;
+use rand::seq::IteratorRandom;
+use rand::Rng;
+
+/// A set of known peers, as learned from `RespondPeers` and
+/// `RespondPeersIntroducer` messages. Peers are keyed by (host, port), so the
+/// same peer advertised multiple times is only stored once, with the most
+/// recent timestamp.
+#[derive(Debug, Clone)]
+pub struct AddressBook {
+    peers: HashMap<(String, u16), u64>,
+    ttl: u64,
+    allow_localhost: bool,
+}
+
+impl AddressBook {
+    /// `ttl` is the number of seconds a peer is kept after the timestamp it
+    /// was last advertised with. Loopback addresses are rejected unless
+    /// `allow_localhost` is set (which is useful for simulators and tests).
+    pub fn new(ttl: u64, allow_localhost: bool) -> Self {
+        Self {
+            peers: HashMap::new(),
+            ttl,
+            allow_localhost,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    pub fn contains(&self, host: &str, port: u16) -> bool {
+        self.peers
+            .contains_key(&(normalize_host(host).to_string(), port))
+    }
+
+    /// Returns true if the host and port are acceptable to store and gossip.
+    pub fn is_valid_peer(&self, host: &str, port: u16) -> bool {
+        if port == 0 {
+            return false;
+        }
+        let host = normalize_host(host);
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            return is_valid_ip(ip, self.allow_localhost);
+        }
+        if host.eq_ignore_ascii_case("localhost") {
+            return self.allow_localhost;
+        }
+        is_valid_hostname(host)
+    }
+
+    /// Adds (or refreshes) a peer. Returns false if the peer was rejected as
+    /// invalid or has already expired at time `now`.
+    pub fn add(&mut self, peer: &TimestampedPeerInfo, now: u64) -> bool {
+        if !self.is_valid_peer(&peer.host, peer.port) || self.is_expired(peer.timestamp, now) {
+            return false;
+        }
+        let key = (normalize_host(&peer.host).to_string(), peer.port);
+        let timestamp = self.peers.entry(key).or_insert(peer.timestamp);
+        *timestamp = (*timestamp).max(peer.timestamp);
+        true
+    }
+
+    /// Adds all peers, returning the number that were accepted.
+    pub fn add_all<'a, I>(&mut self, peers: I, now: u64) -> usize
+    where
+        I: IntoIterator<Item = &'a TimestampedPeerInfo>,
+    {
+        peers.into_iter().filter(|peer| self.add(peer, now)).count()
+    }
+
+    pub fn remove(&mut self, host: &str, port: u16) -> bool {
+        self.peers
+            .remove(&(normalize_host(host).to_string(), port))
+            .is_some()
+    }
+
+    /// Removes all peers whose timestamp is older than the TTL at time `now`.
+    pub fn remove_expired(&mut self, now: u64) {
+        let ttl = self.ttl;
+        self.peers
+            .retain(|_, timestamp| timestamp.saturating_add(ttl) >= now);
+    }
+
+    /// Picks up to `count` distinct peers at random, e.g. for gossiping in a
+    /// `RespondPeers` message.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R, count: usize) -> Vec<TimestampedPeerInfo> {
+        self.peers
+            .iter()
+            .choose_multiple(rng, count)
+            .into_iter()
+            .map(|((host, port), timestamp)| {
+                TimestampedPeerInfo::new(host.clone(), *port, *timestamp)
+            })
+            .collect()
+    }
+
+    pub fn peers(&self) -> impl Iterator<Item = TimestampedPeerInfo> + '_ {
+        self.peers.iter().map(|((host, port), timestamp)| {
+            TimestampedPeerInfo::new(host.clone(), *port, *timestamp)
+        })
+    }
+
+    fn is_expired(&self, timestamp: u64, now: u64) -> bool {
+        timestamp.saturating_add(self.ttl) < now
+    }
+}
+
+// IPv6 addresses may be enclosed in brackets, like in a URL
+fn normalize_host(host: &str) -> &str {
+    host.strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host)
+}
+
+fn is_valid_ip(ip: IpAddr, allow_localhost: bool) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            if ip.is_loopback() {
+                return allow_localhost;
+            }
+            !(ip.is_unspecified() || ip.is_broadcast() || ip.is_multicast())
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_valid_ip(IpAddr::V4(ip), allow_localhost);
+            }
+            if ip.is_loopback() {
+                return allow_localhost;
+            }
+            !(ip.is_unspecified() || ip.is_multicast())
+        }
+    }
+}
+
+// RFC 1123 host names
+fn is_valid_hostname(host: &str) -> bool {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    if host.is_empty() || host.len() > 253 {
+        return false;
+    }
+    host.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rstest::rstest;
+
+    fn peer(host: &str, port: u16, timestamp: u64) -> TimestampedPeerInfo {
+        TimestampedPeerInfo::new(host.to_string(), port, timestamp)
+    }
+
+    #[rstest]
+    #[case("1.2.3.4", 8444, true)]
+    #[case("1.2.3.4", 0, false)]
+    #[case("0.0.0.0", 8444, false)]
+    #[case("255.255.255.255", 8444, false)]
+    #[case("224.0.0.1", 8444, false)]
+    #[case("127.0.0.1", 8444, false)]
+    #[case("127.1.2.3", 8444, false)]
+    #[case("2001:db8::1", 8444, true)]
+    #[case("[2001:db8::1]", 8444, true)]
+    #[case("::", 8444, false)]
+    #[case("::1", 8444, false)]
+    #[case("[::1]", 8444, false)]
+    #[case("::ffff:127.0.0.1", 8444, false)]
+    #[case("::ffff:1.2.3.4", 8444, true)]
+    #[case("ff02::1", 8444, false)]
+    #[case("localhost", 8444, false)]
+    #[case("node.chia.net", 8444, true)]
+    #[case("node.chia.net.", 8444, true)]
+    #[case("", 8444, false)]
+    #[case("[]", 8444, false)]
+    #[case(" 1.2.3.4", 8444, false)]
+    #[case("1.2.3.4:8444", 8444, false)]
+    #[case("-node.chia.net", 8444, false)]
+    #[case("node..chia.net", 8444, false)]
+    #[case("node_1.chia.net", 8444, false)]
+    #[case("\u{e9}.chia.net", 8444, false)]
+    #[case("2001:db8::zz", 8444, false)]
+    fn test_is_valid_peer(#[case] host: &str, #[case] port: u16, #[case] expected: bool) {
+        let book = AddressBook::new(3600, false);
+        assert_eq!(book.is_valid_peer(host, port), expected);
+    }
+
+    #[rstest]
+    #[case("127.0.0.1")]
+    #[case("::1")]
+    #[case("[::1]")]
+    #[case("localhost")]
+    fn test_allow_localhost(#[case] host: &str) {
+        let book = AddressBook::new(3600, true);
+        assert!(book.is_valid_peer(host, 8444));
+    }
+
+    #[test]
+    fn test_long_hostname() {
+        let book = AddressBook::new(3600, false);
+        let label = "a".repeat(63);
+        assert!(book.is_valid_peer(&format!("{label}.net"), 8444));
+        let label = "a".repeat(64);
+        assert!(!book.is_valid_peer(&format!("{label}.net"), 8444));
+        let host = ["a"; 127].join(".");
+        assert!(book.is_valid_peer(&host, 8444));
+        let host = ["a"; 128].join(".");
+        assert!(!book.is_valid_peer(&host, 8444));
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut book = AddressBook::new(3600, false);
+        assert!(book.add(&peer("1.2.3.4", 8444, 100), 100));
+        assert!(book.add(&peer("1.2.3.4", 8444, 200), 200));
+        assert!(book.add(&peer("1.2.3.4", 8444, 150), 200));
+        assert!(book.add(&peer("1.2.3.4", 8445, 100), 200));
+        assert!(book.add(&peer("[2001:db8::1]", 8444, 100), 200));
+        assert!(book.add(&peer("2001:db8::1", 8444, 100), 200));
+        assert_eq!(book.len(), 3);
+        assert!(book.contains("1.2.3.4", 8444));
+        assert!(book.contains("[2001:db8::1]", 8444));
+
+        let mut peers: Vec<_> = book.peers().collect();
+        peers.sort_by(|a, b| (&a.host, a.port).cmp(&(&b.host, b.port)));
+        assert_eq!(
+            peers,
+            vec![
+                peer("1.2.3.4", 8444, 200),
+                peer("1.2.3.4", 8445, 100),
+                peer("2001:db8::1", 8444, 100),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid() {
+        let mut book = AddressBook::new(3600, false);
+        let peers = [
+            peer("", 8444, 100),
+            peer("127.0.0.1", 8444, 100),
+            peer("1.2.3.4", 0, 100),
+            peer("not a host", 8444, 100),
+            peer("1.2.3.4", 8444, 100),
+        ];
+        assert_eq!(book.add_all(&peers, 100), 1);
+        assert_eq!(book.len(), 1);
+        assert!(!book.is_empty());
+    }
+
+    #[test]
+    fn test_expiry() {
+        let mut book = AddressBook::new(100, false);
+        // already expired
+        assert!(!book.add(&peer("1.2.3.4", 8444, 100), 201));
+        assert!(book.add(&peer("1.2.3.4", 8444, 100), 200));
+        assert!(book.add(&peer("1.2.3.5", 8444, 150), 200));
+        assert_eq!(book.len(), 2);
+
+        book.remove_expired(200);
+        assert_eq!(book.len(), 2);
+        book.remove_expired(201);
+        assert_eq!(book.len(), 1);
+        assert!(book.contains("1.2.3.5", 8444));
+        book.remove_expired(251);
+        assert!(book.is_empty());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut book = AddressBook::new(100, false);
+        assert!(book.add(&peer("2001:db8::1", 8444, 100), 100));
+        assert!(!book.remove("2001:db8::1", 8445));
+        assert!(book.remove("[2001:db8::1]", 8444));
+        assert!(book.is_empty());
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut rng = StdRng::seed_from_u64(1337);
+        let mut book = AddressBook::new(3600, false);
+        assert!(book.sample(&mut rng, 10).is_empty());
+
+        for i in 1..=50 {
+            assert!(book.add(&peer(&format!("10.0.0.{i}"), 8444, 100), 100));
+        }
+
+        let sample = book.sample(&mut rng, 10);
+        assert_eq!(sample.len(), 10);
+        for p in &sample {
+            assert!(book.contains(&p.host, p.port));
+        }
+        let mut hosts: Vec<_> = sample.iter().map(|p| p.host.clone()).collect();
+        hosts.sort();
+        hosts.dedup();
+        assert_eq!(hosts.len(), 10);
+
+        assert_eq!(book.sample(&mut rng, 100).len(), 50);
+    }
+}
@@ -0,0 +1,477 @@
+use std::collections::HashSet;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use chia_consensus::network_config::NetworkConfig;
+use chia_protocol::{Handshake, NodeType};
+use futures_util::future::{self, BoxFuture};
+use futures_util::{stream, StreamExt};
+use rand::seq::SliceRandom;
+use tokio::net::TcpStream;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::{ConnectError, Peer, PeerEvent, PeerOptions, Transport};
+
+/// Looks up the addresses of host names, for [`discover_peers_with()`].
+pub trait Resolver: Sync {
+    /// The IPv4 and IPv6 addresses (A and AAAA records) of `host`, with
+    /// `port`.
+    fn lookup<'a>(&'a self, host: &'a str, port: u16)
+        -> BoxFuture<'a, io::Result<Vec<SocketAddr>>>;
+}
+
+/// Looks up host names with the operating system's resolver.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemResolver;
+
+impl Resolver for SystemResolver {
+    fn lookup<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+    ) -> BoxFuture<'a, io::Result<Vec<SocketAddr>>> {
+        Box::pin(async move { Ok(tokio::net::lookup_host((host, port)).await?.collect()) })
+    }
+}
+
+/// Finds up to `count` peers of `network`, by looking up its DNS introducers.
+/// Once connected, more peers can be requested with
+/// [`Peer::request_peers()`].
+pub async fn discover_peers(network: &NetworkConfig, count: usize) -> Vec<SocketAddr> {
+    discover_peers_with(&SystemResolver, network, count).await
+}
+
+/// Like [`discover_peers()`], but looks up the introducers with `resolver`.
+/// All introducers are looked up at the same time, and the ones that fail
+/// are skipped. The addresses are deduplicated, shuffled and the ones that
+/// aren't publicly routable are removed.
+pub async fn discover_peers_with<R: Resolver + ?Sized>(
+    resolver: &R,
+    network: &NetworkConfig,
+    count: usize,
+) -> Vec<SocketAddr> {
+    let lookups = network
+        .dns_introducers
+        .iter()
+        .map(|host| resolver.lookup(host, network.default_full_node_port));
+
+    let mut seen = HashSet::new();
+    let mut peers: Vec<SocketAddr> = future::join_all(lookups)
+        .await
+        .into_iter()
+        .filter_map(Result::ok)
+        .flatten()
+        .filter(|addr| is_routable(addr.ip()) && seen.insert(*addr))
+        .collect();
+
+    peers.shuffle(&mut rand::thread_rng());
+    peers.truncate(count);
+    peers
+}
+
+// addresses that can't be reached over the internet, such as private and
+// link-local ones. Introducers shouldn't return these.
+fn is_routable(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(a == 0
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                // shared address space (carrier-grade NAT), 100.64.0.0/10
+                || (a == 100 && (b & 0xc0) == 64))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_routable(IpAddr::V4(ip));
+            }
+            let [first, second, ..] = ip.segments();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                // unique local, fc00::/7
+                || (first & 0xfe00) == 0xfc00
+                // link-local, fe80::/10
+                || (first & 0xffc0) == 0xfe80
+                // documentation, 2001:db8::/32
+                || (first == 0x2001 && second == 0x0db8))
+        }
+    }
+}
+
+/// Opens connections for [`connect_any_with()`].
+pub trait Connector: Sync {
+    type Transport: Transport;
+
+    fn connect(&self, addr: SocketAddr) -> BoxFuture<'_, Result<Self::Transport, ConnectError>>;
+}
+
+/// Connects to `ws://<addr>/ws`. Full nodes only accept TLS connections
+/// (`wss`), with a certificate from `chia-ssl`. To connect to those, use a
+/// [`Connector`] that sets up TLS.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WebSocketConnector;
+
+impl Connector for WebSocketConnector {
+    type Transport = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    fn connect(&self, addr: SocketAddr) -> BoxFuture<'_, Result<Self::Transport, ConnectError>> {
+        Box::pin(async move {
+            let (ws, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws")).await?;
+            Ok(ws)
+        })
+    }
+}
+
+/// Settings for [`connect_any()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectOptions {
+    /// The network to send in the handshake. Peers that answer with a
+    /// different network are rejected.
+    pub network_id: String,
+    pub node_type: NodeType,
+    pub mempool_updates: bool,
+    pub peer_options: PeerOptions,
+
+    /// The most candidates that are connected to at the same time.
+    pub max_concurrent: usize,
+
+    /// How long to wait for a candidate to connect and answer the handshake.
+    pub candidate_timeout: Duration,
+
+    /// How long to try candidates for in total. `None` means until all of
+    /// them have been tried.
+    pub total_timeout: Option<Duration>,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        Self {
+            network_id: "mainnet".to_string(),
+            node_type: NodeType::Wallet,
+            mempool_updates: false,
+            peer_options: PeerOptions::default(),
+            max_concurrent: 8,
+            candidate_timeout: Duration::from_secs(10),
+            total_timeout: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
+/// A peer that has completed the handshake.
+pub struct ConnectedPeer {
+    pub addr: SocketAddr,
+    pub peer: Peer,
+    /// The handshake the peer answered ours with.
+    pub handshake: Handshake,
+}
+
+/// Connects to the first of `candidates` that answers the handshake, with
+/// [`WebSocketConnector`].
+pub async fn connect_any(
+    candidates: Vec<SocketAddr>,
+    options: &ConnectOptions,
+) -> Result<ConnectedPeer, ConnectError> {
+    connect_any_with(&WebSocketConnector, candidates, options).await
+}
+
+/// Connects to up to `options.max_concurrent` candidates at a time, in
+/// order, and returns the first one that answers the handshake. The other
+/// connections are closed.
+pub async fn connect_any_with<C: Connector + ?Sized>(
+    connector: &C,
+    candidates: Vec<SocketAddr>,
+    options: &ConnectOptions,
+) -> Result<ConnectedPeer, ConnectError> {
+    if candidates.is_empty() {
+        return Err(ConnectError::NoCandidates);
+    }
+
+    let race = async {
+        let mut attempts = stream::iter(candidates)
+            .map(|addr| async move {
+                tokio::time::timeout(
+                    options.candidate_timeout,
+                    connect_peer(connector, addr, options),
+                )
+                .await
+                .unwrap_or(Err(ConnectError::Timeout))
+            })
+            .buffer_unordered(options.max_concurrent.max(1));
+
+        let mut last_error = ConnectError::NoCandidates;
+        while let Some(result) = attempts.next().await {
+            match result {
+                Ok(peer) => return Ok(peer),
+                Err(error) => last_error = error,
+            }
+        }
+        Err(ConnectError::AllFailed(Box::new(last_error)))
+    };
+
+    match options.total_timeout {
+        Some(timeout) => tokio::time::timeout(timeout, race)
+            .await
+            .unwrap_or(Err(ConnectError::Timeout)),
+        None => race.await,
+    }
+}
+
+async fn connect_peer<C: Connector + ?Sized>(
+    connector: &C,
+    addr: SocketAddr,
+    options: &ConnectOptions,
+) -> Result<ConnectedPeer, ConnectError> {
+    let transport = connector.connect(addr).await?;
+    let mut peer = Peer::from_transport(transport, options.peer_options);
+    peer.send_handshake(
+        options.network_id.clone(),
+        options.node_type,
+        options.mempool_updates,
+    )
+    .await?;
+
+    loop {
+        match peer.receiver_mut().recv().await {
+            Ok(PeerEvent::Handshake(handshake)) => {
+                if handshake.network_id != options.network_id {
+                    return Err(ConnectError::WrongNetwork(handshake.network_id));
+                }
+                return Ok(ConnectedPeer {
+                    addr,
+                    peer,
+                    handshake,
+                });
+            }
+            Ok(_) | Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => return Err(ConnectError::Closed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::stream;
+    use chia_protocol::{ChiaProtocolMessage, Message, MessageCodec, ProtocolMessageTypes};
+    use chia_traits::Streamable;
+    use futures_util::SinkExt;
+    use rstest::rstest;
+    use std::collections::HashMap;
+    use tokio::net::TcpListener;
+
+    struct MockResolver(HashMap<&'static str, Vec<SocketAddr>>);
+
+    impl Resolver for MockResolver {
+        fn lookup<'a>(
+            &'a self,
+            host: &'a str,
+            _port: u16,
+        ) -> BoxFuture<'a, io::Result<Vec<SocketAddr>>> {
+            let result = self
+                .0
+                .get(host)
+                .cloned()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, host));
+            Box::pin(future::ready(result))
+        }
+    }
+
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    #[rstest]
+    #[case("1.2.3.4", true)]
+    #[case("0.1.2.3", false)]
+    #[case("127.0.0.1", false)]
+    #[case("10.1.2.3", false)]
+    #[case("172.16.0.1", false)]
+    #[case("192.168.1.1", false)]
+    #[case("169.254.1.1", false)]
+    #[case("100.64.0.1", false)]
+    #[case("100.128.0.1", true)]
+    #[case("224.0.0.1", false)]
+    #[case("255.255.255.255", false)]
+    #[case("192.0.2.1", false)]
+    #[case("2606:4700::1111", true)]
+    #[case("::", false)]
+    #[case("::1", false)]
+    #[case("fd00::1", false)]
+    #[case("fe80::1", false)]
+    #[case("ff02::1", false)]
+    #[case("2001:db8::1", false)]
+    #[case("::ffff:10.0.0.1", false)]
+    #[case("::ffff:1.2.3.4", true)]
+    fn test_is_routable(#[case] ip: &str, #[case] expected: bool) {
+        assert_eq!(is_routable(ip.parse().unwrap()), expected);
+    }
+
+    #[tokio::test]
+    async fn test_discover_peers() {
+        let network = NetworkConfig::mainnet();
+        let resolver = MockResolver(HashMap::from([
+            (
+                "dns-introducer.chia.net",
+                vec![
+                    addr("1.2.3.4:8444"),
+                    addr("10.0.0.1:8444"),
+                    addr("[2606:4700::1111]:8444"),
+                ],
+            ),
+            (
+                "chia.ctrlaltdel.ch",
+                vec![
+                    addr("1.2.3.4:8444"),
+                    addr("127.0.0.1:8444"),
+                    addr("5.6.7.8:8444"),
+                ],
+            ),
+            // the other introducers fail to resolve
+        ]));
+
+        let mut peers = discover_peers_with(&resolver, &network, 10).await;
+        peers.sort();
+        assert_eq!(
+            peers,
+            vec![
+                addr("1.2.3.4:8444"),
+                addr("5.6.7.8:8444"),
+                addr("[2606:4700::1111]:8444"),
+            ]
+        );
+
+        let peers = discover_peers_with(&resolver, &network, 2).await;
+        assert_eq!(peers.len(), 2);
+        assert_ne!(peers[0], peers[1]);
+
+        // a network without introducers
+        let mut network = network;
+        network.dns_introducers.clear();
+        assert!(discover_peers_with(&resolver, &network, 10)
+            .await
+            .is_empty());
+    }
+
+    // a node that answers the handshake with `network_id`
+    async fn mock_node(network_id: &'static str) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let Ok(mut ws) = tokio_tungstenite::accept_async(socket).await else {
+                        return;
+                    };
+                    let codec = MessageCodec::default();
+                    let Some(Ok(request)) = ws.next().await else {
+                        return;
+                    };
+                    let request = codec.decode_frame(request.into_data().as_ref()).unwrap();
+                    assert_eq!(request.msg_type, ProtocolMessageTypes::Handshake);
+                    let request = Handshake::from_bytes(request.data.as_ref()).unwrap();
+
+                    let reply = Handshake {
+                        network_id: network_id.to_string(),
+                        protocol_version: "0.0.36".to_string(),
+                        software_version: "2.5.0".to_string(),
+                        server_port: addr.port(),
+                        node_type: NodeType::FullNode,
+                        capabilities: request.capabilities,
+                    };
+                    let reply = Message {
+                        msg_type: Handshake::msg_type(),
+                        id: None,
+                        data: stream(&reply).unwrap().into(),
+                    };
+                    ws.send(codec.encode_frame(reply).unwrap().into())
+                        .await
+                        .unwrap();
+                    // keep the connection open
+                    while ws.next().await.is_some() {}
+                });
+            }
+        });
+        addr
+    }
+
+    // accepts connections, but never answers
+    async fn silent_node() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            loop {
+                connections.push(listener.accept().await.unwrap());
+            }
+        });
+        addr
+    }
+
+    // nothing listens on this address
+    async fn closed_port() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap()
+    }
+
+    fn options() -> ConnectOptions {
+        ConnectOptions {
+            network_id: "testnet11".to_string(),
+            max_concurrent: 2,
+            candidate_timeout: Duration::from_millis(200),
+            total_timeout: Some(Duration::from_secs(10)),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connect_any() {
+        let good = mock_node("testnet11").await;
+        let candidates = vec![
+            closed_port().await,
+            silent_node().await,
+            mock_node("mainnet").await,
+            silent_node().await,
+            good,
+        ];
+
+        let connected = connect_any(candidates, &options()).await.unwrap();
+        assert_eq!(connected.addr, good);
+        assert_eq!(connected.handshake.network_id, "testnet11");
+        assert_eq!(connected.handshake.server_port, good.port());
+        assert_eq!(connected.handshake.node_type, NodeType::FullNode);
+    }
+
+    #[tokio::test]
+    async fn test_connect_any_failed() {
+        let result = connect_any(vec![], &options()).await;
+        assert!(matches!(result, Err(ConnectError::NoCandidates)));
+
+        let candidates = vec![silent_node().await, mock_node("mainnet").await];
+        let result = connect_any(candidates, &options()).await;
+        let Err(ConnectError::AllFailed(error)) = result else {
+            panic!("expected all candidates to fail");
+        };
+        // the node on the wrong network fails first, the silent one times out
+        assert!(matches!(*error, ConnectError::Timeout));
+    }
+
+    #[tokio::test]
+    async fn test_connect_any_total_timeout() {
+        let options = ConnectOptions {
+            candidate_timeout: Duration::from_secs(10),
+            total_timeout: Some(Duration::from_millis(100)),
+            ..options()
+        };
+        let candidates = vec![silent_node().await, silent_node().await];
+        let result = connect_any(candidates, &options).await;
+        assert!(matches!(result, Err(ConnectError::Timeout)));
+    }
+}
@@ -1,4 +1,6 @@
-use chia_protocol::Message;
+use std::time::Duration;
+
+use chia_protocol::{Bytes, CodecError, Message, ProtocolErrorCode};
 use chia_traits::chia_error;
 use thiserror::Error;
 
@@ -10,12 +12,129 @@ pub enum Error<R> {
     #[error("{0}")]
     WebSocket(#[from] tungstenite::Error),
 
+    #[error("{0}")]
+    Codec(#[from] CodecError),
+
     #[error("{0:?}")]
     InvalidResponse(Message),
 
     #[error("missing response")]
     MissingResponse,
 
+    #[error("request timed out")]
+    Timeout,
+
+    #[error("too many requests in flight")]
+    TooManyRequests,
+
     #[error("rejection")]
     Rejection(R),
+
+    /// The peer answered the request with a `ProtocolError` message.
+    #[error("peer error {0:?}")]
+    Peer(ProtocolErrorCode, Option<Bytes>),
+}
+
+impl<R> Error<R> {
+    /// Whether the peer refused the request because of its rate limits.
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, Self::Peer(ProtocolErrorCode::RateLimited, _))
+    }
+
+    /// How long the peer asked to wait before retrying a rate limited
+    /// request, if it said so.
+    pub fn retry_after(&self) -> Option<Duration> {
+        let Self::Peer(ProtocolErrorCode::RateLimited, Some(data)) = self else {
+            return None;
+        };
+        let seconds: [u8; 4] = data.as_ref().try_into().ok()?;
+        Some(Duration::from_secs(u32::from_be_bytes(seconds).into()))
+    }
+}
+
+/// The errors a [`Transport`](crate::Transport) can fail with.
+#[derive(Debug, Error)]
+pub enum TransportError {
+    #[error("{0}")]
+    WebSocket(#[from] tungstenite::Error),
+
+    #[error("{0}")]
+    Codec(#[from] CodecError),
+}
+
+impl<R> From<TransportError> for Error<R> {
+    fn from(error: TransportError) -> Self {
+        match error {
+            TransportError::WebSocket(error) => Self::WebSocket(error),
+            TransportError::Codec(error) => Self::Codec(error),
+        }
+    }
+}
+
+/// The errors [`connect_any()`](crate::connect_any) can fail with.
+#[derive(Debug, Error)]
+pub enum ConnectError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    WebSocket(#[from] tungstenite::Error),
+
+    #[error("{0}")]
+    Peer(#[from] Error<()>),
+
+    #[error("the peer closed the connection")]
+    Closed,
+
+    #[error("the peer is on network {0}")]
+    WrongNetwork(String),
+
+    #[error("connection timed out")]
+    Timeout,
+
+    #[error("no peers to connect to")]
+    NoCandidates,
+
+    #[error("no peer could be connected to, the last error was: {0}")]
+    AllFailed(Box<ConnectError>),
+}
+
+/// The errors a [`SubscriptionManager`](crate::SubscriptionManager) can fail
+/// with.
+#[derive(Debug, Error)]
+pub enum SubscriptionError {
+    #[error("{0}")]
+    Peer(#[from] Error<()>),
+
+    /// Subscribing to the new items would take the peer over its subscription
+    /// limit, so nothing was sent. The node would otherwise ignore the
+    /// excess items without telling us.
+    #[error("subscribing to {requested} more items would exceed the limit of {max} ({active} are active)")]
+    LimitExceeded {
+        requested: usize,
+        active: usize,
+        max: usize,
+    },
+}
+
+/// The errors loading a [`TlsConfig`](crate::TlsConfig) can fail with.
+#[derive(Debug, Error)]
+pub enum TlsError {
+    #[error("{0}")]
+    Pem(#[from] std::io::Error),
+
+    #[error("no certificate found")]
+    MissingCertificate,
+
+    #[error("no private key found")]
+    MissingPrivateKey,
+
+    #[error("the public Chia CA can't be used for private services")]
+    PublicCa,
+
+    #[error("{0}")]
+    Rustls(#[from] rustls::Error),
+
+    #[error("{0}")]
+    Verifier(#[from] rustls::server::VerifierBuilderError),
 }
@@ -1,6 +1,18 @@
+mod address_book;
+mod discovery;
 mod error;
+mod peak_tracker;
 mod peer;
+mod subscriptions;
+mod tls;
+mod transport;
 mod utils;
 
+pub use address_book::*;
+pub use discovery::*;
 pub use error::*;
+pub use peak_tracker::*;
 pub use peer::*;
+pub use subscriptions::*;
+pub use tls::*;
+pub use transport::*;
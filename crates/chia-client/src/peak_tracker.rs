@@ -0,0 +1,278 @@
+use std::collections::VecDeque;
+
+use chia_protocol::{Bytes32, NewPeakWallet};
+
+/// Why a `NewPeakWallet` announcement didn't change the peak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgnoreReason {
+    /// The header hash was already announced.
+    Duplicate,
+    /// The peak is not heavier than the current peak.
+    NotHeavier,
+    /// The peak forks off further back than the maximum reorg depth. The
+    /// caller may want to sync from scratch (e.g. using a weight proof)
+    /// instead of trusting the peer.
+    ReorgTooDeep,
+}
+
+/// What to do in response to a `NewPeakWallet` announcement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeakAction {
+    Ignore {
+        reason: IgnoreReason,
+    },
+    /// The peak extends the current chain. The blocks `start..=end` need to
+    /// be fetched.
+    FetchBlocks {
+        start: u32,
+        end: u32,
+    },
+    /// The peak is on a different chain. Everything above `fork_height` has
+    /// to be rolled back, and the blocks from `fork_height + 1` up to the new
+    /// peak fetched.
+    Reorg {
+        fork_height: u32,
+    },
+}
+
+/// Keeps track of the peak a light client is following, based on the
+/// `NewPeakWallet` messages from a peer. It remembers the header hashes of the
+/// peaks announced within the last `max_reorg_depth` blocks, to ignore
+/// repeated announcements. This is pure state; it doesn't send any messages.
+#[derive(Debug, Clone)]
+pub struct PeakTracker {
+    peak: Option<NewPeakWallet>,
+    // (height, header hash) of recent peaks, in ascending height order
+    recent: VecDeque<(u32, Bytes32)>,
+    max_reorg_depth: u32,
+}
+
+impl PeakTracker {
+    pub fn new(max_reorg_depth: u32) -> Self {
+        Self {
+            peak: None,
+            recent: VecDeque::new(),
+            max_reorg_depth,
+        }
+    }
+
+    pub fn peak(&self) -> Option<&NewPeakWallet> {
+        self.peak.as_ref()
+    }
+
+    pub fn max_reorg_depth(&self) -> u32 {
+        self.max_reorg_depth
+    }
+
+    /// The header hashes of the recent peaks, and their heights, in ascending
+    /// height order.
+    pub fn recent_header_hashes(&self) -> impl Iterator<Item = (u32, Bytes32)> + '_ {
+        self.recent.iter().copied()
+    }
+
+    pub fn on_new_peak(&mut self, new_peak: NewPeakWallet) -> PeakAction {
+        if self
+            .recent
+            .iter()
+            .any(|(_, hash)| *hash == new_peak.header_hash)
+        {
+            return PeakAction::Ignore {
+                reason: IgnoreReason::Duplicate,
+            };
+        }
+
+        let action = match &self.peak {
+            None => PeakAction::FetchBlocks {
+                start: new_peak.height,
+                end: new_peak.height,
+            },
+            Some(peak) if new_peak.weight <= peak.weight => {
+                return PeakAction::Ignore {
+                    reason: IgnoreReason::NotHeavier,
+                };
+            }
+            Some(peak)
+                if new_peak.height > peak.height
+                    && new_peak.fork_point_with_previous_peak >= peak.height =>
+            {
+                PeakAction::FetchBlocks {
+                    start: peak.height + 1,
+                    end: new_peak.height,
+                }
+            }
+            Some(peak) => {
+                let fork_height = new_peak
+                    .fork_point_with_previous_peak
+                    .min(new_peak.height.saturating_sub(1));
+                if peak.height.saturating_sub(fork_height) > self.max_reorg_depth {
+                    return PeakAction::Ignore {
+                        reason: IgnoreReason::ReorgTooDeep,
+                    };
+                }
+                while self
+                    .recent
+                    .back()
+                    .is_some_and(|(height, _)| *height > fork_height)
+                {
+                    self.recent.pop_back();
+                }
+                PeakAction::Reorg { fork_height }
+            }
+        };
+
+        self.recent
+            .push_back((new_peak.height, new_peak.header_hash));
+        let min_height = new_peak.height.saturating_sub(self.max_reorg_depth);
+        while self
+            .recent
+            .front()
+            .is_some_and(|(height, _)| *height < min_height)
+        {
+            self.recent.pop_front();
+        }
+        self.peak = Some(new_peak);
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peak(id: u8, height: u32, fork_point: u32) -> NewPeakWallet {
+        // the weight grows with the height, and breaks ties by id
+        NewPeakWallet::new(
+            Bytes32::new([id; 32]),
+            height,
+            u128::from(height) * 100 + u128::from(id),
+            fork_point,
+        )
+    }
+
+    #[test]
+    fn test_linear_advance() {
+        let mut tracker = PeakTracker::new(10);
+        assert_eq!(
+            tracker.on_new_peak(peak(1, 100, 99)),
+            PeakAction::FetchBlocks {
+                start: 100,
+                end: 100
+            }
+        );
+        assert_eq!(
+            tracker.on_new_peak(peak(2, 101, 100)),
+            PeakAction::FetchBlocks {
+                start: 101,
+                end: 101
+            }
+        );
+        // we missed a few announcements
+        assert_eq!(
+            tracker.on_new_peak(peak(3, 105, 104)),
+            PeakAction::FetchBlocks {
+                start: 102,
+                end: 105
+            }
+        );
+        assert_eq!(tracker.peak(), Some(&peak(3, 105, 104)));
+        assert_eq!(
+            tracker.recent_header_hashes().collect::<Vec<_>>(),
+            vec![
+                (100, Bytes32::new([1; 32])),
+                (101, Bytes32::new([2; 32])),
+                (105, Bytes32::new([3; 32])),
+            ]
+        );
+
+        // old peaks fall out of the window
+        tracker.on_new_peak(peak(4, 111, 110));
+        assert_eq!(
+            tracker
+                .recent_header_hashes()
+                .map(|(height, _)| height)
+                .collect::<Vec<_>>(),
+            vec![101, 105, 111]
+        );
+    }
+
+    #[test]
+    fn test_short_reorg() {
+        let mut tracker = PeakTracker::new(10);
+        tracker.on_new_peak(peak(1, 100, 99));
+        tracker.on_new_peak(peak(2, 101, 100));
+        tracker.on_new_peak(peak(3, 102, 101));
+
+        // a heavier chain forking off at 100
+        assert_eq!(
+            tracker.on_new_peak(peak(4, 102, 100)),
+            PeakAction::Reorg { fork_height: 100 }
+        );
+        assert_eq!(tracker.peak(), Some(&peak(4, 102, 100)));
+        // the orphaned peaks are forgotten
+        assert_eq!(
+            tracker.recent_header_hashes().collect::<Vec<_>>(),
+            vec![(100, Bytes32::new([1; 32])), (102, Bytes32::new([4; 32]))]
+        );
+
+        // and the new chain keeps advancing
+        assert_eq!(
+            tracker.on_new_peak(peak(5, 103, 102)),
+            PeakAction::FetchBlocks {
+                start: 103,
+                end: 103
+            }
+        );
+    }
+
+    #[test]
+    fn test_deep_reorg() {
+        let mut tracker = PeakTracker::new(10);
+        tracker.on_new_peak(peak(1, 100, 99));
+        tracker.on_new_peak(peak(2, 120, 119));
+
+        assert_eq!(
+            tracker.on_new_peak(peak(3, 121, 105)),
+            PeakAction::Ignore {
+                reason: IgnoreReason::ReorgTooDeep
+            }
+        );
+        assert_eq!(tracker.peak(), Some(&peak(2, 120, 119)));
+
+        // exactly at the maximum depth is fine
+        assert_eq!(
+            tracker.on_new_peak(peak(4, 121, 110)),
+            PeakAction::Reorg { fork_height: 110 }
+        );
+    }
+
+    #[test]
+    fn test_duplicate() {
+        let mut tracker = PeakTracker::new(10);
+        assert!(matches!(
+            tracker.on_new_peak(peak(1, 100, 99)),
+            PeakAction::FetchBlocks { .. }
+        ));
+        assert_eq!(
+            tracker.on_new_peak(peak(1, 100, 99)),
+            PeakAction::Ignore {
+                reason: IgnoreReason::Duplicate
+            }
+        );
+        tracker.on_new_peak(peak(2, 101, 100));
+        // an earlier peak announced again
+        assert_eq!(
+            tracker.on_new_peak(peak(1, 100, 99)),
+            PeakAction::Ignore {
+                reason: IgnoreReason::Duplicate
+            }
+        );
+        // a different peak that's not heavier
+        assert_eq!(
+            tracker.on_new_peak(peak(0, 101, 100)),
+            PeakAction::Ignore {
+                reason: IgnoreReason::NotHeavier
+            }
+        );
+        assert_eq!(tracker.peak(), Some(&peak(2, 101, 100)));
+    }
+}
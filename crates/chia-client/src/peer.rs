@@ -1,43 +1,94 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicU16, Ordering};
-use std::{collections::HashMap, sync::Arc};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
 
 use chia_protocol::*;
 use chia_traits::Streamable;
-use futures_util::stream::SplitSink;
-use futures_util::{SinkExt, StreamExt};
-use tokio::sync::{broadcast, oneshot, Mutex};
+use futures_util::{future, SinkExt, StreamExt};
+use tokio::sync::{broadcast, oneshot, Mutex, Semaphore};
 use tokio::{net::TcpStream, task::JoinHandle};
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
-use tungstenite::Message as WsMessage;
 
 use crate::utils::stream;
-use crate::Error;
+use crate::{Error, MessageSink, Transport};
 
 type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
-type Requests = Arc<Mutex<HashMap<u16, oneshot::Sender<Message>>>>;
+type Requests = Arc<StdMutex<HashMap<u16, oneshot::Sender<Message>>>>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PeerEvent {
+    /// The other side's handshake, which it sends in response to ours.
+    Handshake(Handshake),
     CoinStateUpdate(CoinStateUpdate),
     NewPeakWallet(NewPeakWallet),
     MempoolItemsAdded(MempoolItemsAdded),
     MempoolItemsRemoved(MempoolItemsRemoved),
 }
 
+/// Settings for a [`Peer`] that can't be changed once it's connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerOptions {
+    /// The default timeout for requests, see [`Peer::request_timeout()`].
+    pub request_timeout: Option<Duration>,
+
+    /// The largest message that will be sent or accepted, including the
+    /// header.
+    pub max_message_size: usize,
+
+    /// The most requests that can be waiting for a response at the same time.
+    /// Further requests wait until one of them completes. `None` (the
+    /// default) means no limit, other than the 65536 message ids.
+    pub max_in_flight_requests: Option<NonZeroUsize>,
+}
+
+impl Default for PeerOptions {
+    fn default() -> Self {
+        Self {
+            request_timeout: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            max_in_flight_requests: None,
+        }
+    }
+}
+
 pub struct Peer {
-    sink: Mutex<SplitSink<WebSocket, tungstenite::Message>>,
+    sink: Mutex<MessageSink>,
     inbound_task: JoinHandle<()>,
     event_receiver: broadcast::Receiver<PeerEvent>,
     requests: Requests,
-
-    // TODO: This does not currently prevent multiple requests with the same id at the same time.
-    // If one of them is still running while all other ids are being iterated through.
+    request_timeout: Option<Duration>,
+    in_flight: Option<Semaphore>,
     nonce: AtomicU16,
+    peer_cert_fingerprint: Option<Bytes32>,
+}
+
+// Removes the entry of a request from the map once it completes, times out,
+// or is dropped before completing.
+struct PendingRequest<'a> {
+    requests: &'a Requests,
+    id: u16,
+}
+
+impl Drop for PendingRequest<'_> {
+    fn drop(&mut self) {
+        self.requests.lock().expect("requests").remove(&self.id);
+    }
 }
 
 impl Peer {
+    /// Creates a peer from a websocket connection, with the default options.
     pub fn new(ws: WebSocket) -> Self {
-        let (sink, mut stream) = ws.split();
+        Self::from_transport(ws, PeerOptions::default())
+    }
+
+    /// Creates a peer that exchanges messages over any [`Transport`].
+    pub fn from_transport<T: Transport>(transport: T, options: PeerOptions) -> Self {
+        let peer_cert_fingerprint = transport.peer_cert_fingerprint();
+        let codec = MessageCodec::new(options.max_message_size);
+        let (sink, mut stream) = transport.into_split(codec);
         let (event_sender, event_receiver) = broadcast::channel(32);
 
         let requests = Requests::default();
@@ -46,9 +97,7 @@ impl Peer {
         let inbound_task = tokio::spawn(async move {
             while let Some(message) = stream.next().await {
                 if let Ok(message) = message {
-                    Self::handle_inbound(message, &requests_clone, &event_sender)
-                        .await
-                        .ok();
+                    Self::handle_inbound(message, &requests_clone, &event_sender).ok();
                 }
             }
         });
@@ -58,10 +107,38 @@ impl Peer {
             inbound_task,
             event_receiver,
             requests,
+            request_timeout: options.request_timeout,
+            in_flight: options
+                .max_in_flight_requests
+                .map(|limit| Semaphore::new(limit.get())),
             nonce: AtomicU16::new(0),
+            peer_cert_fingerprint,
         }
     }
 
+    /// The fingerprint of the certificate this peer authenticated with, if
+    /// the transport uses TLS. Services can use it to only accept specific
+    /// peers.
+    pub fn peer_cert_fingerprint(&self) -> Option<Bytes32> {
+        self.peer_cert_fingerprint
+    }
+
+    /// The default timeout for requests sent to this peer. The typed request
+    /// helpers all use this. `None` (the default) means wait indefinitely.
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+    }
+
+    pub fn set_request_timeout(&mut self, timeout: Option<Duration>) {
+        self.request_timeout = timeout;
+    }
+
+    /// The number of requests that have been sent and are still waiting for
+    /// a response.
+    pub fn pending_requests(&self) -> usize {
+        self.requests.lock().expect("requests").len()
+    }
+
     pub async fn send_handshake(
         &self,
         network_id: String,
@@ -126,22 +203,26 @@ impl Peer {
         start_height: u32,
         end_height: u32,
         return_filter: bool,
-    ) -> Result<Vec<HeaderBlock>, Error<()>> {
+    ) -> Result<Vec<HeaderBlock>, Error<RejectBlockHeaders>> {
         let body = RequestBlockHeaders {
             start_height,
             end_height,
             return_filter,
         };
-        let response: RespondBlockHeaders =
-            self.request_or_reject(body)
-                .await
-                .map_err(|error: Error<RejectBlockHeaders>| match error {
-                    Error::Rejection(_rejection) => Error::Rejection(()),
-                    Error::Chia(error) => Error::Chia(error),
-                    Error::WebSocket(error) => Error::WebSocket(error),
-                    Error::InvalidResponse(error) => Error::InvalidResponse(error),
-                    Error::MissingResponse => Error::MissingResponse,
-                })?;
+        let response: RespondBlockHeaders = self.request_or_reject(body).await?;
+        Ok(response.header_blocks)
+    }
+
+    pub async fn request_header_blocks(
+        &self,
+        start_height: u32,
+        end_height: u32,
+    ) -> Result<Vec<HeaderBlock>, Error<RejectHeaderBlocks>> {
+        let body = RequestHeaderBlocks {
+            start_height,
+            end_height,
+        };
+        let response: RespondHeaderBlocks = self.request_or_reject(body).await?;
         Ok(response.header_blocks)
     }
 
@@ -199,6 +280,58 @@ impl Peer {
         Ok(response.coin_states)
     }
 
+    pub async fn request_remove_puzzle_subscriptions(
+        &self,
+        puzzle_hashes: Option<Vec<Bytes32>>,
+    ) -> Result<Vec<Bytes32>, Error<()>> {
+        let body = RequestRemovePuzzleSubscriptions { puzzle_hashes };
+        let response: RespondRemovePuzzleSubscriptions = self.request(body).await?;
+        Ok(response.puzzle_hashes)
+    }
+
+    pub async fn request_remove_coin_subscriptions(
+        &self,
+        coin_ids: Option<Vec<Bytes32>>,
+    ) -> Result<Vec<Bytes32>, Error<()>> {
+        let body = RequestRemoveCoinSubscriptions { coin_ids };
+        let response: RespondRemoveCoinSubscriptions = self.request(body).await?;
+        Ok(response.coin_ids)
+    }
+
+    pub async fn request_puzzle_state(
+        &self,
+        puzzle_hashes: Vec<Bytes32>,
+        previous_height: Option<u32>,
+        header_hash: Bytes32,
+        filters: CoinStateFilters,
+        subscribe_when_finished: bool,
+    ) -> Result<RespondPuzzleState, Error<RejectPuzzleState>> {
+        let body = RequestPuzzleState {
+            puzzle_hashes,
+            previous_height,
+            header_hash,
+            filters,
+            subscribe_when_finished,
+        };
+        self.request_or_reject(body).await
+    }
+
+    pub async fn request_coin_state(
+        &self,
+        coin_ids: Vec<Bytes32>,
+        previous_height: Option<u32>,
+        header_hash: Bytes32,
+        subscribe: bool,
+    ) -> Result<RespondCoinState, Error<RejectCoinState>> {
+        let body = RequestCoinState {
+            coin_ids,
+            previous_height,
+            header_hash,
+            subscribe,
+        };
+        self.request_or_reject(body).await
+    }
+
     pub async fn request_children(&self, coin_id: Bytes32) -> Result<Vec<CoinState>, Error<()>> {
         let body = RequestChildren { coin_name: coin_id };
         let response: RespondChildren = self.request(body).await?;
@@ -226,6 +359,15 @@ impl Peer {
         Ok(response.estimates)
     }
 
+    pub async fn request_cost_info(&self) -> Result<RespondCostInfo, Error<()>> {
+        self.request(RequestCostInfo {}).await
+    }
+
+    pub async fn request_peers(&self) -> Result<Vec<TimestampedPeerInfo>, Error<()>> {
+        let response: RespondPeers = self.request(RequestPeers::new()).await?;
+        Ok(response.peer_list)
+    }
+
     pub async fn send<T>(&self, body: T) -> Result<(), Error<()>>
     where
         T: Streamable + ChiaProtocolMessage,
@@ -237,9 +379,9 @@ impl Peer {
             data: stream(&body)?.into(),
         };
 
-        // Send the message through the websocket.
+        // Send the message through the transport.
         let mut sink = self.sink.lock().await;
-        sink.send(stream(&message)?.into()).await?;
+        sink.send(message).await?;
 
         Ok(())
     }
@@ -250,7 +392,23 @@ impl Peer {
         R: Streamable + ChiaProtocolMessage,
         B: Streamable + ChiaProtocolMessage,
     {
-        let message = self.request_raw(body).await?;
+        self.request_or_reject_with_timeout(body, self.request_timeout)
+            .await
+    }
+
+    /// Like `request_or_reject()`, but overrides the peer's default request
+    /// timeout for this call.
+    pub async fn request_or_reject_with_timeout<T, R, B>(
+        &self,
+        body: B,
+        timeout: Option<Duration>,
+    ) -> Result<T, Error<R>>
+    where
+        T: Streamable + ChiaProtocolMessage,
+        R: Streamable + ChiaProtocolMessage,
+        B: Streamable + ChiaProtocolMessage,
+    {
+        let message = self.request_raw_with_timeout(body, timeout).await?;
         let data = message.data.as_ref();
 
         if message.msg_type == T::msg_type() {
@@ -268,7 +426,21 @@ impl Peer {
         Response: Streamable + ChiaProtocolMessage,
         T: Streamable + ChiaProtocolMessage,
     {
-        let message = self.request_raw(body).await?;
+        self.request_with_timeout(body, self.request_timeout).await
+    }
+
+    /// Like `request()`, but overrides the peer's default request timeout for
+    /// this call.
+    pub async fn request_with_timeout<Response, T>(
+        &self,
+        body: T,
+        timeout: Option<Duration>,
+    ) -> Result<Response, Error<()>>
+    where
+        Response: Streamable + ChiaProtocolMessage,
+        T: Streamable + ChiaProtocolMessage,
+    {
+        let message = self.request_raw_with_timeout(body, timeout).await?;
         let data = message.data.as_ref();
 
         if message.msg_type == Response::msg_type() {
@@ -282,43 +454,93 @@ impl Peer {
     where
         T: Streamable + ChiaProtocolMessage,
     {
-        // Get the current nonce and increment.
-        let message_id = self.nonce.fetch_add(1, Ordering::SeqCst);
+        self.request_raw_with_timeout(body, self.request_timeout)
+            .await
+    }
 
-        // Create the message.
+    /// Sends a request and waits for the response with the same message id.
+    /// If the peer answers with a `ProtocolError`, `Error::Peer` is returned.
+    /// If `timeout` elapses first, the request is abandoned and
+    /// `Error::Timeout` is returned. When the number of requests in flight is
+    /// limited, the timeout starts once the request has been sent.
+    pub async fn request_raw_with_timeout<T, R>(
+        &self,
+        body: T,
+        timeout: Option<Duration>,
+    ) -> Result<Message, Error<R>>
+    where
+        T: Streamable + ChiaProtocolMessage,
+    {
+        let data = stream(&body)?.into();
+
+        // Wait for a free slot. The permit is held until the request completes.
+        let _permit = match &self.in_flight {
+            Some(semaphore) => Some(semaphore.acquire().await.expect("semaphore closed")),
+            None => None,
+        };
+
+        let (message_id, receiver) = self.register_request()?;
+        let _pending = PendingRequest {
+            requests: &self.requests,
+            id: message_id,
+        };
+
+        // Send the message.
         let message = Message {
             msg_type: T::msg_type(),
             id: Some(message_id),
-            data: stream(&body)?.into(),
+            data,
         };
+        self.sink.lock().await.send(message).await?;
 
-        // Create a saved oneshot channel to receive the response.
-        let (sender, receiver) = oneshot::channel::<Message>();
-        self.requests.lock().await.insert(message_id, sender);
-
-        // Send the message.
-        let bytes = match stream(&message) {
-            Ok(bytes) => bytes.into(),
-            Err(error) => {
-                self.requests.lock().await.remove(&message_id);
-                return Err(error.into());
-            }
+        // Wait for the response.
+        let response = if let Some(timeout) = timeout {
+            let Ok(response) = tokio::time::timeout(timeout, receiver).await else {
+                return Err(Error::Timeout);
+            };
+            response
+        } else {
+            receiver.await
         };
-        let send_result = self.sink.lock().await.send(bytes).await;
 
-        if let Err(error) = send_result {
-            self.requests.lock().await.remove(&message_id);
-            return Err(error.into());
+        // Handle the response, if present.
+        let response = response.or(Err(Error::MissingResponse))?;
+
+        // The peer failed to handle the request.
+        if response.msg_type == ProtocolMessageTypes::Error {
+            let error = ProtocolError::from_bytes(response.data.as_ref())
+                .or(Err(Error::InvalidResponse(response)))?;
+            return Err(Error::Peer(error.error_code(), error.data));
         }
 
-        // Wait for the response.
-        let response = receiver.await;
+        Ok(response)
+    }
 
-        // Remove the one shot channel.
-        self.requests.lock().await.remove(&message_id);
+    /// Sends all requests without waiting for the responses in between, and
+    /// returns the results in the same order as the requests. Each request
+    /// succeeds or fails (e.g. with `Error::Timeout`) on its own. The limit of
+    /// [`PeerOptions::max_in_flight_requests`] still applies.
+    pub async fn send_batch<Response, T>(&self, bodies: Vec<T>) -> Vec<Result<Response, Error<()>>>
+    where
+        Response: Streamable + ChiaProtocolMessage,
+        T: Streamable + ChiaProtocolMessage,
+    {
+        future::join_all(bodies.into_iter().map(|body| self.request(body))).await
+    }
 
-        // Handle the response, if present.
-        response.or(Err(Error::MissingResponse))
+    // Picks a message id that isn't used by a request in flight, and saves a
+    // oneshot channel to receive the response with.
+    fn register_request<R>(&self) -> Result<(u16, oneshot::Receiver<Message>), Error<R>> {
+        let mut requests = self.requests.lock().expect("requests");
+        for _ in 0..=u16::MAX {
+            let message_id = self.nonce.fetch_add(1, Ordering::SeqCst);
+            if let Entry::Vacant(entry) = requests.entry(message_id) {
+                let (sender, receiver) = oneshot::channel::<Message>();
+                entry.insert(sender);
+                return Ok((message_id, receiver));
+            }
+        }
+        Err(Error::TooManyRequests)
     }
 
     pub fn receiver(&self) -> &broadcast::Receiver<PeerEvent> {
@@ -329,17 +551,15 @@ impl Peer {
         &mut self.event_receiver
     }
 
-    async fn handle_inbound(
-        message: WsMessage,
+    fn handle_inbound(
+        message: Message,
         requests: &Requests,
         event_sender: &broadcast::Sender<PeerEvent>,
     ) -> Result<(), Error<()>> {
-        // Parse the message.
-        let message = Message::from_bytes(message.into_data().as_ref())?;
-
         if let Some(id) = message.id {
             // Send response through oneshot channel if present.
-            if let Some(request) = requests.lock().await.remove(&id) {
+            let request = requests.lock().expect("requests").remove(&id);
+            if let Some(request) = request {
                 request.send(message).ok();
             }
             return Ok(());
@@ -360,6 +580,7 @@ impl Peer {
 
         // TODO: Handle unexpected messages.
         events!(
+            Handshake,
             CoinStateUpdate,
             NewPeakWallet,
             MempoolItemsAdded,
@@ -375,3 +596,460 @@ impl Drop for Peer {
         self.inbound_task.abort();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryTransport;
+    use chia_protocol::CodecError;
+    use rstest::rstest;
+    use tokio::io::DuplexStream;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_util::codec::Framed;
+
+    type MockPeer = WebSocketStream<TcpStream>;
+    type InMemoryMockPeer = Framed<DuplexStream, MessageCodec>;
+
+    async fn connect() -> (Peer, MockPeer) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (client, server) = tokio::join!(
+            tokio_tungstenite::connect_async(format!("ws://{addr}")),
+            async {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio_tungstenite::accept_async(stream).await
+            }
+        );
+        (Peer::new(client.unwrap().0), server.unwrap())
+    }
+
+    fn connect_in_memory(options: PeerOptions) -> (Peer, InMemoryMockPeer) {
+        let (client, server) = InMemoryTransport::pair();
+        let mock = Framed::new(server.into_inner(), MessageCodec::default());
+        (Peer::from_transport(client, options), mock)
+    }
+
+    fn message<T>(id: Option<u16>, body: &T) -> Message
+    where
+        T: Streamable + ChiaProtocolMessage,
+    {
+        Message {
+            msg_type: T::msg_type(),
+            id,
+            data: stream(body).unwrap().into(),
+        }
+    }
+
+    // waits for the next request and answers it with `body`. Returns the
+    // request
+    async fn respond<T>(mock: &mut MockPeer, body: T) -> Message
+    where
+        T: Streamable + ChiaProtocolMessage,
+    {
+        let codec = MessageCodec::default();
+        let request = mock.next().await.unwrap().unwrap();
+        let request = codec.decode_frame(request.into_data().as_ref()).unwrap();
+        let response = Message {
+            msg_type: T::msg_type(),
+            id: request.id,
+            data: stream(&body).unwrap().into(),
+        };
+        mock.send(codec.encode_frame(response).unwrap().into())
+            .await
+            .unwrap();
+        request
+    }
+
+    #[tokio::test]
+    async fn test_request_children() {
+        let (peer, mut mock) = connect().await;
+        let coin_id = Bytes32::new([1; 32]);
+        let coin_state = CoinState::new(
+            Coin::new(coin_id, Bytes32::new([2; 32]), Amount::new(1337)),
+            None,
+            Some(10),
+        );
+
+        let (response, request) = tokio::join!(
+            peer.request_children(coin_id),
+            respond(&mut mock, RespondChildren::new(vec![coin_state]))
+        );
+        assert_eq!(response.unwrap(), vec![coin_state]);
+        assert_eq!(request.msg_type, ProtocolMessageTypes::RequestChildren);
+        assert_eq!(
+            RequestChildren::from_bytes(request.data.as_ref()).unwrap(),
+            RequestChildren::new(coin_id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_transaction() {
+        let (peer, mut mock) = connect().await;
+        let ack = TransactionAck::new(Bytes32::new([3; 32]), 1, None);
+
+        // an empty spend bundle, with the infinity signature
+        let mut bytes = vec![0_u8; 4 + 96];
+        bytes[4] = 0xc0;
+        let spend_bundle = SpendBundle::from_bytes(&bytes).unwrap();
+
+        let (response, request) = tokio::join!(
+            peer.send_transaction(spend_bundle),
+            respond(&mut mock, ack.clone())
+        );
+        assert_eq!(response.unwrap(), ack);
+        assert_eq!(request.msg_type, ProtocolMessageTypes::SendTransaction);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_puzzle_solution() {
+        let (peer, mut mock) = connect().await;
+        let coin_id = Bytes32::new([4; 32]);
+        let rejection = RejectPuzzleSolution::new(coin_id, 100);
+
+        let (response, _) = tokio::join!(
+            peer.request_puzzle_and_solution(coin_id, 100),
+            respond(&mut mock, rejection.clone())
+        );
+        let Err(Error::Rejection(reason)) = response else {
+            panic!("expected rejection, got {response:?}");
+        };
+        assert_eq!(reason, rejection);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_block_header() {
+        let (peer, mut mock) = connect().await;
+
+        let (response, _) = tokio::join!(
+            peer.request_block_header(42),
+            respond(&mut mock, RejectHeaderRequest::new(42))
+        );
+        let Err(Error::Rejection(reason)) = response else {
+            panic!("expected rejection, got {response:?}");
+        };
+        assert_eq!(reason.height, 42);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_coin_state() {
+        let (peer, mut mock) = connect().await;
+
+        let (response, _) = tokio::join!(
+            peer.request_coin_state(vec![], None, Bytes32::default(), false),
+            respond(
+                &mut mock,
+                RejectCoinState::new(RejectStateReason::ExceededSubscriptionLimit)
+            )
+        );
+        let Err(Error::Rejection(reason)) = response else {
+            panic!("expected rejection, got {response:?}");
+        };
+        assert_eq!(reason.reason, RejectStateReason::ExceededSubscriptionLimit);
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_response() {
+        let (peer, mut mock) = connect().await;
+
+        let (response, _) = tokio::join!(
+            peer.request_children(Bytes32::default()),
+            respond(&mut mock, RespondPeers::new(vec![]))
+        );
+        let Err(Error::InvalidResponse(message)) = response else {
+            panic!("expected invalid response, got {response:?}");
+        };
+        assert_eq!(message.msg_type, ProtocolMessageTypes::RespondPeers);
+    }
+
+    #[rstest]
+    #[case::unknown(ProtocolErrorCode::Unknown, None, None)]
+    #[case::rate_limited(ProtocolErrorCode::RateLimited, None, None)]
+    #[case::retry_after(
+        ProtocolErrorCode::RateLimited,
+        Some(vec![0, 0, 0, 30]),
+        Some(Duration::from_secs(30))
+    )]
+    #[case::invalid_backoff(ProtocolErrorCode::RateLimited, Some(vec![30]), None)]
+    #[case::unknown_capability(ProtocolErrorCode::UnknownCapability, None, None)]
+    #[case::other(ProtocolErrorCode::Other(42), Some(vec![0, 0, 0, 30]), None)]
+    #[tokio::test]
+    async fn test_protocol_error(
+        #[case] code: ProtocolErrorCode,
+        #[case] data: Option<Vec<u8>>,
+        #[case] retry_after: Option<Duration>,
+    ) {
+        let (mut peer, mut mock) = connect().await;
+        // the error arrives long before the timeout
+        peer.set_request_timeout(Some(Duration::from_secs(60)));
+        let error = ProtocolError::new(
+            code.into(),
+            "request failed".to_string(),
+            data.clone().map(Into::into),
+        );
+
+        let (response, _) = tokio::join!(
+            peer.request_coin_state(vec![], None, Bytes32::default(), false),
+            respond(&mut mock, error.clone())
+        );
+        let Err(Error::Peer(actual_code, actual_data)) = &response else {
+            panic!("expected peer error, got {response:?}");
+        };
+        assert_eq!(*actual_code, code);
+        assert_eq!(actual_data, &data.map(Bytes::from));
+        let response = response.unwrap_err();
+        assert_eq!(
+            response.is_rate_limited(),
+            code == ProtocolErrorCode::RateLimited
+        );
+        assert_eq!(response.retry_after(), retry_after);
+
+        // requests without a rejection type are answered the same way
+        let (response, _) = tokio::join!(
+            peer.request_children(Bytes32::default()),
+            respond(&mut mock, error)
+        );
+        assert!(matches!(response, Err(Error::Peer(c, _)) if c == code));
+        assert_eq!(peer.pending_requests(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_protocol_error() {
+        let (peer, mut mock) = connect_in_memory(PeerOptions::default());
+
+        // an error message that can't be parsed
+        let (response, ()) = tokio::join!(peer.request_children(Bytes32::default()), async {
+            let request = mock.next().await.unwrap().unwrap();
+            let response = Message {
+                msg_type: ProtocolMessageTypes::Error,
+                id: request.id,
+                data: vec![0xff].into(),
+            };
+            mock.send(response).await.unwrap();
+        });
+        let Err(Error::InvalidResponse(message)) = response else {
+            panic!("expected invalid response, got {response:?}");
+        };
+        assert_eq!(message.msg_type, ProtocolMessageTypes::Error);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_frames_ignored() {
+        let (peer, mut mock) = connect().await;
+
+        // an unknown message type, and a frame that's cut short
+        mock.send(vec![0xfe, 0, 0, 0, 0, 0].into()).await.unwrap();
+        mock.send(vec![74, 1, 0].into()).await.unwrap();
+
+        let (response, _) = tokio::join!(
+            peer.request_children(Bytes32::default()),
+            respond(&mut mock, RespondChildren::new(vec![]))
+        );
+        assert_eq!(response.unwrap(), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let (mut peer, _mock) = connect().await;
+        peer.set_request_timeout(Some(Duration::from_millis(50)));
+
+        let response = peer.request_cost_info().await;
+        assert!(matches!(response, Err(Error::Timeout)));
+        assert_eq!(peer.pending_requests(), 0);
+
+        // the timeout can also be overridden per call
+        let response: Result<RespondCostInfo, _> = peer
+            .request_with_timeout(RequestCostInfo {}, Some(Duration::from_millis(10)))
+            .await;
+        assert!(matches!(response, Err(Error::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_handshake() {
+        let (mut peer, mut mock) = connect_in_memory(PeerOptions::default());
+
+        peer.send_handshake("testnet11".to_string(), NodeType::Wallet, true)
+            .await
+            .unwrap();
+        let request = mock.next().await.unwrap().unwrap();
+        assert_eq!(request.msg_type, ProtocolMessageTypes::Handshake);
+        assert_eq!(request.id, None);
+        let handshake = Handshake::from_bytes(request.data.as_ref()).unwrap();
+        assert_eq!(handshake.network_id, "testnet11");
+        assert_eq!(handshake.node_type, NodeType::Wallet);
+        assert!(handshake.capabilities.contains(&(5, "1".to_string())));
+
+        // the node answers with its own handshake, which is passed on as an
+        // event
+        let reply = Handshake {
+            network_id: "testnet11".to_string(),
+            protocol_version: "0.0.36".to_string(),
+            software_version: "2.5.0".to_string(),
+            server_port: 58444,
+            node_type: NodeType::FullNode,
+            capabilities: vec![(1, "1".to_string())],
+        };
+        mock.send(message(None, &reply)).await.unwrap();
+        let event = peer.receiver_mut().recv().await.unwrap();
+        assert_eq!(event, PeerEvent::Handshake(reply));
+
+        let coin_id = Bytes32::new([1; 32]);
+        let coin_state = CoinState::new(
+            Coin::new(coin_id, Bytes32::new([2; 32]), Amount::new(1337)),
+            Some(5),
+            None,
+        );
+        let (response, ()) = tokio::join!(peer.request_children(coin_id), async {
+            let request = mock.next().await.unwrap().unwrap();
+            assert_eq!(request.msg_type, ProtocolMessageTypes::RequestChildren);
+            assert_eq!(
+                RequestChildren::from_bytes(request.data.as_ref()).unwrap(),
+                RequestChildren::new(coin_id)
+            );
+            let response = RespondChildren::new(vec![coin_state]);
+            mock.send(message(request.id, &response)).await.unwrap();
+        });
+        assert_eq!(response.unwrap(), vec![coin_state]);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_peers() {
+        // messages sent by one peer arrive as events at the other
+        let (a, b) = InMemoryTransport::pair();
+        let peer = Peer::from_transport(a, PeerOptions::default());
+        let mut other = Peer::from_transport(b, PeerOptions::default());
+
+        let update = CoinStateUpdate::new(10, 10, Bytes32::new([3; 32]), vec![]);
+        peer.send(update.clone()).await.unwrap();
+        let event = other.receiver_mut().recv().await.unwrap();
+        assert_eq!(event, PeerEvent::CoinStateUpdate(update));
+    }
+
+    #[tokio::test]
+    async fn test_options() {
+        let options = PeerOptions {
+            request_timeout: Some(Duration::from_millis(50)),
+            max_message_size: 16,
+            max_in_flight_requests: None,
+        };
+        let (peer, _mock) = connect_in_memory(options);
+        assert_eq!(peer.request_timeout(), Some(Duration::from_millis(50)));
+
+        let response = peer.request_cost_info().await;
+        assert!(matches!(response, Err(Error::Timeout)));
+
+        // the request is rejected before it's sent
+        let response = peer.request_fee_estimates(vec![1; 10]).await;
+        assert!(matches!(
+            response,
+            Err(Error::Codec(CodecError::MessageTooLarge { max: 16, .. }))
+        ));
+    }
+
+    fn children_requests(count: u8) -> Vec<RequestChildren> {
+        (0..count)
+            .map(|i| RequestChildren::new(Bytes32::new([i; 32])))
+            .collect()
+    }
+
+    // answers a `RequestChildren` with a child of the requested coin, so the
+    // response can be matched to the request
+    async fn respond_children(mock: &mut InMemoryMockPeer, request: &Message) {
+        let request_body = RequestChildren::from_bytes(request.data.as_ref()).unwrap();
+        let coin = Coin::new(request_body.coin_name, Bytes32::default(), Amount::new(1));
+        let response = RespondChildren::new(vec![CoinState::new(coin, None, None)]);
+        mock.send(message(request.id, &response)).await.unwrap();
+    }
+
+    fn parent(response: Result<RespondChildren, Error<()>>) -> Bytes32 {
+        response.unwrap().coin_states[0].coin.parent_coin_info
+    }
+
+    #[tokio::test]
+    async fn test_batch_out_of_order() {
+        let (peer, mut mock) = connect_in_memory(PeerOptions::default());
+
+        let (responses, ()) = tokio::join!(peer.send_batch(children_requests(5)), async {
+            let mut requests = Vec::new();
+            for _ in 0..5 {
+                requests.push(mock.next().await.unwrap().unwrap());
+            }
+            // the node answers in the reverse order
+            for request in requests.iter().rev() {
+                respond_children(&mut mock, request).await;
+            }
+        });
+
+        // the results are in the order of the requests
+        assert_eq!(responses.len(), 5);
+        for (i, response) in (0..).zip(responses) {
+            assert_eq!(parent(response), Bytes32::new([i; 32]));
+        }
+        assert_eq!(peer.pending_requests(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_batch_missing_response() {
+        let options = PeerOptions {
+            request_timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let (peer, mut mock) = connect_in_memory(options);
+
+        let (mut responses, ()) = tokio::join!(peer.send_batch(children_requests(4)), async {
+            let mut requests = Vec::new();
+            for _ in 0..4 {
+                requests.push(mock.next().await.unwrap().unwrap());
+            }
+            // the request for the third coin is never answered
+            for request in [&requests[3], &requests[0], &requests[1]] {
+                respond_children(&mut mock, request).await;
+            }
+        });
+
+        assert!(matches!(responses.remove(2), Err(Error::Timeout)));
+        for (i, response) in [0, 1, 3].into_iter().zip(responses) {
+            assert_eq!(parent(response), Bytes32::new([i; 32]));
+        }
+        assert_eq!(peer.pending_requests(), 0);
+
+        // a request that's abandoned by the caller is cleaned up as well
+        let response = tokio::time::timeout(
+            Duration::from_millis(10),
+            peer.request_children(Bytes32::default()),
+        )
+        .await;
+        assert!(response.is_err());
+        assert_eq!(peer.pending_requests(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight_requests() {
+        let options = PeerOptions {
+            max_in_flight_requests: NonZeroUsize::new(2),
+            ..Default::default()
+        };
+        let (peer, mut mock) = connect_in_memory(options);
+
+        let (responses, ()) = tokio::join!(peer.send_batch(children_requests(4)), async {
+            let first = mock.next().await.unwrap().unwrap();
+            let second = mock.next().await.unwrap().unwrap();
+            assert_eq!(peer.pending_requests(), 2);
+
+            // the third request isn't sent until one of the others completes
+            let next = tokio::time::timeout(Duration::from_millis(50), mock.next()).await;
+            assert!(next.is_err());
+
+            respond_children(&mut mock, &second).await;
+            let third = mock.next().await.unwrap().unwrap();
+            respond_children(&mut mock, &third).await;
+            respond_children(&mut mock, &first).await;
+            let fourth = mock.next().await.unwrap().unwrap();
+            respond_children(&mut mock, &fourth).await;
+        });
+
+        for (i, response) in (0..).zip(responses) {
+            assert_eq!(parent(response), Bytes32::new([i; 32]));
+        }
+        assert_eq!(peer.pending_requests(), 0);
+    }
+}
@@ -0,0 +1,504 @@
+use std::collections::HashSet;
+
+use chia_protocol::{Bytes32, CoinState};
+
+use crate::{Peer, SubscriptionError};
+
+/// The full node's default limit on the number of puzzle hashes and coin ids
+/// (combined) a peer can subscribe to.
+pub const DEFAULT_MAX_SUBSCRIPTIONS: usize = 200_000;
+
+/// The default number of puzzle hashes or coin ids sent in a single request.
+pub const DEFAULT_SUBSCRIPTION_BATCH_SIZE: usize = 1000;
+
+/// The limits a [`SubscriptionManager`] stays within.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriptionLimits {
+    /// The most puzzle hashes and coin ids (combined) the node allows us to
+    /// subscribe to. The node doesn't announce this, so it has to match the
+    /// node's configuration. Trusted peers are usually allowed more.
+    pub max_subscriptions: usize,
+
+    /// The most puzzle hashes or coin ids sent in a single request. Larger
+    /// subscriptions are split into several requests.
+    pub batch_size: usize,
+}
+
+impl Default for SubscriptionLimits {
+    fn default() -> Self {
+        Self {
+            max_subscriptions: DEFAULT_MAX_SUBSCRIPTIONS,
+            batch_size: DEFAULT_SUBSCRIPTION_BATCH_SIZE,
+        }
+    }
+}
+
+// the subscribed items, in the order they were subscribed to
+#[derive(Debug, Default, Clone)]
+struct Subscriptions {
+    items: Vec<Bytes32>,
+    lookup: HashSet<Bytes32>,
+}
+
+impl Subscriptions {
+    fn contains(&self, item: &Bytes32) -> bool {
+        self.lookup.contains(item)
+    }
+
+    fn extend(&mut self, items: &[Bytes32]) {
+        for item in items {
+            if self.lookup.insert(*item) {
+                self.items.push(*item);
+            }
+        }
+    }
+
+    fn remove(&mut self, items: &[Bytes32]) {
+        for item in items {
+            self.lookup.remove(item);
+        }
+        let lookup = &self.lookup;
+        self.items.retain(|item| lookup.contains(item));
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.lookup.clear();
+    }
+}
+
+/// Keeps track of the puzzle hashes and coin ids a [`Peer`] is subscribed to.
+/// Items that are already subscribed to aren't sent again, large
+/// subscriptions are split into batches, and subscriptions that would exceed
+/// the node's limit fail up front, rather than being dropped by the node.
+///
+/// The subscriptions only exist on the node as long as the connection does.
+/// After reconnecting, [`replay_onto()`](Self::replay_onto) subscribes the new
+/// peer to everything again.
+pub struct SubscriptionManager {
+    peer: Peer,
+    limits: SubscriptionLimits,
+    puzzle_hashes: Subscriptions,
+    coin_ids: Subscriptions,
+}
+
+impl SubscriptionManager {
+    pub fn new(peer: Peer, limits: SubscriptionLimits) -> Self {
+        Self {
+            peer,
+            limits,
+            puzzle_hashes: Subscriptions::default(),
+            coin_ids: Subscriptions::default(),
+        }
+    }
+
+    pub fn peer(&self) -> &Peer {
+        &self.peer
+    }
+
+    pub fn peer_mut(&mut self) -> &mut Peer {
+        &mut self.peer
+    }
+
+    pub fn into_peer(self) -> Peer {
+        self.peer
+    }
+
+    /// Replaces the peer, e.g. after reconnecting, and returns the old one.
+    /// The subscriptions are kept, but aren't sent to the new peer until
+    /// [`replay_onto()`](Self::replay_onto) is called with it.
+    pub fn set_peer(&mut self, peer: Peer) -> Peer {
+        std::mem::replace(&mut self.peer, peer)
+    }
+
+    pub fn limits(&self) -> SubscriptionLimits {
+        self.limits
+    }
+
+    pub fn set_limits(&mut self, limits: SubscriptionLimits) {
+        self.limits = limits;
+    }
+
+    /// The number of puzzle hashes and coin ids subscribed to. They count
+    /// towards the same limit.
+    pub fn active_subscriptions(&self) -> usize {
+        self.puzzle_hashes.items.len() + self.coin_ids.items.len()
+    }
+
+    /// The number of items that can still be subscribed to before reaching
+    /// the limit.
+    pub fn remaining_subscriptions(&self) -> usize {
+        self.limits
+            .max_subscriptions
+            .saturating_sub(self.active_subscriptions())
+    }
+
+    /// The puzzle hashes subscribed to, in the order they were subscribed to.
+    pub fn puzzle_hashes(&self) -> &[Bytes32] {
+        &self.puzzle_hashes.items
+    }
+
+    /// The coin ids subscribed to, in the order they were subscribed to.
+    pub fn coin_ids(&self) -> &[Bytes32] {
+        &self.coin_ids.items
+    }
+
+    /// Subscribes to updates for the puzzle hashes that aren't subscribed to
+    /// yet, and returns their coin states from `min_height` onwards. If a
+    /// batch fails, the earlier ones stay subscribed to.
+    pub async fn subscribe_puzzle_hashes(
+        &mut self,
+        puzzle_hashes: Vec<Bytes32>,
+        min_height: u32,
+    ) -> Result<Vec<CoinState>, SubscriptionError> {
+        let new = self.new_items(&self.puzzle_hashes, puzzle_hashes)?;
+        let mut coin_states = Vec::new();
+        for batch in new.chunks(self.batch_size()) {
+            let response = self
+                .peer
+                .register_for_ph_updates(batch.to_vec(), min_height)
+                .await?;
+            self.puzzle_hashes.extend(batch);
+            coin_states.extend(response);
+        }
+        Ok(coin_states)
+    }
+
+    /// Subscribes to updates for the coin ids that aren't subscribed to yet,
+    /// and returns their coin states from `min_height` onwards. If a batch
+    /// fails, the earlier ones stay subscribed to.
+    pub async fn subscribe_coin_ids(
+        &mut self,
+        coin_ids: Vec<Bytes32>,
+        min_height: u32,
+    ) -> Result<Vec<CoinState>, SubscriptionError> {
+        let new = self.new_items(&self.coin_ids, coin_ids)?;
+        let mut coin_states = Vec::new();
+        for batch in new.chunks(self.batch_size()) {
+            let response = self
+                .peer
+                .register_for_coin_updates(batch.to_vec(), min_height)
+                .await?;
+            self.coin_ids.extend(batch);
+            coin_states.extend(response);
+        }
+        Ok(coin_states)
+    }
+
+    /// Unsubscribes from the puzzle hashes, to make room for others. Returns
+    /// the ones the node removed.
+    pub async fn unsubscribe_puzzle_hashes(
+        &mut self,
+        puzzle_hashes: Vec<Bytes32>,
+    ) -> Result<Vec<Bytes32>, SubscriptionError> {
+        let removed = self
+            .peer
+            .request_remove_puzzle_subscriptions(Some(puzzle_hashes))
+            .await?;
+        self.puzzle_hashes.remove(&removed);
+        Ok(removed)
+    }
+
+    /// Unsubscribes from the coin ids, to make room for others. Returns the
+    /// ones the node removed.
+    pub async fn unsubscribe_coin_ids(
+        &mut self,
+        coin_ids: Vec<Bytes32>,
+    ) -> Result<Vec<Bytes32>, SubscriptionError> {
+        let removed = self
+            .peer
+            .request_remove_coin_subscriptions(Some(coin_ids))
+            .await?;
+        self.coin_ids.remove(&removed);
+        Ok(removed)
+    }
+
+    /// Unsubscribes from all puzzle hashes and coin ids.
+    pub async fn unsubscribe_all(&mut self) -> Result<(), SubscriptionError> {
+        self.peer.request_remove_puzzle_subscriptions(None).await?;
+        self.puzzle_hashes.clear();
+        self.peer.request_remove_coin_subscriptions(None).await?;
+        self.coin_ids.clear();
+        Ok(())
+    }
+
+    /// Subscribes `peer` to all the puzzle hashes and coin ids that are
+    /// subscribed to, in batches, and returns their coin states from
+    /// `min_height` onwards. This is meant for a new connection, e.g. after
+    /// reconnecting, so `min_height` would typically be the height the
+    /// wallet is synced to.
+    pub async fn replay_onto(
+        &self,
+        peer: &Peer,
+        min_height: u32,
+    ) -> Result<Vec<CoinState>, SubscriptionError> {
+        let mut coin_states = Vec::new();
+        for batch in self.puzzle_hashes.items.chunks(self.batch_size()) {
+            let response = peer
+                .register_for_ph_updates(batch.to_vec(), min_height)
+                .await?;
+            coin_states.extend(response);
+        }
+        for batch in self.coin_ids.items.chunks(self.batch_size()) {
+            let response = peer
+                .register_for_coin_updates(batch.to_vec(), min_height)
+                .await?;
+            coin_states.extend(response);
+        }
+        Ok(coin_states)
+    }
+
+    fn batch_size(&self) -> usize {
+        self.limits.batch_size.max(1)
+    }
+
+    // the items that aren't subscribed to yet, without duplicates, as long
+    // as they fit within the limit
+    fn new_items(
+        &self,
+        active: &Subscriptions,
+        items: Vec<Bytes32>,
+    ) -> Result<Vec<Bytes32>, SubscriptionError> {
+        let mut seen = HashSet::new();
+        let new: Vec<Bytes32> = items
+            .into_iter()
+            .filter(|item| !active.contains(item) && seen.insert(*item))
+            .collect();
+        if new.len() > self.remaining_subscriptions() {
+            return Err(SubscriptionError::LimitExceeded {
+                requested: new.len(),
+                active: self.active_subscriptions(),
+                max: self.limits.max_subscriptions,
+            });
+        }
+        Ok(new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::stream;
+    use crate::{InMemoryTransport, PeerOptions};
+    use chia_protocol::{
+        Amount, ChiaProtocolMessage, Coin, Message, MessageCodec, ProtocolMessageTypes,
+        RegisterForCoinUpdates, RegisterForPhUpdates, RequestRemovePuzzleSubscriptions,
+        RespondRemovePuzzleSubscriptions, RespondToCoinUpdates, RespondToPhUpdates,
+    };
+    use chia_traits::Streamable;
+    use futures_util::{SinkExt, StreamExt};
+    use std::time::Duration;
+    use tokio::io::DuplexStream;
+    use tokio_util::codec::Framed;
+
+    type MockPeer = Framed<DuplexStream, MessageCodec>;
+
+    fn connect() -> (Peer, MockPeer) {
+        let (client, server) = InMemoryTransport::pair();
+        let mock = Framed::new(server.into_inner(), MessageCodec::default());
+        (Peer::from_transport(client, PeerOptions::default()), mock)
+    }
+
+    fn setup(max_subscriptions: usize, batch_size: usize) -> (SubscriptionManager, MockPeer) {
+        let (peer, mock) = connect();
+        let limits = SubscriptionLimits {
+            max_subscriptions,
+            batch_size,
+        };
+        (SubscriptionManager::new(peer, limits), mock)
+    }
+
+    fn items(range: std::ops::Range<u8>) -> Vec<Bytes32> {
+        range.map(|i| Bytes32::new([i; 32])).collect()
+    }
+
+    fn coin_state(puzzle_hash: Bytes32) -> CoinState {
+        CoinState::new(
+            Coin::new(Bytes32::default(), puzzle_hash, Amount::new(1)),
+            Some(1),
+            None,
+        )
+    }
+
+    async fn reply<T: Streamable + ChiaProtocolMessage>(
+        mock: &mut MockPeer,
+        request: &Message,
+        body: &T,
+    ) {
+        let response = Message {
+            msg_type: T::msg_type(),
+            id: request.id,
+            data: stream(body).unwrap().into(),
+        };
+        mock.send(response).await.unwrap();
+    }
+
+    // answers the next `RegisterForPhUpdates` with a coin state for each of
+    // the puzzle hashes, and returns the puzzle hashes
+    async fn respond_ph(mock: &mut MockPeer) -> Vec<Bytes32> {
+        let request = mock.next().await.unwrap().unwrap();
+        assert_eq!(request.msg_type, ProtocolMessageTypes::RegisterForPhUpdates);
+        let body = RegisterForPhUpdates::from_bytes(request.data.as_ref()).unwrap();
+        let coin_states = body.puzzle_hashes.iter().copied().map(coin_state).collect();
+        let response = RespondToPhUpdates::new(body.puzzle_hashes.clone(), 0, coin_states);
+        reply(mock, &request, &response).await;
+        body.puzzle_hashes
+    }
+
+    async fn respond_coin(mock: &mut MockPeer) -> Vec<Bytes32> {
+        let request = mock.next().await.unwrap().unwrap();
+        assert_eq!(
+            request.msg_type,
+            ProtocolMessageTypes::RegisterForCoinUpdates
+        );
+        let body = RegisterForCoinUpdates::from_bytes(request.data.as_ref()).unwrap();
+        let response = RespondToCoinUpdates::new(body.coin_ids.clone(), 0, vec![]);
+        reply(mock, &request, &response).await;
+        body.coin_ids
+    }
+
+    async fn assert_no_request(mock: &mut MockPeer) {
+        let next = tokio::time::timeout(Duration::from_millis(20), mock.next()).await;
+        assert!(next.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_batches() {
+        let (mut manager, mut mock) = setup(100, 3);
+
+        let (coin_states, batches) =
+            tokio::join!(manager.subscribe_puzzle_hashes(items(0..7), 10), async {
+                let mut batches = Vec::new();
+                for _ in 0..3 {
+                    batches.push(respond_ph(&mut mock).await);
+                }
+                batches
+            });
+
+        assert_eq!(batches, [items(0..3), items(3..6), items(6..7)]);
+        let coin_states = coin_states.unwrap();
+        let puzzle_hashes: Vec<Bytes32> =
+            coin_states.iter().map(|cs| cs.coin.puzzle_hash).collect();
+        assert_eq!(puzzle_hashes, items(0..7));
+        assert_eq!(manager.puzzle_hashes(), items(0..7));
+        assert_eq!(manager.active_subscriptions(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_dedup() {
+        let (mut manager, mut mock) = setup(100, 10);
+
+        // duplicates within the request are only sent once
+        let mut request = items(0..3);
+        request.extend(items(1..3));
+        let (result, sent) = tokio::join!(
+            manager.subscribe_puzzle_hashes(request, 0),
+            respond_ph(&mut mock)
+        );
+        assert_eq!(result.unwrap().len(), 3);
+        assert_eq!(sent, items(0..3));
+
+        // only the new puzzle hashes are sent
+        let (result, sent) = tokio::join!(
+            manager.subscribe_puzzle_hashes(items(2..5), 0),
+            respond_ph(&mut mock)
+        );
+        assert_eq!(result.unwrap().len(), 2);
+        assert_eq!(sent, items(3..5));
+
+        // nothing is sent if everything is subscribed to already
+        let coin_states = manager
+            .subscribe_puzzle_hashes(items(0..5), 0)
+            .await
+            .unwrap();
+        assert_eq!(coin_states, vec![]);
+        assert_no_request(&mut mock).await;
+        assert_eq!(manager.active_subscriptions(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_limit() {
+        let (mut manager, mut mock) = setup(5, 10);
+
+        let (result, _) = tokio::join!(
+            manager.subscribe_puzzle_hashes(items(0..3), 0),
+            respond_ph(&mut mock)
+        );
+        result.unwrap();
+
+        // puzzle hashes and coin ids count towards the same limit
+        let (result, _) = tokio::join!(
+            manager.subscribe_coin_ids(items(10..12), 0),
+            respond_coin(&mut mock)
+        );
+        result.unwrap();
+        assert_eq!(manager.remaining_subscriptions(), 0);
+
+        let result = manager.subscribe_puzzle_hashes(items(2..4), 0).await;
+        assert!(matches!(
+            result,
+            Err(SubscriptionError::LimitExceeded {
+                requested: 1,
+                active: 5,
+                max: 5
+            })
+        ));
+        assert_no_request(&mut mock).await;
+        assert_eq!(manager.puzzle_hashes(), items(0..3));
+
+        // unsubscribing makes room for more
+        let (result, ()) = tokio::join!(manager.unsubscribe_puzzle_hashes(items(0..1)), async {
+            let request = mock.next().await.unwrap().unwrap();
+            let body = RequestRemovePuzzleSubscriptions::from_bytes(request.data.as_ref()).unwrap();
+            let removed = body.puzzle_hashes.unwrap();
+            reply(
+                &mut mock,
+                &request,
+                &RespondRemovePuzzleSubscriptions::new(removed),
+            )
+            .await;
+        });
+        assert_eq!(result.unwrap(), items(0..1));
+        assert_eq!(manager.remaining_subscriptions(), 1);
+
+        let (result, sent) = tokio::join!(
+            manager.subscribe_puzzle_hashes(items(2..4), 0),
+            respond_ph(&mut mock)
+        );
+        result.unwrap();
+        assert_eq!(sent, items(3..4));
+        assert_eq!(manager.puzzle_hashes(), items(1..4));
+    }
+
+    #[tokio::test]
+    async fn test_replay() {
+        let (mut manager, mut mock) = setup(100, 2);
+
+        let (result, ()) = tokio::join!(manager.subscribe_puzzle_hashes(items(0..3), 0), async {
+            respond_ph(&mut mock).await;
+            respond_ph(&mut mock).await;
+        });
+        result.unwrap();
+        let (result, _) = tokio::join!(
+            manager.subscribe_coin_ids(items(10..11), 0),
+            respond_coin(&mut mock)
+        );
+        result.unwrap();
+
+        // everything is sent to the new peer, in the same batches
+        let (peer, mut new_mock) = connect();
+        let (coin_states, sent) = tokio::join!(manager.replay_onto(&peer, 20), async {
+            [
+                respond_ph(&mut new_mock).await,
+                respond_ph(&mut new_mock).await,
+                respond_coin(&mut new_mock).await,
+            ]
+        });
+        assert_eq!(coin_states.unwrap().len(), 3);
+        assert_eq!(sent, [items(0..2), items(2..3), items(10..11)]);
+        assert_no_request(&mut mock).await;
+
+        manager.set_peer(peer);
+        assert_eq!(manager.active_subscriptions(), 4);
+    }
+}
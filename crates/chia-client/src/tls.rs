@@ -0,0 +1,417 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use chia_protocol::{Bytes32, MessageCodec};
+use chia_sha2::Sha256;
+use chia_ssl::CHIA_CA_CRT;
+use futures_util::future::BoxFuture;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::verify_server_cert_signed_by_trust_anchor;
+use rustls::crypto::{ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::server::{ParsedCertificate, WebPkiClientVerifier};
+use rustls::{
+    ClientConfig, CommonState, DigitallySignedStruct, RootCertStore, ServerConfig, SignatureScheme,
+};
+use tokio::net::TcpStream;
+use tokio_rustls::{TlsAcceptor, TlsConnector, TlsStream};
+use tokio_tungstenite::WebSocketStream;
+
+use crate::{ConnectError, Connector, MessageSink, MessageStream, TlsError, Transport};
+
+/// The certificates for mutually authenticated TLS connections. Both sides
+/// present a certificate, and each must be signed by the CA the other side
+/// trusts.
+///
+/// Full nodes and wallets use certificates signed by the public Chia CA, which
+/// anyone can sign with. The services of an installation that only talk to
+/// each other, such as the farmer and the harvester, use certificates signed
+/// by the private CA of that installation instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// The CA that the certificate of the other side must be signed by.
+    pub ca_pem: String,
+    pub cert_pem: String,
+    pub key_pem: String,
+    /// Refuse to use the public Chia CA as `ca_pem`. This should be set for
+    /// private services, since otherwise anyone could connect to them.
+    pub require_private_ca: bool,
+}
+
+impl TlsConfig {
+    /// The configuration for connecting to a server. The server's
+    /// certificate is verified against the CA, but not its host name, since
+    /// the certificates don't contain one.
+    pub fn client_config(&self) -> Result<ClientConfig, TlsError> {
+        let provider = Arc::new(ring::default_provider());
+        let verifier = CaVerifier {
+            roots: Arc::new(self.root_store()?),
+            provider: Arc::clone(&provider),
+        };
+        let (certs, key) = self.identity()?;
+        Ok(ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_client_auth_cert(certs, key)?)
+    }
+
+    /// The configuration for accepting connections. Clients must present a
+    /// certificate signed by the CA.
+    pub fn server_config(&self) -> Result<ServerConfig, TlsError> {
+        let provider = Arc::new(ring::default_provider());
+        let verifier = WebPkiClientVerifier::builder_with_provider(
+            Arc::new(self.root_store()?),
+            Arc::clone(&provider),
+        )
+        .build()?;
+        let (certs, key) = self.identity()?;
+        Ok(ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()?
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(certs, key)?)
+    }
+
+    fn root_store(&self) -> Result<RootCertStore, TlsError> {
+        let ca = parse_certificate(&self.ca_pem)?;
+        if self.require_private_ca && ca == parse_certificate(CHIA_CA_CRT)? {
+            return Err(TlsError::PublicCa);
+        }
+        let mut roots = RootCertStore::empty();
+        roots.add(ca)?;
+        Ok(roots)
+    }
+
+    fn identity(&self) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), TlsError> {
+        let cert = parse_certificate(&self.cert_pem)?;
+        let key = rustls_pemfile::private_key(&mut self.key_pem.as_bytes())?
+            .ok_or(TlsError::MissingPrivateKey)?;
+        Ok((vec![cert], key))
+    }
+}
+
+fn parse_certificate(pem: &str) -> Result<CertificateDer<'static>, TlsError> {
+    let cert = rustls_pemfile::certs(&mut pem.as_bytes())
+        .next()
+        .ok_or(TlsError::MissingCertificate)??;
+    Ok(cert)
+}
+
+/// The SHA-256 hash of the DER encoded certificate the other side of a TLS
+/// connection presented, if any.
+pub fn peer_cert_fingerprint(state: &CommonState) -> Option<Bytes32> {
+    let cert = state.peer_certificates()?.first()?;
+    let mut hasher = Sha256::new();
+    hasher.update(cert);
+    Some(hasher.finalize().into())
+}
+
+// Checks that the server's certificate is signed by the CA, like
+// WebPkiClientVerifier does for clients. Unlike WebPkiServerVerifier, it
+// doesn't check the host name.
+#[derive(Debug)]
+struct CaVerifier {
+    roots: Arc<RootCertStore>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for CaVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let cert = ParsedCertificate::try_from(end_entity)?;
+        verify_server_cert_signed_by_trust_anchor(
+            &cert,
+            &self.roots,
+            intermediates,
+            now,
+            self.provider.signature_verification_algorithms.all,
+        )?;
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+/// A websocket connection over TLS, in either direction. Unlike a plain
+/// [`WebSocketStream`], it knows the certificate of the other side, see
+/// [`Peer::peer_cert_fingerprint()`](crate::Peer::peer_cert_fingerprint).
+pub struct TlsTransport {
+    ws: WebSocketStream<TlsStream<TcpStream>>,
+}
+
+impl Transport for TlsTransport {
+    fn into_split(self, codec: MessageCodec) -> (MessageSink, MessageStream) {
+        self.ws.into_split(codec)
+    }
+
+    fn peer_cert_fingerprint(&self) -> Option<Bytes32> {
+        peer_cert_fingerprint(self.ws.get_ref().get_ref().1)
+    }
+}
+
+/// Connects to `wss://<addr>/ws` with a client certificate.
+#[derive(Clone)]
+pub struct TlsWebSocketConnector {
+    connector: TlsConnector,
+}
+
+impl TlsWebSocketConnector {
+    pub fn new(config: &TlsConfig) -> Result<Self, TlsError> {
+        Ok(Self {
+            connector: TlsConnector::from(Arc::new(config.client_config()?)),
+        })
+    }
+}
+
+impl Connector for TlsWebSocketConnector {
+    type Transport = TlsTransport;
+
+    fn connect(&self, addr: SocketAddr) -> BoxFuture<'_, Result<Self::Transport, ConnectError>> {
+        Box::pin(async move {
+            let stream = TcpStream::connect(addr).await?;
+            // the name isn't verified, see CaVerifier
+            let server_name = ServerName::IpAddress(addr.ip().into());
+            let stream = self.connector.connect(server_name, stream).await?;
+            let stream = TlsStream::from(stream);
+            let (ws, _) =
+                tokio_tungstenite::client_async(format!("wss://{addr}/ws"), stream).await?;
+            Ok(TlsTransport { ws })
+        })
+    }
+}
+
+/// Accepts websocket connections over TLS from clients with a certificate
+/// signed by the CA.
+#[derive(Clone)]
+pub struct TlsWebSocketAcceptor {
+    acceptor: TlsAcceptor,
+}
+
+impl TlsWebSocketAcceptor {
+    pub fn new(config: &TlsConfig) -> Result<Self, TlsError> {
+        Ok(Self {
+            acceptor: TlsAcceptor::from(Arc::new(config.server_config()?)),
+        })
+    }
+
+    pub async fn accept(&self, stream: TcpStream) -> Result<TlsTransport, ConnectError> {
+        let stream = self.acceptor.accept(stream).await?;
+        let ws = tokio_tungstenite::accept_async(TlsStream::from(stream)).await?;
+        Ok(TlsTransport { ws })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use chia_protocol::{Handshake, NodeType};
+    use chia_ssl::ChiaCertificate;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::{Peer, PeerEvent, PeerOptions};
+
+    struct Certs {
+        ca: ChiaCertificate,
+        other_ca: ChiaCertificate,
+        server: ChiaCertificate,
+        client: ChiaCertificate,
+        other_client: ChiaCertificate,
+        public: ChiaCertificate,
+    }
+
+    // generating RSA keys is slow, so all tests share the same ones
+    fn certs() -> &'static Certs {
+        static CERTS: OnceLock<Certs> = OnceLock::new();
+        CERTS.get_or_init(|| {
+            let ca = ChiaCertificate::generate_ca().unwrap();
+            let other_ca = ChiaCertificate::generate_ca().unwrap();
+            Certs {
+                server: ChiaCertificate::generate_with_ca(&ca).unwrap(),
+                client: ChiaCertificate::generate_with_ca(&ca).unwrap(),
+                other_client: ChiaCertificate::generate_with_ca(&other_ca).unwrap(),
+                public: ChiaCertificate::generate().unwrap(),
+                ca,
+                other_ca,
+            }
+        })
+    }
+
+    fn config(ca: &ChiaCertificate, cert: &ChiaCertificate) -> TlsConfig {
+        TlsConfig {
+            ca_pem: ca.cert_pem.clone(),
+            cert_pem: cert.cert_pem.clone(),
+            key_pem: cert.key_pem.clone(),
+            require_private_ca: true,
+        }
+    }
+
+    fn fingerprint(cert: &ChiaCertificate) -> Bytes32 {
+        let mut hasher = Sha256::new();
+        hasher.update(parse_certificate(&cert.cert_pem).unwrap());
+        hasher.finalize().into()
+    }
+
+    async fn connect(
+        server: &TlsConfig,
+        client: &TlsConfig,
+    ) -> (
+        Result<TlsTransport, ConnectError>,
+        Result<TlsTransport, ConnectError>,
+    ) {
+        let acceptor = TlsWebSocketAcceptor::new(server).unwrap();
+        let connector = TlsWebSocketConnector::new(client).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::join!(
+            async {
+                let (stream, _) = listener.accept().await.unwrap();
+                acceptor.accept(stream).await
+            },
+            connector.connect(addr)
+        )
+    }
+
+    #[tokio::test]
+    async fn test_private_ca() {
+        let certs = certs();
+        let (server, client) = connect(
+            &config(&certs.ca, &certs.server),
+            &config(&certs.ca, &certs.client),
+        )
+        .await;
+
+        let mut server = Peer::from_transport(server.unwrap(), PeerOptions::default());
+        let client = Peer::from_transport(client.unwrap(), PeerOptions::default());
+        assert_eq!(
+            server.peer_cert_fingerprint(),
+            Some(fingerprint(&certs.client))
+        );
+        assert_eq!(
+            client.peer_cert_fingerprint(),
+            Some(fingerprint(&certs.server))
+        );
+
+        client
+            .send_handshake("mainnet".to_string(), NodeType::Farmer, false)
+            .await
+            .unwrap();
+        let PeerEvent::Handshake(Handshake { node_type, .. }) =
+            server.receiver_mut().recv().await.unwrap()
+        else {
+            panic!("expected a handshake");
+        };
+        assert_eq!(node_type, NodeType::Farmer);
+    }
+
+    #[tokio::test]
+    async fn test_public_ca() {
+        let certs = certs();
+        let ca = ChiaCertificate {
+            cert_pem: CHIA_CA_CRT.to_string(),
+            key_pem: String::new(),
+        };
+        let public = TlsConfig {
+            require_private_ca: false,
+            ..config(&ca, &certs.public)
+        };
+        let (server, client) = connect(&public, &public).await;
+        assert!(server.is_ok());
+        assert!(client.is_ok());
+
+        assert!(matches!(
+            config(&ca, &certs.public).client_config(),
+            Err(TlsError::PublicCa)
+        ));
+        assert!(matches!(
+            config(&ca, &certs.public).server_config(),
+            Err(TlsError::PublicCa)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reject_client() {
+        let certs = certs();
+        let server = config(&certs.ca, &certs.server);
+        for client in [&certs.other_client, &certs.public] {
+            // the client trusts the server, but not the other way around
+            let (server, client) = connect(&server, &config(&certs.ca, client)).await;
+            assert!(server.is_err());
+            assert!(client.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reject_server() {
+        let certs = certs();
+        // the server would accept the client, but the client doesn't trust
+        // the server's certificate
+        let server = TlsConfig {
+            ca_pem: certs.ca.cert_pem.clone(),
+            ..config(&certs.other_ca, &certs.other_client)
+        };
+        let (server, client) = connect(&server, &config(&certs.ca, &certs.client)).await;
+        assert!(server.is_err());
+        assert!(client.is_err());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let certs = certs();
+        let missing_key = TlsConfig {
+            key_pem: String::new(),
+            ..config(&certs.ca, &certs.client)
+        };
+        assert!(matches!(
+            missing_key.client_config(),
+            Err(TlsError::MissingPrivateKey)
+        ));
+        let missing_ca = TlsConfig {
+            ca_pem: String::new(),
+            ..config(&certs.ca, &certs.client)
+        };
+        assert!(matches!(
+            missing_ca.server_config(),
+            Err(TlsError::MissingCertificate)
+        ));
+    }
+}
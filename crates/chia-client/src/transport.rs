@@ -0,0 +1,105 @@
+use std::pin::Pin;
+
+use chia_protocol::{Bytes32, Message, MessageCodec};
+use futures_util::{future, Sink, SinkExt, Stream, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream};
+use tokio_tungstenite::WebSocketStream;
+use tokio_util::codec::Framed;
+use tungstenite::Message as WsMessage;
+
+use crate::TransportError;
+
+/// The sending half of a [`Transport`].
+pub type MessageSink = Pin<Box<dyn Sink<Message, Error = TransportError> + Send>>;
+
+/// The receiving half of a [`Transport`]. Errors for individual messages are
+/// reported, but don't necessarily end the stream.
+pub type MessageStream = Pin<Box<dyn Stream<Item = Result<Message, TransportError>> + Send>>;
+
+/// A connection that a [`Peer`](crate::Peer) exchanges messages over.
+///
+/// The websocket implementation is what full nodes and wallets use, and the
+/// one [`Peer::new()`](crate::Peer::new) expects. [`StreamTransport`] sends
+/// the messages back to back over any byte stream instead, such as a plain
+/// TCP connection, a unix socket or an [`InMemoryTransport`].
+pub trait Transport: Send + 'static {
+    /// Splits the transport into its sending and receiving halves. `codec` is
+    /// used to convert between messages and the wire format.
+    fn into_split(self, codec: MessageCodec) -> (MessageSink, MessageStream);
+
+    /// The SHA-256 hash of the DER encoded certificate the other side
+    /// presented, for transports that authenticate it. See
+    /// [`TlsTransport`](crate::TlsTransport).
+    fn peer_cert_fingerprint(&self) -> Option<Bytes32> {
+        None
+    }
+}
+
+/// Each message is sent as a binary websocket message of its own.
+impl<S> Transport for WebSocketStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    fn into_split(self, codec: MessageCodec) -> (MessageSink, MessageStream) {
+        let (sink, stream) = StreamExt::split::<WsMessage>(self);
+
+        let sink = sink
+            .sink_map_err(TransportError::from)
+            .with(move |message: Message| {
+                future::ready(
+                    codec
+                        .encode_frame(message)
+                        .map(WsMessage::from)
+                        .map_err(TransportError::from),
+                )
+            });
+
+        let stream = stream.map(move |message| -> Result<Message, TransportError> {
+            let message = message?;
+            Ok(codec.decode_frame(message.into_data().as_ref())?)
+        });
+
+        (Box::pin(sink), Box::pin(stream))
+    }
+}
+
+/// Sends messages back to back over a byte stream, without any additional
+/// framing. Since there's no way to find the start of the next message after
+/// an invalid one, the stream ends at the first error.
+#[derive(Debug)]
+pub struct StreamTransport<S> {
+    stream: S,
+}
+
+impl<S> StreamTransport<S> {
+    pub fn new(stream: S) -> Self {
+        Self { stream }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> Transport for StreamTransport<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    fn into_split(self, codec: MessageCodec) -> (MessageSink, MessageStream) {
+        let (sink, stream) = Framed::new(self.stream, codec).split::<Message>();
+        let sink = sink.sink_map_err(TransportError::from);
+        let stream = stream.map(|message| message.map_err(TransportError::from));
+        (Box::pin(sink), Box::pin(stream))
+    }
+}
+
+/// A transport within the same process, mostly useful for tests.
+pub type InMemoryTransport = StreamTransport<DuplexStream>;
+
+impl InMemoryTransport {
+    /// Creates two transports connected to each other.
+    pub fn pair() -> (Self, Self) {
+        let (a, b) = tokio::io::duplex(64 * 1024);
+        (Self::new(a), Self::new(b))
+    }
+}
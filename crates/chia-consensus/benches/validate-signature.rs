@@ -0,0 +1,60 @@
+use chia_bls::{aggregate_verify, sign, BlsCache, SecretKey, Signature};
+use chia_consensus::gen::conditions::{validate_signature, ParseState};
+use chia_protocol::Bytes;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::time::Instant;
+
+// the signature of a spend bundle with a single AGG_SIG condition, like a
+// simple wallet send
+fn run(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validate-signature");
+
+    let sk = SecretKey::from_seed(&[1; 32]);
+    let msg = Bytes::from(vec![2; 64]);
+    let signature = sign(&sk, &msg);
+    let mut state = ParseState::default();
+    state.pkm_pairs.push((sk.public_key(), msg));
+
+    // this is how the signature was validated before the single pair fast
+    // path was added
+    group.bench_function("aggregate_verify 1 pair", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            let valid = aggregate_verify(
+                &signature,
+                state.pkm_pairs.iter().map(|(pk, msg)| (pk, msg.as_slice())),
+            );
+            assert!(black_box(valid));
+            start.elapsed()
+        });
+    });
+
+    group.bench_function("validate_signature 1 pair", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            let _ = black_box(validate_signature(&state, &signature, 0, None));
+            start.elapsed()
+        });
+    });
+
+    let bls_cache = BlsCache::default();
+    group.bench_function("validate_signature 1 pair (cached)", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            let _ = black_box(validate_signature(&state, &signature, 0, Some(&bls_cache)));
+            start.elapsed()
+        });
+    });
+
+    let empty = ParseState::default();
+    group.bench_function("validate_signature 0 pairs", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            let _ = black_box(validate_signature(&empty, &Signature::default(), 0, None));
+            start.elapsed()
+        });
+    });
+}
+
+criterion_group!(validate_signature_bench, run);
+criterion_main!(validate_signature_bench);
@@ -7,6 +7,7 @@ use chia_consensus::gen::conditions::{
 };
 use chia_consensus::gen::spend_visitor::SpendVisitor;
 use chia_consensus::gen::validation_error::{ErrorCode, ValidationErr};
+use chia_protocol::Amount;
 use chia_protocol::Bytes32;
 use chia_protocol::Coin;
 use chia_protocol::CoinSpend;
@@ -40,15 +41,15 @@ fuzz_target!(|data: &[u8]| {
     };
     let puzzle_hash = Bytes32::from(tree_hash(&a, puzzle));
 
-    for new_amount in [0, 2, 3] {
-        for new_parent_amount in [0, 2, 3] {
+    for new_amount in [0_u64, 2, 3] {
+        for new_parent_amount in [0_u64, 2, 3] {
             let new_parent_coin = Coin {
                 parent_coin_info: new_parents_parent.into(),
                 puzzle_hash,
                 amount: if new_parent_amount == 0 {
                     spend.coin.amount
                 } else {
-                    new_parent_amount
+                    new_parent_amount.into()
                 },
             };
 
@@ -58,7 +59,7 @@ fuzz_target!(|data: &[u8]| {
                 amount: if new_amount == 0 {
                     spend.coin.amount
                 } else {
-                    new_amount
+                    new_amount.into()
                 },
             };
 
@@ -79,7 +80,7 @@ fn run_puzzle(
     puzzle: &[u8],
     solution: &[u8],
     parent_id: &[u8],
-    amount: u64,
+    amount: Amount,
 ) -> core::result::Result<SpendBundleConditions, ValidationErr> {
     let puzzle = node_from_bytes(a, puzzle)?;
     let solution = node_from_bytes(a, solution)?;
@@ -89,7 +90,7 @@ fn run_puzzle(
     let Reduction(clvm_cost, conditions) = run_program(a, &dialect, puzzle, solution, max_cost)?;
 
     let mut ret = SpendBundleConditions {
-        removal_amount: amount as u128,
+        removal_amount: u128::from(amount),
         ..Default::default()
     };
     let mut state = ParseState::default();
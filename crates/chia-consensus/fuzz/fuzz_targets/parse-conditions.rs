@@ -7,6 +7,7 @@ use chia_consensus::gen::conditions::{
 };
 use chia_consensus::gen::spend_visitor::SpendVisitor;
 use chia_fuzz::{make_list, BitCursor};
+use chia_protocol::Amount;
 use chia_protocol::Bytes32;
 use chia_protocol::Coin;
 use clvm_utils::tree_hash;
@@ -24,7 +25,7 @@ fuzz_target!(|data: &[u8]| {
 
     let mut ret = SpendBundleConditions::default();
 
-    let amount = 1337_u64;
+    let amount = Amount::new(1337);
     let parent_id: Bytes32 = b"12345678901234567890123456789012".into();
     let puzzle_hash = tree_hash(&a, input);
     let coin_id = Arc::<Bytes32>::new(
@@ -3,7 +3,7 @@ use libfuzzer_sys::fuzz_target;
 
 use chia_consensus::gen::get_puzzle_and_solution::get_puzzle_and_solution_for_coin;
 use chia_fuzz::{make_tree, BitCursor};
-use chia_protocol::Coin;
+use chia_protocol::{Amount, Coin};
 use clvmr::allocator::Allocator;
 use std::collections::HashSet;
 
@@ -19,6 +19,6 @@ fuzz_target!(|data: &[u8]| {
         &a,
         input,
         &HashSet::new(),
-        &Coin::new(HASH.into(), HASH.into(), 1337),
+        &Coin::new(HASH.into(), HASH.into(), Amount::new(1337)),
     );
 });
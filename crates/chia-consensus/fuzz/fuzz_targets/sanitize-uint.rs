@@ -1,14 +1,17 @@
 #![no_main]
 use libfuzzer_sys::fuzz_target;
 
-use chia_consensus::gen::sanitize_int::{sanitize_uint, SanitizedUint};
+use chia_consensus::gen::sanitize_int::{
+    parse_u32_lenient, parse_u32_strict, parse_u64_lenient, parse_u64_strict, SanitizedUint,
+};
 use chia_consensus::gen::validation_error::{ErrorCode, ValidationErr};
 use clvmr::allocator::Allocator;
 
 fuzz_target!(|data: &[u8]| {
     let mut a = Allocator::new();
     let atom = a.new_atom(data).unwrap();
-    match sanitize_uint(&a, atom, 8, ErrorCode::InvalidCoinAmount) {
+    let r = parse_u64_lenient(&a, atom, ErrorCode::InvalidCoinAmount);
+    match r {
         Ok(SanitizedUint::Ok(_)) => {
             assert!(data.len() <= 9);
             if data.len() == 9 {
@@ -26,8 +29,17 @@ fuzz_target!(|data: &[u8]| {
             assert!(c == ErrorCode::InvalidCoinAmount);
         }
     }
+    // the strict parser only differs in how it reports overflows
+    match (r, parse_u64_strict(&a, atom, ErrorCode::InvalidCoinAmount)) {
+        (Ok(SanitizedUint::Ok(v1)), Ok(v2)) => assert_eq!(v1, v2),
+        (Err(e1), Err(e2)) => assert_eq!(e1, e2),
+        (Ok(_), Err(e)) => assert_eq!(e, ValidationErr(atom, ErrorCode::InvalidCoinAmount)),
+        (Err(_), Ok(_)) => panic!("strict parser accepted an invalid value"),
+        (Ok(_), Ok(_)) => panic!("strict parser accepted an overflowing value"),
+    }
 
-    match sanitize_uint(&a, atom, 4, ErrorCode::InvalidCoinAmount) {
+    let r = parse_u32_lenient(&a, atom, ErrorCode::InvalidCoinAmount);
+    match r {
         Ok(SanitizedUint::Ok(_)) => {
             assert!(data.len() <= 5);
             if data.len() == 5 {
@@ -45,4 +57,11 @@ fuzz_target!(|data: &[u8]| {
             assert!(c == ErrorCode::InvalidCoinAmount);
         }
     }
+    match (r, parse_u32_strict(&a, atom, ErrorCode::InvalidCoinAmount)) {
+        (Ok(SanitizedUint::Ok(v1)), Ok(v2)) => assert_eq!(v1, v2),
+        (Err(e1), Err(e2)) => assert_eq!(e1, e2),
+        (Ok(_), Err(e)) => assert_eq!(e, ValidationErr(atom, ErrorCode::InvalidCoinAmount)),
+        (Err(_), Ok(_)) => panic!("strict parser accepted an invalid value"),
+        (Ok(_), Ok(_)) => panic!("strict parser accepted an overflowing value"),
+    }
 });
@@ -1,6 +1,8 @@
 use clvmr::allocator::Allocator;
 use clvmr::chia_dialect::LIMIT_HEAP;
 
+pub use clvmr::allocator::Checkpoint;
+
 pub fn make_allocator(flags: u32) -> Allocator {
     if flags & LIMIT_HEAP != 0 {
         Allocator::new_limited(500_000_000)
@@ -8,3 +10,137 @@ pub fn make_allocator(flags: u32) -> Allocator {
         Allocator::new_limited(u32::MAX as usize)
     }
 }
+
+/// Records how much of the allocator is in use, so that everything allocated
+/// after this point can be discarded with [`restore()`]. This is cheaper than
+/// creating a new allocator and loading shared structures (such as puzzles)
+/// into it again, for example when speculatively running a spend.
+pub fn checkpoint(a: &Allocator) -> Checkpoint {
+    a.checkpoint()
+}
+
+/// Discards all atoms and pairs allocated since `cp` was recorded.
+///
+/// This is sound because nodes in an [`Allocator`] are never modified once
+/// they have been created, so nothing created before the checkpoint can
+/// refer to anything created after it. The caller must uphold the rest of
+/// the contract:
+///
+/// * `cp` must have been recorded from the same allocator, and the
+///   allocator must not have been restored to an earlier checkpoint since.
+/// * No `NodePtr` created after the checkpoint may be used once it has been
+///   restored. Such a node either no longer exists, and using it panics, or
+///   it refers to a new node allocated in its place. This includes nodes
+///   held by return values, such as the ones in `SpendBundleConditions` or
+///   `ValidationErr`. Small atoms are stored in the `NodePtr` itself, so
+///   they are not affected.
+pub fn restore(a: &mut Allocator, cp: &Checkpoint) {
+    a.restore_checkpoint(cp);
+}
+
+/// How much of an [`Allocator`] was used, for example by running a block
+/// generator. This is useful for tuning cost and memory limits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "py-bindings",
+    pyo3::pyclass(name = "AllocatorMetrics", get_all, frozen)
+)]
+pub struct AllocatorMetrics {
+    /// The number of atoms stored in the atom heap.
+    pub atoms: usize,
+    /// The number of small atoms, which are stored in the node itself rather
+    /// than in the atom heap.
+    pub small_atoms: usize,
+    /// The number of pairs.
+    pub pairs: usize,
+    /// The number of bytes in the atom heap. The heap never shrinks (other than
+    /// by restoring a checkpoint), so this is also its peak size.
+    pub heap_size: usize,
+}
+
+impl AllocatorMetrics {
+    /// Everything allocated so far.
+    pub fn new(a: &Allocator) -> Self {
+        Self {
+            atoms: a.atom_count(),
+            small_atoms: a.small_atom_count(),
+            pairs: a.pair_count(),
+            heap_size: a.heap_size(),
+        }
+    }
+
+    /// Everything allocated since `start` was recorded from the same
+    /// allocator.
+    pub fn since(a: &Allocator, start: &Self) -> Self {
+        let now = Self::new(a);
+        Self {
+            atoms: now.atoms.saturating_sub(start.atoms),
+            small_atoms: now.small_atoms.saturating_sub(start.small_atoms),
+            pairs: now.pairs.saturating_sub(start.pairs),
+            heap_size: now.heap_size.saturating_sub(start.heap_size),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[test]
+    fn test_allocator_metrics() {
+        let mut a = make_allocator(LIMIT_HEAP);
+        let atom = a.new_atom(&[0xff; 100]).unwrap();
+        a.new_small_number(1).unwrap();
+        let start = AllocatorMetrics::new(&a);
+        assert_eq!(start.atoms, a.atom_count());
+        assert_eq!(start.small_atoms, a.small_atom_count());
+        assert_eq!(start.heap_size, a.heap_size());
+        assert_eq!(start.pairs, 0);
+
+        a.new_pair(atom, atom).unwrap();
+        a.new_atom(&[0xff; 50]).unwrap();
+        let delta = AllocatorMetrics::since(&a, &start);
+        assert_eq!(delta.pairs, 1);
+        assert_eq!(delta.atoms, 1);
+        assert_eq!(delta.small_atoms, 0);
+        assert_eq!(delta.heap_size, 50);
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let mut a = make_allocator(LIMIT_HEAP);
+        let atom = a.new_atom(&[0xff; 100]).unwrap();
+        let pair = a.new_pair(atom, a.nil()).unwrap();
+        let before = AllocatorMetrics::new(&a);
+
+        let cp = checkpoint(&a);
+        let new_atom = a.new_atom(&[0xee; 50]).unwrap();
+        let new_pair = a.new_pair(new_atom, pair).unwrap();
+        let small = a.new_small_number(1337).unwrap();
+        restore(&mut a, &cp);
+
+        // everything allocated after the checkpoint is gone
+        assert_eq!(a.atom_count(), before.atoms);
+        assert_eq!(a.pair_count(), before.pairs);
+        assert_eq!(a.heap_size(), before.heap_size);
+
+        // but nodes created before it are still valid
+        assert_eq!(a.atom(atom).as_ref(), &[0xff; 100]);
+        assert_eq!(a.next(pair), Some((atom, a.nil())));
+
+        // small atoms live in the NodePtr itself, so they survive
+        assert_eq!(a.small_number(small), Some(1337));
+
+        // the discarded nodes are unreachable
+        let result = catch_unwind(AssertUnwindSafe(|| a.atom(new_atom).as_ref().to_vec()));
+        assert!(result.is_err());
+        let result = catch_unwind(AssertUnwindSafe(|| a.next(new_pair)));
+        assert!(result.is_err());
+
+        // and new nodes are allocated in their place
+        let replacement = a.new_atom(&[0xdd; 50]).unwrap();
+        assert_eq!(replacement, new_atom);
+        assert_eq!(a.atom(new_atom).as_ref(), &[0xdd; 50]);
+    }
+}
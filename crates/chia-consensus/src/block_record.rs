@@ -0,0 +1,590 @@
+//! Computing the [`BlockRecord`] the node keeps for every block, ported from
+//! `chia.consensus.full_block_to_block_record` and `chia.consensus.deficit`.
+//! The parts that depend on other blocks than the previous one (the sub-slot
+//! iters and the sub-epoch summary) are passed in by the caller.
+
+use crate::consensus_constants::ConsensusConstants;
+use crate::error::{Error, Result};
+use crate::pot_iterations::{calculate_ip_iters, is_overflow_block};
+use chia_protocol::{BlockRecord, Bytes32, ChallengeBlockInfo, FullBlock, SubEpochSummary};
+use chia_traits::Streamable;
+
+/// The deficit of a block at `height`, following the block `prev`. The
+/// deficit counts down from `MIN_BLOCKS_PER_CHALLENGE_BLOCK` in each slot. It
+/// stays at the maximum while blocks overflow into the next sub-slot, and it
+/// is reset when a sub-slot ends with a deficit of 0.
+pub fn calculate_deficit(
+    constants: &ConsensusConstants,
+    height: u32,
+    prev: Option<&BlockRecord>,
+    overflow: bool,
+    num_finished_sub_slots: usize,
+) -> Result<u8> {
+    let max_deficit = constants.min_blocks_per_challenge_block;
+    if height == 0 {
+        return Ok(max_deficit - 1);
+    }
+    let Some(prev) = prev else {
+        return Err(Error::MissingPrevBlockRecord(height));
+    };
+
+    let deficit = if prev.deficit == max_deficit {
+        // the previous block was an overflow block. The deficit can only
+        // start counting down once we're in a new sub-slot
+        if overflow && num_finished_sub_slots == 0 {
+            max_deficit
+        } else {
+            max_deficit - 1
+        }
+    } else if prev.deficit == 0 {
+        match num_finished_sub_slots {
+            0 => 0,
+            1 if overflow => max_deficit,
+            _ => max_deficit - 1,
+        }
+    } else {
+        prev.deficit - 1
+    };
+    Ok(deficit)
+}
+
+/// Computes the [`BlockRecord`] of a block that has been validated.
+///
+/// `prev` is the record of the previous block, which is required except for
+/// the genesis block. `sub_slot_iters` is the sub-slot iters in effect for
+/// the block. If the block includes a sub-epoch summary hash, the summary has
+/// to be passed in as `sub_epoch_summary`, since computing it requires the
+/// whole sub-epoch. It's checked against the hash.
+pub fn block_to_block_record(
+    constants: &ConsensusConstants,
+    block: &FullBlock,
+    required_iters: u64,
+    sub_slot_iters: u64,
+    prev: Option<&BlockRecord>,
+    sub_epoch_summary: Option<SubEpochSummary>,
+) -> Result<BlockRecord> {
+    let rcb = &block.reward_chain_block;
+    let height = block.height();
+
+    if let Some(prev) = prev {
+        if prev.header_hash != block.prev_header_hash() {
+            return Err(Error::PrevBlockMismatch {
+                expected: block.prev_header_hash(),
+                found: prev.header_hash,
+            });
+        }
+    }
+
+    let overflow = is_overflow_block(constants, rcb.signage_point_index)?;
+    // the infusion point of the block is derived from these, so make sure
+    // they're consistent
+    calculate_ip_iters(
+        constants,
+        sub_slot_iters,
+        rcb.signage_point_index,
+        required_iters,
+    )?;
+    let deficit = calculate_deficit(
+        constants,
+        height,
+        prev,
+        overflow,
+        block.finished_sub_slots.len(),
+    )?;
+
+    let ses_hash = block
+        .finished_sub_slots
+        .iter()
+        .filter_map(|sub_slot| sub_slot.challenge_chain.subepoch_summary_hash)
+        .last();
+    match (ses_hash, &sub_epoch_summary) {
+        (None, None) => {}
+        (Some(hash), Some(ses)) if Bytes32::new(ses.hash()) == hash => {}
+        _ => return Err(Error::InvalidSubEpochSummary),
+    }
+
+    let prev_transaction_block_height = match prev {
+        Some(prev) if prev.is_transaction_block() => prev.height,
+        Some(prev) => prev.prev_transaction_block_height,
+        None => 0,
+    };
+
+    let challenge_block_info = ChallengeBlockInfo::new(
+        rcb.proof_of_space.clone(),
+        rcb.challenge_chain_sp_vdf.clone(),
+        rcb.challenge_chain_sp_signature.clone(),
+        rcb.challenge_chain_ip_vdf.clone(),
+    );
+
+    let (
+        finished_challenge_slot_hashes,
+        finished_infused_challenge_slot_hashes,
+        finished_reward_slot_hashes,
+    ) = if !block.finished_sub_slots.is_empty() {
+        let sub_slots = &block.finished_sub_slots;
+        (
+            Some(
+                sub_slots
+                    .iter()
+                    .map(|s| Bytes32::new(s.challenge_chain.hash()))
+                    .collect(),
+            ),
+            Some(
+                sub_slots
+                    .iter()
+                    .filter_map(|s| s.infused_challenge_chain.as_ref())
+                    .map(|icc| Bytes32::new(icc.hash()))
+                    .collect(),
+            ),
+            Some(
+                sub_slots
+                    .iter()
+                    .map(|s| Bytes32::new(s.reward_chain.hash()))
+                    .collect(),
+            ),
+        )
+    } else if height == 0 {
+        (
+            Some(vec![constants.genesis_challenge]),
+            None,
+            Some(vec![constants.genesis_challenge]),
+        )
+    } else {
+        (None, None, None)
+    };
+
+    let ftb = block.foliage_transaction_block.as_ref();
+    let transactions_info = block.transactions_info.as_ref();
+    let foliage_block_data = &block.foliage.foliage_block_data;
+
+    Ok(BlockRecord {
+        header_hash: block.header_hash(),
+        prev_hash: block.prev_header_hash(),
+        height,
+        weight: rcb.weight,
+        total_iters: rcb.total_iters,
+        signage_point_index: rcb.signage_point_index,
+        challenge_vdf_output: rcb.challenge_chain_ip_vdf.output,
+        infused_challenge_vdf_output: rcb
+            .infused_challenge_chain_ip_vdf
+            .as_ref()
+            .map(|vdf| vdf.output),
+        reward_infusion_new_challenge: Bytes32::new(rcb.hash()),
+        challenge_block_info_hash: Bytes32::new(challenge_block_info.hash()),
+        sub_slot_iters,
+        pool_puzzle_hash: foliage_block_data.pool_target.puzzle_hash,
+        farmer_puzzle_hash: foliage_block_data.farmer_reward_puzzle_hash,
+        required_iters,
+        deficit,
+        overflow,
+        prev_transaction_block_height,
+        timestamp: ftb.map(|ftb| ftb.timestamp),
+        prev_transaction_block_hash: ftb.map(|ftb| ftb.prev_transaction_block_hash),
+        fees: transactions_info.map(|ti| ti.fees),
+        reward_claims_incorporated: transactions_info
+            .map(|ti| ti.reward_claims_incorporated.clone()),
+        finished_challenge_slot_hashes,
+        finished_infused_challenge_slot_hashes,
+        finished_reward_slot_hashes,
+        sub_epoch_summary_included: sub_epoch_summary,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus_constants::TEST_CONSTANTS;
+    use chia_bls::{G1Element, G2Element};
+    use chia_protocol::{
+        Amount, Bytes, Bytes100, ChallengeChainSubSlot, ClassgroupElement, Coin,
+        EndOfSubSlotBundle, Foliage, FoliageBlockData, FoliageTransactionBlock,
+        InfusedChallengeChainSubSlot, PoolTarget, ProofOfSpace, RewardChainBlock,
+        RewardChainSubSlot, SubSlotProofs, TransactionsInfo, VDFInfo, VDFProof,
+    };
+    use rstest::rstest;
+
+    const MAX_DEFICIT: u8 = TEST_CONSTANTS.min_blocks_per_challenge_block;
+    // 64 signage points of 1000 iterations each
+    const SUB_SLOT_ITERS: u64 = 64_000;
+    const REQUIRED_ITERS: u64 = 500;
+
+    fn vdf(challenge: u8, output: u8) -> VDFInfo {
+        let element = ClassgroupElement::new(Bytes100::new([output; 100]));
+        VDFInfo::new(Bytes32::new([challenge; 32]), 1000, element)
+    }
+
+    fn proof() -> VDFProof {
+        VDFProof::new(0, Bytes::default(), true)
+    }
+
+    fn sub_slot(ses_hash: Option<Bytes32>, with_icc: bool) -> EndOfSubSlotBundle {
+        let icc = with_icc.then(|| InfusedChallengeChainSubSlot::new(vdf(20, 0)));
+        let cc = ChallengeChainSubSlot::new(vdf(21, 0), None, ses_hash, None, None);
+        let rc = RewardChainSubSlot::new(vdf(22, 0), Bytes32::new(cc.hash()), None, 0);
+        let proofs = SubSlotProofs::new(proof(), with_icc.then(proof), proof());
+        EndOfSubSlotBundle::new(cc, icc, rc, proofs)
+    }
+
+    fn block(
+        height: u32,
+        prev_hash: Bytes32,
+        signage_point_index: u8,
+        transaction_block: bool,
+        finished_sub_slots: Vec<EndOfSubSlotBundle>,
+    ) -> FullBlock {
+        let proof_of_space = ProofOfSpace::new(
+            Bytes32::new([1; 32]),
+            Some(G1Element::default()),
+            None,
+            G1Element::default(),
+            32,
+            Bytes::new(vec![0x55; 64]),
+        );
+        let reward_chain_block = RewardChainBlock::new(
+            1000 + u128::from(height),
+            height,
+            123_456_789 + u128::from(height),
+            signage_point_index,
+            Bytes32::new([2; 32]),
+            proof_of_space,
+            None,
+            G2Element::default(),
+            vdf(3, 1),
+            None,
+            G2Element::default(),
+            vdf(4, 2),
+            Some(vdf(5, 3)),
+            transaction_block,
+        );
+        let foliage_block_data = FoliageBlockData::new(
+            Bytes32::new([6; 32]),
+            PoolTarget::new(Bytes32::new([7; 32]), 0),
+            None,
+            Bytes32::new([8; 32]),
+            Bytes32::default(),
+        );
+        let foliage_transaction_block = FoliageTransactionBlock::new(
+            Bytes32::new([9; 32]),
+            1_700_000_000 + u64::from(height),
+            Bytes32::new([10; 32]),
+            Bytes32::new([11; 32]),
+            Bytes32::new([12; 32]),
+            Bytes32::new([13; 32]),
+        );
+        let reward_coin = Coin::new(
+            Bytes32::new([14; 32]),
+            Bytes32::new([7; 32]),
+            Amount::new(1750),
+        );
+        let transactions_info = TransactionsInfo::new(
+            Bytes32::new([15; 32]),
+            Bytes32::new([16; 32]),
+            G2Element::default(),
+            42,
+            1000,
+            vec![reward_coin],
+        );
+        let foliage = Foliage::new(
+            prev_hash,
+            Bytes32::new([17; 32]),
+            foliage_block_data,
+            G2Element::default(),
+            transaction_block.then(|| foliage_transaction_block.hash().into()),
+            transaction_block.then(G2Element::default),
+        );
+        FullBlock::new(
+            finished_sub_slots,
+            reward_chain_block,
+            None,
+            proof(),
+            None,
+            proof(),
+            None,
+            foliage,
+            transaction_block.then_some(foliage_transaction_block),
+            transaction_block.then_some(transactions_info),
+            None,
+            vec![],
+        )
+    }
+
+    fn record(block: &FullBlock, prev: Option<&BlockRecord>) -> BlockRecord {
+        block_to_block_record(
+            &TEST_CONSTANTS,
+            block,
+            REQUIRED_ITERS,
+            SUB_SLOT_ITERS,
+            prev,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn genesis() -> FullBlock {
+        block(0, Bytes32::new([0; 32]), 0, true, vec![])
+    }
+
+    #[test]
+    fn test_genesis() {
+        let block = genesis();
+        let record = record(&block, None);
+
+        assert_eq!(record.header_hash, block.header_hash());
+        assert_eq!(record.prev_hash, Bytes32::new([0; 32]));
+        assert_eq!(record.height, 0);
+        assert_eq!(record.weight, 1000);
+        assert_eq!(record.total_iters, 123_456_789);
+        assert_eq!(
+            record.challenge_vdf_output,
+            block.reward_chain_block.challenge_chain_ip_vdf.output
+        );
+        assert_eq!(record.infused_challenge_vdf_output, Some(vdf(5, 3).output));
+        assert_eq!(
+            record.reward_infusion_new_challenge,
+            Bytes32::new(block.reward_chain_block.hash())
+        );
+        assert_eq!(record.sub_slot_iters, SUB_SLOT_ITERS);
+        assert_eq!(record.pool_puzzle_hash, Bytes32::new([7; 32]));
+        assert_eq!(record.farmer_puzzle_hash, Bytes32::new([8; 32]));
+        assert_eq!(record.required_iters, REQUIRED_ITERS);
+        assert_eq!(record.deficit, MAX_DEFICIT - 1);
+        assert!(!record.overflow);
+        assert_eq!(record.prev_transaction_block_height, 0);
+        assert_eq!(record.timestamp, Some(1_700_000_000));
+        assert_eq!(
+            record.prev_transaction_block_hash,
+            Some(Bytes32::new([9; 32]))
+        );
+        assert_eq!(record.fees, Some(42));
+        assert_eq!(
+            record.reward_claims_incorporated,
+            block
+                .transactions_info
+                .as_ref()
+                .map(|ti| ti.reward_claims_incorporated.clone())
+        );
+        // the genesis block starts the first slot
+        let genesis_challenge = Some(vec![TEST_CONSTANTS.genesis_challenge]);
+        assert_eq!(record.finished_challenge_slot_hashes, genesis_challenge);
+        assert_eq!(record.finished_infused_challenge_slot_hashes, None);
+        assert_eq!(record.finished_reward_slot_hashes, genesis_challenge);
+        assert_eq!(record.sub_epoch_summary_included, None);
+        assert!(record.is_transaction_block());
+        assert!(record.first_in_sub_slot());
+    }
+
+    #[test]
+    fn test_challenge_block_info_hash() {
+        let block = genesis();
+        let rcb = &block.reward_chain_block;
+        let info = ChallengeBlockInfo::new(
+            rcb.proof_of_space.clone(),
+            rcb.challenge_chain_sp_vdf.clone(),
+            rcb.challenge_chain_sp_signature.clone(),
+            rcb.challenge_chain_ip_vdf.clone(),
+        );
+        assert_eq!(
+            record(&block, None).challenge_block_info_hash,
+            Bytes32::new(info.hash())
+        );
+    }
+
+    #[test]
+    fn test_chain() {
+        // a transaction block, followed by a non-transaction block, followed
+        // by an overflow block in a new sub-slot
+        let genesis = genesis();
+        let genesis_record = record(&genesis, None);
+
+        let second = block(1, genesis.header_hash(), 10, false, vec![]);
+        let second_record = record(&second, Some(&genesis_record));
+        assert_eq!(second_record.deficit, MAX_DEFICIT - 2);
+        assert_eq!(second_record.prev_transaction_block_height, 0);
+        assert_eq!(second_record.timestamp, None);
+        assert_eq!(second_record.fees, None);
+        assert_eq!(second_record.reward_claims_incorporated, None);
+        assert_eq!(second_record.finished_challenge_slot_hashes, None);
+        assert!(!second_record.first_in_sub_slot());
+
+        let sub_slots = vec![sub_slot(None, true), sub_slot(None, false)];
+        let third = block(2, second.header_hash(), 62, true, sub_slots.clone());
+        let third_record = record(&third, Some(&second_record));
+        assert!(third_record.overflow);
+        assert_eq!(third_record.deficit, MAX_DEFICIT - 3);
+        // the previous transaction block is found through the previous record
+        assert_eq!(third_record.prev_transaction_block_height, 0);
+        assert_eq!(
+            third_record.finished_challenge_slot_hashes,
+            Some(
+                sub_slots
+                    .iter()
+                    .map(|s| Bytes32::new(s.challenge_chain.hash()))
+                    .collect()
+            )
+        );
+        assert_eq!(
+            third_record.finished_reward_slot_hashes,
+            Some(
+                sub_slots
+                    .iter()
+                    .map(|s| Bytes32::new(s.reward_chain.hash()))
+                    .collect()
+            )
+        );
+        // only the sub-slots with an infused challenge chain are included
+        assert_eq!(
+            third_record.finished_infused_challenge_slot_hashes,
+            Some(vec![Bytes32::new(
+                sub_slots[0]
+                    .infused_challenge_chain
+                    .as_ref()
+                    .unwrap()
+                    .hash()
+            )])
+        );
+
+        let fourth = block(3, third.header_hash(), 0, false, vec![]);
+        let fourth_record = record(&fourth, Some(&third_record));
+        assert_eq!(fourth_record.prev_transaction_block_height, 2);
+    }
+
+    fn prev_record(deficit: u8) -> BlockRecord {
+        let mut prev = record(&genesis(), None);
+        prev.deficit = deficit;
+        prev
+    }
+
+    #[rstest]
+    // counting down within the slot
+    #[case(10, false, 0, 9)]
+    #[case(1, true, 0, 0)]
+    // after an overflow block, the deficit stays at the maximum until the
+    // next sub-slot
+    #[case(MAX_DEFICIT, true, 0, MAX_DEFICIT)]
+    #[case(MAX_DEFICIT, true, 1, MAX_DEFICIT - 1)]
+    #[case(MAX_DEFICIT, false, 0, MAX_DEFICIT - 1)]
+    // a deficit of 0 is reset in the next sub-slot
+    #[case(0, false, 0, 0)]
+    #[case(0, true, 0, 0)]
+    #[case(0, false, 1, MAX_DEFICIT - 1)]
+    #[case(0, true, 1, MAX_DEFICIT)]
+    #[case(0, true, 2, MAX_DEFICIT - 1)]
+    fn test_calculate_deficit(
+        #[case] prev_deficit: u8,
+        #[case] overflow: bool,
+        #[case] num_finished_sub_slots: usize,
+        #[case] expected: u8,
+    ) {
+        let prev = prev_record(prev_deficit);
+        assert_eq!(
+            calculate_deficit(
+                &TEST_CONSTANTS,
+                1,
+                Some(&prev),
+                overflow,
+                num_finished_sub_slots
+            )
+            .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_sub_epoch_summary() {
+        let genesis = genesis();
+        let genesis_record = record(&genesis, None);
+
+        let ses = SubEpochSummary::new(
+            Bytes32::new([30; 32]),
+            Bytes32::new([31; 32]),
+            2,
+            Some(1000),
+            Some(SUB_SLOT_ITERS),
+        );
+        let ses_hash = Bytes32::new(ses.hash());
+        let with_ses = block(
+            1,
+            genesis.header_hash(),
+            0,
+            false,
+            vec![sub_slot(Some(ses_hash), false)],
+        );
+        let without_ses = block(1, genesis.header_hash(), 0, false, vec![]);
+
+        let run = |block: &FullBlock, ses: Option<SubEpochSummary>| {
+            block_to_block_record(
+                &TEST_CONSTANTS,
+                block,
+                REQUIRED_ITERS,
+                SUB_SLOT_ITERS,
+                Some(&genesis_record),
+                ses,
+            )
+        };
+
+        let record = run(&with_ses, Some(ses.clone())).unwrap();
+        assert_eq!(record.sub_epoch_summary_included, Some(ses.clone()));
+
+        // the summary is required, must match, and can't be included
+        // without its hash in the block
+        let mut other = ses.clone();
+        other.num_blocks_overflow = 3;
+        for (block, ses) in [
+            (&with_ses, None),
+            (&with_ses, Some(other)),
+            (&without_ses, Some(ses)),
+        ] {
+            assert_eq!(run(block, ses), Err(Error::InvalidSubEpochSummary));
+        }
+    }
+
+    #[test]
+    fn test_prev_block() {
+        let genesis = genesis();
+        let genesis_record = record(&genesis, None);
+        let next = block(1, genesis.header_hash(), 0, false, vec![]);
+        let run = |block: &FullBlock, prev: Option<&BlockRecord>| {
+            block_to_block_record(
+                &TEST_CONSTANTS,
+                block,
+                REQUIRED_ITERS,
+                SUB_SLOT_ITERS,
+                prev,
+                None,
+            )
+        };
+
+        assert_eq!(run(&next, None), Err(Error::MissingPrevBlockRecord(1)));
+
+        let unrelated = block(1, Bytes32::new([99; 32]), 0, false, vec![]);
+        assert_eq!(
+            run(&unrelated, Some(&genesis_record)),
+            Err(Error::PrevBlockMismatch {
+                expected: Bytes32::new([99; 32]),
+                found: genesis.header_hash(),
+            })
+        );
+    }
+
+    #[rstest]
+    #[case::required_iters_zero(0, SUB_SLOT_ITERS, 0)]
+    #[case::required_iters_too_high(1000, SUB_SLOT_ITERS, 0)]
+    #[case::sub_slot_iters(REQUIRED_ITERS, SUB_SLOT_ITERS + 1, 0)]
+    #[case::signage_point_index(REQUIRED_ITERS, SUB_SLOT_ITERS, 64)]
+    fn test_invalid_iters(
+        #[case] required_iters: u64,
+        #[case] sub_slot_iters: u64,
+        #[case] signage_point_index: u8,
+    ) {
+        let block = block(0, Bytes32::default(), signage_point_index, true, vec![]);
+        assert!(block_to_block_record(
+            &TEST_CONSTANTS,
+            &block,
+            required_iters,
+            sub_slot_iters,
+            None,
+            None
+        )
+        .is_err());
+    }
+}
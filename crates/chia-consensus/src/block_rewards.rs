@@ -0,0 +1,322 @@
+use crate::gen::validation_error::ErrorCode;
+use chia_protocol::{Amount, Bytes32, Coin};
+use std::collections::HashSet;
+
+const MOJO_PER_CHIA: u64 = 1_000_000_000_000;
+const BLOCKS_PER_YEAR: u32 = 1_681_920;
+
+// the total block reward (pool + farmer) at the specified height, not counting
+// the pre-farm in the genesis block. It halves every 3 years, for 12 years.
+fn block_reward(height: u32) -> u64 {
+    let halvings = match height / BLOCKS_PER_YEAR {
+        0..=2 => 0,
+        3..=5 => 1,
+        6..=8 => 2,
+        9..=11 => 3,
+        _ => 4,
+    };
+    (2 * MOJO_PER_CHIA) >> halvings
+}
+
+/// The pool reward at the specified height, i.e. 7/8 of the block reward.
+/// This mirrors `calculate_pool_reward()` in chia-blockchain.
+pub fn calculate_pool_reward(height: u32) -> u64 {
+    if height == 0 {
+        // the pre-farm. The total pre-farm is 21 million XCH, which doesn't
+        // fit in a u64
+        return 18_375_000 * MOJO_PER_CHIA;
+    }
+    block_reward(height) / 8 * 7
+}
+
+/// The farmer reward at the specified height, not including fees, i.e. 1/8 of
+/// the block reward. This mirrors `calculate_base_farmer_reward()` in
+/// chia-blockchain.
+pub fn calculate_base_farmer_reward(height: u32) -> u64 {
+    if height == 0 {
+        return 2_625_000 * MOJO_PER_CHIA;
+    }
+    block_reward(height) / 8
+}
+
+fn reward_parent_id(prefix: &[u8], height: u32) -> Bytes32 {
+    let mut ret = [0_u8; 32];
+    ret[..16].copy_from_slice(prefix);
+    ret[16..].copy_from_slice(&u128::from(height).to_be_bytes());
+    ret.into()
+}
+
+/// The parent coin ID of the pool reward coin at the specified height. Reward
+/// coins don't have a real parent.
+pub fn pool_parent_id(height: u32, genesis_challenge: &Bytes32) -> Bytes32 {
+    reward_parent_id(&genesis_challenge[..16], height)
+}
+
+/// The parent coin ID of the farmer reward coin at the specified height.
+pub fn farmer_parent_id(height: u32, genesis_challenge: &Bytes32) -> Bytes32 {
+    reward_parent_id(&genesis_challenge[16..], height)
+}
+
+pub fn create_pool_coin(
+    height: u32,
+    puzzle_hash: Bytes32,
+    reward: u64,
+    genesis_challenge: &Bytes32,
+) -> Coin {
+    Coin::new(
+        pool_parent_id(height, genesis_challenge),
+        puzzle_hash,
+        Amount::new(reward),
+    )
+}
+
+pub fn create_farmer_coin(
+    height: u32,
+    puzzle_hash: Bytes32,
+    reward: u64,
+    genesis_challenge: &Bytes32,
+) -> Coin {
+    Coin::new(
+        farmer_parent_id(height, genesis_challenge),
+        puzzle_hash,
+        Amount::new(reward),
+    )
+}
+
+/// The pool and farmer reward coins for the transaction block at the specified
+/// height, which are claimed by the next transaction block. The farmer coin
+/// also pays out the `fees` collected by the block. This mirrors how
+/// `validate_block_body()` in chia-blockchain constructs them.
+pub fn expected_reward_coins(
+    height: u32,
+    pool_puzzle_hash: Bytes32,
+    farmer_puzzle_hash: Bytes32,
+    fees: u64,
+    genesis_challenge: &Bytes32,
+) -> Vec<Coin> {
+    vec![
+        create_pool_coin(
+            height,
+            pool_puzzle_hash,
+            calculate_pool_reward(height),
+            genesis_challenge,
+        ),
+        // the fees of a block can't exceed the total supply, so this doesn't
+        // overflow for any valid block
+        create_farmer_coin(
+            height,
+            farmer_puzzle_hash,
+            calculate_base_farmer_reward(height).saturating_add(fees),
+            genesis_challenge,
+        ),
+    ]
+}
+
+/// Checks the reward claims of a transaction block against the expected
+/// reward coins. The order doesn't matter, but there must not be any
+/// duplicates.
+pub fn validate_reward_coins(claimed: &[Coin], expected: &[Coin]) -> Result<(), ErrorCode> {
+    let claimed_set: HashSet<&Coin> = claimed.iter().collect();
+    let expected_set: HashSet<&Coin> = expected.iter().collect();
+    if claimed_set != expected_set || claimed.len() != expected_set.len() {
+        return Err(ErrorCode::InvalidRewardCoins);
+    }
+    Ok(())
+}
+
+/// The fees collected by a block, given the total amounts of the coins it
+/// spends and creates (`removal_amount` and `addition_amount` in
+/// `SpendBundleConditions`). A block may not create more value than it
+/// spends, and must pay at least the fee reserved by its `RESERVE_FEE`
+/// conditions.
+pub fn compute_block_fees(
+    removal_amount: u128,
+    addition_amount: u128,
+    reserve_fee: u64,
+) -> Result<u64, ErrorCode> {
+    let fees = removal_amount
+        .checked_sub(addition_amount)
+        .ok_or(ErrorCode::MintingCoin)?;
+    let fees = u64::try_from(fees).map_err(|_| ErrorCode::InvalidBlockFeeAmount)?;
+    if fees < reserve_fee {
+        return Err(ErrorCode::ReserveFeeConditionFailed);
+    }
+    Ok(fees)
+}
+
+/// Checks the fees claimed in the transactions info of a block against the
+/// amounts in its conditions, see `compute_block_fees()`.
+pub fn validate_block_fees(
+    removal_amount: u128,
+    addition_amount: u128,
+    reserve_fee: u64,
+    claimed_fees: u64,
+) -> Result<(), ErrorCode> {
+    if compute_block_fees(removal_amount, addition_amount, reserve_fee)? != claimed_fees {
+        return Err(ErrorCode::InvalidBlockFeeAmount);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 18_375_000_000_000_000_000, 2_625_000_000_000_000_000)]
+    #[case(1, 1_750_000_000_000, 250_000_000_000)]
+    #[case(3 * 1_681_920 - 1, 1_750_000_000_000, 250_000_000_000)]
+    #[case(3 * 1_681_920, 875_000_000_000, 125_000_000_000)]
+    #[case(6 * 1_681_920, 437_500_000_000, 62_500_000_000)]
+    #[case(9 * 1_681_920, 218_750_000_000, 31_250_000_000)]
+    #[case(12 * 1_681_920, 109_375_000_000, 15_625_000_000)]
+    #[case(u32::MAX, 109_375_000_000, 15_625_000_000)]
+    fn test_rewards(#[case] height: u32, #[case] pool: u64, #[case] farmer: u64) {
+        assert_eq!(calculate_pool_reward(height), pool);
+        assert_eq!(calculate_base_farmer_reward(height), farmer);
+    }
+
+    const MAINNET_GENESIS_CHALLENGE: Bytes32 = Bytes32::new(hex!(
+        "ccd5bb71183532bff220ba46c268991a3ff07eb358e8255a65c30a2dce0e5fbb"
+    ));
+
+    // heights around each halving of the mainnet reward schedule
+    #[rstest]
+    #[case(3 * 1_681_920 - 1, 1_750_000_000_000, 250_000_000_000)]
+    #[case(3 * 1_681_920, 875_000_000_000, 125_000_000_000)]
+    #[case(3 * 1_681_920 + 1, 875_000_000_000, 125_000_000_000)]
+    #[case(6 * 1_681_920 - 1, 875_000_000_000, 125_000_000_000)]
+    #[case(6 * 1_681_920, 437_500_000_000, 62_500_000_000)]
+    #[case(6 * 1_681_920 + 1, 437_500_000_000, 62_500_000_000)]
+    #[case(9 * 1_681_920 - 1, 437_500_000_000, 62_500_000_000)]
+    #[case(9 * 1_681_920, 218_750_000_000, 31_250_000_000)]
+    #[case(9 * 1_681_920 + 1, 218_750_000_000, 31_250_000_000)]
+    #[case(12 * 1_681_920 - 1, 218_750_000_000, 31_250_000_000)]
+    #[case(12 * 1_681_920, 109_375_000_000, 15_625_000_000)]
+    #[case(12 * 1_681_920 + 1, 109_375_000_000, 15_625_000_000)]
+    fn test_expected_reward_coins(
+        #[case] height: u32,
+        #[case] pool: u64,
+        #[case] farmer: u64,
+        #[values(0, 1, 1_000_000)] fees: u64,
+    ) {
+        let pool_ph = Bytes32::new([1; 32]);
+        let farmer_ph = Bytes32::new([2; 32]);
+        let coins =
+            expected_reward_coins(height, pool_ph, farmer_ph, fees, &MAINNET_GENESIS_CHALLENGE);
+        let mut pool_parent = [0_u8; 32];
+        pool_parent[..16].copy_from_slice(&MAINNET_GENESIS_CHALLENGE[..16]);
+        pool_parent[28..].copy_from_slice(&height.to_be_bytes());
+        let mut farmer_parent = [0_u8; 32];
+        farmer_parent[..16].copy_from_slice(&MAINNET_GENESIS_CHALLENGE[16..]);
+        farmer_parent[28..].copy_from_slice(&height.to_be_bytes());
+        assert_eq!(
+            coins,
+            vec![
+                Coin::new(pool_parent.into(), pool_ph, Amount::new(pool)),
+                Coin::new(farmer_parent.into(), farmer_ph, Amount::new(farmer + fees)),
+            ]
+        );
+
+        // the claims may be in any order
+        assert_eq!(validate_reward_coins(&coins, &coins), Ok(()));
+        let reversed = vec![coins[1], coins[0]];
+        assert_eq!(validate_reward_coins(&reversed, &coins), Ok(()));
+    }
+
+    #[test]
+    fn test_genesis_reward_coins() {
+        let ph = Bytes32::new([3; 32]);
+        let coins = expected_reward_coins(0, ph, ph, 0, &MAINNET_GENESIS_CHALLENGE);
+        assert_eq!(coins[0].amount, 18_375_000_000_000_000_000);
+        assert_eq!(coins[1].amount, 2_625_000_000_000_000_000);
+        assert_eq!(
+            coins[0].parent_coin_info,
+            Bytes32::new(hex!(
+                "ccd5bb71183532bff220ba46c268991a00000000000000000000000000000000"
+            ))
+        );
+        assert_eq!(
+            coins[1].parent_coin_info,
+            Bytes32::new(hex!(
+                "3ff07eb358e8255a65c30a2dce0e5fbb00000000000000000000000000000000"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_invalid_reward_coins() {
+        let ph = Bytes32::new([4; 32]);
+        let coins = expected_reward_coins(1000, ph, ph, 10, &MAINNET_GENESIS_CHALLENGE);
+        let err = Err(ErrorCode::InvalidRewardCoins);
+
+        // missing a coin
+        assert_eq!(validate_reward_coins(&coins[..1], &coins), err);
+        // duplicate coin
+        let dup = vec![coins[0], coins[1], coins[1]];
+        assert_eq!(validate_reward_coins(&dup, &coins), err);
+        // wrong amount, i.e. not accounting for the fees
+        let mut wrong = coins.clone();
+        wrong[1].amount = Amount::new(wrong[1].amount.get() - 10);
+        assert_eq!(validate_reward_coins(&wrong, &coins), err);
+        // the coins of a different height
+        let other = expected_reward_coins(1001, ph, ph, 10, &MAINNET_GENESIS_CHALLENGE);
+        assert_eq!(validate_reward_coins(&other, &coins), err);
+    }
+
+    #[rstest]
+    #[case(100, 100, 0, Ok(0))]
+    #[case(100, 60, 0, Ok(40))]
+    #[case(100, 60, 40, Ok(40))]
+    #[case(100, 60, 41, Err(ErrorCode::ReserveFeeConditionFailed))]
+    #[case(100, 101, 0, Err(ErrorCode::MintingCoin))]
+    #[case(u128::from(u64::MAX), 0, 0, Ok(u64::MAX))]
+    #[case(u128::from(u64::MAX) + 1, 0, 0, Err(ErrorCode::InvalidBlockFeeAmount))]
+    #[case(u128::from(u64::MAX) * 2, u128::from(u64::MAX), 0, Ok(u64::MAX))]
+    fn test_compute_block_fees(
+        #[case] removal_amount: u128,
+        #[case] addition_amount: u128,
+        #[case] reserve_fee: u64,
+        #[case] expected: Result<u64, ErrorCode>,
+    ) {
+        assert_eq!(
+            compute_block_fees(removal_amount, addition_amount, reserve_fee),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(40, Ok(()))]
+    #[case(39, Err(ErrorCode::InvalidBlockFeeAmount))]
+    #[case(41, Err(ErrorCode::InvalidBlockFeeAmount))]
+    #[case(0, Err(ErrorCode::InvalidBlockFeeAmount))]
+    fn test_validate_block_fees(#[case] claimed: u64, #[case] expected: Result<(), ErrorCode>) {
+        assert_eq!(validate_block_fees(100, 60, 10, claimed), expected);
+        // an invalid block fails the same way regardless of the claimed fees
+        assert_eq!(
+            validate_block_fees(60, 100, 0, claimed),
+            Err(ErrorCode::MintingCoin)
+        );
+    }
+
+    #[test]
+    fn test_parent_ids() {
+        let challenge = Bytes32::new(hex!(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+        ));
+        assert_eq!(
+            pool_parent_id(0x1234, &challenge),
+            Bytes32::new(hex!(
+                "000102030405060708090a0b0c0d0e0f00000000000000000000000000001234"
+            ))
+        );
+        assert_eq!(
+            farmer_parent_id(0x1234, &challenge),
+            Bytes32::new(hex!(
+                "101112131415161718191a1b1c1d1e1f00000000000000000000000000001234"
+            ))
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! Verification of the coins (and merkle proofs) in `RespondAdditions` and
+//! `RespondRemovals` against the additions and removals roots of a transaction
+//! block.
+//!
+//! The additions merkle set of a block contains, for every puzzle hash coins
+//! were created with, the puzzle hash itself and `hash_coin_ids()` of the IDs of
+//! those coins. The removals merkle set contains the IDs of the spent coins.
+
+use crate::error::{Error, Result};
+use crate::merkle_set::{compute_merkle_set_root, MerkleSetBuilder};
+use crate::merkle_tree::validate_merkle_proof;
+use chia_protocol::{Bytes, Bytes32, Coin, HeaderBlock};
+use chia_sha2::Sha256;
+use std::collections::BTreeMap;
+
+/// The hash of a set of coin IDs, as stored in the additions merkle set. A
+/// single ID is hashed on its own, more IDs are sorted in descending order and
+/// hashed together.
+pub fn hash_coin_ids(coin_ids: &mut [Bytes32]) -> Bytes32 {
+    coin_ids.sort_unstable_by(|lhs, rhs| rhs.cmp(lhs));
+    let mut ctx = Sha256::new();
+    for id in coin_ids.iter() {
+        ctx.update(id);
+    }
+    Bytes32::new(ctx.finalize())
+}
+
+/// The root of the merkle set of `coin_ids`, e.g. the removals root of a block.
+/// The IDs must be sorted in ascending order (duplicates are ignored). They're
+/// hashed as they come, so they can be streamed from a database cursor (or a
+/// page at a time) without holding all of them in memory.
+pub fn coin_ids_to_merkle_root(coin_ids: impl IntoIterator<Item = Bytes32>) -> Result<Bytes32> {
+    let mut builder = MerkleSetBuilder::new();
+    for id in coin_ids {
+        builder.push(id.to_bytes())?;
+    }
+    Ok(builder.finalize())
+}
+
+/// The additions root of a block that created `coins`, in any order. The
+/// coins are grouped by puzzle hash, and every group adds its puzzle hash and
+/// `hash_coin_ids()` of its coin IDs to the set. Since a group isn't complete
+/// until all coins have been seen, the coin IDs are kept in memory, but not
+/// the coins themselves.
+pub fn coins_to_addition_root(coins: impl IntoIterator<Item = Coin>) -> Bytes32 {
+    let mut by_puzzle_hash = BTreeMap::<Bytes32, Vec<Bytes32>>::new();
+    for coin in coins {
+        by_puzzle_hash
+            .entry(coin.puzzle_hash)
+            .or_default()
+            .push(coin.coin_id());
+    }
+    let mut leafs = Vec::<[u8; 32]>::with_capacity(by_puzzle_hash.len() * 2);
+    for (puzzle_hash, mut ids) in by_puzzle_hash {
+        leafs.push(puzzle_hash.to_bytes());
+        leafs.push(hash_coin_ids(&mut ids).to_bytes());
+    }
+    Bytes32::new(compute_merkle_set_root(&mut leafs))
+}
+
+// fails unless the proof shows whether item is in the set with the specified
+// root, and the answer is `expect_included`
+fn check_proof(proof: &[u8], item: &Bytes32, root: &Bytes32, expect_included: bool) -> Result<()> {
+    match validate_merkle_proof(proof, item.into(), root.into()) {
+        Ok(included) if included == expect_included => Ok(()),
+        _ => Err(Error::InvalidMerkleProof(*item)),
+    }
+}
+
+/// Verifies the coins of a `RespondAdditions` message against the additions
+/// root of a block. Without proofs, the coins must be all the additions of the
+/// block. With proofs, there must be one proof per puzzle hash. A puzzle hash
+/// with no coins needs a proof of exclusion, otherwise both the puzzle hash and
+/// the hash of the coin IDs need proofs of inclusion.
+pub fn verify_additions(
+    additions_root: &Bytes32,
+    coins: &[(Bytes32, Vec<Coin>)],
+    proofs: Option<&[(Bytes32, Bytes, Option<Bytes>)]>,
+) -> Result<()> {
+    let Some(proofs) = proofs else {
+        let mut leafs = Vec::<[u8; 32]>::with_capacity(coins.len() * 2);
+        for (puzzle_hash, coins) in coins {
+            let mut ids: Vec<Bytes32> = coins.iter().map(Coin::coin_id).collect();
+            leafs.push(puzzle_hash.to_bytes());
+            leafs.push(hash_coin_ids(&mut ids).to_bytes());
+        }
+        if compute_merkle_set_root(&mut leafs) != additions_root.to_bytes() {
+            return Err(Error::AdditionsRootMismatch);
+        }
+        return Ok(());
+    };
+
+    if coins.len() != proofs.len() {
+        return Err(Error::ProofCountMismatch);
+    }
+
+    for ((puzzle_hash, coins), (proof_puzzle_hash, puzzle_hash_proof, coins_proof)) in
+        coins.iter().zip(proofs)
+    {
+        if puzzle_hash != proof_puzzle_hash || coins.iter().any(|c| c.puzzle_hash != *puzzle_hash) {
+            return Err(Error::PuzzleHashMismatch);
+        }
+        if coins.is_empty() {
+            check_proof(puzzle_hash_proof, puzzle_hash, additions_root, false)?;
+            continue;
+        }
+        check_proof(puzzle_hash_proof, puzzle_hash, additions_root, true)?;
+        let mut ids: Vec<Bytes32> = coins.iter().map(Coin::coin_id).collect();
+        let coins_hash = hash_coin_ids(&mut ids);
+        let Some(coins_proof) = coins_proof else {
+            return Err(Error::InvalidMerkleProof(coins_hash));
+        };
+        check_proof(coins_proof, &coins_hash, additions_root, true)?;
+    }
+    Ok(())
+}
+
+/// Verifies the coins of a `RespondRemovals` message against the removals root
+/// of a block. Without proofs, the coins must be all the removals of the block.
+/// With proofs, every coin ID needs a proof of inclusion, or a proof of
+/// exclusion if the coin is None.
+pub fn verify_removals(
+    removals_root: &Bytes32,
+    coins: &[(Bytes32, Option<Coin>)],
+    proofs: Option<&[(Bytes32, Bytes)]>,
+) -> Result<()> {
+    for (coin_id, coin) in coins {
+        if coin.is_some_and(|c| c.coin_id() != *coin_id) {
+            return Err(Error::CoinMismatch);
+        }
+    }
+
+    let Some(proofs) = proofs else {
+        let mut leafs: Vec<[u8; 32]> = coins
+            .iter()
+            .filter(|(_, coin)| coin.is_some())
+            .map(|(coin_id, _)| coin_id.to_bytes())
+            .collect();
+        if compute_merkle_set_root(&mut leafs) != removals_root.to_bytes() {
+            return Err(Error::RemovalsRootMismatch);
+        }
+        return Ok(());
+    };
+
+    if coins.len() != proofs.len() {
+        return Err(Error::ProofCountMismatch);
+    }
+
+    for ((coin_id, coin), (proof_coin_id, proof)) in coins.iter().zip(proofs) {
+        if coin_id != proof_coin_id {
+            return Err(Error::CoinMismatch);
+        }
+        check_proof(proof, coin_id, removals_root, coin.is_some())?;
+    }
+    Ok(())
+}
+
+fn transaction_block_roots(header: &HeaderBlock) -> Result<(Bytes32, Bytes32)> {
+    let tx_block = header
+        .foliage_transaction_block
+        .as_ref()
+        .ok_or(Error::NotTransactionBlock)?;
+    Ok((tx_block.additions_root, tx_block.removals_root))
+}
+
+/// Like `verify_additions()`, against the additions root of the header block.
+pub fn verify_additions_proofs(
+    header: &HeaderBlock,
+    coins: &[(Bytes32, Vec<Coin>)],
+    proofs: Option<&[(Bytes32, Bytes, Option<Bytes>)]>,
+) -> Result<()> {
+    verify_additions(&transaction_block_roots(header)?.0, coins, proofs)
+}
+
+/// Like `verify_removals()`, against the removals root of the header block.
+pub fn verify_removals_proofs(
+    header: &HeaderBlock,
+    coins: &[(Bytes32, Option<Coin>)],
+    proofs: Option<&[(Bytes32, Bytes)]>,
+) -> Result<()> {
+    verify_removals(&transaction_block_roots(header)?.1, coins, proofs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus_constants::TEST_CONSTANTS;
+    use crate::gen::additions_and_removals::additions_and_removals;
+    use crate::gen::flags::ALLOW_BACKREFS;
+    use crate::merkle_tree::MerkleSet;
+    use chia_protocol::Amount;
+    use std::fs::read_to_string;
+
+    // the additions (grouped by puzzle hash) and removals of a real mainnet
+    // block
+    fn mainnet_block() -> (BTreeMap<Bytes32, Vec<Coin>>, Vec<Coin>) {
+        let test_file =
+            read_to_string("../../generator-tests/block-834768.txt").expect("test file not found");
+        let (generator, _) = test_file.split_once('\n').expect("invalid test file");
+        let generator = hex::decode(generator).expect("invalid hex encoded generator");
+        let (additions, removals) = additions_and_removals(
+            &generator,
+            Vec::<Vec<u8>>::new(),
+            ALLOW_BACKREFS,
+            &TEST_CONSTANTS,
+        )
+        .expect("additions_and_removals()");
+
+        let mut by_puzzle_hash = BTreeMap::<Bytes32, Vec<Coin>>::new();
+        for (coin, _) in additions {
+            by_puzzle_hash
+                .entry(coin.puzzle_hash)
+                .or_default()
+                .push(coin);
+        }
+        assert!(by_puzzle_hash.len() > 1);
+        assert!(removals.len() > 1);
+        (by_puzzle_hash, removals)
+    }
+
+    // builds the merkle sets the same way the full node does
+    fn additions_set(additions: &BTreeMap<Bytes32, Vec<Coin>>) -> MerkleSet {
+        let mut leafs = Vec::<[u8; 32]>::new();
+        for (puzzle_hash, coins) in additions {
+            let mut ids: Vec<Bytes32> = coins.iter().map(Coin::coin_id).collect();
+            leafs.push(puzzle_hash.to_bytes());
+            leafs.push(hash_coin_ids(&mut ids).to_bytes());
+        }
+        MerkleSet::from_leafs(&mut leafs)
+    }
+
+    fn removals_set(removals: &[Coin]) -> MerkleSet {
+        let mut leafs: Vec<[u8; 32]> = removals.iter().map(|c| c.coin_id().to_bytes()).collect();
+        MerkleSet::from_leafs(&mut leafs)
+    }
+
+    fn proof(set: &MerkleSet, item: &Bytes32) -> Bytes {
+        set.generate_proof(&item.to_bytes()).unwrap().1.into()
+    }
+
+    fn sha256(buf: &[u8]) -> Bytes32 {
+        let mut ctx = Sha256::new();
+        ctx.update(buf);
+        Bytes32::new(ctx.finalize())
+    }
+
+    #[test]
+    fn test_hash_coin_ids() {
+        let a = Bytes32::new([1; 32]);
+        let b = Bytes32::new([2; 32]);
+        assert_eq!(hash_coin_ids(&mut [a]), sha256(&[1; 32]));
+        let mut expect = [2_u8; 64];
+        expect[32..].copy_from_slice(&[1; 32]);
+        assert_eq!(hash_coin_ids(&mut [a, b]), sha256(&expect));
+        assert_eq!(hash_coin_ids(&mut [b, a]), sha256(&expect));
+    }
+
+    #[test]
+    fn test_coins_to_addition_root() {
+        let (additions, _) = mainnet_block();
+        let root = Bytes32::new(additions_set(&additions).get_root());
+        let mut coins: Vec<Coin> = additions.into_values().flatten().collect();
+        assert_eq!(coins_to_addition_root(coins.iter().copied()), root);
+        // the order of the coins doesn't matter
+        coins.reverse();
+        assert_eq!(coins_to_addition_root(coins), root);
+        assert_eq!(
+            coins_to_addition_root(Vec::<Coin>::new()),
+            Bytes32::new([0; 32])
+        );
+    }
+
+    #[test]
+    fn test_coin_ids_to_merkle_root() {
+        let (_, removals) = mainnet_block();
+        let root = Bytes32::new(removals_set(&removals).get_root());
+        let mut ids: Vec<Bytes32> = removals.iter().map(Coin::coin_id).collect();
+        ids.sort_unstable();
+        assert_eq!(coin_ids_to_merkle_root(ids.iter().copied()), Ok(root));
+
+        // the same thing, a page at a time
+        let mut builder = MerkleSetBuilder::new();
+        for page in ids.chunks(7) {
+            for id in page {
+                builder.push(id.to_bytes()).unwrap();
+            }
+        }
+        assert_eq!(builder.finalize(), root);
+
+        ids.swap(0, 1);
+        assert_eq!(
+            coin_ids_to_merkle_root(ids.iter().copied()),
+            Err(Error::MerkleSetLeafOutOfOrder(ids[1]))
+        );
+    }
+
+    #[test]
+    fn test_verify_all_additions() {
+        let (additions, _) = mainnet_block();
+        let root = Bytes32::new(additions_set(&additions).get_root());
+        let mut coins: Vec<(Bytes32, Vec<Coin>)> = additions.into_iter().collect();
+        verify_additions(&root, &coins, None).unwrap();
+
+        // a missing coin
+        coins[0].1.pop();
+        assert_eq!(
+            verify_additions(&root, &coins, None),
+            Err(Error::AdditionsRootMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_additions_proofs() {
+        let (additions, _) = mainnet_block();
+        let set = additions_set(&additions);
+        let root = Bytes32::new(set.get_root());
+
+        let mut coins = Vec::<(Bytes32, Vec<Coin>)>::new();
+        let mut proofs = Vec::<(Bytes32, Bytes, Option<Bytes>)>::new();
+        for (puzzle_hash, bucket) in additions.iter().take(3) {
+            let mut ids: Vec<Bytes32> = bucket.iter().map(Coin::coin_id).collect();
+            coins.push((*puzzle_hash, bucket.clone()));
+            proofs.push((
+                *puzzle_hash,
+                proof(&set, puzzle_hash),
+                Some(proof(&set, &hash_coin_ids(&mut ids))),
+            ));
+        }
+        // a puzzle hash without any coins in the block
+        let missing = Bytes32::new([0x42; 32]);
+        coins.push((missing, vec![]));
+        proofs.push((missing, proof(&set, &missing), None));
+
+        verify_additions(&root, &coins, Some(proofs.as_slice())).unwrap();
+
+        // claiming there are no coins for a puzzle hash that has some
+        let mut bad_coins = coins.clone();
+        bad_coins[0].1.clear();
+        assert!(matches!(
+            verify_additions(&root, &bad_coins, Some(proofs.as_slice())),
+            Err(Error::InvalidMerkleProof(_))
+        ));
+
+        // a coin that wasn't created in the block
+        let mut bad_coins = coins.clone();
+        let extra = Coin::new(Bytes32::new([7; 32]), bad_coins[0].0, Amount::MOJO);
+        bad_coins[0].1.push(extra);
+        assert!(matches!(
+            verify_additions(&root, &bad_coins, Some(proofs.as_slice())),
+            Err(Error::InvalidMerkleProof(_))
+        ));
+
+        // a coin in the wrong bucket
+        let mut bad_coins = coins.clone();
+        let coin = bad_coins[1].1[0];
+        bad_coins[0].1.push(coin);
+        assert_eq!(
+            verify_additions(&root, &bad_coins, Some(proofs.as_slice())),
+            Err(Error::PuzzleHashMismatch)
+        );
+
+        // a missing coin list proof
+        let mut bad_proofs = proofs.clone();
+        bad_proofs[0].2 = None;
+        assert!(matches!(
+            verify_additions(&root, &coins, Some(bad_proofs.as_slice())),
+            Err(Error::InvalidMerkleProof(_))
+        ));
+
+        // proofs against a different root
+        assert!(matches!(
+            verify_additions(&Bytes32::new([1; 32]), &coins, Some(proofs.as_slice())),
+            Err(Error::InvalidMerkleProof(_))
+        ));
+
+        assert_eq!(
+            verify_additions(&root, &coins[1..], Some(proofs.as_slice())),
+            Err(Error::ProofCountMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_all_removals() {
+        let (_, removals) = mainnet_block();
+        let root = Bytes32::new(removals_set(&removals).get_root());
+        let mut coins: Vec<(Bytes32, Option<Coin>)> =
+            removals.iter().map(|c| (c.coin_id(), Some(*c))).collect();
+        verify_removals(&root, &coins, None).unwrap();
+
+        coins.pop();
+        assert_eq!(
+            verify_removals(&root, &coins, None),
+            Err(Error::RemovalsRootMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_removals_proofs() {
+        let (_, removals) = mainnet_block();
+        let set = removals_set(&removals);
+        let root = Bytes32::new(set.get_root());
+
+        let spent = removals[0];
+        let unspent = Coin::new(Bytes32::new([1; 32]), Bytes32::new([2; 32]), Amount::MOJO);
+        let coins = [(spent.coin_id(), Some(spent)), (unspent.coin_id(), None)];
+        let proofs = [
+            (spent.coin_id(), proof(&set, &spent.coin_id())),
+            (unspent.coin_id(), proof(&set, &unspent.coin_id())),
+        ];
+        verify_removals(&root, &coins, Some(proofs.as_slice())).unwrap();
+
+        // claiming a spent coin wasn't spent, and the other way around
+        let mut bad_coins = coins;
+        bad_coins[0].1 = None;
+        assert_eq!(
+            verify_removals(&root, &bad_coins, Some(proofs.as_slice())),
+            Err(Error::InvalidMerkleProof(spent.coin_id()))
+        );
+        let mut bad_coins = coins;
+        bad_coins[1].1 = Some(unspent);
+        assert_eq!(
+            verify_removals(&root, &bad_coins, Some(proofs.as_slice())),
+            Err(Error::InvalidMerkleProof(unspent.coin_id()))
+        );
+
+        // a coin that doesn't match its ID
+        let mut bad_coins = coins;
+        bad_coins[0].1 = Some(unspent);
+        assert_eq!(
+            verify_removals(&root, &bad_coins, Some(proofs.as_slice())),
+            Err(Error::CoinMismatch)
+        );
+
+        // proofs in the wrong order
+        let swapped = [proofs[1].clone(), proofs[0].clone()];
+        assert_eq!(
+            verify_removals(&root, &coins, Some(swapped.as_slice())),
+            Err(Error::CoinMismatch)
+        );
+    }
+}
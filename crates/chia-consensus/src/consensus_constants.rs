@@ -1,9 +1,8 @@
-use chia_protocol::Bytes32;
+use chia_protocol::{bytes32, Bytes32};
 use chia_streamable_macro::streamable;
 
 #[cfg(feature = "py-bindings")]
 use chia_py_streamable_macro::{PyGetters, PyJsonDict, PyStreamable};
-use hex_literal::hex;
 
 #[cfg_attr(
     feature = "py-bindings",
@@ -156,36 +155,34 @@ pub const TEST_CONSTANTS: ConsensusConstants = ConsensusConstants {
     num_sp_intervals_extra: 3,
     max_future_time2: 2 * 60,
     number_of_timestamps: 11,
-    genesis_challenge: Bytes32::new(hex!(
+    genesis_challenge: bytes32!("ccd5bb71183532bff220ba46c268991a3ff07eb358e8255a65c30a2dce0e5fbb"),
+    agg_sig_me_additional_data: bytes32!(
         "ccd5bb71183532bff220ba46c268991a3ff07eb358e8255a65c30a2dce0e5fbb"
-    )),
-    agg_sig_me_additional_data: Bytes32::new(hex!(
-        "ccd5bb71183532bff220ba46c268991a3ff07eb358e8255a65c30a2dce0e5fbb"
-    )),
-    agg_sig_parent_additional_data: Bytes32::new(hex!(
+    ),
+    agg_sig_parent_additional_data: bytes32!(
         "baf5d69c647c91966170302d18521b0a85663433d161e72c826ed08677b53a74"
-    )),
-    agg_sig_puzzle_additional_data: Bytes32::new(hex!(
+    ),
+    agg_sig_puzzle_additional_data: bytes32!(
         "284fa2ef486c7a41cc29fc99c9d08376161e93dd37817edb8219f42dca7592c4"
-    )),
-    agg_sig_amount_additional_data: Bytes32::new(hex!(
+    ),
+    agg_sig_amount_additional_data: bytes32!(
         "cda186a9cd030f7a130fae45005e81cae7a90e0fa205b75f6aebc0d598e0348e"
-    )),
-    agg_sig_puzzle_amount_additional_data: Bytes32::new(hex!(
+    ),
+    agg_sig_puzzle_amount_additional_data: bytes32!(
         "0f7d90dff0613e6901e24dae59f1e690f18b8f5fbdcf1bb192ac9deaf7de22ad"
-    )),
-    agg_sig_parent_amount_additional_data: Bytes32::new(hex!(
+    ),
+    agg_sig_parent_amount_additional_data: bytes32!(
         "585796bd90bb553c0430b87027ffee08d88aba0162c6e1abbbcc6b583f2ae7f9"
-    )),
-    agg_sig_parent_puzzle_additional_data: Bytes32::new(hex!(
+    ),
+    agg_sig_parent_puzzle_additional_data: bytes32!(
         "2ebfdae17b29d83bae476a25ea06f0c4bd57298faddbbc3ec5ad29b9b86ce5df"
-    )),
-    genesis_pre_farm_pool_puzzle_hash: Bytes32::new(hex!(
+    ),
+    genesis_pre_farm_pool_puzzle_hash: bytes32!(
         "d23da14695a188ae5708dd152263c4db883eb27edeb936178d4d988b8f3ce5fc"
-    )),
-    genesis_pre_farm_farmer_puzzle_hash: Bytes32::new(hex!(
+    ),
+    genesis_pre_farm_farmer_puzzle_hash: bytes32!(
         "3d8765d3a597ec1d99663f6c9816d915b9f68613ac94009884c4addaefcce6af"
-    )),
+    ),
     max_vdf_witness_size: 64,
     mempool_block_buffer: 10,
     max_coin_amount: u64::MAX,
@@ -1,4 +1,5 @@
 use crate::gen::validation_error::ValidationErr;
+use chia_protocol::Bytes32;
 use clvm_traits::{FromClvmError, ToClvmError};
 use clvmr::reduction::EvalErr;
 use thiserror::Error;
@@ -47,6 +48,84 @@ pub enum Error {
     #[error("expected lineage proof, found eve proof")]
     ExpectedLineageProof,
 
+    #[error("signage point index {0} too high")]
+    InvalidSignagePointIndex(u8),
+
+    #[error("sub slot iters {0} is not a multiple of the number of signage points")]
+    InvalidSubSlotIters(u64),
+
+    #[error("invalid sp iters {sp_iters} for sub slot iters {sub_slot_iters}")]
+    InvalidSpIters { sp_iters: u64, sub_slot_iters: u64 },
+
+    #[error("required iters {required_iters} is not below the sp interval iters {sp_interval_iters} or not > 0")]
+    InvalidRequiredIters {
+        required_iters: u64,
+        sp_interval_iters: u64,
+    },
+
+    #[error("invalid plot size {0}")]
+    InvalidPlotSize(u8),
+
+    #[error("iterations overflow")]
+    IterationsOverflow,
+
+    #[error("not a transaction block")]
+    NotTransactionBlock,
+
+    #[error("additions root mismatch")]
+    AdditionsRootMismatch,
+
+    #[error("removals root mismatch")]
+    RemovalsRootMismatch,
+
+    #[error("invalid merkle proof for {0}")]
+    InvalidMerkleProof(Bytes32),
+
+    #[error("number of proofs doesn't match the number of items")]
+    ProofCountMismatch,
+
+    #[error("invalid transactions filter")]
+    InvalidTransactionsFilter,
+
+    #[error("{0} is missing from the transactions filter")]
+    MissingFromTransactionsFilter(Bytes32),
+
+    #[error("invalid network config: {0}")]
+    InvalidNetworkConfig(String),
+
+    #[error("unknown network {0}")]
+    UnknownNetwork(String),
+
+    #[error("sub-slot challenge {found} doesn't match the previous challenge {expected}")]
+    SubSlotChallengeMismatch { expected: Bytes32, found: Bytes32 },
+
+    #[error("sub-slot deficit {found} doesn't match the expected deficit {expected}")]
+    InvalidDeficit { expected: u8, found: u8 },
+
+    #[error("reward chain sub-slot has the wrong challenge chain hash")]
+    InvalidChallengeSlotHashRc,
+
+    #[error("reward chain sub-slot has the wrong infused challenge chain hash")]
+    InvalidIccHashRc,
+
+    #[error("challenge chain sub-slot has the wrong infused challenge chain hash")]
+    InvalidIccHashCc,
+
+    #[error("invalid {0} sub-slot VDF")]
+    InvalidSubSlotVdf(&'static str),
+
+    #[error("the block at height {0} needs the record of the previous block")]
+    MissingPrevBlockRecord(u32),
+
+    #[error("the previous block record {found} doesn't match the previous block hash {expected}")]
+    PrevBlockMismatch { expected: Bytes32, found: Bytes32 },
+
+    #[error("sub-epoch summary doesn't match the hash in the block")]
+    InvalidSubEpochSummary,
+
+    #[error("merkle set leaf {0} is less than the previous leaf")]
+    MerkleSetLeafOutOfOrder(Bytes32),
+
     #[error("{0}")]
     Custom(String),
 }
@@ -1,3 +1,4 @@
+use crate::allocator::{checkpoint, restore};
 use crate::error::{Error, Result};
 use chia_protocol::Bytes32;
 use chia_protocol::Coin;
@@ -8,7 +9,7 @@ use chia_puzzles::Proof;
 use clvm_traits::{FromClvm, ToClvm};
 use clvm_utils::CurriedProgram;
 use clvm_utils::TreeHash;
-use clvm_utils::{tree_hash, tree_hash_atom, tree_hash_pair};
+use clvm_utils::{tree_hash, tree_hash_atom, tree_hash_pair, NIL_TREE_HASH};
 use clvmr::allocator::{Allocator, NodePtr};
 
 // TODO: replace this with a generic function to compute the hash of curried
@@ -21,7 +22,7 @@ fn curry_single_arg(arg_hash: TreeHash, rest: TreeHash) -> TreeHash {
         tree_hash_atom(&[OP_CONS]),
         tree_hash_pair(
             tree_hash_pair(tree_hash_atom(&[OP_QUOTE]), arg_hash),
-            tree_hash_pair(rest, tree_hash_atom(&[])),
+            tree_hash_pair(rest, NIL_TREE_HASH),
         ),
     )
 }
@@ -46,7 +47,7 @@ fn curry_and_treehash(inner_puzzle_hash: &Bytes32, singleton_struct: &SingletonS
                 tree_hash_atom(&[OP_QUOTE]),
                 singleton_struct.mod_hash.into(),
             ),
-            tree_hash_pair(args_hash, tree_hash_atom(&[])),
+            tree_hash_pair(args_hash, NIL_TREE_HASH),
         ),
     )
     .into()
@@ -67,7 +68,10 @@ pub fn fast_forward_singleton(
 ) -> Result<NodePtr> {
     // a coin with an even amount is not a valid singleton
     // as defined by singleton_top_layer_v1_1.clsp
-    if (coin.amount & 1) == 0 || (new_parent.amount & 1) == 0 || (new_coin.amount & 1) == 0 {
+    if (u64::from(coin.amount) & 1) == 0
+        || (u64::from(new_parent.amount) & 1) == 0
+        || (u64::from(new_coin.amount) & 1) == 0
+    {
         return Err(Error::CoinAmountEven);
     }
 
@@ -117,7 +121,7 @@ pub fn fast_forward_singleton(
     let parent_coin = Coin {
         parent_coin_info: lineage_proof.parent_parent_coin_info,
         puzzle_hash: parent_puzzle_hash,
-        amount: lineage_proof.parent_amount,
+        amount: lineage_proof.parent_amount.into(),
     };
 
     if parent_coin.coin_id() != coin.parent_coin_info {
@@ -140,8 +144,8 @@ pub fn fast_forward_singleton(
 
     // update the solution to use the new parent coin's information
     lineage_proof.parent_parent_coin_info = new_parent.parent_coin_info;
-    lineage_proof.parent_amount = new_parent.amount;
-    new_solution.amount = new_coin.amount;
+    lineage_proof.parent_amount = new_parent.amount.into();
+    new_solution.amount = new_coin.amount.into();
 
     let expected_new_parent = new_parent.coin_id();
 
@@ -152,6 +156,33 @@ pub fn fast_forward_singleton(
     Ok(new_solution.to_clvm(a)?)
 }
 
+// returns whether the spend of the specified singleton coin can be
+// fast-forwarded, by attempting to rebase it onto a dummy parent coin. The
+// new solution is thrown away, and so is everything else allocated along the
+// way, leaving the allocator as it was.
+pub fn supports_fast_forward(
+    a: &mut Allocator,
+    puzzle: NodePtr,
+    solution: NodePtr,
+    coin: &Coin,
+) -> bool {
+    let new_parent = Coin {
+        parent_coin_info: [0_u8; 32].into(),
+        puzzle_hash: coin.puzzle_hash,
+        amount: coin.amount,
+    };
+    let new_coin = Coin {
+        parent_coin_info: new_parent.coin_id(),
+        puzzle_hash: coin.puzzle_hash,
+        amount: coin.amount,
+    };
+
+    let cp = checkpoint(a);
+    let ret = fast_forward_singleton(a, puzzle, solution, coin, &new_coin, &new_parent).is_ok();
+    restore(a, &cp);
+    ret
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,6 +193,7 @@ mod tests {
     };
     use crate::gen::spend_visitor::SpendVisitor;
     use crate::gen::validation_error::ValidationErr;
+    use chia_protocol::Amount;
     use chia_protocol::Bytes32;
     use chia_protocol::Coin;
     use chia_protocol::CoinSpend;
@@ -183,7 +215,7 @@ mod tests {
         puzzle: &[u8],
         solution: &[u8],
         parent_id: &[u8],
-        amount: u64,
+        amount: Amount,
     ) -> core::result::Result<SpendBundleConditions, ValidationErr> {
         let puzzle = node_from_bytes(a, puzzle)?;
         let solution = node_from_bytes(a, solution)?;
@@ -194,7 +226,7 @@ mod tests {
             run_program(a, &dialect, puzzle, solution, max_cost)?;
 
         let mut ret = SpendBundleConditions {
-            removal_amount: amount as u128,
+            removal_amount: u128::from(amount),
             ..Default::default()
         };
         let mut state = ParseState::default();
@@ -267,7 +299,7 @@ mod tests {
             amount: if prev_amount == 0 {
                 spend.coin.amount
             } else {
-                prev_amount
+                prev_amount.into()
             },
         };
 
@@ -277,7 +309,7 @@ mod tests {
             amount: if new_amount == 0 {
                 spend.coin.amount
             } else {
-                new_amount
+                new_amount.into()
             },
         };
 
@@ -316,6 +348,46 @@ mod tests {
         assert!(conditions1.spends[0].create_coin == conditions2.spends[0].create_coin);
     }
 
+    #[rstest]
+    #[case("e3c0", None, true)]
+    #[case("bb13", None, true)]
+    // a coin with an even amount is not a singleton
+    #[case("e3c0", Some(2), false)]
+    fn test_supports_fast_forward(
+        #[case] spend_file: &str,
+        #[case] amount: Option<u64>,
+        #[case] expected: bool,
+    ) {
+        let spend_bytes =
+            fs::read(format!("../../ff-tests/{spend_file}.spend")).expect("read file");
+        let mut spend = CoinSpend::from_bytes(&spend_bytes).expect("parse CoinSpend");
+        if let Some(amount) = amount {
+            spend.coin.amount = Amount::new(amount);
+        }
+
+        let mut a = Allocator::new_limited(500_000_000);
+        let puzzle = spend.puzzle_reveal.to_clvm(&mut a).expect("to_clvm");
+        let solution = spend.solution.to_clvm(&mut a).expect("to_clvm");
+        let puzzle_hash = tree_hash(&a, puzzle);
+        let solution_hash = tree_hash(&a, solution);
+        let atoms = a.atom_count();
+        let pairs = a.pair_count();
+        let heap_size = a.heap_size();
+
+        assert_eq!(
+            supports_fast_forward(&mut a, puzzle, solution, &spend.coin),
+            expected
+        );
+
+        // the speculative fast-forward didn't leave anything behind, and the
+        // puzzle and solution are still intact
+        assert_eq!(a.atom_count(), atoms);
+        assert_eq!(a.pair_count(), pairs);
+        assert_eq!(a.heap_size(), heap_size);
+        assert_eq!(tree_hash(&a, puzzle), puzzle_hash);
+        assert_eq!(tree_hash(&a, solution), solution_hash);
+    }
+
     #[allow(clippy::needless_pass_by_value)]
     fn run_ff_test(
         mutate: fn(&mut Allocator, &mut Coin, &mut Coin, &mut Coin, &mut Vec<u8>, &mut Vec<u8>),
@@ -375,21 +447,21 @@ mod tests {
     fn test_even_amount() {
         run_ff_test(
             |_a, coin, _new_coin, _new_parent, _puzzle, _solution| {
-                coin.amount = 2;
+                coin.amount = Amount::new(2);
             },
             Error::CoinAmountEven,
         );
 
         run_ff_test(
             |_a, _coin, new_coin, _new_parent, _puzzle, _solution| {
-                new_coin.amount = 2;
+                new_coin.amount = Amount::new(2);
             },
             Error::CoinAmountEven,
         );
 
         run_ff_test(
             |_a, _coin, _new_coin, new_parent, _puzzle, _solution| {
-                new_parent.amount = 2;
+                new_parent.amount = Amount::new(2);
             },
             Error::CoinAmountEven,
         );
@@ -399,7 +471,7 @@ mod tests {
     fn test_amount_mismatch() {
         run_ff_test(
             |_a, coin, _new_coin, _new_parent, _puzzle, _solution| {
-                coin.amount = 3;
+                coin.amount = Amount::new(3);
             },
             Error::CoinAmountMismatch,
         );
@@ -519,7 +591,7 @@ mod tests {
                 *new_parent = Coin {
                     parent_coin_info: lineage_proof.parent_parent_coin_info,
                     puzzle_hash: parent_puzzle_hash,
-                    amount: lineage_proof.parent_amount,
+                    amount: lineage_proof.parent_amount.into(),
                 };
 
                 *solution = serialize_solution(a, &new_solution);
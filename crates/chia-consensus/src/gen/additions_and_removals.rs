@@ -3,34 +3,75 @@ use crate::gen::run_block_generator::subtract_cost;
 use chia_protocol::Coin;
 
 use crate::allocator::make_allocator;
+use crate::block_rewards::{
+    calculate_base_farmer_reward, calculate_pool_reward, create_farmer_coin, create_pool_coin,
+};
 use crate::consensus_constants::ConsensusConstants;
 use crate::gen::validation_error::{atom, first, next, rest, ErrorCode, ValidationErr};
-use chia_protocol::{Bytes, Bytes32};
+use chia_protocol::{Amount, Bytes, Bytes32};
+use chia_streamable_macro::Streamable;
 use clvm_traits::FromClvm;
 use clvm_utils::{tree_hash_cached, TreeHash};
-use clvmr::allocator::NodePtr;
+use clvmr::allocator::{Allocator, NodePtr, SExp};
 use clvmr::chia_dialect::ChiaDialect;
 use clvmr::reduction::Reduction;
 use clvmr::run_program::run_program;
 use clvmr::serde::node_from_bytes_backrefs_record;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "py-bindings")]
+use chia_py_streamable_macro::{PyJsonDict, PyStreamable};
+
+/// An addition from a block, along with all of its memos.
+#[derive(Streamable, Hash, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "py-bindings",
+    pyo3::pyclass(name = "CoinAddition", get_all, frozen),
+    derive(PyJsonDict, PyStreamable)
+)]
+pub struct CoinAddition {
+    pub coin: Coin,
+    // the first memo, if it's at most 32 bytes. This is the same hint as
+    // additions_and_removals() returns
+    pub hint: Option<Bytes>,
+    // every atom in the memo list of the CREATE_COIN condition, in order.
+    // Items that are pairs are skipped
+    pub memos: Vec<Bytes>,
+}
 
-/// Run a *trusted* block generator and return its additions and removals. This
-/// function does not validate the block, it is assumed to be valid.
-/// The returned vectors are additions (with hints) and removals.
-#[allow(clippy::type_complexity)]
-pub fn additions_and_removals<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
+/// All coins created and spent by a transaction block.
+#[derive(Streamable, Hash, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "py-bindings",
+    pyo3::pyclass(name = "BlockCoinReport", get_all, frozen),
+    derive(PyJsonDict, PyStreamable)
+)]
+pub struct BlockCoinReport {
+    pub height: u32,
+    pub additions: Vec<CoinAddition>,
+    pub removals: Vec<Coin>,
+    // the pool and farmer reward coins. The fees are included in the farmer
+    // coin
+    pub reward_coins: Vec<Coin>,
+    // the sum of all removals minus the sum of all additions
+    pub fees: u64,
+}
+
+// runs a *trusted* block generator and calls add_removal() for every spent
+// coin and add_addition() for every CREATE_COIN condition, with the coin and
+// the list of memos (which may be NIL)
+fn visit_coins<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
     program: &[u8],
     block_refs: I,
     flags: u32,
     constants: &ConsensusConstants,
-) -> Result<(Vec<(Coin, Option<Bytes>)>, Vec<Coin>), ValidationErr>
+    mut add_removal: impl FnMut(Coin),
+    mut add_addition: impl FnMut(&Allocator, Coin, NodePtr),
+) -> Result<(), ValidationErr>
 where
     <I as IntoIterator>::IntoIter: DoubleEndedIterator,
 {
     let mut a = make_allocator(flags);
-    let mut additions = Vec::<(Coin, Option<Bytes>)>::new();
-    let mut removals = Vec::<Coin>::new();
 
     let mut cost_left = constants.max_block_cost_clvm;
 
@@ -54,7 +95,7 @@ where
         all_spends = tail;
         // process the spend
         let (parent_id, (puzzle, (amount, (solution, _spend_level_extra)))) =
-            <(Bytes32, (NodePtr, (u64, (NodePtr, NodePtr))))>::from_clvm(&a, spend)
+            <(Bytes32, (NodePtr, (Amount, (NodePtr, NodePtr))))>::from_clvm(&a, spend)
                 .map_err(|_| ValidationErr(spend, ErrorCode::InvalidCondition))?;
 
         let Reduction(clvm_cost, mut iter) =
@@ -70,7 +111,7 @@ where
             amount,
         };
 
-        removals.push(coin);
+        add_removal(coin);
         let spend_id = coin.coin_id();
 
         while let Some((mut c, next)) = next(&a, iter)? {
@@ -87,7 +128,7 @@ where
             }
             c = rest(&a, c)?;
 
-            let (puzzle_hash, (amount, hint)) = <(Bytes32, (u64, NodePtr))>::from_clvm(&a, c)
+            let (puzzle_hash, (amount, rest)) = <(Bytes32, (Amount, NodePtr))>::from_clvm(&a, c)
                 .map_err(|_| ValidationErr(c, ErrorCode::InvalidCondition))?;
 
             let coin = Coin {
@@ -96,27 +137,208 @@ where
                 amount,
             };
 
-            // there was another item in the list
-            // the item was a cons-box, and params is the left-hand
-            // side, the list element
-
-            let hint =
-                if let Ok(((hint, _), _)) = <((Bytes, NodePtr), NodePtr)>::from_clvm(&a, hint) {
-                    if hint.len() <= 32 {
-                        Some(hint)
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                };
-            additions.push((coin, hint));
+            // the memo list is the optional third argument
+            let memos = a.next(rest).map_or(NodePtr::NIL, |(memos, _)| memos);
+            add_addition(&a, coin, memos);
         }
     }
+    Ok(())
+}
+
+/// Run a *trusted* block generator and return its additions and removals. This
+/// function does not validate the block, it is assumed to be valid.
+/// The returned vectors are additions (with hints) and removals.
+#[allow(clippy::type_complexity)]
+pub fn additions_and_removals<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
+    program: &[u8],
+    block_refs: I,
+    flags: u32,
+    constants: &ConsensusConstants,
+) -> Result<(Vec<(Coin, Option<Bytes>)>, Vec<Coin>), ValidationErr>
+where
+    <I as IntoIterator>::IntoIter: DoubleEndedIterator,
+{
+    let mut additions = Vec::<(Coin, Option<Bytes>)>::new();
+    let mut removals = Vec::<Coin>::new();
+
+    visit_coins(
+        program,
+        block_refs,
+        flags,
+        constants,
+        |coin| removals.push(coin),
+        |a, coin, memos| additions.push((coin, parse_hint(a, memos))),
+    )?;
 
     Ok((additions, removals))
 }
 
+/// Like `additions_and_removals()`, but only returns the additions whose
+/// puzzle hash is in `watch_puzzle_hashes` or whose hint is in `watch_hints`,
+/// and the removals whose puzzle hash is in `watch_puzzle_hashes`. The third
+/// element of the result is the fee of the whole block, including the coins
+/// that were filtered out.
+/// Coins are filtered as they are found, so hints of the coins that aren't
+/// returned are never copied out of the allocator.
+#[allow(clippy::type_complexity)]
+pub fn additions_and_removals_filtered<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
+    program: &[u8],
+    block_refs: I,
+    flags: u32,
+    constants: &ConsensusConstants,
+    watch_puzzle_hashes: &HashSet<Bytes32>,
+    watch_hints: &HashSet<Bytes32>,
+) -> Result<(Vec<(Coin, Option<Bytes>)>, Vec<Coin>, u64), ValidationErr>
+where
+    <I as IntoIterator>::IntoIter: DoubleEndedIterator,
+{
+    let mut additions = Vec::<(Coin, Option<Bytes>)>::new();
+    let mut removals = Vec::<Coin>::new();
+    let mut removal_amount: u128 = 0;
+    let mut addition_amount: u128 = 0;
+
+    visit_coins(
+        program,
+        block_refs,
+        flags,
+        constants,
+        |coin| {
+            removal_amount += u128::from(coin.amount);
+            if watch_puzzle_hashes.contains(&coin.puzzle_hash) {
+                removals.push(coin);
+            }
+        },
+        |a, coin, memos| {
+            addition_amount += u128::from(coin.amount);
+            let hint = hint_atom(a, memos);
+            // only a hint of exactly 32 bytes can be in watch_hints
+            let watched = watch_puzzle_hashes.contains(&coin.puzzle_hash)
+                || hint.is_some_and(|hint| {
+                    Bytes32::try_from(a.atom(hint).as_ref())
+                        .is_ok_and(|hint| watch_hints.contains(&hint))
+                });
+            if watched {
+                additions.push((coin, hint.map(|hint| a.atom(hint).as_ref().into())));
+            }
+        },
+    )?;
+
+    let fees = block_fees(removal_amount, addition_amount)?;
+    Ok((additions, removals, fees))
+}
+
+// the hint is the first memo, if it's an atom of at most 32 bytes
+fn hint_atom(a: &Allocator, memos: NodePtr) -> Option<NodePtr> {
+    let (hint, _) = a.next(memos)?;
+    match a.sexp(hint) {
+        SExp::Atom if a.atom_len(hint) <= 32 => Some(hint),
+        _ => None,
+    }
+}
+
+fn parse_hint(a: &Allocator, memos: NodePtr) -> Option<Bytes> {
+    hint_atom(a, memos).map(|hint| a.atom(hint).as_ref().into())
+}
+
+// the sum of all removals minus the sum of all additions
+fn block_fees(removal_amount: u128, addition_amount: u128) -> Result<u64, ValidationErr> {
+    removal_amount
+        .checked_sub(addition_amount)
+        .and_then(|fees| u64::try_from(fees).ok())
+        .ok_or(ValidationErr(
+            NodePtr::NIL,
+            ErrorCode::InvalidBlockFeeAmount,
+        ))
+}
+
+// every atom in the memo list
+fn parse_memos(a: &Allocator, mut memos: NodePtr) -> Vec<Bytes> {
+    let mut ret = Vec::new();
+    while let Some((memo, tail)) = a.next(memos) {
+        memos = tail;
+        if let SExp::Atom = a.sexp(memo) {
+            ret.push(a.atom(memo).as_ref().into());
+        }
+    }
+    ret
+}
+
+/// Run a *trusted* block generator and return a report of all coins it
+/// created and spent, including the full memo list of each addition. Like
+/// `additions_and_removals()`, this does not validate the block.
+/// The reward coins are the ones `create_pool_coin()` and
+/// `create_farmer_coin()` compute for `height`, with this block's fees added
+/// to the farmer coin. This is meant for indexing deposits by hint, where the
+/// report for each block can be stored and compared across reorgs.
+pub fn block_coin_report<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
+    program: &[u8],
+    block_refs: I,
+    flags: u32,
+    height: u32,
+    farmer_puzzle_hash: Bytes32,
+    pool_puzzle_hash: Bytes32,
+    constants: &ConsensusConstants,
+) -> Result<BlockCoinReport, ValidationErr>
+where
+    <I as IntoIterator>::IntoIter: DoubleEndedIterator,
+{
+    let mut additions = Vec::<CoinAddition>::new();
+    let mut removals = Vec::<Coin>::new();
+    let mut removal_amount: u128 = 0;
+    let mut addition_amount: u128 = 0;
+
+    visit_coins(
+        program,
+        block_refs,
+        flags,
+        constants,
+        |coin| {
+            removal_amount += u128::from(coin.amount);
+            removals.push(coin);
+        },
+        |a, coin, memos| {
+            addition_amount += u128::from(coin.amount);
+            additions.push(CoinAddition {
+                coin,
+                hint: parse_hint(a, memos),
+                memos: parse_memos(a, memos),
+            });
+        },
+    )?;
+
+    let fees = block_fees(removal_amount, addition_amount)?;
+
+    let farmer_reward = calculate_base_farmer_reward(height)
+        .checked_add(fees)
+        .ok_or(ValidationErr(
+            NodePtr::NIL,
+            ErrorCode::InvalidBlockFeeAmount,
+        ))?;
+
+    let reward_coins = vec![
+        create_pool_coin(
+            height,
+            pool_puzzle_hash,
+            calculate_pool_reward(height),
+            &constants.genesis_challenge,
+        ),
+        create_farmer_coin(
+            height,
+            farmer_puzzle_hash,
+            farmer_reward,
+            &constants.genesis_challenge,
+        ),
+    ];
+
+    Ok(BlockCoinReport {
+        height,
+        additions,
+        removals,
+        reward_coins,
+        fees,
+    })
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -230,5 +452,247 @@ mod test {
         for r in &removals {
             assert!(expect_removals.contains(r));
         }
+
+        // the report must agree with additions_and_removals()
+        let report = block_coin_report(
+            &generator,
+            &block_refs,
+            ALLOW_BACKREFS,
+            1000,
+            Bytes32::new([1; 32]),
+            Bytes32::new([2; 32]),
+            &TEST_CONSTANTS,
+        )
+        .expect("block_coin_report()");
+
+        assert_eq!(report.height, 1000);
+        assert_eq!(report.removals, removals);
+        assert_eq!(report.additions.len(), additions.len());
+        for (add, (coin, hint)) in report.additions.iter().zip(&additions) {
+            assert_eq!(add.coin, *coin);
+            assert_eq!(add.hint, *hint);
+            if let Some(hint) = hint {
+                assert_eq!(add.memos.first(), Some(hint));
+            }
+        }
+
+        let fees = u64::try_from(conds.removal_amount - conds.addition_amount).unwrap();
+        assert_eq!(report.fees, fees);
+
+        // watch every other puzzle hash and hint. The filtered call must
+        // return the same coins as filtering the unfiltered result
+        let watch_puzzle_hashes: HashSet<Bytes32> = additions
+            .iter()
+            .map(|(coin, _)| coin.puzzle_hash)
+            .chain(removals.iter().map(|coin| coin.puzzle_hash))
+            .step_by(2)
+            .collect();
+        let watch_hints: HashSet<Bytes32> = additions
+            .iter()
+            .filter_map(|(_, hint)| hint.as_ref())
+            .filter_map(|hint| Bytes32::try_from(hint.as_ref()).ok())
+            .step_by(2)
+            .collect();
+        let (filtered_additions, filtered_removals, filtered_fees) =
+            additions_and_removals_filtered(
+                &generator,
+                &block_refs,
+                ALLOW_BACKREFS,
+                &TEST_CONSTANTS,
+                &watch_puzzle_hashes,
+                &watch_hints,
+            )
+            .expect("additions_and_removals_filtered()");
+
+        let expect_additions: Vec<_> = additions
+            .iter()
+            .filter(|(coin, hint)| {
+                watch_puzzle_hashes.contains(&coin.puzzle_hash)
+                    || hint
+                        .as_ref()
+                        .and_then(|hint| Bytes32::try_from(hint.as_ref()).ok())
+                        .is_some_and(|hint| watch_hints.contains(&hint))
+            })
+            .cloned()
+            .collect();
+        let expect_removals: Vec<_> = removals
+            .iter()
+            .filter(|coin| watch_puzzle_hashes.contains(&coin.puzzle_hash))
+            .copied()
+            .collect();
+        assert_eq!(filtered_additions, expect_additions);
+        assert_eq!(filtered_removals, expect_removals);
+        // the fees cover the whole block
+        assert_eq!(filtered_fees, fees);
+
+        // with nothing to watch, nothing is returned
+        let (none_added, none_removed, none_fees) = additions_and_removals_filtered(
+            &generator,
+            &block_refs,
+            ALLOW_BACKREFS,
+            &TEST_CONSTANTS,
+            &HashSet::new(),
+            &HashSet::new(),
+        )
+        .expect("additions_and_removals_filtered()");
+        assert!(none_added.is_empty());
+        assert!(none_removed.is_empty());
+        assert_eq!(none_fees, fees);
+        assert_eq!(
+            report.reward_coins,
+            vec![
+                create_pool_coin(
+                    1000,
+                    Bytes32::new([2; 32]),
+                    calculate_pool_reward(1000),
+                    &TEST_CONSTANTS.genesis_challenge
+                ),
+                create_farmer_coin(
+                    1000,
+                    Bytes32::new([1; 32]),
+                    calculate_base_farmer_reward(1000) + fees,
+                    &TEST_CONSTANTS.genesis_challenge
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_block_coin_report_memos() {
+        use crate::gen::opcodes::CREATE_COIN;
+        use crate::gen::solution_generator::solution_generator;
+        use clvm_traits::ToClvm;
+        use clvm_utils::ONE_TREE_HASH;
+        use clvmr::serde::node_to_bytes;
+
+        let mut a = Allocator::new();
+        // the puzzle "1" returns its solution as the conditions
+        let puzzle_hash: Bytes32 = ONE_TREE_HASH.into();
+        let coin = Coin::new(Bytes32::new([3; 32]), puzzle_hash, Amount::new(1000));
+        let hint = Bytes::from(vec![4; 32]);
+        let long_memo = Bytes::from(vec![5; 33]);
+        let short_memo = Bytes::from(b"deposit #1".to_vec());
+        let op = u16::from(CREATE_COIN);
+
+        // a nested list in the memos
+        let pair = (short_memo.clone(), short_memo.clone())
+            .to_clvm(&mut a)
+            .unwrap();
+        let hint_atom = hint.clone().to_clvm(&mut a).unwrap();
+        let nested = vec![hint_atom, pair].to_clvm(&mut a).unwrap();
+
+        let solution = (
+            // no memos
+            (op, (Bytes32::new([6; 32]), (1_u64, ()))),
+            (
+                // a hint followed by more memos
+                (
+                    op,
+                    (
+                        Bytes32::new([7; 32]),
+                        (
+                            2_u64,
+                            (
+                                vec![hint.clone(), short_memo.clone(), long_memo.clone()],
+                                (),
+                            ),
+                        ),
+                    ),
+                ),
+                (
+                    // the first memo is too long to be a hint
+                    (
+                        op,
+                        (
+                            Bytes32::new([8; 32]),
+                            (3_u64, (vec![long_memo.clone(), hint.clone()], ())),
+                        ),
+                    ),
+                    (
+                        // pairs in the memo list are skipped
+                        (op, (Bytes32::new([9; 32]), (4_u64, (nested, ())))),
+                        (
+                            // the memos aren't a list
+                            (op, (Bytes32::new([10; 32]), (5_u64, (hint.clone(), ())))),
+                            (),
+                        ),
+                    ),
+                ),
+            ),
+        )
+            .to_clvm(&mut a)
+            .unwrap();
+        let solution = node_to_bytes(&a, solution).unwrap();
+
+        let generator = solution_generator([(coin, &[1_u8][..], solution.as_slice())]).unwrap();
+
+        let report = block_coin_report(
+            &generator,
+            Vec::<&[u8]>::new(),
+            0,
+            1,
+            Bytes32::new([1; 32]),
+            Bytes32::new([2; 32]),
+            &TEST_CONSTANTS,
+        )
+        .expect("block_coin_report()");
+
+        assert_eq!(report.removals, vec![coin]);
+        assert_eq!(report.fees, 1000 - 15);
+        assert_eq!(report.reward_coins[1].amount, 250_000_000_000 + 985);
+
+        let coin_id = coin.coin_id();
+        let expected = [
+            (6, 1, None, vec![]),
+            (
+                7,
+                2,
+                Some(hint.clone()),
+                vec![hint.clone(), short_memo.clone(), long_memo.clone()],
+            ),
+            (8, 3, None, vec![long_memo.clone(), hint.clone()]),
+            (9, 4, Some(hint.clone()), vec![hint.clone()]),
+            (10, 5, None, vec![]),
+        ];
+        assert_eq!(report.additions.len(), expected.len());
+        for (add, (ph, amount, expect_hint, expect_memos)) in report.additions.iter().zip(expected)
+        {
+            assert_eq!(
+                add.coin,
+                Coin::new(coin_id, Bytes32::new([ph; 32]), Amount::new(amount))
+            );
+            assert_eq!(add.hint, expect_hint);
+            assert_eq!(add.memos, expect_memos);
+        }
+
+        // the hints must match additions_and_removals()
+        let (additions, _) =
+            additions_and_removals(&generator, Vec::<&[u8]>::new(), 0, &TEST_CONSTANTS)
+                .expect("additions_and_removals()");
+        for (add, (coin, hint)) in report.additions.iter().zip(&additions) {
+            assert_eq!(add.coin, *coin);
+            assert_eq!(add.hint, *hint);
+        }
+
+        // only the additions whose first memo is the hint match it
+        let (filtered, removals, fees) = additions_and_removals_filtered(
+            &generator,
+            Vec::<&[u8]>::new(),
+            0,
+            &TEST_CONSTANTS,
+            &HashSet::from([Bytes32::new([10; 32])]),
+            &HashSet::from([Bytes32::new([4; 32])]),
+        )
+        .expect("additions_and_removals_filtered()");
+        assert_eq!(
+            filtered,
+            vec![
+                additions[1].clone(),
+                additions[3].clone(),
+                additions[4].clone()
+            ]
+        );
+        assert!(removals.is_empty());
+        assert_eq!(fees, 1000 - 15);
     }
 }
@@ -0,0 +1,404 @@
+use crate::gen::validation_error::{ErrorCode, ValidationErr};
+use clvmr::allocator::{Allocator, NodePtr};
+use clvmr::cost::Cost;
+use clvmr::dialect::{Dialect, OperatorSet};
+use clvmr::reduction::{EvalErr, Reduction, Response};
+use clvmr::run_program::run_program;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// The number of conditions parsed between checks of the cancellation token
+/// (in addition to the check at every spend boundary).
+pub const CANCEL_CHECK_INTERVAL: u32 = 256;
+
+/// A handle used to abort block generator execution and condition parsing
+/// from another thread, e.g. when the node is shutting down or an RPC call has
+/// timed out. Cloning the token gives another handle to the same flag. Once
+/// cancelled, a token stays cancelled.
+#[cfg_attr(
+    feature = "py-bindings",
+    pyo3::pyclass(name = "CancellationToken", frozen)
+)]
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pyo3::pymethods]
+impl CancellationToken {
+    #[new]
+    pub fn init() -> Self {
+        Self::new()
+    }
+
+    #[pyo3(name = "cancel")]
+    pub fn py_cancel(&self) {
+        self.cancel();
+    }
+
+    #[pyo3(name = "is_cancelled")]
+    pub fn py_is_cancelled(&self) -> bool {
+        self.is_cancelled()
+    }
+}
+
+/// Fails with `ErrorCode::Cancelled` if `token` is set and has been cancelled.
+pub fn check_cancelled(token: Option<&CancellationToken>) -> Result<(), ValidationErr> {
+    match token {
+        Some(token) if token.is_cancelled() => {
+            Err(ValidationErr(NodePtr::NIL, ErrorCode::Cancelled))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Wraps another dialect and fails every operator call once the token has been
+// cancelled. This is what lets us abort a long running program, rather than
+// having to wait for it to complete.
+struct CancellableDialect<'a, D: Dialect> {
+    dialect: &'a D,
+    token: &'a CancellationToken,
+}
+
+impl<D: Dialect> Dialect for CancellableDialect<'_, D> {
+    fn quote_kw(&self) -> u32 {
+        self.dialect.quote_kw()
+    }
+
+    fn apply_kw(&self) -> u32 {
+        self.dialect.apply_kw()
+    }
+
+    fn softfork_kw(&self) -> u32 {
+        self.dialect.softfork_kw()
+    }
+
+    fn softfork_extension(&self, ext: u32) -> OperatorSet {
+        self.dialect.softfork_extension(ext)
+    }
+
+    fn op(
+        &self,
+        allocator: &mut Allocator,
+        op: NodePtr,
+        args: NodePtr,
+        max_cost: Cost,
+        extensions: OperatorSet,
+    ) -> Response {
+        if self.token.is_cancelled() {
+            return Err(EvalErr(op, "cancelled".to_string()));
+        }
+        self.dialect.op(allocator, op, args, max_cost, extensions)
+    }
+
+    fn allow_unknown_ops(&self) -> bool {
+        self.dialect.allow_unknown_ops()
+    }
+}
+
+/// Like `run_program()`, but if `token` is set, the program is aborted with
+/// `ErrorCode::Cancelled` as soon as the token is cancelled. Without a token,
+/// this is exactly `run_program()`.
+pub fn run_program_cancellable<D: Dialect>(
+    a: &mut Allocator,
+    dialect: &D,
+    program: NodePtr,
+    args: NodePtr,
+    max_cost: Cost,
+    token: Option<&CancellationToken>,
+) -> Result<Reduction, ValidationErr> {
+    let Some(token) = token else {
+        return Ok(run_program(a, dialect, program, args, max_cost)?);
+    };
+    check_cancelled(Some(token))?;
+    let dialect = CancellableDialect { dialect, token };
+    run_program(a, &dialect, program, args, max_cost).map_err(|err| {
+        if token.is_cancelled() {
+            ValidationErr(err.0, ErrorCode::Cancelled)
+        } else {
+            err.into()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus_constants::TEST_CONSTANTS;
+    use crate::gen::conditions::{parse_spends_cancellable, EmptyVisitor};
+    use crate::gen::flags::DONT_VALIDATE_SIGNATURE;
+    use crate::gen::run_block_generator::{run_block_generator2, run_block_generator2_cancellable};
+    use crate::gen::solution_generator::solution_generator;
+    use chia_bls::Signature;
+    use chia_protocol::{Amount, Bytes32, Coin};
+    use clvmr::chia_dialect::ChiaDialect;
+    use clvmr::serde::{node_from_bytes, node_to_bytes};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn list(a: &mut Allocator, items: &[NodePtr]) -> NodePtr {
+        let mut ret = a.nil();
+        for item in items.iter().rev() {
+            ret = a.new_pair(*item, ret).unwrap();
+        }
+        ret
+    }
+
+    fn op(a: &mut Allocator, opcode: u32, args: &[NodePtr]) -> NodePtr {
+        let mut items = vec![a.new_small_number(opcode).unwrap()];
+        items.extend_from_slice(args);
+        list(a, &items)
+    }
+
+    fn quote(a: &mut Allocator, n: NodePtr) -> NodePtr {
+        let q = a.new_small_number(1).unwrap();
+        a.new_pair(q, n).unwrap()
+    }
+
+    // a puzzle that counts down from `iterations` to 0 and then returns no
+    // conditions. It's a cheap way to make a puzzle take a long time to run
+    fn loop_puzzle(iterations: u32) -> Vec<u8> {
+        const OP_A: u32 = 2;
+        const OP_I: u32 = 3;
+        const OP_C: u32 = 4;
+        const OP_SUB: u32 = 17;
+
+        let mut a = Allocator::new();
+        let env = a.new_small_number(1).unwrap();
+        let self_path = a.new_small_number(2).unwrap();
+        let counter = a.new_small_number(5).unwrap();
+
+        // (a (i 5 (q . (a 2 (c 2 (c (- 5 (q . 1)) ())))) (q . (q . ()))) 1)
+        let one = a.new_small_number(1).unwrap();
+        let one = quote(&mut a, one);
+        let decrement = op(&mut a, OP_SUB, &[counter, one]);
+        let args = op(&mut a, OP_C, &[decrement, NodePtr::NIL]);
+        let args = op(&mut a, OP_C, &[self_path, args]);
+        let recurse = op(&mut a, OP_A, &[self_path, args]);
+        let recurse = quote(&mut a, recurse);
+        let done = quote(&mut a, NodePtr::NIL);
+        let done = quote(&mut a, done);
+        let branch = op(&mut a, OP_I, &[counter, recurse, done]);
+        let body = op(&mut a, OP_A, &[branch, env]);
+
+        // (a (q . body) (c (q . body) (c (q . iterations) ())))
+        let quoted_body = quote(&mut a, body);
+        let iterations = a.new_small_number(iterations).unwrap();
+        let iterations = quote(&mut a, iterations);
+        let args = op(&mut a, OP_C, &[iterations, NodePtr::NIL]);
+        let args = op(&mut a, OP_C, &[quoted_body, args]);
+        let puzzle = op(&mut a, OP_A, &[quoted_body, args]);
+        node_to_bytes(&a, puzzle).unwrap()
+    }
+
+    fn loop_generator(num_spends: u8, iterations: u32) -> Vec<u8> {
+        let puzzle = loop_puzzle(iterations);
+        let solution = [0x80_u8];
+        let spends = (0..num_spends).map(|i| {
+            (
+                Coin::new(Bytes32::new([i; 32]), Bytes32::default(), Amount::new(1)),
+                puzzle.as_slice(),
+                solution.as_slice(),
+            )
+        });
+        solution_generator(spends).expect("solution_generator")
+    }
+
+    #[test]
+    fn test_token() {
+        let token = CancellationToken::new();
+        let other = token.clone();
+        assert!(!token.is_cancelled());
+        assert_eq!(check_cancelled(Some(&token)), Ok(()));
+        assert_eq!(check_cancelled(None), Ok(()));
+
+        other.cancel();
+        assert!(token.is_cancelled());
+        assert_eq!(
+            check_cancelled(Some(&token)),
+            Err(ValidationErr(NodePtr::NIL, ErrorCode::Cancelled))
+        );
+    }
+
+    #[test]
+    fn test_run_program_cancellable() {
+        let mut a = Allocator::new();
+        // (+ (q . 1) (q . 2))
+        let program = node_from_bytes(&mut a, &hex::decode("ff10ffff0101ffff010280").unwrap())
+            .expect("node_from_bytes");
+        let dialect = ChiaDialect::new(0);
+        let token = CancellationToken::new();
+
+        let Reduction(_, result) = run_program_cancellable(
+            &mut a,
+            &dialect,
+            program,
+            NodePtr::NIL,
+            11_000_000_000,
+            None,
+        )
+        .expect("run_program");
+        assert_eq!(a.atom(result).as_ref(), &[3]);
+
+        let Reduction(_, result) = run_program_cancellable(
+            &mut a,
+            &dialect,
+            program,
+            NodePtr::NIL,
+            11_000_000_000,
+            Some(&token),
+        )
+        .expect("run_program");
+        assert_eq!(a.atom(result).as_ref(), &[3]);
+
+        token.cancel();
+        let err = run_program_cancellable(
+            &mut a,
+            &dialect,
+            program,
+            NodePtr::NIL,
+            11_000_000_000,
+            Some(&token),
+        )
+        .unwrap_err();
+        assert_eq!(err.1, ErrorCode::Cancelled);
+    }
+
+    #[test]
+    fn test_not_cancelled() {
+        let generator = loop_generator(10, 100);
+        let blocks: &[&[u8]] = &[];
+        let token = CancellationToken::new();
+
+        let mut a1 = Allocator::new();
+        let expected = run_block_generator2(
+            &mut a1,
+            &generator,
+            blocks,
+            11_000_000_000,
+            DONT_VALIDATE_SIGNATURE,
+            &Signature::default(),
+            None,
+            &TEST_CONSTANTS,
+        )
+        .expect("run_block_generator2");
+
+        let mut a2 = Allocator::new();
+        let conds = run_block_generator2_cancellable(
+            &mut a2,
+            &generator,
+            blocks,
+            11_000_000_000,
+            DONT_VALIDATE_SIGNATURE,
+            &Signature::default(),
+            None,
+            &TEST_CONSTANTS,
+            &token,
+        )
+        .expect("run_block_generator2_cancellable");
+
+        assert_eq!(conds.spends.len(), 10);
+        assert_eq!(conds.cost, expected.cost);
+    }
+
+    #[test]
+    fn test_cancelled_before_start() {
+        let generator = loop_generator(10, 100);
+        let blocks: &[&[u8]] = &[];
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let mut a = Allocator::new();
+        let err = run_block_generator2_cancellable(
+            &mut a,
+            &generator,
+            blocks,
+            11_000_000_000,
+            DONT_VALIDATE_SIGNATURE,
+            &Signature::default(),
+            None,
+            &TEST_CONSTANTS,
+            &token,
+        )
+        .unwrap_err();
+        assert_eq!(err.1, ErrorCode::Cancelled);
+
+        // (() ((parent puzzle-hash amount conditions)))
+        let mut a = Allocator::new();
+        let parent = a.new_atom(&[1; 32]).unwrap();
+        let puzzle_hash = a.new_atom(&[2; 32]).unwrap();
+        let amount = a.new_small_number(1).unwrap();
+        let spend = list(&mut a, &[parent, puzzle_hash, amount, NodePtr::NIL]);
+        let spends = list(&mut a, &[spend]);
+        let spends = list(&mut a, &[spends]);
+        let err = parse_spends_cancellable::<EmptyVisitor>(
+            &a,
+            spends,
+            11_000_000_000,
+            DONT_VALIDATE_SIGNATURE,
+            &Signature::default(),
+            None,
+            &TEST_CONSTANTS,
+            &token,
+        )
+        .unwrap_err();
+        assert_eq!(err.1, ErrorCode::Cancelled);
+    }
+
+    #[test]
+    fn test_cancel_from_another_thread() {
+        // this generator takes (much) longer to run than it takes to cancel
+        // it. If cancellation doesn't work, it fails with CostExceeded
+        // eventually
+        let generator = loop_generator(250, 100_000);
+        let token = CancellationToken::new();
+
+        let (started_tx, started_rx) = mpsc::channel();
+        let (cancelled_tx, cancelled_rx) = mpsc::channel();
+        let canceller = {
+            let token = token.clone();
+            thread::spawn(move || {
+                started_rx.recv().unwrap();
+                thread::sleep(Duration::from_millis(50));
+                cancelled_tx.send(Instant::now()).unwrap();
+                token.cancel();
+            })
+        };
+
+        let blocks: &[&[u8]] = &[];
+        let mut a = Allocator::new();
+        started_tx.send(()).unwrap();
+        let err = run_block_generator2_cancellable(
+            &mut a,
+            &generator,
+            blocks,
+            11_000_000_000,
+            DONT_VALIDATE_SIGNATURE,
+            &Signature::default(),
+            None,
+            &TEST_CONSTANTS,
+            &token,
+        )
+        .unwrap_err();
+        let returned = Instant::now();
+        canceller.join().unwrap();
+
+        assert_eq!(err.1, ErrorCode::Cancelled);
+        let cancelled = cancelled_rx.recv().unwrap();
+        assert!(returned.duration_since(cancelled) < Duration::from_millis(500));
+    }
+}
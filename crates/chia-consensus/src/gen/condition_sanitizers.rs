@@ -1,5 +1,6 @@
-use super::sanitize_int::{sanitize_uint, SanitizedUint};
+use super::sanitize_int::parse_u64_strict;
 use super::validation_error::{atom, ErrorCode, ValidationErr};
+use chia_protocol::Amount;
 use clvmr::allocator::{Allocator, NodePtr};
 
 pub fn sanitize_hash(
@@ -17,14 +18,9 @@ pub fn sanitize_hash(
     }
 }
 
-pub fn parse_amount(a: &Allocator, n: NodePtr, code: ErrorCode) -> Result<u64, ValidationErr> {
+pub fn parse_amount(a: &Allocator, n: NodePtr, code: ErrorCode) -> Result<Amount, ValidationErr> {
     // amounts are not allowed to exceed 2^64. i.e. 8 bytes
-    match sanitize_uint(a, n, 8, code)? {
-        SanitizedUint::NegativeOverflow | SanitizedUint::PositiveOverflow => {
-            Err(ValidationErr(n, code))
-        }
-        SanitizedUint::Ok(r) => Ok(r),
-    }
+    Ok(parse_u64_strict(a, n, code)?.into())
 }
 
 pub fn sanitize_announce_msg(
@@ -150,7 +146,7 @@ fn test_sanitize_announce_msg() {
 }
 
 #[cfg(test)]
-fn amount_tester(buf: &[u8]) -> Result<u64, ValidationErr> {
+fn amount_tester(buf: &[u8]) -> Result<Amount, ValidationErr> {
     let mut a = Allocator::new();
     let n = a.new_atom(buf).unwrap();
 
@@ -174,7 +170,7 @@ fn test_sanitize_amount() {
     );
 
     // leading zeros are somtimes necessary to make values positive
-    assert_eq!(amount_tester(&[0, 0xff]), Ok(0xff));
+    assert_eq!(amount_tester(&[0, 0xff]), Ok(Amount::new(0xff)));
     // but are disallowed when they are redundant
     assert_eq!(
         amount_tester(&[0, 0, 0, 0xff]).unwrap_err().1,
@@ -204,6 +200,6 @@ fn test_sanitize_amount() {
     // this is small enough though
     assert_eq!(
         amount_tester(&[0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
-        Ok(0xffff_ffff_ffff_ffff)
+        Ok(Amount::MAX)
     );
 }
@@ -3,27 +3,29 @@ use super::condition_sanitizers::{
     parse_amount, sanitize_announce_msg, sanitize_hash, sanitize_message_mode,
 };
 use super::opcodes::{
-    compute_unknown_condition_cost, parse_opcode, ConditionOpcode, AGG_SIG_AMOUNT, AGG_SIG_COST,
+    compute_unknown_condition_cost, parse_opcode, softfork_cost, ConditionOpcode, AGG_SIG_AMOUNT,
     AGG_SIG_ME, AGG_SIG_PARENT, AGG_SIG_PARENT_AMOUNT, AGG_SIG_PARENT_PUZZLE, AGG_SIG_PUZZLE,
     AGG_SIG_PUZZLE_AMOUNT, AGG_SIG_UNSAFE, ASSERT_BEFORE_HEIGHT_ABSOLUTE,
     ASSERT_BEFORE_HEIGHT_RELATIVE, ASSERT_BEFORE_SECONDS_ABSOLUTE, ASSERT_BEFORE_SECONDS_RELATIVE,
-    ASSERT_COIN_ANNOUNCEMENT, ASSERT_CONCURRENT_PUZZLE, ASSERT_CONCURRENT_SPEND, ASSERT_EPHEMERAL,
     ASSERT_HEIGHT_ABSOLUTE, ASSERT_HEIGHT_RELATIVE, ASSERT_MY_AMOUNT, ASSERT_MY_BIRTH_HEIGHT,
-    ASSERT_MY_BIRTH_SECONDS, ASSERT_MY_COIN_ID, ASSERT_MY_PARENT_ID, ASSERT_MY_PUZZLEHASH,
-    ASSERT_PUZZLE_ANNOUNCEMENT, ASSERT_SECONDS_ABSOLUTE, ASSERT_SECONDS_RELATIVE, CREATE_COIN,
-    CREATE_COIN_ANNOUNCEMENT, CREATE_COIN_COST, CREATE_PUZZLE_ANNOUNCEMENT, RECEIVE_MESSAGE,
-    REMARK, RESERVE_FEE, SEND_MESSAGE, SOFTFORK,
+    ASSERT_MY_BIRTH_SECONDS, ASSERT_SECONDS_ABSOLUTE, ASSERT_SECONDS_RELATIVE, CREATE_COIN,
+    RESERVE_FEE,
 };
-use super::sanitize_int::{sanitize_uint, SanitizedUint};
+use super::sanitize_int::{parse_u32_lenient, parse_u64_lenient, SanitizedUint};
 use super::validation_error::{first, next, rest, ErrorCode, ValidationErr};
 use crate::consensus_constants::ConsensusConstants;
-use crate::gen::flags::{DONT_VALIDATE_SIGNATURE, NO_UNKNOWN_CONDS, STRICT_ARGS_COUNT};
+use crate::gen::cancellation::{check_cancelled, CancellationToken, CANCEL_CHECK_INTERVAL};
+use crate::gen::flags::{
+    CHECK_DUPLICATE_COIN_IDS, DONT_VALIDATE_SIGNATURE, NO_UNKNOWN_CONDS, STRICT_ARGS_COUNT,
+};
 use crate::gen::make_aggsig_final_message::u64_to_bytes;
 use crate::gen::messages::{Message, SpendId};
 use crate::gen::spend_visitor::SpendVisitor;
+use crate::gen::trace::{debug_event, debug_span, trace_error};
 use crate::gen::validation_error::check_nil;
-use chia_bls::{aggregate_verify, BlsCache, PublicKey, Signature};
-use chia_protocol::{Bytes, Bytes32};
+use crate::gen::validation_metrics::ValidationMetrics;
+use chia_bls::{aggregate_verify, verify, BlsCache, PublicKey, Signature};
+use chia_protocol::{Amount, Bytes, Bytes32, Coin};
 use chia_sha2::Sha256;
 use clvmr::allocator::{Allocator, NodePtr, SExp};
 use clvmr::cost::Cost;
@@ -31,6 +33,7 @@ use std::cmp::{max, min};
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
+use std::time::Instant;
 
 // spend flags
 
@@ -70,7 +73,7 @@ impl SpendVisitor for MempoolVisitor {
         let mut spend_flags = ELIGIBLE_FOR_DEDUP;
 
         // spend eligible for fast-forward must be singletons, which use odd amounts
-        if (spend.coin_amount & 1) == 1 {
+        if (u64::from(spend.coin_amount) & 1) == 1 {
             spend_flags |= ELIGIBLE_FOR_FF;
         }
         spend.flags |= spend_flags;
@@ -136,7 +139,7 @@ impl SpendVisitor for MempoolVisitor {
         // puzzle hash as our input coin
         if (spend.flags & ELIGIBLE_FOR_FF) != 0
             && !spend.create_coin.iter().any(|c| {
-                (c.amount & 1) == 1
+                (u64::from(c.amount) & 1) == 1
                     && a.atom(spend.puzzle_hash).as_ref() == c.puzzle_hash.as_slice()
             })
         {
@@ -186,7 +189,7 @@ pub enum Condition {
     AggSigParentPuzzle(NodePtr, NodePtr),
     // puzzle hash (32 bytes), amount-node, amount integer, hint is an optional
     // hash (32 bytes), may be left as nil
-    CreateCoin(NodePtr, u64, NodePtr),
+    CreateCoin(NodePtr, Amount, NodePtr),
     // amount
     ReserveFee(u64),
     // message (<= 1024 bytes)
@@ -205,7 +208,7 @@ pub enum Condition {
     AssertMyParentId(NodePtr),
     AssertMyPuzzlehash(NodePtr),
     // amount
-    AssertMyAmount(u64),
+    AssertMyAmount(Amount),
     // seconds
     AssertMyBirthSeconds(u64),
     // block height
@@ -273,6 +276,126 @@ fn maybe_check_args_terminator(
     Ok(())
 }
 
+/// What a condition turns into when its integer argument is validly encoded,
+/// but doesn't fit in the condition's integer type.
+#[derive(Debug, Clone, Copy)]
+enum OutOfRange {
+    /// The condition fails with this error code
+    Fail(ErrorCode),
+    /// The condition is trivially true, and is ignored
+    Skip,
+    /// The condition is trivially true, but since it's relative to the coin's
+    /// creation, the coin still can't be ephemeral
+    SkipRelative,
+}
+
+/// The rules for a condition's integer argument. `code` is the error for an
+/// invalid encoding (see sanitize_int.rs).
+#[derive(Debug, Clone, Copy)]
+struct UintArg {
+    code: ErrorCode,
+    negative: OutOfRange,
+    too_large: OutOfRange,
+}
+
+/// This is the behavior of every condition that takes an integer argument.
+/// The width of the integer is the type the condition stores it in (u32 or
+/// u64), with the exception of ASSERT_SECONDS_ABSOLUTE, which only accepts 4
+/// bytes. SOFTFORK is handled in parse_args() since it reports out of range
+/// costs against the argument list, rather than the argument.
+fn uint_arg(op: ConditionOpcode) -> UintArg {
+    use OutOfRange::{Fail, Skip, SkipRelative};
+    let (code, negative, too_large) = match op {
+        CREATE_COIN => (
+            ErrorCode::InvalidCoinAmount,
+            Fail(ErrorCode::CoinAmountNegative),
+            Fail(ErrorCode::CoinAmountExceedsMaximum),
+        ),
+        RESERVE_FEE => always_fail(ErrorCode::ReserveFeeConditionFailed),
+        ASSERT_MY_AMOUNT => always_fail(ErrorCode::AssertMyAmountFailed),
+        ASSERT_MY_BIRTH_SECONDS => always_fail(ErrorCode::AssertMyBirthSecondsFailed),
+        ASSERT_MY_BIRTH_HEIGHT => always_fail(ErrorCode::AssertMyBirthHeightFailed),
+        // a timelock in the past is always satisfied
+        ASSERT_SECONDS_RELATIVE => (
+            ErrorCode::AssertSecondsRelativeFailed,
+            SkipRelative,
+            Fail(ErrorCode::AssertSecondsRelativeFailed),
+        ),
+        ASSERT_SECONDS_ABSOLUTE => (
+            ErrorCode::AssertSecondsAbsoluteFailed,
+            Skip,
+            Fail(ErrorCode::AssertSecondsAbsoluteFailed),
+        ),
+        ASSERT_HEIGHT_RELATIVE => (
+            ErrorCode::AssertHeightRelativeFailed,
+            SkipRelative,
+            Fail(ErrorCode::AssertHeightRelativeFailed),
+        ),
+        ASSERT_HEIGHT_ABSOLUTE => (
+            ErrorCode::AssertHeightAbsoluteFailed,
+            Skip,
+            Fail(ErrorCode::AssertHeightAbsoluteFailed),
+        ),
+        // an expiry too far in the future is always satisfied
+        ASSERT_BEFORE_SECONDS_RELATIVE => (
+            ErrorCode::AssertBeforeSecondsRelativeFailed,
+            Fail(ErrorCode::AssertBeforeSecondsRelativeFailed),
+            SkipRelative,
+        ),
+        ASSERT_BEFORE_SECONDS_ABSOLUTE => (
+            ErrorCode::AssertBeforeSecondsAbsoluteFailed,
+            Fail(ErrorCode::AssertBeforeSecondsAbsoluteFailed),
+            Skip,
+        ),
+        ASSERT_BEFORE_HEIGHT_RELATIVE => (
+            ErrorCode::AssertBeforeHeightRelativeFailed,
+            Fail(ErrorCode::AssertBeforeHeightRelativeFailed),
+            SkipRelative,
+        ),
+        ASSERT_BEFORE_HEIGHT_ABSOLUTE => (
+            ErrorCode::AssertBeforeHeightAbsoluteFailed,
+            Fail(ErrorCode::AssertBeforeHeightAbsoluteFailed),
+            Skip,
+        ),
+        _ => panic!("condition {op} does not take an integer argument"),
+    };
+    UintArg {
+        code,
+        negative,
+        too_large,
+    }
+}
+
+fn always_fail(code: ErrorCode) -> (ErrorCode, OutOfRange, OutOfRange) {
+    (code, OutOfRange::Fail(code), OutOfRange::Fail(code))
+}
+
+fn out_of_range(node: NodePtr, rule: OutOfRange) -> Result<Condition, ValidationErr> {
+    match rule {
+        OutOfRange::Fail(code) => Err(ValidationErr(node, code)),
+        OutOfRange::Skip => Ok(Condition::Skip),
+        OutOfRange::SkipRelative => Ok(Condition::SkipRelativeCondition),
+    }
+}
+
+/// Parses the integer argument `node` of condition `op`, following
+/// `uint_arg()`, and builds the condition with `make`. `parse` determines the
+/// width of the integer.
+fn uint_condition<T>(
+    a: &Allocator,
+    node: NodePtr,
+    op: ConditionOpcode,
+    parse: fn(&Allocator, NodePtr, ErrorCode) -> Result<SanitizedUint<T>, ValidationErr>,
+    make: impl FnOnce(T) -> Condition,
+) -> Result<Condition, ValidationErr> {
+    let rule = uint_arg(op);
+    match parse(a, node, rule.code)? {
+        SanitizedUint::Ok(r) => Ok(make(r)),
+        SanitizedUint::NegativeOverflow => out_of_range(node, rule.negative),
+        SanitizedUint::PositiveOverflow => out_of_range(node, rule.too_large),
+    }
+}
+
 pub fn parse_args(
     a: &Allocator,
     mut c: NodePtr,
@@ -280,14 +403,14 @@ pub fn parse_args(
     flags: u32,
 ) -> Result<Condition, ValidationErr> {
     match op {
-        AGG_SIG_UNSAFE
-        | AGG_SIG_ME
-        | AGG_SIG_PUZZLE
-        | AGG_SIG_PUZZLE_AMOUNT
-        | AGG_SIG_PARENT
-        | AGG_SIG_AMOUNT
-        | AGG_SIG_PARENT_PUZZLE
-        | AGG_SIG_PARENT_AMOUNT => {
+        ConditionOpcode::AggSigUnsafe
+        | ConditionOpcode::AggSigMe
+        | ConditionOpcode::AggSigPuzzle
+        | ConditionOpcode::AggSigPuzzleAmount
+        | ConditionOpcode::AggSigParent
+        | ConditionOpcode::AggSigAmount
+        | ConditionOpcode::AggSigParentPuzzle
+        | ConditionOpcode::AggSigParentAmount => {
             let pubkey = sanitize_hash(a, first(a, c)?, 48, ErrorCode::InvalidPublicKey)?;
             c = rest(a, c)?;
             let message = sanitize_announce_msg(a, first(a, c)?, ErrorCode::InvalidMessage)?;
@@ -310,18 +433,15 @@ pub fn parse_args(
                 }
             }
         }
-        CREATE_COIN => {
+        ConditionOpcode::CreateCoin => {
             let puzzle_hash = sanitize_hash(a, first(a, c)?, 32, ErrorCode::InvalidPuzzleHash)?;
             c = rest(a, c)?;
             let node = first(a, c)?;
-            let amount = match sanitize_uint(a, node, 8, ErrorCode::InvalidCoinAmount)? {
-                SanitizedUint::PositiveOverflow => {
-                    return Err(ValidationErr(node, ErrorCode::CoinAmountExceedsMaximum));
-                }
-                SanitizedUint::NegativeOverflow => {
-                    return Err(ValidationErr(node, ErrorCode::CoinAmountNegative));
-                }
-                SanitizedUint::Ok(amount) => amount,
+            let rule = uint_arg(op);
+            let amount = match parse_u64_lenient(a, node, rule.code)? {
+                SanitizedUint::Ok(amount) => Amount::new(amount),
+                SanitizedUint::NegativeOverflow => return out_of_range(node, rule.negative),
+                SanitizedUint::PositiveOverflow => return out_of_range(node, rule.too_large),
             };
             // CREATE_COIN takes an optional 3rd parameter, which is a list of
             // byte buffers (typically a 32 byte hash). We only pull out the
@@ -351,50 +471,59 @@ pub fn parse_args(
             }
             Ok(Condition::CreateCoin(puzzle_hash, amount, a.nil()))
         }
-        SOFTFORK => {
+        ConditionOpcode::Softfork => {
             if (flags & NO_UNKNOWN_CONDS) != 0 {
                 // We don't know of any new softforked-in conditions, so they
                 // are all unknown
                 Err(ValidationErr(c, ErrorCode::InvalidConditionOpcode))
             } else {
-                match sanitize_uint(a, first(a, c)?, 4, ErrorCode::InvalidSoftforkCost)? {
+                match parse_u32_lenient(a, first(a, c)?, ErrorCode::InvalidSoftforkCost)? {
                     // the first argument represents the cost of the condition.
                     // We scale it by 10000 to make the argument be a bit smaller
-                    SanitizedUint::Ok(cost) => Ok(Condition::Softfork(cost * 10000)),
+                    SanitizedUint::Ok(cost) => Ok(Condition::Softfork(softfork_cost(cost))),
+                    // unlike other conditions, an out of range cost is
+                    // reported against the argument list
                     _ => Err(ValidationErr(c, ErrorCode::InvalidSoftforkCost)),
                 }
             }
         }
-        256..=65535 => {
+        ConditionOpcode::Unknown(op) => {
             // All of these conditions are unknown
             // but they have costs
             if (flags & NO_UNKNOWN_CONDS) != 0 {
                 Err(ValidationErr(c, ErrorCode::InvalidConditionOpcode))
             } else {
-                Ok(Condition::Softfork(compute_unknown_condition_cost(op)))
+                Ok(Condition::Softfork(compute_unknown_condition_cost(
+                    op.into(),
+                )))
             }
         }
-        RESERVE_FEE => {
+        ConditionOpcode::ReserveFee => {
             maybe_check_args_terminator(a, c, flags)?;
-            let fee = parse_amount(a, first(a, c)?, ErrorCode::ReserveFeeConditionFailed)?;
-            Ok(Condition::ReserveFee(fee))
+            uint_condition(
+                a,
+                first(a, c)?,
+                op,
+                parse_u64_lenient,
+                Condition::ReserveFee,
+            )
         }
-        CREATE_COIN_ANNOUNCEMENT => {
+        ConditionOpcode::CreateCoinAnnouncement => {
             maybe_check_args_terminator(a, c, flags)?;
             let msg = sanitize_announce_msg(a, first(a, c)?, ErrorCode::InvalidCoinAnnouncement)?;
             Ok(Condition::CreateCoinAnnouncement(msg))
         }
-        ASSERT_COIN_ANNOUNCEMENT => {
+        ConditionOpcode::AssertCoinAnnouncement => {
             maybe_check_args_terminator(a, c, flags)?;
             let id = sanitize_hash(a, first(a, c)?, 32, ErrorCode::AssertCoinAnnouncementFailed)?;
             Ok(Condition::AssertCoinAnnouncement(id))
         }
-        CREATE_PUZZLE_ANNOUNCEMENT => {
+        ConditionOpcode::CreatePuzzleAnnouncement => {
             maybe_check_args_terminator(a, c, flags)?;
             let msg = sanitize_announce_msg(a, first(a, c)?, ErrorCode::InvalidPuzzleAnnouncement)?;
             Ok(Condition::CreatePuzzleAnnouncement(msg))
         }
-        ASSERT_PUZZLE_ANNOUNCEMENT => {
+        ConditionOpcode::AssertPuzzleAnnouncement => {
             maybe_check_args_terminator(a, c, flags)?;
             let id = sanitize_hash(
                 a,
@@ -404,147 +533,141 @@ pub fn parse_args(
             )?;
             Ok(Condition::AssertPuzzleAnnouncement(id))
         }
-        ASSERT_CONCURRENT_SPEND => {
+        ConditionOpcode::AssertConcurrentSpend => {
             maybe_check_args_terminator(a, c, flags)?;
             let id = sanitize_hash(a, first(a, c)?, 32, ErrorCode::AssertConcurrentSpendFailed)?;
             Ok(Condition::AssertConcurrentSpend(id))
         }
-        ASSERT_CONCURRENT_PUZZLE => {
+        ConditionOpcode::AssertConcurrentPuzzle => {
             maybe_check_args_terminator(a, c, flags)?;
             let id = sanitize_hash(a, first(a, c)?, 32, ErrorCode::AssertConcurrentPuzzleFailed)?;
             Ok(Condition::AssertConcurrentPuzzle(id))
         }
-        ASSERT_MY_COIN_ID => {
+        ConditionOpcode::AssertMyCoinId => {
             maybe_check_args_terminator(a, c, flags)?;
             let id = sanitize_hash(a, first(a, c)?, 32, ErrorCode::AssertMyCoinIdFailed)?;
             Ok(Condition::AssertMyCoinId(id))
         }
-        ASSERT_MY_PARENT_ID => {
+        ConditionOpcode::AssertMyParentId => {
             maybe_check_args_terminator(a, c, flags)?;
             let id = sanitize_hash(a, first(a, c)?, 32, ErrorCode::AssertMyParentIdFailed)?;
             Ok(Condition::AssertMyParentId(id))
         }
-        ASSERT_MY_PUZZLEHASH => {
+        ConditionOpcode::AssertMyPuzzlehash => {
             maybe_check_args_terminator(a, c, flags)?;
             let id = sanitize_hash(a, first(a, c)?, 32, ErrorCode::AssertMyPuzzleHashFailed)?;
             Ok(Condition::AssertMyPuzzlehash(id))
         }
-        ASSERT_MY_AMOUNT => {
+        ConditionOpcode::AssertMyAmount => {
             maybe_check_args_terminator(a, c, flags)?;
-            let amount = parse_amount(a, first(a, c)?, ErrorCode::AssertMyAmountFailed)?;
-            Ok(Condition::AssertMyAmount(amount))
+            uint_condition(a, first(a, c)?, op, parse_u64_lenient, |amount| {
+                Condition::AssertMyAmount(Amount::new(amount))
+            })
         }
-        ASSERT_MY_BIRTH_SECONDS => {
+        ConditionOpcode::AssertMyBirthSeconds => {
             maybe_check_args_terminator(a, c, flags)?;
-            let node = first(a, c)?;
-            let code = ErrorCode::AssertMyBirthSecondsFailed;
-            match sanitize_uint(a, node, 8, code)? {
-                SanitizedUint::PositiveOverflow | SanitizedUint::NegativeOverflow => {
-                    Err(ValidationErr(node, code))
-                }
-                SanitizedUint::Ok(r) => Ok(Condition::AssertMyBirthSeconds(r)),
-            }
+            uint_condition(
+                a,
+                first(a, c)?,
+                op,
+                parse_u64_lenient,
+                Condition::AssertMyBirthSeconds,
+            )
         }
-        ASSERT_MY_BIRTH_HEIGHT => {
+        ConditionOpcode::AssertMyBirthHeight => {
             maybe_check_args_terminator(a, c, flags)?;
-            let node = first(a, c)?;
-            let code = ErrorCode::AssertMyBirthHeightFailed;
-            match sanitize_uint(a, node, 4, code)? {
-                SanitizedUint::PositiveOverflow | SanitizedUint::NegativeOverflow => {
-                    Err(ValidationErr(node, code))
-                }
-                SanitizedUint::Ok(r) => Ok(Condition::AssertMyBirthHeight(r as u32)),
-            }
+            uint_condition(
+                a,
+                first(a, c)?,
+                op,
+                parse_u32_lenient,
+                Condition::AssertMyBirthHeight,
+            )
         }
-        ASSERT_EPHEMERAL => {
+        ConditionOpcode::AssertEphemeral => {
             // this condition does not take any parameters
             if (flags & STRICT_ARGS_COUNT) != 0 {
                 check_nil(a, c)?;
             }
             Ok(Condition::AssertEphemeral)
         }
-        ASSERT_SECONDS_RELATIVE => {
+        ConditionOpcode::AssertSecondsRelative => {
             maybe_check_args_terminator(a, c, flags)?;
-            let node = first(a, c)?;
-            let code = ErrorCode::AssertSecondsRelativeFailed;
-            match sanitize_uint(a, node, 8, code)? {
-                SanitizedUint::PositiveOverflow => Err(ValidationErr(node, code)),
-                SanitizedUint::NegativeOverflow => Ok(Condition::SkipRelativeCondition),
-                SanitizedUint::Ok(r) => Ok(Condition::AssertSecondsRelative(r)),
-            }
+            uint_condition(
+                a,
+                first(a, c)?,
+                op,
+                parse_u64_lenient,
+                Condition::AssertSecondsRelative,
+            )
         }
-        ASSERT_SECONDS_ABSOLUTE => {
+        ConditionOpcode::AssertSecondsAbsolute => {
             maybe_check_args_terminator(a, c, flags)?;
-            let node = first(a, c)?;
-            let code = ErrorCode::AssertSecondsAbsoluteFailed;
-            match sanitize_uint(a, node, 4, code)? {
-                SanitizedUint::PositiveOverflow => Err(ValidationErr(node, code)),
-                SanitizedUint::NegativeOverflow => Ok(Condition::Skip),
-                SanitizedUint::Ok(r) => Ok(Condition::AssertSecondsAbsolute(r)),
-            }
+            uint_condition(a, first(a, c)?, op, parse_u32_lenient, |r| {
+                Condition::AssertSecondsAbsolute(r.into())
+            })
         }
-        ASSERT_HEIGHT_RELATIVE => {
+        ConditionOpcode::AssertHeightRelative => {
             maybe_check_args_terminator(a, c, flags)?;
-            let node = first(a, c)?;
-            let code = ErrorCode::AssertHeightRelativeFailed;
-            match sanitize_uint(a, node, 4, code)? {
-                SanitizedUint::PositiveOverflow => Err(ValidationErr(node, code)),
-                SanitizedUint::NegativeOverflow => Ok(Condition::SkipRelativeCondition),
-                SanitizedUint::Ok(r) => Ok(Condition::AssertHeightRelative(r as u32)),
-            }
+            uint_condition(
+                a,
+                first(a, c)?,
+                op,
+                parse_u32_lenient,
+                Condition::AssertHeightRelative,
+            )
         }
-        ASSERT_HEIGHT_ABSOLUTE => {
+        ConditionOpcode::AssertHeightAbsolute => {
             maybe_check_args_terminator(a, c, flags)?;
-            let node = first(a, c)?;
-            let code = ErrorCode::AssertHeightAbsoluteFailed;
-            match sanitize_uint(a, node, 4, code)? {
-                SanitizedUint::PositiveOverflow => Err(ValidationErr(node, code)),
-                SanitizedUint::NegativeOverflow => Ok(Condition::Skip),
-                SanitizedUint::Ok(r) => Ok(Condition::AssertHeightAbsolute(r as u32)),
-            }
+            uint_condition(
+                a,
+                first(a, c)?,
+                op,
+                parse_u32_lenient,
+                Condition::AssertHeightAbsolute,
+            )
         }
-        ASSERT_BEFORE_SECONDS_RELATIVE => {
+        ConditionOpcode::AssertBeforeSecondsRelative => {
             maybe_check_args_terminator(a, c, flags)?;
-            let node = first(a, c)?;
-            let code = ErrorCode::AssertBeforeSecondsRelativeFailed;
-            match sanitize_uint(a, node, 8, code)? {
-                SanitizedUint::PositiveOverflow => Ok(Condition::SkipRelativeCondition),
-                SanitizedUint::NegativeOverflow => Err(ValidationErr(node, code)),
-                SanitizedUint::Ok(r) => Ok(Condition::AssertBeforeSecondsRelative(r)),
-            }
+            uint_condition(
+                a,
+                first(a, c)?,
+                op,
+                parse_u64_lenient,
+                Condition::AssertBeforeSecondsRelative,
+            )
         }
-        ASSERT_BEFORE_SECONDS_ABSOLUTE => {
+        ConditionOpcode::AssertBeforeSecondsAbsolute => {
             maybe_check_args_terminator(a, c, flags)?;
-
-            let node = first(a, c)?;
-            let code = ErrorCode::AssertBeforeSecondsAbsoluteFailed;
-            match sanitize_uint(a, node, 8, code)? {
-                SanitizedUint::PositiveOverflow => Ok(Condition::Skip),
-                SanitizedUint::NegativeOverflow => Err(ValidationErr(node, code)),
-                SanitizedUint::Ok(r) => Ok(Condition::AssertBeforeSecondsAbsolute(r)),
-            }
+            uint_condition(
+                a,
+                first(a, c)?,
+                op,
+                parse_u64_lenient,
+                Condition::AssertBeforeSecondsAbsolute,
+            )
         }
-        ASSERT_BEFORE_HEIGHT_RELATIVE => {
+        ConditionOpcode::AssertBeforeHeightRelative => {
             maybe_check_args_terminator(a, c, flags)?;
-            let node = first(a, c)?;
-            let code = ErrorCode::AssertBeforeHeightRelativeFailed;
-            match sanitize_uint(a, node, 4, code)? {
-                SanitizedUint::PositiveOverflow => Ok(Condition::SkipRelativeCondition),
-                SanitizedUint::NegativeOverflow => Err(ValidationErr(node, code)),
-                SanitizedUint::Ok(r) => Ok(Condition::AssertBeforeHeightRelative(r as u32)),
-            }
+            uint_condition(
+                a,
+                first(a, c)?,
+                op,
+                parse_u32_lenient,
+                Condition::AssertBeforeHeightRelative,
+            )
         }
-        ASSERT_BEFORE_HEIGHT_ABSOLUTE => {
+        ConditionOpcode::AssertBeforeHeightAbsolute => {
             maybe_check_args_terminator(a, c, flags)?;
-            let node = first(a, c)?;
-            let code = ErrorCode::AssertBeforeHeightAbsoluteFailed;
-            match sanitize_uint(a, node, 4, code)? {
-                SanitizedUint::PositiveOverflow => Ok(Condition::Skip),
-                SanitizedUint::NegativeOverflow => Err(ValidationErr(node, code)),
-                SanitizedUint::Ok(r) => Ok(Condition::AssertBeforeHeightAbsolute(r as u32)),
-            }
+            uint_condition(
+                a,
+                first(a, c)?,
+                op,
+                parse_u32_lenient,
+                Condition::AssertBeforeHeightAbsolute,
+            )
         }
-        SEND_MESSAGE => {
+        ConditionOpcode::SendMessage => {
             let mode = sanitize_message_mode(a, first(a, c)?)?;
             c = rest(a, c)?;
             let message = sanitize_announce_msg(a, first(a, c)?, ErrorCode::InvalidMessage)?;
@@ -561,7 +684,7 @@ pub fn parse_args(
                 message,
             ))
         }
-        RECEIVE_MESSAGE => {
+        ConditionOpcode::ReceiveMessage => {
             let mode = sanitize_message_mode(a, first(a, c)?)?;
             c = rest(a, c)?;
             let message = sanitize_announce_msg(a, first(a, c)?, ErrorCode::InvalidMessage)?;
@@ -577,18 +700,17 @@ pub fn parse_args(
                 message,
             ))
         }
-        REMARK => {
+        ConditionOpcode::Remark => {
             // this condition is always true, we always ignore arguments
             Ok(Condition::Skip)
         }
-        _ => Err(ValidationErr(c, ErrorCode::InvalidConditionOpcode)),
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct NewCoin {
     pub puzzle_hash: Bytes32,
-    pub amount: u64,
+    pub amount: Amount,
     // the hint is optional. When not provided, this points to nil (NodePtr
     // value -1). The hint is not part of the unique identity of a coin, it's not
     // hashed when computing the coin ID
@@ -616,7 +738,7 @@ pub struct SpendConditions {
     // the parent coin ID of the coin being spent
     pub parent_id: NodePtr,
     // the amount of the coin that's being spent
-    pub coin_amount: u64,
+    pub coin_amount: Amount,
     // the puzzle hash of the p
     pub puzzle_hash: NodePtr,
     // the coin ID of the coin being spent. This is computed from parent_id,
@@ -657,7 +779,7 @@ pub struct SpendConditions {
 impl SpendConditions {
     pub fn new(
         parent_id: NodePtr,
-        coin_amount: u64,
+        coin_amount: Amount,
         puzzle_hash: NodePtr,
         coin_id: Arc<Bytes32>,
     ) -> SpendConditions {
@@ -786,6 +908,20 @@ pub struct ParseState {
     // TODO: We would probably save heap allocations by turning this into a
     // blst_pairing object.
     pub pkm_pairs: Vec<(PublicKey, Bytes)>,
+
+    // if set, parsing is aborted with ErrorCode::Cancelled once this token is
+    // cancelled. It's checked before every spend and every
+    // CANCEL_CHECK_INTERVAL conditions
+    cancel: Option<CancellationToken>,
+}
+
+impl ParseState {
+    pub fn with_cancellation(token: Option<CancellationToken>) -> Self {
+        Self {
+            cancel: token,
+            ..Default::default()
+        }
+    }
 }
 
 // returns (parent-id, puzzle-hash, amount, condition-list)
@@ -805,6 +941,7 @@ pub(crate) fn parse_single_spend(
 }
 
 #[allow(clippy::too_many_arguments)]
+#[inline]
 pub fn process_single_spend<V: SpendVisitor>(
     a: &Allocator,
     ret: &mut SpendBundleConditions,
@@ -817,6 +954,38 @@ pub fn process_single_spend<V: SpendVisitor>(
     max_cost: &mut Cost,
     constants: &ConsensusConstants,
 ) -> Result<(), ValidationErr> {
+    process_single_spend_instrumented::<V>(
+        a,
+        ret,
+        state,
+        parent_id,
+        puzzle_hash,
+        amount,
+        conditions,
+        flags,
+        max_cost,
+        constants,
+        None,
+    )
+}
+
+// Like process_single_spend(), but also reports the spend and its conditions
+// to `metrics`, if set.
+#[allow(clippy::too_many_arguments)]
+pub fn process_single_spend_instrumented<V: SpendVisitor>(
+    a: &Allocator,
+    ret: &mut SpendBundleConditions,
+    state: &mut ParseState,
+    parent_id: NodePtr,
+    puzzle_hash: NodePtr,
+    amount: NodePtr,
+    conditions: NodePtr,
+    flags: u32,
+    max_cost: &mut Cost,
+    constants: &ConsensusConstants,
+    metrics: Option<&dyn ValidationMetrics>,
+) -> Result<(), ValidationErr> {
+    check_cancelled(state.cancel.as_ref())?;
     let parent_id = sanitize_hash(a, parent_id, 32, ErrorCode::InvalidParentId)?;
     let puzzle_hash = sanitize_hash(a, puzzle_hash, 32, ErrorCode::InvalidPuzzleHash)?;
     let my_amount = parse_amount(a, amount, ErrorCode::InvalidCoinAmount)?;
@@ -836,28 +1005,40 @@ pub fn process_single_spend<V: SpendVisitor>(
     {
         // if this coin ID has already been added to this set, it's a double
         // spend
+        debug_event!(error = ?ErrorCode::DoubleSpend, %coin_id);
         return Err(ValidationErr(parent_id, ErrorCode::DoubleSpend));
     }
 
     state.spent_puzzles.insert(puzzle_hash);
 
-    ret.removal_amount += my_amount as u128;
+    ret.removal_amount += u128::from(my_amount);
+
+    debug_span!("spend", %coin_id, amount = my_amount.get());
 
     let mut spend = SpendConditions::new(parent_id, my_amount, puzzle_hash, coin_id);
 
     let mut visitor = V::new_spend(&mut spend);
 
-    parse_conditions(
-        a,
-        ret,
-        state,
-        spend,
-        conditions,
-        flags,
-        max_cost,
-        constants,
-        &mut visitor,
-    )
+    trace_error(
+        parse_conditions_instrumented(
+            a,
+            ret,
+            state,
+            spend,
+            conditions,
+            flags,
+            max_cost,
+            constants,
+            &mut visitor,
+            metrics,
+        ),
+        "spend",
+    )?;
+
+    if let Some(metrics) = metrics {
+        metrics.on_spend_parsed();
+    }
+    Ok(())
 }
 
 fn assert_not_ephemeral(spend_flags: &mut u32, state: &mut ParseState, idx: usize) {
@@ -889,7 +1070,27 @@ fn to_key(a: &Allocator, pk: NodePtr) -> Result<PublicKey, ValidationErr> {
 }
 
 #[allow(clippy::too_many_arguments)]
+#[inline]
 pub fn parse_conditions<V: SpendVisitor>(
+    a: &Allocator,
+    ret: &mut SpendBundleConditions,
+    state: &mut ParseState,
+    spend: SpendConditions,
+    iter: NodePtr,
+    flags: u32,
+    max_cost: &mut Cost,
+    constants: &ConsensusConstants,
+    visitor: &mut V,
+) -> Result<(), ValidationErr> {
+    parse_conditions_instrumented(
+        a, ret, state, spend, iter, flags, max_cost, constants, visitor, None,
+    )
+}
+
+// Like parse_conditions(), but also reports the opcode of every (known)
+// condition to `metrics`, if set.
+#[allow(clippy::too_many_arguments)]
+pub fn parse_conditions_instrumented<V: SpendVisitor>(
     a: &Allocator,
     ret: &mut SpendBundleConditions,
     state: &mut ParseState,
@@ -899,11 +1100,20 @@ pub fn parse_conditions<V: SpendVisitor>(
     max_cost: &mut Cost,
     constants: &ConsensusConstants,
     visitor: &mut V,
+    metrics: Option<&dyn ValidationMetrics>,
 ) -> Result<(), ValidationErr> {
     let mut announce_countdown: u32 = 1024;
+    let mut cancel_countdown: u32 = CANCEL_CHECK_INTERVAL;
 
     while let Some((mut c, next)) = next(a, iter)? {
         iter = next;
+        if let Some(token) = &state.cancel {
+            cancel_countdown -= 1;
+            if cancel_countdown == 0 {
+                check_cancelled(Some(token))?;
+                cancel_countdown = CANCEL_CHECK_INTERVAL;
+            }
+        }
         let Some(op) = parse_opcode(a, first(a, c)?, flags) else {
             // in strict mode we don't allow unknown conditions
             if (flags & NO_UNKNOWN_CONDS) != 0 {
@@ -913,29 +1123,25 @@ pub fn parse_conditions<V: SpendVisitor>(
             continue;
         };
 
+        if let Some(metrics) = metrics {
+            metrics.on_condition(op);
+        }
+
         // subtract the max_cost based on the current condition
-        // in case we exceed the limit, we want to fail as early as possible
-        match op {
-            CREATE_COIN => {
-                if *max_cost < CREATE_COIN_COST {
-                    return Err(ValidationErr(c, ErrorCode::CostExceeded));
-                }
-                *max_cost -= CREATE_COIN_COST;
+        // in case we exceed the limit, we want to fail as early as possible.
+        // The cost of SOFTFORK and unknown conditions is subtracted once
+        // their arguments have been parsed
+        if op == CREATE_COIN || op.is_agg_sig() {
+            let cost = op.cost().unwrap_or_default();
+            if *max_cost < cost {
+                debug_event!(
+                    error = ?ErrorCode::CostExceeded,
+                    cost_left = *max_cost,
+                    cost
+                );
+                return Err(ValidationErr(c, ErrorCode::CostExceeded));
             }
-            AGG_SIG_UNSAFE
-            | AGG_SIG_ME
-            | AGG_SIG_PUZZLE
-            | AGG_SIG_PUZZLE_AMOUNT
-            | AGG_SIG_PARENT
-            | AGG_SIG_AMOUNT
-            | AGG_SIG_PARENT_PUZZLE
-            | AGG_SIG_PARENT_AMOUNT => {
-                if *max_cost < AGG_SIG_COST {
-                    return Err(ValidationErr(c, ErrorCode::CostExceeded));
-                }
-                *max_cost -= AGG_SIG_COST;
-            }
-            _ => (),
+            *max_cost -= cost;
         }
         c = rest(a, c)?;
         let cva = parse_args(a, c, op, flags)?;
@@ -955,9 +1161,10 @@ pub fn parse_conditions<V: SpendVisitor>(
                     hint,
                 };
                 if !spend.create_coin.insert(new_coin) {
+                    debug_event!(error = ?ErrorCode::DuplicateOutput, amount = amount.get());
                     return Err(ValidationErr(c, ErrorCode::DuplicateOutput));
                 }
-                ret.addition_amount += amount as u128;
+                ret.addition_amount += u128::from(amount);
             }
             Condition::AssertSecondsRelative(s) => {
                 // keep the most strict condition. i.e. the highest limit
@@ -968,6 +1175,11 @@ pub fn parse_conditions<V: SpendVisitor>(
                 }
                 if let Some(bs) = spend.before_seconds_relative {
                     if bs <= s {
+                        debug_event!(
+                            error = ?ErrorCode::ImpossibleSecondsRelativeConstraints,
+                            seconds_relative = s,
+                            before_seconds_relative = bs
+                        );
                         // this spend bundle requres to be spent *before* a
                         // timestamp and also *after* a timestamp that's the
                         // same or later. that's impossible.
@@ -992,6 +1204,11 @@ pub fn parse_conditions<V: SpendVisitor>(
                 }
                 if let Some(bs) = spend.before_height_relative {
                     if bs <= h {
+                        debug_event!(
+                            error = ?ErrorCode::ImpossibleHeightRelativeConstraints,
+                            height_relative = h,
+                            before_height_relative = bs
+                        );
                         // this spend bundle requres to be spent *before* a
                         // height and also *after* a height that's the
                         // same or later. that's impossible.
@@ -1016,6 +1233,11 @@ pub fn parse_conditions<V: SpendVisitor>(
                 }
                 if let Some(sr) = spend.seconds_relative {
                     if s <= sr {
+                        debug_event!(
+                            error = ?ErrorCode::ImpossibleSecondsRelativeConstraints,
+                            seconds_relative = sr,
+                            before_seconds_relative = s
+                        );
                         // this spend bundle requres to be spent *before* a
                         // timestamp and also *after* a timestamp that's the
                         // same or later. that's impossible.
@@ -1044,6 +1266,11 @@ pub fn parse_conditions<V: SpendVisitor>(
                 }
                 if let Some(hr) = spend.height_relative {
                     if h <= hr {
+                        debug_event!(
+                            error = ?ErrorCode::ImpossibleHeightRelativeConstraints,
+                            height_relative = hr,
+                            before_height_relative = h
+                        );
                         // this spend bundle requres to be spent *before* a
                         // height and also *after* a height that's the
                         // same or later. that's impossible.
@@ -1070,6 +1297,11 @@ pub fn parse_conditions<V: SpendVisitor>(
             }
             Condition::AssertMyAmount(amount) => {
                 if amount != spend.coin_amount {
+                    debug_event!(
+                        error = ?ErrorCode::AssertMyAmountFailed,
+                        asserted = amount.get(),
+                        coin_amount = spend.coin_amount.get()
+                    );
                     return Err(ValidationErr(c, ErrorCode::AssertMyAmountFailed));
                 }
             }
@@ -1078,6 +1310,11 @@ pub fn parse_conditions<V: SpendVisitor>(
                 // error if it's different from the new birth assertion. One of
                 // them must be false
                 if spend.birth_seconds.map(|v| v == s) == Some(false) {
+                    debug_event!(
+                        error = ?ErrorCode::AssertMyBirthSecondsFailed,
+                        asserted = s,
+                        previous = ?spend.birth_seconds
+                    );
                     return Err(ValidationErr(c, ErrorCode::AssertMyBirthSecondsFailed));
                 }
                 spend.birth_seconds = Some(s);
@@ -1088,6 +1325,11 @@ pub fn parse_conditions<V: SpendVisitor>(
                 // error if it's different from the new birth assertion. One of
                 // them must be false
                 if spend.birth_height.map(|v| v == h) == Some(false) {
+                    debug_event!(
+                        error = ?ErrorCode::AssertMyBirthHeightFailed,
+                        asserted = h,
+                        previous = ?spend.birth_height
+                    );
                     return Err(ValidationErr(c, ErrorCode::AssertMyBirthHeightFailed));
                 }
                 spend.birth_height = Some(h);
@@ -1161,7 +1403,7 @@ pub fn parse_conditions<V: SpendVisitor>(
                 spend.agg_sig_amount.push((to_key(a, pk)?, msg));
                 if (flags & DONT_VALIDATE_SIGNATURE) == 0 {
                     let mut msg = a.atom(msg).as_ref().to_vec();
-                    msg.extend(u64_to_bytes(spend.coin_amount).as_slice());
+                    msg.extend(u64_to_bytes(spend.coin_amount.into()).as_slice());
                     msg.extend(constants.agg_sig_amount_additional_data.as_slice());
                     state.pkm_pairs.push((to_key(a, pk)?, msg.into()));
                 }
@@ -1171,7 +1413,7 @@ pub fn parse_conditions<V: SpendVisitor>(
                 if (flags & DONT_VALIDATE_SIGNATURE) == 0 {
                     let mut msg = a.atom(msg).as_ref().to_vec();
                     msg.extend(a.atom(spend.puzzle_hash).as_ref());
-                    msg.extend(u64_to_bytes(spend.coin_amount).as_slice());
+                    msg.extend(u64_to_bytes(spend.coin_amount.into()).as_slice());
                     msg.extend(constants.agg_sig_puzzle_amount_additional_data.as_slice());
                     state.pkm_pairs.push((to_key(a, pk)?, msg.into()));
                 }
@@ -1181,7 +1423,7 @@ pub fn parse_conditions<V: SpendVisitor>(
                 if (flags & DONT_VALIDATE_SIGNATURE) == 0 {
                     let mut msg = a.atom(msg).as_ref().to_vec();
                     msg.extend(a.atom(spend.parent_id).as_ref());
-                    msg.extend(u64_to_bytes(spend.coin_amount).as_slice());
+                    msg.extend(u64_to_bytes(spend.coin_amount.into()).as_slice());
                     msg.extend(constants.agg_sig_parent_amount_additional_data.as_slice());
                     state.pkm_pairs.push((to_key(a, pk)?, msg.into()));
                 }
@@ -1209,6 +1451,7 @@ pub fn parse_conditions<V: SpendVisitor>(
             }
             Condition::Softfork(cost) => {
                 if *max_cost < cost {
+                    debug_event!(error = ?ErrorCode::CostExceeded, cost_left = *max_cost, cost);
                     return Err(ValidationErr(c, ErrorCode::CostExceeded));
                 }
                 *max_cost -= cost;
@@ -1219,7 +1462,7 @@ pub fn parse_conditions<V: SpendVisitor>(
                     src_mode,
                     spend.parent_id,
                     spend.puzzle_hash,
-                    spend.coin_amount,
+                    spend.coin_amount.into(),
                     &spend.coin_id,
                 )?;
                 state.messages.push(Message {
@@ -1235,7 +1478,7 @@ pub fn parse_conditions<V: SpendVisitor>(
                     dst_mode,
                     spend.parent_id,
                     spend.puzzle_hash,
-                    spend.coin_amount,
+                    spend.coin_amount.into(),
                     &spend.coin_id,
                 )?;
                 state.messages.push(Message {
@@ -1285,6 +1528,7 @@ fn is_ephemeral(
 // This function parses, and validates aspects of, the above structure and
 // returns a list of all spends, along with all conditions, organized by
 // condition op-code
+#[inline]
 pub fn parse_spends<V: SpendVisitor>(
     a: &Allocator,
     spends: NodePtr,
@@ -1293,9 +1537,93 @@ pub fn parse_spends<V: SpendVisitor>(
     aggregate_signature: &Signature,
     bls_cache: Option<&BlsCache>,
     constants: &ConsensusConstants,
+) -> Result<SpendBundleConditions, ValidationErr> {
+    parse_spends_instrumented::<V>(
+        a,
+        spends,
+        max_cost,
+        flags,
+        aggregate_signature,
+        bls_cache,
+        constants,
+        None,
+    )
+}
+
+// Like parse_spends(), but also reports every spend, condition and the
+// signature validation to `metrics`, if set.
+#[allow(clippy::too_many_arguments)]
+pub fn parse_spends_instrumented<V: SpendVisitor>(
+    a: &Allocator,
+    spends: NodePtr,
+    max_cost: Cost,
+    flags: u32,
+    aggregate_signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+    metrics: Option<&dyn ValidationMetrics>,
+) -> Result<SpendBundleConditions, ValidationErr> {
+    debug_span!("parse_spends", max_cost, flags);
+    trace_error(
+        parse_spends_inner::<V>(
+            a,
+            spends,
+            max_cost,
+            flags,
+            aggregate_signature,
+            bls_cache,
+            constants,
+            metrics,
+            None,
+        ),
+        "spend bundle",
+    )
+}
+
+// Like parse_spends(), but aborts with ErrorCode::Cancelled once `token` is
+// cancelled (from another thread).
+#[allow(clippy::too_many_arguments)]
+pub fn parse_spends_cancellable<V: SpendVisitor>(
+    a: &Allocator,
+    spends: NodePtr,
+    max_cost: Cost,
+    flags: u32,
+    aggregate_signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+    token: &CancellationToken,
+) -> Result<SpendBundleConditions, ValidationErr> {
+    debug_span!("parse_spends", max_cost, flags);
+    trace_error(
+        parse_spends_inner::<V>(
+            a,
+            spends,
+            max_cost,
+            flags,
+            aggregate_signature,
+            bls_cache,
+            constants,
+            None,
+            Some(token),
+        ),
+        "spend bundle",
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn parse_spends_inner<V: SpendVisitor>(
+    a: &Allocator,
+    spends: NodePtr,
+    max_cost: Cost,
+    flags: u32,
+    aggregate_signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+    metrics: Option<&dyn ValidationMetrics>,
+    cancel: Option<&CancellationToken>,
 ) -> Result<SpendBundleConditions, ValidationErr> {
     let mut ret = SpendBundleConditions::default();
-    let mut state = ParseState::default();
+    let mut state = ParseState::with_cancellation(cancel.cloned());
 
     let mut cost_left = max_cost;
 
@@ -1309,7 +1637,7 @@ pub fn parse_spends<V: SpendVisitor>(
         // as well as updates it with any conditions
         let (parent_id, puzzle_hash, amount, conds) = parse_single_spend(a, spend)?;
 
-        process_single_spend::<V>(
+        process_single_spend_instrumented::<V>(
             a,
             &mut ret,
             &mut state,
@@ -1320,11 +1648,15 @@ pub fn parse_spends<V: SpendVisitor>(
             flags,
             &mut cost_left,
             constants,
+            metrics,
         )?;
     }
 
     validate_conditions(a, &ret, &state, spends, flags)?;
-    validate_signature(&state, aggregate_signature, flags, bls_cache)?;
+    // validating the signature can take a while, don't start it if we've
+    // been cancelled already
+    check_cancelled(cancel)?;
+    validate_signature_instrumented(&state, aggregate_signature, flags, bls_cache, metrics)?;
     ret.validated_signature = (flags & DONT_VALIDATE_SIGNATURE) == 0;
 
     ret.cost = max_cost - cost_left;
@@ -1336,21 +1668,36 @@ pub fn validate_conditions(
     ret: &SpendBundleConditions,
     state: &ParseState,
     spends: NodePtr,
-    _flags: u32,
+    flags: u32,
 ) -> Result<(), ValidationErr> {
     if ret.removal_amount < ret.addition_amount {
         // The sum of removal amounts must not be less than the sum of addition
         // amounts
+        debug_event!(
+            error = ?ErrorCode::MintingCoin,
+            removal_amount = %ret.removal_amount,
+            addition_amount = %ret.addition_amount
+        );
         return Err(ValidationErr(spends, ErrorCode::MintingCoin));
     }
 
     if ret.removal_amount - ret.addition_amount < ret.reserve_fee as u128 {
         // the actual fee is lower than the reserved fee
+        debug_event!(
+            error = ?ErrorCode::ReserveFeeConditionFailed,
+            fee = %(ret.removal_amount - ret.addition_amount),
+            reserve_fee = ret.reserve_fee
+        );
         return Err(ValidationErr(spends, ErrorCode::ReserveFeeConditionFailed));
     }
 
     if let Some(bh) = ret.before_height_absolute {
         if bh <= ret.height_absolute {
+            debug_event!(
+                error = ?ErrorCode::ImpossibleHeightAbsoluteConstraints,
+                height_absolute = ret.height_absolute,
+                before_height_absolute = bh
+            );
             // this spend bundle requres to be spent *before* a
             // height and also *after* a height that's the
             // same or later. that's impossible.
@@ -1363,6 +1710,11 @@ pub fn validate_conditions(
 
     if let Some(bs) = ret.before_seconds_absolute {
         if bs <= ret.seconds_absolute {
+            debug_event!(
+                error = ?ErrorCode::ImpossibleSecondsAbsoluteConstraints,
+                seconds_absolute = ret.seconds_absolute,
+                before_seconds_absolute = bs
+            );
             // this spend bundle requres to be spent *before* a
             // timestamp and also *after* a timestamp that's the
             // same or later. that's impossible.
@@ -1405,6 +1757,22 @@ pub fn validate_conditions(
         }
     }
 
+    // two different spends must not create coins with the same coin ID. Since
+    // the parent coin IDs are unique (we reject double spends), this can only
+    // happen through a hash collision, so callers have to opt in to the check
+    if (flags & CHECK_DUPLICATE_COIN_IDS) != 0 {
+        let mut created_coins = HashSet::<Bytes32>::new();
+        for spend in &ret.spends {
+            for new_coin in &spend.create_coin {
+                let coin_id =
+                    Coin::new(*spend.coin_id, new_coin.puzzle_hash, new_coin.amount).coin_id();
+                if !created_coins.insert(coin_id) {
+                    return Err(ValidationErr(spends, ErrorCode::DuplicateCoinId));
+                }
+            }
+        }
+    }
+
     // check all the assert announcements
     // if there are no asserts, there is no need to hash all the announcements
     if !state.assert_coin.is_empty() {
@@ -1499,30 +1867,59 @@ pub fn validate_conditions(
     Ok(())
 }
 
+#[inline]
 pub fn validate_signature(
     state: &ParseState,
     signature: &Signature,
     flags: u32,
     bls_cache: Option<&BlsCache>,
+) -> Result<(), ValidationErr> {
+    validate_signature_instrumented(state, signature, flags, bls_cache, None)
+}
+
+// Like validate_signature(), but also reports how long the validation took,
+// and how many of the pairings were found in the BLS cache, to `metrics`, if
+// set. The cache hits are counted from the cache's stats, so they may include
+// lookups by other threads using the same cache at the same time.
+pub fn validate_signature_instrumented(
+    state: &ParseState,
+    signature: &Signature,
+    flags: u32,
+    bls_cache: Option<&BlsCache>,
+    metrics: Option<&dyn ValidationMetrics>,
 ) -> Result<(), ValidationErr> {
     if (flags & DONT_VALIDATE_SIGNATURE) != 0 {
         return Ok(());
     }
 
-    if let Some(bls_cache) = bls_cache {
-        if !bls_cache.aggregate_verify(
-            state.pkm_pairs.iter().map(|(pk, msg)| (pk, msg.as_slice())),
+    let start = metrics.map(|_| (Instant::now(), bls_cache.map(|c| c.stats().hits)));
+
+    let valid = match (state.pkm_pairs.as_slice(), bls_cache) {
+        // without any AGG_SIG conditions, the signature must be the identity
+        ([], _) => *signature == Signature::default(),
+        // a single pair is verified directly, which is faster than (and
+        // equivalent to) aggregate_verify(). With a BLS cache, we still go
+        // through the cache, to add the pairing to it
+        ([(pk, msg)], None) => verify(signature, pk, msg),
+        (pkm_pairs, Some(bls_cache)) => bls_cache.aggregate_verify(
+            pkm_pairs.iter().map(|(pk, msg)| (pk, msg.as_slice())),
             signature,
-        ) {
-            return Err(ValidationErr(
-                NodePtr::NIL,
-                ErrorCode::BadAggregateSignature,
-            ));
-        }
-    } else if !aggregate_verify(
-        signature,
-        state.pkm_pairs.iter().map(|(pk, msg)| (pk, msg.as_slice())),
-    ) {
+        ),
+        (pkm_pairs, None) => aggregate_verify(
+            signature,
+            pkm_pairs.iter().map(|(pk, msg)| (pk, msg.as_slice())),
+        ),
+    };
+
+    if let (Some(metrics), Some((start, hits))) = (metrics, start) {
+        let cached_pairs = match (bls_cache, hits) {
+            (Some(bls_cache), Some(hits)) => bls_cache.stats().hits.saturating_sub(hits),
+            _ => 0,
+        };
+        metrics.on_signature_validated(start.elapsed(), cached_pairs);
+    }
+
+    if !valid {
         return Err(ValidationErr(
             NodePtr::NIL,
             ErrorCode::BadAggregateSignature,
@@ -1531,9 +1928,18 @@ pub fn validate_signature(
     Ok(())
 }
 
+#[cfg(test)]
+use super::opcodes::{
+    AGG_SIG_COST, ASSERT_COIN_ANNOUNCEMENT, ASSERT_CONCURRENT_PUZZLE, ASSERT_CONCURRENT_SPEND,
+    ASSERT_MY_COIN_ID, ASSERT_MY_PARENT_ID, ASSERT_MY_PUZZLEHASH, ASSERT_PUZZLE_ANNOUNCEMENT,
+    CREATE_COIN_ANNOUNCEMENT, CREATE_COIN_COST, CREATE_PUZZLE_ANNOUNCEMENT, RECEIVE_MESSAGE,
+    SEND_MESSAGE, SOFTFORK,
+};
 #[cfg(test)]
 use crate::consensus_constants::TEST_CONSTANTS;
 #[cfg(test)]
+use crate::gen::spend_visitor::{collect_stats, CompositeVisitor, SpendStats, StatsVisitor};
+#[cfg(test)]
 use clvmr::number::Number;
 #[cfg(test)]
 use clvmr::serde::node_to_bytes;
@@ -1545,6 +1951,10 @@ use hex_literal::hex;
 use num_traits::Num;
 #[cfg(test)]
 use rstest::rstest;
+#[cfg(test)]
+use std::collections::BTreeMap;
+#[cfg(test)]
+use std::iter::zip;
 
 #[cfg(test)]
 const H1: &[u8; 32] = &[
@@ -1777,7 +2187,7 @@ fn cond_test_cb(
         print!("{c:02x}");
     }
     println!();
-    match parse_spends::<MempoolVisitor>(
+    let ret = parse_spends::<MempoolVisitor>(
         &a,
         n,
         11_000_000_000,
@@ -1785,7 +2195,39 @@ fn cond_test_cb(
         signature,
         bls_cache,
         &TEST_CONSTANTS,
-    ) {
+    );
+
+    // running another visitor alongside the MempoolVisitor must not affect
+    // the result
+    let (composite, stats) = collect_stats(|| {
+        parse_spends::<CompositeVisitor<(MempoolVisitor, StatsVisitor)>>(
+            &a,
+            n,
+            11_000_000_000,
+            flags,
+            signature,
+            bls_cache,
+            &TEST_CONSTANTS,
+        )
+    });
+    match (&ret, &composite) {
+        (Ok(list), Ok(composite)) => {
+            assert_eq!(list.cost, composite.cost);
+            assert_eq!(list.spends.len(), composite.spends.len());
+            assert_eq!(list.spends.len(), stats.len());
+            for ((spend, composite), stats) in zip(zip(&list.spends, &composite.spends), &stats) {
+                assert_eq!(spend.coin_id, composite.coin_id);
+                assert_eq!(spend.flags, composite.flags);
+                assert_eq!(*spend.coin_id, stats.coin_id);
+            }
+        }
+        // when several assertions fail, which one is reported first depends
+        // on the iteration order of a HashSet
+        (Err(e), Err(composite)) => assert_eq!(e.1, composite.1),
+        _ => panic!("CompositeVisitor changed the result: {ret:?} {composite:?}"),
+    }
+
+    match ret {
         Ok(list) => {
             for n in &list.spends {
                 println!("{n:?}");
@@ -1966,10 +2408,7 @@ fn test_strict_args_count(
 ) {
     // extra args are disallowed when STRICT_ARGS_COUNT is set
     let ret = cond_test_flag(
-        &format!(
-            "((({{h1}} ({{h2}} (123 ((({} ({} ( 1337 )))))",
-            condition as u8, arg
-        ),
+        &format!("((({{h1}} ({{h2}} (123 ((({condition} ({arg} ( 1337 )))))"),
         flags | DONT_VALIDATE_SIGNATURE,
     );
     if flags == 0 {
@@ -2073,10 +2512,7 @@ fn test_extra_arg(
     // and a failure in mempool mode
     assert_eq!(
         cond_test_sig(
-            &format!(
-                "((({{h1}} ({{h2}} (123 ((({} ({} ( 1337 ) {} ))))",
-                condition as u8, arg, extra_cond
-            ),
+            &format!("((({{h1}} ({{h2}} (123 ((({condition} ({arg} ( 1337 ) {extra_cond} ))))"),
             &signature,
             None,
             MEMPOOL_MODE,
@@ -2087,10 +2523,7 @@ fn test_extra_arg(
     );
 
     let (a, conds) = cond_test_sig(
-        &format!(
-            "((({{h1}} ({{h2}} (123 ((({} ({} ( 1337 ) {} ))))",
-            condition as u8, arg, extra_cond
-        ),
+        &format!("((({{h1}} ({{h2}} (123 ((({condition} ({arg} ( 1337 ) {extra_cond} ))))"),
         &signature,
         None,
         0,
@@ -2155,8 +2588,7 @@ fn test_single_condition(
     #[case] test: impl Fn(&SpendBundleConditions, &SpendConditions),
 ) {
     let (a, conds) = cond_test(&format!(
-        "((({{h1}} ({{h2}} (123 ((({} ({} )))))",
-        condition as u8, arg
+        "((({{h1}} ({{h2}} (123 ((({condition} ({arg} )))))"
     ))
     .unwrap();
 
@@ -2182,8 +2614,7 @@ fn test_single_condition(
 #[case(ASSERT_HEIGHT_RELATIVE, "-1")]
 fn test_single_condition_no_op(#[case] condition: ConditionOpcode, #[case] value: &str) {
     let (_, conds) = cond_test(&format!(
-        "((({{h1}} ({{h2}} (123 ((({} ({} )))))",
-        condition as u8, value
+        "((({{h1}} ({{h2}} (123 ((({condition} ({value} )))))"
     ))
     .unwrap();
 
@@ -2268,8 +2699,7 @@ fn test_single_condition_failure(
     #[case] expected_error: ErrorCode,
 ) {
     let err = cond_test(&format!(
-        "((({{h1}} ({{h2}} (123 ((({} ({} )))))",
-        condition as u8, arg
+        "((({{h1}} ({{h2}} (123 ((({condition} ({arg} )))))"
     ))
     .unwrap_err()
     .1;
@@ -2298,7 +2728,7 @@ fn test_multiple_conditions(
     #[case] condition: ConditionOpcode,
     #[case] test: impl Fn(&SpendBundleConditions, &SpendConditions),
 ) {
-    let val = condition as u8;
+    let val = condition;
     let (a, conds) = cond_test(&format!(
         "((({{h1}} ({{h2}} (1234 ((({val} (100 ) (({val} (503 ) (({val} (90 )))))"
     ))
@@ -2346,12 +2776,9 @@ fn test_multiple_conditions(
 fn test_missing_arg(#[case] condition: ConditionOpcode) {
     // extra args are disallowed in mempool mode
     assert_eq!(
-        cond_test_flag(
-            &format!("((({{h1}} ({{h2}} (123 ((({} )))))", condition as u8),
-            0
-        )
-        .unwrap_err()
-        .1,
+        cond_test_flag(&format!("((({{h1}} ({{h2}} (123 ((({condition} )))))"), 0)
+            .unwrap_err()
+            .1,
         ErrorCode::InvalidCondition
     );
 }
@@ -2769,6 +3196,67 @@ fn test_minting_coin() {
     );
 }
 
+#[test]
+fn test_create_coin_same_output_different_parents() {
+    // CREATE_COIN
+    // two spends create a coin with the same puzzle hash and amount. Since the
+    // parents differ, so do the coin IDs
+    let (_, conds) =
+        cond_test("((({h1} ({h2} (123 (((51 ({h2} (42 ))) (({h2} ({h2} (123 (((51 ({h2} (42 )))))")
+            .unwrap();
+
+    assert_eq!(conds.spends.len(), 2);
+    assert_eq!(conds.addition_amount, 84);
+    for spend in &conds.spends {
+        assert_eq!(spend.create_coin.len(), 1);
+    }
+}
+
+#[cfg(test)]
+fn duplicate_coin_id_conditions(a: &mut Allocator) -> SpendBundleConditions {
+    // the parent coin IDs are unique in any spend bundle we parse, so the only
+    // way to produce a collision is to construct the conditions by hand
+    let parent = a.new_atom(H1).unwrap();
+    let puzzle_hash = a.new_atom(H2).unwrap();
+    let coin_id = Arc::new(test_coin_id(H1, H2, 123));
+
+    let mut ret = SpendBundleConditions::default();
+    for _ in 0..2 {
+        let mut spend =
+            SpendConditions::new(parent, Amount::new(123), puzzle_hash, coin_id.clone());
+        spend.create_coin.insert(NewCoin {
+            puzzle_hash: Bytes32::from(H2),
+            amount: Amount::new(42),
+            hint: a.nil(),
+        });
+        ret.spends.push(spend);
+    }
+    ret.removal_amount = 246;
+    ret.addition_amount = 84;
+    ret
+}
+
+#[test]
+fn test_duplicate_coin_id() {
+    let mut a = Allocator::new();
+    let ret = duplicate_coin_id_conditions(&mut a);
+    let state = ParseState::default();
+    assert_eq!(
+        validate_conditions(&a, &ret, &state, a.nil(), CHECK_DUPLICATE_COIN_IDS)
+            .unwrap_err()
+            .1,
+        ErrorCode::DuplicateCoinId
+    );
+}
+
+#[test]
+fn test_duplicate_coin_id_no_check() {
+    let mut a = Allocator::new();
+    let ret = duplicate_coin_id_conditions(&mut a);
+    let state = ParseState::default();
+    validate_conditions(&a, &ret, &state, a.nil(), 0).expect("validate_conditions");
+}
+
 #[test]
 fn test_create_coin_amount_exceeds_max() {
     // CREATE_COIN
@@ -3028,12 +3516,12 @@ fn test_multiple_create_coin() {
 
     assert!(spend.create_coin.contains(&NewCoin {
         puzzle_hash: H2.into(),
-        amount: 42_u64,
+        amount: Amount::new(42),
         hint: a.nil()
     }));
     assert!(spend.create_coin.contains(&NewCoin {
         puzzle_hash: H2.into(),
-        amount: 43_u64,
+        amount: Amount::new(43),
         hint: a.nil()
     }));
     assert_eq!(spend.flags, ELIGIBLE_FOR_DEDUP | ELIGIBLE_FOR_FF);
@@ -3058,7 +3546,9 @@ fn test_create_coin_exceed_cost() {
                     let coin = a.new_pair(val, coin).unwrap();
                     let val = a.new_atom(H2).unwrap();
                     let coin = a.new_pair(val, coin).unwrap();
-                    let val = a.new_atom(&u64_to_bytes(u64::from(CREATE_COIN))).unwrap();
+                    let val = a
+                        .new_atom(&u64_to_bytes(u16::from(CREATE_COIN).into()))
+                        .unwrap();
                     let coin = a.new_pair(val, coin).unwrap();
 
                     // add the CREATE_COIN condition to the list (called rest)
@@ -3168,7 +3658,7 @@ fn test_duplicate_agg_sig(
     // we cannot deduplicate AGG_SIG conditions. Their signatures will be
     // aggregated, and so must all copies of the public keys
     let (a, conds) =
-        cond_test_flag(&format!("((({{h1}} ({{h2}} (123 ((({} ({{pubkey}} ({{msg1}} ) (({} ({{pubkey}} ({{msg1}} ) ))))", condition as u8, condition as u8),
+        cond_test_flag(&format!("((({{h1}} ({{h2}} (123 ((({condition} ({{pubkey}} ({{msg1}} ) (({condition} ({{pubkey}} ({{msg1}} ) ))))"),
             mempool | DONT_VALIDATE_SIGNATURE)
             .unwrap();
 
@@ -3205,10 +3695,7 @@ fn test_agg_sig_invalid_pubkey(
 ) {
     assert_eq!(
         cond_test_flag(
-            &format!(
-                "((({{h1}} ({{h2}} (123 ((({} ({{h2}} ({{msg1}} )))))",
-                condition as u8
-            ),
+            &format!("((({{h1}} ({{h2}} (123 ((({condition} ({{h2}} ({{msg1}} )))))"),
             mempool | DONT_VALIDATE_SIGNATURE
         )
         .unwrap_err()
@@ -3233,8 +3720,7 @@ fn test_agg_sig_infinity_pubkey(
 ) {
     let ret = cond_test_flag(
         &format!(
-            "((({{h1}} ({{h2}} (123 ((({} (0xc00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000 ({{msg1}} )))))",
-            condition as u8
+            "((({{h1}} ({{h2}} (123 ((({condition} (0xc00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000 ({{msg1}} )))))"
             ),
             mempool
     );
@@ -3257,10 +3743,7 @@ fn test_agg_sig_invalid_msg(
 ) {
     assert_eq!(
         cond_test_flag(
-            &format!(
-                "((({{h1}} ({{h2}} (123 ((({} ({{pubkey}} ({{longmsg}} )))))",
-                condition as u8
-            ),
+            &format!("((({{h1}} ({{h2}} (123 ((({condition} ({{pubkey}} ({{longmsg}} )))))"),
             mempool
         )
         .unwrap_err()
@@ -3295,7 +3778,9 @@ fn test_agg_sig_exceed_cost(#[case] condition: ConditionOpcode) {
                     let aggsig = a.new_pair(val, aggsig).unwrap();
                     let val = a.new_atom(PUBKEY).unwrap();
                     let aggsig = a.new_pair(val, aggsig).unwrap();
-                    let val = a.new_atom(&u64_to_bytes(u64::from(condition))).unwrap();
+                    let val = a
+                        .new_atom(&u64_to_bytes(u16::from(condition).into()))
+                        .unwrap();
                     let aggsig = a.new_pair(val, aggsig).unwrap();
 
                     // add the condition to the list (called rest)
@@ -3315,7 +3800,7 @@ fn test_agg_sig_exceed_cost(#[case] condition: ConditionOpcode) {
 #[test]
 fn test_single_agg_sig_unsafe() {
     // AGG_SIG_UNSAFE
-    let signature = sign_tx(H1, H2, 123, 49, MSG1);
+    let signature = sign_tx(H1, H2, 123, AGG_SIG_UNSAFE, MSG1);
 
     let (a, conds) = cond_test_sig(
         "((({h1} ({h2} (123 (((49 ({pubkey} ({msg1} )))))",
@@ -3353,10 +3838,7 @@ fn test_single_agg_sig_unsafe() {
 fn test_agg_sig_extra_arg(#[case] condition: ConditionOpcode) {
     // extra args are ignored in consensus mode
     let (a, conds) = cond_test_flag(
-        &format!(
-            "((({{h1}} ({{h2}} (123 ((({} ({{pubkey}} ({{msg1}} ( 1337 ) ))))",
-            condition as u8
-        ),
+        &format!("((({{h1}} ({{h2}} (123 ((({condition} ({{pubkey}} ({{msg1}} ( 1337 ) ))))"),
         DONT_VALIDATE_SIGNATURE,
     )
     .unwrap();
@@ -3376,10 +3858,7 @@ fn test_agg_sig_extra_arg(#[case] condition: ConditionOpcode) {
     // but not in mempool mode
     assert_eq!(
         cond_test_flag(
-            &format!(
-                "((({{h1}} ({{h2}} (123 ((({} ({{pubkey}} ({{msg1}} ( 1337 ) ))))",
-                condition as u8
-            ),
+            &format!("((({{h1}} ({{h2}} (123 ((({condition} ({{pubkey}} ({{msg1}} ( 1337 ) ))))"),
             MEMPOOL_MODE,
         )
         .unwrap_err()
@@ -3392,7 +3871,7 @@ fn test_agg_sig_extra_arg(#[case] condition: ConditionOpcode) {
 fn test_agg_sig_unsafe_invalid_terminator() {
     // AGG_SIG_UNSAFE
     // in non-mempool mode, even an invalid terminator is allowed
-    let signature = sign_tx(H1, H2, 123, 49, MSG1);
+    let signature = sign_tx(H1, H2, 123, AGG_SIG_UNSAFE, MSG1);
     let (a, conds) = cond_test_sig(
         "((({h1} ({h2} (123 (((49 ({pubkey} ({msg1} 456 ))))",
         &signature,
@@ -3421,7 +3900,7 @@ fn test_agg_sig_me_invalid_terminator() {
     // AGG_SIG_ME
     // this has an invalid list terminator of the argument list. This is OK
     // according to the original consensus rules
-    let signature = sign_tx(H1, H2, 123, 50, MSG1);
+    let signature = sign_tx(H1, H2, 123, AGG_SIG_ME, MSG1);
     let (a, conds) = cond_test_sig(
         "((({h1} ({h2} (123 (((50 ({pubkey} ({msg1} 456 ))))",
         &signature,
@@ -3449,8 +3928,8 @@ fn test_agg_sig_me_invalid_terminator() {
 fn test_duplicate_agg_sig_unsafe() {
     // AGG_SIG_UNSAFE
     // these conditions may not be deduplicated
-    let mut signature = sign_tx(H1, H2, 123, 49, MSG1);
-    signature.aggregate(&sign_tx(H1, H2, 123, 49, MSG1));
+    let mut signature = sign_tx(H1, H2, 123, AGG_SIG_UNSAFE, MSG1);
+    signature.aggregate(&sign_tx(H1, H2, 123, AGG_SIG_UNSAFE, MSG1));
     let (a, conds) = cond_test_sig(
         "((({h1} ({h2} (123 (((49 ({pubkey} ({msg1} ) ((49 ({pubkey} ({msg1} ) ))))",
         &signature,
@@ -3501,21 +3980,20 @@ fn final_message(
     parent: &[u8; 32],
     puzzle: &[u8; 32],
     amount: u64,
-    opcode: u16,
+    opcode: ConditionOpcode,
     msg: &[u8],
 ) -> Vec<u8> {
     use crate::allocator::make_allocator;
     use crate::gen::make_aggsig_final_message::make_aggsig_final_message;
     use crate::gen::owned_conditions::OwnedSpendConditions;
-    use chia_protocol::Coin;
     use clvmr::LIMIT_HEAP;
 
-    let coin = Coin::new(Bytes32::from(parent), Bytes32::from(puzzle), amount);
+    let coin = Coin::new(Bytes32::from(parent), Bytes32::from(puzzle), amount.into());
 
     let mut a: Allocator = make_allocator(LIMIT_HEAP);
     let spend = SpendConditions::new(
         a.new_atom(parent.as_slice()).expect("should pass"),
-        amount,
+        amount.into(),
         a.new_atom(puzzle.as_slice()).expect("test should pass"),
         Arc::new(Bytes32::try_from(coin.coin_id()).expect("test should pass")),
     );
@@ -3532,7 +4010,7 @@ fn sign_tx(
     parent: &[u8; 32],
     puzzle: &[u8; 32],
     amount: u64,
-    opcode: u16,
+    opcode: ConditionOpcode,
     msg: &[u8],
 ) -> Signature {
     use chia_bls::{sign, SecretKey};
@@ -3562,7 +4040,17 @@ fn sign_tx(
 #[case("0x012ebfdae17b29d83bae476a25ea06f0c4bd57298faddbbc3ec5ad29b9b86ce5df")]
 fn test_agg_sig_unsafe_invalid_msg(
     #[case] msg: &str,
-    #[values(43, 44, 45, 46, 47, 48, 49, 50)] opcode: u16,
+    #[values(
+        AGG_SIG_PARENT,
+        AGG_SIG_PUZZLE,
+        AGG_SIG_AMOUNT,
+        AGG_SIG_PUZZLE_AMOUNT,
+        AGG_SIG_PARENT_AMOUNT,
+        AGG_SIG_PARENT_PUZZLE,
+        AGG_SIG_UNSAFE,
+        AGG_SIG_ME
+    )]
+    opcode: ConditionOpcode,
 ) {
     let signature = sign_tx(
         H1,
@@ -3605,7 +4093,7 @@ fn test_agg_sig_unsafe_exceed_cost() {
                     let val = a.new_atom(PUBKEY).unwrap();
                     let aggsig = a.new_pair(val, aggsig).unwrap();
                     let val = a
-                        .new_atom(&u64_to_bytes(u64::from(AGG_SIG_UNSAFE)))
+                        .new_atom(&u64_to_bytes(u16::from(AGG_SIG_UNSAFE).into()))
                         .unwrap();
                     let aggsig = a.new_pair(val, aggsig).unwrap();
 
@@ -4050,11 +4538,10 @@ fn test_impossible_constraints_single_spend(
     let test: &str = &format!(
         "(\
        (({{h1}} ({{h1}} (123 (\
-           (({} ({} ) \
-           (({} ({} ) \
+           (({cond1} ({value1} ) \
+           (({cond2} ({value2} ) \
            ))\
-       ))",
-        cond1 as u8, value1, cond2 as u8, value2
+       ))"
     );
     if let Some(e) = expected_err {
         assert_eq!(cond_test(test).unwrap_err().1, e);
@@ -4146,13 +4633,12 @@ fn test_impossible_constraints_separate_spends(
     let test: &str = &format!(
         "(\
        (({{h1}} ({{h1}} (123 (\
-           (({} ({} ) \
+           (({cond1} ({value1} ) \
            ))\
        (({{h1}} ({{h2}} (123 (\
-           (({} ({} ) \
+           (({cond2} ({value2} ) \
            ))\
-       ))",
-        cond1 as u8, value1, cond2 as u8, value2
+       ))"
     );
     if let Some(e) = expected_err {
         assert_eq!(cond_test(test).unwrap_err().1, e);
@@ -4190,7 +4676,7 @@ fn test_conflicting_my_birth_assertions(
     #[case] condition: ConditionOpcode,
     #[case] expected: ErrorCode,
 ) {
-    let val = condition as u8;
+    let val = condition;
     assert_eq!(
         cond_test(&format!(
             "((({{h1}} ({{h2}} (1234 ((({val} (100 ) (({val} (503 ) (({val} (90 )))))"
@@ -4209,7 +4695,7 @@ fn test_multiple_my_birth_assertions(
     #[case] condition: ConditionOpcode,
     #[case] test: impl Fn(&SpendConditions),
 ) {
-    let val = condition as u8;
+    let val = condition;
     let (a, conds) = cond_test(&format!(
         "((({{h1}} ({{h2}} (1234 ((({val} (100 ) (({val} (100 ) (({val} (100 )))))"
     ))
@@ -4371,7 +4857,7 @@ fn test_relative_condition_on_ephemeral(
     // assert-before conditions as well as disallowing relative conditions on
     // ephemeral coins
 
-    let cond = condition as u8;
+    let cond = condition;
 
     // the coin11 value is the coinID computed from (H1, H1, 123).
     // coin11 is the first coin we spend in this case.
@@ -4534,7 +5020,7 @@ fn test_limit_announcements(
                 let ann = a.nil();
                 let val = a.new_atom(H2).unwrap();
                 let ann = a.new_pair(val, ann).unwrap();
-                let val = a.new_atom(&u64_to_bytes(u64::from(cond))).unwrap();
+                let val = a.new_atom(&u64_to_bytes(u16::from(cond).into())).unwrap();
                 let ann = a.new_pair(val, ann).unwrap();
 
                 // add the condition to the list
@@ -4690,9 +5176,6 @@ fn test_eligible_for_ff_invalid_agg_sig_me(
 
 #[cfg(test)]
 fn add_signature(sig: &mut Signature, puzzle: &mut String, opcode: ConditionOpcode) {
-    if opcode == 0 {
-        return;
-    }
     sig.aggregate(&sign_tx(H1, H2, 123, opcode, MSG1));
     puzzle.push_str(format!("(({opcode} ({{pubkey}} ({{msg1}} )").as_str());
 }
@@ -4722,14 +5205,14 @@ fn test_agg_sig(
     let mut signature = Signature::default();
     let bls_cache = BlsCache::default();
     let cache: Option<&BlsCache> = if with_cache {
-        populate_cache(43, &bls_cache);
-        populate_cache(44, &bls_cache);
-        populate_cache(45, &bls_cache);
-        populate_cache(46, &bls_cache);
-        populate_cache(47, &bls_cache);
-        populate_cache(48, &bls_cache);
-        populate_cache(49, &bls_cache);
-        populate_cache(50, &bls_cache);
+        populate_cache(AGG_SIG_PARENT, &bls_cache);
+        populate_cache(AGG_SIG_PUZZLE, &bls_cache);
+        populate_cache(AGG_SIG_AMOUNT, &bls_cache);
+        populate_cache(AGG_SIG_PUZZLE_AMOUNT, &bls_cache);
+        populate_cache(AGG_SIG_PARENT_AMOUNT, &bls_cache);
+        populate_cache(AGG_SIG_PARENT_PUZZLE, &bls_cache);
+        populate_cache(AGG_SIG_UNSAFE, &bls_cache);
+        populate_cache(AGG_SIG_ME, &bls_cache);
         Some(&bls_cache)
     } else {
         None
@@ -4981,7 +5464,7 @@ fn test_limit_messages(#[case] count: i32, #[case] expect_err: Option<ErrorCode>
             let send = a.new_pair(val, send).unwrap();
             let val = a.new_small_number(0x3f).unwrap();
             let send = a.new_pair(val, send).unwrap();
-            let val = a.new_small_number(SEND_MESSAGE.into()).unwrap();
+            let val = a.new_small_number(u16::from(SEND_MESSAGE).into()).unwrap();
             let send = a.new_pair(val, send).unwrap();
 
             // (67 0x3f {msg1} {coin12})
@@ -4992,7 +5475,9 @@ fn test_limit_messages(#[case] count: i32, #[case] expect_err: Option<ErrorCode>
             let recv = a.new_pair(val, recv).unwrap();
             let val = a.new_small_number(0x3f).unwrap();
             let recv = a.new_pair(val, recv).unwrap();
-            let val = a.new_small_number(RECEIVE_MESSAGE.into()).unwrap();
+            let val = a
+                .new_small_number(u16::from(RECEIVE_MESSAGE).into())
+                .unwrap();
             let recv = a.new_pair(val, recv).unwrap();
 
             for _ in 0..count {
@@ -5526,3 +6011,396 @@ fn test_message_eligible_for_ff() {
         assert_eq!((cond.spends[1].flags & ELIGIBLE_FOR_FF), 0);
     }
 }
+
+#[test]
+fn test_stats_visitor() {
+    // CREATE_COIN, CREATE_COIN, REMARK, ASSERT_SECONDS_RELATIVE (trivially
+    // true), CREATE_COIN_ANNOUNCEMENT. And a second spend without conditions
+    let mut a = Allocator::new();
+    let n = parse_list(
+        &mut a,
+        "((({h1} ({h2} (123 (((51 ({h2} (42 ) ((51 ({h2} (43 ) ((1 ) ((80 (-1 ) ((60 ({msg1} ))) (({h2} ({h2} (123 (0 )))",
+        &None,
+    );
+
+    // the StatsVisitor doesn't record anything outside of collect_stats()
+    let (conds, stats) = collect_stats(|| {
+        parse_spends::<StatsVisitor>(
+            &a,
+            n,
+            11_000_000_000,
+            MEMPOOL_MODE,
+            &Signature::default(),
+            None,
+            &TEST_CONSTANTS,
+        )
+        .expect("parse_spends")
+    });
+    assert_eq!(conds.spends.len(), 2);
+    assert_eq!(
+        stats,
+        [
+            SpendStats {
+                coin_id: test_coin_id(H1, H2, 123),
+                conditions: [(CREATE_COIN, 2), (CREATE_COIN_ANNOUNCEMENT, 1)].into(),
+                skipped: 2,
+            },
+            SpendStats {
+                coin_id: test_coin_id(H2, H2, 123),
+                conditions: BTreeMap::new(),
+                skipped: 0,
+            },
+        ]
+    );
+
+    // nested calls each collect their own stats
+    let ((inner, inner_stats), outer_stats) = collect_stats(|| {
+        collect_stats(|| {
+            parse_spends::<StatsVisitor>(
+                &a,
+                n,
+                11_000_000_000,
+                MEMPOOL_MODE,
+                &Signature::default(),
+                None,
+                &TEST_CONSTANTS,
+            )
+        })
+    });
+    assert!(inner.is_ok());
+    assert_eq!(inner_stats, stats);
+    assert!(outer_stats.is_empty());
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+enum UintResult {
+    Value(u64),
+    Skip,
+    SkipRelative,
+    Err(ErrorCode),
+}
+
+#[cfg(test)]
+fn uint_result(r: Result<Condition, ValidationErr>) -> UintResult {
+    match r {
+        Err(ValidationErr(_, code)) => UintResult::Err(code),
+        Ok(Condition::Skip) => UintResult::Skip,
+        Ok(Condition::SkipRelativeCondition) => UintResult::SkipRelative,
+        Ok(Condition::CreateCoin(_, amount, _) | Condition::AssertMyAmount(amount)) => {
+            UintResult::Value(amount.get())
+        }
+        Ok(
+            Condition::SendMessage(_, SpendId::Amount(amount), _)
+            | Condition::ReceiveMessage(SpendId::Amount(amount), _, _),
+        ) => UintResult::Value(amount),
+        Ok(
+            Condition::Softfork(r)
+            | Condition::ReserveFee(r)
+            | Condition::AssertMyBirthSeconds(r)
+            | Condition::AssertSecondsRelative(r)
+            | Condition::AssertSecondsAbsolute(r)
+            | Condition::AssertBeforeSecondsRelative(r)
+            | Condition::AssertBeforeSecondsAbsolute(r),
+        ) => UintResult::Value(r),
+        Ok(
+            Condition::AssertMyBirthHeight(r)
+            | Condition::AssertHeightRelative(r)
+            | Condition::AssertHeightAbsolute(r)
+            | Condition::AssertBeforeHeightRelative(r)
+            | Condition::AssertBeforeHeightAbsolute(r),
+        ) => UintResult::Value(r.into()),
+        Ok(c) => panic!("unexpected condition {c:?}"),
+    }
+}
+
+// This pins down the behavior of every integer argument of every condition,
+// for negative, zero, max, too large and non-canonical values.
+#[cfg(test)]
+#[rstest]
+#[case::create_coin(
+    CREATE_COIN,
+    8,
+    1,
+    UintResult::Err(ErrorCode::CoinAmountNegative),
+    UintResult::Err(ErrorCode::CoinAmountExceedsMaximum),
+    ErrorCode::InvalidCoinAmount
+)]
+#[case::send_message(
+    SEND_MESSAGE,
+    8,
+    1,
+    UintResult::Err(ErrorCode::CoinAmountNegative),
+    UintResult::Err(ErrorCode::CoinAmountExceedsMaximum),
+    ErrorCode::InvalidCoinAmount
+)]
+#[case::receive_message(
+    RECEIVE_MESSAGE,
+    8,
+    1,
+    UintResult::Err(ErrorCode::CoinAmountNegative),
+    UintResult::Err(ErrorCode::CoinAmountExceedsMaximum),
+    ErrorCode::InvalidCoinAmount
+)]
+#[case::softfork(
+    SOFTFORK,
+    4,
+    10000,
+    UintResult::Err(ErrorCode::InvalidSoftforkCost),
+    UintResult::Err(ErrorCode::InvalidSoftforkCost),
+    ErrorCode::InvalidSoftforkCost
+)]
+#[case::reserve_fee(
+    RESERVE_FEE,
+    8,
+    1,
+    UintResult::Err(ErrorCode::ReserveFeeConditionFailed),
+    UintResult::Err(ErrorCode::ReserveFeeConditionFailed),
+    ErrorCode::ReserveFeeConditionFailed
+)]
+#[case::assert_my_amount(
+    ASSERT_MY_AMOUNT,
+    8,
+    1,
+    UintResult::Err(ErrorCode::AssertMyAmountFailed),
+    UintResult::Err(ErrorCode::AssertMyAmountFailed),
+    ErrorCode::AssertMyAmountFailed
+)]
+#[case::assert_my_birth_seconds(
+    ASSERT_MY_BIRTH_SECONDS,
+    8,
+    1,
+    UintResult::Err(ErrorCode::AssertMyBirthSecondsFailed),
+    UintResult::Err(ErrorCode::AssertMyBirthSecondsFailed),
+    ErrorCode::AssertMyBirthSecondsFailed
+)]
+#[case::assert_my_birth_height(
+    ASSERT_MY_BIRTH_HEIGHT,
+    4,
+    1,
+    UintResult::Err(ErrorCode::AssertMyBirthHeightFailed),
+    UintResult::Err(ErrorCode::AssertMyBirthHeightFailed),
+    ErrorCode::AssertMyBirthHeightFailed
+)]
+#[case::assert_seconds_relative(
+    ASSERT_SECONDS_RELATIVE,
+    8,
+    1,
+    UintResult::SkipRelative,
+    UintResult::Err(ErrorCode::AssertSecondsRelativeFailed),
+    ErrorCode::AssertSecondsRelativeFailed
+)]
+#[case::assert_seconds_absolute(
+    ASSERT_SECONDS_ABSOLUTE,
+    4,
+    1,
+    UintResult::Skip,
+    UintResult::Err(ErrorCode::AssertSecondsAbsoluteFailed),
+    ErrorCode::AssertSecondsAbsoluteFailed
+)]
+#[case::assert_height_relative(
+    ASSERT_HEIGHT_RELATIVE,
+    4,
+    1,
+    UintResult::SkipRelative,
+    UintResult::Err(ErrorCode::AssertHeightRelativeFailed),
+    ErrorCode::AssertHeightRelativeFailed
+)]
+#[case::assert_height_absolute(
+    ASSERT_HEIGHT_ABSOLUTE,
+    4,
+    1,
+    UintResult::Skip,
+    UintResult::Err(ErrorCode::AssertHeightAbsoluteFailed),
+    ErrorCode::AssertHeightAbsoluteFailed
+)]
+#[case::assert_before_seconds_relative(
+    ASSERT_BEFORE_SECONDS_RELATIVE,
+    8,
+    1,
+    UintResult::Err(ErrorCode::AssertBeforeSecondsRelativeFailed),
+    UintResult::SkipRelative,
+    ErrorCode::AssertBeforeSecondsRelativeFailed
+)]
+#[case::assert_before_seconds_absolute(
+    ASSERT_BEFORE_SECONDS_ABSOLUTE,
+    8,
+    1,
+    UintResult::Err(ErrorCode::AssertBeforeSecondsAbsoluteFailed),
+    UintResult::Skip,
+    ErrorCode::AssertBeforeSecondsAbsoluteFailed
+)]
+#[case::assert_before_height_relative(
+    ASSERT_BEFORE_HEIGHT_RELATIVE,
+    4,
+    1,
+    UintResult::Err(ErrorCode::AssertBeforeHeightRelativeFailed),
+    UintResult::SkipRelative,
+    ErrorCode::AssertBeforeHeightRelativeFailed
+)]
+#[case::assert_before_height_absolute(
+    ASSERT_BEFORE_HEIGHT_ABSOLUTE,
+    4,
+    1,
+    UintResult::Err(ErrorCode::AssertBeforeHeightAbsoluteFailed),
+    UintResult::Skip,
+    ErrorCode::AssertBeforeHeightAbsoluteFailed
+)]
+fn test_uint_args(
+    #[case] op: ConditionOpcode,
+    #[case] width: usize,
+    #[case] scale: u64,
+    #[case] negative: UintResult,
+    #[case] too_large: UintResult,
+    #[case] invalid: ErrorCode,
+) {
+    let parse = |value: &[u8]| {
+        let mut a = Allocator::new();
+        let value = a.new_atom(value).unwrap();
+        let mut args = a.new_pair(value, NodePtr::NIL).unwrap();
+        // the arguments preceding the integer
+        let prefix: &[&[u8]] = match op {
+            CREATE_COIN => &[&H2[..]],
+            // only commit to the amount of the other coin
+            SEND_MESSAGE => &[&[0b000_001][..], &[][..]],
+            RECEIVE_MESSAGE => &[&[0b001_000][..], &[][..]],
+            _ => &[],
+        };
+        for arg in prefix.iter().rev() {
+            let arg = a.new_atom(arg).unwrap();
+            args = a.new_pair(arg, args).unwrap();
+        }
+        uint_result(parse_args(&a, args, op, 0))
+    };
+
+    let max = if width == 4 {
+        u64::from(u32::MAX)
+    } else {
+        u64::MAX
+    };
+    let with_leading_zero = |value: &[u8]| [&[0_u8][..], value].concat();
+
+    // in range
+    assert_eq!(parse(&[]), UintResult::Value(0));
+    assert_eq!(parse(&[1]), UintResult::Value(scale));
+    assert_eq!(parse(&[0x7f]), UintResult::Value(0x7f * scale));
+    assert_eq!(parse(&[0, 0x80]), UintResult::Value(0x80 * scale));
+    assert_eq!(
+        parse(&with_leading_zero(&vec![0xff; width])),
+        UintResult::Value(max * scale)
+    );
+
+    // negative
+    assert_eq!(parse(&[0xff]), negative);
+    assert_eq!(parse(&[0x80]), negative);
+    assert_eq!(parse(&vec![0xff; width]), negative);
+    assert_eq!(parse(&vec![0xff; width + 2]), negative);
+
+    // too large
+    let mut overflow = vec![0; width + 1];
+    overflow[0] = 1;
+    assert_eq!(parse(&overflow), too_large);
+    assert_eq!(
+        parse(&with_leading_zero(&[&[0x80][..], &vec![0; width]].concat())),
+        too_large
+    );
+    assert_eq!(parse(&with_leading_zero(&vec![0xff; width + 1])), too_large);
+    assert_eq!(parse(&[0x7f; 100]), too_large);
+
+    // redundant leading zeros are invalid, regardless of the value
+    let invalid = UintResult::Err(invalid);
+    assert_eq!(parse(&[0]), invalid);
+    assert_eq!(parse(&[0, 1]), invalid);
+    assert_eq!(parse(&[0, 0, 0x80]), invalid);
+    assert_eq!(parse(&vec![0; width + 1]), invalid);
+    assert_eq!(parse(&with_leading_zero(&overflow)), invalid);
+    assert_eq!(parse(&with_leading_zero(&[0x7f; 100])), invalid);
+}
+
+#[cfg(test)]
+fn signature_state(pkm_pairs: &[(&chia_bls::SecretKey, &[u8])]) -> ParseState {
+    ParseState {
+        pkm_pairs: pkm_pairs
+            .iter()
+            .map(|(sk, msg)| (sk.public_key(), Bytes::from(*msg)))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+#[rstest]
+#[case(0)]
+#[case(1)]
+#[case(2)]
+#[case(5)]
+fn test_validate_signature(#[case] num_pairs: usize) {
+    use chia_bls::{sign, SecretKey};
+
+    let keys: Vec<SecretKey> = (0..num_pairs)
+        .map(|i| SecretKey::from_seed(&[i as u8; 32]))
+        .collect();
+    let msgs: Vec<[u8; 3]> = (0..num_pairs).map(|i| [i as u8, 1, 2]).collect();
+    let pairs: Vec<(&SecretKey, &[u8])> =
+        zip(&keys, &msgs).map(|(sk, msg)| (sk, &msg[..])).collect();
+    let state = signature_state(&pairs);
+
+    let mut signature = Signature::default();
+    for (sk, msg) in &pairs {
+        signature += &sign(sk, msg);
+    }
+    let bad_signature = sign(&SecretKey::from_seed(&[0xff; 32]), b"foobar");
+
+    let bls_cache = BlsCache::default();
+    for cache in [None, Some(&bls_cache)] {
+        assert_eq!(validate_signature(&state, &signature, 0, cache), Ok(()));
+        // the cache is populated by now, and must agree
+        assert_eq!(validate_signature(&state, &signature, 0, cache), Ok(()));
+
+        for sig in [&bad_signature, &(&signature + &bad_signature)] {
+            assert_eq!(
+                validate_signature(&state, sig, 0, cache),
+                Err(ValidationErr(
+                    NodePtr::NIL,
+                    ErrorCode::BadAggregateSignature
+                ))
+            );
+            assert_eq!(
+                validate_signature(&state, sig, DONT_VALIDATE_SIGNATURE, cache),
+                Ok(())
+            );
+        }
+
+        // the fast paths agree with aggregate_verify()
+        for sig in [&signature, &bad_signature, &Signature::default()] {
+            let expect = aggregate_verify(
+                sig,
+                state.pkm_pairs.iter().map(|(pk, msg)| (pk, msg.as_slice())),
+            );
+            assert_eq!(validate_signature(&state, sig, 0, cache).is_ok(), expect);
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_validate_signature_wrong_message() {
+    use chia_bls::{sign, SecretKey};
+
+    let sk = SecretKey::from_seed(&[1; 32]);
+    let state = signature_state(&[(&sk, &b"foo"[..])]);
+    for sig in [
+        sign(&sk, b"bar"),
+        sign(&SecretKey::from_seed(&[2; 32]), b"foo"),
+    ] {
+        for cache in [None, Some(&BlsCache::default())] {
+            assert_eq!(
+                validate_signature(&state, &sig, 0, cache),
+                Err(ValidationErr(
+                    NodePtr::NIL,
+                    ErrorCode::BadAggregateSignature
+                ))
+            );
+        }
+    }
+}
@@ -0,0 +1,486 @@
+use crate::consensus_constants::ConsensusConstants;
+use crate::gen::flags::DONT_VALIDATE_SIGNATURE;
+use crate::gen::owned_conditions::{OwnedSpendBundleConditions, OwnedSpendConditions};
+use crate::gen::run_block_generator::run_block_generator_for_height;
+use crate::gen::validation_error::ValidationErr;
+use crate::spendbundle_validation::get_flags_for_height_and_constants;
+use chia_bls::{BlsCache, PublicKey, Signature};
+use chia_protocol::{Bytes, Bytes32};
+use chia_sha2::Sha256;
+use clvmr::allocator::Allocator;
+use linked_hash_map::LinkedHashMap;
+use std::mem::size_of;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+/// Counters of how a [`ConditionsCache`] has been used. Every miss means the
+/// generator was run.
+#[cfg_attr(
+    feature = "py-bindings",
+    pyo3::pyclass(name = "ConditionsCacheStats", get_all, frozen)
+)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConditionsCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// The number of entries that were dropped to make room for new ones.
+    pub evictions: u64,
+}
+
+// the generator, the block references and the flags it was run with. The
+// flags include the ones implied by the height, so a generator run on both
+// sides of a fork is cached separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct CacheKey {
+    generator: [u8; 32],
+    refs: [u8; 32],
+    flags: u32,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    conditions: OwnedSpendBundleConditions,
+    // the aggregate signature that was validated, if any
+    signature: Option<Signature>,
+    size: usize,
+}
+
+#[derive(Debug)]
+struct ConditionsCacheData {
+    // in least recently used order
+    items: LinkedHashMap<CacheKey, CacheEntry>,
+    max_entries: NonZeroUsize,
+    max_bytes: usize,
+    bytes: usize,
+    stats: ConditionsCacheStats,
+}
+
+impl ConditionsCacheData {
+    fn get(
+        &mut self,
+        key: &CacheKey,
+        max_cost: u64,
+        signature: Option<&Signature>,
+    ) -> Option<OwnedSpendBundleConditions> {
+        // a result that exceeds the cost limit, or that didn't validate the
+        // signature we're asked to validate, has to be computed again
+        match self.items.get_refresh(key) {
+            Some(entry)
+                if entry.conditions.cost <= max_cost
+                    && (signature.is_none() || entry.signature.as_ref() == signature) =>
+            {
+                self.stats.hits += 1;
+                Some(entry.conditions.clone())
+            }
+            _ => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn put(&mut self, key: CacheKey, entry: CacheEntry) {
+        self.bytes += entry.size;
+        if let Some(old) = self.items.insert(key, entry) {
+            self.bytes -= old.size;
+        }
+        // the most recent entry is kept, even if it exceeds max_bytes by
+        // itself
+        while self.items.len() > self.max_entries.get()
+            || (self.bytes > self.max_bytes && self.items.len() > 1)
+        {
+            let (_, oldest) = self.items.pop_front().expect("non-empty cache");
+            self.bytes -= oldest.size;
+            self.stats.evictions += 1;
+        }
+    }
+}
+
+/// A cache of the results of running block generators, so that a block
+/// that's validated more than once (e.g. in pre-validation and then when it's
+/// added to the chain) is only run once. See [`get_or_run()`].
+///
+/// The cache is bounded both by the number of entries and by the approximate
+/// amount of memory used by the cached conditions. When either limit is
+/// exceeded, the least recently used entries are evicted.
+#[cfg_attr(feature = "py-bindings", pyo3::pyclass(name = "ConditionsCache"))]
+#[derive(Debug)]
+pub struct ConditionsCache {
+    cache: Mutex<ConditionsCacheData>,
+}
+
+impl ConditionsCache {
+    pub fn new(max_entries: NonZeroUsize, max_bytes: usize) -> Self {
+        Self {
+            cache: Mutex::new(ConditionsCacheData {
+                items: LinkedHashMap::new(),
+                max_entries,
+                max_bytes,
+                bytes: 0,
+                stats: ConditionsCacheStats::default(),
+            }),
+        }
+    }
+
+    pub fn stats(&self) -> ConditionsCacheStats {
+        self.cache.lock().expect("cache").stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.lock().expect("cache").items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.lock().expect("cache").items.is_empty()
+    }
+
+    /// The approximate amount of memory used by the cached conditions.
+    pub fn size_bytes(&self) -> usize {
+        self.cache.lock().expect("cache").bytes
+    }
+
+    pub fn clear(&self) {
+        let mut c = self.cache.lock().expect("cache");
+        c.items.clear();
+        c.bytes = 0;
+    }
+}
+
+fn agg_sigs_size(agg_sigs: &[(PublicKey, Bytes)]) -> usize {
+    agg_sigs
+        .iter()
+        .map(|(_, msg)| size_of::<(PublicKey, Bytes)>() + msg.len())
+        .sum()
+}
+
+fn spend_size(spend: &OwnedSpendConditions) -> usize {
+    let create_coin: usize = spend
+        .create_coin
+        .iter()
+        .map(|(_, _, hint)| {
+            size_of::<(Bytes32, u64, Option<Bytes>)>() + hint.as_ref().map_or(0, Bytes::len)
+        })
+        .sum();
+    size_of::<OwnedSpendConditions>()
+        + create_coin
+        + agg_sigs_size(&spend.agg_sig_me)
+        + agg_sigs_size(&spend.agg_sig_parent)
+        + agg_sigs_size(&spend.agg_sig_puzzle)
+        + agg_sigs_size(&spend.agg_sig_amount)
+        + agg_sigs_size(&spend.agg_sig_puzzle_amount)
+        + agg_sigs_size(&spend.agg_sig_parent_amount)
+        + agg_sigs_size(&spend.agg_sig_parent_puzzle)
+}
+
+// the approximate amount of memory used by the conditions
+fn conditions_size(conditions: &OwnedSpendBundleConditions) -> usize {
+    size_of::<CacheEntry>()
+        + conditions.spends.iter().map(spend_size).sum::<usize>()
+        + agg_sigs_size(&conditions.agg_sig_unsafe)
+}
+
+fn cache_key<GenBuf: AsRef<[u8]>>(program: &[u8], block_refs: &[GenBuf], flags: u32) -> CacheKey {
+    let mut ctx = Sha256::new();
+    ctx.update(program);
+    let generator = ctx.finalize();
+
+    // the references are length-prefixed, to tell [ab, c] from [a, bc]
+    let mut ctx = Sha256::new();
+    for r in block_refs {
+        let r = r.as_ref();
+        ctx.update((r.len() as u64).to_be_bytes());
+        ctx.update(r);
+    }
+    let refs = ctx.finalize();
+
+    CacheKey {
+        generator,
+        refs,
+        flags,
+    }
+}
+
+/// Like `run_block_generator_for_height()`, but returns the cached result if
+/// the same generator, with the same block references, has already been run
+/// with the same flags (including the ones implied by `height`). Successful
+/// results are added to the cache, errors are not.
+///
+/// If the signature is validated (i.e. `DONT_VALIDATE_SIGNATURE` isn't set),
+/// only a result that validated the same signature is used. A result that
+/// cost more than `max_cost` is not used either. In those cases the generator
+/// is run again, and the new result replaces the cached one.
+///
+/// The cache assumes the consensus constants are always the same.
+#[allow(clippy::too_many_arguments)]
+pub fn get_or_run<GenBuf: AsRef<[u8]>>(
+    cache: &ConditionsCache,
+    a: &mut Allocator,
+    program: &[u8],
+    block_refs: &[GenBuf],
+    max_cost: u64,
+    flags: u32,
+    height: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+) -> Result<OwnedSpendBundleConditions, ValidationErr> {
+    let all_flags = flags | get_flags_for_height_and_constants(height, constants);
+    let validate_signature = (flags & DONT_VALIDATE_SIGNATURE) == 0;
+    // a result that validated the signature can be used when we're not
+    // validating it, so that flag is not part of the key
+    let key = cache_key(program, block_refs, all_flags & !DONT_VALIDATE_SIGNATURE);
+
+    if let Some(conditions) = cache.cache.lock().expect("cache").get(
+        &key,
+        max_cost,
+        validate_signature.then_some(signature),
+    ) {
+        return Ok(conditions);
+    }
+
+    let conditions = run_block_generator_for_height(
+        a, program, block_refs, max_cost, flags, height, signature, bls_cache, constants,
+    )?;
+    let conditions = OwnedSpendBundleConditions::from(a, conditions);
+
+    let size = conditions_size(&conditions);
+    cache.cache.lock().expect("cache").put(
+        key,
+        CacheEntry {
+            conditions: conditions.clone(),
+            signature: validate_signature.then(|| signature.clone()),
+            size,
+        },
+    );
+    Ok(conditions)
+}
+
+#[cfg(feature = "py-bindings")]
+#[pyo3::pymethods]
+impl ConditionsCache {
+    #[new]
+    #[pyo3(signature = (max_entries=1000, max_bytes=100_000_000))]
+    pub fn init(max_entries: usize, max_bytes: usize) -> pyo3::PyResult<Self> {
+        let Some(max_entries) = NonZeroUsize::new(max_entries) else {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Cannot have a cache size less than one.",
+            ));
+        };
+        Ok(Self::new(max_entries, max_bytes))
+    }
+
+    #[pyo3(name = "len")]
+    pub fn py_len(&self) -> usize {
+        self.len()
+    }
+
+    #[pyo3(name = "size_bytes")]
+    pub fn py_size_bytes(&self) -> usize {
+        self.size_bytes()
+    }
+
+    #[pyo3(name = "stats")]
+    pub fn py_stats(&self) -> ConditionsCacheStats {
+        self.stats()
+    }
+
+    #[pyo3(name = "clear")]
+    pub fn py_clear(&self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::make_allocator;
+    use crate::consensus_constants::TEST_CONSTANTS;
+    use crate::gen::validation_error::ErrorCode;
+    use clvmr::LIMIT_HEAP;
+    use rstest::rstest;
+
+    const MAX_COST: u64 = 11_000_000_000;
+    const HEIGHT: u32 = TEST_CONSTANTS.hard_fork_height;
+
+    fn generator() -> Vec<u8> {
+        let test_file = std::fs::read_to_string("../../generator-tests/create-coin-hint.txt")
+            .expect("test file not found");
+        let (generator, _) = test_file.split_once('\n').expect("invalid test file");
+        hex::decode(generator).expect("invalid hex encoded generator")
+    }
+
+    fn run_with(
+        cache: &ConditionsCache,
+        max_cost: u64,
+        flags: u32,
+        height: u32,
+        signature: &Signature,
+    ) -> Result<OwnedSpendBundleConditions, ErrorCode> {
+        let mut a = make_allocator(LIMIT_HEAP);
+        get_or_run(
+            cache,
+            &mut a,
+            &generator(),
+            &Vec::<&[u8]>::new(),
+            max_cost,
+            flags,
+            height,
+            signature,
+            None,
+            &TEST_CONSTANTS,
+        )
+        .map_err(|e| e.1)
+    }
+
+    fn run(
+        cache: &ConditionsCache,
+        flags: u32,
+        height: u32,
+    ) -> Result<OwnedSpendBundleConditions, ErrorCode> {
+        run_with(cache, MAX_COST, flags, height, &Signature::default())
+    }
+
+    fn stats(hits: u64, misses: u64, evictions: u64) -> ConditionsCacheStats {
+        ConditionsCacheStats {
+            hits,
+            misses,
+            evictions,
+        }
+    }
+
+    fn new_cache(max_entries: usize, max_bytes: usize) -> ConditionsCache {
+        ConditionsCache::new(NonZeroUsize::new(max_entries).unwrap(), max_bytes)
+    }
+
+    #[test]
+    fn test_cache_hit() {
+        let cache = new_cache(10, 1_000_000);
+        assert!(cache.is_empty());
+
+        let mut first = run(&cache, DONT_VALIDATE_SIGNATURE, HEIGHT).expect("run");
+        assert_eq!(cache.stats(), stats(0, 1, 0));
+        assert_eq!(cache.len(), 1);
+        assert!(cache.size_bytes() > 0);
+
+        // the second run doesn't execute the generator
+        let second = run(&cache, DONT_VALIDATE_SIGNATURE, HEIGHT).expect("run");
+        assert_eq!(cache.stats(), stats(1, 1, 0));
+        assert_eq!(first, second);
+
+        // and the result is the same as running it without a cache
+        let mut a = make_allocator(LIMIT_HEAP);
+        let conditions = run_block_generator_for_height(
+            &mut a,
+            &generator(),
+            &Vec::<&[u8]>::new(),
+            MAX_COST,
+            DONT_VALIDATE_SIGNATURE,
+            HEIGHT,
+            &Signature::default(),
+            None,
+            &TEST_CONSTANTS,
+        )
+        .expect("run_block_generator_for_height");
+        // the order of the CREATE_COIN conditions isn't deterministic
+        first.normalize();
+        let mut conditions = OwnedSpendBundleConditions::from(&a, conditions);
+        conditions.normalize();
+        assert_eq!(first, conditions);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.size_bytes(), 0);
+    }
+
+    #[rstest]
+    // on the other side of the hard fork
+    #[case(DONT_VALIDATE_SIGNATURE, 0)]
+    // different flags
+    #[case(DONT_VALIDATE_SIGNATURE | crate::gen::flags::MEMPOOL_MODE, HEIGHT)]
+    fn test_cache_key(#[case] flags: u32, #[case] height: u32) {
+        let cache = new_cache(10, 1_000_000);
+        run(&cache, DONT_VALIDATE_SIGNATURE, HEIGHT).expect("run");
+        let _ = run(&cache, flags, height);
+        assert_eq!(cache.stats(), stats(0, 2, 0));
+    }
+
+    #[test]
+    fn test_validate_signature() {
+        let cache = new_cache(10, 1_000_000);
+        let sig = Signature::default();
+        let conditions =
+            run_with(&cache, MAX_COST, DONT_VALIDATE_SIGNATURE, HEIGHT, &sig).expect("run");
+        assert!(!conditions.validated_signature);
+
+        // the signature wasn't validated, so the generator is run again. The
+        // generator has no AGG_SIG conditions, so the empty signature is valid
+        let conditions = run_with(&cache, MAX_COST, 0, HEIGHT, &sig).expect("run");
+        assert!(conditions.validated_signature);
+        assert_eq!(cache.stats(), stats(0, 2, 0));
+        assert_eq!(cache.len(), 1);
+
+        // now the validated result is used, whether the signature is
+        // validated or not
+        assert_eq!(
+            run_with(&cache, MAX_COST, 0, HEIGHT, &sig),
+            Ok(conditions.clone())
+        );
+        assert_eq!(
+            run_with(&cache, MAX_COST, DONT_VALIDATE_SIGNATURE, HEIGHT, &sig),
+            Ok(conditions)
+        );
+        assert_eq!(cache.stats(), stats(2, 2, 0));
+
+        // but not to validate a different signature
+        let other_sig = chia_bls::sign(&chia_bls::SecretKey::from_seed(&[1; 32]), b"foobar");
+        assert_eq!(
+            run_with(&cache, MAX_COST, 0, HEIGHT, &other_sig),
+            Err(ErrorCode::BadAggregateSignature)
+        );
+        assert_eq!(cache.stats(), stats(2, 3, 0));
+    }
+
+    #[test]
+    fn test_max_cost() {
+        let cache = new_cache(10, 1_000_000);
+        let conditions = run(&cache, DONT_VALIDATE_SIGNATURE, HEIGHT).expect("run");
+
+        // with a lower cost limit, the cached result can't be used
+        assert_eq!(
+            run_with(
+                &cache,
+                conditions.cost - 1,
+                DONT_VALIDATE_SIGNATURE,
+                HEIGHT,
+                &Signature::default()
+            ),
+            Err(ErrorCode::CostExceeded)
+        );
+        assert_eq!(cache.stats(), stats(0, 2, 0));
+        run_with(
+            &cache,
+            conditions.cost,
+            DONT_VALIDATE_SIGNATURE,
+            HEIGHT,
+            &Signature::default(),
+        )
+        .expect("run");
+        assert_eq!(cache.stats(), stats(1, 2, 0));
+    }
+
+    #[rstest]
+    #[case(1, 1_000_000)]
+    #[case(10, 0)]
+    fn test_eviction(#[case] max_entries: usize, #[case] max_bytes: usize) {
+        let cache = new_cache(max_entries, max_bytes);
+        run(&cache, DONT_VALIDATE_SIGNATURE, HEIGHT).expect("run");
+        run(&cache, DONT_VALIDATE_SIGNATURE, 0).expect("run");
+        assert_eq!(cache.stats(), stats(0, 2, 1));
+        assert_eq!(cache.len(), 1);
+
+        // the oldest entry was evicted
+        run(&cache, DONT_VALIDATE_SIGNATURE, 0).expect("run");
+        run(&cache, DONT_VALIDATE_SIGNATURE, HEIGHT).expect("run");
+        assert_eq!(cache.stats(), stats(1, 3, 2));
+    }
+}
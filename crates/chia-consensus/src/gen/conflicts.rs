@@ -0,0 +1,203 @@
+use std::collections::{BTreeSet, HashSet};
+
+use chia_protocol::{Bytes32, Coin};
+
+use super::owned_conditions::{OwnedSpendBundleConditions, OwnedSpendConditions};
+
+#[cfg(feature = "py-bindings")]
+use pyo3::prelude::*;
+
+/// The ways two validated spend bundles interact, as computed by
+/// [`conflicts_with`]. Every list is sorted and free of duplicates, so the
+/// report is deterministic regardless of the order of spends in the bundles.
+///
+/// Announcements, messages and ASSERT_CONCURRENT_* conditions are not part of
+/// the report. A spend bundle only passes validation if all of those are
+/// satisfied by its own spends, so they can't tie two bundles together.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "py-bindings",
+    pyo3::pyclass(name = "Conflict", get_all, frozen)
+)]
+pub struct Conflict {
+    /// Coins spent by both bundles. If this is non-empty, the bundles can't
+    /// both be included in a block, and one has to replace the other.
+    pub shared_spends: Vec<Bytes32>,
+    /// Coins created by both bundles. Two CREATE_COIN conditions produce the
+    /// same coin only if they have the same parent, so this implies a shared
+    /// spend.
+    pub shared_additions: Vec<Bytes32>,
+    /// Coins spent by `a` that are created by `b`. Evicting `b` invalidates `a`.
+    pub a_depends_on_b: Vec<Bytes32>,
+    /// Coins spent by `b` that are created by `a`. Evicting `a` invalidates `b`.
+    pub b_depends_on_a: Vec<Bytes32>,
+}
+
+impl Conflict {
+    /// Returns true if the bundles are independent of each other, i.e. both
+    /// can be included, and either can be evicted without affecting the other.
+    pub fn is_empty(&self) -> bool {
+        self.shared_spends.is_empty()
+            && self.shared_additions.is_empty()
+            && self.a_depends_on_b.is_empty()
+            && self.b_depends_on_a.is_empty()
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl Conflict {
+    #[pyo3(name = "is_empty")]
+    fn py_is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+fn removals(sb: &OwnedSpendBundleConditions) -> HashSet<Bytes32> {
+    sb.spends.iter().map(|spend| spend.coin_id).collect()
+}
+
+fn additions(sb: &OwnedSpendBundleConditions) -> HashSet<Bytes32> {
+    sb.spends.iter().flat_map(created_coin_ids).collect()
+}
+
+pub(crate) fn created_coin_ids(spend: &OwnedSpendConditions) -> impl Iterator<Item = Bytes32> + '_ {
+    spend
+        .create_coin
+        .iter()
+        .map(|(puzzle_hash, amount, _hint)| {
+            Coin::new(spend.coin_id, *puzzle_hash, (*amount).into()).coin_id()
+        })
+}
+
+fn sorted_intersection(lhs: &HashSet<Bytes32>, rhs: &HashSet<Bytes32>) -> Vec<Bytes32> {
+    lhs.intersection(rhs)
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Compares the conditions of two validated spend bundles, to tell whether
+/// they can coexist in the mempool and what happens if one of them is evicted.
+///
+/// A coin's id is its `coin_id`, for spends, and the id of the coin with the
+/// spend's coin id as parent and the CREATE_COIN's puzzle hash and amount, for
+/// additions. The function is symmetric: swapping `a` and `b` swaps
+/// `a_depends_on_b` and `b_depends_on_a`, and leaves the rest unchanged.
+///
+/// For example, a fee-bumped replacement of `a` spends (at least) the same
+/// coins as `a`, so it has `shared_spends`. A transaction spending one of
+/// `a`'s outputs has `a`'s addition in its `depends_on` list.
+pub fn conflicts_with(a: &OwnedSpendBundleConditions, b: &OwnedSpendBundleConditions) -> Conflict {
+    let a_removals = removals(a);
+    let b_removals = removals(b);
+    let a_additions = additions(a);
+    let b_additions = additions(b);
+
+    Conflict {
+        shared_spends: sorted_intersection(&a_removals, &b_removals),
+        shared_additions: sorted_intersection(&a_additions, &b_additions),
+        a_depends_on_b: sorted_intersection(&a_removals, &b_additions),
+        b_depends_on_a: sorted_intersection(&b_removals, &a_additions),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::test_helpers::{child, coin, spend};
+    use rstest::rstest;
+
+    fn bundle(spends: Vec<OwnedSpendConditions>, fee: u64) -> OwnedSpendBundleConditions {
+        OwnedSpendBundleConditions {
+            spends,
+            reserve_fee: fee,
+            ..Default::default()
+        }
+    }
+
+    fn check(a: &OwnedSpendBundleConditions, b: &OwnedSpendBundleConditions, expected: &Conflict) {
+        assert_eq!(&conflicts_with(a, b), expected);
+
+        // the report is symmetric
+        let swapped = Conflict {
+            a_depends_on_b: expected.b_depends_on_a.clone(),
+            b_depends_on_a: expected.a_depends_on_b.clone(),
+            ..expected.clone()
+        };
+        assert_eq!(conflicts_with(b, a), swapped);
+    }
+
+    #[test]
+    fn test_independent() {
+        let ph = Bytes32::new([1; 32]);
+        let a = bundle(vec![spend(&coin(1), &[(ph, 900)])], 100);
+        let b = bundle(vec![spend(&coin(2), &[(ph, 900)])], 100);
+        let conflict = conflicts_with(&a, &b);
+        assert!(conflict.is_empty());
+        check(&a, &b, &Conflict::default());
+    }
+
+    #[rstest]
+    fn test_fee_replacement(#[values(false, true)] same_outputs: bool) {
+        // the replacement spends the same coin plus another one to pay a
+        // higher fee
+        let ph = Bytes32::new([1; 32]);
+        let original = bundle(vec![spend(&coin(1), &[(ph, 900)])], 100);
+        let outputs: &[(Bytes32, u64)] = if same_outputs {
+            &[(ph, 900)]
+        } else {
+            &[(ph, 800)]
+        };
+        let replacement = bundle(vec![spend(&coin(2), &[]), spend(&coin(1), outputs)], 1200);
+
+        let expected = Conflict {
+            shared_spends: vec![coin(1).coin_id()],
+            shared_additions: if same_outputs {
+                vec![child(&coin(1), ph, 900).coin_id()]
+            } else {
+                vec![]
+            },
+            ..Default::default()
+        };
+        check(&original, &replacement, &expected);
+        assert!(!conflicts_with(&original, &replacement).is_empty());
+    }
+
+    #[test]
+    fn test_dependent_transaction() {
+        // b spends an output of a
+        let ph = Bytes32::new([1; 32]);
+        let a = bundle(vec![spend(&coin(1), &[(ph, 600), (ph, 400)])], 0);
+        let output = child(&coin(1), ph, 400);
+        let b = bundle(
+            vec![spend(&output, &[(ph, 300)]), spend(&coin(2), &[])],
+            100,
+        );
+
+        let expected = Conflict {
+            b_depends_on_a: vec![output.coin_id()],
+            ..Default::default()
+        };
+        check(&a, &b, &expected);
+    }
+
+    #[test]
+    fn test_deterministic_order() {
+        let ph = Bytes32::new([1; 32]);
+        let spends: Vec<_> = (1..10).map(|i| spend(&coin(i), &[(ph, 1)])).collect();
+        let a = bundle(spends.clone(), 0);
+        let b = bundle(spends.into_iter().rev().collect(), 0);
+
+        let conflict = conflicts_with(&a, &b);
+        let mut expected_spends: Vec<_> = (1..10).map(|i| coin(i).coin_id()).collect();
+        expected_spends.sort();
+        assert_eq!(conflict.shared_spends, expected_spends);
+        assert_eq!(conflict.shared_additions.len(), 9);
+        let mut sorted = conflict.shared_additions.clone();
+        sorted.sort();
+        assert_eq!(conflict.shared_additions, sorted);
+        assert_eq!(conflict, conflicts_with(&b, &a));
+    }
+}
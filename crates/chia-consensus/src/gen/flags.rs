@@ -22,4 +22,9 @@ pub const ALLOW_BACKREFS: u32 = 0x0200_0000;
 // re-run it to compute additions and removals.
 pub const DONT_VALIDATE_SIGNATURE: u32 = 0x1_0000;
 
+// When this flag is set, validate_conditions() ensures no two spends create
+// coins with the same coin ID. This is not part of consensus, and requires
+// hashing every created coin, so it's only done when asked for.
+pub const CHECK_DUPLICATE_COIN_IDS: u32 = 0x4_0000;
+
 pub const MEMPOOL_MODE: u32 = CLVM_MEMPOOL_MODE | NO_UNKNOWN_CONDS | STRICT_ARGS_COUNT;
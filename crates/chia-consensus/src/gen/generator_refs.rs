@@ -0,0 +1,402 @@
+use crate::gen::flags::ALLOW_BACKREFS;
+use crate::gen::validation_error::ValidationErr;
+use crate::generator_rom::{CLVM_DESERIALIZER, GENERATOR_ROM};
+use clvmr::allocator::{Allocator, NodePtr, SExp};
+use clvmr::chia_dialect::ChiaDialect;
+use clvmr::cost::Cost;
+use clvmr::dialect::{Dialect, OperatorSet};
+use clvmr::reduction::{Reduction, Response};
+use clvmr::run_program::run_program;
+use clvmr::serde::{node_from_bytes, node_from_bytes_backrefs};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// The parts of a single referenced generator that were read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RefUsage {
+    /// The byte ranges that were read, sorted and without overlaps.
+    pub ranges: Vec<Range<usize>>,
+}
+
+impl RefUsage {
+    pub fn is_used(&self) -> bool {
+        !self.ranges.is_empty()
+    }
+
+    pub fn bytes_read(&self) -> usize {
+        self.ranges.iter().map(ExactSizeIterator::len).sum()
+    }
+}
+
+/// Which of the referenced generators passed to a block generator were
+/// actually read by it, and which parts of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RefUsageReport {
+    /// One entry per referenced generator, in the order they were passed in.
+    pub refs: Vec<RefUsage>,
+}
+
+impl RefUsageReport {
+    /// The indices of the referenced generators that were read.
+    pub fn used_refs(&self) -> Vec<usize> {
+        (0..self.refs.len())
+            .filter(|i| self.refs[*i].is_used())
+            .collect()
+    }
+
+    /// The indices of the referenced generators that weren't read at all.
+    pub fn unused_refs(&self) -> Vec<usize> {
+        (0..self.refs.len())
+            .filter(|i| !self.refs[*i].is_used())
+            .collect()
+    }
+
+    /// The total number of bytes read, across all referenced generators.
+    pub fn total_bytes(&self) -> usize {
+        self.refs.iter().map(RefUsage::bytes_read).sum()
+    }
+}
+
+// the operators that only pass atoms along (or look at their length), without
+// reading their contents. substr produces a view into the same atom, which
+// is only recorded once something reads it.
+const OP_IF: u8 = 3;
+const OP_CONS: u8 = 4;
+const OP_FIRST: u8 = 5;
+const OP_REST: u8 = 6;
+const OP_LISTP: u8 = 7;
+const OP_SUBSTR: u8 = 12;
+
+// Wraps the ChiaDialect and records every atom that's passed to an operator
+// and lies within one of the referenced generators. The referenced generators
+// are atoms in the environment, so the only way the generator can read them
+// is through operators, or by returning them.
+struct RefTracker {
+    dialect: ChiaDialect,
+    refs: Vec<NodePtr>,
+    ranges: RefCell<Vec<Vec<Range<usize>>>>,
+}
+
+impl RefTracker {
+    fn new(dialect: ChiaDialect, refs: Vec<NodePtr>) -> Self {
+        let ranges = RefCell::new(vec![Vec::new(); refs.len()]);
+        Self {
+            dialect,
+            refs,
+            ranges,
+        }
+    }
+
+    // substrings of an atom share its buffer in the allocator, so an atom
+    // that was cut out of a referenced generator points into it
+    fn record(&self, a: &Allocator, node: NodePtr) {
+        let SExp::Atom = a.sexp(node) else {
+            return;
+        };
+        let atom = a.atom(node);
+        let bytes = atom.as_ref();
+        if bytes.is_empty() {
+            return;
+        }
+        let start = bytes.as_ptr() as usize;
+        for (i, ref_node) in self.refs.iter().enumerate() {
+            let ref_atom = a.atom(*ref_node);
+            let base = ref_atom.as_ref().as_ptr() as usize;
+            if start >= base && start + bytes.len() <= base + ref_atom.as_ref().len() {
+                let offset = start - base;
+                self.ranges.borrow_mut()[i].push(offset..offset + bytes.len());
+                return;
+            }
+        }
+    }
+
+    // records all atoms in the tree. Shared sub trees are only visited once
+    fn record_tree(&self, a: &Allocator, node: NodePtr) {
+        let mut visited = HashSet::new();
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node) {
+                continue;
+            }
+            match a.sexp(node) {
+                SExp::Atom => self.record(a, node),
+                SExp::Pair(left, right) => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+            }
+        }
+    }
+
+    fn into_report(self) -> RefUsageReport {
+        let refs = self
+            .ranges
+            .into_inner()
+            .into_iter()
+            .map(|ranges| RefUsage {
+                ranges: merge_ranges(ranges),
+            })
+            .collect();
+        RefUsageReport { refs }
+    }
+}
+
+impl Dialect for RefTracker {
+    fn quote_kw(&self) -> u32 {
+        self.dialect.quote_kw()
+    }
+
+    fn apply_kw(&self) -> u32 {
+        self.dialect.apply_kw()
+    }
+
+    fn softfork_kw(&self) -> u32 {
+        self.dialect.softfork_kw()
+    }
+
+    fn softfork_extension(&self, ext: u32) -> OperatorSet {
+        self.dialect.softfork_extension(ext)
+    }
+
+    fn op(
+        &self,
+        allocator: &mut Allocator,
+        op: NodePtr,
+        args: NodePtr,
+        max_cost: Cost,
+        extensions: OperatorSet,
+    ) -> Response {
+        let opcode = allocator.atom(op);
+        let pass_through = matches!(
+            opcode.as_ref(),
+            [OP_IF | OP_CONS | OP_FIRST | OP_REST | OP_LISTP | OP_SUBSTR]
+        );
+        if !pass_through {
+            let mut args_left = args;
+            while let Some((arg, rest)) = allocator.next(args_left) {
+                self.record(allocator, arg);
+                args_left = rest;
+            }
+        }
+        self.dialect.op(allocator, op, args, max_cost, extensions)
+    }
+
+    fn allow_unknown_ops(&self) -> bool {
+        self.dialect.allow_unknown_ops()
+    }
+}
+
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|r| (r.start, r.end));
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for r in ranges {
+        match merged.last_mut() {
+            Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+            _ => merged.push(r),
+        }
+    }
+    merged
+}
+
+// returns the list of referenced generators, as passed to the generator, as
+// well as the nodes of each referenced generator
+fn new_refs(a: &mut Allocator, refs: &[&[u8]]) -> Result<(NodePtr, Vec<NodePtr>), ValidationErr> {
+    let nodes = refs
+        .iter()
+        .map(|r| a.new_atom(r))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut list = NodePtr::NIL;
+    for node in nodes.iter().rev() {
+        list = a.new_pair(*node, list)?;
+    }
+    Ok((list, nodes))
+}
+
+fn parse_generator(
+    a: &mut Allocator,
+    generator: &[u8],
+    flags: u32,
+) -> Result<NodePtr, ValidationErr> {
+    if (flags & ALLOW_BACKREFS) != 0 {
+        Ok(node_from_bytes_backrefs(a, generator)?)
+    } else {
+        Ok(node_from_bytes(a, generator)?)
+    }
+}
+
+/// Reports which of the referenced generators (`refs`) are read by
+/// `generator`, and which byte ranges of them. This runs the generator the
+/// way `run_block_generator2()` does, but not the puzzles it returns. An
+/// atom counts as read when it's passed to an operator (other than ones that
+/// just pass it along, like `c` and `substr`) or when it's part of the spends
+/// returned by the generator.
+///
+/// Back references in the generator can't point into referenced generators,
+/// so this is the only way they can be used.
+pub fn analyze_generator_refs(
+    generator: &[u8],
+    refs: &[&[u8]],
+    max_cost: Cost,
+    flags: u32,
+) -> Result<RefUsageReport, ValidationErr> {
+    let mut a = Allocator::new();
+    let program = parse_generator(&mut a, generator, flags)?;
+
+    let (ref_list, ref_nodes) = new_refs(&mut a, refs)?;
+    let clvm_deserializer = node_from_bytes(&mut a, &CLVM_DESERIALIZER)?;
+    let args = a.new_pair(ref_list, NodePtr::NIL)?;
+    let args = a.new_pair(clvm_deserializer, args)?;
+
+    let tracker = RefTracker::new(ChiaDialect::new(flags), ref_nodes);
+    let Reduction(_, output) = run_program(&mut a, &tracker, program, args, max_cost)?;
+    tracker.record_tree(&a, output);
+    Ok(tracker.into_report())
+}
+
+/// Like [`analyze_generator_refs()`], but runs the generator through the
+/// generator ROM, the way `run_block_generator()` does it before the hard
+/// fork. The ROM also runs the puzzles and computes their tree hashes, so
+/// everything that's read along the way is recorded.
+pub fn analyze_generator_refs_rom(
+    generator: &[u8],
+    refs: &[&[u8]],
+    max_cost: Cost,
+    flags: u32,
+) -> Result<RefUsageReport, ValidationErr> {
+    let mut a = Allocator::new();
+    let rom = node_from_bytes(&mut a, &GENERATOR_ROM)?;
+    let program = parse_generator(&mut a, generator, flags)?;
+
+    let (ref_list, ref_nodes) = new_refs(&mut a, refs)?;
+    let args = a.new_pair(ref_list, NodePtr::NIL)?;
+    let args = a.new_pair(args, NodePtr::NIL)?;
+    let args = a.new_pair(program, args)?;
+
+    let tracker = RefTracker::new(ChiaDialect::new(flags), ref_nodes);
+    run_program(&mut a, &tracker, rom, args, max_cost)?;
+    Ok(tracker.into_report())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clvmr::serde::node_to_bytes;
+    use rstest::rstest;
+
+    const REF0: &[u8] = &[0x10; 40];
+    const REF1: &[u8] = &[0x11; 50];
+    const REF2: &[u8] = &[0x12; 60];
+
+    fn op(a: &mut Allocator, opcode: u8, args: &[NodePtr]) -> NodePtr {
+        let mut list = NodePtr::NIL;
+        for arg in args.iter().rev() {
+            list = a.new_pair(*arg, list).unwrap();
+        }
+        let opcode = a.new_atom(&[opcode]).unwrap();
+        a.new_pair(opcode, list).unwrap()
+    }
+
+    fn quote(a: &mut Allocator, value: NodePtr) -> NodePtr {
+        let q = a.new_atom(&[1]).unwrap();
+        a.new_pair(q, value).unwrap()
+    }
+
+    fn num(a: &mut Allocator, value: u8) -> NodePtr {
+        let atom = a.new_atom(&[value]).unwrap();
+        quote(a, atom)
+    }
+
+    // the expressions for the first and third referenced generator. The
+    // generator is passed (DESERIALIZER (ref0 ref1 ref2))
+    fn refs_0_and_2(a: &mut Allocator) -> (NodePtr, NodePtr) {
+        let env = a.new_atom(&[1]).unwrap();
+        let rest = op(a, OP_REST, &[env]);
+        let ref_list = op(a, OP_FIRST, &[rest]);
+        let ref0 = op(a, OP_FIRST, &[ref_list]);
+        let tail = op(a, OP_REST, &[ref_list]);
+        let tail = op(a, OP_REST, &[tail]);
+        let ref2 = op(a, OP_FIRST, &[tail]);
+        (ref0, ref2)
+    }
+
+    // a generator that hashes the first referenced generator and compares
+    // it to bytes 2..5 of the third one, and then returns no spends
+    fn compare_generator() -> Vec<u8> {
+        let mut a = Allocator::new();
+        let (ref0, ref2) = refs_0_and_2(&mut a);
+        let hash = op(&mut a, 11, &[ref0]);
+        let (start, end) = (num(&mut a, 2), num(&mut a, 5));
+        let slice = op(&mut a, OP_SUBSTR, &[ref2, start, end]);
+        let cond = op(&mut a, 9, &[hash, slice]);
+        let no_spends = a.new_pair(NodePtr::NIL, NodePtr::NIL).unwrap();
+        let no_spends = quote(&mut a, no_spends);
+        let program = op(&mut a, OP_IF, &[cond, no_spends, no_spends]);
+        node_to_bytes(&a, program).unwrap()
+    }
+
+    // a generator that returns bytes 2..5 of the third referenced generator,
+    // without reading them
+    fn return_generator() -> Vec<u8> {
+        let mut a = Allocator::new();
+        let (_, ref2) = refs_0_and_2(&mut a);
+        let (start, end) = (num(&mut a, 2), num(&mut a, 5));
+        let slice = op(&mut a, OP_SUBSTR, &[ref2, start, end]);
+        let program = op(&mut a, OP_CONS, &[slice, NodePtr::NIL]);
+        node_to_bytes(&a, program).unwrap()
+    }
+
+    #[rstest]
+    #[case::compare(compare_generator(), vec![0..40], vec![], vec![2..5], false)]
+    #[case::compare_rom(compare_generator(), vec![0..40], vec![], vec![2..5], true)]
+    #[case::returned(return_generator(), vec![], vec![], vec![2..5], false)]
+    fn test_analyze_generator_refs(
+        #[case] generator: Vec<u8>,
+        #[case] ref0: Vec<Range<usize>>,
+        #[case] ref1: Vec<Range<usize>>,
+        #[case] ref2: Vec<Range<usize>>,
+        #[case] rom: bool,
+    ) {
+        let analyze = if rom {
+            analyze_generator_refs_rom
+        } else {
+            analyze_generator_refs
+        };
+        let report = analyze(&generator, &[REF0, REF1, REF2], 11_000_000_000, 0).unwrap();
+        let expected = [ref0, ref1, ref2].map(|ranges| RefUsage { ranges });
+        assert_eq!(report.refs, expected);
+
+        let used: Vec<usize> = (0..3).filter(|i| report.refs[*i].is_used()).collect();
+        assert_eq!(report.used_refs(), used);
+        assert_eq!(
+            report.total_bytes(),
+            report.refs.iter().map(RefUsage::bytes_read).sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn test_two_of_three() {
+        let report =
+            analyze_generator_refs(&compare_generator(), &[REF0, REF1, REF2], 11_000_000_000, 0)
+                .unwrap();
+        assert_eq!(report.used_refs(), [0, 2]);
+        assert_eq!(report.unused_refs(), [1]);
+        assert_eq!(report.total_bytes(), 43);
+    }
+
+    #[test]
+    fn test_no_refs() {
+        let report = analyze_generator_refs(&return_generator(), &[], 11_000_000_000, 0);
+        // the generator fails, since there's no third referenced generator
+        assert!(report.is_err());
+    }
+
+    #[rstest]
+    #[case(vec![], vec![])]
+    #[case(vec![3..5, 0..2], vec![0..2, 3..5])]
+    #[case(vec![0..2, 2..4], vec![0..4])]
+    #[case(vec![0..5, 1..3, 4..8], vec![0..8])]
+    fn test_merge_ranges(#[case] ranges: Vec<Range<usize>>, #[case] expected: Vec<Range<usize>>) {
+        assert_eq!(merge_ranges(ranges), expected);
+    }
+}
@@ -65,7 +65,7 @@ mod test {
     use crate::gen::make_aggsig_final_message::u64_to_bytes;
     use crate::gen::run_block_generator::{run_block_generator2, setup_generator_args};
     use chia_bls::Signature;
-    use chia_protocol::Bytes32;
+    use chia_protocol::{Amount, Bytes32};
     use chia_sha2::Sha256;
     use clvm_traits::FromClvm;
     use clvm_utils::tree_hash;
@@ -136,7 +136,7 @@ mod test {
                 &a,
                 generator_output,
                 &HashSet::new(),
-                &Coin::new(parent, tree_hash(&a, puzzle1).into(), 1337),
+                &Coin::new(parent, tree_hash(&a, puzzle1).into(), Amount::new(1337)),
             )
             .unwrap(),
             (puzzle1, solution1)
@@ -148,7 +148,11 @@ mod test {
                 &a,
                 generator_output,
                 &HashSet::new(),
-                &Coin::new(make_dummy_id(2), tree_hash(&a, puzzle1).into(), 1337),
+                &Coin::new(
+                    make_dummy_id(2),
+                    tree_hash(&a, puzzle1).into(),
+                    Amount::new(1337)
+                ),
             )
             .unwrap_err()
             .1,
@@ -161,7 +165,7 @@ mod test {
                 &a,
                 generator_output,
                 &HashSet::new(),
-                &Coin::new(parent, tree_hash(&a, puzzle1).into(), 42),
+                &Coin::new(parent, tree_hash(&a, puzzle1).into(), Amount::new(42)),
             )
             .unwrap_err()
             .1,
@@ -174,7 +178,7 @@ mod test {
                 &a,
                 generator_output,
                 &HashSet::new(),
-                &Coin::new(parent, make_dummy_id(4), 1337),
+                &Coin::new(parent, make_dummy_id(4), Amount::new(1337)),
             )
             .unwrap_err()
             .1,
@@ -256,7 +260,7 @@ mod test {
             let mut expected_additions: HashSet<(Bytes32, u64)> = s
                 .create_coin
                 .iter()
-                .map(|c| (c.puzzle_hash, c.amount))
+                .map(|c| (c.puzzle_hash, u64::from(c.amount)))
                 .collect();
 
             let dialect = &ChiaDialect::new(MEMPOOL_MODE);
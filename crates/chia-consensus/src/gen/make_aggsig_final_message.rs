@@ -11,38 +11,49 @@ pub fn make_aggsig_final_message(
     msg: &mut Vec<u8>,
     spend: &OwnedSpendConditions,
     constants: &ConsensusConstants,
+) {
+    let coin = Coin::new(spend.parent_id, spend.puzzle_hash, spend.coin_amount.into());
+    make_aggsig_final_message_for_coin(opcode, msg, &coin, constants);
+}
+
+/// Appends the suffix required by the AGG_SIG_* condition `opcode` to `msg`,
+/// for a condition created by spending `coin`. AGG_SIG_UNSAFE (and any other
+/// opcode) leaves the message unchanged.
+pub fn make_aggsig_final_message_for_coin(
+    opcode: ConditionOpcode,
+    msg: &mut Vec<u8>,
+    coin: &Coin,
+    constants: &ConsensusConstants,
 ) {
     match opcode {
         AGG_SIG_PARENT => {
-            msg.extend(spend.parent_id.as_slice());
+            msg.extend(coin.parent_coin_info.as_slice());
             msg.extend(constants.agg_sig_parent_additional_data.as_slice());
         }
         AGG_SIG_PUZZLE => {
-            msg.extend(spend.puzzle_hash.as_slice());
+            msg.extend(coin.puzzle_hash.as_slice());
             msg.extend(constants.agg_sig_puzzle_additional_data.as_slice());
         }
         AGG_SIG_AMOUNT => {
-            msg.extend(u64_to_bytes(spend.coin_amount).as_slice());
+            msg.extend(u64_to_bytes(coin.amount.into()).as_slice());
             msg.extend(constants.agg_sig_amount_additional_data.as_slice());
         }
         AGG_SIG_PUZZLE_AMOUNT => {
-            msg.extend(spend.puzzle_hash.as_slice());
-            msg.extend(u64_to_bytes(spend.coin_amount).as_slice());
+            msg.extend(coin.puzzle_hash.as_slice());
+            msg.extend(u64_to_bytes(coin.amount.into()).as_slice());
             msg.extend(constants.agg_sig_puzzle_amount_additional_data.as_slice());
         }
         AGG_SIG_PARENT_AMOUNT => {
-            msg.extend(spend.parent_id.as_slice());
-            msg.extend(u64_to_bytes(spend.coin_amount).as_slice());
+            msg.extend(coin.parent_coin_info.as_slice());
+            msg.extend(u64_to_bytes(coin.amount.into()).as_slice());
             msg.extend(constants.agg_sig_parent_amount_additional_data.as_slice());
         }
         AGG_SIG_PARENT_PUZZLE => {
-            msg.extend(spend.parent_id.as_slice());
-            msg.extend(spend.puzzle_hash.as_slice());
+            msg.extend(coin.parent_coin_info.as_slice());
+            msg.extend(coin.puzzle_hash.as_slice());
             msg.extend(constants.agg_sig_parent_puzzle_additional_data.as_slice());
         }
         AGG_SIG_ME => {
-            let coin: Coin = Coin::new(spend.parent_id, spend.puzzle_hash, spend.coin_amount);
-
             msg.extend(coin.coin_id().as_slice());
             msg.extend(constants.agg_sig_me_additional_data.as_slice());
         }
@@ -123,7 +134,7 @@ mod tests {
         let coin = Coin::new(
             Bytes32::try_from(parent_id.clone()).expect("test should pass"),
             Bytes32::try_from(puzzle_hash.clone()).expect("test should pass"),
-            coin_amount,
+            coin_amount.into(),
         );
 
         match opcode {
@@ -175,7 +186,7 @@ mod tests {
         let mut a: Allocator = make_allocator(LIMIT_HEAP);
         let spend = SpendConditions::new(
             a.new_atom(parent_id.as_slice()).expect("should pass"),
-            coin_amount,
+            coin_amount.into(),
             a.new_atom(puzzle_hash.as_slice())
                 .expect("test should pass"),
             Arc::new(Bytes32::try_from(coin.coin_id()).expect("test should pass")),
@@ -183,7 +194,11 @@ mod tests {
 
         let spend = OwnedSpendConditions::from(&a, spend);
 
+        let mut coin_msg = msg.clone();
         make_aggsig_final_message(opcode, &mut msg, &spend, &TEST_CONSTANTS);
         assert_eq!(msg, expected_result);
+
+        make_aggsig_final_message_for_coin(opcode, &mut coin_msg, &coin, &TEST_CONSTANTS);
+        assert_eq!(coin_msg, expected_result);
     }
 }
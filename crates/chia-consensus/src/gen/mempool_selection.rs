@@ -0,0 +1,426 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use chia_protocol::Bytes32;
+
+use super::conflicts::created_coin_ids;
+use super::owned_conditions::OwnedSpendBundleConditions;
+
+#[cfg(feature = "py-bindings")]
+use pyo3::prelude::*;
+
+/// A validated mempool item, to be considered by [`select_spends`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "py-bindings",
+    pyo3::pyclass(name = "MempoolCandidate", get_all, frozen)
+)]
+pub struct MempoolCandidate {
+    /// Identifies the item in the [`Selection`], e.g. the spend bundle name.
+    /// It's also the tie-break between items with the same fee per cost.
+    pub id: Bytes32,
+    pub fee: u64,
+    pub cost: u64,
+    pub conditions: OwnedSpendBundleConditions,
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl MempoolCandidate {
+    #[new]
+    fn py_new(id: Bytes32, fee: u64, cost: u64, conditions: OwnedSpendBundleConditions) -> Self {
+        Self {
+            id,
+            fee,
+            cost,
+            conditions,
+        }
+    }
+}
+
+/// The items picked by [`select_spends`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "py-bindings",
+    pyo3::pyclass(name = "MempoolSelection", get_all, frozen)
+)]
+pub struct Selection {
+    /// The ids of the included items, in the order they were picked. Every
+    /// item comes after the items it depends on.
+    pub ids: Vec<Bytes32>,
+    /// The sum of the fees of the included items (saturating at `u64::MAX`).
+    pub fee: u64,
+    /// The sum of the costs of the included items.
+    pub cost: u64,
+}
+
+/// Picks the mempool items to include in a block, greedily by fee per cost,
+/// without exceeding `max_cost`.
+///
+/// An item that spends a coin created by another item depends on it, and is
+/// only included along with it (and, transitively, everything that it
+/// depends on). Items are ranked by the fee per cost of such a package, and
+/// a package is included if it fits in the remaining cost and doesn't spend
+/// any coin that's already spent by the selection. Items with the same fee
+/// per cost are considered in order of their `id`, then their position in
+/// `items`.
+///
+/// If more than one item creates the same coin (which means they spend the
+/// same coin, so only one of them can be included), spends of that coin
+/// depend on the one with the lowest `id`.
+pub fn select_spends(items: &[MempoolCandidate], max_cost: u64) -> Selection {
+    let removals: Vec<Vec<Bytes32>> = items
+        .iter()
+        .map(|item| {
+            item.conditions
+                .spends
+                .iter()
+                .map(|spend| spend.coin_id)
+                .collect()
+        })
+        .collect();
+
+    let mut creators = HashMap::<Bytes32, usize>::new();
+    for (index, item) in items.iter().enumerate() {
+        for coin_id in item.conditions.spends.iter().flat_map(created_coin_ids) {
+            let creator = creators.entry(coin_id).or_insert(index);
+            if item.id < items[*creator].id {
+                *creator = index;
+            }
+        }
+    }
+
+    // spending a coin created by the same item doesn't make it a dependency.
+    // The dependencies are visited in order of their id, so the order of the
+    // selection doesn't depend on the order of `items`
+    let dependencies: Vec<Vec<usize>> = removals
+        .iter()
+        .enumerate()
+        .map(|(index, coins)| {
+            let mut deps: Vec<usize> = coins
+                .iter()
+                .filter_map(|coin_id| creators.get(coin_id).copied())
+                .filter(|dep| *dep != index)
+                .collect();
+            deps.sort_unstable_by_key(|dep| (items[*dep].id, *dep));
+            deps.dedup();
+            deps
+        })
+        .collect();
+
+    let packages: Vec<Vec<usize>> = (0..items.len())
+        .map(|index| with_dependencies(index, &dependencies))
+        .collect();
+
+    // the (fee, cost) of every item along with its dependencies
+    let rates: Vec<(u128, u128)> = packages
+        .iter()
+        .map(|package| {
+            package.iter().fold((0, 0), |(fee, cost), i| {
+                (
+                    fee + u128::from(items[*i].fee),
+                    cost + u128::from(items[*i].cost),
+                )
+            })
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|a, b| {
+        compare_rates(rates[*b], rates[*a])
+            .then_with(|| items[*a].id.cmp(&items[*b].id))
+            .then(a.cmp(b))
+    });
+
+    let mut included = vec![false; items.len()];
+    let mut spent = HashSet::<Bytes32>::new();
+    let mut selection = Selection::default();
+    for index in order {
+        if included[index] {
+            continue;
+        }
+        let package: Vec<usize> = packages[index]
+            .iter()
+            .copied()
+            .filter(|i| !included[*i])
+            .collect();
+
+        let cost: u128 = package.iter().map(|i| u128::from(items[*i].cost)).sum();
+        if u128::from(selection.cost) + cost > u128::from(max_cost) {
+            continue;
+        }
+
+        let mut package_spent = HashSet::<Bytes32>::new();
+        if !package
+            .iter()
+            .flat_map(|i| &removals[*i])
+            .all(|coin_id| !spent.contains(coin_id) && package_spent.insert(*coin_id))
+        {
+            continue;
+        }
+
+        spent.extend(package_spent);
+        for i in package {
+            included[i] = true;
+            selection.ids.push(items[i].id);
+            selection.fee = selection.fee.saturating_add(items[i].fee);
+            selection.cost += items[i].cost;
+        }
+    }
+    selection
+}
+
+/// Compares two fee per cost rates, given as `(fee, cost)`, without dividing.
+fn compare_rates(lhs: (u128, u128), rhs: (u128, u128)) -> Ordering {
+    // the products for a single item always fit in a u128. Only packages
+    // with enormous fees and costs saturate, which just makes them compare
+    // as equal
+    let (lhs_fee, lhs_cost) = lhs;
+    let (rhs_fee, rhs_cost) = rhs;
+    (lhs_fee.saturating_mul(rhs_cost)).cmp(&rhs_fee.saturating_mul(lhs_cost))
+}
+
+/// Returns `index` along with everything it depends on, directly or
+/// indirectly. Every item comes after the items it depends on (unless they
+/// depend on each other).
+fn with_dependencies(index: usize, dependencies: &[Vec<usize>]) -> Vec<usize> {
+    let mut visited = HashSet::from([index]);
+    let mut ret = Vec::new();
+    // a depth first search, where each entry is an item and the position of
+    // its next dependency to visit
+    let mut stack = vec![(index, 0_usize)];
+    while let Some(top) = stack.last_mut() {
+        let (item, next) = *top;
+        if let Some(dep) = dependencies[item].get(next) {
+            top.1 += 1;
+            if visited.insert(*dep) {
+                stack.push((*dep, 0));
+            }
+        } else {
+            ret.push(item);
+            stack.pop();
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::owned_conditions::OwnedSpendConditions;
+    use crate::gen::test_helpers::{child, coin, spend};
+    use chia_protocol::Coin;
+    use rand::rngs::SmallRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use rstest::rstest;
+
+    const PH: Bytes32 = Bytes32::new([100; 32]);
+
+    fn candidate(
+        id: u8,
+        fee: u64,
+        cost: u64,
+        spends: Vec<OwnedSpendConditions>,
+    ) -> MempoolCandidate {
+        MempoolCandidate {
+            id: Bytes32::new([id; 32]),
+            fee,
+            cost,
+            conditions: OwnedSpendBundleConditions {
+                spends,
+                reserve_fee: fee,
+                cost,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn ids(ids: &[u8]) -> Vec<Bytes32> {
+        ids.iter().map(|id| Bytes32::new([*id; 32])).collect()
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(select_spends(&[], 1000), Selection::default());
+    }
+
+    // the items have a fee per cost of 1, 3 and 2
+    #[rstest]
+    #[case(0, &[])]
+    #[case(99, &[])]
+    #[case(100, &[2])]
+    #[case(199, &[2])]
+    #[case(200, &[2, 3])]
+    #[case(250, &[2, 3])]
+    #[case(300, &[2, 3, 1])]
+    fn test_fee_per_cost(#[case] max_cost: u64, #[case] expected: &[u8]) {
+        let items = [
+            candidate(1, 100, 100, vec![spend(&coin(1), &[])]),
+            candidate(2, 300, 100, vec![spend(&coin(2), &[])]),
+            candidate(3, 200, 100, vec![spend(&coin(3), &[])]),
+        ];
+        let selection = select_spends(&items, max_cost);
+        assert_eq!(selection.ids, ids(expected));
+        assert_eq!(selection.cost, expected.len() as u64 * 100);
+    }
+
+    #[test]
+    fn test_tie_break() {
+        // the same fee per cost, so the lowest id goes first
+        let items = [
+            candidate(3, 200, 200, vec![spend(&coin(3), &[])]),
+            candidate(1, 100, 100, vec![spend(&coin(1), &[])]),
+            candidate(2, 300, 300, vec![spend(&coin(2), &[])]),
+        ];
+        assert_eq!(select_spends(&items, 1000).ids, ids(&[1, 2, 3]));
+        assert_eq!(select_spends(&items, 500).ids, ids(&[1, 2]));
+    }
+
+    #[test]
+    fn test_dependency() {
+        // the child pays a high fee for spending the output of a parent that
+        // pays none, so the parent is included ahead of a better item
+        let output = child(&coin(1), PH, 400);
+        let items = [
+            candidate(1, 0, 100, vec![spend(&coin(1), &[(PH, 600), (PH, 400)])]),
+            candidate(2, 1000, 100, vec![spend(&output, &[])]),
+            candidate(3, 400, 100, vec![spend(&coin(3), &[])]),
+        ];
+        let selection = select_spends(&items, 200);
+        assert_eq!(selection.ids, ids(&[1, 2]));
+        assert_eq!(selection.fee, 1000);
+        assert_eq!(selection.cost, 200);
+
+        // the child doesn't fit without its parent
+        assert_eq!(select_spends(&items, 199).ids, ids(&[3]));
+        assert_eq!(select_spends(&items, 300).ids, ids(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn test_double_spend() {
+        // the replacement spends the same coin as the original, for a higher
+        // fee per cost, and the child of the original can't be included
+        // without it
+        let output = child(&coin(1), PH, 900);
+        let items = [
+            candidate(1, 100, 100, vec![spend(&coin(1), &[(PH, 900)])]),
+            candidate(2, 300, 100, vec![spend(&coin(1), &[(PH, 800)])]),
+            candidate(3, 100, 100, vec![spend(&output, &[])]),
+        ];
+        let selection = select_spends(&items, 1000);
+        assert_eq!(selection.ids, ids(&[2]));
+    }
+
+    #[test]
+    fn test_ephemeral_spend() {
+        // spending a coin created in the same item isn't a dependency
+        let ephemeral = child(&coin(1), PH, 500);
+        let items = [candidate(
+            1,
+            100,
+            100,
+            vec![spend(&coin(1), &[(PH, 500)]), spend(&ephemeral, &[])],
+        )];
+        assert_eq!(select_spends(&items, 100).ids, ids(&[1]));
+    }
+
+    // a random mempool, where items spend coins that exist, coins created by
+    // other items, and (rarely) coins spent by other items
+    fn random_items(rng: &mut SmallRng) -> Vec<MempoolCandidate> {
+        let mut coins: Vec<Coin> = (0..20).map(coin).collect();
+        let mut items = Vec::new();
+        for id in 0..rng.gen_range(1..60) {
+            let mut spends = Vec::new();
+            for _ in 0..rng.gen_range(1..3) {
+                let parent = if rng.gen_bool(0.1) {
+                    *coins.choose(rng).unwrap()
+                } else {
+                    let index = rng.gen_range(0..coins.len());
+                    coins.swap_remove(index)
+                };
+                let outputs: Vec<(Bytes32, u64)> = (0..rng.gen_range(0..3))
+                    .map(|_| (PH, rng.gen_range(1..1000)))
+                    .collect();
+                coins.extend(
+                    outputs
+                        .iter()
+                        .map(|(puzzle_hash, amount)| child(&parent, *puzzle_hash, *amount)),
+                );
+                spends.push(spend(&parent, &outputs));
+                if coins.is_empty() {
+                    coins.push(coin(100 + id));
+                }
+            }
+            items.push(candidate(
+                id,
+                rng.gen_range(0..10_000),
+                rng.gen_range(0..1_000),
+                spends,
+            ));
+        }
+        items
+    }
+
+    #[test]
+    fn test_random_selection() {
+        let mut rng = SmallRng::seed_from_u64(1337);
+        for _ in 0..200 {
+            let mut items = random_items(&mut rng);
+            let max_cost = rng.gen_range(0..20_000);
+            let selection = select_spends(&items, max_cost);
+
+            // never exceeds the max cost, and the totals add up
+            assert!(selection.cost <= max_cost);
+            let picked: Vec<&MempoolCandidate> = selection
+                .ids
+                .iter()
+                .map(|id| items.iter().find(|item| item.id == *id).unwrap())
+                .collect();
+            assert_eq!(
+                selection.cost,
+                picked.iter().map(|item| item.cost).sum::<u64>()
+            );
+            assert_eq!(
+                selection.fee,
+                picked.iter().map(|item| item.fee).sum::<u64>()
+            );
+
+            // no coin is spent twice, and every coin created by a candidate
+            // is created by an item picked earlier (or the same item)
+            let mut spent = HashSet::new();
+            let mut created = HashSet::new();
+            for item in &picked {
+                let additions: Vec<Bytes32> = item
+                    .conditions
+                    .spends
+                    .iter()
+                    .flat_map(created_coin_ids)
+                    .collect();
+                for spend in &item.conditions.spends {
+                    assert!(spent.insert(spend.coin_id));
+                    let from_candidate = items.iter().any(|other| {
+                        other
+                            .conditions
+                            .spends
+                            .iter()
+                            .flat_map(created_coin_ids)
+                            .any(|coin_id| coin_id == spend.coin_id)
+                    });
+                    if from_candidate {
+                        assert!(
+                            created.contains(&spend.coin_id) || additions.contains(&spend.coin_id)
+                        );
+                    }
+                }
+                created.extend(additions);
+            }
+
+            // the selection is deterministic, regardless of the order of
+            // the items
+            assert_eq!(select_spends(&items, max_cost), selection);
+            items.shuffle(&mut rng);
+            assert_eq!(select_spends(&items, max_cost), selection);
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::gen::condition_sanitizers::sanitize_hash;
-use crate::gen::sanitize_int::{sanitize_uint, SanitizedUint};
+use crate::gen::sanitize_int::{parse_u64_lenient, SanitizedUint};
 use crate::gen::validation_error::{first, rest, ErrorCode, ValidationErr};
 use chia_protocol::Bytes32;
 use clvmr::{Allocator, NodePtr};
@@ -58,7 +58,7 @@ impl SpendId {
         };
 
         let amount = if (mode & AMOUNT) != 0 {
-            let amount = match sanitize_uint(a, first(a, *args)?, 8, ErrorCode::InvalidCoinAmount)?
+            let amount = match parse_u64_lenient(a, first(a, *args)?, ErrorCode::InvalidCoinAmount)?
             {
                 SanitizedUint::PositiveOverflow => {
                     return Err(ValidationErr(*args, ErrorCode::CoinAmountExceedsMaximum));
@@ -173,6 +173,12 @@ impl Message {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gen::conditions::{parse_args, Condition};
+    use crate::gen::flags::STRICT_ARGS_COUNT;
+    use crate::gen::opcodes::{RECEIVE_MESSAGE, SEND_MESSAGE};
+    use chia_protocol::Bytes;
+    use chia_puzzles::{MessageMode, ReceiveMessage, SendMessage, SpendTarget};
+    use clvm_traits::ToClvm;
     use hex_literal::hex;
     use rstest::rstest;
 
@@ -268,4 +274,85 @@ mod tests {
         src.make_key(&mut key, &a);
         assert_eq!(key, hex::decode(expected).unwrap());
     }
+
+    // the key make_key() is expected to produce for the other side of a
+    // message, built by the wallet
+    fn target_key(target: SpendTarget) -> Vec<u8> {
+        let mut key = vec![target.mode().bits()];
+        match target {
+            SpendTarget::None => {}
+            SpendTarget::Amount(amount) => key.extend_from_slice(&amount.to_be_bytes()),
+            SpendTarget::Puzzle(hash) | SpendTarget::Parent(hash) | SpendTarget::Coin(hash) => {
+                key.extend_from_slice(&hash);
+            }
+            SpendTarget::PuzzleAmount(hash, amount) | SpendTarget::ParentAmount(hash, amount) => {
+                key.extend_from_slice(&hash);
+                key.extend_from_slice(&amount.to_be_bytes());
+            }
+            SpendTarget::ParentPuzzle(parent, puzzle) => {
+                key.extend_from_slice(&parent);
+                key.extend_from_slice(&puzzle);
+            }
+        }
+        key
+    }
+
+    // conditions built with the chia-puzzles helpers must parse in the node,
+    // with the same modes and targets
+    #[rstest]
+    fn test_wallet_messages(
+        #[values(
+            SpendTarget::None,
+            SpendTarget::Amount(1337),
+            SpendTarget::Puzzle(Bytes32::new(BUF1)),
+            SpendTarget::PuzzleAmount(Bytes32::new(BUF1), 1337),
+            SpendTarget::Parent(Bytes32::new(BUF0)),
+            SpendTarget::ParentAmount(Bytes32::new(BUF0), 1337),
+            SpendTarget::ParentPuzzle(Bytes32::new(BUF0), Bytes32::new(BUF1)),
+            SpendTarget::Coin(Bytes32::new(BUF2))
+        )]
+        target: SpendTarget,
+        #[values(
+            MessageMode::None,
+            MessageMode::Amount,
+            MessageMode::Puzzle,
+            MessageMode::PuzzleAmount,
+            MessageMode::Parent,
+            MessageMode::ParentAmount,
+            MessageMode::ParentPuzzle,
+            MessageMode::Coin
+        )]
+        self_mode: MessageMode,
+    ) {
+        let mut a = Allocator::new();
+        let msg = Bytes::new(b"foobar".to_vec());
+
+        let send = SendMessage::new(self_mode, target, msg.clone()).unwrap();
+        let cond = send.to_clvm(&mut a).unwrap();
+        let Condition::SendMessage(src_mode, dst, _) =
+            parse_args(&a, rest(&a, cond).unwrap(), SEND_MESSAGE, STRICT_ARGS_COUNT).unwrap()
+        else {
+            panic!("expected SEND_MESSAGE");
+        };
+        assert_eq!(src_mode, self_mode.bits());
+        let mut key = Vec::<u8>::new();
+        dst.make_key(&mut key, &a);
+        assert_eq!(key, target_key(target));
+
+        let receive = ReceiveMessage::new(target, self_mode, msg).unwrap();
+        let cond = receive.to_clvm(&mut a).unwrap();
+        let Condition::ReceiveMessage(src, dst_mode, _) = parse_args(
+            &a,
+            rest(&a, cond).unwrap(),
+            RECEIVE_MESSAGE,
+            STRICT_ARGS_COUNT,
+        )
+        .unwrap() else {
+            panic!("expected RECEIVE_MESSAGE");
+        };
+        assert_eq!(dst_mode, self_mode.bits());
+        let mut key = Vec::<u8>::new();
+        src.make_key(&mut key, &a);
+        assert_eq!(key, target_key(target));
+    }
 }
@@ -1,10 +1,15 @@
 pub mod additions_and_removals;
+pub mod cancellation;
 mod coin_id;
 mod condition_sanitizers;
 pub mod conditions;
+pub mod conditions_cache;
+pub mod conflicts;
 pub mod flags;
+pub mod generator_refs;
 pub mod get_puzzle_and_solution;
 pub mod make_aggsig_final_message;
+pub mod mempool_selection;
 pub mod messages;
 pub mod opcodes;
 pub mod owned_conditions;
@@ -12,7 +17,12 @@ pub mod run_block_generator;
 pub mod sanitize_int;
 pub mod solution_generator;
 pub mod spend_visitor;
+mod trace;
 pub mod validation_error;
+pub mod validation_metrics;
+
+#[cfg(test)]
+pub(crate) mod test_helpers;
 
 // these tests are large and expensive. They take a long time to run in
 // unoptimized builds. Only run these with --release
@@ -1,69 +1,196 @@
+use crate::gen::validation_error::ErrorCode;
 use clvmr::allocator::{Allocator, NodePtr, SExp};
 use clvmr::cost::Cost;
+use std::cmp::Ordering;
+use std::fmt;
 
-pub type ConditionOpcode = u16;
-
-// AGG_SIG is ascii "1"
-pub const AGG_SIG_PARENT: ConditionOpcode = 43;
-pub const AGG_SIG_PUZZLE: ConditionOpcode = 44;
-pub const AGG_SIG_AMOUNT: ConditionOpcode = 45;
-pub const AGG_SIG_PUZZLE_AMOUNT: ConditionOpcode = 46;
-pub const AGG_SIG_PARENT_AMOUNT: ConditionOpcode = 47;
-pub const AGG_SIG_PARENT_PUZZLE: ConditionOpcode = 48;
-pub const AGG_SIG_UNSAFE: ConditionOpcode = 49;
-pub const AGG_SIG_ME: ConditionOpcode = 50;
-
-// the conditions below reserve coin amounts and have to be accounted for in
-// output totals
-pub const CREATE_COIN: ConditionOpcode = 51;
-pub const RESERVE_FEE: ConditionOpcode = 52;
-
-// the conditions below deal with announcements, for inter-coin communication
-pub const CREATE_COIN_ANNOUNCEMENT: ConditionOpcode = 60;
-pub const ASSERT_COIN_ANNOUNCEMENT: ConditionOpcode = 61;
-pub const CREATE_PUZZLE_ANNOUNCEMENT: ConditionOpcode = 62;
-pub const ASSERT_PUZZLE_ANNOUNCEMENT: ConditionOpcode = 63;
-pub const ASSERT_CONCURRENT_SPEND: ConditionOpcode = 64;
-pub const ASSERT_CONCURRENT_PUZZLE: ConditionOpcode = 65;
-
-pub const SEND_MESSAGE: ConditionOpcode = 66;
-pub const RECEIVE_MESSAGE: ConditionOpcode = 67;
-
-// the conditions below let coins inquire about themselves
-pub const ASSERT_MY_COIN_ID: ConditionOpcode = 70;
-pub const ASSERT_MY_PARENT_ID: ConditionOpcode = 71;
-pub const ASSERT_MY_PUZZLEHASH: ConditionOpcode = 72;
-pub const ASSERT_MY_AMOUNT: ConditionOpcode = 73;
-pub const ASSERT_MY_BIRTH_SECONDS: ConditionOpcode = 74;
-pub const ASSERT_MY_BIRTH_HEIGHT: ConditionOpcode = 75;
-pub const ASSERT_EPHEMERAL: ConditionOpcode = 76;
-
-// the conditions below ensure that we're "far enough" in the future
-// wall-clock time
-pub const ASSERT_SECONDS_RELATIVE: ConditionOpcode = 80;
-pub const ASSERT_SECONDS_ABSOLUTE: ConditionOpcode = 81;
-
-// block index
-pub const ASSERT_HEIGHT_RELATIVE: ConditionOpcode = 82;
-pub const ASSERT_HEIGHT_ABSOLUTE: ConditionOpcode = 83;
-
-// spend is not valid if block timestamp exceeds the specified one
-pub const ASSERT_BEFORE_SECONDS_RELATIVE: ConditionOpcode = 84;
-pub const ASSERT_BEFORE_SECONDS_ABSOLUTE: ConditionOpcode = 85;
-
-// spend is not valid if block height exceeds the specified height
-pub const ASSERT_BEFORE_HEIGHT_RELATIVE: ConditionOpcode = 86;
-pub const ASSERT_BEFORE_HEIGHT_ABSOLUTE: ConditionOpcode = 87;
-
-// no-op condition
-pub const REMARK: ConditionOpcode = 1;
-
-// takes its cost as the first parameter, followed by future extensions
-// the cost is specified in increments of 10000, to keep the values smaller
-pub const SOFTFORK: ConditionOpcode = 90;
+macro_rules! condition_opcodes {
+    ($($(#[$meta:meta])* $name:ident = $variant:ident($value:literal),)*) => {
+        /// The opcode of a condition, i.e. the first item of a condition
+        /// returned by a puzzle. The discriminants are the opcodes as they
+        /// appear in CLVM. 2-byte opcodes (256 and above) that don't have a
+        /// meaning yet are `Unknown`. They are ignored, but have a cost, to
+        /// allow soft-forking in new conditions. Every opcode has exactly one
+        /// representation, so `Unknown` can only be created by `try_from()`.
+        #[non_exhaustive]
+        #[repr(u16)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ConditionOpcode {
+            $($(#[$meta])* $variant = $value,)*
+            Unknown(UnknownOpcode),
+        }
+
+        // these predate the enum, and are kept for compatibility
+        $(pub const $name: ConditionOpcode = ConditionOpcode::$variant;)*
+
+        impl ConditionOpcode {
+            $(pub const $name: Self = Self::$variant;)*
+        }
+
+        impl TryFrom<u16> for ConditionOpcode {
+            type Error = ErrorCode;
+
+            fn try_from(value: u16) -> Result<Self, ErrorCode> {
+                match value {
+                    $($value => Ok(Self::$variant),)*
+                    256..=u16::MAX => Ok(Self::Unknown(UnknownOpcode(value))),
+                    _ => Err(ErrorCode::InvalidConditionOpcode),
+                }
+            }
+        }
+
+        impl From<ConditionOpcode> for u16 {
+            fn from(op: ConditionOpcode) -> u16 {
+                match op {
+                    $(ConditionOpcode::$variant => $value,)*
+                    ConditionOpcode::Unknown(op) => op.0,
+                }
+            }
+        }
+    };
+}
+
+/// A 2-byte condition opcode that doesn't have a meaning yet. It can't be
+/// created with a value that belongs to one of the named opcodes, which keeps
+/// the derived `Eq` and `Hash` of `ConditionOpcode` consistent with its `Ord`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnknownOpcode(u16);
+
+impl From<UnknownOpcode> for u16 {
+    fn from(op: UnknownOpcode) -> u16 {
+        op.0
+    }
+}
+
+condition_opcodes! {
+    // no-op condition
+    REMARK = Remark(1),
+
+    // AGG_SIG is ascii "1"
+    AGG_SIG_PARENT = AggSigParent(43),
+    AGG_SIG_PUZZLE = AggSigPuzzle(44),
+    AGG_SIG_AMOUNT = AggSigAmount(45),
+    AGG_SIG_PUZZLE_AMOUNT = AggSigPuzzleAmount(46),
+    AGG_SIG_PARENT_AMOUNT = AggSigParentAmount(47),
+    AGG_SIG_PARENT_PUZZLE = AggSigParentPuzzle(48),
+    AGG_SIG_UNSAFE = AggSigUnsafe(49),
+    AGG_SIG_ME = AggSigMe(50),
+
+    // the conditions below reserve coin amounts and have to be accounted for in
+    // output totals
+    CREATE_COIN = CreateCoin(51),
+    RESERVE_FEE = ReserveFee(52),
+
+    // the conditions below deal with announcements, for inter-coin communication
+    CREATE_COIN_ANNOUNCEMENT = CreateCoinAnnouncement(60),
+    ASSERT_COIN_ANNOUNCEMENT = AssertCoinAnnouncement(61),
+    CREATE_PUZZLE_ANNOUNCEMENT = CreatePuzzleAnnouncement(62),
+    ASSERT_PUZZLE_ANNOUNCEMENT = AssertPuzzleAnnouncement(63),
+    ASSERT_CONCURRENT_SPEND = AssertConcurrentSpend(64),
+    ASSERT_CONCURRENT_PUZZLE = AssertConcurrentPuzzle(65),
+
+    SEND_MESSAGE = SendMessage(66),
+    RECEIVE_MESSAGE = ReceiveMessage(67),
+
+    // the conditions below let coins inquire about themselves
+    ASSERT_MY_COIN_ID = AssertMyCoinId(70),
+    ASSERT_MY_PARENT_ID = AssertMyParentId(71),
+    ASSERT_MY_PUZZLEHASH = AssertMyPuzzlehash(72),
+    ASSERT_MY_AMOUNT = AssertMyAmount(73),
+    ASSERT_MY_BIRTH_SECONDS = AssertMyBirthSeconds(74),
+    ASSERT_MY_BIRTH_HEIGHT = AssertMyBirthHeight(75),
+    ASSERT_EPHEMERAL = AssertEphemeral(76),
+
+    // the conditions below ensure that we're "far enough" in the future
+    // wall-clock time
+    ASSERT_SECONDS_RELATIVE = AssertSecondsRelative(80),
+    ASSERT_SECONDS_ABSOLUTE = AssertSecondsAbsolute(81),
+
+    // block index
+    ASSERT_HEIGHT_RELATIVE = AssertHeightRelative(82),
+    ASSERT_HEIGHT_ABSOLUTE = AssertHeightAbsolute(83),
+
+    // spend is not valid if block timestamp exceeds the specified one
+    ASSERT_BEFORE_SECONDS_RELATIVE = AssertBeforeSecondsRelative(84),
+    ASSERT_BEFORE_SECONDS_ABSOLUTE = AssertBeforeSecondsAbsolute(85),
+
+    // spend is not valid if block height exceeds the specified height
+    ASSERT_BEFORE_HEIGHT_RELATIVE = AssertBeforeHeightRelative(86),
+    ASSERT_BEFORE_HEIGHT_ABSOLUTE = AssertBeforeHeightAbsolute(87),
+
+    // takes its cost as the first parameter, followed by future extensions
+    // the cost is specified in increments of 10000, to keep the values smaller
+    SOFTFORK = Softfork(90),
+}
+
+impl ConditionOpcode {
+    /// Whether this is one of the AGG_SIG_* conditions, which require the
+    /// spend bundle to be signed.
+    pub fn is_agg_sig(self) -> bool {
+        matches!(
+            self,
+            Self::AggSigParent
+                | Self::AggSigPuzzle
+                | Self::AggSigAmount
+                | Self::AggSigPuzzleAmount
+                | Self::AggSigParentAmount
+                | Self::AggSigParentPuzzle
+                | Self::AggSigUnsafe
+                | Self::AggSigMe
+        )
+    }
+
+    /// The cost of a condition with this opcode. SOFTFORK takes its cost as
+    /// its first argument (see `softfork_cost()`), so it doesn't have one.
+    pub fn cost(self) -> Option<Cost> {
+        match self {
+            Self::CreateCoin => Some(CREATE_COIN_COST),
+            op if op.is_agg_sig() => Some(AGG_SIG_COST),
+            Self::Softfork => None,
+            Self::Unknown(op) => Some(compute_unknown_condition_cost(op.into())),
+            _ => Some(0),
+        }
+    }
+
+    /// The opcode the way it's encoded in CLVM, which is what
+    /// `parse_opcode()` expects.
+    pub fn to_bytes(self) -> Vec<u8> {
+        let op = u16::from(self);
+        if op < 256 {
+            vec![op as u8]
+        } else {
+            op.to_be_bytes().to_vec()
+        }
+    }
+}
+
+impl Ord for ConditionOpcode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        u16::from(*self).cmp(&u16::from(*other))
+    }
+}
+
+impl PartialOrd for ConditionOpcode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// conditions are printed the way they appear in CLVM
+impl fmt::Display for ConditionOpcode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", u16::from(*self))
+    }
+}
 
 pub const CREATE_COIN_COST: Cost = 1_800_000;
 pub const AGG_SIG_COST: Cost = 1_200_000;
+pub const SOFTFORK_COST_FACTOR: Cost = 10000;
+
+/// The cost of a SOFTFORK condition whose first argument is `cost`.
+pub fn softfork_cost(cost: u32) -> Cost {
+    Cost::from(cost) * SOFTFORK_COST_FACTOR
+}
 
 // 2-byte condition opcodes have costs according to this table:
 
@@ -96,7 +223,7 @@ const fn calculate_cost_table() -> [u64; 256] {
 
 const COSTS: [Cost; 256] = calculate_cost_table();
 
-pub fn compute_unknown_condition_cost(op: ConditionOpcode) -> Cost {
+pub fn compute_unknown_condition_cost(op: u16) -> Cost {
     if op < 256 {
         0
     } else {
@@ -109,58 +236,15 @@ pub fn parse_opcode(a: &Allocator, op: NodePtr, _flags: u32) -> Option<Condition
         SExp::Atom => a.atom(op),
         SExp::Pair(..) => return None,
     };
-    let buf = buf.as_ref();
-    if buf.len() == 2 {
-        if buf[0] == 0 {
-            // no redundant leading zeroes
-            None
-        } else {
-            // These are 2-byte condition codes whose first byte is non-zero
-            Some(ConditionOpcode::from_be_bytes(buf.try_into().unwrap()))
-        }
-    } else if buf.len() == 1 {
-        let b0 = ConditionOpcode::from(buf[0]);
-        match b0 {
-            AGG_SIG_UNSAFE
-            | AGG_SIG_ME
-            | CREATE_COIN
-            | RESERVE_FEE
-            | CREATE_COIN_ANNOUNCEMENT
-            | ASSERT_COIN_ANNOUNCEMENT
-            | CREATE_PUZZLE_ANNOUNCEMENT
-            | ASSERT_PUZZLE_ANNOUNCEMENT
-            | ASSERT_MY_COIN_ID
-            | ASSERT_MY_PARENT_ID
-            | ASSERT_MY_PUZZLEHASH
-            | ASSERT_MY_AMOUNT
-            | ASSERT_SECONDS_RELATIVE
-            | ASSERT_SECONDS_ABSOLUTE
-            | ASSERT_HEIGHT_RELATIVE
-            | ASSERT_HEIGHT_ABSOLUTE
-            | REMARK
-            | ASSERT_BEFORE_SECONDS_RELATIVE
-            | ASSERT_BEFORE_SECONDS_ABSOLUTE
-            | ASSERT_BEFORE_HEIGHT_RELATIVE
-            | ASSERT_BEFORE_HEIGHT_ABSOLUTE
-            | ASSERT_CONCURRENT_SPEND
-            | ASSERT_CONCURRENT_PUZZLE
-            | ASSERT_MY_BIRTH_SECONDS
-            | ASSERT_MY_BIRTH_HEIGHT
-            | ASSERT_EPHEMERAL
-            | SOFTFORK
-            | AGG_SIG_PARENT
-            | AGG_SIG_PUZZLE
-            | AGG_SIG_AMOUNT
-            | AGG_SIG_PUZZLE_AMOUNT
-            | AGG_SIG_PARENT_AMOUNT
-            | AGG_SIG_PARENT_PUZZLE
-            | SEND_MESSAGE
-            | RECEIVE_MESSAGE => Some(b0),
-            _ => None,
-        }
-    } else {
-        None
-    }
+    let op = match buf.as_ref() {
+        // no redundant leading zeroes
+        [0, _] => return None,
+        // These are 2-byte condition codes whose first byte is non-zero
+        [b0, b1] => u16::from_be_bytes([*b0, *b1]),
+        [b0] => u16::from(*b0),
+        _ => return None,
+    };
+    ConditionOpcode::try_from(op).ok()
 }
 
 #[cfg(test)]
@@ -175,36 +259,36 @@ use rstest::rstest;
 #[cfg(test)]
 #[rstest]
 // leading zeros are not allowed, it makes it a different value
-#[case(&[ASSERT_HEIGHT_ABSOLUTE as u8, 0, 0], None)]
-#[case(&[0, ASSERT_HEIGHT_ABSOLUTE as u8], None)]
+#[case(&[83, 0, 0], None)]
+#[case(&[0, 83], None)]
 #[case(&[0], None)]
 // all condition codes
-#[case(&[AGG_SIG_UNSAFE as u8], Some(AGG_SIG_UNSAFE))]
-#[case(&[AGG_SIG_ME as u8], Some(AGG_SIG_ME))]
-#[case(&[CREATE_COIN as u8], Some(CREATE_COIN))]
-#[case(&[RESERVE_FEE as u8], Some(RESERVE_FEE))]
-#[case(&[CREATE_COIN_ANNOUNCEMENT as u8], Some(CREATE_COIN_ANNOUNCEMENT))]
-#[case(&[ASSERT_COIN_ANNOUNCEMENT as u8], Some(ASSERT_COIN_ANNOUNCEMENT))]
-#[case(&[CREATE_PUZZLE_ANNOUNCEMENT as u8], Some(CREATE_PUZZLE_ANNOUNCEMENT))]
-#[case(&[ASSERT_PUZZLE_ANNOUNCEMENT as u8], Some(ASSERT_PUZZLE_ANNOUNCEMENT))]
-#[case(&[ASSERT_CONCURRENT_SPEND as u8], Some(ASSERT_CONCURRENT_SPEND))]
-#[case(&[ASSERT_CONCURRENT_PUZZLE as u8], Some(ASSERT_CONCURRENT_PUZZLE))]
-#[case(&[ASSERT_MY_COIN_ID as u8], Some(ASSERT_MY_COIN_ID))]
-#[case(&[ASSERT_MY_PARENT_ID as u8], Some(ASSERT_MY_PARENT_ID))]
-#[case(&[ASSERT_MY_PUZZLEHASH as u8], Some(ASSERT_MY_PUZZLEHASH))]
-#[case(&[ASSERT_MY_AMOUNT as u8], Some(ASSERT_MY_AMOUNT))]
-#[case(&[ASSERT_MY_BIRTH_SECONDS as u8], Some(ASSERT_MY_BIRTH_SECONDS))]
-#[case(&[ASSERT_MY_BIRTH_HEIGHT as u8], Some(ASSERT_MY_BIRTH_HEIGHT))]
-#[case(&[ASSERT_EPHEMERAL as u8], Some(ASSERT_EPHEMERAL))]
-#[case(&[ASSERT_SECONDS_RELATIVE as u8], Some(ASSERT_SECONDS_RELATIVE))]
-#[case(&[ASSERT_SECONDS_ABSOLUTE as u8], Some(ASSERT_SECONDS_ABSOLUTE))]
-#[case(&[ASSERT_HEIGHT_RELATIVE as u8], Some(ASSERT_HEIGHT_RELATIVE))]
-#[case(&[ASSERT_HEIGHT_ABSOLUTE as u8], Some(ASSERT_HEIGHT_ABSOLUTE))]
-#[case(&[ASSERT_BEFORE_SECONDS_RELATIVE as u8], Some(ASSERT_BEFORE_SECONDS_RELATIVE))]
-#[case(&[ASSERT_BEFORE_SECONDS_ABSOLUTE as u8], Some(ASSERT_BEFORE_SECONDS_ABSOLUTE))]
-#[case(&[ASSERT_BEFORE_HEIGHT_RELATIVE as u8], Some(ASSERT_BEFORE_HEIGHT_RELATIVE))]
-#[case(&[ASSERT_BEFORE_HEIGHT_ABSOLUTE as u8], Some(ASSERT_BEFORE_HEIGHT_ABSOLUTE))]
-#[case(&[REMARK as u8], Some(REMARK))]
+#[case(&AGG_SIG_UNSAFE.to_bytes(), Some(AGG_SIG_UNSAFE))]
+#[case(&AGG_SIG_ME.to_bytes(), Some(AGG_SIG_ME))]
+#[case(&CREATE_COIN.to_bytes(), Some(CREATE_COIN))]
+#[case(&RESERVE_FEE.to_bytes(), Some(RESERVE_FEE))]
+#[case(&CREATE_COIN_ANNOUNCEMENT.to_bytes(), Some(CREATE_COIN_ANNOUNCEMENT))]
+#[case(&ASSERT_COIN_ANNOUNCEMENT.to_bytes(), Some(ASSERT_COIN_ANNOUNCEMENT))]
+#[case(&CREATE_PUZZLE_ANNOUNCEMENT.to_bytes(), Some(CREATE_PUZZLE_ANNOUNCEMENT))]
+#[case(&ASSERT_PUZZLE_ANNOUNCEMENT.to_bytes(), Some(ASSERT_PUZZLE_ANNOUNCEMENT))]
+#[case(&ASSERT_CONCURRENT_SPEND.to_bytes(), Some(ASSERT_CONCURRENT_SPEND))]
+#[case(&ASSERT_CONCURRENT_PUZZLE.to_bytes(), Some(ASSERT_CONCURRENT_PUZZLE))]
+#[case(&ASSERT_MY_COIN_ID.to_bytes(), Some(ASSERT_MY_COIN_ID))]
+#[case(&ASSERT_MY_PARENT_ID.to_bytes(), Some(ASSERT_MY_PARENT_ID))]
+#[case(&ASSERT_MY_PUZZLEHASH.to_bytes(), Some(ASSERT_MY_PUZZLEHASH))]
+#[case(&ASSERT_MY_AMOUNT.to_bytes(), Some(ASSERT_MY_AMOUNT))]
+#[case(&ASSERT_MY_BIRTH_SECONDS.to_bytes(), Some(ASSERT_MY_BIRTH_SECONDS))]
+#[case(&ASSERT_MY_BIRTH_HEIGHT.to_bytes(), Some(ASSERT_MY_BIRTH_HEIGHT))]
+#[case(&ASSERT_EPHEMERAL.to_bytes(), Some(ASSERT_EPHEMERAL))]
+#[case(&ASSERT_SECONDS_RELATIVE.to_bytes(), Some(ASSERT_SECONDS_RELATIVE))]
+#[case(&ASSERT_SECONDS_ABSOLUTE.to_bytes(), Some(ASSERT_SECONDS_ABSOLUTE))]
+#[case(&ASSERT_HEIGHT_RELATIVE.to_bytes(), Some(ASSERT_HEIGHT_RELATIVE))]
+#[case(&ASSERT_HEIGHT_ABSOLUTE.to_bytes(), Some(ASSERT_HEIGHT_ABSOLUTE))]
+#[case(&ASSERT_BEFORE_SECONDS_RELATIVE.to_bytes(), Some(ASSERT_BEFORE_SECONDS_RELATIVE))]
+#[case(&ASSERT_BEFORE_SECONDS_ABSOLUTE.to_bytes(), Some(ASSERT_BEFORE_SECONDS_ABSOLUTE))]
+#[case(&ASSERT_BEFORE_HEIGHT_RELATIVE.to_bytes(), Some(ASSERT_BEFORE_HEIGHT_RELATIVE))]
+#[case(&ASSERT_BEFORE_HEIGHT_ABSOLUTE.to_bytes(), Some(ASSERT_BEFORE_HEIGHT_ABSOLUTE))]
+#[case(&REMARK.to_bytes(), Some(REMARK))]
 fn test_parse_opcode(#[case] input: &[u8], #[case] expected: Option<ConditionOpcode>) {
     let mut a = Allocator::new();
     assert_eq!(opcode_tester(&mut a, input, 0), expected);
@@ -213,21 +297,21 @@ fn test_parse_opcode(#[case] input: &[u8], #[case] expected: Option<ConditionOpc
 
 #[cfg(test)]
 #[rstest]
-#[case(&[AGG_SIG_UNSAFE as u8], Some(AGG_SIG_UNSAFE))]
-#[case(&[AGG_SIG_ME as u8], Some(AGG_SIG_ME))]
-#[case(&[CREATE_COIN as u8], Some(CREATE_COIN))]
+#[case(&AGG_SIG_UNSAFE.to_bytes(), Some(AGG_SIG_UNSAFE))]
+#[case(&AGG_SIG_ME.to_bytes(), Some(AGG_SIG_ME))]
+#[case(&CREATE_COIN.to_bytes(), Some(CREATE_COIN))]
 // the SOFTOFORK and new AGG_SIG_* condition is only recognized when the flag is set
-#[case(&[SOFTFORK as u8], Some(SOFTFORK))]
-#[case(&[AGG_SIG_PARENT as u8], Some(AGG_SIG_PARENT))]
-#[case(&[AGG_SIG_PUZZLE as u8], Some(AGG_SIG_PUZZLE))]
-#[case(&[AGG_SIG_AMOUNT as u8], Some(AGG_SIG_AMOUNT))]
-#[case(&[AGG_SIG_PUZZLE_AMOUNT as u8], Some(AGG_SIG_PUZZLE_AMOUNT))]
-#[case(&[AGG_SIG_PARENT_AMOUNT as u8], Some(AGG_SIG_PARENT_AMOUNT))]
-#[case(&[AGG_SIG_PARENT_PUZZLE as u8], Some(AGG_SIG_PARENT_PUZZLE))]
-#[case(&[ASSERT_EPHEMERAL as u8], Some(ASSERT_EPHEMERAL))]
-#[case(&[ASSERT_BEFORE_SECONDS_RELATIVE as u8], Some(ASSERT_BEFORE_SECONDS_RELATIVE))]
-#[case(&[SEND_MESSAGE as u8], Some(SEND_MESSAGE))]
-#[case(&[RECEIVE_MESSAGE as u8], Some(RECEIVE_MESSAGE))]
+#[case(&SOFTFORK.to_bytes(), Some(SOFTFORK))]
+#[case(&AGG_SIG_PARENT.to_bytes(), Some(AGG_SIG_PARENT))]
+#[case(&AGG_SIG_PUZZLE.to_bytes(), Some(AGG_SIG_PUZZLE))]
+#[case(&AGG_SIG_AMOUNT.to_bytes(), Some(AGG_SIG_AMOUNT))]
+#[case(&AGG_SIG_PUZZLE_AMOUNT.to_bytes(), Some(AGG_SIG_PUZZLE_AMOUNT))]
+#[case(&AGG_SIG_PARENT_AMOUNT.to_bytes(), Some(AGG_SIG_PARENT_AMOUNT))]
+#[case(&AGG_SIG_PARENT_PUZZLE.to_bytes(), Some(AGG_SIG_PARENT_PUZZLE))]
+#[case(&ASSERT_EPHEMERAL.to_bytes(), Some(ASSERT_EPHEMERAL))]
+#[case(&ASSERT_BEFORE_SECONDS_RELATIVE.to_bytes(), Some(ASSERT_BEFORE_SECONDS_RELATIVE))]
+#[case(&SEND_MESSAGE.to_bytes(), Some(SEND_MESSAGE))]
+#[case(&RECEIVE_MESSAGE.to_bytes(), Some(RECEIVE_MESSAGE))]
 fn test_parse_opcode_softfork(#[case] input: &[u8], #[case] expected: Option<ConditionOpcode>) {
     let mut a = Allocator::new();
     assert_eq!(opcode_tester(&mut a, input, 0), expected);
@@ -242,3 +326,72 @@ fn test_parse_invalid_opcode() {
     let p = a.new_pair(v1, v2).unwrap();
     assert_eq!(parse_opcode(&a, p, 0), None);
 }
+
+#[test]
+fn test_opcode_round_trip() {
+    let mut a = Allocator::new();
+    for value in 0..=u16::MAX {
+        match ConditionOpcode::try_from(value) {
+            Ok(op) => {
+                assert_eq!(u16::from(op), value);
+                assert_eq!(op.to_string(), value.to_string());
+                assert_eq!(opcode_tester(&mut a, &op.to_bytes(), 0), Some(op));
+            }
+            Err(code) => {
+                assert_eq!(code, ErrorCode::InvalidConditionOpcode);
+                assert!(value < 256);
+                assert_eq!(opcode_tester(&mut a, &[value as u8], 0), None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[rstest]
+#[case(CREATE_COIN, Some(CREATE_COIN_COST))]
+#[case(AGG_SIG_UNSAFE, Some(AGG_SIG_COST))]
+#[case(AGG_SIG_ME, Some(AGG_SIG_COST))]
+#[case(AGG_SIG_PARENT_PUZZLE, Some(AGG_SIG_COST))]
+#[case(REMARK, Some(0))]
+#[case(RESERVE_FEE, Some(0))]
+#[case(ASSERT_HEIGHT_RELATIVE, Some(0))]
+#[case(SOFTFORK, None)]
+fn test_opcode_cost(#[case] op: ConditionOpcode, #[case] expected: Option<Cost>) {
+    assert_eq!(op.cost(), expected);
+}
+
+#[cfg(test)]
+#[rstest]
+#[case(0x0100, 100)]
+#[case(0x0101, 106)]
+#[case(0xff00, 100)]
+fn test_unknown_opcode_cost(#[case] value: u16, #[case] expected: Cost) {
+    let op = ConditionOpcode::try_from(value).unwrap();
+    assert!(matches!(op, ConditionOpcode::Unknown(_)));
+    assert_eq!(op.cost(), Some(expected));
+}
+
+#[test]
+fn test_opcode_ord_matches_eq() {
+    use std::collections::{BTreeSet, HashSet};
+    // every value has a single representation, so ordering, equality and
+    // hashing all agree
+    let ops: Vec<ConditionOpcode> = (0..=u16::MAX)
+        .filter_map(|v| ConditionOpcode::try_from(v).ok())
+        .collect();
+    for pair in ops.windows(2) {
+        assert!(pair[0] < pair[1]);
+        assert_ne!(pair[0], pair[1]);
+    }
+    let doubled: Vec<ConditionOpcode> = ops.iter().chain(ops.iter()).copied().collect();
+    assert_eq!(doubled.iter().collect::<BTreeSet<_>>().len(), ops.len());
+    assert_eq!(doubled.iter().collect::<HashSet<_>>().len(), ops.len());
+    assert_eq!(ConditionOpcode::try_from(51), Ok(CREATE_COIN));
+}
+
+#[test]
+fn test_softfork_cost() {
+    assert_eq!(softfork_cost(0), 0);
+    assert_eq!(softfork_cost(2), 20000);
+    assert_eq!(softfork_cost(u32::MAX), u64::from(u32::MAX) * 10000);
+}
@@ -2,8 +2,11 @@ use chia_bls::PublicKey;
 use chia_protocol::{Bytes, Bytes32};
 use chia_streamable_macro::Streamable;
 use clvmr::{Allocator, NodePtr};
+use std::cmp::Ordering;
 
-use super::conditions::{SpendBundleConditions, SpendConditions};
+use super::conditions::{
+    SpendBundleConditions, SpendConditions, ELIGIBLE_FOR_DEDUP, ELIGIBLE_FOR_FF,
+};
 
 #[cfg(feature = "py-bindings")]
 use chia_py_streamable_macro::{PyJsonDict, PyStreamable};
@@ -15,7 +18,8 @@ use pyo3::prelude::*;
 #[cfg(feature = "py-bindings")]
 use pyo3::types::PyType;
 
-#[derive(Streamable, Hash, Debug, Clone, Eq, PartialEq)]
+#[derive(Streamable, Hash, Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(
     feature = "py-bindings",
     pyo3::pyclass(name = "SpendConditions", get_all, frozen),
@@ -43,7 +47,8 @@ pub struct OwnedSpendConditions {
     pub flags: u32,
 }
 
-#[derive(Streamable, Hash, Debug, Clone, Eq, PartialEq)]
+#[derive(Streamable, Hash, Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(
     feature = "py-bindings",
     pyo3::pyclass(name = "SpendBundleConditions", get_all, frozen),
@@ -79,7 +84,7 @@ impl OwnedSpendConditions {
         for c in spend.create_coin {
             create_coin.push((
                 c.puzzle_hash,
-                c.amount,
+                c.amount.into(),
                 if c.hint == a.nil() {
                     None
                 } else {
@@ -100,7 +105,7 @@ impl OwnedSpendConditions {
                 .as_ref()
                 .try_into()
                 .expect("OwnedSpend internal error (puzzle_hash)"),
-            coin_amount: spend.coin_amount,
+            coin_amount: spend.coin_amount.into(),
             height_relative: spend.height_relative,
             seconds_relative: spend.seconds_relative,
             before_height_relative: spend.before_height_relative,
@@ -148,6 +153,106 @@ impl OwnedSpendBundleConditions {
     }
 }
 
+impl OwnedSpendConditions {
+    /// Sorts the conditions of this spend into canonical order. Every list
+    /// holds the conditions of a single opcode, and is sorted by the
+    /// condition arguments. `CREATE_COIN` conditions are sorted by puzzle
+    /// hash, then amount, then hint. `AGG_SIG_*` conditions are sorted by the
+    /// (compressed) public key, then the message. The order of conditions
+    /// doesn't affect the validity or the cost of a spend.
+    pub fn normalize(&mut self) {
+        self.create_coin.sort();
+        sort_agg_sigs(&mut self.agg_sig_me);
+        sort_agg_sigs(&mut self.agg_sig_parent);
+        sort_agg_sigs(&mut self.agg_sig_puzzle);
+        sort_agg_sigs(&mut self.agg_sig_amount);
+        sort_agg_sigs(&mut self.agg_sig_puzzle_amount);
+        sort_agg_sigs(&mut self.agg_sig_parent_amount);
+        sort_agg_sigs(&mut self.agg_sig_parent_puzzle);
+    }
+
+    /// Whether the mempool may deduplicate this spend, i.e. include a single
+    /// copy of it for several spend bundles (see `ELIGIBLE_FOR_DEDUP`).
+    pub fn eligible_for_dedup(&self) -> bool {
+        (self.flags & ELIGIBLE_FOR_DEDUP) != 0
+    }
+
+    /// Whether this looks like a singleton spend that the mempool may
+    /// fast-forward to the latest version of the singleton (see
+    /// `ELIGIBLE_FOR_FF`).
+    pub fn eligible_for_fast_forward(&self) -> bool {
+        (self.flags & ELIGIBLE_FOR_FF) != 0
+    }
+}
+
+/// Spends are ordered by coin ID (lexicographically). Since a coin can only
+/// be spent once, that's enough to order the spends of a valid spend bundle.
+/// The remaining fields are compared in declaration order, to break ties
+/// consistently with `Eq`. Lists of conditions are compared element by
+/// element, the same way they are sorted by `normalize()`.
+impl Ord for OwnedSpendConditions {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.coin_id
+            .cmp(&other.coin_id)
+            .then_with(|| self.parent_id.cmp(&other.parent_id))
+            .then_with(|| self.puzzle_hash.cmp(&other.puzzle_hash))
+            .then_with(|| self.coin_amount.cmp(&other.coin_amount))
+            .then_with(|| self.height_relative.cmp(&other.height_relative))
+            .then_with(|| self.seconds_relative.cmp(&other.seconds_relative))
+            .then_with(|| {
+                self.before_height_relative
+                    .cmp(&other.before_height_relative)
+            })
+            .then_with(|| {
+                self.before_seconds_relative
+                    .cmp(&other.before_seconds_relative)
+            })
+            .then_with(|| self.birth_height.cmp(&other.birth_height))
+            .then_with(|| self.birth_seconds.cmp(&other.birth_seconds))
+            .then_with(|| self.create_coin.cmp(&other.create_coin))
+            .then_with(|| cmp_agg_sigs(&self.agg_sig_me, &other.agg_sig_me))
+            .then_with(|| cmp_agg_sigs(&self.agg_sig_parent, &other.agg_sig_parent))
+            .then_with(|| cmp_agg_sigs(&self.agg_sig_puzzle, &other.agg_sig_puzzle))
+            .then_with(|| cmp_agg_sigs(&self.agg_sig_amount, &other.agg_sig_amount))
+            .then_with(|| cmp_agg_sigs(&self.agg_sig_puzzle_amount, &other.agg_sig_puzzle_amount))
+            .then_with(|| cmp_agg_sigs(&self.agg_sig_parent_amount, &other.agg_sig_parent_amount))
+            .then_with(|| cmp_agg_sigs(&self.agg_sig_parent_puzzle, &other.agg_sig_parent_puzzle))
+            .then_with(|| self.flags.cmp(&other.flags))
+    }
+}
+
+impl PartialOrd for OwnedSpendConditions {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl OwnedSpendBundleConditions {
+    /// Sorts the spends, and the conditions of each spend, into canonical
+    /// order (see [`OwnedSpendConditions::normalize()`]). Spends are sorted by
+    /// coin ID and the unsafe `AGG_SIG` conditions by public key, then
+    /// message. This makes the result of validating a spend bundle
+    /// independent of the order its spends and conditions were parsed in,
+    /// which is useful when building blocks reproducibly.
+    pub fn normalize(&mut self) {
+        for spend in &mut self.spends {
+            spend.normalize();
+        }
+        self.spends.sort();
+        sort_agg_sigs(&mut self.agg_sig_unsafe);
+    }
+}
+
+fn sort_agg_sigs(agg_sigs: &mut [(PublicKey, Bytes)]) {
+    agg_sigs.sort_by_cached_key(|(pk, msg)| (pk.to_bytes(), msg.clone()));
+}
+
+fn cmp_agg_sigs(lhs: &[(PublicKey, Bytes)], rhs: &[(PublicKey, Bytes)]) -> Ordering {
+    lhs.iter()
+        .map(|(pk, msg)| (pk.to_bytes(), msg))
+        .cmp(rhs.iter().map(|(pk, msg)| (pk.to_bytes(), msg)))
+}
+
 fn convert_agg_sigs(a: &Allocator, agg_sigs: &[(PublicKey, NodePtr)]) -> Vec<(PublicKey, Bytes)> {
     let mut ret = Vec::<(PublicKey, Bytes)>::new();
     for (pk, msg) in agg_sigs {
@@ -166,6 +271,18 @@ impl OwnedSpendConditions {
             "OwnedSpendConditions does not support from_parent().",
         ))
     }
+
+    #[getter]
+    #[pyo3(name = "eligible_for_dedup")]
+    fn py_eligible_for_dedup(&self) -> bool {
+        self.eligible_for_dedup()
+    }
+
+    #[getter]
+    #[pyo3(name = "eligible_for_fast_forward")]
+    fn py_eligible_for_fast_forward(&self) -> bool {
+        self.eligible_for_fast_forward()
+    }
 }
 
 #[cfg(feature = "py-bindings")]
@@ -179,3 +296,175 @@ impl OwnedSpendBundleConditions {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chia_bls::SecretKey;
+    use rand::rngs::SmallRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    fn random_bytes32(rng: &mut SmallRng) -> Bytes32 {
+        // use a small range of values to make ties likely
+        Bytes32::new([rng.gen_range(0..4); 32])
+    }
+
+    fn random_agg_sigs(rng: &mut SmallRng) -> Vec<(PublicKey, Bytes)> {
+        (0..rng.gen_range(0..4))
+            .map(|_| {
+                let sk = SecretKey::from_seed(&[rng.gen_range(0..3_u8); 32]);
+                let msg = vec![rng.gen_range(0..3_u8); rng.gen_range(0..3)];
+                (sk.public_key(), msg.into())
+            })
+            .collect()
+    }
+
+    fn random_spend(rng: &mut SmallRng) -> OwnedSpendConditions {
+        OwnedSpendConditions {
+            coin_id: random_bytes32(rng),
+            parent_id: random_bytes32(rng),
+            puzzle_hash: random_bytes32(rng),
+            coin_amount: rng.gen_range(0..3),
+            height_relative: rng.gen_bool(0.5).then(|| rng.gen_range(0..3)),
+            before_seconds_relative: rng.gen_bool(0.5).then(|| rng.gen_range(0..3)),
+            create_coin: (0..rng.gen_range(0..5))
+                .map(|_| {
+                    let hint: Option<Bytes> = rng
+                        .gen_bool(0.5)
+                        .then(|| vec![rng.gen_range(0..3_u8); 3].into());
+                    (random_bytes32(rng), rng.gen_range(0..3), hint)
+                })
+                .collect(),
+            agg_sig_me: random_agg_sigs(rng),
+            agg_sig_parent: random_agg_sigs(rng),
+            agg_sig_puzzle_amount: random_agg_sigs(rng),
+            flags: rng.gen_range(0..2),
+            ..Default::default()
+        }
+    }
+
+    fn random_bundle(rng: &mut SmallRng) -> OwnedSpendBundleConditions {
+        OwnedSpendBundleConditions {
+            spends: (0..rng.gen_range(0..6))
+                .map(|_| random_spend(rng))
+                .collect(),
+            agg_sig_unsafe: random_agg_sigs(rng),
+            cost: 1234,
+            ..Default::default()
+        }
+    }
+
+    // shuffles the spends, as well as the conditions of each spend
+    fn shuffle(rng: &mut SmallRng, bundle: &mut OwnedSpendBundleConditions) {
+        bundle.spends.shuffle(rng);
+        bundle.agg_sig_unsafe.shuffle(rng);
+        for spend in &mut bundle.spends {
+            spend.create_coin.shuffle(rng);
+            spend.agg_sig_me.shuffle(rng);
+            spend.agg_sig_parent.shuffle(rng);
+            spend.agg_sig_puzzle_amount.shuffle(rng);
+        }
+    }
+
+    #[test]
+    fn test_normalize_idempotent() {
+        let mut rng = SmallRng::seed_from_u64(1337);
+        for _ in 0..200 {
+            let mut bundle = random_bundle(&mut rng);
+            bundle.normalize();
+            let normalized = bundle.clone();
+            bundle.normalize();
+            assert_eq!(bundle, normalized);
+
+            assert!(bundle.spends.windows(2).all(|w| w[0] <= w[1]));
+            for spend in &bundle.spends {
+                assert!(spend.create_coin.windows(2).all(|w| w[0] <= w[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalize_permutation() {
+        let mut rng = SmallRng::seed_from_u64(1337);
+        for _ in 0..200 {
+            let bundle = random_bundle(&mut rng);
+            let mut expected = bundle.clone();
+            expected.normalize();
+            for _ in 0..5 {
+                let mut shuffled = bundle.clone();
+                shuffle(&mut rng, &mut shuffled);
+                shuffled.normalize();
+                assert_eq!(shuffled, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ord_consistent_with_eq() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        for _ in 0..1000 {
+            let lhs = random_spend(&mut rng);
+            let rhs = random_spend(&mut rng);
+            assert_eq!(lhs.cmp(&rhs) == Ordering::Equal, lhs == rhs);
+            assert_eq!(lhs.cmp(&rhs), rhs.cmp(&lhs).reverse());
+            assert_eq!(lhs.cmp(&lhs.clone()), Ordering::Equal);
+            if lhs.coin_id != rhs.coin_id {
+                assert_eq!(lhs.cmp(&rhs), lhs.coin_id.cmp(&rhs.coin_id));
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        let mut rng = SmallRng::seed_from_u64(1337);
+        for _ in 0..50 {
+            let bundle = random_bundle(&mut rng);
+            let json = serde_json::to_string(&bundle).unwrap();
+            let parsed: OwnedSpendBundleConditions = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, bundle);
+        }
+    }
+
+    // if this fails, the JSON schema changed. Make sure that's intended (RPC
+    // clients depend on it) and update the snapshot
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_snapshot() {
+        use crate::allocator::make_allocator;
+        use crate::consensus_constants::TEST_CONSTANTS;
+        use crate::gen::flags::{ALLOW_BACKREFS, DONT_VALIDATE_SIGNATURE};
+        use crate::gen::run_block_generator::run_block_generator;
+        use chia_bls::Signature;
+
+        let test_file =
+            std::fs::read_to_string("../../generator-tests/create-coin-different-amounts.txt")
+                .expect("test file not found");
+        let (generator, _) = test_file.split_once('\n').expect("invalid test file");
+        let generator = hex::decode(generator).expect("invalid hex encoded generator");
+
+        let mut a = make_allocator(ALLOW_BACKREFS);
+        let conds = run_block_generator(
+            &mut a,
+            &generator,
+            Vec::<&[u8]>::new(),
+            11_000_000_000,
+            ALLOW_BACKREFS | DONT_VALIDATE_SIGNATURE,
+            &Signature::default(),
+            None,
+            &TEST_CONSTANTS,
+        )
+        .expect("run_block_generator");
+        let mut conds = OwnedSpendBundleConditions::from(&a, conds);
+        conds.normalize();
+
+        let json = serde_json::to_string_pretty(&conds).unwrap() + "\n";
+        let expected = include_str!("../../test-data/create-coin-different-amounts.json");
+        assert_eq!(json, expected);
+        assert_eq!(
+            serde_json::from_str::<OwnedSpendBundleConditions>(expected).unwrap(),
+            conds
+        );
+    }
+}
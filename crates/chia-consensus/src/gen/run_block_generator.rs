@@ -1,18 +1,22 @@
+use crate::allocator::AllocatorMetrics;
 use crate::consensus_constants::ConsensusConstants;
+use crate::gen::cancellation::{check_cancelled, run_program_cancellable, CancellationToken};
 use crate::gen::conditions::{
-    parse_spends, process_single_spend, validate_conditions, validate_signature, EmptyVisitor,
-    ParseState, SpendBundleConditions,
+    parse_spends, parse_spends_cancellable, process_single_spend_instrumented, validate_conditions,
+    validate_signature_instrumented, EmptyVisitor, ParseState, SpendBundleConditions,
 };
 use crate::gen::flags::{ALLOW_BACKREFS, DONT_VALIDATE_SIGNATURE};
+use crate::gen::trace::{debug_span, trace_error};
 use crate::gen::validation_error::{first, ErrorCode, ValidationErr};
+use crate::gen::validation_metrics::ValidationMetrics;
 use crate::generator_rom::{CLVM_DESERIALIZER, GENERATOR_ROM};
+use crate::spendbundle_validation::get_flags_for_height_and_constants;
 use chia_bls::{BlsCache, Signature};
 use clvm_utils::{tree_hash_cached, TreeHash};
 use clvmr::allocator::{Allocator, NodePtr};
 use clvmr::chia_dialect::ChiaDialect;
 use clvmr::cost::Cost;
 use clvmr::reduction::Reduction;
-use clvmr::run_program::run_program;
 use clvmr::serde::{node_from_bytes, node_from_bytes_backrefs, node_from_bytes_backrefs_record};
 use std::collections::{HashMap, HashSet};
 
@@ -79,6 +83,76 @@ pub fn run_block_generator<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
     bls_cache: Option<&BlsCache>,
     constants: &ConsensusConstants,
 ) -> Result<SpendBundleConditions, ValidationErr>
+where
+    <I as IntoIterator>::IntoIter: DoubleEndedIterator,
+{
+    debug_span!(
+        "run_block_generator",
+        program_len = program.len(),
+        max_cost,
+        flags
+    );
+    trace_error(
+        run_block_generator_inner(
+            a, program, block_refs, max_cost, flags, signature, bls_cache, constants, None,
+        ),
+        "block",
+    )
+}
+
+// Like run_block_generator(), but aborts with ErrorCode::Cancelled once
+// `token` is cancelled (from another thread). The generator ROM is interrupted
+// at its next operator call, and condition parsing at the next spend (or
+// every CANCEL_CHECK_INTERVAL conditions).
+#[allow(clippy::too_many_arguments)]
+pub fn run_block_generator_cancellable<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
+    a: &mut Allocator,
+    program: &[u8],
+    block_refs: I,
+    max_cost: u64,
+    flags: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+    token: &CancellationToken,
+) -> Result<SpendBundleConditions, ValidationErr>
+where
+    <I as IntoIterator>::IntoIter: DoubleEndedIterator,
+{
+    debug_span!(
+        "run_block_generator",
+        program_len = program.len(),
+        max_cost,
+        flags
+    );
+    trace_error(
+        run_block_generator_inner(
+            a,
+            program,
+            block_refs,
+            max_cost,
+            flags,
+            signature,
+            bls_cache,
+            constants,
+            Some(token),
+        ),
+        "block",
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_block_generator_inner<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
+    a: &mut Allocator,
+    program: &[u8],
+    block_refs: I,
+    max_cost: u64,
+    flags: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+    cancel: Option<&CancellationToken>,
+) -> Result<SpendBundleConditions, ValidationErr>
 where
     <I as IntoIterator>::IntoIter: DoubleEndedIterator,
 {
@@ -110,21 +184,33 @@ where
 
     let dialect = ChiaDialect::new(flags);
     let Reduction(clvm_cost, generator_output) =
-        run_program(a, &dialect, generator_rom, args, cost_left)?;
+        run_program_cancellable(a, &dialect, generator_rom, args, cost_left, cancel)?;
 
     subtract_cost(a, &mut cost_left, clvm_cost)?;
 
     // we pass in what's left of max_cost here, to fail early in case the
     // cost of a condition brings us over the cost limit
-    let mut result = parse_spends::<EmptyVisitor>(
-        a,
-        generator_output,
-        cost_left,
-        flags,
-        signature,
-        bls_cache,
-        constants,
-    )?;
+    let mut result = match cancel {
+        Some(token) => parse_spends_cancellable::<EmptyVisitor>(
+            a,
+            generator_output,
+            cost_left,
+            flags,
+            signature,
+            bls_cache,
+            constants,
+            token,
+        )?,
+        None => parse_spends::<EmptyVisitor>(
+            a,
+            generator_output,
+            cost_left,
+            flags,
+            signature,
+            bls_cache,
+            constants,
+        )?,
+    };
     result.cost += max_cost - cost_left;
     Ok(result)
 }
@@ -159,6 +245,7 @@ pub fn extract_n<const N: usize>(
 // it also does not apply the stack depth or object allocation limits the same,
 // as each puzzle run in its own environment.
 #[allow(clippy::too_many_arguments)]
+#[inline]
 pub fn run_block_generator2<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
     a: &mut Allocator,
     program: &[u8],
@@ -169,6 +256,100 @@ pub fn run_block_generator2<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>
     bls_cache: Option<&BlsCache>,
     constants: &ConsensusConstants,
 ) -> Result<SpendBundleConditions, ValidationErr>
+where
+    <I as IntoIterator>::IntoIter: DoubleEndedIterator,
+{
+    run_block_generator2_instrumented(
+        a, program, block_refs, max_cost, flags, signature, bls_cache, constants, None,
+    )
+}
+
+// Like run_block_generator2(), but also reports every spend and condition,
+// the signature validation and the cost of the block to `metrics`, if set.
+#[allow(clippy::too_many_arguments)]
+pub fn run_block_generator2_instrumented<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
+    a: &mut Allocator,
+    program: &[u8],
+    block_refs: I,
+    max_cost: u64,
+    flags: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+    metrics: Option<&dyn ValidationMetrics>,
+) -> Result<SpendBundleConditions, ValidationErr>
+where
+    <I as IntoIterator>::IntoIter: DoubleEndedIterator,
+{
+    debug_span!(
+        "run_block_generator2",
+        program_len = program.len(),
+        max_cost,
+        flags
+    );
+    trace_error(
+        run_block_generator2_inner(
+            a, program, block_refs, max_cost, flags, signature, bls_cache, constants, metrics, None,
+        ),
+        "block",
+    )
+}
+
+// Like run_block_generator2(), but aborts with ErrorCode::Cancelled once
+// `token` is cancelled (from another thread). The generator and the puzzles
+// are interrupted at their next operator call, and condition parsing at the
+// next spend (or every CANCEL_CHECK_INTERVAL conditions).
+#[allow(clippy::too_many_arguments)]
+pub fn run_block_generator2_cancellable<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
+    a: &mut Allocator,
+    program: &[u8],
+    block_refs: I,
+    max_cost: u64,
+    flags: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+    token: &CancellationToken,
+) -> Result<SpendBundleConditions, ValidationErr>
+where
+    <I as IntoIterator>::IntoIter: DoubleEndedIterator,
+{
+    debug_span!(
+        "run_block_generator2",
+        program_len = program.len(),
+        max_cost,
+        flags
+    );
+    trace_error(
+        run_block_generator2_inner(
+            a,
+            program,
+            block_refs,
+            max_cost,
+            flags,
+            signature,
+            bls_cache,
+            constants,
+            None,
+            Some(token),
+        ),
+        "block",
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_block_generator2_inner<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
+    a: &mut Allocator,
+    program: &[u8],
+    block_refs: I,
+    max_cost: u64,
+    flags: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+    metrics: Option<&dyn ValidationMetrics>,
+    cancel: Option<&CancellationToken>,
+) -> Result<SpendBundleConditions, ValidationErr>
 where
     <I as IntoIterator>::IntoIter: DoubleEndedIterator,
 {
@@ -186,7 +367,8 @@ where
     let args = setup_generator_args(a, block_refs)?;
     let dialect = ChiaDialect::new(flags);
 
-    let Reduction(clvm_cost, mut all_spends) = run_program(a, &dialect, program, args, cost_left)?;
+    let Reduction(clvm_cost, mut all_spends) =
+        run_program_cancellable(a, &dialect, program, args, cost_left, cancel)?;
 
     subtract_cost(a, &mut cost_left, clvm_cost)?;
     all_spends = first(a, all_spends)?;
@@ -196,7 +378,7 @@ where
     // where extra may be nil, or additional extension data
 
     let mut ret = SpendBundleConditions::default();
-    let mut state = ParseState::default();
+    let mut state = ParseState::with_cancellation(cancel.cloned());
     let mut cache = HashMap::<NodePtr, TreeHash>::new();
 
     while let Some((spend, rest)) = a.next(all_spends) {
@@ -205,15 +387,16 @@ where
         let [parent_id, puzzle, amount, solution, _spend_level_extra] =
             extract_n::<5>(a, spend, ErrorCode::InvalidCondition)?;
 
+        // this also checks the cancellation token before running the puzzle
         let Reduction(clvm_cost, conditions) =
-            run_program(a, &dialect, puzzle, solution, cost_left)?;
+            run_program_cancellable(a, &dialect, puzzle, solution, cost_left, cancel)?;
 
         subtract_cost(a, &mut cost_left, clvm_cost)?;
 
         let buf = tree_hash_cached(a, puzzle, &backrefs, &mut cache);
         let puzzle_hash = a.new_atom(&buf)?;
 
-        process_single_spend::<EmptyVisitor>(
+        process_single_spend_instrumented::<EmptyVisitor>(
             a,
             &mut ret,
             &mut state,
@@ -224,6 +407,7 @@ where
             flags,
             &mut cost_left,
             constants,
+            metrics,
         )?;
     }
     if a.atom_len(all_spends) != 0 {
@@ -231,9 +415,107 @@ where
     }
 
     validate_conditions(a, &ret, &state, a.nil(), flags)?;
-    validate_signature(&state, signature, flags, bls_cache)?;
+    check_cancelled(cancel)?;
+    validate_signature_instrumented(&state, signature, flags, bls_cache, metrics)?;
     ret.validated_signature = (flags & DONT_VALIDATE_SIGNATURE) == 0;
 
     ret.cost = max_cost - cost_left;
+    if let Some(metrics) = metrics {
+        metrics.on_block_cost(ret.cost);
+    }
     Ok(ret)
 }
+
+// Runs the block generator the way consensus requires for a block at the
+// specified height. Before the hard fork, that's run_block_generator() (using
+// the CLVM generator ROM). From the hard fork and on, it's
+// run_block_generator2(), which has a lower cost. The flags required at the
+// height (e.g. ALLOW_BACKREFS) are set automatically, and `flags` may add
+// more, such as DONT_VALIDATE_SIGNATURE or MEMPOOL_MODE.
+#[allow(clippy::too_many_arguments)]
+pub fn run_block_generator_for_height<GenBuf: AsRef<[u8]>, I: IntoIterator<Item = GenBuf>>(
+    a: &mut Allocator,
+    program: &[u8],
+    block_refs: I,
+    max_cost: u64,
+    flags: u32,
+    height: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+) -> Result<SpendBundleConditions, ValidationErr>
+where
+    <I as IntoIterator>::IntoIter: DoubleEndedIterator,
+{
+    let flags = flags | get_flags_for_height_and_constants(height, constants);
+    if height >= constants.hard_fork_height {
+        run_block_generator2(
+            a, program, block_refs, max_cost, flags, signature, bls_cache, constants,
+        )
+    } else {
+        run_block_generator(
+            a, program, block_refs, max_cost, flags, signature, bls_cache, constants,
+        )
+    }
+}
+
+// Like run_block_generator_for_height(), but aborts with ErrorCode::Cancelled
+// once `token` is cancelled (from another thread).
+#[allow(clippy::too_many_arguments)]
+pub fn run_block_generator_for_height_cancellable<
+    GenBuf: AsRef<[u8]>,
+    I: IntoIterator<Item = GenBuf>,
+>(
+    a: &mut Allocator,
+    program: &[u8],
+    block_refs: I,
+    max_cost: u64,
+    flags: u32,
+    height: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+    token: &CancellationToken,
+) -> Result<SpendBundleConditions, ValidationErr>
+where
+    <I as IntoIterator>::IntoIter: DoubleEndedIterator,
+{
+    let flags = flags | get_flags_for_height_and_constants(height, constants);
+    if height >= constants.hard_fork_height {
+        run_block_generator2_cancellable(
+            a, program, block_refs, max_cost, flags, signature, bls_cache, constants, token,
+        )
+    } else {
+        run_block_generator_cancellable(
+            a, program, block_refs, max_cost, flags, signature, bls_cache, constants, token,
+        )
+    }
+}
+
+// Like run_block_generator_for_height(), but also reports how much of the
+// allocator running the generator used. The metrics only cover this call, even
+// if the allocator was used before.
+#[allow(clippy::too_many_arguments)]
+pub fn run_block_generator_for_height_with_metrics<
+    GenBuf: AsRef<[u8]>,
+    I: IntoIterator<Item = GenBuf>,
+>(
+    a: &mut Allocator,
+    program: &[u8],
+    block_refs: I,
+    max_cost: u64,
+    flags: u32,
+    height: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+) -> Result<(SpendBundleConditions, AllocatorMetrics), ValidationErr>
+where
+    <I as IntoIterator>::IntoIter: DoubleEndedIterator,
+{
+    let start = AllocatorMetrics::new(a);
+    let conds = run_block_generator_for_height(
+        a, program, block_refs, max_cost, flags, height, signature, bls_cache, constants,
+    )?;
+    Ok((conds, AllocatorMetrics::since(a, &start)))
+}
@@ -1,21 +1,42 @@
+//! Parsing of the unsigned integer arguments of conditions.
+//!
+//! CLVM integers are signed, big-endian and of arbitrary length. An integer
+//! argument is only accepted in its canonical form:
+//!
+//! * zero is the empty atom. `0x00` is invalid.
+//! * a leading zero byte is only allowed if it's required to keep the value
+//!   from being negative, i.e. if the next byte has its top bit set. Any other
+//!   leading zero is invalid.
+//! * an atom whose first byte has its top bit set is negative.
+//!
+//! These rules are the same in mempool mode and in consensus mode. Invalid
+//! encodings, and pairs, fail with the error code passed in by the caller.
+//!
+//! A `u64` may be at most 8 bytes and a `u32` at most 4 bytes, not counting
+//! the leading zero byte. The `lenient` parsers report values that don't fit
+//! as [`SanitizedUint::NegativeOverflow`] or
+//! [`SanitizedUint::PositiveOverflow`], since some conditions are ignored,
+//! rather than failed, by those. The `strict` parsers fail with the caller's
+//! error code instead.
+
 use super::validation_error::{atom, ErrorCode, ValidationErr};
 use clvmr::allocator::{Allocator, NodePtr};
 
 use clvmr::op_utils::u64_from_bytes;
 
-#[derive(PartialEq, Debug)]
-pub enum SanitizedUint {
-    Ok(u64),
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SanitizedUint<T> {
+    Ok(T),
     PositiveOverflow,
     NegativeOverflow,
 }
 
-pub fn sanitize_uint(
+fn parse_uint(
     a: &Allocator,
     n: NodePtr,
     max_size: usize,
     code: ErrorCode,
-) -> Result<SanitizedUint, ValidationErr> {
+) -> Result<SanitizedUint<u64>, ValidationErr> {
     assert!(max_size <= 8);
 
     let buf = atom(a, n, code)?;
@@ -48,59 +69,141 @@ pub fn sanitize_uint(
     Ok(SanitizedUint::Ok(u64_from_bytes(buf)))
 }
 
-#[test]
-fn test_sanitize_uint() {
-    let mut a = Allocator::new();
+/// Parses a `u64` of at most 8 bytes (9 with the leading zero). Values that
+/// are negative or too large are returned as overflows.
+pub fn parse_u64_lenient(
+    a: &Allocator,
+    n: NodePtr,
+    code: ErrorCode,
+) -> Result<SanitizedUint<u64>, ValidationErr> {
+    parse_uint(a, n, 8, code)
+}
+
+/// Parses a `u32` of at most 4 bytes (5 with the leading zero). Values that
+/// are negative or too large are returned as overflows.
+pub fn parse_u32_lenient(
+    a: &Allocator,
+    n: NodePtr,
+    code: ErrorCode,
+) -> Result<SanitizedUint<u32>, ValidationErr> {
+    Ok(match parse_uint(a, n, 4, code)? {
+        SanitizedUint::Ok(r) => {
+            SanitizedUint::Ok(u32::try_from(r).expect("at most 4 significant bytes"))
+        }
+        SanitizedUint::PositiveOverflow => SanitizedUint::PositiveOverflow,
+        SanitizedUint::NegativeOverflow => SanitizedUint::NegativeOverflow,
+    })
+}
+
+/// Parses a `u64` of at most 8 bytes (9 with the leading zero). Values that
+/// are negative or too large fail with `code`.
+pub fn parse_u64_strict(a: &Allocator, n: NodePtr, code: ErrorCode) -> Result<u64, ValidationErr> {
+    match parse_u64_lenient(a, n, code)? {
+        SanitizedUint::Ok(r) => Ok(r),
+        SanitizedUint::PositiveOverflow | SanitizedUint::NegativeOverflow => {
+            Err(ValidationErr(n, code))
+        }
+    }
+}
 
-    // start with one big buffer.
-    let atom = {
-        let mut buf = Vec::<u8>::new();
-        for _i in 0..1024 {
-            buf.push(0);
+/// Parses a `u32` of at most 4 bytes (5 with the leading zero). Values that
+/// are negative or too large fail with `code`.
+pub fn parse_u32_strict(a: &Allocator, n: NodePtr, code: ErrorCode) -> Result<u32, ValidationErr> {
+    match parse_u32_lenient(a, n, code)? {
+        SanitizedUint::Ok(r) => Ok(r),
+        SanitizedUint::PositiveOverflow | SanitizedUint::NegativeOverflow => {
+            Err(ValidationErr(n, code))
         }
+    }
+}
 
-        // make some of the bytes non-zero
-        buf[0] = 0xff;
-        buf[100] = 0x7f;
-        buf[1023] = 0xff;
-        a.new_atom(&buf)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_sanitize_uint() {
+        let mut a = Allocator::new();
+
+        // start with one big buffer.
+        let atom = {
+            let mut buf = Vec::<u8>::new();
+            for _i in 0..1024 {
+                buf.push(0);
+            }
+
+            // make some of the bytes non-zero
+            buf[0] = 0xff;
+            buf[100] = 0x7f;
+            buf[1023] = 0xff;
+            a.new_atom(&buf)
+        }
+        .unwrap();
+
+        let e = ErrorCode::InvalidCoinAmount;
+        let no_leading_zero = a.new_substr(atom, 0, 8).unwrap();
+        // this is a negative number, not allowed
+        assert_eq!(
+            parse_u64_lenient(&a, no_leading_zero, e),
+            Ok(SanitizedUint::NegativeOverflow)
+        );
+
+        let just_zeros = a.new_substr(atom, 10, 70).unwrap();
+        // a zero value must be represented by an empty atom
+        assert_eq!(
+            parse_u64_lenient(&a, just_zeros, e).unwrap_err().1,
+            ErrorCode::InvalidCoinAmount
+        );
+
+        let a1 = a.new_substr(atom, 1, 101).unwrap();
+        assert_eq!(
+            parse_u64_lenient(&a, a1, e).unwrap_err().1,
+            ErrorCode::InvalidCoinAmount
+        );
+
+        // a new all-zeros range
+        let a1 = a.new_substr(atom, 1000, 1024).unwrap();
+        assert_eq!(
+            parse_u64_lenient(&a, a1, e).unwrap_err().1,
+            ErrorCode::InvalidCoinAmount
+        );
+
+        let exceed_maximum = a.new_substr(atom, 100, 110).unwrap();
+        assert_eq!(
+            parse_u64_lenient(&a, exceed_maximum, e),
+            Ok(SanitizedUint::PositiveOverflow)
+        );
+    }
+
+    #[rstest]
+    #[case::zero("", Some(0), Some(0))]
+    #[case::one("01", Some(1), Some(1))]
+    #[case::leading_zero("0080", Some(0x80), Some(0x80))]
+    #[case::u32_max("00ffffffff", Some(0xffff_ffff), Some(0xffff_ffff))]
+    #[case::u32_overflow("0100000000", None, Some(0x1_0000_0000))]
+    #[case::u64_max("00ffffffffffffffff", None, Some(u64::MAX))]
+    #[case::u64_overflow("010000000000000000", None, None)]
+    #[case::negative("ff", None, None)]
+    fn test_strict(#[case] input: &str, #[case] as_u32: Option<u32>, #[case] as_u64: Option<u64>) {
+        let mut a = Allocator::new();
+        let n = a.new_atom(&hex::decode(input).unwrap()).unwrap();
+        let e = ErrorCode::InvalidCoinAmount;
+        assert_eq!(
+            parse_u32_strict(&a, n, e).ok(),
+            as_u32,
+            "u32 parse of {input}"
+        );
+        assert_eq!(
+            parse_u64_strict(&a, n, e).ok(),
+            as_u64,
+            "u64 parse of {input}"
+        );
+        if as_u32.is_none() {
+            assert_eq!(parse_u32_strict(&a, n, e), Err(ValidationErr(n, e)));
+        }
+        if as_u64.is_none() {
+            assert_eq!(parse_u64_strict(&a, n, e), Err(ValidationErr(n, e)));
+        }
     }
-    .unwrap();
-
-    let e = ErrorCode::InvalidCoinAmount;
-    let no_leading_zero = a.new_substr(atom, 0, 8).unwrap();
-    // this is a negative number, not allowed
-    assert!(sanitize_uint(&a, no_leading_zero, 8, e) == Ok(SanitizedUint::NegativeOverflow));
-
-    let just_zeros = a.new_substr(atom, 10, 70).unwrap();
-    // a zero value must be represented by an empty atom
-    assert_eq!(
-        sanitize_uint(&a, just_zeros, 8, e).unwrap_err().1,
-        ErrorCode::InvalidCoinAmount
-    );
-
-    let a1 = a.new_substr(atom, 1, 101).unwrap();
-    assert_eq!(
-        sanitize_uint(&a, a1, 8, e).unwrap_err().1,
-        ErrorCode::InvalidCoinAmount
-    );
-
-    let a1 = a.new_substr(atom, 1, 101).unwrap();
-    assert_eq!(
-        sanitize_uint(&a, a1, 8, e).unwrap_err().1,
-        ErrorCode::InvalidCoinAmount
-    );
-
-    // a new all-zeros range
-    let a1 = a.new_substr(atom, 1000, 1024).unwrap();
-    assert_eq!(
-        sanitize_uint(&a, a1, 8, e).unwrap_err().1,
-        ErrorCode::InvalidCoinAmount
-    );
-
-    let exceed_maximum = a.new_substr(atom, 100, 110).unwrap();
-    assert_eq!(
-        sanitize_uint(&a, exceed_maximum, 8, e),
-        Ok(SanitizedUint::PositiveOverflow)
-    );
 }
@@ -1,6 +1,8 @@
+use crate::consensus_constants::ConsensusConstants;
 use chia_protocol::Coin;
 use chia_protocol::CoinSpend;
 use clvmr::allocator::{Allocator, NodePtr};
+use clvmr::cost::Cost;
 use clvmr::serde::{node_from_bytes_backrefs, node_to_bytes, node_to_bytes_backrefs};
 use std::io;
 
@@ -16,20 +18,7 @@ where
 
     let mut spend_list = a.nil();
     for s in spends {
-        let item = a.nil();
-        // solution
-        let solution = node_from_bytes_backrefs(a, s.2.as_ref())?;
-        let item = a.new_pair(solution, item)?;
-        // amount
-        let amount = a.new_number(s.0.amount.into())?;
-        let item = a.new_pair(amount, item)?;
-        // puzzle reveal
-        let puzzle = node_from_bytes_backrefs(a, s.1.as_ref())?;
-        let item = a.new_pair(puzzle, item)?;
-        // parent-id
-        let parent_id = a.new_atom(&s.0.parent_coin_info)?;
-        let item = a.new_pair(parent_id, item)?;
-
+        let item = build_spend(a, &s.0, s.1.as_ref(), s.2.as_ref())?;
         spend_list = a.new_pair(item, spend_list)?;
     }
 
@@ -40,6 +29,28 @@ where
     Ok(quote)
 }
 
+// ( parent-id puzzle-reveal amount solution )
+fn build_spend(
+    a: &mut Allocator,
+    coin: &Coin,
+    puzzle: &[u8],
+    solution: &[u8],
+) -> io::Result<NodePtr> {
+    let item = a.nil();
+    // solution
+    let solution = node_from_bytes_backrefs(a, solution)?;
+    let item = a.new_pair(solution, item)?;
+    // amount
+    let amount = a.new_number(u64::from(coin.amount).into())?;
+    let item = a.new_pair(amount, item)?;
+    // puzzle reveal
+    let puzzle = node_from_bytes_backrefs(a, puzzle)?;
+    let item = a.new_pair(puzzle, item)?;
+    // parent-id
+    let parent_id = a.new_atom(&coin.parent_coin_info)?;
+    Ok(a.new_pair(parent_id, item)?)
+}
+
 // this function returns the number of bytes the specified
 // number is serialized to, in CLVM serialized form
 fn clvm_bytes_len(val: u64) -> usize {
@@ -68,18 +79,34 @@ fn clvm_bytes_len(val: u64) -> usize {
 pub fn calculate_generator_length<I>(spends: I) -> usize
 where
     I: AsRef<[CoinSpend]>,
+{
+    calculate_spends_length(
+        spends
+            .as_ref()
+            .iter()
+            .map(|s| (s.coin, s.puzzle_reveal.as_ref(), s.solution.as_ref())),
+    )
+}
+
+// like calculate_generator_length(), but the tuple has the Coin, puzzle-reveal
+// and solution, like solution_generator() takes them
+pub fn calculate_spends_length<BufRef, I>(spends: I) -> usize
+where
+    BufRef: AsRef<[u8]>,
+    I: IntoIterator<Item = (Coin, BufRef, BufRef)>,
 {
     let mut size: usize = 5; // (q . (())) => ff01ff8080 => 5 bytes
 
-    for s in spends.as_ref() {
-        let puzzle = s.puzzle_reveal.as_ref();
-        let solution = s.solution.as_ref();
+    for (coin, puzzle, solution) in spends {
         // Each spend has the following form:
         // ( parent-id puzzle-reveal amount solution )
         // parent-id is always 32 bytes + 1 byte length prefix = 33
         // + 6 bytes for list extension
         // coin amount is already prepended correctly in clvm_bytes_len()
-        size += 39 + puzzle.len() + clvm_bytes_len(s.coin.amount) + solution.len();
+        size += 39
+            + puzzle.as_ref().len()
+            + clvm_bytes_len(coin.amount.into())
+            + solution.as_ref().len();
     }
 
     size
@@ -96,6 +123,104 @@ where
     node_to_bytes(&a, generator)
 }
 
+/// The result of adding a spend to a `GeneratorBuilder`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddReport {
+    /// The number of bytes the spend added to the generator.
+    pub added_bytes: usize,
+    /// The size of the generator, including this spend.
+    pub cumulative_bytes: usize,
+    /// The byte cost of the generator, including this spend. This is the
+    /// cost of the generator as `finalize()` returns it, i.e. without
+    /// back-references.
+    pub estimated_byte_cost: Cost,
+}
+
+/// Builds the same generator as `solution_generator()`, one spend at a time.
+/// Each spend is serialized as it's added, so the reported sizes are exact.
+/// This allows a block builder to stop adding spends (and backtrack) once the
+/// block would exceed its cost limit.
+pub struct GeneratorBuilder {
+    a: Allocator,
+    // the serialized ( parent-id puzzle-reveal amount solution ) of each
+    // spend, in the order they were added
+    spends: Vec<Vec<u8>>,
+    size: usize,
+    cost_per_byte: Cost,
+}
+
+impl GeneratorBuilder {
+    // (q . (())) => ff01ff8080
+    const EMPTY_SIZE: usize = 5;
+
+    pub fn new(constants: &ConsensusConstants) -> Self {
+        Self {
+            a: Allocator::new(),
+            spends: Vec::new(),
+            size: Self::EMPTY_SIZE,
+            cost_per_byte: constants.cost_per_byte,
+        }
+    }
+
+    pub fn add_spend(&mut self, spend: &CoinSpend) -> io::Result<AddReport> {
+        let item = build_spend(
+            &mut self.a,
+            &spend.coin,
+            spend.puzzle_reveal.as_ref(),
+            spend.solution.as_ref(),
+        )?;
+        let item = node_to_bytes(&self.a, item)?;
+        // the item is prepended to the spend list with a pair (0xff)
+        let added_bytes = 1 + item.len();
+        self.spends.push(item);
+        self.size += added_bytes;
+        Ok(AddReport {
+            added_bytes,
+            cumulative_bytes: self.size,
+            estimated_byte_cost: self.byte_cost(),
+        })
+    }
+
+    /// Removes the most recently added spend, returning the number of bytes
+    /// it had added. Returns None if there are no spends.
+    pub fn remove_last(&mut self) -> Option<usize> {
+        let item = self.spends.pop()?;
+        let removed_bytes = 1 + item.len();
+        self.size -= removed_bytes;
+        Some(removed_bytes)
+    }
+
+    /// The number of spends in the generator.
+    pub fn num_spends(&self) -> usize {
+        self.spends.len()
+    }
+
+    /// The size of the generator `finalize()` returns.
+    pub fn generator_size(&self) -> usize {
+        self.size
+    }
+
+    pub fn byte_cost(&self) -> Cost {
+        (self.size as Cost).saturating_mul(self.cost_per_byte)
+    }
+
+    /// Returns the serialized generator. It's identical to what
+    /// `solution_generator()` returns for the same spends.
+    pub fn finalize(self) -> Vec<u8> {
+        let mut ret = Vec::with_capacity(self.size);
+        ret.extend_from_slice(&[0xff, 0x01, 0xff]);
+        // solution_generator() prepends every spend to the list, so the last
+        // spend comes first
+        for item in self.spends.iter().rev() {
+            ret.push(0xff);
+            ret.extend_from_slice(item);
+        }
+        ret.extend_from_slice(&[0x80, 0x80]);
+        debug_assert_eq!(ret.len(), self.size);
+        ret
+    }
+}
+
 pub fn solution_generator_backrefs<BufRef, I>(spends: I) -> io::Result<Vec<u8>>
 where
     BufRef: AsRef<[u8]>,
@@ -109,7 +234,8 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chia_protocol::Program;
+    use crate::consensus_constants::TEST_CONSTANTS;
+    use chia_protocol::{Amount, Bytes32, Program};
     use chia_traits::Streamable;
     use clvmr::{run_program, ChiaDialect};
     use hex_literal::hex;
@@ -182,12 +308,12 @@ mod tests {
         let coin1: Coin = Coin::new(
             hex!("ccd5bb71183532bff220ba46c268991a00000000000000000000000000036840").into(),
             hex!("fcc78a9e396df6ceebc217d2446bc016e0b3d5922fb32e5783ec5a85d490cfb6").into(),
-            1_750_000_000_000,
+            Amount::new(1_750_000_000_000),
         );
         let coin2: Coin = Coin::new(
             hex!("ccd5bb71183532bff220ba46c268991a00000000000000000000000000000000").into(),
             hex!("d23da14695a188ae5708dd152263c4db883eb27edeb936178d4d988b8f3ce5fc").into(),
-            18_375_000_000_000_000_000,
+            Amount::new(18_375_000_000_000_000_000),
         );
 
         let spends = [
@@ -315,7 +441,7 @@ mod tests {
             let coin: Coin = Coin::new(
                 hex!("ccd5bb71183532bff220ba46c268991a00000000000000000000000000036840").into(),
                 hex!("fcc78a9e396df6ceebc217d2446bc016e0b3d5922fb32e5783ec5a85d490cfb6").into(),
-                i,
+                i.into(),
             );
             spends.push((coin, PUZZLE1.as_ref(), SOLUTION1.as_ref()));
             coin_spends.push(CoinSpend {
@@ -342,7 +468,7 @@ mod tests {
         let coin: Coin = Coin::new(
             hex!("ccd5bb71183532bff220ba46c268991a00000000000000000000000000036840").into(),
             hex!("fcc78a9e396df6ceebc217d2446bc016e0b3d5922fb32e5783ec5a85d490cfb6").into(),
-            100,
+            Amount::new(100),
         );
         spends.push((coin, puzzle, solution));
         coin_spends.push(CoinSpend {
@@ -369,12 +495,12 @@ mod tests {
         let coin1: Coin = Coin::new(
             hex!("ccd5bb71183532bff220ba46c268991a00000000000000000000000000036840").into(),
             hex!("fcc78a9e396df6ceebc217d2446bc016e0b3d5922fb32e5783ec5a85d490cfb6").into(),
-            1_750_000_000_000,
+            Amount::new(1_750_000_000_000),
         );
         let coin2: Coin = Coin::new(
             hex!("ccd5bb71183532bff220ba46c268991a00000000000000000000000000000000").into(),
             hex!("d23da14695a188ae5708dd152263c4db883eb27edeb936178d4d988b8f3ce5fc").into(),
-            18_375_000_000_000_000_000,
+            Amount::new(18_375_000_000_000_000_000),
         );
 
         let result = solution_generator_backrefs([
@@ -501,4 +627,108 @@ mod tests {
         let bytes = node_to_bytes(&a, atom).expect("node_to_bytes");
         assert_eq!(bytes.len(), len);
     }
+
+    fn coin_spend(i: u64) -> CoinSpend {
+        // vary the size of the amount, puzzle and solution
+        let (puzzle, solution) = match i % 3 {
+            0 => (PUZZLE1.as_ref(), SOLUTION1.as_ref()),
+            1 => (PUZZLE2.as_ref(), SOLUTION2.as_ref()),
+            _ => (PUZZLE2.as_ref(), SOLUTION1.as_ref()),
+        };
+        let mut parent = [0_u8; 32];
+        parent[..8].copy_from_slice(&i.to_be_bytes());
+        CoinSpend {
+            coin: Coin::new(
+                parent.into(),
+                hex!("fcc78a9e396df6ceebc217d2446bc016e0b3d5922fb32e5783ec5a85d490cfb6").into(),
+                Amount::new(i * i * i * 1_000_000),
+            ),
+            puzzle_reveal: Program::from(puzzle),
+            solution: Program::from(solution),
+        }
+    }
+
+    #[test]
+    fn test_generator_builder() {
+        let mut builder = GeneratorBuilder::new(&TEST_CONSTANTS);
+        assert_eq!(builder.generator_size(), 5);
+        assert_eq!(builder.remove_last(), None);
+
+        let mut spends = Vec::<CoinSpend>::new();
+        let mut total_added = 0;
+        for i in 0..50 {
+            let spend = coin_spend(i);
+            let report = builder.add_spend(&spend).expect("add_spend");
+            spends.push(spend);
+            total_added += report.added_bytes;
+            assert_eq!(report.cumulative_bytes, 5 + total_added);
+            assert_eq!(report.cumulative_bytes, calculate_generator_length(&spends));
+            assert_eq!(
+                report.estimated_byte_cost,
+                report.cumulative_bytes as u64 * TEST_CONSTANTS.cost_per_byte
+            );
+
+            // backtrack in the middle, as if these spends exceeded the cost
+            // limit
+            if i == 25 {
+                for j in 100..103 {
+                    let report = builder.add_spend(&coin_spend(j)).expect("add_spend");
+                    assert_eq!(report.cumulative_bytes, builder.generator_size());
+                }
+                let mut removed = 0;
+                for _ in 100..103 {
+                    removed += builder.remove_last().expect("remove_last");
+                }
+                assert!(removed > 0);
+                assert_eq!(builder.generator_size(), 5 + total_added);
+            }
+        }
+        assert_eq!(builder.num_spends(), 50);
+
+        let generator = builder.finalize();
+        assert_eq!(generator.len(), 5 + total_added);
+        let expected = solution_generator(
+            spends
+                .iter()
+                .map(|s| (s.coin, s.puzzle_reveal.as_ref(), s.solution.as_ref())),
+        )
+        .expect("solution_generator");
+        assert_eq!(generator, expected);
+    }
+
+    #[test]
+    fn test_generator_builder_empty() {
+        let builder = GeneratorBuilder::new(&TEST_CONSTANTS);
+        assert_eq!(builder.byte_cost(), 5 * TEST_CONSTANTS.cost_per_byte);
+        assert_eq!(
+            builder.finalize(),
+            solution_generator(Vec::<(Coin, &[u8], &[u8])>::new()).expect("solution_generator")
+        );
+    }
+
+    #[rstest]
+    #[case(hex!("f800000000").as_ref(), SOLUTION1.as_ref())]
+    #[case(hex!("fffffe0000ff41ff013a").as_ref(), SOLUTION1.as_ref())]
+    #[case(PUZZLE1.as_ref(), hex!("00").as_ref())]
+    fn test_generator_builder_edge_case(#[case] puzzle: &[u8], #[case] solution: &[u8]) {
+        // the puzzle and solution are re-serialized, so their sizes in the
+        // generator may differ from the input. The report must still be exact
+        let spend = CoinSpend {
+            coin: Coin::new(
+                Bytes32::new([1; 32]),
+                Bytes32::new([2; 32]),
+                Amount::new(100),
+            ),
+            puzzle_reveal: Program::from(puzzle),
+            solution: Program::from(solution),
+        };
+        let mut builder = GeneratorBuilder::new(&TEST_CONSTANTS);
+        let report = builder.add_spend(&spend).expect("add_spend");
+        let generator = builder.finalize();
+        assert_eq!(generator.len(), report.cumulative_bytes);
+        assert_eq!(
+            generator,
+            solution_generator([(spend.coin, puzzle, solution)]).expect("solution_generator")
+        );
+    }
 }
@@ -1,11 +1,160 @@
 use crate::gen::conditions::{Condition, SpendConditions};
+use crate::gen::opcodes::{
+    ConditionOpcode, AGG_SIG_AMOUNT, AGG_SIG_ME, AGG_SIG_PARENT, AGG_SIG_PARENT_AMOUNT,
+    AGG_SIG_PARENT_PUZZLE, AGG_SIG_PUZZLE, AGG_SIG_PUZZLE_AMOUNT, AGG_SIG_UNSAFE,
+    ASSERT_BEFORE_HEIGHT_ABSOLUTE, ASSERT_BEFORE_HEIGHT_RELATIVE, ASSERT_BEFORE_SECONDS_ABSOLUTE,
+    ASSERT_BEFORE_SECONDS_RELATIVE, ASSERT_COIN_ANNOUNCEMENT, ASSERT_CONCURRENT_PUZZLE,
+    ASSERT_CONCURRENT_SPEND, ASSERT_EPHEMERAL, ASSERT_HEIGHT_ABSOLUTE, ASSERT_HEIGHT_RELATIVE,
+    ASSERT_MY_AMOUNT, ASSERT_MY_BIRTH_HEIGHT, ASSERT_MY_BIRTH_SECONDS, ASSERT_MY_COIN_ID,
+    ASSERT_MY_PARENT_ID, ASSERT_MY_PUZZLEHASH, ASSERT_PUZZLE_ANNOUNCEMENT, ASSERT_SECONDS_ABSOLUTE,
+    ASSERT_SECONDS_RELATIVE, CREATE_COIN, CREATE_COIN_ANNOUNCEMENT, CREATE_PUZZLE_ANNOUNCEMENT,
+    RECEIVE_MESSAGE, RESERVE_FEE, SEND_MESSAGE, SOFTFORK,
+};
+use chia_protocol::Bytes32;
 use clvmr::allocator::Allocator;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 
-// These are customization points for the condition parsing and validation. The
-// mempool wants to record additional information than plain consensus
-// validation, so it hooks into these.
+/// These are customization points for the condition parsing and validation.
+/// The mempool wants to record additional information than plain consensus
+/// validation, so it hooks into these. Visitors are passed as a type
+/// parameter to `parse_spends()` and `process_single_spend()`.
+///
+/// A new visitor is created for every spend, it's then passed each condition
+/// of that spend, in order, and finally `post_spend()` is called once all
+/// conditions have been parsed. Visitors may modify the spend, e.g. its flags.
+///
+/// This trait exposes the internal representation of conditions, so it's not
+/// covered by the same stability guarantees as the rest of the API. New
+/// conditions, and changes to `Condition` and `SpendConditions`, may require
+/// visitors to be updated.
 pub trait SpendVisitor {
     fn new_spend(spend: &mut SpendConditions) -> Self;
     fn condition(&mut self, spend: &mut SpendConditions, c: &Condition);
     fn post_spend(&mut self, a: &Allocator, spend: &mut SpendConditions);
 }
+
+/// Runs two visitors in a single pass over the conditions. The first visitor
+/// is always called before the second, so the second one sees any changes the
+/// first one makes to the spend. Composites can be nested to run more than
+/// two visitors.
+pub struct CompositeVisitor<T>(pub T);
+
+impl<A: SpendVisitor, B: SpendVisitor> SpendVisitor for CompositeVisitor<(A, B)> {
+    fn new_spend(spend: &mut SpendConditions) -> Self {
+        let a = A::new_spend(spend);
+        let b = B::new_spend(spend);
+        Self((a, b))
+    }
+
+    fn condition(&mut self, spend: &mut SpendConditions, c: &Condition) {
+        self.0 .0.condition(spend, c);
+        self.0 .1.condition(spend, c);
+    }
+
+    fn post_spend(&mut self, a: &Allocator, spend: &mut SpendConditions) {
+        self.0 .0.post_spend(a, spend);
+        self.0 .1.post_spend(a, spend);
+    }
+}
+
+/// The conditions of a single spend, as recorded by [`StatsVisitor`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpendStats {
+    pub coin_id: Bytes32,
+    /// The number of conditions of each kind, by opcode.
+    pub conditions: BTreeMap<ConditionOpcode, u32>,
+    /// The number of conditions that are ignored, such as `REMARK`, or time
+    /// locks that are trivially satisfied.
+    pub skipped: u32,
+}
+
+thread_local! {
+    static SPEND_STATS: RefCell<Option<Vec<SpendStats>>> = const { RefCell::new(None) };
+}
+
+/// Records how many conditions of each kind every spend has. Since visitors are
+/// created by the parser, the stats are stored on the side, and only while
+/// running inside [`collect_stats`]. Outside of it, the visitor does nothing.
+pub struct StatsVisitor {
+    stats: SpendStats,
+}
+
+/// Runs `f` (typically a call to `parse_spends()` with a visitor that includes
+/// [`StatsVisitor`]) and returns its result along with the stats of every spend
+/// that was parsed, in order.
+pub fn collect_stats<R>(f: impl FnOnce() -> R) -> (R, Vec<SpendStats>) {
+    let outer = SPEND_STATS.with(|stats| stats.replace(Some(Vec::new())));
+    let ret = f();
+    let stats = SPEND_STATS.with(|stats| stats.replace(outer));
+    (ret, stats.unwrap_or_default())
+}
+
+fn condition_opcode(c: &Condition) -> Option<ConditionOpcode> {
+    let op = match c {
+        Condition::AggSigUnsafe(..) => AGG_SIG_UNSAFE,
+        Condition::AggSigMe(..) => AGG_SIG_ME,
+        Condition::AggSigParent(..) => AGG_SIG_PARENT,
+        Condition::AggSigPuzzle(..) => AGG_SIG_PUZZLE,
+        Condition::AggSigAmount(..) => AGG_SIG_AMOUNT,
+        Condition::AggSigPuzzleAmount(..) => AGG_SIG_PUZZLE_AMOUNT,
+        Condition::AggSigParentAmount(..) => AGG_SIG_PARENT_AMOUNT,
+        Condition::AggSigParentPuzzle(..) => AGG_SIG_PARENT_PUZZLE,
+        Condition::CreateCoin(..) => CREATE_COIN,
+        Condition::ReserveFee(..) => RESERVE_FEE,
+        Condition::CreateCoinAnnouncement(..) => CREATE_COIN_ANNOUNCEMENT,
+        Condition::CreatePuzzleAnnouncement(..) => CREATE_PUZZLE_ANNOUNCEMENT,
+        Condition::AssertCoinAnnouncement(..) => ASSERT_COIN_ANNOUNCEMENT,
+        Condition::AssertPuzzleAnnouncement(..) => ASSERT_PUZZLE_ANNOUNCEMENT,
+        Condition::AssertConcurrentSpend(..) => ASSERT_CONCURRENT_SPEND,
+        Condition::AssertConcurrentPuzzle(..) => ASSERT_CONCURRENT_PUZZLE,
+        Condition::AssertMyCoinId(..) => ASSERT_MY_COIN_ID,
+        Condition::AssertMyParentId(..) => ASSERT_MY_PARENT_ID,
+        Condition::AssertMyPuzzlehash(..) => ASSERT_MY_PUZZLEHASH,
+        Condition::AssertMyAmount(..) => ASSERT_MY_AMOUNT,
+        Condition::AssertMyBirthSeconds(..) => ASSERT_MY_BIRTH_SECONDS,
+        Condition::AssertMyBirthHeight(..) => ASSERT_MY_BIRTH_HEIGHT,
+        Condition::AssertSecondsRelative(..) => ASSERT_SECONDS_RELATIVE,
+        Condition::AssertSecondsAbsolute(..) => ASSERT_SECONDS_ABSOLUTE,
+        Condition::AssertHeightRelative(..) => ASSERT_HEIGHT_RELATIVE,
+        Condition::AssertHeightAbsolute(..) => ASSERT_HEIGHT_ABSOLUTE,
+        Condition::AssertBeforeSecondsRelative(..) => ASSERT_BEFORE_SECONDS_RELATIVE,
+        Condition::AssertBeforeSecondsAbsolute(..) => ASSERT_BEFORE_SECONDS_ABSOLUTE,
+        Condition::AssertBeforeHeightRelative(..) => ASSERT_BEFORE_HEIGHT_RELATIVE,
+        Condition::AssertBeforeHeightAbsolute(..) => ASSERT_BEFORE_HEIGHT_ABSOLUTE,
+        Condition::AssertEphemeral => ASSERT_EPHEMERAL,
+        Condition::Softfork(..) => SOFTFORK,
+        Condition::SendMessage(..) => SEND_MESSAGE,
+        Condition::ReceiveMessage(..) => RECEIVE_MESSAGE,
+        Condition::Skip | Condition::SkipRelativeCondition => {
+            return None;
+        }
+    };
+    Some(op)
+}
+
+impl SpendVisitor for StatsVisitor {
+    fn new_spend(spend: &mut SpendConditions) -> Self {
+        Self {
+            stats: SpendStats {
+                coin_id: *spend.coin_id,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn condition(&mut self, _spend: &mut SpendConditions, c: &Condition) {
+        match condition_opcode(c) {
+            Some(op) => *self.stats.conditions.entry(op).or_default() += 1,
+            None => self.stats.skipped += 1,
+        }
+    }
+
+    fn post_spend(&mut self, _a: &Allocator, _spend: &mut SpendConditions) {
+        SPEND_STATS.with(|stats| {
+            if let Some(stats) = stats.borrow_mut().as_mut() {
+                stats.push(std::mem::take(&mut self.stats));
+            }
+        });
+    }
+}
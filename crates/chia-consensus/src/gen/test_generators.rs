@@ -1,12 +1,18 @@
 use super::conditions::{NewCoin, SpendBundleConditions, SpendConditions};
-use super::run_block_generator::{run_block_generator, run_block_generator2};
-use crate::allocator::make_allocator;
-use crate::consensus_constants::TEST_CONSTANTS;
+use super::owned_conditions::OwnedSpendBundleConditions;
+use super::run_block_generator::{
+    run_block_generator, run_block_generator2, run_block_generator_for_height,
+    run_block_generator_for_height_with_metrics,
+};
+use crate::allocator::{make_allocator, AllocatorMetrics};
+use crate::consensus_constants::{ConsensusConstants, TEST_CONSTANTS};
 use crate::gen::flags::{ALLOW_BACKREFS, DONT_VALIDATE_SIGNATURE, MEMPOOL_MODE};
+use crate::gen::validation_error::ErrorCode;
 use chia_bls::Signature;
 use chia_protocol::{Bytes, Bytes48};
 use clvmr::allocator::NodePtr;
 use clvmr::Allocator;
+use std::fmt;
 use std::iter::zip;
 use text_diff::diff;
 use text_diff::Difference;
@@ -155,58 +161,242 @@ pub(crate) fn print_diff(output: &str, expected: &str) {
     }
 }
 
+/// The first field where the (normalized) results of `run_block_generator()`
+/// and `run_block_generator2()` differ.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Divergence {
+    /// the index of the spend (after sorting by coin ID), or None if the
+    /// field isn't part of a spend
+    pub spend: Option<usize>,
+    pub field: &'static str,
+    pub lhs: String,
+    pub rhs: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(idx) = self.spend {
+            write!(f, "spend {idx}: ")?;
+        }
+        write!(
+            f,
+            "{}\n  run_block_generator:  {}\n  run_block_generator2: {}",
+            self.field, self.lhs, self.rhs
+        )
+    }
+}
+
+/// Differences between `run_block_generator()` and `run_block_generator2()`
+/// that are expected for a test case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExpectedDiff {
+    /// `run_block_generator2()` doesn't pay for running the generator ROM, so
+    /// when both succeed, its cost is strictly lower.
+    LowerCost,
+}
+
+// the generator succeeds (at least in some mode) and only differs in cost
+const ROM_COST: &[ExpectedDiff] = &[ExpectedDiff::LowerCost];
+// the generator fails the same way in both modes
+const IDENTICAL: &[ExpectedDiff] = &[];
+
+macro_rules! compare_fields {
+    ($spend:expr, $lhs:expr, $rhs:expr, $($field:ident),+) => {
+        $(
+            if $lhs.$field != $rhs.$field {
+                return Some(Divergence {
+                    spend: $spend,
+                    field: stringify!($field),
+                    lhs: format!("{:?}", $lhs.$field),
+                    rhs: format!("{:?}", $rhs.$field),
+                });
+            }
+        )+
+    };
+}
+
+/// Compares two normalized results, field by field, and returns the first
+/// field that differs.
+pub(crate) fn first_divergence(
+    lhs: &Result<OwnedSpendBundleConditions, ErrorCode>,
+    rhs: &Result<OwnedSpendBundleConditions, ErrorCode>,
+) -> Option<Divergence> {
+    let (lhs, rhs) = match (lhs, rhs) {
+        (Ok(lhs), Ok(rhs)) => (lhs, rhs),
+        (lhs, rhs) => {
+            let summary = |r: &Result<OwnedSpendBundleConditions, ErrorCode>| match r {
+                Ok(_) => "Ok".to_string(),
+                Err(code) => format!("Err({code:?})"),
+            };
+            if summary(lhs) == summary(rhs) {
+                return None;
+            }
+            return Some(Divergence {
+                spend: None,
+                field: "result",
+                lhs: summary(lhs),
+                rhs: summary(rhs),
+            });
+        }
+    };
+
+    if lhs.spends.len() != rhs.spends.len() {
+        return Some(Divergence {
+            spend: None,
+            field: "spends.len()",
+            lhs: lhs.spends.len().to_string(),
+            rhs: rhs.spends.len().to_string(),
+        });
+    }
+    for (idx, (l, r)) in zip(&lhs.spends, &rhs.spends).enumerate() {
+        compare_fields!(
+            Some(idx),
+            l,
+            r,
+            coin_id,
+            parent_id,
+            puzzle_hash,
+            coin_amount,
+            height_relative,
+            seconds_relative,
+            before_height_relative,
+            before_seconds_relative,
+            birth_height,
+            birth_seconds,
+            create_coin,
+            agg_sig_me,
+            agg_sig_parent,
+            agg_sig_puzzle,
+            agg_sig_amount,
+            agg_sig_puzzle_amount,
+            agg_sig_parent_amount,
+            agg_sig_parent_puzzle,
+            flags
+        );
+    }
+    compare_fields!(
+        None,
+        lhs,
+        rhs,
+        reserve_fee,
+        height_absolute,
+        seconds_absolute,
+        before_height_absolute,
+        before_seconds_absolute,
+        agg_sig_unsafe,
+        cost,
+        removal_amount,
+        addition_amount,
+        validated_signature
+    );
+    None
+}
+
+/// Runs the generator with both `run_block_generator()` and
+/// `run_block_generator2()` and panics with the first field where their
+/// results differ, unless the difference is listed in `expected_diffs`.
+pub(crate) fn assert_generators_agree(
+    generator: &[u8],
+    block_refs: &[Vec<u8>],
+    max_cost: u64,
+    flags: u32,
+    constants: &ConsensusConstants,
+    expected_diffs: &[ExpectedDiff],
+) {
+    let run = |hard_fork: bool| {
+        let mut a = make_allocator(flags);
+        let flags = flags | DONT_VALIDATE_SIGNATURE;
+        let signature = Signature::default();
+        let conds = if hard_fork {
+            run_block_generator2(
+                &mut a, generator, block_refs, max_cost, flags, &signature, None, constants,
+            )
+        } else {
+            run_block_generator(
+                &mut a, generator, block_refs, max_cost, flags, &signature, None, constants,
+            )
+        };
+        conds
+            .map(|conds| {
+                let mut conds = OwnedSpendBundleConditions::from(&a, conds);
+                conds.normalize();
+                conds
+            })
+            .map_err(|err| err.1)
+    };
+
+    let lhs = run(false);
+    let mut rhs = run(true);
+
+    if let (Ok(lhs), Ok(rhs)) = (&lhs, &mut rhs) {
+        if expected_diffs.contains(&ExpectedDiff::LowerCost) {
+            assert!(
+                rhs.cost < lhs.cost,
+                "expected run_block_generator2 to have lower cost ({} >= {})",
+                rhs.cost,
+                lhs.cost
+            );
+            rhs.cost = lhs.cost;
+        }
+    }
+
+    if let Some(divergence) = first_divergence(&lhs, &rhs) {
+        panic!("run_block_generator2 diverged from run_block_generator: {divergence}");
+    }
+}
+
 #[rstest]
-#[case("new-agg-sigs")]
-#[case("infinity-g1")]
-#[case("block-1ee588dc")]
-#[case("block-6fe59b24")]
-#[case("block-b45268ac")]
-#[case("block-c2a8df0d")]
-#[case("block-e5002df2")]
-#[case("block-4671894")]
-#[case("block-225758")]
-#[case("assert-puzzle-announce-fail")]
-#[case("block-834752")]
-#[case("block-834752-compressed")]
-#[case("block-834760")]
-#[case("block-834761")]
-#[case("block-834765")]
-#[case("block-834766")]
-#[case("block-834768")]
-#[case("create-coin-different-amounts")]
-#[case("create-coin-hint-duplicate-outputs")]
-#[case("create-coin-hint")]
-#[case("create-coin-hint2")]
-#[case("deep-recursion-plus")]
-#[case("double-spend")]
-#[case("duplicate-coin-announce")]
-#[case("duplicate-create-coin")]
-#[case("duplicate-height-absolute-div")]
-#[case("duplicate-height-absolute-substr-tail")]
-#[case("duplicate-height-absolute-substr")]
-#[case("duplicate-height-absolute")]
-#[case("duplicate-height-relative")]
-#[case("duplicate-outputs")]
-#[case("duplicate-reserve-fee")]
-#[case("duplicate-seconds-absolute")]
-#[case("duplicate-seconds-relative")]
-#[case("height-absolute-ladder")]
-#[case("infinite-recursion1")]
-#[case("infinite-recursion2")]
-#[case("infinite-recursion3")]
-#[case("infinite-recursion4")]
-#[case("invalid-conditions")]
-#[case("just-puzzle-announce")]
-#[case("many-create-coin")]
-#[case("many-large-ints-negative")]
-#[case("many-large-ints")]
-#[case("max-height")]
-#[case("multiple-reserve-fee")]
-#[case("negative-reserve-fee")]
-#[case("recursion-pairs")]
-#[case("unknown-condition")]
-#[case("duplicate-messages")]
-fn run_generator(#[case] name: &str) {
+#[case("new-agg-sigs", ROM_COST)]
+#[case("infinity-g1", IDENTICAL)]
+#[case("block-1ee588dc", ROM_COST)]
+#[case("block-6fe59b24", ROM_COST)]
+#[case("block-b45268ac", ROM_COST)]
+#[case("block-c2a8df0d", ROM_COST)]
+#[case("block-e5002df2", ROM_COST)]
+#[case("block-4671894", ROM_COST)]
+#[case("block-225758", ROM_COST)]
+#[case("assert-puzzle-announce-fail", IDENTICAL)]
+#[case("block-834752", ROM_COST)]
+#[case("block-834752-compressed", ROM_COST)]
+#[case("block-834760", ROM_COST)]
+#[case("block-834761", ROM_COST)]
+#[case("block-834765", ROM_COST)]
+#[case("block-834766", ROM_COST)]
+#[case("block-834768", ROM_COST)]
+#[case("create-coin-different-amounts", ROM_COST)]
+#[case("create-coin-hint-duplicate-outputs", IDENTICAL)]
+#[case("create-coin-hint", ROM_COST)]
+#[case("create-coin-hint2", ROM_COST)]
+#[case("deep-recursion-plus", IDENTICAL)]
+#[case("double-spend", IDENTICAL)]
+#[case("duplicate-coin-announce", IDENTICAL)]
+#[case("duplicate-create-coin", IDENTICAL)]
+#[case("duplicate-height-absolute-div", ROM_COST)]
+#[case("duplicate-height-absolute-substr-tail", IDENTICAL)]
+#[case("duplicate-height-absolute-substr", IDENTICAL)]
+#[case("duplicate-height-absolute", IDENTICAL)]
+#[case("duplicate-height-relative", IDENTICAL)]
+#[case("duplicate-outputs", IDENTICAL)]
+#[case("duplicate-reserve-fee", IDENTICAL)]
+#[case("duplicate-seconds-absolute", IDENTICAL)]
+#[case("duplicate-seconds-relative", IDENTICAL)]
+#[case("height-absolute-ladder", IDENTICAL)]
+#[case("infinite-recursion1", IDENTICAL)]
+#[case("infinite-recursion2", IDENTICAL)]
+#[case("infinite-recursion3", IDENTICAL)]
+#[case("infinite-recursion4", IDENTICAL)]
+#[case("invalid-conditions", IDENTICAL)]
+#[case("just-puzzle-announce", ROM_COST)]
+#[case("many-create-coin", ROM_COST)]
+#[case("many-large-ints-negative", ROM_COST)]
+#[case("many-large-ints", IDENTICAL)]
+#[case("max-height", ROM_COST)]
+#[case("multiple-reserve-fee", ROM_COST)]
+#[case("negative-reserve-fee", IDENTICAL)]
+#[case("recursion-pairs", IDENTICAL)]
+#[case("unknown-condition", ROM_COST)]
+#[case("duplicate-messages", IDENTICAL)]
+fn run_generator(#[case] name: &str, #[case] expected_diffs: &[ExpectedDiff]) {
     use std::fs::read_to_string;
 
     let filename = format!("../../generator-tests/{name}.txt");
@@ -231,6 +421,15 @@ fn run_generator(#[case] name: &str) {
     const DEFAULT_FLAGS: u32 = ALLOW_BACKREFS;
     for (flags, expected) in zip(&[DEFAULT_FLAGS, DEFAULT_FLAGS | MEMPOOL_MODE], expected) {
         println!("flags: {flags:x}");
+        assert_generators_agree(
+            &generator,
+            &block_refs,
+            11_000_000_000,
+            *flags,
+            &TEST_CONSTANTS,
+            expected_diffs,
+        );
+
         let mut a = make_allocator(*flags);
         let conds = run_block_generator(
             &mut a,
@@ -288,3 +487,309 @@ fn run_generator(#[case] name: &str) {
         }
     }
 }
+
+#[test]
+fn generators_divergence() {
+    use std::fs::read_to_string;
+
+    let test_file =
+        read_to_string("../../generator-tests/create-coin-hint.txt").expect("test file not found");
+    let (generator, _) = test_file.split_once('\n').expect("invalid test file");
+    let generator = hex::decode(generator).expect("invalid hex encoded generator");
+
+    let mut a = make_allocator(0);
+    let conds = run_block_generator(
+        &mut a,
+        &generator,
+        Vec::<&[u8]>::new(),
+        11_000_000_000,
+        DONT_VALIDATE_SIGNATURE,
+        &Signature::default(),
+        None,
+        &TEST_CONSTANTS,
+    )
+    .expect("run_block_generator");
+    let mut conds = OwnedSpendBundleConditions::from(&a, conds);
+    conds.normalize();
+    let lhs = Ok(conds.clone());
+
+    assert_eq!(first_divergence(&lhs, &Ok(conds.clone())), None);
+
+    // the spend index and field are reported
+    let mut rhs = conds.clone();
+    rhs.spends[0].create_coin[1].1 += 1;
+    let divergence = first_divergence(&lhs, &Ok(rhs)).expect("divergence");
+    assert_eq!(divergence.spend, Some(0));
+    assert_eq!(divergence.field, "create_coin");
+
+    let mut rhs = conds.clone();
+    rhs.cost -= 1;
+    let divergence = first_divergence(&lhs, &Ok(rhs)).expect("divergence");
+    assert_eq!(divergence.spend, None);
+    assert_eq!(divergence.field, "cost");
+    assert_eq!(divergence.lhs, conds.cost.to_string());
+    assert_eq!(divergence.rhs, (conds.cost - 1).to_string());
+
+    let err = Err(ErrorCode::DoubleSpend);
+    let divergence = first_divergence(&lhs, &err).expect("divergence");
+    assert_eq!(divergence.field, "result");
+    assert_eq!(divergence.rhs, "Err(DoubleSpend)");
+    assert_eq!(first_divergence(&err, &Err(ErrorCode::DoubleSpend)), None);
+    assert!(first_divergence(&err, &Err(ErrorCode::InvalidCondition)).is_some());
+}
+
+#[rstest]
+#[case("block-834768")]
+#[case("block-225758")]
+#[case("block-834752-compressed")]
+fn run_generator_for_height(#[case] name: &str) {
+    use std::fs::read_to_string;
+
+    let filename = format!("../../generator-tests/{name}.txt");
+    let test_file = read_to_string(filename).expect("test file not found");
+    let (generator, _) = test_file.split_once('\n').expect("invalid test file");
+    let generator = hex::decode(generator).expect("invalid hex encoded generator");
+
+    let mut block_refs = Vec::<Vec<u8>>::new();
+    if let Ok(env_hex) = read_to_string(format!("../../generator-tests/{name}.env")) {
+        block_refs.push(hex::decode(env_hex).expect("hex decode env-file"));
+    }
+
+    let run = |height: u32| {
+        let mut a = make_allocator(0);
+        run_block_generator_for_height(
+            &mut a,
+            &generator,
+            &block_refs,
+            11_000_000_000,
+            DONT_VALIDATE_SIGNATURE,
+            height,
+            &Signature::default(),
+            None,
+            &TEST_CONSTANTS,
+        )
+        .map(|conds| conds.cost)
+    };
+    let run_direct = |flags: u32, hard_fork: bool| {
+        let mut a = make_allocator(0);
+        let flags = flags | DONT_VALIDATE_SIGNATURE;
+        let signature = Signature::default();
+        let conds = if hard_fork {
+            run_block_generator2(
+                &mut a,
+                &generator,
+                &block_refs,
+                11_000_000_000,
+                flags,
+                &signature,
+                None,
+                &TEST_CONSTANTS,
+            )
+        } else {
+            run_block_generator(
+                &mut a,
+                &generator,
+                &block_refs,
+                11_000_000_000,
+                flags,
+                &signature,
+                None,
+                &TEST_CONSTANTS,
+            )
+        };
+        conds.map(|conds| conds.cost)
+    };
+
+    let hard_fork_height = TEST_CONSTANTS.hard_fork_height;
+    let pre_fork = run(hard_fork_height - 1);
+    let post_fork = run(hard_fork_height).expect("generator failed after the hard fork");
+
+    // after the hard fork, the cost of the generator ROM is no longer paid
+    assert_eq!(Ok(post_fork), run_direct(ALLOW_BACKREFS, true));
+
+    if name.ends_with("-compressed") {
+        // back-references are only allowed after the hard fork
+        assert!(pre_fork.is_err());
+    } else {
+        let pre_fork = pre_fork.expect("generator failed before the hard fork");
+        assert_eq!(Ok(pre_fork), run_direct(0, false));
+        assert!(post_fork < pre_fork);
+    }
+}
+
+#[rstest]
+fn run_generator_with_metrics(#[values(0, 1)] fork: u32) {
+    use std::fs::read_to_string;
+
+    // both before and after the hard fork
+    let height = TEST_CONSTANTS.hard_fork_height - 1 + fork;
+
+    let run = |a: &mut Allocator, name: &str| {
+        let filename = format!("../../generator-tests/{name}.txt");
+        let test_file = read_to_string(filename).expect("test file not found");
+        let (generator, _) = test_file.split_once('\n').expect("invalid test file");
+        let generator = hex::decode(generator).expect("invalid hex encoded generator");
+
+        let (conds, metrics) = run_block_generator_for_height_with_metrics(
+            a,
+            &generator,
+            Vec::<&[u8]>::new(),
+            11_000_000_000,
+            DONT_VALIDATE_SIGNATURE,
+            height,
+            &Signature::default(),
+            None,
+            &TEST_CONSTANTS,
+        )
+        .expect("run_block_generator");
+
+        // the metrics don't change the result
+        let mut a2 = make_allocator(0);
+        let expected = run_block_generator_for_height(
+            &mut a2,
+            &generator,
+            Vec::<&[u8]>::new(),
+            11_000_000_000,
+            DONT_VALIDATE_SIGNATURE,
+            height,
+            &Signature::default(),
+            None,
+            &TEST_CONSTANTS,
+        )
+        .expect("run_block_generator");
+        assert_eq!(conds.cost, expected.cost);
+        assert_eq!(metrics, AllocatorMetrics::new(&a2));
+        (generator.len(), metrics)
+    };
+
+    let mut a = make_allocator(0);
+    let (small_len, small) = run(&mut a, "block-834760");
+    assert_eq!(small, AllocatorMetrics::new(&a));
+
+    // only what the second generator allocated is counted
+    let (large_len, large) = run(&mut a, "block-c2a8df0d");
+    assert_eq!(AllocatorMetrics::since(&a, &small), large);
+
+    assert!(small_len < large_len);
+    assert!(small.atoms < large.atoms);
+    assert!(small.small_atoms < large.small_atoms);
+    assert!(small.pairs < large.pairs);
+    assert!(small.heap_size < large.heap_size);
+}
+
+// SpendBundle::estimate_cost() is meant to be an upper bound of the cost of
+// the spends. Make sure it is for the spends in all mainnet blocks we have,
+// when the wallet knows which conditions its spends output
+#[rstest]
+#[case("block-1ee588dc")]
+#[case("block-6fe59b24")]
+#[case("block-b45268ac")]
+#[case("block-c2a8df0d")]
+#[case("block-e5002df2")]
+#[case("block-4671894")]
+#[case("block-225758")]
+#[case("block-834752")]
+#[case("block-834752-compressed")]
+#[case("block-834760")]
+#[case("block-834761")]
+#[case("block-834765")]
+#[case("block-834766")]
+#[case("block-834768")]
+fn estimate_cost_upper_bound(#[case] name: &str) {
+    use super::opcodes::{ConditionOpcode, AGG_SIG_ME, AGG_SIG_UNSAFE, CREATE_COIN};
+    use super::run_block_generator::setup_generator_args;
+    use chia_protocol::{Amount, Bytes32, Coin, CoinSpend, Program, SpendBundle};
+    use chia_traits::Streamable;
+    use clvm_traits::FromClvm;
+    use clvm_utils::tree_hash;
+    use clvmr::chia_dialect::ChiaDialect;
+    use clvmr::op_utils::first;
+    use clvmr::run_program::run_program;
+    use clvmr::serde::{node_from_bytes_backrefs, node_to_bytes};
+    use std::fs::read_to_string;
+
+    let filename = format!("../../generator-tests/{name}.txt");
+    let test_file = read_to_string(filename).expect("test file not found");
+    let (generator, _) = test_file.split_once('\n').expect("invalid test file");
+    let generator = hex::decode(generator).expect("invalid hex encoded generator");
+
+    let mut block_refs = Vec::<Vec<u8>>::new();
+    if let Ok(env_hex) = read_to_string(format!("../../generator-tests/{name}.env")) {
+        block_refs.push(hex::decode(env_hex).expect("hex decode env-file"));
+    }
+
+    let mut a = make_allocator(ALLOW_BACKREFS);
+    let conds = run_block_generator(
+        &mut a,
+        &generator,
+        &block_refs,
+        11_000_000_000,
+        ALLOW_BACKREFS | DONT_VALIDATE_SIGNATURE,
+        &Signature::default(),
+        None,
+        &TEST_CONSTANTS,
+    )
+    .expect("run_block_generator");
+
+    let condition_cost = |op: ConditionOpcode| (u16::from(op), op.cost().expect("cost"));
+    let mut condition_costs = Vec::<(u16, u64)>::new();
+    for s in &conds.spends {
+        let agg_sigs = s.agg_sig_me.len()
+            + s.agg_sig_parent.len()
+            + s.agg_sig_puzzle.len()
+            + s.agg_sig_amount.len()
+            + s.agg_sig_puzzle_amount.len()
+            + s.agg_sig_parent_amount.len()
+            + s.agg_sig_parent_puzzle.len();
+        condition_costs.extend((0..s.create_coin.len()).map(|_| condition_cost(CREATE_COIN)));
+        condition_costs.extend((0..agg_sigs).map(|_| condition_cost(AGG_SIG_ME)));
+    }
+    condition_costs.extend((0..conds.agg_sig_unsafe.len()).map(|_| condition_cost(AGG_SIG_UNSAFE)));
+
+    // this is the spend bundle the wallet(s) would have submitted
+    let mut a = make_allocator(ALLOW_BACKREFS);
+    let program = node_from_bytes_backrefs(&mut a, &generator).expect("generator");
+    let args = setup_generator_args(&mut a, &block_refs).expect("block refs");
+    let dialect = ChiaDialect::new(0);
+    let output = run_program(&mut a, &dialect, program, args, 11_000_000_000)
+        .expect("run generator")
+        .1;
+    let mut all_spends = first(&a, output).expect("spend list");
+    let mut coin_spends = Vec::<CoinSpend>::new();
+    while let Some((spend, tail)) = a.next(all_spends) {
+        all_spends = tail;
+        let (parent_id, (puzzle, (amount, (solution, _)))) =
+            <(Bytes32, (NodePtr, (Amount, (NodePtr, NodePtr))))>::from_clvm(&a, spend)
+                .expect("spend");
+        coin_spends.push(CoinSpend::new(
+            Coin::new(parent_id, tree_hash(&a, puzzle).into(), amount),
+            Program::new(node_to_bytes(&a, puzzle).expect("puzzle").into()),
+            Program::new(node_to_bytes(&a, solution).expect("solution").into()),
+        ));
+    }
+    assert_eq!(coin_spends.len(), conds.spends.len());
+    let bundle = SpendBundle::new(coin_spends, Signature::default());
+
+    let estimate = bundle.estimate_cost(TEST_CONSTANTS.cost_per_byte, &condition_costs);
+    assert_eq!(
+        estimate.serialized_size,
+        bundle.to_bytes().expect("to_bytes").len() as u64
+    );
+    assert_eq!(
+        estimate.byte_cost,
+        estimate.serialized_size * TEST_CONSTANTS.cost_per_byte
+    );
+    assert_eq!(
+        estimate.condition_cost,
+        condition_costs.iter().map(|(_, cost)| cost).sum::<u64>()
+    );
+    assert_eq!(
+        estimate.cost,
+        estimate.byte_cost + estimate.execution_cost + estimate.condition_cost
+    );
+    assert!(
+        estimate.cost >= conds.cost,
+        "estimate {estimate:?} is below the actual cost {}",
+        conds.cost
+    );
+}
@@ -0,0 +1,25 @@
+use super::owned_conditions::OwnedSpendConditions;
+use chia_protocol::{Bytes32, Coin};
+
+pub(crate) fn coin(i: u8) -> Coin {
+    Coin::new(Bytes32::new([i; 32]), Bytes32::new([100; 32]), 1000.into())
+}
+
+pub(crate) fn child(parent: &Coin, puzzle_hash: Bytes32, amount: u64) -> Coin {
+    Coin::new(parent.coin_id(), puzzle_hash, amount.into())
+}
+
+/// the conditions of spending `coin`, creating the coins in `create_coin`
+pub(crate) fn spend(coin: &Coin, create_coin: &[(Bytes32, u64)]) -> OwnedSpendConditions {
+    OwnedSpendConditions {
+        coin_id: coin.coin_id(),
+        parent_id: coin.parent_coin_info,
+        puzzle_hash: coin.puzzle_hash,
+        coin_amount: coin.amount.into(),
+        create_coin: create_coin
+            .iter()
+            .map(|(puzzle_hash, amount)| (*puzzle_hash, *amount, None))
+            .collect(),
+        ..Default::default()
+    }
+}
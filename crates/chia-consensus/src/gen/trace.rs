@@ -0,0 +1,131 @@
+// Instrumentation for the optional "tracing" feature. Without the feature, the
+// macros expand to nothing and trace_error() is a no-op, so validation doesn't
+// pay anything for it.
+
+use crate::gen::validation_error::ValidationErr;
+
+// Enters a debug level span, which lasts until the end of the enclosing block.
+macro_rules! debug_span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = ::tracing::debug_span!($($arg)*).entered();
+    };
+}
+
+// Emits a debug level event.
+macro_rules! debug_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!($($arg)*);
+    };
+}
+
+pub(crate) use {debug_event, debug_span};
+
+// Emits an event with the error code, if `result` is an error.
+#[inline]
+pub(crate) fn trace_error<T>(
+    result: Result<T, ValidationErr>,
+    context: &'static str,
+) -> Result<T, ValidationErr> {
+    #[cfg(feature = "tracing")]
+    if let Err(ValidationErr(_, code)) = &result {
+        ::tracing::debug!(error = ?code, "{context} failed validation");
+    }
+    #[cfg(not(feature = "tracing"))]
+    let _ = context;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_tracing_is_optional() {
+        // builds without the feature must not depend on tracing. This skips
+        // the feature itself and tracing-subscriber, which is only used by
+        // the tests
+        let manifest = include_str!("../../Cargo.toml");
+        let deps: Vec<&str> = manifest
+            .lines()
+            .filter(|line| line.starts_with("tracing = {"))
+            .collect();
+        assert!(!deps.is_empty());
+        for dep in deps {
+            assert!(dep.contains("optional = true"), "{dep}");
+        }
+        assert!(!manifest
+            .lines()
+            .any(|line| line.starts_with("default") && line.contains("tracing")));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_error_events() {
+        use crate::consensus_constants::TEST_CONSTANTS;
+        use crate::gen::conditions::{parse_spends, MempoolVisitor};
+        use crate::gen::flags::DONT_VALIDATE_SIGNATURE;
+        use chia_bls::Signature;
+        use clvmr::Allocator;
+        use std::io::Write;
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        // a spend of a coin with amount 123, asserting its amount is 100
+        let mut a = Allocator::new();
+        let mut spend = Vec::new();
+        for item in [&[1_u8; 32][..], &[2; 32], &[123]] {
+            spend.push(a.new_atom(item).unwrap());
+        }
+        let mut cond = a.nil();
+        for item in [&[73_u8][..], &[100]].iter().rev() {
+            let atom = a.new_atom(item).unwrap();
+            cond = a.new_pair(atom, cond).unwrap();
+        }
+        let conds = a.new_pair(cond, a.nil()).unwrap();
+        let mut spend_list = a.new_pair(conds, a.nil()).unwrap();
+        for item in spend.iter().rev() {
+            spend_list = a.new_pair(*item, spend_list).unwrap();
+        }
+        let spends = a.new_pair(spend_list, a.nil()).unwrap();
+        let spends = a.new_pair(spends, a.nil()).unwrap();
+
+        let result = tracing::subscriber::with_default(subscriber, || {
+            parse_spends::<MempoolVisitor>(
+                &a,
+                spends,
+                11_000_000_000,
+                DONT_VALIDATE_SIGNATURE,
+                &Signature::default(),
+                None,
+                &TEST_CONSTANTS,
+            )
+        });
+        assert!(result.is_err());
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("AssertMyAmountFailed"), "{output}");
+        assert!(output.contains("asserted=100"), "{output}");
+        assert!(output.contains("coin_amount=123"), "{output}");
+        assert!(output.contains("spend{coin_id="), "{output}");
+    }
+}
@@ -3,9 +3,16 @@ use clvmr::reduction::EvalErr;
 use thiserror::Error;
 
 #[cfg(feature = "py-bindings")]
-use pyo3::PyErr;
+use pyo3::prelude::*;
+#[cfg(feature = "py-bindings")]
+use pyo3::sync::GILOnceCell;
+#[cfg(feature = "py-bindings")]
+use pyo3::types::{PyDict, PyTuple, PyType};
 
+// The numeric value of each error code is stable and matches the Err enum in
+// chia-blockchain. See the `From<ErrorCode> for u32` implementation below.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
 pub enum ErrorCode {
     #[default]
     Unknown,
@@ -163,6 +170,10 @@ pub enum ErrorCode {
     InvalidMessageMode,
     InvalidCoinId,
     MessageNotSentOrReceived,
+    DuplicateCoinId,
+    /// Validation was aborted through a `CancellationToken`. This doesn't say
+    /// anything about whether the block or spend bundle is valid.
+    Cancelled,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
@@ -185,10 +196,138 @@ impl From<std::io::Error> for ValidationErr {
     }
 }
 
+// All validation errors raised to python derive from ValidationError, which
+// in turn derives from both ValueError and TypeError. Depending on the entry
+// point, these errors used to be raised as plain ValueError or TypeError, so
+// existing callers catching either keep working. The numeric error code is
+// in the "code" attribute, and the name of the error in the "message"
+// attribute. pyo3's create_exception!() only supports a single base class,
+// so these types are created by calling type() instead.
+#[cfg(feature = "py-bindings")]
+fn new_exception_type<'py>(
+    py: Python<'py>,
+    name: &str,
+    bases: Bound<'py, PyTuple>,
+    doc: &str,
+) -> Py<PyType> {
+    let create = || -> PyResult<Py<PyType>> {
+        let dict = PyDict::new(py);
+        dict.set_item("__module__", "chia_rs")?;
+        dict.set_item("__doc__", doc)?;
+        let ty = py.get_type::<PyType>().call1((name, bases, dict))?;
+        Ok(ty.downcast_into::<PyType>()?.unbind())
+    };
+    create().expect("failed to create exception type")
+}
+
+/// The python type `chia_rs.ValidationError`
+#[cfg(feature = "py-bindings")]
+pub fn validation_error_type(py: Python<'_>) -> &Bound<'_, PyType> {
+    static TYPE_OBJECT: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+    TYPE_OBJECT
+        .get_or_init(py, || {
+            let bases = PyTuple::new(
+                py,
+                [
+                    py.get_type::<pyo3::exceptions::PyValueError>(),
+                    py.get_type::<pyo3::exceptions::PyTypeError>(),
+                ],
+            )
+            .expect("failed to create tuple");
+            new_exception_type(
+                py,
+                "ValidationError",
+                bases,
+                "A block or spend bundle failed validation",
+            )
+        })
+        .bind(py)
+}
+
+/// The python type `chia_rs.CostError`
+#[cfg(feature = "py-bindings")]
+pub fn cost_error_type(py: Python<'_>) -> &Bound<'_, PyType> {
+    static TYPE_OBJECT: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+    TYPE_OBJECT
+        .get_or_init(py, || {
+            let bases =
+                PyTuple::new(py, [validation_error_type(py)]).expect("failed to create tuple");
+            new_exception_type(
+                py,
+                "CostError",
+                bases,
+                "A block or spend bundle exceeded a cost limit",
+            )
+        })
+        .bind(py)
+}
+
+/// The python type `chia_rs.SignatureError`
+#[cfg(feature = "py-bindings")]
+pub fn signature_error_type(py: Python<'_>) -> &Bound<'_, PyType> {
+    static TYPE_OBJECT: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+    TYPE_OBJECT
+        .get_or_init(py, || {
+            let bases =
+                PyTuple::new(py, [validation_error_type(py)]).expect("failed to create tuple");
+            new_exception_type(
+                py,
+                "SignatureError",
+                bases,
+                "A block or spend bundle has an invalid signature",
+            )
+        })
+        .bind(py)
+}
+
+#[cfg(feature = "py-bindings")]
+impl ErrorCode {
+    /// Creates the python exception for this error code, with the arguments
+    /// `args`. Most entry points use `("ValidationError", code)`, via
+    /// `From<ErrorCode> for PyErr`.
+    pub fn to_py_err<A>(self, args: A) -> PyErr
+    where
+        A: pyo3::PyErrArguments + Send + Sync + 'static,
+    {
+        let code = u32::from(self);
+        let message = format!("{self:?}");
+        Python::with_gil(|py| {
+            let ty = match self {
+                ErrorCode::CostExceeded
+                | ErrorCode::InvalidBlockCost
+                | ErrorCode::InvalidCostResult
+                | ErrorCode::InvalidSoftforkCost => cost_error_type(py),
+                ErrorCode::BadAggregateSignature
+                | ErrorCode::InvalidCoinbaseSignature
+                | ErrorCode::InvalidPlotSignature
+                | ErrorCode::InvalidCcSignature
+                | ErrorCode::InvalidRcSignature
+                | ErrorCode::InvalidPoolSignature
+                | ErrorCode::BadCoinbaseSignature => signature_error_type(py),
+                _ => validation_error_type(py),
+            };
+            let ret = PyErr::from_type(ty.clone(), args);
+            let value = ret.value(py);
+            // setting attributes on an exception object cannot fail in
+            // practice. If it did, the code is still available in args
+            value.setattr("code", code).ok();
+            value.setattr("message", message).ok();
+            ret
+        })
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+impl From<ErrorCode> for PyErr {
+    fn from(err: ErrorCode) -> PyErr {
+        err.to_py_err(("ValidationError", u32::from(err)))
+    }
+}
+
 #[cfg(feature = "py-bindings")]
 impl From<ValidationErr> for PyErr {
     fn from(err: ValidationErr) -> PyErr {
-        pyo3::exceptions::PyValueError::new_err(("ValidationError", u32::from(err.1)))
+        err.1.into()
     }
 }
 
@@ -359,10 +498,171 @@ impl From<ErrorCode> for u32 {
             ErrorCode::InvalidMessageMode => 145,
             ErrorCode::InvalidCoinId => 146,
             ErrorCode::MessageNotSentOrReceived => 147,
+            ErrorCode::DuplicateCoinId => 148,
+            ErrorCode::Cancelled => 149,
         }
     }
 }
 
+impl TryFrom<u32> for ErrorCode {
+    type Error = u32;
+
+    /// The inverse of `u32::from(ErrorCode)`. Some numeric codes are shared by
+    /// several (more specific) variants; those decode to the first variant
+    /// listed for that code. Unknown values are returned as the error.
+    fn try_from(code: u32) -> Result<Self, u32> {
+        Ok(match code {
+            1 => ErrorCode::Unknown,
+            2 => ErrorCode::InvalidBlockSolution,
+            3 => ErrorCode::InvalidCoinSolution,
+            4 => ErrorCode::DuplicateOutput,
+            5 => ErrorCode::DoubleSpend,
+            6 => ErrorCode::UnknownUnspent,
+            7 => ErrorCode::BadAggregateSignature,
+            8 => ErrorCode::WrongPuzzleHash,
+            9 => ErrorCode::BadFarmerCoinAmount,
+            10 => ErrorCode::InvalidCondition,
+            11 => ErrorCode::AssertMyCoinIdFailed,
+            12 => ErrorCode::AssertPuzzleAnnouncementFailed,
+            13 => ErrorCode::AssertHeightRelativeFailed,
+            14 => ErrorCode::AssertHeightAbsoluteFailed,
+            15 => ErrorCode::AssertSecondsAbsoluteFailed,
+            16 => ErrorCode::CoinAmountExceedsMaximum,
+            17 => ErrorCode::SexpError,
+            18 => ErrorCode::InvalidFeeLowFee,
+            19 => ErrorCode::MempoolConflict,
+            20 => ErrorCode::MintingCoin,
+            21 => ErrorCode::ExtendsUnknownBlock,
+            22 => ErrorCode::CoinbaseNotYetSpendable,
+            23 => ErrorCode::CostExceeded,
+            24 => ErrorCode::BadAdditionRoot,
+            25 => ErrorCode::BadRemovalRoot,
+            26 => ErrorCode::InvalidPospaceHash,
+            27 => ErrorCode::InvalidCoinbaseSignature,
+            28 => ErrorCode::InvalidPlotSignature,
+            29 => ErrorCode::TimestampTooFarInPast,
+            30 => ErrorCode::TimestampTooFarInFuture,
+            31 => ErrorCode::InvalidTransactionsFilterHash,
+            32 => ErrorCode::InvalidPospaceChallenge,
+            33 => ErrorCode::InvalidPospace,
+            34 => ErrorCode::InvalidHeight,
+            35 => ErrorCode::InvalidCoinbaseAmount,
+            36 => ErrorCode::InvalidMerkleRoot,
+            37 => ErrorCode::InvalidBlockFeeAmount,
+            38 => ErrorCode::InvalidWeight,
+            39 => ErrorCode::InvalidTotalIters,
+            40 => ErrorCode::BlockIsNotFinished,
+            41 => ErrorCode::InvalidNumIterations,
+            42 => ErrorCode::InvalidPot,
+            43 => ErrorCode::InvalidPotChallenge,
+            44 => ErrorCode::InvalidTransactionsGeneratorHash,
+            45 => ErrorCode::InvalidPoolTarget,
+            46 => ErrorCode::InvalidCoinbaseParent,
+            47 => ErrorCode::InvalidFeesCoinParent,
+            48 => ErrorCode::ReserveFeeConditionFailed,
+            49 => ErrorCode::NotBlockButHasData,
+            50 => ErrorCode::IsTransactionBlockButNoData,
+            51 => ErrorCode::InvalidPrevBlockHash,
+            52 => ErrorCode::InvalidTransactionsInfoHash,
+            53 => ErrorCode::InvalidFoliageBlockHash,
+            54 => ErrorCode::InvalidRewardCoins,
+            55 => ErrorCode::InvalidBlockCost,
+            56 => ErrorCode::NoEndOfSlotInfo,
+            57 => ErrorCode::InvalidPrevChallengeSlotHash,
+            58 => ErrorCode::InvalidSubEpochSummaryHash,
+            59 => ErrorCode::NoSubEpochSummaryHash,
+            60 => ErrorCode::ShouldNotMakeChallengeBlock,
+            61 => ErrorCode::ShouldMakeChallengeBlock,
+            62 => ErrorCode::InvalidChallengeChainData,
+            65 => ErrorCode::InvalidCcEosVdf,
+            66 => ErrorCode::InvalidRcEosVdf,
+            67 => ErrorCode::InvalidChallengeSlotHashRc,
+            68 => ErrorCode::InvalidPriorPointRc,
+            69 => ErrorCode::InvalidDeficit,
+            70 => ErrorCode::InvalidSubEpochSummary,
+            71 => ErrorCode::InvalidPrevSubEpochSummaryHash,
+            72 => ErrorCode::InvalidRewardChainHash,
+            73 => ErrorCode::InvalidSubEpochOverflow,
+            74 => ErrorCode::InvalidNewDifficulty,
+            75 => ErrorCode::InvalidNewSubSlotIters,
+            76 => ErrorCode::InvalidCcSpVdf,
+            77 => ErrorCode::InvalidRcSpVdf,
+            78 => ErrorCode::InvalidCcSignature,
+            79 => ErrorCode::InvalidRcSignature,
+            80 => ErrorCode::CannotMakeCcBlock,
+            81 => ErrorCode::InvalidRcSpPrevIp,
+            82 => ErrorCode::InvalidRcIpPrevIp,
+            83 => ErrorCode::InvalidIsTransactionBlock,
+            84 => ErrorCode::InvalidUrsbHash,
+            85 => ErrorCode::OldPoolTarget,
+            86 => ErrorCode::InvalidPoolSignature,
+            87 => ErrorCode::InvalidFoliageBlockPresence,
+            88 => ErrorCode::InvalidCcIpVdf,
+            89 => ErrorCode::InvalidRcIpVdf,
+            90 => ErrorCode::IpShouldBeNone,
+            91 => ErrorCode::InvalidRewardBlockHash,
+            92 => ErrorCode::InvalidMadeNonOverflowInfusions,
+            93 => ErrorCode::NoOverflowsInFirstSubSlotNewEpoch,
+            94 => ErrorCode::MempoolNotInitialized,
+            95 => ErrorCode::ShouldNotHaveIcc,
+            96 => ErrorCode::ShouldHaveIcc,
+            97 => ErrorCode::InvalidIccVdf,
+            98 => ErrorCode::InvalidIccHashCc,
+            99 => ErrorCode::InvalidIccHashRc,
+            100 => ErrorCode::InvalidIccEosVdf,
+            101 => ErrorCode::InvalidSpIndex,
+            102 => ErrorCode::TooManyBlocks,
+            103 => ErrorCode::InvalidCcChallenge,
+            104 => ErrorCode::InvalidPrefarm,
+            105 => ErrorCode::AssertSecondsRelativeFailed,
+            106 => ErrorCode::BadCoinbaseSignature,
+            108 => ErrorCode::NoTransactionsWhileSyncing,
+            109 => ErrorCode::AlreadyIncludingTransaction,
+            110 => ErrorCode::IncompatibleNetworkId,
+            111 => ErrorCode::PreSoftForkMaxGeneratorSize,
+            112 => ErrorCode::InvalidRequiredIters,
+            113 => ErrorCode::TooManyGeneratorRefs,
+            114 => ErrorCode::AssertMyParentIdFailed,
+            115 => ErrorCode::AssertMyPuzzleHashFailed,
+            116 => ErrorCode::AssertMyAmountFailed,
+            117 => ErrorCode::GeneratorRuntimeError,
+            118 => ErrorCode::InvalidCostResult,
+            119 => ErrorCode::InvalidTransactionsGeneratorRefsRoot,
+            120 => ErrorCode::FutureGeneratorRefs,
+            121 => ErrorCode::GeneratorRefHasNoGenerator,
+            122 => ErrorCode::DoubleSpendInFork,
+            123 => ErrorCode::InvalidFeeTooCloseToZero,
+            124 => ErrorCode::CoinAmountNegative,
+            125 => ErrorCode::InternalProtocolError,
+            126 => ErrorCode::InvalidSpendBundle,
+            127 => ErrorCode::FailedGettingGeneratorMultiprocessing,
+            128 => ErrorCode::AssertBeforeSecondsAbsoluteFailed,
+            129 => ErrorCode::AssertBeforeSecondsRelativeFailed,
+            130 => ErrorCode::AssertBeforeHeightAbsoluteFailed,
+            131 => ErrorCode::AssertBeforeHeightRelativeFailed,
+            132 => ErrorCode::AssertConcurrentSpendFailed,
+            133 => ErrorCode::AssertConcurrentPuzzleFailed,
+            134 => ErrorCode::ImpossibleSecondsRelativeConstraints,
+            135 => ErrorCode::ImpossibleSecondsAbsoluteConstraints,
+            136 => ErrorCode::ImpossibleHeightRelativeConstraints,
+            137 => ErrorCode::ImpossibleHeightAbsoluteConstraints,
+            138 => ErrorCode::AssertMyBirthSecondsFailed,
+            139 => ErrorCode::AssertMyBirthHeightFailed,
+            140 => ErrorCode::AssertEphemeralFailed,
+            141 => ErrorCode::EphemeralRelativeCondition,
+            142 => ErrorCode::InvalidSoftforkCondition,
+            143 => ErrorCode::InvalidSoftforkCost,
+            144 => ErrorCode::TooManyAnnouncements,
+            145 => ErrorCode::InvalidMessageMode,
+            146 => ErrorCode::InvalidCoinId,
+            147 => ErrorCode::MessageNotSentOrReceived,
+            148 => ErrorCode::DuplicateCoinId,
+            149 => ErrorCode::Cancelled,
+            _ => return Err(code),
+        })
+    }
+}
+
 pub fn rest(a: &Allocator, n: NodePtr) -> Result<NodePtr, ValidationErr> {
     match a.sexp(n) {
         SExp::Pair(_, right) => Ok(right),
@@ -398,3 +698,228 @@ pub fn check_nil(a: &Allocator, n: NodePtr) -> Result<(), ValidationErr> {
         Err(ValidationErr(n, ErrorCode::InvalidCondition))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    // These numbers are part of the interface to chia-blockchain (and stored
+    // in databases and logs). They must never change. New error codes must be
+    // appended here with a new, unique, number.
+    const STABLE_CODES: &[(ErrorCode, u32)] = &[
+        (ErrorCode::Unknown, 1),
+        (ErrorCode::InvalidBlockSolution, 2),
+        (ErrorCode::InvalidCoinSolution, 3),
+        (ErrorCode::DuplicateOutput, 4),
+        (ErrorCode::DoubleSpend, 5),
+        (ErrorCode::UnknownUnspent, 6),
+        (ErrorCode::BadAggregateSignature, 7),
+        (ErrorCode::WrongPuzzleHash, 8),
+        (ErrorCode::BadFarmerCoinAmount, 9),
+        (ErrorCode::InvalidCondition, 10),
+        (ErrorCode::InvalidConditionOpcode, 10),
+        (ErrorCode::InvalidParentId, 10),
+        (ErrorCode::InvalidPuzzleHash, 10),
+        (ErrorCode::InvalidPublicKey, 10),
+        (ErrorCode::InvalidMessage, 10),
+        (ErrorCode::InvalidCoinAmount, 10),
+        (ErrorCode::InvalidCoinAnnouncement, 10),
+        (ErrorCode::InvalidPuzzleAnnouncement, 10),
+        (ErrorCode::AssertMyCoinIdFailed, 11),
+        (ErrorCode::AssertPuzzleAnnouncementFailed, 12),
+        (ErrorCode::AssertCoinAnnouncementFailed, 12),
+        (ErrorCode::AssertHeightRelativeFailed, 13),
+        (ErrorCode::AssertHeightAbsoluteFailed, 14),
+        (ErrorCode::AssertSecondsAbsoluteFailed, 15),
+        (ErrorCode::CoinAmountExceedsMaximum, 16),
+        (ErrorCode::SexpError, 17),
+        (ErrorCode::InvalidFeeLowFee, 18),
+        (ErrorCode::MempoolConflict, 19),
+        (ErrorCode::MintingCoin, 20),
+        (ErrorCode::ExtendsUnknownBlock, 21),
+        (ErrorCode::CoinbaseNotYetSpendable, 22),
+        (ErrorCode::CostExceeded, 23),
+        (ErrorCode::BadAdditionRoot, 24),
+        (ErrorCode::BadRemovalRoot, 25),
+        (ErrorCode::InvalidPospaceHash, 26),
+        (ErrorCode::InvalidCoinbaseSignature, 27),
+        (ErrorCode::InvalidPlotSignature, 28),
+        (ErrorCode::TimestampTooFarInPast, 29),
+        (ErrorCode::TimestampTooFarInFuture, 30),
+        (ErrorCode::InvalidTransactionsFilterHash, 31),
+        (ErrorCode::InvalidPospaceChallenge, 32),
+        (ErrorCode::InvalidPospace, 33),
+        (ErrorCode::InvalidHeight, 34),
+        (ErrorCode::InvalidCoinbaseAmount, 35),
+        (ErrorCode::InvalidMerkleRoot, 36),
+        (ErrorCode::InvalidBlockFeeAmount, 37),
+        (ErrorCode::InvalidWeight, 38),
+        (ErrorCode::InvalidTotalIters, 39),
+        (ErrorCode::BlockIsNotFinished, 40),
+        (ErrorCode::InvalidNumIterations, 41),
+        (ErrorCode::InvalidPot, 42),
+        (ErrorCode::InvalidPotChallenge, 43),
+        (ErrorCode::InvalidTransactionsGeneratorHash, 44),
+        (ErrorCode::InvalidPoolTarget, 45),
+        (ErrorCode::InvalidCoinbaseParent, 46),
+        (ErrorCode::InvalidFeesCoinParent, 47),
+        (ErrorCode::ReserveFeeConditionFailed, 48),
+        (ErrorCode::NotBlockButHasData, 49),
+        (ErrorCode::IsTransactionBlockButNoData, 50),
+        (ErrorCode::InvalidPrevBlockHash, 51),
+        (ErrorCode::InvalidTransactionsInfoHash, 52),
+        (ErrorCode::InvalidFoliageBlockHash, 53),
+        (ErrorCode::InvalidRewardCoins, 54),
+        (ErrorCode::InvalidBlockCost, 55),
+        (ErrorCode::NoEndOfSlotInfo, 56),
+        (ErrorCode::InvalidPrevChallengeSlotHash, 57),
+        (ErrorCode::InvalidSubEpochSummaryHash, 58),
+        (ErrorCode::NoSubEpochSummaryHash, 59),
+        (ErrorCode::ShouldNotMakeChallengeBlock, 60),
+        (ErrorCode::ShouldMakeChallengeBlock, 61),
+        (ErrorCode::InvalidChallengeChainData, 62),
+        (ErrorCode::InvalidCcEosVdf, 65),
+        (ErrorCode::InvalidRcEosVdf, 66),
+        (ErrorCode::InvalidChallengeSlotHashRc, 67),
+        (ErrorCode::InvalidPriorPointRc, 68),
+        (ErrorCode::InvalidDeficit, 69),
+        (ErrorCode::InvalidSubEpochSummary, 70),
+        (ErrorCode::InvalidPrevSubEpochSummaryHash, 71),
+        (ErrorCode::InvalidRewardChainHash, 72),
+        (ErrorCode::InvalidSubEpochOverflow, 73),
+        (ErrorCode::InvalidNewDifficulty, 74),
+        (ErrorCode::InvalidNewSubSlotIters, 75),
+        (ErrorCode::InvalidCcSpVdf, 76),
+        (ErrorCode::InvalidRcSpVdf, 77),
+        (ErrorCode::InvalidCcSignature, 78),
+        (ErrorCode::InvalidRcSignature, 79),
+        (ErrorCode::CannotMakeCcBlock, 80),
+        (ErrorCode::InvalidRcSpPrevIp, 81),
+        (ErrorCode::InvalidRcIpPrevIp, 82),
+        (ErrorCode::InvalidIsTransactionBlock, 83),
+        (ErrorCode::InvalidUrsbHash, 84),
+        (ErrorCode::OldPoolTarget, 85),
+        (ErrorCode::InvalidPoolSignature, 86),
+        (ErrorCode::InvalidFoliageBlockPresence, 87),
+        (ErrorCode::InvalidCcIpVdf, 88),
+        (ErrorCode::InvalidRcIpVdf, 89),
+        (ErrorCode::IpShouldBeNone, 90),
+        (ErrorCode::InvalidRewardBlockHash, 91),
+        (ErrorCode::InvalidMadeNonOverflowInfusions, 92),
+        (ErrorCode::NoOverflowsInFirstSubSlotNewEpoch, 93),
+        (ErrorCode::MempoolNotInitialized, 94),
+        (ErrorCode::ShouldNotHaveIcc, 95),
+        (ErrorCode::ShouldHaveIcc, 96),
+        (ErrorCode::InvalidIccVdf, 97),
+        (ErrorCode::InvalidIccHashCc, 98),
+        (ErrorCode::InvalidIccHashRc, 99),
+        (ErrorCode::InvalidIccEosVdf, 100),
+        (ErrorCode::InvalidSpIndex, 101),
+        (ErrorCode::TooManyBlocks, 102),
+        (ErrorCode::InvalidCcChallenge, 103),
+        (ErrorCode::InvalidPrefarm, 104),
+        (ErrorCode::AssertSecondsRelativeFailed, 105),
+        (ErrorCode::BadCoinbaseSignature, 106),
+        (ErrorCode::NoTransactionsWhileSyncing, 108),
+        (ErrorCode::AlreadyIncludingTransaction, 109),
+        (ErrorCode::IncompatibleNetworkId, 110),
+        (ErrorCode::PreSoftForkMaxGeneratorSize, 111),
+        (ErrorCode::InvalidRequiredIters, 112),
+        (ErrorCode::TooManyGeneratorRefs, 113),
+        (ErrorCode::AssertMyParentIdFailed, 114),
+        (ErrorCode::AssertMyPuzzleHashFailed, 115),
+        (ErrorCode::AssertMyAmountFailed, 116),
+        (ErrorCode::GeneratorRuntimeError, 117),
+        (ErrorCode::InvalidCostResult, 118),
+        (ErrorCode::InvalidTransactionsGeneratorRefsRoot, 119),
+        (ErrorCode::FutureGeneratorRefs, 120),
+        (ErrorCode::GeneratorRefHasNoGenerator, 121),
+        (ErrorCode::DoubleSpendInFork, 122),
+        (ErrorCode::InvalidFeeTooCloseToZero, 123),
+        (ErrorCode::CoinAmountNegative, 124),
+        (ErrorCode::InternalProtocolError, 125),
+        (ErrorCode::InvalidSpendBundle, 126),
+        (ErrorCode::FailedGettingGeneratorMultiprocessing, 127),
+        (ErrorCode::AssertBeforeSecondsAbsoluteFailed, 128),
+        (ErrorCode::AssertBeforeSecondsRelativeFailed, 129),
+        (ErrorCode::AssertBeforeHeightAbsoluteFailed, 130),
+        (ErrorCode::AssertBeforeHeightRelativeFailed, 131),
+        (ErrorCode::AssertConcurrentSpendFailed, 132),
+        (ErrorCode::AssertConcurrentPuzzleFailed, 133),
+        (ErrorCode::ImpossibleSecondsRelativeConstraints, 134),
+        (ErrorCode::ImpossibleSecondsAbsoluteConstraints, 135),
+        (ErrorCode::ImpossibleHeightRelativeConstraints, 136),
+        (ErrorCode::ImpossibleHeightAbsoluteConstraints, 137),
+        (ErrorCode::AssertMyBirthSecondsFailed, 138),
+        (ErrorCode::AssertMyBirthHeightFailed, 139),
+        (ErrorCode::AssertEphemeralFailed, 140),
+        (ErrorCode::EphemeralRelativeCondition, 141),
+        (ErrorCode::InvalidSoftforkCondition, 142),
+        (ErrorCode::InvalidSoftforkCost, 143),
+        (ErrorCode::TooManyAnnouncements, 144),
+        (ErrorCode::InvalidMessageMode, 145),
+        (ErrorCode::InvalidCoinId, 146),
+        (ErrorCode::MessageNotSentOrReceived, 147),
+        (ErrorCode::DuplicateCoinId, 148),
+        (ErrorCode::Cancelled, 149),
+    ];
+
+    // these error codes are more specific versions of another error code, and
+    // share its numeric value
+    const ALIASES: &[ErrorCode] = &[
+        ErrorCode::InvalidConditionOpcode,
+        ErrorCode::InvalidParentId,
+        ErrorCode::InvalidPuzzleHash,
+        ErrorCode::InvalidPublicKey,
+        ErrorCode::InvalidMessage,
+        ErrorCode::InvalidCoinAmount,
+        ErrorCode::InvalidCoinAnnouncement,
+        ErrorCode::InvalidPuzzleAnnouncement,
+        ErrorCode::AssertCoinAnnouncementFailed,
+    ];
+
+    #[test]
+    fn test_stable_error_codes() {
+        let mut seen = HashMap::<u32, ErrorCode>::new();
+        for (err, code) in STABLE_CODES {
+            assert_eq!(u32::from(*err), *code, "{err:?}");
+            if ALIASES.contains(err) {
+                continue;
+            }
+            if let Some(prev) = seen.insert(*code, *err) {
+                panic!("{err:?} and {prev:?} both map to {code}");
+            }
+            assert_eq!(ErrorCode::try_from(*code), Ok(*err));
+        }
+    }
+
+    #[test]
+    fn test_error_code_round_trip() {
+        for code in 0..=u16::MAX as u32 {
+            let Ok(err) = ErrorCode::try_from(code) else {
+                continue;
+            };
+            assert_eq!(u32::from(err), code);
+            assert!(STABLE_CODES.contains(&(err, code)), "{err:?} missing");
+        }
+    }
+
+    #[test]
+    fn test_aliases() {
+        for err in ALIASES {
+            let code = u32::from(*err);
+            let canonical = ErrorCode::try_from(code).expect("alias must decode");
+            assert_ne!(canonical, *err);
+            assert_eq!(u32::from(canonical), code);
+        }
+    }
+
+    #[test]
+    fn test_unknown_codes() {
+        assert_eq!(ErrorCode::try_from(0), Err(0));
+        assert_eq!(ErrorCode::try_from(63), Err(63));
+        assert_eq!(ErrorCode::try_from(107), Err(107));
+        assert_eq!(ErrorCode::try_from(u32::MAX), Err(u32::MAX));
+    }
+}
@@ -0,0 +1,302 @@
+use crate::gen::opcodes::ConditionOpcode;
+use clvmr::cost::Cost;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Hooks for collecting telemetry while validating blocks and spend bundles,
+/// e.g. to feed Prometheus counters. Pass an implementation to the
+/// `*_instrumented()` variants of the validation functions, such as
+/// `run_block_generator2_instrumented()` or `parse_spends_instrumented()`.
+///
+/// Every method has a default implementation that does nothing, so only the
+/// interesting events need to be implemented. The hooks are called from the
+/// hot path of validation and should be cheap. Since they take `&self`,
+/// implementations need interior mutability, typically atomics.
+///
+/// When validation fails, the hooks may have been called for the parts that
+/// were validated before the failure.
+pub trait ValidationMetrics {
+    /// Called for every spend whose conditions were parsed successfully.
+    fn on_spend_parsed(&self) {}
+
+    /// Called once the aggregate signature has been checked, whether it was
+    /// valid or not. `cached_pairs` is the number of pairings that were
+    /// found in the BLS cache (0 if no cache was used). Not called when
+    /// signature validation is disabled with `DONT_VALIDATE_SIGNATURE`.
+    fn on_signature_validated(&self, _duration: Duration, _cached_pairs: u64) {}
+
+    /// Called for every condition, as it's parsed. Unknown conditions, which
+    /// are ignored by the parser, are not reported.
+    fn on_condition(&self, _opcode: ConditionOpcode) {}
+
+    /// Called with the total cost of a block once it has been validated.
+    fn on_block_cost(&self, _cost: Cost) {}
+}
+
+/// The number of times each hook of [`CountingMetrics`] was called, and the
+/// sums of their arguments.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsCounts {
+    pub spends: u64,
+    pub signatures: u64,
+    pub signature_time: Duration,
+    pub cached_pairs: u64,
+    pub conditions: BTreeMap<ConditionOpcode, u64>,
+    pub blocks: u64,
+    pub cost: Cost,
+}
+
+/// A [`ValidationMetrics`] implementation that simply counts the events.
+#[derive(Debug, Default)]
+pub struct CountingMetrics {
+    counts: Mutex<MetricsCounts>,
+}
+
+impl CountingMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn counts(&self) -> MetricsCounts {
+        self.counts.lock().expect("metrics").clone()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut MetricsCounts)) {
+        f(&mut self.counts.lock().expect("metrics"));
+    }
+}
+
+impl ValidationMetrics for CountingMetrics {
+    fn on_spend_parsed(&self) {
+        self.update(|c| c.spends += 1);
+    }
+
+    fn on_signature_validated(&self, duration: Duration, cached_pairs: u64) {
+        self.update(|c| {
+            c.signatures += 1;
+            c.signature_time += duration;
+            c.cached_pairs += cached_pairs;
+        });
+    }
+
+    fn on_condition(&self, opcode: ConditionOpcode) {
+        self.update(|c| *c.conditions.entry(opcode).or_default() += 1);
+    }
+
+    fn on_block_cost(&self, cost: Cost) {
+        self.update(|c| {
+            c.blocks += 1;
+            c.cost += cost;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus_constants::TEST_CONSTANTS;
+    use crate::gen::flags::DONT_VALIDATE_SIGNATURE;
+    use crate::gen::opcodes::{AGG_SIG_UNSAFE, ASSERT_HEIGHT_ABSOLUTE, CREATE_COIN};
+    use crate::gen::run_block_generator::{
+        run_block_generator2, run_block_generator2_instrumented,
+    };
+    use crate::gen::solution_generator::solution_generator;
+    use chia_bls::{sign, BlsCache, SecretKey, Signature};
+    use chia_protocol::{Amount, Bytes32, Coin};
+    use clvmr::serde::node_to_bytes;
+    use clvmr::{Allocator, NodePtr};
+
+    fn list(a: &mut Allocator, items: &[NodePtr]) -> NodePtr {
+        let mut ret = a.nil();
+        for item in items.iter().rev() {
+            ret = a.new_pair(*item, ret).unwrap();
+        }
+        ret
+    }
+
+    fn condition(a: &mut Allocator, opcode: u16, args: &[&[u8]]) -> NodePtr {
+        let mut items = vec![a.new_small_number(opcode.into()).unwrap()];
+        for arg in args {
+            items.push(a.new_atom(arg).unwrap());
+        }
+        list(a, &items)
+    }
+
+    // a puzzle that just returns the specified conditions
+    fn puzzle(a: &mut Allocator, conditions: &[NodePtr]) -> Vec<u8> {
+        let conditions = list(a, conditions);
+        let quote = a.new_small_number(1).unwrap();
+        let puzzle = a.new_pair(quote, conditions).unwrap();
+        node_to_bytes(a, puzzle).unwrap()
+    }
+
+    // A block generator with two spends. Between them, they have 3
+    // CREATE_COIN conditions, one AGG_SIG_UNSAFE, one ASSERT_HEIGHT_ABSOLUTE
+    // and an unknown condition. Returns the generator and its aggregate
+    // signature.
+    fn generator() -> (Vec<u8>, Signature) {
+        let sk = SecretKey::from_seed(&[1; 32]);
+        let mut a = Allocator::new();
+
+        let conditions = [
+            condition(&mut a, CREATE_COIN.into(), &[&[1; 32], &[1]]),
+            condition(&mut a, ASSERT_HEIGHT_ABSOLUTE.into(), &[&[100]]),
+            condition(&mut a, 250, &[&[1, 2, 3]]),
+            condition(&mut a, CREATE_COIN.into(), &[&[3; 32], &[2]]),
+        ];
+        let puzzle1 = puzzle(&mut a, &conditions);
+
+        let conditions = [
+            condition(
+                &mut a,
+                AGG_SIG_UNSAFE.into(),
+                &[&sk.public_key().to_bytes(), b"hello"],
+            ),
+            condition(&mut a, CREATE_COIN.into(), &[&[1; 32], &[5]]),
+        ];
+        let puzzle2 = puzzle(&mut a, &conditions);
+
+        let solution = [0x80_u8];
+        let spends = [
+            (
+                Coin::new(Bytes32::new([2; 32]), Bytes32::default(), Amount::new(10)),
+                puzzle1.as_slice(),
+                solution.as_slice(),
+            ),
+            (
+                Coin::new(Bytes32::new([4; 32]), Bytes32::default(), Amount::new(5)),
+                puzzle2.as_slice(),
+                solution.as_slice(),
+            ),
+        ];
+        let generator = solution_generator(spends).expect("solution_generator");
+        (generator, sign(&sk, b"hello"))
+    }
+
+    fn run(
+        flags: u32,
+        signature: &Signature,
+        bls_cache: Option<&BlsCache>,
+        metrics: Option<&dyn ValidationMetrics>,
+    ) -> Option<Cost> {
+        let (generator, _) = generator();
+        let blocks: &[&[u8]] = &[];
+        let mut a = Allocator::new();
+        run_block_generator2_instrumented(
+            &mut a,
+            &generator,
+            blocks,
+            11_000_000_000,
+            flags,
+            signature,
+            bls_cache,
+            &TEST_CONSTANTS,
+            metrics,
+        )
+        .ok()
+        .map(|conds| conds.cost)
+    }
+
+    fn expected_conditions() -> BTreeMap<ConditionOpcode, u64> {
+        BTreeMap::from([
+            (AGG_SIG_UNSAFE, 1),
+            (CREATE_COIN, 3),
+            (ASSERT_HEIGHT_ABSOLUTE, 1),
+        ])
+    }
+
+    #[test]
+    fn test_metrics() {
+        let (generator, signature) = generator();
+        let metrics = CountingMetrics::new();
+        let cost = run(0, &signature, None, Some(&metrics)).expect("valid block");
+
+        let counts = metrics.counts();
+        assert_eq!(counts.spends, 2);
+        assert_eq!(counts.conditions, expected_conditions());
+        assert_eq!(counts.signatures, 1);
+        assert_eq!(counts.cached_pairs, 0);
+        assert_eq!(counts.blocks, 1);
+        assert_eq!(counts.cost, cost);
+
+        // the metrics don't affect the result
+        let blocks: &[&[u8]] = &[];
+        let mut a = Allocator::new();
+        let conds = run_block_generator2(
+            &mut a,
+            &generator,
+            blocks,
+            11_000_000_000,
+            0,
+            &signature,
+            None,
+            &TEST_CONSTANTS,
+        )
+        .expect("run_block_generator2");
+        assert_eq!(conds.cost, cost);
+    }
+
+    #[test]
+    fn test_metrics_no_signature() {
+        let metrics = CountingMetrics::new();
+        let cost = run(
+            DONT_VALIDATE_SIGNATURE,
+            &Signature::default(),
+            None,
+            Some(&metrics),
+        )
+        .expect("valid block");
+
+        let counts = metrics.counts();
+        assert_eq!(counts.spends, 2);
+        assert_eq!(counts.conditions, expected_conditions());
+        assert_eq!(counts.signatures, 0);
+        assert_eq!(counts.blocks, 1);
+        assert_eq!(counts.cost, cost);
+    }
+
+    #[test]
+    fn test_metrics_bls_cache() {
+        let (_, signature) = generator();
+        let bls_cache = BlsCache::default();
+        let metrics = CountingMetrics::new();
+
+        // the first time, the pairing is added to the cache. The second time
+        // it's found there
+        run(0, &signature, Some(&bls_cache), Some(&metrics)).expect("valid block");
+        assert_eq!(metrics.counts().cached_pairs, 0);
+        run(0, &signature, Some(&bls_cache), Some(&metrics)).expect("valid block");
+
+        let counts = metrics.counts();
+        assert_eq!(counts.signatures, 2);
+        assert_eq!(counts.cached_pairs, 1);
+        assert_eq!(counts.blocks, 2);
+    }
+
+    #[test]
+    fn test_metrics_invalid_signature() {
+        let metrics = CountingMetrics::new();
+        assert!(run(0, &Signature::default(), None, Some(&metrics)).is_none());
+
+        // the signature was checked, but the block was never completed
+        let counts = metrics.counts();
+        assert_eq!(counts.spends, 2);
+        assert_eq!(counts.signatures, 1);
+        assert_eq!(counts.blocks, 0);
+        assert_eq!(counts.cost, 0);
+    }
+
+    #[test]
+    fn test_default_hooks() {
+        // an implementation that doesn't override any hooks
+        struct NoMetrics;
+        impl ValidationMetrics for NoMetrics {}
+
+        let (_, signature) = generator();
+        assert_eq!(
+            run(0, &signature, None, Some(&NoMetrics)),
+            run(0, &signature, None, None)
+        );
+    }
+}
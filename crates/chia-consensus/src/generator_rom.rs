@@ -40,3 +40,63 @@ ff02ffff01ff05ffff02ff3effff04ff02ffff04ff05ff8080808080ffff04ffff01ffffff81ff7f
 // constant from the main chia blockchain:
 // https://github.com/Chia-Network/chia-blockchain/blob/main/chia/consensus/default_constants.py
 pub const COST_PER_BYTE: u64 = 12000;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::make_allocator;
+    use crate::consensus_constants::TEST_CONSTANTS;
+    use crate::gen::flags::{ALLOW_BACKREFS, DONT_VALIDATE_SIGNATURE};
+    use crate::gen::run_block_generator::run_block_generator;
+    use chia_bls::Signature;
+    use chia_protocol::{Program, RunError};
+    use clvmr::serde::node_from_bytes_backrefs;
+
+    #[test]
+    fn run_generator_rom() {
+        let test_file =
+            std::fs::read_to_string("../../generator-tests/block-834768.txt").expect("test file");
+        let (generator, _) = test_file.split_once('\n').expect("invalid test file");
+        let generator = hex::decode(generator).expect("hex::decode");
+
+        // the ROM is passed the generator and the (empty) list of block
+        // references, the same way run_block_generator() does it
+        let mut a = make_allocator(0);
+        let program = node_from_bytes_backrefs(&mut a, &generator).expect("generator");
+        let args = a.new_pair(a.nil(), a.nil()).unwrap();
+        let args = a.new_pair(args, a.nil()).unwrap();
+        let args = a.new_pair(program, args).unwrap();
+
+        let rom = Program::new(GENERATOR_ROM.to_vec().into());
+        let (cost, output) = rom
+            .run_with_cost(&mut a, 11_000_000_000, 0, args)
+            .expect("run_with_cost");
+        assert!(cost > 0);
+
+        let mut a2 = make_allocator(0);
+        let conds = run_block_generator(
+            &mut a2,
+            &generator,
+            Vec::<&[u8]>::new(),
+            11_000_000_000,
+            ALLOW_BACKREFS | DONT_VALIDATE_SIGNATURE,
+            &Signature::default(),
+            None,
+            &TEST_CONSTANTS,
+        )
+        .expect("run_block_generator");
+        // the ROM returns a list with one element, the list of spends
+        let mut count = 0;
+        let mut iter = a.next(output).expect("spends").0;
+        while let Some((_, tail)) = a.next(iter) {
+            count += 1;
+            iter = tail;
+        }
+        assert_eq!(count, conds.spends.len());
+
+        assert_eq!(
+            rom.run_with_cost(&mut a, cost - 1, 0, args),
+            Err(RunError::CostExceeded)
+        );
+    }
+}
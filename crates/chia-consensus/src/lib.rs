@@ -2,6 +2,9 @@
 #![doc = include_str!("../README.md")]
 
 pub mod allocator;
+pub mod block_record;
+pub mod block_rewards;
+pub mod coin_proofs;
 pub mod consensus_constants;
 pub mod error;
 pub mod fast_forward;
@@ -9,5 +12,14 @@ pub mod gen;
 pub mod generator_rom;
 pub mod merkle_set;
 pub mod merkle_tree;
+#[cfg(feature = "serde")]
+pub mod network_config;
+pub mod pot_iterations;
+pub mod proof_of_space;
+#[cfg(feature = "sim")]
+pub mod simulator;
 pub mod spendbundle_conditions;
 pub mod spendbundle_validation;
+pub mod sub_slot_validation;
+pub mod time_locks;
+pub mod transactions_filter;
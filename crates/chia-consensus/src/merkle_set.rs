@@ -1,5 +1,8 @@
+use crate::error::{Error, Result};
+use chia_protocol::Bytes32;
 use chia_sha2::Sha256;
 use hex_literal::hex;
+use std::cmp::Ordering;
 
 fn get_bit(val: &[u8; 32], bit: u8) -> u8 {
     ((val[(bit / 8) as usize] & (0x80 >> (bit & 7))) != 0).into()
@@ -174,9 +177,152 @@ pub fn compute_merkle_set_root(leafs: &mut [[u8; 32]]) -> [u8; 32] {
     }
 }
 
+// returns the index of the first bit that differs between a and b, which must
+// not be equal
+fn first_differing_bit(a: &[u8; 32], b: &[u8; 32]) -> u8 {
+    let (idx, (x, y)) = a
+        .iter()
+        .zip(b)
+        .enumerate()
+        .find(|(_, (x, y))| x != y)
+        .expect("leafs must differ");
+    (idx * 8) as u8 + (x ^ y).leading_zeros() as u8
+}
+
+// a complete sub tree of the merkle set
+struct SubTree {
+    hash: [u8; 32],
+    node_type: NodeType,
+    // the depth of the root of this sub tree. A single leaf has depth 256
+    depth: u16,
+    // any of the leafs in the sub tree. They all share the same bits down to
+    // depth
+    leaf: [u8; 32],
+}
+
+impl SubTree {
+    fn leaf(leaf: [u8; 32]) -> Self {
+        Self {
+            hash: leaf,
+            node_type: NodeType::Term,
+            depth: 256,
+            leaf,
+        }
+    }
+
+    // computes the hash of this sub tree as seen from the specified depth. In
+    // between, there are nodes that only have this sub tree on one side. Those
+    // nodes just forward the hash, except for Mid nodes, which are hashed
+    // with an Empty node on the other side (see radix_sort())
+    fn lift(&self, depth: u16) -> ([u8; 32], NodeType) {
+        if self.node_type != NodeType::Mid {
+            return (self.hash, self.node_type);
+        }
+        let mut ret = self.hash;
+        for bit in (depth..self.depth).rev() {
+            ret = if get_bit(&self.leaf, bit as u8) == 1 {
+                hash(NodeType::Empty, NodeType::Mid, &BLANK, &ret)
+            } else {
+                hash(NodeType::Mid, NodeType::Empty, &ret, &BLANK)
+            };
+        }
+        (ret, NodeType::Mid)
+    }
+
+    // joins two sub trees with a node at the specified depth
+    fn join(left: &SubTree, right: &SubTree, depth: u8) -> Self {
+        let (left_hash, left_type) = left.lift(u16::from(depth) + 1);
+        let (right_hash, right_type) = right.lift(u16::from(depth) + 1);
+        let node_type = if left_type == NodeType::Term && right_type == NodeType::Term {
+            NodeType::MidDbl
+        } else {
+            NodeType::Mid
+        };
+        Self {
+            hash: hash(left_type, right_type, &left_hash, &right_hash),
+            node_type,
+            depth: depth.into(),
+            leaf: left.leaf,
+        }
+    }
+}
+
+/// Computes the root of a merkle set one leaf at a time, without holding all
+/// leafs in memory. The leafs must be pushed in ascending order (duplicates
+/// are ignored, just like `compute_merkle_set_root()` does). At most 256 sub
+/// trees are kept in memory at any time, regardless of the number of leafs.
+/// Since the leafs are only hashed in order, they can be pushed a page at a
+/// time, e.g. while reading sorted coin IDs from a database.
+#[derive(Default)]
+pub struct MerkleSetBuilder {
+    // the sub trees that are still waiting for their right hand sibling, along
+    // with the depth of the node that will join them. The depths are strictly
+    // increasing
+    pending: Vec<(SubTree, u8)>,
+    // the most recently pushed leaf
+    last: Option<[u8; 32]>,
+}
+
+impl MerkleSetBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a leaf to the set. Fails if the leaf is less than the previous
+    /// one.
+    pub fn push(&mut self, leaf: [u8; 32]) -> Result<()> {
+        let Some(prev) = self.last else {
+            self.last = Some(leaf);
+            return Ok(());
+        };
+        match leaf.cmp(&prev) {
+            Ordering::Less => return Err(Error::MerkleSetLeafOutOfOrder(leaf.into())),
+            Ordering::Equal => return Ok(()),
+            Ordering::Greater => {}
+        }
+
+        // the previous leaf and the new leaf are joined at the first bit they
+        // differ in. Any pending sub tree joined at a greater depth is
+        // complete now
+        let depth = first_differing_bit(&prev, &leaf);
+        let mut node = SubTree::leaf(prev);
+        while self.pending.last().is_some_and(|(_, d)| *d > depth) {
+            let (left, join_depth) = self.pending.pop().expect("pending is not empty");
+            node = SubTree::join(&left, &node, join_depth);
+        }
+        self.pending.push((node, depth));
+        self.last = Some(leaf);
+        Ok(())
+    }
+
+    /// The root of the merkle set of all leafs pushed so far. This is the same
+    /// as `compute_merkle_set_root()` of those leafs.
+    pub fn finalize(mut self) -> Bytes32 {
+        let Some(last) = self.last else {
+            return BLANK.into();
+        };
+        let mut node = SubTree::leaf(last);
+        while let Some((left, join_depth)) = self.pending.pop() {
+            node = SubTree::join(&left, &node, join_depth);
+        }
+        match node.lift(0) {
+            (hash, NodeType::Term) => {
+                // a set with a single item, see compute_merkle_set_root()
+                let mut hasher = Sha256::new();
+                hasher.update([NodeType::Term as u8]);
+                hasher.update(hash);
+                hasher.finalize().into()
+            }
+            (hash, _) => hash.into(),
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
 
     fn h2(buf1: &[u8], buf2: &[u8]) -> [u8; 32] {
         let mut hasher = Sha256::new();
@@ -493,4 +639,63 @@ pub mod test {
             assert_eq!(compute_merkle_set_root(&mut leafs), root);
         }
     }
+
+    fn builder_root(leafs: &[[u8; 32]]) -> Bytes32 {
+        let mut builder = MerkleSetBuilder::new();
+        for leaf in leafs {
+            builder.push(*leaf).expect("leafs are sorted");
+        }
+        builder.finalize()
+    }
+
+    #[test]
+    fn test_builder() {
+        for (root, mut leafs) in merkle_set_test_cases() {
+            leafs.sort_unstable();
+            assert_eq!(builder_root(&leafs), Bytes32::from(root));
+        }
+    }
+
+    #[test]
+    fn test_builder_random() {
+        let mut rng = SmallRng::seed_from_u64(1337);
+        for _ in 0..200 {
+            let num_leafs = rng.gen_range(0..300);
+            let mut leafs = Vec::<[u8; 32]>::with_capacity(num_leafs);
+            for _ in 0..num_leafs {
+                if leafs.is_empty() || rng.gen_range(0..4) != 0 {
+                    leafs.push(rng.gen());
+                } else {
+                    // leafs that only differ in a single (possibly deep) bit
+                    // make for long chains of one-sided nodes
+                    let mut leaf = leafs[rng.gen_range(0..leafs.len())];
+                    let bit: usize = rng.gen_range(0..256);
+                    leaf[bit / 8] ^= 0x80 >> (bit % 8);
+                    leafs.push(leaf);
+                }
+            }
+            let expected = compute_merkle_set_root(&mut leafs.clone());
+            leafs.sort_unstable();
+            assert_eq!(builder_root(&leafs), Bytes32::from(expected));
+        }
+    }
+
+    #[test]
+    fn test_builder_out_of_order() {
+        let a = hex!("7000000000000000000000000000000000000000000000000000000000000000");
+        let b = hex!("7100000000000000000000000000000000000000000000000000000000000000");
+
+        let mut builder = MerkleSetBuilder::new();
+        builder.push(a).unwrap();
+        builder.push(b).unwrap();
+        // duplicates are fine
+        builder.push(b).unwrap();
+        assert_eq!(
+            builder.push(a),
+            Err(Error::MerkleSetLeafOutOfOrder(a.into()))
+        );
+        // the failed push doesn't affect the set
+        let expected = compute_merkle_set_root(&mut [a, b]);
+        assert_eq!(builder.finalize(), Bytes32::from(expected));
+    }
 }
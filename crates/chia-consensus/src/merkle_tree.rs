@@ -1,13 +1,16 @@
 // This file contains the code used to create a full MerkleSet and is heavily reliant on the code in merkle_set.rs.
+// It also contains MerkleTree, the simple binary tree used by the merkle puzzles.
 
 use crate::merkle_set::{hash, NodeType, BLANK};
 use hex_literal::hex;
 
-#[cfg(feature = "py-bindings")]
 use chia_protocol::Bytes32;
+#[cfg(feature = "py-bindings")]
+use chia_protocol::Program;
 use chia_sha2::Sha256;
 #[cfg(feature = "py-bindings")]
 use chia_traits::ChiaToPython;
+use clvm_traits::{FromClvm, ToClvm};
 #[cfg(feature = "py-bindings")]
 use pyo3::exceptions::PyValueError;
 #[cfg(feature = "py-bindings")]
@@ -554,6 +557,199 @@ impl MerkleSet {
     }
 }
 
+// the prefixes of the leaf and node hashes in a MerkleTree, as in
+// merkle_utils.clib
+const TREE_LEAF_PREFIX: u8 = 1;
+const TREE_NODE_PREFIX: u8 = 2;
+
+fn tree_leaf_hash(leaf: &Bytes32) -> Bytes32 {
+    let mut hasher = Sha256::new();
+    hasher.update([TREE_LEAF_PREFIX]);
+    hasher.update(leaf);
+    hasher.finalize().into()
+}
+
+fn tree_node_hash(left: &Bytes32, right: &Bytes32) -> Bytes32 {
+    let mut hasher = Sha256::new();
+    hasher.update([TREE_NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+// the number of leaves in the left sub-tree of a tree with len leaves. The
+// left sub-tree gets the extra leaf when len is odd
+fn tree_split(len: usize) -> usize {
+    len.div_ceil(2)
+}
+
+fn tree_root(leaves: &[Bytes32]) -> Bytes32 {
+    if let [leaf] = leaves {
+        return tree_leaf_hash(leaf);
+    }
+    let (left, right) = leaves.split_at(tree_split(leaves.len()));
+    tree_node_hash(&tree_root(left), &tree_root(right))
+}
+
+// A binary merkle tree of (already hashed) leaves, in the order they were
+// added. This is the tree used by the merkle puzzles (merkle_utils.clib) and
+// by MerkleTree in chia-blockchain, unlike MerkleSet which sorts its leaves.
+//
+// Leaves are hashed as sha256(1 + leaf) and nodes as sha256(2 + left + right).
+// Each node splits its leaves in half, and when the number of leaves is odd,
+// the left half gets the extra one. Leaves are never duplicated, so the leaf
+// that's left over is promoted to a higher level of the tree, with a shorter
+// proof.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "py-bindings", pyclass(frozen, name = "MerkleTree"))]
+pub struct MerkleTree {
+    leaves: Vec<Bytes32>,
+    root: Bytes32,
+}
+
+impl MerkleTree {
+    // returns None if there are no leaves, since such a tree doesn't have a
+    // root
+    pub fn new(leaves: Vec<Bytes32>) -> Option<MerkleTree> {
+        if leaves.is_empty() {
+            return None;
+        }
+        let root = tree_root(&leaves);
+        Some(MerkleTree { leaves, root })
+    }
+
+    pub fn leaves(&self) -> &[Bytes32] {
+        &self.leaves
+    }
+
+    pub fn root(&self) -> Bytes32 {
+        self.root
+    }
+
+    // returns the proof for the leaf at index, or None if it's out of range
+    pub fn proof_for(&self, index: usize) -> Option<MerkleProof> {
+        if index >= self.leaves.len() {
+            return None;
+        }
+
+        // the siblings from the root down to the leaf, and whether the leaf
+        // is on the right of each of them
+        let mut siblings = Vec::new();
+        let mut leaves = &self.leaves[..];
+        let mut index = index;
+        while leaves.len() > 1 {
+            let (left, right) = leaves.split_at(tree_split(leaves.len()));
+            if index < left.len() {
+                siblings.push((false, tree_root(right)));
+                leaves = left;
+            } else {
+                siblings.push((true, tree_root(left)));
+                leaves = right;
+                index -= left.len();
+            }
+        }
+
+        // the proof goes from the leaf up to the root, and the lowest bit of
+        // the path is the bottom level
+        let mut path = 0;
+        let mut hashes = Vec::with_capacity(siblings.len());
+        for (bit, (is_right, sibling)) in siblings.into_iter().rev().enumerate() {
+            if is_right {
+                path |= 1 << bit;
+            }
+            hashes.push(sibling);
+        }
+        Some(MerkleProof { path, hashes })
+    }
+}
+
+// A proof that a leaf is in a MerkleTree. Bit n of path is set if the leaf is
+// in the right sub-tree at the nth level above it, and hashes are the
+// siblings from the bottom up. It converts to and from CLVM as
+// (path . hashes), which is what simplify_merkle_proof in merkle_utils.clib
+// expects.
+#[derive(PartialEq, Eq, Debug, Clone, ToClvm, FromClvm)]
+#[clvm(list)]
+#[cfg_attr(
+    feature = "py-bindings",
+    pyclass(frozen, eq, name = "MerkleProof", get_all)
+)]
+pub struct MerkleProof {
+    pub path: u64,
+    #[clvm(rest)]
+    pub hashes: Vec<Bytes32>,
+}
+
+impl MerkleProof {
+    // returns true if leaf is in the tree with the specified root. Like the
+    // merkle puzzles, bits of path beyond the number of hashes are ignored
+    pub fn verify(&self, root: &Bytes32, leaf: &Bytes32) -> bool {
+        let mut current = tree_leaf_hash(leaf);
+        let mut path = self.path;
+        for sibling in &self.hashes {
+            current = if path & 1 == 0 {
+                tree_node_hash(&current, sibling)
+            } else {
+                tree_node_hash(sibling, &current)
+            };
+            path >>= 1;
+        }
+        current == *root
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl MerkleTree {
+    #[new]
+    pub fn init(leaves: Vec<Bytes32>) -> PyResult<Self> {
+        MerkleTree::new(leaves).ok_or_else(|| PyValueError::new_err("MerkleTree has no leaves"))
+    }
+
+    #[getter]
+    #[pyo3(name = "leaves")]
+    pub fn py_leaves(&self) -> Vec<Bytes32> {
+        self.leaves.clone()
+    }
+
+    #[pyo3(name = "root")]
+    pub fn py_root(&self) -> Bytes32 {
+        self.root
+    }
+
+    #[pyo3(name = "proof_for")]
+    pub fn py_proof_for(&self, index: usize) -> PyResult<MerkleProof> {
+        self.proof_for(index)
+            .ok_or_else(|| PyValueError::new_err("leaf index out of range"))
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl MerkleProof {
+    #[new]
+    pub fn init(path: u64, hashes: Vec<Bytes32>) -> Self {
+        MerkleProof { path, hashes }
+    }
+
+    #[pyo3(name = "verify")]
+    pub fn py_verify(&self, root: Bytes32, leaf: Bytes32) -> bool {
+        self.verify(&root, &leaf)
+    }
+
+    // the proof as the (path . hashes) CLVM structure, to pass to the merkle
+    // puzzles
+    #[pyo3(name = "to_program")]
+    pub fn py_to_program(&self) -> PyResult<Program> {
+        let mut a = clvmr::Allocator::new();
+        let proof = self
+            .to_clvm(&mut a)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let bytes = clvmr::serde::node_to_bytes(&a, proof)?;
+        Ok(Program::from(bytes))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -562,6 +758,7 @@ mod tests {
     use hex_literal::hex;
     use rand::rngs::SmallRng;
     use rand::{Rng, SeedableRng};
+    use rstest::rstest;
 
     impl MerkleSet {
         // this checks the correctness of the tree and its merkle root by
@@ -769,4 +966,113 @@ mod tests {
             assert_eq!(MerkleSet::from_leafs(&mut leafs).get_root(), root);
         }
     }
+
+    fn tree_leaves(count: u8) -> Vec<Bytes32> {
+        (1..=count).map(|i| Bytes32::new([i; 32])).collect()
+    }
+
+    // the roots are the same as the ones of MerkleTree in chia-blockchain
+    #[rstest]
+    #[case(1, "ce041765675ad4d93378e20bd3a7d0d97ddcf3385fb6341581b21d4bc9e3e69e")]
+    #[case(2, "00f2e7e0bc3ee77f0b5aa330406f69bfbd5c2e3b8a4338dba49f64bb3f0247c4")]
+    #[case(3, "adb439d3868b9273de8753e20a62a8e6d9ff6cfb43b189337a23df0690c7f55b")]
+    #[case(4, "7eb919730e38f305365791a43adddeea0fc275371aac8c7b08983937beeb956f")]
+    #[case(5, "9b0694c2f45fe7a5dedbe5dfed0d9d933cef7a6fd16fc77de1d0372ecfe6023e")]
+    fn test_merkle_tree_root(#[case] count: u8, #[case] root: &str) {
+        let tree = MerkleTree::new(tree_leaves(count)).unwrap();
+        assert_eq!(hex::encode(tree.root()), root);
+
+        for (index, leaf) in tree.leaves().iter().enumerate() {
+            let proof = tree.proof_for(index).unwrap();
+            assert!(proof.verify(&tree.root(), leaf));
+            // the proof is only valid for this leaf and this root
+            assert!(!proof.verify(&tree.root(), &Bytes32::new([0; 32])));
+            assert!(!proof.verify(&Bytes32::new([0; 32]), leaf));
+        }
+        assert_eq!(tree.proof_for(count as usize), None);
+    }
+
+    #[test]
+    fn test_merkle_tree_empty() {
+        assert_eq!(MerkleTree::new(vec![]), None);
+    }
+
+    // with 5 leaves, the tree looks like this. The left sub-tree gets the
+    // extra leaf at each level, and e is promoted rather than duplicated:
+    //
+    //            o
+    //          /   \
+    //         o     o
+    //        / \   / \
+    //       o   c d   e
+    //      / \
+    //     a   b
+    #[rstest]
+    #[case(0, 0b000, &["f1386fff8b06ac98d347997ff5d0abad3b977514b1b7cfe0689f45f3f1393497", "131c41585fc6b26c2cf8ea6fc61be03c3c4e3facb3f7e70ec69ea094b17dc3e1", "ace51b6a3330beba34aac1d08980b5e65cd540baeb60b86bd9ee81e24c37a007"])]
+    #[case(1, 0b001, &["ce041765675ad4d93378e20bd3a7d0d97ddcf3385fb6341581b21d4bc9e3e69e", "131c41585fc6b26c2cf8ea6fc61be03c3c4e3facb3f7e70ec69ea094b17dc3e1", "ace51b6a3330beba34aac1d08980b5e65cd540baeb60b86bd9ee81e24c37a007"])]
+    #[case(2, 0b01, &["00f2e7e0bc3ee77f0b5aa330406f69bfbd5c2e3b8a4338dba49f64bb3f0247c4", "ace51b6a3330beba34aac1d08980b5e65cd540baeb60b86bd9ee81e24c37a007"])]
+    #[case(3, 0b10, &["90cbc3c7c7634183ae482172520c1b8d85ee10f1ca0b4744fdbe7da2245141bb", "adb439d3868b9273de8753e20a62a8e6d9ff6cfb43b189337a23df0690c7f55b"])]
+    #[case(4, 0b11, &["db1a2656e1809de78fb29dddf24a1c75fbf7c6dc1f1341f485457c713ce49fa0", "adb439d3868b9273de8753e20a62a8e6d9ff6cfb43b189337a23df0690c7f55b"])]
+    fn test_merkle_tree_proof(#[case] index: usize, #[case] path: u64, #[case] hashes: &[&str]) {
+        let tree = MerkleTree::new(tree_leaves(5)).unwrap();
+        let proof = tree.proof_for(index).unwrap();
+        assert_eq!(proof.path, path);
+        assert_eq!(
+            proof.hashes,
+            hashes
+                .iter()
+                .map(|h| Bytes32::try_from(hex::decode(h).unwrap()).unwrap())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    // this follows simplify_merkle_proof in merkle_utils.clib, which is what
+    // the merkle puzzles use to compute the root from a leaf and its proof
+    fn simplify_merkle_proof(
+        a: &clvmr::Allocator,
+        leaf: &Bytes32,
+        proof: clvmr::NodePtr,
+    ) -> Bytes32 {
+        let (bitpath, mut hashes) = a.next(proof).expect("(bitpath . hashes_path)");
+        let mut bitpath = u64::try_from(a.number(bitpath)).expect("bitpath");
+        let mut leaf_hash = tree_leaf_hash(leaf);
+        while let Some((first, rest)) = a.next(hashes) {
+            let first = Bytes32::try_from(a.atom(first).as_ref()).expect("hash");
+            leaf_hash = if bitpath & 1 == 1 {
+                tree_node_hash(&first, &leaf_hash)
+            } else {
+                tree_node_hash(&leaf_hash, &first)
+            };
+            bitpath >>= 1;
+            hashes = rest;
+        }
+        leaf_hash
+    }
+
+    #[rstest]
+    fn test_merkle_proof_clvm(#[values(1, 2, 3, 7, 8, 9)] count: u8) {
+        let tree = MerkleTree::new(tree_leaves(count)).unwrap();
+        for (index, leaf) in tree.leaves().iter().enumerate() {
+            let proof = tree.proof_for(index).unwrap();
+            let mut a = clvmr::Allocator::new();
+            let node = proof.to_clvm(&mut a).unwrap();
+            assert_eq!(simplify_merkle_proof(&a, leaf, node), tree.root());
+            assert_eq!(MerkleProof::from_clvm(&a, node).unwrap(), proof);
+        }
+    }
+
+    #[test]
+    fn test_merkle_proof_clvm_format() {
+        // (path . hashes), i.e. the hashes are the rest of the list
+        let proof = MerkleProof {
+            path: 2,
+            hashes: vec![Bytes32::new([0xaa; 32])],
+        };
+        let mut a = clvmr::Allocator::new();
+        let node = proof.to_clvm(&mut a).unwrap();
+        assert_eq!(
+            hex::encode(clvmr::serde::node_to_bytes(&a, node).unwrap()),
+            format!("ff02ffa0{}80", "aa".repeat(32))
+        );
+    }
 }
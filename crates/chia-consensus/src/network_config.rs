@@ -0,0 +1,372 @@
+//! The network configuration from chia-blockchain's `config.yaml`.
+//!
+//! chia-blockchain defines the networks in the `network_overrides` section of
+//! its config. Each network has a set of overrides of the (mainnet) default
+//! consensus constants, and some settings, like the address prefix and the
+//! default port. The network to use is picked by `selected_network`. This
+//! module parses those two sections, so Rust tools can use an existing chia
+//! config, including custom networks.
+
+use std::collections::BTreeMap;
+
+use chia_protocol::{bytes32, Bytes32};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+use crate::consensus_constants::{ConsensusConstants, TEST_CONSTANTS};
+use crate::error::{Error, Result};
+
+/// The settings of a network, in `network_overrides.config`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkSettings {
+    pub address_prefix: String,
+    pub default_full_node_port: u16,
+}
+
+/// The `network_overrides` section of the chia config.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkOverrides {
+    /// The consensus constants to override, per network. The keys are the
+    /// upper case names of the constants.
+    #[serde(default)]
+    pub constants: BTreeMap<String, BTreeMap<String, Value>>,
+    #[serde(default)]
+    pub config: BTreeMap<String, NetworkSettings>,
+}
+
+// the parts of the chia config we use, everything else is ignored
+#[derive(Deserialize)]
+struct ChiaConfig {
+    network_overrides: NetworkOverrides,
+    selected_network: String,
+}
+
+/// A network, with its consensus constants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkConfig {
+    pub name: String,
+    pub address_prefix: String,
+    pub default_full_node_port: u16,
+    /// The DNS introducers to find peers with. These are not part of
+    /// `network_overrides`, so this is empty for networks other than mainnet
+    /// and testnet11.
+    pub dns_introducers: Vec<String>,
+    pub constants: ConsensusConstants,
+}
+
+impl NetworkConfig {
+    pub fn mainnet() -> Self {
+        Self {
+            name: "mainnet".to_string(),
+            address_prefix: "xch".to_string(),
+            default_full_node_port: 8444,
+            dns_introducers: dns_introducers("mainnet"),
+            constants: TEST_CONSTANTS,
+        }
+    }
+
+    pub fn testnet11() -> Self {
+        let pre_farm_puzzle_hash =
+            bytes32!("08296fc227decd043aee855741444538e4cc9a31772c4d1a9e6242d1e777e42a");
+        Self {
+            name: "testnet11".to_string(),
+            address_prefix: "txch".to_string(),
+            default_full_node_port: 58444,
+            dns_introducers: dns_introducers("testnet11"),
+            constants: ConsensusConstants {
+                min_plot_size: 18,
+                genesis_challenge: bytes32!(
+                    "37a90eb5185a9c4439a91ddc98bbadce7b4feba060d50116a067de66bf236615"
+                ),
+                genesis_pre_farm_pool_puzzle_hash: pre_farm_puzzle_hash,
+                genesis_pre_farm_farmer_puzzle_hash: pre_farm_puzzle_hash,
+                sub_slot_iters_starting: 67_108_864,
+                hard_fork_height: 0,
+                plot_filter_128_height: 6_029_568,
+                plot_filter_64_height: 11_075_328,
+                plot_filter_32_height: 16_121_088,
+                difficulty_constant_factor: 10_052_721_566_054,
+                difficulty_starting: 30,
+                epoch_blocks: 768,
+                mempool_block_buffer: 10,
+                ..TEST_CONSTANTS
+            },
+        }
+    }
+
+    pub fn genesis_challenge(&self) -> Bytes32 {
+        self.constants.genesis_challenge
+    }
+
+    /// Parses the selected network from a chia config file.
+    pub fn from_yaml_str(yaml: &str) -> Result<Self> {
+        let config: ChiaConfig =
+            serde_yaml::from_str(yaml).map_err(|e| Error::InvalidNetworkConfig(e.to_string()))?;
+        Self::from_overrides(&config.network_overrides, &config.selected_network)
+    }
+
+    /// The network `name`, as defined by `overrides`. The constants that are
+    /// not overridden have their mainnet value.
+    pub fn from_overrides(overrides: &NetworkOverrides, name: &str) -> Result<Self> {
+        let settings = overrides
+            .config
+            .get(name)
+            .ok_or_else(|| Error::UnknownNetwork(name.to_string()))?;
+        let constants = match overrides.constants.get(name) {
+            Some(constants) => apply_overrides(&TEST_CONSTANTS, constants)?,
+            None => TEST_CONSTANTS,
+        };
+        Ok(Self {
+            name: name.to_string(),
+            address_prefix: settings.address_prefix.clone(),
+            default_full_node_port: settings.default_full_node_port,
+            dns_introducers: dns_introducers(name),
+            constants,
+        })
+    }
+}
+
+fn dns_introducers(network: &str) -> Vec<String> {
+    let introducers: &[&str] = match network {
+        "mainnet" => &[
+            "dns-introducer.chia.net",
+            "chia.ctrlaltdel.ch",
+            "seeder.dexie.space",
+            "chia.hoffmang.com",
+            "seeder.xchpool.org",
+        ],
+        "testnet11" => &["dns-introducer-testnet11.chia.net"],
+        _ => &[],
+    };
+    introducers.iter().map(ToString::to_string).collect()
+}
+
+// a value in network_overrides.constants
+trait FromOverride: Sized {
+    fn from_override(value: &Value) -> Option<Self>;
+}
+
+macro_rules! impl_from_override_int {
+    ($($t:ty),*) => {
+        $(
+        impl FromOverride for $t {
+            fn from_override(value: &Value) -> Option<Self> {
+                <$t>::try_from(value.as_u64()?).ok()
+            }
+        }
+        )*
+    };
+}
+
+impl_from_override_int!(u8, u16, u32);
+
+impl FromOverride for u64 {
+    fn from_override(value: &Value) -> Option<Self> {
+        value.as_u64()
+    }
+}
+
+// YAML integers are limited to 64 bits
+impl FromOverride for u128 {
+    fn from_override(value: &Value) -> Option<Self> {
+        value.as_u64().map(u128::from)
+    }
+}
+
+// like chia-blockchain, bytes are hex encoded strings
+impl FromOverride for Bytes32 {
+    fn from_override(value: &Value) -> Option<Self> {
+        let s = value.as_str()?;
+        let s = s.strip_prefix("0x").unwrap_or(s);
+        Bytes32::try_from(hex::decode(s).ok()?).ok()
+    }
+}
+
+macro_rules! override_constants {
+    ($base:expr, $overrides:expr, $($field:ident),* $(,)?) => {{
+        // this fails to compile if a field is missing from the list
+        let ConsensusConstants { $($field: _),* } = $base;
+        let mut ret = $base.clone();
+        for (key, value) in $overrides {
+            match key.to_ascii_lowercase().as_str() {
+                $(stringify!($field) => {
+                    ret.$field = FromOverride::from_override(value).ok_or_else(|| {
+                        Error::InvalidNetworkConfig(format!("invalid value for {key}: {value:?}"))
+                    })?;
+                })*
+                // chia-blockchain ignores (with a warning) the constants it
+                // doesn't know, like the heights of past soft forks
+                _ => {}
+            }
+        }
+        ret
+    }};
+}
+
+/// Applies the overrides of one network to `base`, the way chia-blockchain's
+/// `ConsensusConstants.replace_str_to_bytes()` does.
+pub fn apply_overrides(
+    base: &ConsensusConstants,
+    overrides: &BTreeMap<String, Value>,
+) -> Result<ConsensusConstants> {
+    Ok(override_constants!(
+        base,
+        overrides,
+        slot_blocks_target,
+        min_blocks_per_challenge_block,
+        max_sub_slot_blocks,
+        num_sps_sub_slot,
+        sub_slot_iters_starting,
+        difficulty_constant_factor,
+        difficulty_starting,
+        difficulty_change_max_factor,
+        sub_epoch_blocks,
+        epoch_blocks,
+        significant_bits,
+        discriminant_size_bits,
+        number_zero_bits_plot_filter,
+        min_plot_size,
+        max_plot_size,
+        sub_slot_time_target,
+        num_sp_intervals_extra,
+        max_future_time2,
+        number_of_timestamps,
+        genesis_challenge,
+        agg_sig_me_additional_data,
+        agg_sig_parent_additional_data,
+        agg_sig_puzzle_additional_data,
+        agg_sig_amount_additional_data,
+        agg_sig_puzzle_amount_additional_data,
+        agg_sig_parent_amount_additional_data,
+        agg_sig_parent_puzzle_additional_data,
+        genesis_pre_farm_pool_puzzle_hash,
+        genesis_pre_farm_farmer_puzzle_hash,
+        max_vdf_witness_size,
+        mempool_block_buffer,
+        max_coin_amount,
+        max_block_cost_clvm,
+        cost_per_byte,
+        weight_proof_threshold,
+        weight_proof_recent_blocks,
+        max_block_count_per_requests,
+        blocks_cache_size,
+        max_generator_size,
+        max_generator_ref_list_size,
+        pool_sub_slot_iters,
+        soft_fork6_height,
+        hard_fork_height,
+        plot_filter_128_height,
+        plot_filter_64_height,
+        plot_filter_32_height,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INITIAL_CONFIG: &str = include_str!("../test-data/initial-config.yaml");
+
+    #[test]
+    fn test_mainnet() {
+        let config = NetworkConfig::from_yaml_str(INITIAL_CONFIG).unwrap();
+        assert_eq!(config, NetworkConfig::mainnet());
+    }
+
+    #[test]
+    fn test_testnet11() {
+        let yaml = INITIAL_CONFIG.replace(
+            "selected_network: &selected_network \"mainnet\"",
+            "selected_network: &selected_network \"testnet11\"",
+        );
+        let config = NetworkConfig::from_yaml_str(&yaml).unwrap();
+        assert_eq!(config, NetworkConfig::testnet11());
+
+        assert_eq!(config.name, "testnet11");
+        assert_eq!(config.address_prefix, "txch");
+        assert_eq!(config.default_full_node_port, 58444);
+        assert_eq!(
+            config.genesis_challenge(),
+            bytes32!("37a90eb5185a9c4439a91ddc98bbadce7b4feba060d50116a067de66bf236615")
+        );
+        let constants = &config.constants;
+        assert_eq!(constants.min_plot_size, 18);
+        assert_eq!(constants.sub_slot_iters_starting, 67_108_864);
+        assert_eq!(constants.difficulty_constant_factor, 10_052_721_566_054);
+        assert_eq!(constants.difficulty_starting, 30);
+        assert_eq!(constants.epoch_blocks, 768);
+        assert_eq!(constants.hard_fork_height, 0);
+        assert_eq!(constants.plot_filter_32_height, 16_121_088);
+        // not overridden
+        assert_eq!(constants.sub_epoch_blocks, TEST_CONSTANTS.sub_epoch_blocks);
+        assert_eq!(
+            constants.agg_sig_me_additional_data,
+            TEST_CONSTANTS.agg_sig_me_additional_data
+        );
+    }
+
+    #[test]
+    fn test_custom_network() {
+        let yaml = r#"
+network_overrides:
+  constants:
+    mynet:
+      GENESIS_CHALLENGE: "0x0101010101010101010101010101010101010101010101010101010101010101"
+      MAX_BLOCK_COST_CLVM: 1000
+  config:
+    mynet:
+      address_prefix: "my"
+      default_full_node_port: 1234
+selected_network: mynet
+"#;
+        let config = NetworkConfig::from_yaml_str(yaml).unwrap();
+        assert_eq!(config.name, "mynet");
+        assert_eq!(config.address_prefix, "my");
+        assert_eq!(config.default_full_node_port, 1234);
+        assert!(config.dns_introducers.is_empty());
+        assert_eq!(config.genesis_challenge(), Bytes32::new([1; 32]));
+        assert_eq!(config.constants.max_block_cost_clvm, 1000);
+        assert_eq!(config.constants.cost_per_byte, TEST_CONSTANTS.cost_per_byte);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let overrides: NetworkOverrides = serde_yaml::from_str(
+            r"
+constants:
+  mynet:
+    MIN_PLOT_SIZE: 256
+    GENESIS_CHALLENGE: abcd
+config:
+  mynet:
+    address_prefix: my
+    default_full_node_port: 1234
+",
+        )
+        .unwrap();
+        assert_eq!(
+            NetworkConfig::from_overrides(&overrides, "othernet"),
+            Err(Error::UnknownNetwork("othernet".to_string()))
+        );
+        assert!(matches!(
+            NetworkConfig::from_overrides(&overrides, "mynet"),
+            Err(Error::InvalidNetworkConfig(_))
+        ));
+
+        for value in [
+            "MIN_PLOT_SIZE: 256",
+            "MIN_PLOT_SIZE: -1",
+            "GENESIS_CHALLENGE: abcd",
+        ] {
+            let overrides: BTreeMap<String, Value> = serde_yaml::from_str(value).unwrap();
+            assert!(matches!(
+                apply_overrides(&TEST_CONSTANTS, &overrides),
+                Err(Error::InvalidNetworkConfig(_))
+            ));
+        }
+        assert!(matches!(
+            NetworkConfig::from_yaml_str("selected_network: mainnet"),
+            Err(Error::InvalidNetworkConfig(_))
+        ));
+    }
+}
@@ -0,0 +1,571 @@
+//! The signage point and infusion point iteration math, and the plot filter.
+//! These are pure functions of the consensus constants, ported from
+//! `chia.consensus.pot_iterations` and `chia.types.blockchain_format.proof_of_space`.
+//! It also has the partial proof math of the pool protocol, as done by the
+//! pool reference server.
+
+use crate::consensus_constants::ConsensusConstants;
+use crate::error::{Error, Result};
+use chia_protocol::Bytes32;
+use chia_sha2::Sha256;
+use num_bigint::BigUint;
+
+/// Returns whether a block at the specified signage point is infused in the
+/// next sub-slot.
+pub fn is_overflow_block(constants: &ConsensusConstants, signage_point_index: u8) -> Result<bool> {
+    let index = u32::from(signage_point_index);
+    if index >= constants.num_sps_sub_slot {
+        return Err(Error::InvalidSignagePointIndex(signage_point_index));
+    }
+    Ok(index >= constants.num_sps_sub_slot - u32::from(constants.num_sp_intervals_extra))
+}
+
+/// The number of iterations between two signage points.
+pub fn calculate_sp_interval_iters(
+    constants: &ConsensusConstants,
+    sub_slot_iters: u64,
+) -> Result<u64> {
+    let num_sps = u64::from(constants.num_sps_sub_slot);
+    if sub_slot_iters.checked_rem(num_sps) != Some(0) {
+        return Err(Error::InvalidSubSlotIters(sub_slot_iters));
+    }
+    Ok(sub_slot_iters / num_sps)
+}
+
+/// The number of iterations into the sub-slot of the specified signage point.
+pub fn calculate_sp_iters(
+    constants: &ConsensusConstants,
+    sub_slot_iters: u64,
+    signage_point_index: u8,
+) -> Result<u64> {
+    if u32::from(signage_point_index) >= constants.num_sps_sub_slot {
+        return Err(Error::InvalidSignagePointIndex(signage_point_index));
+    }
+    Ok(calculate_sp_interval_iters(constants, sub_slot_iters)? * u64::from(signage_point_index))
+}
+
+/// The number of iterations into the sub-slot where a block is infused. For
+/// overflow blocks, this is into the next sub-slot.
+pub fn calculate_ip_iters(
+    constants: &ConsensusConstants,
+    sub_slot_iters: u64,
+    signage_point_index: u8,
+    required_iters: u64,
+) -> Result<u64> {
+    // Note that the sub_slot_iters is for the block passed in, which might be
+    // in the previous epoch
+    let sp_iters = calculate_sp_iters(constants, sub_slot_iters, signage_point_index)?;
+    let sp_interval_iters = calculate_sp_interval_iters(constants, sub_slot_iters)?;
+    if sp_iters.checked_rem(sp_interval_iters) != Some(0) || sp_iters >= sub_slot_iters {
+        return Err(Error::InvalidSpIters {
+            sp_iters,
+            sub_slot_iters,
+        });
+    }
+
+    if required_iters >= sp_interval_iters || required_iters == 0 {
+        return Err(Error::InvalidRequiredIters {
+            required_iters,
+            sp_interval_iters,
+        });
+    }
+
+    let ip_iters = (u128::from(sp_iters)
+        + u128::from(constants.num_sp_intervals_extra) * u128::from(sp_interval_iters)
+        + u128::from(required_iters))
+        % u128::from(sub_slot_iters);
+    Ok(u64::try_from(ip_iters).expect("less than sub_slot_iters"))
+}
+
+/// The number of iterations required for a proof of space with the specified
+/// quality string, at the signage point with the specified challenge chain
+/// output hash.
+pub fn calculate_iterations_quality(
+    difficulty_constant_factor: u128,
+    quality_string: &Bytes32,
+    size: u8,
+    difficulty: u64,
+    cc_sp_output_hash: &Bytes32,
+) -> Result<u64> {
+    if size == 0 {
+        return Err(Error::InvalidPlotSize(size));
+    }
+
+    let mut ctx = Sha256::new();
+    ctx.update(quality_string);
+    ctx.update(cc_sp_output_hash);
+    let sp_quality_string = BigUint::from_bytes_be(&ctx.finalize());
+
+    // the expected size of a plot of size k is (2k + 1) * 2^(k - 1)
+    let expected_plot_size = BigUint::from(2 * u32::from(size) + 1) << (size - 1);
+    let iters = BigUint::from(difficulty) * difficulty_constant_factor * sp_quality_string
+        / ((BigUint::from(1_u8) << 256) * expected_plot_size);
+    let iters = u64::try_from(iters).map_err(|_| Error::IterationsOverflow)?;
+    Ok(iters.max(1))
+}
+
+/// Returns whether a proof of space is a valid partial at the specified pool
+/// difficulty, i.e. whether its required iterations are less than the
+/// iterations of a signage point interval. Pools check partials against
+/// `constants.pool_sub_slot_iters`, regardless of the sub-slot iterations of
+/// the blockchain.
+pub fn is_valid_partial(
+    constants: &ConsensusConstants,
+    quality_string: &Bytes32,
+    size: u8,
+    difficulty: u64,
+    cc_sp_output_hash: &Bytes32,
+    sub_slot_iters: u64,
+) -> Result<bool> {
+    let iters_limit = calculate_sp_interval_iters(constants, sub_slot_iters)?;
+    match calculate_iterations_quality(
+        constants.difficulty_constant_factor,
+        quality_string,
+        size,
+        difficulty,
+        cc_sp_output_hash,
+    ) {
+        Ok(required_iters) => Ok(required_iters < iters_limit),
+        // more iterations than fit in a u64 are never less than the limit
+        Err(Error::IterationsOverflow) => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+/// The highest pool difficulty at which a proof of space is still a valid
+/// partial (see [`is_valid_partial`]), or 0 if it isn't valid at any
+/// difficulty.
+pub fn max_partial_difficulty(
+    constants: &ConsensusConstants,
+    quality_string: &Bytes32,
+    size: u8,
+    cc_sp_output_hash: &Bytes32,
+    sub_slot_iters: u64,
+) -> Result<u64> {
+    if size == 0 {
+        return Err(Error::InvalidPlotSize(size));
+    }
+    let iters_limit = calculate_sp_interval_iters(constants, sub_slot_iters)?;
+    // the required iterations are always at least 1
+    if iters_limit <= 1 {
+        return Ok(0);
+    }
+
+    let mut ctx = Sha256::new();
+    ctx.update(quality_string);
+    ctx.update(cc_sp_output_hash);
+    let sp_quality_string = BigUint::from_bytes_be(&ctx.finalize());
+
+    // the required iterations are difficulty * factor / divisor, rounded
+    // down, and they're less than the limit as long as difficulty * factor <
+    // iters_limit * divisor
+    let factor = BigUint::from(constants.difficulty_constant_factor) * sp_quality_string;
+    // a quality of 0 requires no iterations at any difficulty
+    if factor.bits() == 0 {
+        return Ok(u64::MAX);
+    }
+    let expected_plot_size = BigUint::from(2 * u32::from(size) + 1) << (size - 1);
+    let divisor = (BigUint::from(1_u8) << 256) * expected_plot_size;
+    let difficulty = (BigUint::from(iters_limit) * divisor - 1_u8) / factor;
+    Ok(u64::try_from(difficulty).unwrap_or(u64::MAX))
+}
+
+/// The number of leading zero bits the plot filter requires at the specified
+/// height. The filter is halved at the hard fork and at each of the plot
+/// filter adjustment heights after it.
+pub fn calculate_prefix_bits(constants: &ConsensusConstants, height: u32) -> u8 {
+    let reduction: u8 = if height >= constants.plot_filter_32_height {
+        4
+    } else if height >= constants.plot_filter_64_height {
+        3
+    } else if height >= constants.plot_filter_128_height {
+        2
+    } else {
+        u8::from(height >= constants.hard_fork_height)
+    };
+    constants
+        .number_zero_bits_plot_filter
+        .saturating_sub(reduction)
+}
+
+pub fn calculate_plot_filter_input(
+    plot_id: &Bytes32,
+    challenge_hash: &Bytes32,
+    signage_point: &Bytes32,
+) -> Bytes32 {
+    let mut ctx = Sha256::new();
+    ctx.update(plot_id);
+    ctx.update(challenge_hash);
+    ctx.update(signage_point);
+    ctx.finalize().into()
+}
+
+/// Returns whether the plot with the specified ID passes the plot filter for
+/// a signage point at the specified height.
+pub fn passes_plot_filter(
+    constants: &ConsensusConstants,
+    height: u32,
+    plot_id: &Bytes32,
+    challenge_hash: &Bytes32,
+    signage_point: &Bytes32,
+) -> bool {
+    let prefix_bits = u32::from(calculate_prefix_bits(constants, height));
+    if prefix_bits == 0 {
+        return true;
+    }
+    let filter = calculate_plot_filter_input(plot_id, challenge_hash, signage_point);
+    leading_zero_bits(&filter) >= prefix_bits
+}
+
+fn leading_zero_bits(buf: &[u8]) -> u32 {
+    let mut ret = 0;
+    for b in buf {
+        if *b != 0 {
+            return ret + b.leading_zeros();
+        }
+        ret += 8;
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus_constants::TEST_CONSTANTS;
+    use hex_literal::hex;
+    use rstest::rstest;
+
+    // the constants used by chia-blockchain's test_pot_iterations.py
+    const CONSTANTS: ConsensusConstants = ConsensusConstants {
+        num_sps_sub_slot: 32,
+        sub_slot_time_target: 300,
+        ..TEST_CONSTANTS
+    };
+
+    #[test]
+    fn test_is_overflow_block() {
+        assert!(!is_overflow_block(&CONSTANTS, 27).unwrap());
+        assert!(!is_overflow_block(&CONSTANTS, 28).unwrap());
+        assert!(is_overflow_block(&CONSTANTS, 29).unwrap());
+        assert!(is_overflow_block(&CONSTANTS, 30).unwrap());
+        assert!(is_overflow_block(&CONSTANTS, 31).unwrap());
+        assert_eq!(
+            is_overflow_block(&CONSTANTS, 32).unwrap_err(),
+            Error::InvalidSignagePointIndex(32)
+        );
+    }
+
+    #[test]
+    fn test_calculate_sp_iters() {
+        let ssi: u64 = 100_001 * 64 * 4;
+        assert_eq!(
+            calculate_sp_iters(&CONSTANTS, ssi, 32).unwrap_err(),
+            Error::InvalidSignagePointIndex(32)
+        );
+        calculate_sp_iters(&CONSTANTS, ssi, 31).unwrap();
+    }
+
+    #[test]
+    fn test_calculate_ip_iters() {
+        let ssi: u64 = 100_001 * 64 * 4;
+        let sp_interval_iters = ssi / u64::from(CONSTANTS.num_sps_sub_slot);
+        let extra = u64::from(CONSTANTS.num_sp_intervals_extra);
+
+        // Invalid signage point index
+        assert_eq!(
+            calculate_ip_iters(&CONSTANTS, ssi, 123, 100_000).unwrap_err(),
+            Error::InvalidSignagePointIndex(123)
+        );
+
+        let sp_iters = sp_interval_iters * 13;
+
+        // required_iters too high
+        for required_iters in [sp_interval_iters, sp_interval_iters * 12] {
+            assert_eq!(
+                calculate_ip_iters(&CONSTANTS, ssi, 13, required_iters).unwrap_err(),
+                Error::InvalidRequiredIters {
+                    required_iters,
+                    sp_interval_iters
+                }
+            );
+        }
+
+        // required_iters too low (0)
+        assert_eq!(
+            calculate_ip_iters(&CONSTANTS, ssi, 13, 0).unwrap_err(),
+            Error::InvalidRequiredIters {
+                required_iters: 0,
+                sp_interval_iters
+            }
+        );
+
+        let required_iters = sp_interval_iters - 1;
+        let ip_iters = calculate_ip_iters(&CONSTANTS, ssi, 13, required_iters).unwrap();
+        assert_eq!(
+            ip_iters,
+            sp_iters + extra * sp_interval_iters + required_iters
+        );
+
+        let required_iters = 1;
+        let ip_iters = calculate_ip_iters(&CONSTANTS, ssi, 13, required_iters).unwrap();
+        assert_eq!(
+            ip_iters,
+            sp_iters + extra * sp_interval_iters + required_iters
+        );
+
+        // int(ssi * 4 / 300)
+        let required_iters = 341_336;
+        let ip_iters = calculate_ip_iters(&CONSTANTS, ssi, 13, required_iters).unwrap();
+        assert_eq!(
+            ip_iters,
+            sp_iters + extra * sp_interval_iters + required_iters
+        );
+        assert!(sp_iters < ip_iters);
+
+        // Overflow
+        let sp_iters = sp_interval_iters * u64::from(CONSTANTS.num_sps_sub_slot - 1);
+        let ip_iters = calculate_ip_iters(
+            &CONSTANTS,
+            ssi,
+            u8::try_from(CONSTANTS.num_sps_sub_slot - 1).unwrap(),
+            required_iters,
+        )
+        .unwrap();
+        assert_eq!(
+            ip_iters,
+            (sp_iters + extra * sp_interval_iters + required_iters) % ssi
+        );
+        assert!(sp_iters > ip_iters);
+    }
+
+    #[test]
+    fn test_invalid_sub_slot_iters() {
+        let ssi: u64 = 100_001 * 64 * 4 + 1;
+        assert_eq!(
+            calculate_sp_interval_iters(&CONSTANTS, ssi).unwrap_err(),
+            Error::InvalidSubSlotIters(ssi)
+        );
+        assert_eq!(
+            calculate_ip_iters(&CONSTANTS, 0, 0, 1).unwrap_err(),
+            Error::InvalidSpIters {
+                sp_iters: 0,
+                sub_slot_iters: 0
+            }
+        );
+    }
+
+    #[rstest]
+    #[case(2_u128.pow(67), [1; 32], 32, 7, [2; 32], 7_172_079_287)]
+    #[case(2_u128.pow(67), [1; 32], 32, 1000, [2; 32], 1_024_582_755_317)]
+    #[case(2_u128.pow(67), [1; 32], 35, 1000, [2; 32], 117_249_787_140)]
+    #[case(2_u128.pow(67), [5; 32], 50, 1, [6; 32], 2306)]
+    // the result is never less than 1
+    #[case(1, [1; 32], 32, 1, [2; 32], 1)]
+    fn test_calculate_iterations_quality(
+        #[case] difficulty_constant_factor: u128,
+        #[case] quality_string: [u8; 32],
+        #[case] size: u8,
+        #[case] difficulty: u64,
+        #[case] cc_sp_output_hash: [u8; 32],
+        #[case] expected: u64,
+    ) {
+        let iters = calculate_iterations_quality(
+            difficulty_constant_factor,
+            &quality_string.into(),
+            size,
+            difficulty,
+            &cc_sp_output_hash.into(),
+        )
+        .unwrap();
+        assert_eq!(iters, expected);
+    }
+
+    // the partials are checked against the pool sub-slot iterations, with 32
+    // signage points per sub-slot
+    #[rstest]
+    #[case([1; 32], 32, [2; 32], 1)]
+    #[case([1; 32], 35, [2; 32], 10)]
+    #[case([5; 32], 32, [6; 32], 1)]
+    fn test_max_partial_difficulty(
+        #[case] quality_string: [u8; 32],
+        #[case] size: u8,
+        #[case] cc_sp_output_hash: [u8; 32],
+        #[case] expected: u64,
+    ) {
+        let quality_string = Bytes32::new(quality_string);
+        let cc_sp_output_hash = Bytes32::new(cc_sp_output_hash);
+        let difficulty = max_partial_difficulty(
+            &CONSTANTS,
+            &quality_string,
+            size,
+            &cc_sp_output_hash,
+            CONSTANTS.pool_sub_slot_iters,
+        )
+        .unwrap();
+        assert_eq!(difficulty, expected);
+
+        // it's the highest difficulty where the partial is valid
+        let is_valid = |difficulty| {
+            is_valid_partial(
+                &CONSTANTS,
+                &quality_string,
+                size,
+                difficulty,
+                &cc_sp_output_hash,
+                CONSTANTS.pool_sub_slot_iters,
+            )
+            .unwrap()
+        };
+        assert!(is_valid(difficulty));
+        assert!(!is_valid(difficulty + 1));
+    }
+
+    #[test]
+    fn test_partial_not_good_enough() {
+        // with 64 signage points per sub-slot, the limit is halved and this
+        // proof isn't good enough at any difficulty
+        let quality_string = Bytes32::new([5; 32]);
+        let cc_sp_output_hash = Bytes32::new([6; 32]);
+        assert_eq!(
+            max_partial_difficulty(
+                &TEST_CONSTANTS,
+                &quality_string,
+                32,
+                &cc_sp_output_hash,
+                TEST_CONSTANTS.pool_sub_slot_iters
+            )
+            .unwrap(),
+            0
+        );
+        assert!(!is_valid_partial(
+            &TEST_CONSTANTS,
+            &quality_string,
+            32,
+            1,
+            &cc_sp_output_hash,
+            TEST_CONSTANTS.pool_sub_slot_iters
+        )
+        .unwrap());
+
+        // required iterations that overflow aren't valid either
+        assert!(!is_valid_partial(
+            &TEST_CONSTANTS,
+            &quality_string,
+            32,
+            u64::MAX,
+            &cc_sp_output_hash,
+            TEST_CONSTANTS.pool_sub_slot_iters
+        )
+        .unwrap());
+        assert_eq!(
+            max_partial_difficulty(&TEST_CONSTANTS, &quality_string, 0, &cc_sp_output_hash, 64)
+                .unwrap_err(),
+            Error::InvalidPlotSize(0)
+        );
+    }
+
+    #[test]
+    fn test_calculate_iterations_quality_errors() {
+        let quality = Bytes32::new([3; 32]);
+        let cc_sp_output_hash = Bytes32::new([4; 32]);
+        assert_eq!(
+            calculate_iterations_quality(2_u128.pow(67), &quality, 32, 1 << 40, &cc_sp_output_hash)
+                .unwrap_err(),
+            Error::IterationsOverflow
+        );
+        assert_eq!(
+            calculate_iterations_quality(2_u128.pow(67), &quality, 0, 7, &cc_sp_output_hash)
+                .unwrap_err(),
+            Error::InvalidPlotSize(0)
+        );
+    }
+
+    // the values from chia-blockchain's test_calculate_prefix_bits, with the
+    // mainnet heights
+    #[rstest]
+    #[case(0, 9)]
+    #[case(5_495_999, 9)]
+    #[case(5_496_000, 8)]
+    #[case(10_542_000, 7)]
+    #[case(15_592_000, 6)]
+    #[case(20_643_000, 5)]
+    fn test_calculate_prefix_bits(#[case] height: u32, #[case] expected: u8) {
+        assert_eq!(calculate_prefix_bits(&TEST_CONSTANTS, height), expected);
+    }
+
+    #[rstest]
+    #[case(0, 3)]
+    #[case(5_496_000, 2)]
+    #[case(10_542_000, 1)]
+    #[case(15_592_000, 0)]
+    #[case(20_643_000, 0)]
+    fn test_calculate_prefix_bits_clamp_zero(#[case] height: u32, #[case] expected: u8) {
+        let constants = ConsensusConstants {
+            number_zero_bits_plot_filter: 3,
+            ..TEST_CONSTANTS
+        };
+        assert_eq!(calculate_prefix_bits(&constants, height), expected);
+    }
+
+    #[test]
+    fn test_calculate_plot_filter_input() {
+        assert_eq!(
+            calculate_plot_filter_input(
+                &Bytes32::new([30; 32]),
+                &Bytes32::new([115; 32]),
+                &Bytes32::new([3; 32])
+            ),
+            Bytes32::new(hex!(
+                "005c7b64b7004fb99a2895989eb6a6f89ec8794aea1d03b3868efd81bb909255"
+            ))
+        );
+    }
+
+    // the plot ID and challenge hash are chosen to give a filter input with
+    // the specified number of leading zero bits, for a signage point of [3; 32]
+    #[rstest]
+    #[case::zeros_10(7, 60, 0, true)]
+    #[case::zeros_10(7, 60, 20_643_000, true)]
+    #[case::zeros_9(30, 115, 0, true)]
+    #[case::zeros_8(0, 200, 0, false)]
+    #[case::zeros_8(0, 200, 5_496_000, true)]
+    #[case::zeros_7(1, 244, 5_496_000, false)]
+    #[case::zeros_7(1, 244, 10_542_000, true)]
+    #[case::zeros_6(0, 119, 10_542_000, false)]
+    #[case::zeros_6(0, 119, 15_592_000, true)]
+    #[case::zeros_5(0, 81, 15_592_000, false)]
+    #[case::zeros_5(0, 81, 20_643_000, true)]
+    #[case::zeros_4(0, 9, 20_643_000, false)]
+    fn test_passes_plot_filter(
+        #[case] plot_id: u8,
+        #[case] challenge_hash: u8,
+        #[case] height: u32,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            passes_plot_filter(
+                &TEST_CONSTANTS,
+                height,
+                &Bytes32::new([plot_id; 32]),
+                &Bytes32::new([challenge_hash; 32]),
+                &Bytes32::new([3; 32]),
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_passes_plot_filter_no_prefix_bits() {
+        let constants = ConsensusConstants {
+            number_zero_bits_plot_filter: 0,
+            ..TEST_CONSTANTS
+        };
+        assert!(passes_plot_filter(
+            &constants,
+            0,
+            &Bytes32::new([0; 32]),
+            &Bytes32::new([9; 32]),
+            &Bytes32::new([3; 32]),
+        ));
+    }
+}
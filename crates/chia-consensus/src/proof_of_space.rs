@@ -0,0 +1,125 @@
+use chia_bls::{PublicKey, SecretKey};
+use chia_protocol::Bytes32;
+use chia_sha2::Sha256;
+
+/// The plot ID of a plot that pays its pool rewards to the owner of
+/// `pool_pk` (an "OG" plot).
+pub fn calculate_plot_id_pk(pool_pk: &PublicKey, plot_pk: &PublicKey) -> Bytes32 {
+    let mut ctx = Sha256::new();
+    ctx.update(pool_pk.to_bytes());
+    ctx.update(plot_pk.to_bytes());
+    ctx.finalize().into()
+}
+
+/// The plot ID of a plot that pays its pool rewards to a pool contract (a
+/// plot NFT), with the puzzle hash `pool_contract_ph`.
+pub fn calculate_plot_id_ph(pool_contract_ph: &Bytes32, plot_pk: &PublicKey) -> Bytes32 {
+    let mut ctx = Sha256::new();
+    ctx.update(pool_contract_ph);
+    ctx.update(plot_pk.to_bytes());
+    ctx.finalize().into()
+}
+
+/// The secret key of the taproot tweak, which makes the plot key of a pool
+/// contract plot require the farmer's signature. It's derived from the hash
+/// of the sum of both keys followed by the keys themselves.
+pub fn generate_taproot_sk(local_pk: &PublicKey, farmer_pk: &PublicKey) -> SecretKey {
+    let mut ctx = Sha256::new();
+    ctx.update((local_pk + farmer_pk).to_bytes());
+    ctx.update(local_pk.to_bytes());
+    ctx.update(farmer_pk.to_bytes());
+    let taproot_hash: [u8; 32] = ctx.finalize();
+    SecretKey::from_seed(&taproot_hash)
+}
+
+/// The plot public key of a plot with the specified local and farmer keys.
+/// Pool contract plots include the taproot tweak, OG plots don't.
+pub fn generate_plot_public_key(
+    local_pk: &PublicKey,
+    farmer_pk: &PublicKey,
+    include_taproot: bool,
+) -> PublicKey {
+    let plot_pk = local_pk + farmer_pk;
+    if include_taproot {
+        plot_pk + &generate_taproot_sk(local_pk, farmer_pk).public_key()
+    } else {
+        plot_pk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::rstest;
+
+    const GENERATOR: [u8; 48] = hex!("97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb");
+    const INFINITY: [u8; 48] = hex!("c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000");
+
+    // the expected plot IDs are the sha256 of the concatenated keys
+    #[rstest]
+    #[case(
+        GENERATOR,
+        INFINITY,
+        hex!("ba39c4b5313a2c4b6f41d1491c98e4e39b98a9b931facec29a8407462a3711de")
+    )]
+    #[case(
+        INFINITY,
+        GENERATOR,
+        hex!("5a4870ba56a6e2f277af745e28701d625e85b7e9c60ce9bc6e976c9ee72d4c98")
+    )]
+    fn test_calculate_plot_id_pk(
+        #[case] pool_pk: [u8; 48],
+        #[case] plot_pk: [u8; 48],
+        #[case] expected: [u8; 32],
+    ) {
+        let pool_pk = PublicKey::from_bytes(&pool_pk).unwrap();
+        let plot_pk = PublicKey::from_bytes(&plot_pk).unwrap();
+        assert_eq!(
+            calculate_plot_id_pk(&pool_pk, &plot_pk),
+            Bytes32::new(expected)
+        );
+    }
+
+    #[rstest]
+    #[case(
+        GENERATOR,
+        hex!("faf009d9c3f92fb4b01a6a0ffb1be7e4b60745fa48db1e33993037239ffeb2a1")
+    )]
+    #[case(
+        INFINITY,
+        hex!("0fe4fa260eec3a9731f46f5e8192498562861ccb60f50e51a6c611c9885ed43c")
+    )]
+    fn test_calculate_plot_id_ph(#[case] plot_pk: [u8; 48], #[case] expected: [u8; 32]) {
+        let plot_pk = PublicKey::from_bytes(&plot_pk).unwrap();
+        assert_eq!(
+            calculate_plot_id_ph(&Bytes32::new([0x11; 32]), &plot_pk),
+            Bytes32::new(expected)
+        );
+    }
+
+    #[rstest]
+    fn test_generate_plot_public_key(#[values(false, true)] include_taproot: bool) {
+        let local_sk = SecretKey::from_seed(&[1; 32]);
+        let farmer_sk = SecretKey::from_seed(&[2; 32]);
+        let local_pk = local_sk.public_key();
+        let farmer_pk = farmer_sk.public_key();
+
+        let plot_pk = generate_plot_public_key(&local_pk, &farmer_pk, include_taproot);
+
+        // the plot key is the public key of the sum of the secret keys
+        let mut plot_sk = local_sk + &farmer_sk;
+        if include_taproot {
+            let mut taproot_message = (&local_pk + &farmer_pk).to_bytes().to_vec();
+            taproot_message.extend_from_slice(&local_pk.to_bytes());
+            taproot_message.extend_from_slice(&farmer_pk.to_bytes());
+            let mut ctx = Sha256::new();
+            ctx.update(&taproot_message);
+            let taproot_sk = SecretKey::from_seed(&ctx.finalize());
+            assert_eq!(generate_taproot_sk(&local_pk, &farmer_pk), taproot_sk);
+            plot_sk += &taproot_sk;
+        }
+        assert_eq!(plot_pk, plot_sk.public_key());
+        assert_eq!(plot_pk == &local_pk + &farmer_pk, !include_taproot);
+    }
+}
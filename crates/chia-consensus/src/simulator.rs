@@ -0,0 +1,461 @@
+use crate::block_rewards::{
+    calculate_base_farmer_reward, calculate_pool_reward, create_farmer_coin, create_pool_coin,
+};
+use crate::consensus_constants::ConsensusConstants;
+use crate::gen::validation_error::ErrorCode;
+use crate::spendbundle_validation::validate_clvm_and_signature;
+use chia_protocol::{Amount, Bytes32, Coin, CoinState, SpendBundle};
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "py-bindings")]
+use pyo3::prelude::*;
+
+/// The timestamp of the genesis block of a [`Simulator`].
+pub const GENESIS_TIMESTAMP: u64 = 1_600_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CoinRecord {
+    coin: Coin,
+    created_height: u32,
+    spent_height: Option<u32>,
+    // the timestamp of the block that created the coin
+    timestamp: u64,
+}
+
+/// A minimal, in-memory blockchain, for testing puzzle drivers and wallets
+/// without running a full node. It tracks the coin set and validates spend
+/// bundles the way the mempool does, but there are no proofs of space,
+/// signage points or sub-slots. Every block is a transaction block.
+///
+/// The simulator is deterministic. The genesis block is at height 0 with
+/// [`GENESIS_TIMESTAMP`] and has no coins. Every new block has the current
+/// timestamp, which only changes when calling `pass_time()`.
+#[cfg_attr(feature = "py-bindings", pyclass(name = "Simulator"))]
+#[derive(Debug, Clone)]
+pub struct Simulator {
+    constants: ConsensusConstants,
+    coins: HashMap<Bytes32, CoinRecord>,
+    // the timestamp of each block, indexed by height
+    block_timestamps: Vec<u64>,
+    // the timestamp the next block will get
+    timestamp: u64,
+}
+
+impl Simulator {
+    pub fn new(constants: ConsensusConstants) -> Self {
+        Self {
+            constants,
+            coins: HashMap::new(),
+            block_timestamps: vec![GENESIS_TIMESTAMP],
+            timestamp: GENESIS_TIMESTAMP,
+        }
+    }
+
+    pub fn constants(&self) -> &ConsensusConstants {
+        &self.constants
+    }
+
+    /// The height of the peak block.
+    pub fn height(&self) -> u32 {
+        (self.block_timestamps.len() - 1) as u32
+    }
+
+    /// The timestamp the next block will get.
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Advances the timestamp of the next block.
+    pub fn pass_time(&mut self, seconds: u64) {
+        self.timestamp += seconds;
+    }
+
+    /// Adds a block with no transactions. The pool and farmer rewards are
+    /// both paid to `puzzle_hash`, and returned as (pool, farmer) coins.
+    /// Unlike on the real chain, fees aren't paid out.
+    pub fn farm_block(&mut self, puzzle_hash: Bytes32) -> Vec<Coin> {
+        let height = self.height() + 1;
+        let challenge = &self.constants.genesis_challenge;
+        let rewards = vec![
+            create_pool_coin(
+                height,
+                puzzle_hash,
+                calculate_pool_reward(height),
+                challenge,
+            ),
+            create_farmer_coin(
+                height,
+                puzzle_hash,
+                calculate_base_farmer_reward(height),
+                challenge,
+            ),
+        ];
+        for coin in &rewards {
+            self.insert_coin(*coin, height);
+        }
+        self.block_timestamps.push(self.timestamp);
+        rewards
+    }
+
+    /// Validates the spend bundle against the current peak, the same way the
+    /// mempool would, and includes it in a new block. Returns the cost of the
+    /// spend bundle. If it's invalid, nothing changes.
+    pub fn push_bundle(&mut self, spend_bundle: &SpendBundle) -> Result<u64, ErrorCode> {
+        let peak = self.height();
+        let height = peak + 1;
+        let (conditions, _, _) = validate_clvm_and_signature(
+            spend_bundle,
+            self.constants.max_block_cost_clvm,
+            &self.constants,
+            height,
+        )?;
+
+        // ephemeral coins are created and spent in the new block
+        let additions: Vec<Coin> = conditions
+            .spends
+            .iter()
+            .flat_map(|spend| {
+                spend.create_coin.iter().map(|(puzzle_hash, amount, _)| {
+                    Coin::new(spend.coin_id, *puzzle_hash, Amount::new(*amount))
+                })
+            })
+            .collect();
+        let ephemeral: HashSet<Bytes32> = additions.iter().map(Coin::coin_id).collect();
+
+        if peak < conditions.height_absolute {
+            return Err(ErrorCode::AssertHeightAbsoluteFailed);
+        }
+        if self.timestamp < conditions.seconds_absolute {
+            return Err(ErrorCode::AssertSecondsAbsoluteFailed);
+        }
+        if conditions
+            .before_height_absolute
+            .is_some_and(|before| peak >= before)
+        {
+            return Err(ErrorCode::AssertBeforeHeightAbsoluteFailed);
+        }
+        if conditions
+            .before_seconds_absolute
+            .is_some_and(|before| self.timestamp >= before)
+        {
+            return Err(ErrorCode::AssertBeforeSecondsAbsoluteFailed);
+        }
+
+        for spend in &conditions.spends {
+            let (created_height, created_timestamp) = match self.coins.get(&spend.coin_id) {
+                Some(record) if record.spent_height.is_some() => {
+                    return Err(ErrorCode::DoubleSpend);
+                }
+                Some(record) => (record.created_height, record.timestamp),
+                None if ephemeral.contains(&spend.coin_id) => (height, self.timestamp),
+                None => return Err(ErrorCode::UnknownUnspent),
+            };
+
+            if spend
+                .birth_height
+                .is_some_and(|birth| birth != created_height)
+            {
+                return Err(ErrorCode::AssertMyBirthHeightFailed);
+            }
+            if spend
+                .birth_seconds
+                .is_some_and(|birth| birth != created_timestamp)
+            {
+                return Err(ErrorCode::AssertMyBirthSecondsFailed);
+            }
+            if spend.height_relative.is_some_and(|relative| {
+                u64::from(peak) < u64::from(created_height) + u64::from(relative)
+            }) {
+                return Err(ErrorCode::AssertHeightRelativeFailed);
+            }
+            if spend
+                .seconds_relative
+                .is_some_and(|relative| self.timestamp < created_timestamp.saturating_add(relative))
+            {
+                return Err(ErrorCode::AssertSecondsRelativeFailed);
+            }
+            if spend.before_height_relative.is_some_and(|relative| {
+                u64::from(peak) >= u64::from(created_height) + u64::from(relative)
+            }) {
+                return Err(ErrorCode::AssertBeforeHeightRelativeFailed);
+            }
+            if spend.before_seconds_relative.is_some_and(|relative| {
+                self.timestamp >= created_timestamp.saturating_add(relative)
+            }) {
+                return Err(ErrorCode::AssertBeforeSecondsRelativeFailed);
+            }
+        }
+
+        for coin in additions {
+            self.insert_coin(coin, height);
+        }
+        for spend in &conditions.spends {
+            self.coins
+                .get_mut(&spend.coin_id)
+                .expect("removals were checked above")
+                .spent_height = Some(height);
+        }
+        self.block_timestamps.push(self.timestamp);
+
+        Ok(conditions.cost)
+    }
+
+    /// The state of a coin, if it has been created.
+    pub fn coin_state(&self, coin_id: Bytes32) -> Option<CoinState> {
+        self.coins.get(&coin_id).map(|record| {
+            CoinState::new(
+                record.coin,
+                record.spent_height,
+                Some(record.created_height),
+            )
+        })
+    }
+
+    /// All unspent coins with the given puzzle hash, oldest first.
+    pub fn unspent_coins(&self, puzzle_hash: Bytes32) -> Vec<Coin> {
+        let mut records: Vec<&CoinRecord> = self
+            .coins
+            .values()
+            .filter(|record| {
+                record.spent_height.is_none() && record.coin.puzzle_hash == puzzle_hash
+            })
+            .collect();
+        records.sort_by_key(|record| (record.created_height, record.coin.coin_id()));
+        records.into_iter().map(|record| record.coin).collect()
+    }
+
+    /// Undoes all blocks above `height`, as if the chain reorged to a fork at
+    /// that height. The timestamp of the next block goes back to the timestamp
+    /// of the new peak. Rewinding to the current height, or above, does
+    /// nothing.
+    pub fn rewind(&mut self, height: u32) {
+        if height >= self.height() {
+            return;
+        }
+        self.coins
+            .retain(|_, record| record.created_height <= height);
+        for record in self.coins.values_mut() {
+            if record.spent_height.is_some_and(|spent| spent > height) {
+                record.spent_height = None;
+            }
+        }
+        self.block_timestamps.truncate(height as usize + 1);
+        self.timestamp = self.block_timestamps[height as usize];
+    }
+
+    fn insert_coin(&mut self, coin: Coin, height: u32) {
+        self.coins.insert(
+            coin.coin_id(),
+            CoinRecord {
+                coin,
+                created_height: height,
+                spent_height: None,
+                timestamp: self.timestamp,
+            },
+        );
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl Simulator {
+    #[new]
+    pub fn py_new(constants: ConsensusConstants) -> Self {
+        Self::new(constants)
+    }
+
+    #[getter(height)]
+    pub fn py_height(&self) -> u32 {
+        self.height()
+    }
+
+    #[getter(timestamp)]
+    pub fn py_timestamp(&self) -> u64 {
+        self.timestamp()
+    }
+
+    #[pyo3(name = "pass_time")]
+    pub fn py_pass_time(&mut self, seconds: u64) {
+        self.pass_time(seconds);
+    }
+
+    #[pyo3(name = "farm_block")]
+    pub fn py_farm_block(&mut self, puzzle_hash: Bytes32) -> Vec<Coin> {
+        self.farm_block(puzzle_hash)
+    }
+
+    #[pyo3(name = "push_bundle")]
+    pub fn py_push_bundle(&mut self, py: Python<'_>, spend_bundle: &SpendBundle) -> PyResult<u64> {
+        Ok(py.allow_threads(|| self.push_bundle(spend_bundle))?)
+    }
+
+    #[pyo3(name = "coin_state")]
+    pub fn py_coin_state(&self, coin_id: Bytes32) -> Option<CoinState> {
+        self.coin_state(coin_id)
+    }
+
+    #[pyo3(name = "unspent_coins")]
+    pub fn py_unspent_coins(&self, puzzle_hash: Bytes32) -> Vec<Coin> {
+        self.unspent_coins(puzzle_hash)
+    }
+
+    #[pyo3(name = "rewind")]
+    pub fn py_rewind(&mut self, height: u32) {
+        self.rewind(height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus_constants::TEST_CONSTANTS;
+    use crate::gen::opcodes::{ConditionOpcode, ASSERT_HEIGHT_RELATIVE, CREATE_COIN};
+    use chia_bls::Signature;
+    use chia_protocol::{CoinSpend, Program};
+    use clvm_utils::ONE_TREE_HASH;
+    use clvmr::serde::node_to_bytes;
+    use clvmr::{Allocator, NodePtr};
+    use rstest::rstest;
+
+    // the puzzle "1" returns its solution as the conditions
+    fn puzzle_hash() -> Bytes32 {
+        ONE_TREE_HASH.into()
+    }
+
+    fn spend(coin: Coin, conditions: &[(ConditionOpcode, Bytes32, u64)]) -> SpendBundle {
+        let mut a = Allocator::new();
+        let mut solution = NodePtr::NIL;
+        for (opcode, puzzle_hash, amount) in conditions.iter().rev() {
+            let amount = a.new_number((*amount).into()).unwrap();
+            let args = a.new_pair(amount, NodePtr::NIL).unwrap();
+            let args = if *opcode == CREATE_COIN {
+                let puzzle_hash = a.new_atom(puzzle_hash).unwrap();
+                a.new_pair(puzzle_hash, args).unwrap()
+            } else {
+                args
+            };
+            let opcode = a.new_small_number(u16::from(*opcode).into()).unwrap();
+            let condition = a.new_pair(opcode, args).unwrap();
+            solution = a.new_pair(condition, solution).unwrap();
+        }
+        let coin_spend = CoinSpend::new(
+            coin,
+            Program::from(vec![1_u8]),
+            Program::from(node_to_bytes(&a, solution).unwrap()),
+        );
+        SpendBundle::new(vec![coin_spend], Signature::default())
+    }
+
+    #[test]
+    fn test_create_and_spend() {
+        let mut sim = Simulator::new(TEST_CONSTANTS);
+        assert_eq!(sim.height(), 0);
+
+        let rewards = sim.farm_block(puzzle_hash());
+        assert_eq!(sim.height(), 1);
+        assert_eq!(rewards.len(), 2);
+        assert_eq!(rewards[0].amount, 1_750_000_000_000);
+        assert_eq!(rewards[1].amount, 250_000_000_000);
+        assert_eq!(
+            sim.coin_state(rewards[0].coin_id()),
+            Some(CoinState::new(rewards[0], None, Some(1)))
+        );
+
+        let parent = rewards[0];
+        let bundle = spend(
+            parent,
+            &[
+                (CREATE_COIN, puzzle_hash(), 1000),
+                (CREATE_COIN, puzzle_hash(), parent.amount.get() - 1000),
+            ],
+        );
+        assert!(sim.push_bundle(&bundle).unwrap() > 0);
+        assert_eq!(sim.height(), 2);
+        assert_eq!(
+            sim.coin_state(parent.coin_id()),
+            Some(CoinState::new(parent, Some(2), Some(1)))
+        );
+
+        let child = Coin::new(parent.coin_id(), puzzle_hash(), Amount::new(1000));
+        assert_eq!(
+            sim.coin_state(child.coin_id()),
+            Some(CoinState::new(child, None, Some(2)))
+        );
+        assert!(sim.unspent_coins(puzzle_hash()).contains(&child));
+
+        // the same coin can't be spent twice
+        assert_eq!(sim.push_bundle(&bundle), Err(ErrorCode::DoubleSpend));
+        assert_eq!(sim.height(), 2);
+
+        let unknown = Coin::new(Bytes32::default(), puzzle_hash(), Amount::new(1));
+        assert_eq!(
+            sim.push_bundle(&spend(unknown, &[])),
+            Err(ErrorCode::UnknownUnspent)
+        );
+
+        // the child can be spent in the block after it was created
+        sim.push_bundle(&spend(child, &[])).unwrap();
+        assert_eq!(
+            sim.coin_state(child.coin_id()),
+            Some(CoinState::new(child, Some(3), Some(2)))
+        );
+    }
+
+    #[rstest]
+    #[case(0, true)]
+    #[case(1, true)]
+    #[case(2, false)]
+    fn test_height_relative(#[case] relative: u64, #[case] valid: bool) {
+        let mut sim = Simulator::new(TEST_CONSTANTS);
+        let coin = sim.farm_block(puzzle_hash())[0];
+        sim.farm_block(puzzle_hash());
+
+        let bundle = spend(
+            coin,
+            &[(ASSERT_HEIGHT_RELATIVE, Bytes32::default(), relative)],
+        );
+        if valid {
+            sim.push_bundle(&bundle).unwrap();
+        } else {
+            assert_eq!(
+                sim.push_bundle(&bundle),
+                Err(ErrorCode::AssertHeightRelativeFailed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_rewind() {
+        let mut sim = Simulator::new(TEST_CONSTANTS);
+        let coin = sim.farm_block(puzzle_hash())[0];
+        sim.pass_time(100);
+        let later = sim.farm_block(puzzle_hash())[0];
+        let child = Coin::new(coin.coin_id(), puzzle_hash(), coin.amount);
+        sim.push_bundle(&spend(
+            coin,
+            &[(CREATE_COIN, puzzle_hash(), coin.amount.get())],
+        ))
+        .unwrap();
+        assert_eq!(sim.height(), 3);
+        assert_eq!(sim.timestamp(), GENESIS_TIMESTAMP + 100);
+
+        sim.rewind(1);
+        assert_eq!(sim.height(), 1);
+        assert_eq!(sim.timestamp(), GENESIS_TIMESTAMP);
+        assert_eq!(
+            sim.coin_state(coin.coin_id()),
+            Some(CoinState::new(coin, None, Some(1)))
+        );
+        assert_eq!(sim.coin_state(later.coin_id()), None);
+        assert_eq!(sim.coin_state(child.coin_id()), None);
+
+        // rewinding forward does nothing
+        sim.rewind(5);
+        assert_eq!(sim.height(), 1);
+
+        // the chain is deterministic, so farming the same blocks again
+        // creates the same coins
+        sim.pass_time(100);
+        assert_eq!(sim.farm_block(puzzle_hash())[0], later);
+    }
+}
@@ -1,14 +1,16 @@
+use crate::allocator::{checkpoint, restore};
 use crate::consensus_constants::ConsensusConstants;
 use crate::gen::conditions::{
     process_single_spend, validate_conditions, MempoolVisitor, ParseState, SpendBundleConditions,
 };
 use crate::gen::flags::{DONT_VALIDATE_SIGNATURE, MEMPOOL_MODE};
+use crate::gen::owned_conditions::OwnedSpendBundleConditions;
 use crate::gen::run_block_generator::subtract_cost;
-use crate::gen::solution_generator::calculate_generator_length;
+use crate::gen::solution_generator::calculate_spends_length;
 use crate::gen::validation_error::ValidationErr;
 use crate::spendbundle_validation::get_flags_for_height_and_constants;
 use chia_bls::PublicKey;
-use chia_protocol::{Bytes, SpendBundle};
+use chia_protocol::{Bytes, Coin, SpendBundle};
 use clvm_utils::tree_hash;
 use clvmr::allocator::Allocator;
 use clvmr::chia_dialect::ChiaDialect;
@@ -36,6 +38,27 @@ pub fn get_conditions_from_spendbundle(
     .0)
 }
 
+// Like get_conditions_from_spendbundle(), but everything allocated while
+// running the spend bundle is discarded before returning, leaving the
+// allocator the way it was. This lets the mempool speculatively run a spend
+// bundle (e.g. after fast-forwarding it) in an allocator that already has
+// shared structures loaded. The conditions would refer to the discarded
+// nodes, so they are returned as OwnedSpendBundleConditions, and the node in
+// an error is replaced by nil.
+pub fn get_conditions_from_spendbundle_speculative(
+    a: &mut Allocator,
+    spend_bundle: &SpendBundle,
+    max_cost: u64,
+    height: u32,
+    constants: &ConsensusConstants,
+) -> Result<OwnedSpendBundleConditions, ValidationErr> {
+    let cp = checkpoint(a);
+    let ret = get_conditions_from_spendbundle(a, spend_bundle, max_cost, height, constants)
+        .map(|conditions| OwnedSpendBundleConditions::from(a, conditions));
+    restore(a, &cp);
+    ret.map_err(|ValidationErr(_, code)| ValidationErr(a.nil(), code))
+}
+
 // returns the conditions for the spendbundle, along with the (public key,
 // message) pairs emitted by the spends (for validating the aggregate signature)
 #[allow(clippy::type_complexity)]
@@ -46,6 +69,26 @@ pub fn run_spendbundle(
     height: u32,
     flags: u32,
     constants: &ConsensusConstants,
+) -> Result<(SpendBundleConditions, Vec<(PublicKey, Bytes)>), ValidationErr> {
+    let spends: Vec<(Coin, &[u8], &[u8])> = spend_bundle
+        .coin_spends
+        .iter()
+        .map(|cs| (cs.coin, cs.puzzle_reveal.as_slice(), cs.solution.as_slice()))
+        .collect();
+    run_spends(a, &spends, max_cost, height, flags, constants)
+}
+
+// like run_spendbundle(), but the spends are (Coin, puzzle-reveal, solution)
+// tuples. This allows running the spends of a serialized spend bundle without
+// copying its puzzles and solutions, see SpendBundle::parse_borrowed()
+#[allow(clippy::type_complexity)]
+pub fn run_spends<BufRef: AsRef<[u8]>>(
+    a: &mut Allocator,
+    spends: &[(Coin, BufRef, BufRef)],
+    max_cost: u64,
+    height: u32,
+    flags: u32,
+    constants: &ConsensusConstants,
 ) -> Result<(SpendBundleConditions, Vec<(PublicKey, Bytes)>), ValidationErr> {
     let flags = get_flags_for_height_and_constants(height, constants) | flags | MEMPOOL_MODE;
 
@@ -57,18 +100,21 @@ pub fn run_spendbundle(
     let mut state = ParseState::default();
     // We don't pay the size cost (nor execution cost) of being wrapped by a
     // quote (in solution_generator).
-    let generator_length_without_quote =
-        calculate_generator_length(&spend_bundle.coin_spends) - QUOTE_BYTES;
+    let generator_length_without_quote = calculate_spends_length(
+        spends
+            .iter()
+            .map(|(coin, puzzle, solution)| (*coin, puzzle.as_ref(), solution.as_ref())),
+    ) - QUOTE_BYTES;
 
     let byte_cost = generator_length_without_quote as u64 * constants.cost_per_byte;
     subtract_cost(a, &mut cost_left, byte_cost)?;
 
-    for coin_spend in &spend_bundle.coin_spends {
+    for (coin, puzzle, solution) in spends {
         // process the spend
-        let puz = node_from_bytes(a, coin_spend.puzzle_reveal.as_slice())?;
-        let sol = node_from_bytes(a, coin_spend.solution.as_slice())?;
-        let parent = a.new_atom(coin_spend.coin.parent_coin_info.as_slice())?;
-        let amount = a.new_number(coin_spend.coin.amount.into())?;
+        let puz = node_from_bytes(a, puzzle.as_ref())?;
+        let sol = node_from_bytes(a, solution.as_ref())?;
+        let parent = a.new_atom(coin.parent_coin_info.as_slice())?;
+        let amount = a.new_number(u64::from(coin.amount).into())?;
         let Reduction(clvm_cost, conditions) = run_program(a, &dialect, puz, sol, cost_left)?;
 
         subtract_cost(a, &mut cost_left, clvm_cost)?;
@@ -197,6 +243,118 @@ mod tests {
         assert_eq!(conditions.cost, cost);
     }
 
+    // the puzzle is the identity function, so the solution is the list of
+    // conditions. Like the MempoolVisitor tests in conditions.rs, the
+    // output has the same puzzle hash and amount as the spent coin
+    #[rstest]
+    #[case::plain(1000, false, true, false)]
+    #[case::agg_sig_me(1001, true, false, false)]
+    #[case::fast_forward(1001, false, true, true)]
+    fn test_eligibility_flags(
+        #[case] amount: u64,
+        #[case] agg_sig_me: bool,
+        #[case] dedup: bool,
+        #[case] fast_forward: bool,
+    ) {
+        use chia_bls::SecretKey;
+        use chia_protocol::{Bytes32, Coin, Program};
+        use clvm_traits::ToClvm;
+        use clvmr::serde::node_to_bytes;
+
+        let mut a = make_allocator(LIMIT_HEAP);
+        let puzzle_hash = Bytes32::from(tree_hash(&a, a.one()));
+        let mut conditions = vec![(51, (puzzle_hash, (amount, ())))
+            .to_clvm(&mut a)
+            .expect("to_clvm")];
+        if agg_sig_me {
+            let pk = SecretKey::from_seed(&[1; 32]).public_key();
+            let pk = Bytes::from(pk.to_bytes().to_vec());
+            let msg = Bytes::from(b"hello".to_vec());
+            conditions.push((50, (pk, (msg, ()))).to_clvm(&mut a).expect("to_clvm"));
+        }
+        let solution = conditions.to_clvm(&mut a).expect("to_clvm");
+
+        let coin = Coin::new(Bytes32::new([1; 32]), puzzle_hash, amount.into());
+        let spend = CoinSpend::new(
+            coin,
+            Program::from(vec![1_u8]),
+            node_to_bytes(&a, solution).expect("node_to_bytes").into(),
+        );
+        let bundle = SpendBundle::new(vec![spend], Signature::default());
+        let conditions =
+            get_conditions_from_spendbundle(&mut a, &bundle, 11_000_000_000, 0, &TEST_CONSTANTS)
+                .expect("get_conditions_from_spendbundle");
+
+        // the flags survive the conversion to owned conditions
+        let conditions = OwnedSpendBundleConditions::from(&a, conditions);
+        assert_eq!(conditions.spends.len(), 1);
+        let spend = &conditions.spends[0];
+        assert_eq!(spend.eligible_for_dedup(), dedup);
+        assert_eq!(spend.eligible_for_fast_forward(), fast_forward);
+        assert_eq!((spend.flags & ELIGIBLE_FOR_DEDUP) != 0, dedup);
+        assert_eq!((spend.flags & ELIGIBLE_FOR_FF) != 0, fast_forward);
+    }
+
+    #[rstest]
+    #[case("3000253", 51_216_870)]
+    #[case("1000101", 250_083_677)]
+    fn test_get_conditions_from_spendbundle_speculative(#[case] filename: &str, #[case] cost: u64) {
+        let bundle = SpendBundle::from_bytes(
+            &read(format!("../../test-bundles/{filename}.bundle")).expect("read file"),
+        )
+        .expect("parse bundle");
+
+        let mut a = make_allocator(LIMIT_HEAP);
+        let expected = {
+            let mut a = make_allocator(LIMIT_HEAP);
+            let conditions =
+                get_conditions_from_spendbundle(&mut a, &bundle, cost, 0, &TEST_CONSTANTS)
+                    .expect("get_conditions_from_spendbundle");
+            let mut conditions = OwnedSpendBundleConditions::from(&a, conditions);
+            // the order of the CREATE_COIN conditions isn't deterministic
+            conditions.normalize();
+            conditions
+        };
+
+        // a node created before the speculative run must survive it
+        let shared = a.new_atom(&[0x42; 100]).expect("new_atom");
+        let atoms = a.atom_count();
+        let pairs = a.pair_count();
+        let heap_size = a.heap_size();
+
+        for _ in 0..2 {
+            let mut conditions = get_conditions_from_spendbundle_speculative(
+                &mut a,
+                &bundle,
+                cost,
+                0,
+                &TEST_CONSTANTS,
+            )
+            .expect("get_conditions_from_spendbundle_speculative");
+            conditions.normalize();
+            assert_eq!(conditions, expected);
+
+            assert_eq!(a.atom_count(), atoms);
+            assert_eq!(a.pair_count(), pairs);
+            assert_eq!(a.heap_size(), heap_size);
+            assert_eq!(a.atom(shared).as_ref(), &[0x42; 100]);
+        }
+
+        // failures leave the allocator intact too
+        let err = get_conditions_from_spendbundle_speculative(
+            &mut a,
+            &bundle,
+            cost - 1,
+            0,
+            &TEST_CONSTANTS,
+        )
+        .unwrap_err();
+        assert_eq!(err.0, a.nil());
+        assert_eq!(a.atom_count(), atoms);
+        assert_eq!(a.pair_count(), pairs);
+        assert_eq!(a.heap_size(), heap_size);
+    }
+
     #[cfg(not(debug_assertions))]
     use crate::gen::flags::ALLOW_BACKREFS;
 
@@ -241,7 +399,7 @@ mod tests {
                 Coin::new(
                     a.atom(parent_id).as_ref().try_into().expect("parent_id"),
                     tree_hash(&a, puzzle).into(),
-                    a.number(amount).try_into().expect("amount"),
+                    u64::try_from(a.number(amount)).expect("amount").into(),
                 ),
                 node_to_bytes(&a, puzzle).expect("node_to_bytes").into(),
                 node_to_bytes(&a, solution).expect("node_to_bytes").into(),
@@ -1,12 +1,14 @@
 use crate::allocator::make_allocator;
 use crate::consensus_constants::ConsensusConstants;
 use crate::gen::flags::ALLOW_BACKREFS;
+use crate::gen::make_aggsig_final_message::make_aggsig_final_message_for_coin;
+use crate::gen::opcodes::ConditionOpcode;
 use crate::gen::owned_conditions::OwnedSpendBundleConditions;
 use crate::gen::validation_error::ErrorCode;
-use crate::spendbundle_conditions::run_spendbundle;
-use chia_bls::GTElement;
-use chia_bls::{aggregate_verify_gt, hash_to_g2};
-use chia_protocol::SpendBundle;
+use crate::spendbundle_conditions::run_spends;
+use chia_bls::{aggregate_verify, aggregate_verify_gt, hash_to_g2};
+use chia_bls::{BlsCache, GTElement, PublicKey, Signature};
+use chia_protocol::{Bytes, Coin, SpendBundle};
 use chia_sha2::Sha256;
 use clvmr::chia_dialect::ENABLE_KECCAK;
 use clvmr::LIMIT_HEAP;
@@ -23,11 +25,46 @@ pub fn validate_clvm_and_signature(
     max_cost: u64,
     constants: &ConsensusConstants,
     height: u32,
+) -> Result<(OwnedSpendBundleConditions, Vec<ValidationPair>, Duration), ErrorCode> {
+    let spends: Vec<(Coin, &[u8], &[u8])> = spend_bundle
+        .coin_spends
+        .iter()
+        .map(|cs| (cs.coin, cs.puzzle_reveal.as_slice(), cs.solution.as_slice()))
+        .collect();
+    validate_spends(
+        &spends,
+        &spend_bundle.aggregated_signature,
+        max_cost,
+        constants,
+        height,
+    )
+}
+
+// like validate_clvm_and_signature(), but takes a serialized spend bundle. The
+// puzzles and solutions are run straight from the buffer, without parsing it
+// into a SpendBundle (and copying them) first
+pub fn validate_serialized_clvm_and_signature(
+    spend_bundle: &[u8],
+    max_cost: u64,
+    constants: &ConsensusConstants,
+    height: u32,
+) -> Result<(OwnedSpendBundleConditions, Vec<ValidationPair>, Duration), ErrorCode> {
+    let (spends, signature) =
+        SpendBundle::parse_borrowed(spend_bundle).map_err(|_| ErrorCode::InvalidSpendBundle)?;
+    validate_spends(&spends, &signature, max_cost, constants, height)
+}
+
+fn validate_spends(
+    spends: &[(Coin, &[u8], &[u8])],
+    signature: &Signature,
+    max_cost: u64,
+    constants: &ConsensusConstants,
+    height: u32,
 ) -> Result<(OwnedSpendBundleConditions, Vec<ValidationPair>, Duration), ErrorCode> {
     let start_time = Instant::now();
     let mut a = make_allocator(LIMIT_HEAP);
     let (sbc, pkm_pairs) =
-        run_spendbundle(&mut a, spend_bundle, max_cost, height, 0, constants).map_err(|e| e.1)?;
+        run_spends(&mut a, spends, max_cost, height, 0, constants).map_err(|e| e.1)?;
     let conditions = OwnedSpendBundleConditions::from(&a, sbc);
 
     // Collect all pairs in a single vector to avoid multiple iterations
@@ -47,10 +84,7 @@ pub fn validate_clvm_and_signature(
         pairs.push((key.finalize(), pairing));
     }
     // Verify aggregated signature
-    let result = aggregate_verify_gt(
-        &spend_bundle.aggregated_signature,
-        pairs.iter().map(|tuple| &tuple.1),
-    );
+    let result = aggregate_verify_gt(signature, pairs.iter().map(|tuple| &tuple.1));
     if !result {
         return Err(ErrorCode::BadAggregateSignature);
     }
@@ -59,6 +93,45 @@ pub fn validate_clvm_and_signature(
     Ok((conditions, pairs, start_time.elapsed()))
 }
 
+/// A public key and message that a spend bundle is expected to have signed,
+/// along with the AGG_SIG_* condition that requires the signature and the coin
+/// whose spend created that condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignaturePair {
+    pub public_key: PublicKey,
+    pub message: Bytes,
+    pub opcode: ConditionOpcode,
+    pub coin: Coin,
+}
+
+/// Verifies the aggregated signature of a spend bundle against signature pairs
+/// that are already known, e.g. because the wallet built the spend bundle
+/// itself. Unlike validate_clvm_and_signature(), no puzzles are run. The final
+/// message of each pair is computed from its opcode, coin and the consensus
+/// constants. Pairings are looked up in, and added to, the cache if one is
+/// passed in.
+pub fn verify_aggregate_signature(
+    spend_bundle: &SpendBundle,
+    pairs: &[SignaturePair],
+    constants: &ConsensusConstants,
+    cache: Option<&BlsCache>,
+) -> bool {
+    if !pairs.iter().all(|pair| pair.opcode.is_agg_sig()) {
+        return false;
+    }
+
+    let pks_msgs = pairs.iter().map(|pair| {
+        let mut msg = pair.message.to_vec();
+        make_aggsig_final_message_for_coin(pair.opcode, &mut msg, &pair.coin, constants);
+        (&pair.public_key, msg)
+    });
+
+    match cache {
+        Some(cache) => cache.aggregate_verify(pks_msgs, &spend_bundle.aggregated_signature),
+        None => aggregate_verify(&spend_bundle.aggregated_signature, pks_msgs),
+    }
+}
+
 pub fn get_flags_for_height_and_constants(height: u32, constants: &ConsensusConstants) -> u32 {
     let mut flags: u32 = 0;
 
@@ -95,9 +168,14 @@ mod tests {
     use super::*;
     use crate::consensus_constants::TEST_CONSTANTS;
     use crate::gen::make_aggsig_final_message::u64_to_bytes;
+    use crate::gen::opcodes::{
+        AGG_SIG_AMOUNT, AGG_SIG_ME, AGG_SIG_PARENT, AGG_SIG_PARENT_AMOUNT, AGG_SIG_PARENT_PUZZLE,
+        AGG_SIG_PUZZLE, AGG_SIG_PUZZLE_AMOUNT, AGG_SIG_UNSAFE, CREATE_COIN,
+    };
     use chia_bls::{sign, G2Element, SecretKey, Signature};
+    use chia_protocol::{Amount, Coin, CoinSpend, Program};
     use chia_protocol::{Bytes, Bytes32};
-    use chia_protocol::{Coin, CoinSpend, Program};
+    use chia_traits::Streamable;
     use clvm_utils::tree_hash_atom;
     use hex::FromHex;
     use hex_literal::hex;
@@ -119,7 +197,7 @@ mod tests {
         let test_coin = Coin::new(
             hex!("4444444444444444444444444444444444444444444444444444444444444444").into(),
             hex!("3333333333333333333333333333333333333333333333333333333333333333").into(),
-            1,
+            Amount::new(1),
         );
 
         let solution = Bytes::new(
@@ -156,7 +234,7 @@ ff01\
         let test_coin = Coin::new(
             hex!("4444444444444444444444444444444444444444444444444444444444444444").into(),
             hex!("3333333333333333333333333333333333333333333333333333333333333333").into(),
-            1,
+            Amount::new(1),
         );
 
         let solution = hex!("ffff31ffb0997cc43ed8788f841fcf3071f6f212b89ba494b6ebaf1bda88c3f9de9d968a61f3b7284a5ee13889399ca71a026549a2ff8568656c6c6f8080").to_vec();
@@ -179,13 +257,40 @@ ff01\
         .expect("SpendBundle should be valid for this test");
     }
 
+    #[rstest]
+    #[case("3000253")]
+    #[case("1000101")]
+    fn test_validate_serialized(#[case] filename: &str) {
+        let buf =
+            std::fs::read(format!("../../test-bundles/{filename}.bundle")).expect("read file");
+        let spend_bundle = SpendBundle::from_bytes(&buf).expect("parse bundle");
+        let max_cost = TEST_CONSTANTS.max_block_cost_clvm;
+
+        let expected = validate_clvm_and_signature(&spend_bundle, max_cost, &TEST_CONSTANTS, 236)
+            .map(|(conditions, pairs, _duration)| (conditions, pairs));
+        let result = validate_serialized_clvm_and_signature(&buf, max_cost, &TEST_CONSTANTS, 236)
+            .map(|(conditions, pairs, _duration)| (conditions, pairs));
+        assert_eq!(result, expected);
+
+        assert_eq!(
+            validate_serialized_clvm_and_signature(
+                &buf[..buf.len() - 1],
+                max_cost,
+                &TEST_CONSTANTS,
+                236
+            )
+            .unwrap_err(),
+            ErrorCode::InvalidSpendBundle
+        );
+    }
+
     #[test]
     fn test_go_over_cost() {
         use std::fs::read_to_string;
         let test_coin = Coin::new(
             hex!("9dcf97a184f32623d11a73124ceb99a5709b083721e878a16d78f596718ba7b2").into(),
             hex!("9dcf97a184f32623d11a73124ceb99a5709b083721e878a16d78f596718ba7b2").into(),
-            1_000_000_000,
+            Amount::new(1_000_000_000),
         );
         let my_str =
             read_to_string("../../generator-tests/large_spendbundle_validation_test.clsp.hex")
@@ -222,7 +327,7 @@ ff01\
         let test_coin = Coin::new(
             hex!("4444444444444444444444444444444444444444444444444444444444444444").into(),
             full_puz,
-            1,
+            Amount::new(1),
         );
 
         let solution = hex!("ffff32ffb0997cc43ed8788f841fcf3071f6f212b89ba494b6ebaf1bda88c3f9de9d968a61f3b7284a5ee13889399ca71a026549a2ff8568656c6c6f8080").to_vec();
@@ -263,7 +368,7 @@ ff01\
         let test_coin = Coin::new(
             hex!("4444444444444444444444444444444444444444444444444444444444444444").into(),
             full_puz,
-            1,
+            Amount::new(1),
         );
 
         let solution = hex!("ffff30ffb0997cc43ed8788f841fcf3071f6f212b89ba494b6ebaf1bda88c3f9de9d968a61f3b7284a5ee13889399ca71a026549a2ff8568656c6c6f8080").to_vec();
@@ -310,7 +415,7 @@ ff01\
         let test_coin = Coin::new(
             hex!("4444444444444444444444444444444444444444444444444444444444444444").into(),
             full_puz,
-            1,
+            Amount::new(1),
         );
 
         let solution = hex!("ffff2fffb0997cc43ed8788f841fcf3071f6f212b89ba494b6ebaf1bda88c3f9de9d968a61f3b7284a5ee13889399ca71a026549a2ff8568656c6c6f8080").to_vec();
@@ -322,7 +427,7 @@ ff01\
         result.extend(
             [
                 test_coin.parent_coin_info.as_slice(),
-                u64_to_bytes(test_coin.amount).as_slice(),
+                u64_to_bytes(test_coin.amount.into()).as_slice(),
                 TEST_CONSTANTS
                     .agg_sig_parent_amount_additional_data
                     .as_slice(),
@@ -353,7 +458,7 @@ ff01\
         let test_coin = Coin::new(
             hex!("4444444444444444444444444444444444444444444444444444444444444444").into(),
             full_puz,
-            1,
+            Amount::new(1),
         );
 
         let solution = hex!("ffff2effb0997cc43ed8788f841fcf3071f6f212b89ba494b6ebaf1bda88c3f9de9d968a61f3b7284a5ee13889399ca71a026549a2ff8568656c6c6f8080").to_vec();
@@ -365,7 +470,7 @@ ff01\
         result.extend(
             [
                 test_coin.puzzle_hash.as_slice(),
-                u64_to_bytes(test_coin.amount).as_slice(),
+                u64_to_bytes(test_coin.amount.into()).as_slice(),
                 TEST_CONSTANTS
                     .agg_sig_puzzle_amount_additional_data
                     .as_slice(),
@@ -410,7 +515,7 @@ ff01\
         let test_coin = Coin::new(
             hex!("4444444444444444444444444444444444444444444444444444444444444444").into(),
             full_puz,
-            1,
+            Amount::new(1),
         );
         let spend = CoinSpend::new(test_coin, Program::new(vec![1_u8].into()), sol_bytes.into());
         let mut result = msg.to_vec();
@@ -419,7 +524,7 @@ ff01\
                 result.extend(
                     [
                         test_coin.puzzle_hash.as_slice(),
-                        u64_to_bytes(test_coin.amount).as_slice(),
+                        u64_to_bytes(test_coin.amount.into()).as_slice(),
                         TEST_CONSTANTS
                             .agg_sig_puzzle_amount_additional_data
                             .as_slice(),
@@ -431,7 +536,7 @@ ff01\
                 result.extend(
                     [
                         test_coin.parent_coin_info.as_slice(),
-                        u64_to_bytes(test_coin.amount).as_slice(),
+                        u64_to_bytes(test_coin.amount.into()).as_slice(),
                         TEST_CONSTANTS
                             .agg_sig_parent_amount_additional_data
                             .as_slice(),
@@ -478,4 +583,159 @@ ff01\
         );
         assert!(matches!(result, Err(ErrorCode::BadAggregateSignature)));
     }
+
+    #[rstest]
+    fn test_verify_aggregate_signature(
+        #[values(
+            AGG_SIG_UNSAFE,
+            AGG_SIG_ME,
+            AGG_SIG_PARENT,
+            AGG_SIG_PUZZLE,
+            AGG_SIG_AMOUNT,
+            AGG_SIG_PUZZLE_AMOUNT,
+            AGG_SIG_PARENT_AMOUNT,
+            AGG_SIG_PARENT_PUZZLE
+        )]
+        opcode: ConditionOpcode,
+        #[values(false, true)] use_cache: bool,
+    ) {
+        let sk = SecretKey::from_seed(&[7; 32]);
+        let coin = Coin::new(
+            Bytes32::new([4; 32]),
+            Bytes32::new([3; 32]),
+            Amount::new(0x8000),
+        );
+        let message = Bytes::new(b"hello".to_vec());
+
+        let mut final_message = message.to_vec();
+        make_aggsig_final_message_for_coin(opcode, &mut final_message, &coin, &TEST_CONSTANTS);
+        let spend_bundle = SpendBundle::new(vec![], sign(&sk, &final_message));
+
+        let cache = BlsCache::default();
+        let cache = use_cache.then_some(&cache);
+
+        let pair = SignaturePair {
+            public_key: sk.public_key(),
+            message: message.clone(),
+            opcode,
+            coin,
+        };
+        assert!(verify_aggregate_signature(
+            &spend_bundle,
+            &[pair.clone()],
+            &TEST_CONSTANTS,
+            cache
+        ));
+
+        // the signature doesn't match any other opcode
+        for other in [
+            AGG_SIG_UNSAFE,
+            AGG_SIG_ME,
+            AGG_SIG_PARENT,
+            AGG_SIG_PUZZLE_AMOUNT,
+        ] {
+            if other == opcode {
+                continue;
+            }
+            let pair = SignaturePair {
+                opcode: other,
+                ..pair.clone()
+            };
+            assert!(!verify_aggregate_signature(
+                &spend_bundle,
+                &[pair],
+                &TEST_CONSTANTS,
+                cache
+            ));
+        }
+
+        // nor a different coin, unless the message doesn't depend on it
+        let pair = SignaturePair {
+            coin: Coin::new(coin.parent_coin_info, coin.puzzle_hash, Amount::new(1)),
+            ..pair.clone()
+        };
+        assert_eq!(
+            verify_aggregate_signature(&spend_bundle, &[pair], &TEST_CONSTANTS, cache),
+            matches!(
+                opcode,
+                AGG_SIG_UNSAFE | AGG_SIG_PARENT | AGG_SIG_PUZZLE | AGG_SIG_PARENT_PUZZLE
+            )
+        );
+    }
+
+    #[test]
+    fn test_verify_aggregate_signature_multiple() {
+        let sk1 = SecretKey::from_seed(&[1; 32]);
+        let sk2 = SecretKey::from_seed(&[2; 32]);
+        let coin1 = Coin::new(Bytes32::new([1; 32]), Bytes32::new([2; 32]), Amount::new(1));
+        let coin2 = Coin::new(Bytes32::new([3; 32]), Bytes32::new([4; 32]), Amount::new(2));
+
+        let pairs = [
+            SignaturePair {
+                public_key: sk1.public_key(),
+                message: Bytes::new(b"foo".to_vec()),
+                opcode: AGG_SIG_ME,
+                coin: coin1,
+            },
+            SignaturePair {
+                public_key: sk2.public_key(),
+                message: Bytes::new(b"bar".to_vec()),
+                opcode: AGG_SIG_PUZZLE_AMOUNT,
+                coin: coin2,
+            },
+        ];
+        let mut sig = Signature::default();
+        for (sk, pair) in [&sk1, &sk2].into_iter().zip(&pairs) {
+            let mut msg = pair.message.to_vec();
+            make_aggsig_final_message_for_coin(pair.opcode, &mut msg, &pair.coin, &TEST_CONSTANTS);
+            sig.aggregate(&sign(sk, &msg));
+        }
+        let spend_bundle = SpendBundle::new(vec![], sig);
+        assert!(verify_aggregate_signature(
+            &spend_bundle,
+            &pairs,
+            &TEST_CONSTANTS,
+            None
+        ));
+        assert!(!verify_aggregate_signature(
+            &spend_bundle,
+            &pairs[..1],
+            &TEST_CONSTANTS,
+            None
+        ));
+
+        // no pairs means the signature must be the identity
+        let empty = SpendBundle::new(vec![], Signature::default());
+        assert!(verify_aggregate_signature(
+            &empty,
+            &[],
+            &TEST_CONSTANTS,
+            None
+        ));
+        assert!(!verify_aggregate_signature(
+            &spend_bundle,
+            &[],
+            &TEST_CONSTANTS,
+            None
+        ));
+    }
+
+    #[test]
+    fn test_verify_aggregate_signature_not_agg_sig() {
+        let sk = SecretKey::from_seed(&[7; 32]);
+        let message = Bytes::new(b"hello".to_vec());
+        let spend_bundle = SpendBundle::new(vec![], sign(&sk, &message));
+        let pair = SignaturePair {
+            public_key: sk.public_key(),
+            message,
+            opcode: CREATE_COIN,
+            coin: Coin::new(Bytes32::default(), Bytes32::default(), Amount::ZERO),
+        };
+        assert!(!verify_aggregate_signature(
+            &spend_bundle,
+            &[pair],
+            &TEST_CONSTANTS,
+            None
+        ));
+    }
 }
@@ -0,0 +1,321 @@
+//! The structural checks of end-of-sub-slot bundles, ported from the finished
+//! sub-slot validation in `chia.consensus.block_header_validation`. VDF proofs
+//! are not verified here, see `verify_sub_slot_vdfs()`.
+
+use crate::consensus_constants::ConsensusConstants;
+use crate::error::{Error, Result};
+use chia_protocol::{Bytes32, EndOfSubSlotBundle, VDFInfo, VDFProof};
+use chia_traits::Streamable;
+
+/// Validates that an end-of-sub-slot bundle follows the sub-slot with the
+/// challenge `prev_challenge` (the genesis challenge for the first sub-slot),
+/// and that its challenge, infused challenge and reward chains are consistent
+/// with each other. Returns the challenge of the next sub-slot, which is the
+/// hash of the challenge chain sub-slot.
+///
+/// `expected_deficit` is the deficit the reward chain must have at the end of
+/// this sub-slot. It depends on the previous blocks, so it's up to the caller,
+/// as are the challenges of the reward and infused challenge chain VDFs.
+pub fn validate_sub_slot_chain(
+    prev_challenge: &Bytes32,
+    bundle: &EndOfSubSlotBundle,
+    constants: &ConsensusConstants,
+    expected_deficit: u8,
+) -> Result<Bytes32> {
+    let cc = &bundle.challenge_chain;
+    let rc = &bundle.reward_chain;
+
+    let challenge = cc.challenge_chain_end_of_slot_vdf.challenge;
+    if challenge != *prev_challenge {
+        return Err(Error::SubSlotChallengeMismatch {
+            expected: *prev_challenge,
+            found: challenge,
+        });
+    }
+
+    if rc.deficit != expected_deficit {
+        return Err(Error::InvalidDeficit {
+            expected: expected_deficit,
+            found: rc.deficit,
+        });
+    }
+
+    let cc_hash = Bytes32::new(cc.hash());
+    if rc.challenge_chain_sub_slot_hash != cc_hash {
+        return Err(Error::InvalidChallengeSlotHashRc);
+    }
+
+    let icc_hash = bundle
+        .infused_challenge_chain
+        .as_ref()
+        .map(|icc| Bytes32::new(icc.hash()));
+    if rc.infused_challenge_chain_sub_slot_hash != icc_hash {
+        return Err(Error::InvalidIccHashRc);
+    }
+
+    // the challenge chain only commits to the infused challenge chain at the
+    // end of the sub-slot where the deficit is reset
+    let expected_cc_icc_hash = if rc.deficit == constants.min_blocks_per_challenge_block {
+        icc_hash
+    } else {
+        None
+    };
+    if cc.infused_challenge_chain_sub_slot_hash != expected_cc_icc_hash {
+        return Err(Error::InvalidIccHashCc);
+    }
+
+    Ok(cc_hash)
+}
+
+/// Passes each end-of-sub-slot VDF of the bundle, along with its proof, to
+/// `verify`, which does the actual VDF verification. Fails if any of them
+/// doesn't verify, or if the infused challenge chain has a VDF but no proof
+/// (or the other way around).
+pub fn verify_sub_slot_vdfs<F>(bundle: &EndOfSubSlotBundle, mut verify: F) -> Result<()>
+where
+    F: FnMut(&VDFInfo, &VDFProof) -> bool,
+{
+    let proofs = &bundle.proofs;
+
+    if !verify(
+        &bundle.challenge_chain.challenge_chain_end_of_slot_vdf,
+        &proofs.challenge_chain_slot_proof,
+    ) {
+        return Err(Error::InvalidSubSlotVdf("challenge chain"));
+    }
+
+    match (
+        &bundle.infused_challenge_chain,
+        &proofs.infused_challenge_chain_slot_proof,
+    ) {
+        (Some(icc), Some(proof)) => {
+            if !verify(&icc.infused_challenge_chain_end_of_slot_vdf, proof) {
+                return Err(Error::InvalidSubSlotVdf("infused challenge chain"));
+            }
+        }
+        (None, None) => {}
+        _ => return Err(Error::InvalidSubSlotVdf("infused challenge chain")),
+    }
+
+    if !verify(
+        &bundle.reward_chain.end_of_slot_vdf,
+        &proofs.reward_chain_slot_proof,
+    ) {
+        return Err(Error::InvalidSubSlotVdf("reward chain"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus_constants::TEST_CONSTANTS;
+    use chia_protocol::{
+        Bytes, ChallengeChainSubSlot, ClassgroupElement, InfusedChallengeChainSubSlot,
+        RewardChainSubSlot, SubSlotProofs,
+    };
+    use hex_literal::hex;
+    use rstest::rstest;
+
+    const MIN_DEFICIT: u8 = TEST_CONSTANTS.min_blocks_per_challenge_block;
+
+    fn vdf(challenge: Bytes32) -> VDFInfo {
+        VDFInfo::new(challenge, 1000, ClassgroupElement::default())
+    }
+
+    fn proof(witness_type: u8) -> VDFProof {
+        VDFProof::new(witness_type, Bytes::new(vec![witness_type]), false)
+    }
+
+    // a consistent bundle following the sub-slot with `prev_challenge`
+    fn bundle(prev_challenge: Bytes32, deficit: u8, with_icc: bool) -> EndOfSubSlotBundle {
+        let icc = with_icc.then(|| InfusedChallengeChainSubSlot::new(vdf(Bytes32::new([2; 32]))));
+        let icc_hash = icc.as_ref().map(|icc| Bytes32::new(icc.hash()));
+        let cc = ChallengeChainSubSlot::new(
+            vdf(prev_challenge),
+            if deficit == MIN_DEFICIT {
+                icc_hash
+            } else {
+                None
+            },
+            None,
+            None,
+            None,
+        );
+        let rc = RewardChainSubSlot::new(
+            vdf(Bytes32::new([3; 32])),
+            Bytes32::new(cc.hash()),
+            icc_hash,
+            deficit,
+        );
+        let proofs = SubSlotProofs::new(proof(0), with_icc.then(|| proof(1)), proof(2));
+        EndOfSubSlotBundle::new(cc, icc, rc, proofs)
+    }
+
+    // the expected challenges are the sha256 of the serialized challenge chain
+    // sub-slots, each following the previous one, starting at the genesis
+    // challenge
+    #[test]
+    fn test_sub_slot_chain() {
+        let mut challenge = TEST_CONSTANTS.genesis_challenge;
+        for (deficit, with_icc, expected) in [
+            (
+                MIN_DEFICIT,
+                false,
+                hex!("5c88f4d66e9bc9ab2c2f3a003069ad916777159fd9a4d8308429b3e0dabef8b8"),
+            ),
+            (
+                MIN_DEFICIT,
+                true,
+                hex!("558ec422357e7539b167580d6f6874c5bf6ac7fb7ef8211331d11bd5ee84ed13"),
+            ),
+            (
+                3,
+                true,
+                hex!("d35cc14565dcb96228b31d72021214c56c18bf092f6f976cb210a9766d28851e"),
+            ),
+        ] {
+            let sub_slot = bundle(challenge, deficit, with_icc);
+            challenge =
+                validate_sub_slot_chain(&challenge, &sub_slot, &TEST_CONSTANTS, deficit).unwrap();
+            assert_eq!(challenge, Bytes32::new(expected));
+        }
+    }
+
+    #[test]
+    fn test_wrong_prev_challenge() {
+        let prev = TEST_CONSTANTS.genesis_challenge;
+        let sub_slot = bundle(prev, MIN_DEFICIT, false);
+        let other = Bytes32::new([1; 32]);
+        assert_eq!(
+            validate_sub_slot_chain(&other, &sub_slot, &TEST_CONSTANTS, MIN_DEFICIT),
+            Err(Error::SubSlotChallengeMismatch {
+                expected: other,
+                found: prev
+            })
+        );
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(MIN_DEFICIT)]
+    fn test_wrong_deficit(#[case] deficit: u8) {
+        let prev = TEST_CONSTANTS.genesis_challenge;
+        let sub_slot = bundle(prev, 4, true);
+        assert_eq!(
+            validate_sub_slot_chain(&prev, &sub_slot, &TEST_CONSTANTS, deficit),
+            Err(Error::InvalidDeficit {
+                expected: deficit,
+                found: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_cc_hash_mismatch() {
+        let prev = TEST_CONSTANTS.genesis_challenge;
+        let mut sub_slot = bundle(prev, MIN_DEFICIT, false);
+        sub_slot.challenge_chain.new_sub_slot_iters = Some(1024);
+        assert_eq!(
+            validate_sub_slot_chain(&prev, &sub_slot, &TEST_CONSTANTS, MIN_DEFICIT),
+            Err(Error::InvalidChallengeSlotHashRc)
+        );
+    }
+
+    #[rstest]
+    fn test_icc_hash_rc(#[values(0, MIN_DEFICIT)] deficit: u8) {
+        let prev = TEST_CONSTANTS.genesis_challenge;
+
+        // the reward chain commits to an infused challenge chain that's missing
+        let mut sub_slot = bundle(prev, deficit, true);
+        sub_slot.infused_challenge_chain = None;
+        assert_eq!(
+            validate_sub_slot_chain(&prev, &sub_slot, &TEST_CONSTANTS, deficit),
+            Err(Error::InvalidIccHashRc)
+        );
+
+        // the reward chain doesn't commit to the infused challenge chain
+        let mut sub_slot = bundle(prev, deficit, true);
+        sub_slot.reward_chain.infused_challenge_chain_sub_slot_hash = None;
+        sub_slot
+            .challenge_chain
+            .infused_challenge_chain_sub_slot_hash = None;
+        sub_slot.reward_chain.challenge_chain_sub_slot_hash =
+            Bytes32::new(sub_slot.challenge_chain.hash());
+        assert_eq!(
+            validate_sub_slot_chain(&prev, &sub_slot, &TEST_CONSTANTS, deficit),
+            Err(Error::InvalidIccHashRc)
+        );
+    }
+
+    #[rstest]
+    fn test_icc_hash_cc(#[values(0, MIN_DEFICIT)] deficit: u8) {
+        let prev = TEST_CONSTANTS.genesis_challenge;
+        let mut sub_slot = bundle(prev, deficit, true);
+
+        // the challenge chain must include the infused challenge chain hash
+        // only when the deficit is reset
+        sub_slot
+            .challenge_chain
+            .infused_challenge_chain_sub_slot_hash = if deficit == MIN_DEFICIT {
+            None
+        } else {
+            sub_slot.reward_chain.infused_challenge_chain_sub_slot_hash
+        };
+        sub_slot.reward_chain.challenge_chain_sub_slot_hash =
+            Bytes32::new(sub_slot.challenge_chain.hash());
+        assert_eq!(
+            validate_sub_slot_chain(&prev, &sub_slot, &TEST_CONSTANTS, deficit),
+            Err(Error::InvalidIccHashCc)
+        );
+    }
+
+    #[rstest]
+    fn test_verify_sub_slot_vdfs(#[values(false, true)] with_icc: bool) {
+        let sub_slot = bundle(TEST_CONSTANTS.genesis_challenge, MIN_DEFICIT, with_icc);
+
+        let mut verified = Vec::new();
+        verify_sub_slot_vdfs(&sub_slot, |info, proof| {
+            verified.push((info.challenge, proof.witness_type));
+            true
+        })
+        .unwrap();
+
+        let mut expected = vec![(TEST_CONSTANTS.genesis_challenge, 0)];
+        if with_icc {
+            expected.push((Bytes32::new([2; 32]), 1));
+        }
+        expected.push((Bytes32::new([3; 32]), 2));
+        assert_eq!(verified, expected);
+    }
+
+    #[rstest]
+    #[case(0, "challenge chain")]
+    #[case(1, "infused challenge chain")]
+    #[case(2, "reward chain")]
+    fn test_invalid_sub_slot_vdf(#[case] witness_type: u8, #[case] chain: &'static str) {
+        let sub_slot = bundle(TEST_CONSTANTS.genesis_challenge, MIN_DEFICIT, true);
+        assert_eq!(
+            verify_sub_slot_vdfs(&sub_slot, |_, proof| proof.witness_type != witness_type),
+            Err(Error::InvalidSubSlotVdf(chain))
+        );
+    }
+
+    #[test]
+    fn test_missing_icc_proof() {
+        let mut sub_slot = bundle(TEST_CONSTANTS.genesis_challenge, MIN_DEFICIT, true);
+        sub_slot.proofs.infused_challenge_chain_slot_proof = None;
+        assert_eq!(
+            verify_sub_slot_vdfs(&sub_slot, |_, _| true),
+            Err(Error::InvalidSubSlotVdf("infused challenge chain"))
+        );
+
+        let mut sub_slot = bundle(TEST_CONSTANTS.genesis_challenge, MIN_DEFICIT, false);
+        sub_slot.proofs.infused_challenge_chain_slot_proof = Some(proof(1));
+        assert_eq!(
+            verify_sub_slot_vdfs(&sub_slot, |_, _| true),
+            Err(Error::InvalidSubSlotVdf("infused challenge chain"))
+        );
+    }
+}
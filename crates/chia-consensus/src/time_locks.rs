@@ -0,0 +1,216 @@
+use crate::gen::owned_conditions::{OwnedSpendBundleConditions, OwnedSpendConditions};
+use crate::gen::validation_error::ErrorCode;
+use chia_protocol::Bytes32;
+use std::collections::HashMap;
+
+/// Checks whether the time locks of a validated spend bundle (or block) are
+/// satisfied at `block_height` and `block_timestamp`, and returns the first
+/// one that isn't. This mirrors `check_time_locks()` in chia-blockchain.
+///
+/// `coin_confirm_info` maps the ID of each spent coin to the height and
+/// timestamp of the block it was created in. A coin that's missing from it is
+/// assumed to be created in the same block (i.e. ephemeral), and consensus
+/// doesn't allow relative conditions, including `ASSERT_MY_BIRTH_*`, on those.
+///
+/// The `ASSERT_HEIGHT_*` and `ASSERT_SECONDS_*` conditions are inclusive: they
+/// are satisfied from the specified height or time onwards. The
+/// `ASSERT_BEFORE_*` conditions are exclusive: they are satisfied strictly
+/// before the specified height or time. Relative heights and times that
+/// overflow are never reached.
+pub fn satisfiable_at(
+    conds: &OwnedSpendBundleConditions,
+    block_height: u32,
+    block_timestamp: u64,
+    coin_confirm_info: &HashMap<Bytes32, (u32, u64)>,
+) -> Result<(), ErrorCode> {
+    if block_height < conds.height_absolute {
+        return Err(ErrorCode::AssertHeightAbsoluteFailed);
+    }
+    if block_timestamp < conds.seconds_absolute {
+        return Err(ErrorCode::AssertSecondsAbsoluteFailed);
+    }
+    if conds
+        .before_height_absolute
+        .is_some_and(|before| block_height >= before)
+    {
+        return Err(ErrorCode::AssertBeforeHeightAbsoluteFailed);
+    }
+    if conds
+        .before_seconds_absolute
+        .is_some_and(|before| block_timestamp >= before)
+    {
+        return Err(ErrorCode::AssertBeforeSecondsAbsoluteFailed);
+    }
+
+    for spend in &conds.spends {
+        let Some(&(confirmed_height, confirmed_timestamp)) = coin_confirm_info.get(&spend.coin_id)
+        else {
+            if has_relative_conditions(spend) {
+                return Err(ErrorCode::EphemeralRelativeCondition);
+            }
+            continue;
+        };
+
+        if spend
+            .birth_height
+            .is_some_and(|birth| birth != confirmed_height)
+        {
+            return Err(ErrorCode::AssertMyBirthHeightFailed);
+        }
+        if spend
+            .birth_seconds
+            .is_some_and(|birth| birth != confirmed_timestamp)
+        {
+            return Err(ErrorCode::AssertMyBirthSecondsFailed);
+        }
+
+        // the sum of two u32 always fits in a u64
+        let height = u64::from(block_height);
+        let relative_height = |h: u32| u64::from(confirmed_height) + u64::from(h);
+        // None means the time is never reached
+        let relative_seconds = |s: u64| confirmed_timestamp.checked_add(s);
+
+        if spend
+            .height_relative
+            .is_some_and(|h| height < relative_height(h))
+        {
+            return Err(ErrorCode::AssertHeightRelativeFailed);
+        }
+        if spend
+            .seconds_relative
+            .is_some_and(|s| relative_seconds(s).is_none_or(|t| block_timestamp < t))
+        {
+            return Err(ErrorCode::AssertSecondsRelativeFailed);
+        }
+        if spend
+            .before_height_relative
+            .is_some_and(|h| height >= relative_height(h))
+        {
+            return Err(ErrorCode::AssertBeforeHeightRelativeFailed);
+        }
+        if spend
+            .before_seconds_relative
+            .is_some_and(|s| relative_seconds(s).is_some_and(|t| block_timestamp >= t))
+        {
+            return Err(ErrorCode::AssertBeforeSecondsRelativeFailed);
+        }
+    }
+    Ok(())
+}
+
+fn has_relative_conditions(spend: &OwnedSpendConditions) -> bool {
+    spend.height_relative.is_some()
+        || spend.seconds_relative.is_some()
+        || spend.before_height_relative.is_some()
+        || spend.before_seconds_relative.is_some()
+        || spend.birth_height.is_some()
+        || spend.birth_seconds.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const COIN_ID: Bytes32 = Bytes32::new([1; 32]);
+    const CONFIRMED_HEIGHT: u32 = 100;
+    const CONFIRMED_TIMESTAMP: u64 = 10_000;
+
+    fn spend(coin_id: Bytes32) -> OwnedSpendConditions {
+        OwnedSpendConditions {
+            coin_id,
+            coin_amount: 1,
+            ..Default::default()
+        }
+    }
+
+    fn conditions(spends: Vec<OwnedSpendConditions>) -> OwnedSpendBundleConditions {
+        OwnedSpendBundleConditions {
+            spends,
+            removal_amount: 1,
+            ..Default::default()
+        }
+    }
+
+    fn confirm_info() -> HashMap<Bytes32, (u32, u64)> {
+        HashMap::from([(COIN_ID, (CONFIRMED_HEIGHT, CONFIRMED_TIMESTAMP))])
+    }
+
+    type Conds = OwnedSpendBundleConditions;
+    type Update = fn(&mut Conds);
+
+    // each condition is tested exactly at its limit and one below it. The
+    // coin was confirmed at height 100 and timestamp 10000
+    #[rstest]
+    #[case::no_conditions(|_: &mut Conds| {}, 0, 0, None)]
+    #[case::height_absolute(|c: &mut Conds| c.height_absolute = 200, 199, 0, Some(ErrorCode::AssertHeightAbsoluteFailed))]
+    #[case::height_absolute(|c: &mut Conds| c.height_absolute = 200, 200, 0, None)]
+    #[case::seconds_absolute(|c: &mut Conds| c.seconds_absolute = 20_000, 0, 19_999, Some(ErrorCode::AssertSecondsAbsoluteFailed))]
+    #[case::seconds_absolute(|c: &mut Conds| c.seconds_absolute = 20_000, 0, 20_000, None)]
+    #[case::before_height_absolute(|c: &mut Conds| c.before_height_absolute = Some(200), 199, 0, None)]
+    #[case::before_height_absolute(|c: &mut Conds| c.before_height_absolute = Some(200), 200, 0, Some(ErrorCode::AssertBeforeHeightAbsoluteFailed))]
+    #[case::before_seconds_absolute(|c: &mut Conds| c.before_seconds_absolute = Some(20_000), 0, 19_999, None)]
+    #[case::before_seconds_absolute(|c: &mut Conds| c.before_seconds_absolute = Some(20_000), 0, 20_000, Some(ErrorCode::AssertBeforeSecondsAbsoluteFailed))]
+    #[case::height_relative(|c: &mut Conds| c.spends[0].height_relative = Some(10), 109, 0, Some(ErrorCode::AssertHeightRelativeFailed))]
+    #[case::height_relative(|c: &mut Conds| c.spends[0].height_relative = Some(10), 110, 0, None)]
+    #[case::height_relative_zero(|c: &mut Conds| c.spends[0].height_relative = Some(0), 100, 0, None)]
+    #[case::seconds_relative(|c: &mut Conds| c.spends[0].seconds_relative = Some(100), 0, 10_099, Some(ErrorCode::AssertSecondsRelativeFailed))]
+    #[case::seconds_relative(|c: &mut Conds| c.spends[0].seconds_relative = Some(100), 0, 10_100, None)]
+    #[case::before_height_relative(|c: &mut Conds| c.spends[0].before_height_relative = Some(10), 109, 0, None)]
+    #[case::before_height_relative(|c: &mut Conds| c.spends[0].before_height_relative = Some(10), 110, 0, Some(ErrorCode::AssertBeforeHeightRelativeFailed))]
+    #[case::before_seconds_relative(|c: &mut Conds| c.spends[0].before_seconds_relative = Some(100), 0, 10_099, None)]
+    #[case::before_seconds_relative(|c: &mut Conds| c.spends[0].before_seconds_relative = Some(100), 0, 10_100, Some(ErrorCode::AssertBeforeSecondsRelativeFailed))]
+    #[case::birth_height(|c: &mut Conds| c.spends[0].birth_height = Some(100), 0, 0, None)]
+    #[case::birth_height(|c: &mut Conds| c.spends[0].birth_height = Some(99), 0, 0, Some(ErrorCode::AssertMyBirthHeightFailed))]
+    #[case::birth_height(|c: &mut Conds| c.spends[0].birth_height = Some(101), 0, 0, Some(ErrorCode::AssertMyBirthHeightFailed))]
+    #[case::birth_seconds(|c: &mut Conds| c.spends[0].birth_seconds = Some(10_000), 0, 0, None)]
+    #[case::birth_seconds(|c: &mut Conds| c.spends[0].birth_seconds = Some(10_001), 0, 0, Some(ErrorCode::AssertMyBirthSecondsFailed))]
+    // relative heights and times that overflow are never reached
+    #[case::height_relative_overflow(|c: &mut Conds| c.spends[0].height_relative = Some(u32::MAX), u32::MAX, 0, Some(ErrorCode::AssertHeightRelativeFailed))]
+    #[case::seconds_relative_overflow(|c: &mut Conds| c.spends[0].seconds_relative = Some(u64::MAX), 0, u64::MAX, Some(ErrorCode::AssertSecondsRelativeFailed))]
+    #[case::before_height_relative_overflow(|c: &mut Conds| c.spends[0].before_height_relative = Some(u32::MAX), u32::MAX, 0, None)]
+    #[case::before_seconds_relative_overflow(|c: &mut Conds| c.spends[0].before_seconds_relative = Some(u64::MAX), 0, u64::MAX, None)]
+    // the absolute conditions are checked before the relative ones
+    #[case::first_violation(|c: &mut Conds| {
+        c.seconds_absolute = 1;
+        c.spends[0].height_relative = Some(1);
+    }, 0, 0, Some(ErrorCode::AssertSecondsAbsoluteFailed))]
+    fn test_satisfiable_at(
+        #[case] update: Update,
+        #[case] height: u32,
+        #[case] timestamp: u64,
+        #[case] expected: Option<ErrorCode>,
+    ) {
+        let mut conds = conditions(vec![spend(COIN_ID)]);
+        update(&mut conds);
+        assert_eq!(
+            satisfiable_at(&conds, height, timestamp, &confirm_info()),
+            expected.map_or(Ok(()), Err)
+        );
+    }
+
+    #[rstest]
+    #[case::no_conditions(|_: &mut Conds| {}, None)]
+    #[case::height_absolute(|c: &mut Conds| c.height_absolute = 1, None)]
+    #[case::height_relative(|c: &mut Conds| c.spends[1].height_relative = Some(0), Some(ErrorCode::EphemeralRelativeCondition))]
+    #[case::seconds_relative(|c: &mut Conds| c.spends[1].seconds_relative = Some(0), Some(ErrorCode::EphemeralRelativeCondition))]
+    #[case::before_height_relative(|c: &mut Conds| c.spends[1].before_height_relative = Some(10), Some(ErrorCode::EphemeralRelativeCondition))]
+    #[case::before_seconds_relative(|c: &mut Conds| c.spends[1].before_seconds_relative = Some(10), Some(ErrorCode::EphemeralRelativeCondition))]
+    #[case::birth_height(|c: &mut Conds| c.spends[1].birth_height = Some(0), Some(ErrorCode::EphemeralRelativeCondition))]
+    #[case::birth_seconds(|c: &mut Conds| c.spends[1].birth_seconds = Some(0), Some(ErrorCode::EphemeralRelativeCondition))]
+    // the known coin is checked first
+    #[case::known_coin_first(|c: &mut Conds| {
+        c.spends[0].height_relative = Some(100);
+        c.spends[1].height_relative = Some(0);
+    }, Some(ErrorCode::AssertHeightRelativeFailed))]
+    fn test_missing_coin_info(#[case] update: Update, #[case] expected: Option<ErrorCode>) {
+        // the second coin isn't in the confirm info, e.g. because it's
+        // created by the first one
+        let mut conds = conditions(vec![spend(COIN_ID), spend(Bytes32::new([2; 32]))]);
+        update(&mut conds);
+        assert_eq!(
+            satisfiable_at(&conds, 150, 15_000, &confirm_info()),
+            expected.map_or(Ok(()), Err)
+        );
+    }
+}
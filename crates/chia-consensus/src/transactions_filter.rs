@@ -0,0 +1,569 @@
+//! The transactions filter of a block is a BIP 158 style Golomb-coded set of
+//! the puzzle hashes of the coins it creates and the IDs of the coins it
+//! spends. Light wallets download the filter to test whether a block may
+//! affect them, without downloading the block. The `filter_hash` in the
+//! `FoliageTransactionBlock` is the sha256 of the encoded filter.
+//!
+//! The parameters and encoding match the `chiabip158` library used by
+//! chia-blockchain: a false positive rate parameter `P` of 20, `M` of 2^20 and
+//! a SipHash key of zero. Apart from those parameters, the encoding is the
+//! same as bitcoin's basic block filter.
+
+use crate::error::{Error, Result};
+use chia_protocol::{Bytes32, Coin};
+use std::collections::HashSet;
+
+// the parameters of a Golomb-coded set. P is the number of low bits stored
+// verbatim for every item, M is the inverse of the false positive rate and
+// the items are hashed with SipHash, keyed by k0 and k1
+struct FilterParams {
+    p: u8,
+    m: u64,
+    k0: u64,
+    k1: u64,
+}
+
+const CHIA_FILTER: FilterParams = FilterParams {
+    p: 20,
+    m: 1 << 20,
+    k0: 0,
+    k1: 0,
+};
+
+/// Computes the encoded transactions filter for a block with the specified
+/// additions and removals (coin IDs). Duplicate items are only included
+/// once.
+pub fn compute_transactions_filter(additions: &[Coin], removals: &[Bytes32]) -> Vec<u8> {
+    let elements: HashSet<&[u8]> = additions
+        .iter()
+        .map(|c| c.puzzle_hash.as_ref())
+        .chain(removals.iter().map(AsRef::as_ref))
+        .collect();
+    encode_filter(&CHIA_FILTER, &elements)
+}
+
+/// Verifies that a transactions filter (for example from a header block)
+/// includes the puzzle hash of every addition and the ID of every removal. A
+/// node can't leave coins out of the filter to hide them from a light wallet
+/// without this failing. Since the filter is probabilistic, it may include
+/// items that aren't in the block, so this can't detect extra items.
+pub fn verify_transactions_filter(
+    filter: &[u8],
+    additions: &[Coin],
+    removals: &[Bytes32],
+) -> Result<()> {
+    let values = decode_filter(&CHIA_FILTER, filter)?;
+    let n = values.len() as u64;
+    for item in additions
+        .iter()
+        .map(|c| &c.puzzle_hash)
+        .chain(removals.iter())
+    {
+        if values
+            .binary_search(&hash_to_range(&CHIA_FILTER, item.as_ref(), n))
+            .is_err()
+        {
+            return Err(Error::MissingFromTransactionsFilter(*item));
+        }
+    }
+    Ok(())
+}
+
+fn encode_filter(params: &FilterParams, elements: &HashSet<&[u8]>) -> Vec<u8> {
+    let n = elements.len() as u64;
+    let mut ret = Vec::new();
+    write_compact_size(&mut ret, n);
+    if elements.is_empty() {
+        return ret;
+    }
+
+    let mut values: Vec<u64> = elements
+        .iter()
+        .map(|e| hash_to_range(params, e, n))
+        .collect();
+    values.sort_unstable();
+
+    let mut writer = BitWriter::new(ret);
+    let mut last = 0;
+    for v in values {
+        writer.golomb_rice_encode(v - last, params.p);
+        last = v;
+    }
+    writer.finish()
+}
+
+// maps the item uniformly onto the range [0, N * M), where N is the number of
+// items in the filter
+fn hash_to_range(params: &FilterParams, item: &[u8], n: u64) -> u64 {
+    let range = u128::from(n) * u128::from(params.m);
+    let v = (u128::from(siphash24(params.k0, params.k1, item)) * range) >> 64;
+    u64::try_from(v).expect("value within range")
+}
+
+// returns the (sorted) hashed values of the items in the filter. The filter
+// must be encoded exactly the way encode_filter() encodes it, without any
+// trailing data
+fn decode_filter(params: &FilterParams, filter: &[u8]) -> Result<Vec<u64>> {
+    let (n, filter) = read_compact_size(filter).ok_or(Error::InvalidTransactionsFilter)?;
+
+    // every item takes at least P + 1 bits. This keeps us from allocating
+    // space for items that aren't there
+    if n > (filter.len() as u64 * 8) / (u64::from(params.p) + 1) {
+        return Err(Error::InvalidTransactionsFilter);
+    }
+    let range = n * params.m;
+
+    let mut reader = BitReader::new(filter);
+    let mut values = Vec::with_capacity(n as usize);
+    let mut last = 0_u64;
+    for _ in 0..n {
+        let value = reader
+            .golomb_rice_decode(params.p)
+            .and_then(|delta| last.checked_add(delta))
+            .filter(|value| *value < range)
+            .ok_or(Error::InvalidTransactionsFilter)?;
+        values.push(value);
+        last = value;
+    }
+    if !reader.at_end() {
+        return Err(Error::InvalidTransactionsFilter);
+    }
+    Ok(values)
+}
+
+// the variable length integer encoding used by bitcoin. Returns the value and
+// the remaining input
+fn read_compact_size(input: &[u8]) -> Option<(u64, &[u8])> {
+    let (prefix, input) = input.split_first()?;
+    let (size, min) = match prefix {
+        0xfd => (2, 0xfd),
+        0xfe => (4, 0x1_0000),
+        0xff => (8, 0x1_0000_0000),
+        n => return Some((u64::from(*n), input)),
+    };
+    if input.len() < size {
+        return None;
+    }
+    let (bytes, input) = input.split_at(size);
+    let mut buf = [0_u8; 8];
+    buf[..size].copy_from_slice(bytes);
+    let n = u64::from_le_bytes(buf);
+    // only the shortest encoding is valid
+    if n < min {
+        return None;
+    }
+    Some((n, input))
+}
+
+// the variable length integer encoding used by bitcoin
+fn write_compact_size(out: &mut Vec<u8>, n: u64) {
+    if n < 0xfd {
+        out.push(u8::try_from(n).unwrap());
+    } else if let Ok(n) = u16::try_from(n) {
+        out.push(0xfd);
+        out.extend_from_slice(&n.to_le_bytes());
+    } else if let Ok(n) = u32::try_from(n) {
+        out.push(0xfe);
+        out.extend_from_slice(&n.to_le_bytes());
+    } else {
+        out.push(0xff);
+        out.extend_from_slice(&n.to_le_bytes());
+    }
+}
+
+// writes bits, most significant bit first, padding the last byte with zeros
+struct BitWriter {
+    out: Vec<u8>,
+    byte: u8,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn new(out: Vec<u8>) -> Self {
+        Self {
+            out,
+            byte: 0,
+            bits: 0,
+        }
+    }
+
+    fn write_bit(&mut self, bit: bool) {
+        self.byte = (self.byte << 1) | u8::from(bit);
+        self.bits += 1;
+        if self.bits == 8 {
+            self.out.push(self.byte);
+            self.byte = 0;
+            self.bits = 0;
+        }
+    }
+
+    // the quotient is written in unary, followed by the low P bits
+    fn golomb_rice_encode(&mut self, delta: u64, p: u8) {
+        for _ in 0..(delta >> p) {
+            self.write_bit(true);
+        }
+        self.write_bit(false);
+        for i in (0..p).rev() {
+            self.write_bit((delta >> i) & 1 != 0);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.out.push(self.byte << (8 - self.bits));
+        }
+        self.out
+    }
+}
+
+// reads bits, most significant bit first
+struct BitReader<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn read_bit(&mut self) -> Option<bool> {
+        let byte = self.input.get(self.pos / 8)?;
+        let bit = (byte >> (7 - self.pos % 8)) & 1 != 0;
+        self.pos += 1;
+        Some(bit)
+    }
+
+    // the inverse of BitWriter::golomb_rice_encode()
+    fn golomb_rice_decode(&mut self, p: u8) -> Option<u64> {
+        let mut quotient = 0_u64;
+        while self.read_bit()? {
+            quotient += 1;
+        }
+        let mut delta = quotient.checked_mul(1 << p)?;
+        for i in (0..p).rev() {
+            if self.read_bit()? {
+                delta |= 1 << i;
+            }
+        }
+        Some(delta)
+    }
+
+    // true if only the zero padding of the last byte is left
+    fn at_end(&mut self) -> bool {
+        while self.pos % 8 != 0 {
+            if self.read_bit() != Some(false) {
+                return false;
+            }
+        }
+        self.pos / 8 == self.input.len()
+    }
+}
+
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13);
+    v[1] ^= v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16);
+    v[3] ^= v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21);
+    v[3] ^= v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17);
+    v[1] ^= v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+fn sip_compress(v: &mut [u64; 4], m: u64) {
+    v[3] ^= m;
+    sip_round(v);
+    sip_round(v);
+    v[0] ^= m;
+}
+
+// SipHash-2-4
+fn siphash24(k0: u64, k1: u64, data: &[u8]) -> u64 {
+    let mut v = [
+        k0 ^ 0x736f_6d65_7073_6575,
+        k1 ^ 0x646f_7261_6e64_6f6d,
+        k0 ^ 0x6c79_6765_6e65_7261,
+        k1 ^ 0x7465_6462_7974_6573,
+    ];
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        sip_compress(&mut v, u64::from_le_bytes(chunk.try_into().unwrap()));
+    }
+    let tail = chunks.remainder();
+    let mut last = [0_u8; 8];
+    last[..tail.len()].copy_from_slice(tail);
+    // the low byte of the length goes in the top byte of the last word
+    sip_compress(
+        &mut v,
+        u64::from_le_bytes(last) | ((data.len() as u64) << 56),
+    );
+
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        sip_round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chia_protocol::Amount;
+    use chia_sha2::Sha256;
+    use hex_literal::hex;
+    use rstest::rstest;
+
+    fn coin(puzzle_hash: u8) -> Coin {
+        Coin::new(
+            Bytes32::new([0xaa; 32]),
+            Bytes32::new([puzzle_hash; 32]),
+            Amount::MOJO,
+        )
+    }
+
+    fn sha256(buf: &[u8]) -> [u8; 32] {
+        let mut ctx = Sha256::new();
+        ctx.update(buf);
+        ctx.finalize()
+    }
+
+    #[rstest]
+    // test vectors from the SipHash paper
+    #[case(0x0706_0504_0302_0100, 0x0f0e_0d0c_0b0a_0908, &[], 0x726f_db47_dd0e_0e31)]
+    #[case(
+        0x0706_0504_0302_0100,
+        0x0f0e_0d0c_0b0a_0908,
+        &hex!("000102030405060708090a0b0c0d0e"),
+        0xa129_ca61_49be_45e5
+    )]
+    #[case(0, 0, &[], 0x1e92_4b9d_7377_00d7)]
+    #[case(0, 0, &[1; 32], 0x478c_ccdd_f826_3591)]
+    fn test_siphash(#[case] k0: u64, #[case] k1: u64, #[case] data: &[u8], #[case] expect: u64) {
+        assert_eq!(siphash24(k0, k1, data), expect);
+    }
+
+    #[rstest]
+    #[case(0, &[0])]
+    #[case(0xfc, &[0xfc])]
+    #[case(0xfd, &[0xfd, 0xfd, 0])]
+    #[case(0xffff, &[0xfd, 0xff, 0xff])]
+    #[case(0x1_0000, &[0xfe, 0, 0, 1, 0])]
+    #[case(0x1_0000_0000, &[0xff, 0, 0, 0, 0, 1, 0, 0, 0])]
+    fn test_compact_size(#[case] n: u64, #[case] expect: &[u8]) {
+        let mut out = Vec::new();
+        write_compact_size(&mut out, n);
+        assert_eq!(out, expect);
+    }
+
+    #[test]
+    fn test_empty_filter() {
+        let filter = compute_transactions_filter(&[], &[]);
+        assert_eq!(filter, [0]);
+        // this is the filter_hash of blocks without any additions or removals
+        assert_eq!(
+            sha256(&filter),
+            hex!("6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d")
+        );
+    }
+
+    #[rstest]
+    #[case(&[1], &[], "0123c660")]
+    #[case(&[], &[1], "0123c660")]
+    #[case(&[1, 2], &[], "02478cc8cfa480")]
+    #[case(&[1, 2], &[3], "036b5331377690c00a")]
+    #[case(&[3, 1], &[2], "036b5331377690c00a")]
+    // duplicates are only included once
+    #[case(&[1, 1, 2], &[3, 2], "036b5331377690c00a")]
+    fn test_transactions_filter(
+        #[case] additions: &[u8],
+        #[case] removals: &[u8],
+        #[case] expect: &str,
+    ) {
+        let additions: Vec<Coin> = additions.iter().map(|ph| coin(*ph)).collect();
+        let removals: Vec<Bytes32> = removals.iter().map(|id| Bytes32::new([*id; 32])).collect();
+        let filter = compute_transactions_filter(&additions, &removals);
+        assert_eq!(hex::encode(&filter), expect);
+        assert_eq!(
+            verify_transactions_filter(&filter, &additions, &removals),
+            Ok(())
+        );
+        // any subset of the items is included too
+        assert_eq!(verify_transactions_filter(&filter, &[], &removals), Ok(()));
+        assert_eq!(verify_transactions_filter(&filter, &additions, &[]), Ok(()));
+    }
+
+    #[rstest]
+    #[case(&[0], 0, &[])]
+    #[case(&[0xfc], 0xfc, &[])]
+    #[case(&[0xfd, 0xfd, 0], 0xfd, &[])]
+    #[case(&[0xfe, 0, 0, 1, 0, 7], 0x1_0000, &[7])]
+    #[case(&[0xff, 0, 0, 0, 0, 1, 0, 0, 0], 0x1_0000_0000, &[])]
+    fn test_read_compact_size(#[case] input: &[u8], #[case] n: u64, #[case] rest: &[u8]) {
+        assert_eq!(read_compact_size(input), Some((n, rest)));
+    }
+
+    #[rstest]
+    #[case(&[])]
+    #[case(&[0xfd, 0])]
+    // the value must use the shortest encoding
+    #[case(&[0xfd, 0xfc, 0])]
+    #[case(&[0xfe, 0xff, 0xff, 0, 0])]
+    #[case(&[0xff, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0])]
+    fn test_read_compact_size_invalid(#[case] input: &[u8]) {
+        assert_eq!(read_compact_size(input), None);
+    }
+
+    #[rstest]
+    // no item count
+    #[case("")]
+    // items missing
+    #[case("01")]
+    #[case("0123c6")]
+    #[case("036b5331377690c0")]
+    // trailing data
+    #[case("0000")]
+    #[case("0123c66000")]
+    // non-zero padding
+    #[case("0123c661")]
+    // more items than could possibly fit
+    #[case("fdffff00")]
+    #[case("ffffffffffffffffff")]
+    // the item is out of range, i.e. its quotient is too large
+    #[case("01fffffe000000")]
+    fn test_invalid_filter(#[case] filter: &str) {
+        let filter = hex::decode(filter).unwrap();
+        assert_eq!(
+            verify_transactions_filter(&filter, &[], &[]),
+            Err(Error::InvalidTransactionsFilter)
+        );
+    }
+
+    #[test]
+    fn test_missing_items() {
+        let additions = [coin(1), coin(2)];
+        let removals = [Bytes32::new([3; 32])];
+        let filter = compute_transactions_filter(&additions[..1], &removals);
+
+        assert_eq!(
+            verify_transactions_filter(&filter, &additions, &removals),
+            Err(Error::MissingFromTransactionsFilter(Bytes32::new([2; 32])))
+        );
+        assert_eq!(
+            verify_transactions_filter(&filter, &[], &[Bytes32::new([4; 32])]),
+            Err(Error::MissingFromTransactionsFilter(Bytes32::new([4; 32])))
+        );
+        assert_eq!(
+            verify_transactions_filter(&[0], &[], &removals),
+            Err(Error::MissingFromTransactionsFilter(removals[0]))
+        );
+        // the filter of an empty block includes nothing
+        assert_eq!(verify_transactions_filter(&[0], &[], &[]), Ok(()));
+    }
+
+    // the first test vector from BIP 158 (testnet-19.json), the basic filter
+    // of the testnet genesis block. Bitcoin keys SipHash with the first 16
+    // bytes of the block hash, and its only element is the output script of
+    // the genesis coinbase
+    const BIP158_FILTER: FilterParams = FilterParams {
+        p: 19,
+        m: 784_931,
+        k0: u64::from_le_bytes(hex!("43497fd7f8269571")),
+        k1: u64::from_le_bytes(hex!("08f4a30fd9cec3ae")),
+    };
+    const BIP158_GENESIS_SCRIPT: [u8; 67] = hex!(
+        "4104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac"
+    );
+
+    #[test]
+    fn test_bip158_vector() {
+        let elements = HashSet::from([BIP158_GENESIS_SCRIPT.as_slice()]);
+        assert_eq!(encode_filter(&BIP158_FILTER, &elements), hex!("019dfca8"));
+    }
+
+    #[test]
+    fn test_decode_bip158_vector() {
+        let values = decode_filter(&BIP158_FILTER, &hex!("019dfca8")).unwrap();
+        assert_eq!(
+            values,
+            [hash_to_range(&BIP158_FILTER, &BIP158_GENESIS_SCRIPT, 1)]
+        );
+    }
+
+    #[test]
+    fn test_large_filter() {
+        // 256 unique items, which needs a 3 byte item count
+        let additions: Vec<Coin> = (0..200).map(coin).collect();
+        let removals: Vec<Bytes32> = (100..=255).map(|id| Bytes32::new([id; 32])).collect();
+        let filter = compute_transactions_filter(&additions, &removals);
+        assert_eq!(filter.len(), 694);
+        assert_eq!(
+            verify_transactions_filter(&filter, &additions, &removals),
+            Ok(())
+        );
+        assert_eq!(filter[..12], hex!("fd0001a7a03ebe358878d0db"));
+        assert_eq!(
+            sha256(&filter),
+            hex!("47e40ff68e37774a88254aa5675cc144ae886c67ea6e241e937c0547579025ca")
+        );
+    }
+
+    // the additions and removals of the block generators in generator-tests.
+    // The filters of the actual blocks also cover the reward coins, so these
+    // aren't compared against any real block's transactions_filter
+    #[rstest]
+    #[case("block-225758")]
+    #[case("block-834752")]
+    #[case("block-834760")]
+    #[case("block-834765")]
+    #[case("block-834768")]
+    fn test_generator_block(#[case] name: &str) {
+        use crate::consensus_constants::TEST_CONSTANTS;
+        use crate::gen::additions_and_removals::additions_and_removals;
+        use crate::gen::flags::ALLOW_BACKREFS;
+        use std::fs::read_to_string;
+
+        let test_file = read_to_string(format!("../../generator-tests/{name}.txt"))
+            .expect("test file not found");
+        let (generator, _) = test_file.split_once('\n').expect("invalid test file");
+        let generator = hex::decode(generator).expect("invalid hex encoded generator");
+        let mut block_refs = Vec::<Vec<u8>>::new();
+        if let Ok(env_hex) = read_to_string(format!("../../generator-tests/{name}.env")) {
+            block_refs.push(hex::decode(env_hex).expect("hex decode env-file"));
+        }
+
+        let (additions, removals) =
+            additions_and_removals(&generator, &block_refs, ALLOW_BACKREFS, &TEST_CONSTANTS)
+                .expect("additions_and_removals()");
+        let additions: Vec<Coin> = additions.into_iter().map(|(coin, _)| coin).collect();
+        let removals: Vec<Bytes32> = removals.iter().map(Coin::coin_id).collect();
+        assert!(!additions.is_empty());
+
+        let filter = compute_transactions_filter(&additions, &removals);
+        assert_eq!(
+            verify_transactions_filter(&filter, &additions, &removals),
+            Ok(())
+        );
+
+        // a filter that leaves out any one coin fails verification
+        let filter = compute_transactions_filter(&additions[1..], &removals);
+        let puzzle_hash = additions[0].puzzle_hash;
+        if !additions[1..].iter().any(|c| c.puzzle_hash == puzzle_hash) {
+            assert_eq!(
+                verify_transactions_filter(&filter, &additions, &removals),
+                Err(Error::MissingFromTransactionsFilter(puzzle_hash))
+            );
+        }
+        let filter = compute_transactions_filter(&additions, &removals[1..]);
+        assert_eq!(
+            verify_transactions_filter(&filter, &additions, &removals),
+            Err(Error::MissingFromTransactionsFilter(removals[0]))
+        );
+    }
+}
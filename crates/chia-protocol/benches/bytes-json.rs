@@ -0,0 +1,89 @@
+use chia_protocol::Bytes32;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::time::Instant;
+
+// converting a large response, such as the coin IDs of a block, to its JSON
+// form
+fn run(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bytes-json");
+
+    let hashes: Vec<Bytes32> = (0..10_000_u32)
+        .map(|i| {
+            let mut hash = [0; 32];
+            hash[..4].copy_from_slice(&i.to_be_bytes());
+            Bytes32::new(hash)
+        })
+        .collect();
+
+    // this is how the hex strings were built before `to_hex_into()`
+    group.bench_function("format! hex::encode 10k Bytes32", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            let strings: Vec<String> = hashes
+                .iter()
+                .map(|hash| format!("0x{}", hex::encode(hash)))
+                .collect();
+            black_box(strings);
+            start.elapsed()
+        });
+    });
+
+    group.bench_function("to_hex_into 10k Bytes32", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            let strings: Vec<String> = hashes
+                .iter()
+                .map(|hash| {
+                    let mut buf = String::with_capacity(66);
+                    buf.push_str("0x");
+                    hash.to_hex_into(&mut buf);
+                    buf
+                })
+                .collect();
+            black_box(strings);
+            start.elapsed()
+        });
+    });
+
+    group.bench_function("serde_json 10k Bytes32", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            black_box(serde_json::to_string(&hashes).unwrap());
+            start.elapsed()
+        });
+    });
+
+    let json = serde_json::to_string(&hashes).unwrap();
+    group.bench_function("serde_json parse 10k Bytes32", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            black_box(serde_json::from_str::<Vec<Bytes32>>(&json).unwrap());
+            start.elapsed()
+        });
+    });
+
+    let strings: Vec<String> = hashes.iter().map(|hash| format!("0x{hash}")).collect();
+    group.bench_function("hex::decode 10k Bytes32", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            for s in &strings {
+                let buf = hex::decode(&s[2..]).unwrap();
+                black_box(Bytes32::try_from(buf).unwrap());
+            }
+            start.elapsed()
+        });
+    });
+
+    group.bench_function("try_from_hex 10k Bytes32", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            for s in &strings {
+                black_box(Bytes32::try_from_hex(s).unwrap());
+            }
+            start.elapsed()
+        });
+    });
+}
+
+criterion_group!(bytes_json, run);
+criterion_main!(bytes_json);
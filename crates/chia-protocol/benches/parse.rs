@@ -0,0 +1,63 @@
+use chia_protocol::{Bytes, SpendBundle};
+use chia_traits::{ParseLimits, Streamable};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::fs;
+use std::time::Instant;
+
+// every list, byte buffer and program is checked against the parse limits
+// (which live in a thread-local) as it's parsed. This measures the cost of
+// that, with and without limits in effect
+fn run(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+
+    let limits = ParseLimits {
+        max_vec_len: 1_000_000,
+        max_bytes_len: 10_000_000,
+        max_total_alloc: 100_000_000,
+    };
+
+    let bundle = fs::read("../../test-bundles/1000101.bundle").expect("read file");
+
+    group.bench_function("SpendBundle from_bytes", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            black_box(SpendBundle::from_bytes(&bundle).expect("parse"));
+            start.elapsed()
+        });
+    });
+
+    group.bench_function("SpendBundle parse_with_limits", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            black_box(SpendBundle::parse_with_limits(&bundle, limits).expect("parse"));
+            start.elapsed()
+        });
+    });
+
+    // the worst case, lots of short byte buffers
+    let buffers: Vec<Bytes> = (0..10_000_u32)
+        .map(|i| i.to_be_bytes().to_vec().into())
+        .collect();
+    let buffers = buffers.to_bytes().expect("to_bytes");
+
+    group.bench_function("10k Bytes from_bytes", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            black_box(Vec::<Bytes>::from_bytes(&buffers).expect("parse"));
+            start.elapsed()
+        });
+    });
+
+    group.bench_function("10k Bytes parse_with_limits", |b| {
+        b.iter(|| {
+            let start = Instant::now();
+            black_box(Vec::<Bytes>::parse_with_limits(&buffers, limits).expect("parse"));
+            start.elapsed()
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(parse, run);
+criterion_main!(parse);
@@ -46,7 +46,7 @@ fuzz_target!(|data: &[u8]| {
                 expected.insert(Coin {
                     parent_coin_info,
                     puzzle_hash,
-                    amount,
+                    amount: amount.into(),
                 });
                 total_cost += 1_800_000;
             }
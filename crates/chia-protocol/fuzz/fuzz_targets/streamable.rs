@@ -78,6 +78,7 @@ fuzz_target!(|data: &[u8]| {
     test::<SubEpochSegments>(data);
     test::<SubEpochSummary>(data);
     test::<WeightProof>(data);
+    test::<PeerInfo>(data);
     test::<TimestampedPeerInfo>(data);
     test::<RecentChainData>(data);
     test::<ProofBlockHeader>(data);
@@ -145,4 +146,8 @@ fuzz_target!(|data: &[u8]| {
     test::<RespondCompactVDF>(data);
     test::<RequestPeers>(data);
     test::<RespondPeers>(data);
+
+    // Introducer Protocol
+    test::<RequestPeersIntroducer>(data);
+    test::<RespondPeersIntroducer>(data);
 });
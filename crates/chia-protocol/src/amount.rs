@@ -0,0 +1,307 @@
+use chia_sha2::Sha256;
+use chia_traits::{chia_error, diff_leaf, FieldDiff, Streamable, StreamableDiff};
+use clvm_traits::{ClvmDecoder, ClvmEncoder, FromClvm, FromClvmError, ToClvm, ToClvmError};
+use std::fmt;
+use std::io::Cursor;
+
+#[cfg(feature = "py-bindings")]
+use chia_traits::{ChiaToPython, FromJsonDict, JsonValue, ToJsonDict};
+#[cfg(feature = "py-bindings")]
+use pyo3::prelude::*;
+
+/// A coin value, in mojos. This is serialized exactly like a `u64` (as
+/// Streamable, as CLVM and with serde) and is exposed to python as a plain
+/// `uint64`. The point of the newtype is to keep coin values from being mixed
+/// up with other integers, and to make overflow handling explicit at every
+/// call site.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Amount(u64);
+
+/// The largest value a single coin can hold.
+pub const MAX_COIN_AMOUNT: Amount = Amount::MAX;
+
+impl Amount {
+    pub const ZERO: Self = Self(0);
+    /// The smallest unit, 1 mojo
+    pub const MOJO: Self = Self(1);
+    pub const MAX: Self = Self(u64::MAX);
+
+    pub const fn new(mojos: u64) -> Self {
+        Self(mojos)
+    }
+
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    pub fn checked_mul(self, rhs: u64) -> Option<Self> {
+        self.0.checked_mul(rhs).map(Self)
+    }
+
+    #[must_use]
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+
+    #[must_use]
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+
+    /// Sums all amounts, returning None on overflow.
+    pub fn checked_sum<I: IntoIterator<Item = Self>>(amounts: I) -> Option<Self> {
+        amounts.into_iter().try_fold(Self::ZERO, Self::checked_add)
+    }
+}
+
+impl From<u64> for Amount {
+    fn from(mojos: u64) -> Self {
+        Self(mojos)
+    }
+}
+
+impl From<Amount> for u64 {
+    fn from(amount: Amount) -> Self {
+        amount.0
+    }
+}
+
+impl From<Amount> for u128 {
+    fn from(amount: Amount) -> Self {
+        amount.0.into()
+    }
+}
+
+impl PartialEq<u64> for Amount {
+    fn eq(&self, other: &u64) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Amount> for u64 {
+    fn eq(&self, other: &Amount) -> bool {
+        *self == other.0
+    }
+}
+
+// printed like the u64 it replaces, e.g. in the Debug output of Coin
+impl fmt::Debug for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Streamable for Amount {
+    fn update_digest(&self, digest: &mut Sha256) {
+        self.0.update_digest(digest);
+    }
+
+    fn stream(&self, out: &mut Vec<u8>) -> chia_error::Result<()> {
+        self.0.stream(out)
+    }
+
+    fn parse<const TRUSTED: bool>(input: &mut Cursor<&[u8]>) -> chia_error::Result<Self> {
+        Ok(Self(u64::parse::<TRUSTED>(input)?))
+    }
+}
+
+impl StreamableDiff for Amount {
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+        diff_leaf(self, other, path, out);
+    }
+
+    fn summary(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl<N, E: ClvmEncoder<Node = N>> ToClvm<E> for Amount {
+    fn to_clvm(&self, encoder: &mut E) -> Result<N, ToClvmError> {
+        self.0.to_clvm(encoder)
+    }
+}
+
+impl<N, D: ClvmDecoder<Node = N>> FromClvm<D> for Amount {
+    fn from_clvm(decoder: &D, node: N) -> Result<Self, FromClvmError> {
+        Ok(Self(u64::from_clvm(decoder, node)?))
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+impl ChiaToPython for Amount {
+    fn to_python<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.0.to_python(py)
+    }
+
+    fn py_type_name() -> String {
+        u64::py_type_name()
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+impl ToJsonDict for Amount {
+    fn to_json_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        self.0.to_json_dict(py)
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+impl FromJsonDict for Amount {
+    fn from_json_dict(o: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self(u64::from_json_dict(o)?))
+    }
+
+    fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+        Ok(Self(u64::from_json_value(v)?))
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+impl<'py> FromPyObject<'py> for Amount {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Ok(Self(ob.extract()?))
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+impl<'py> IntoPyObject<'py> for Amount {
+    type Target = PyAny;
+    type Output = Bound<'py, Self::Target>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(self.0.into_pyobject(py)?.into_any())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Amount {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Amount {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <u64 as serde::Deserialize>::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clvmr::serde::{node_from_bytes, node_to_bytes};
+    use clvmr::Allocator;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 0, Some(0))]
+    #[case(1, 2, Some(3))]
+    #[case(u64::MAX - 1, 1, Some(u64::MAX))]
+    #[case(u64::MAX, 1, None)]
+    fn test_checked_add(#[case] a: u64, #[case] b: u64, #[case] expected: Option<u64>) {
+        let sum = Amount::new(a).checked_add(Amount::new(b));
+        assert_eq!(sum, expected.map(Amount::new));
+        let saturated = Amount::new(a).saturating_add(Amount::new(b));
+        assert_eq!(saturated, expected.unwrap_or(u64::MAX));
+    }
+
+    #[rstest]
+    #[case(3, 2, Some(1))]
+    #[case(2, 2, Some(0))]
+    #[case(0, 1, None)]
+    fn test_checked_sub(#[case] a: u64, #[case] b: u64, #[case] expected: Option<u64>) {
+        let diff = Amount::new(a).checked_sub(Amount::new(b));
+        assert_eq!(diff, expected.map(Amount::new));
+        let saturated = Amount::new(a).saturating_sub(Amount::new(b));
+        assert_eq!(saturated, expected.unwrap_or(0));
+    }
+
+    #[rstest]
+    #[case(3, 2, Some(6))]
+    #[case(u64::MAX, 1, Some(u64::MAX))]
+    #[case(u64::MAX / 2 + 1, 2, None)]
+    fn test_checked_mul(#[case] a: u64, #[case] b: u64, #[case] expected: Option<u64>) {
+        assert_eq!(Amount::new(a).checked_mul(b), expected.map(Amount::new));
+    }
+
+    #[test]
+    fn test_checked_sum() {
+        assert_eq!(Amount::checked_sum([Amount::ZERO; 0]), Some(Amount::ZERO));
+        assert_eq!(
+            Amount::checked_sum([Amount::MOJO, Amount::new(41)]),
+            Some(Amount::new(42))
+        );
+        assert_eq!(Amount::checked_sum([MAX_COIN_AMOUNT, Amount::MOJO]), None);
+    }
+
+    #[rstest]
+    #[case(0, "80")]
+    #[case(1, "01")]
+    #[case(0x80, "820080")]
+    #[case(u64::MAX, "8900ffffffffffffffff")]
+    fn test_clvm_roundtrip(#[case] mojos: u64, #[case] serialized: &str) {
+        let a = &mut Allocator::new();
+        let node = Amount::new(mojos).to_clvm(a).unwrap();
+        assert_eq!(hex::encode(node_to_bytes(a, node).unwrap()), serialized);
+
+        let node = node_from_bytes(a, &hex::decode(serialized).unwrap()).unwrap();
+        assert_eq!(Amount::from_clvm(a, node).unwrap(), mojos);
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1337)]
+    #[case(u64::MAX)]
+    fn test_streamable(#[case] mojos: u64) {
+        let amount = Amount::from(mojos);
+        let bytes = amount.to_bytes().unwrap();
+        assert_eq!(bytes, mojos.to_bytes().unwrap());
+        assert_eq!(Amount::from_bytes(&bytes).unwrap(), amount);
+        assert_eq!(Streamable::hash(&amount), Streamable::hash(&mojos));
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(format!("{:?}", Amount::new(1337)), "1337");
+        assert_eq!(
+            format!("{:?}", Some(Amount::MAX)),
+            "Some(18446744073709551615)"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let amount = Amount::new(1_750_000_000_000);
+        let json = serde_json::to_string(&amount).unwrap();
+        assert_eq!(json, "1750000000000");
+        assert_eq!(serde_json::from_str::<Amount>(&json).unwrap(), amount);
+        assert_eq!(
+            serde_json::to_string(&Amount::MAX).unwrap(),
+            u64::MAX.to_string()
+        );
+        assert!(serde_json::from_str::<Amount>("-1").is_err());
+    }
+}
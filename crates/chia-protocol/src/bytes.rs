@@ -1,5 +1,8 @@
 use chia_sha2::Sha256;
-use chia_traits::{chia_error, read_bytes, Streamable};
+use chia_traits::{
+    bytes_summary, chia_error, diff_leaf, read_bytes, reserve_bytes, FieldDiff, Streamable,
+    StreamableDiff,
+};
 use clvm_traits::{ClvmDecoder, ClvmEncoder, FromClvm, FromClvmError, ToClvm, ToClvmError};
 use clvm_utils::TreeHash;
 use clvmr::Atom;
@@ -9,7 +12,7 @@ use std::io::Cursor;
 use std::ops::Deref;
 
 #[cfg(feature = "py-bindings")]
-use chia_traits::{ChiaToPython, FromJsonDict, ToJsonDict};
+use chia_traits::{json_str, ChiaToPython, FromJsonDict, JsonValue, ToJsonDict};
 #[cfg(feature = "py-bindings")]
 use hex::FromHex;
 #[cfg(feature = "py-bindings")]
@@ -17,7 +20,9 @@ use pyo3::exceptions::PyValueError;
 #[cfg(feature = "py-bindings")]
 use pyo3::prelude::*;
 #[cfg(feature = "py-bindings")]
-use pyo3::types::PyBytes;
+use pyo3::sync::GILOnceCell;
+#[cfg(feature = "py-bindings")]
+use pyo3::types::{PyBytes, PyType};
 
 #[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -47,11 +52,46 @@ impl Bytes {
     pub fn into_inner(self) -> Vec<u8> {
         self.0
     }
+
+    /// Appends the lowercase hex encoding of the bytes to `buf`.
+    pub fn to_hex_into(&self, buf: &mut String) {
+        encode_hex_into(&self.0, buf);
+    }
+}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+fn encode_hex_into(bytes: &[u8], buf: &mut String) {
+    buf.reserve(bytes.len() * 2);
+    for b in bytes {
+        buf.push(HEX_DIGITS[usize::from(b >> 4)].into());
+        buf.push(HEX_DIGITS[usize::from(b & 0xf)].into());
+    }
+}
+
+// the `0x`-prefixed hex string used in JSON
+#[cfg(any(feature = "py-bindings", feature = "serde"))]
+fn json_hex(bytes: &[u8]) -> String {
+    let mut buf = String::with_capacity(2 + bytes.len() * 2);
+    buf.push_str("0x");
+    encode_hex_into(bytes, &mut buf);
+    buf
+}
+
+// writes the hex encoding in chunks, through a buffer on the stack
+fn write_hex(bytes: &[u8], formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut buf = [0_u8; 128];
+    for chunk in bytes.chunks(buf.len() / 2) {
+        let out = &mut buf[..chunk.len() * 2];
+        hex::encode_to_slice(chunk, out).expect("buffer size");
+        formatter.write_str(std::str::from_utf8(out).expect("hex digits"))?;
+    }
+    Ok(())
 }
 
 impl fmt::Debug for Bytes {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter.write_str(&hex::encode(self))
+        write_hex(&self.0, formatter)
     }
 }
 
@@ -79,34 +119,74 @@ impl Streamable for Bytes {
 
     fn parse<const TRUSTED: bool>(input: &mut Cursor<&[u8]>) -> chia_error::Result<Self> {
         let len = u32::parse::<TRUSTED>(input)?;
+        reserve_bytes(len)?;
         Ok(Bytes(read_bytes(input, len as usize)?.to_vec()))
     }
 }
 
+impl StreamableDiff for Bytes {
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+        diff_leaf(self, other, path, out);
+    }
+
+    fn summary(&self) -> String {
+        bytes_summary(&self.0)
+    }
+}
+
 #[cfg(feature = "py-bindings")]
 impl ToJsonDict for Bytes {
     fn to_json_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
-        Ok(format!("0x{self}").into_pyobject(py)?.into_any().unbind())
+        Ok(json_hex(&self.0).into_pyobject(py)?.into_any().unbind())
     }
 }
 
+#[cfg(feature = "py-bindings")]
+fn parse_json_hex(s: &str) -> PyResult<Vec<u8>> {
+    let Some(s) = s.strip_prefix("0x") else {
+        return Err(PyValueError::new_err(
+            "bytes object is expected to start with 0x",
+        ));
+    };
+    Vec::from_hex(s).map_err(|_| PyValueError::new_err("invalid hex"))
+}
+
 #[cfg(feature = "py-bindings")]
 impl FromJsonDict for Bytes {
     fn from_json_dict(o: &Bound<'_, PyAny>) -> PyResult<Self> {
         let s: String = o.extract()?;
-        if !s.starts_with("0x") {
-            return Err(PyValueError::new_err(
-                "bytes object is expected to start with 0x",
-            ));
-        }
-        let s = &s[2..];
-        let buf = match Vec::from_hex(s) {
-            Err(_) => {
-                return Err(PyValueError::new_err("invalid hex"));
-            }
-            Ok(v) => v,
-        };
-        Ok(buf.into())
+        Ok(parse_json_hex(&s)?.into())
+    }
+
+    fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+        Ok(parse_json_hex(json_str(v)?)?.into())
+    }
+}
+
+/// Serialized as a `0x`-prefixed hex string, like in `to_json_dict()`, or as
+/// raw bytes in binary formats.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Bytes {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_hex(&self.0, serializer)
+    }
+}
+
+// the same as `chia_traits::serialize_hex_bytes()`, without the intermediate
+// hex string
+#[cfg(feature = "serde")]
+fn serialize_hex<S: serde::Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&json_hex(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Bytes {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        chia_traits::deserialize_hex_bytes(deserializer).map(Self)
     }
 }
 
@@ -170,6 +250,30 @@ impl<const N: usize> BytesImpl<N> {
         Self(bytes)
     }
 
+    /// Parses a hex string of exactly `N` bytes, with an optional `0x`
+    /// prefix. Since this is a `const fn`, an invalid string in a constant
+    /// fails the build. See [`bytes32!`](crate::bytes32).
+    ///
+    /// # Panics
+    ///
+    /// If the string has the wrong length or isn't valid hex.
+    pub const fn from_hex(s: &str) -> Self {
+        let s = s.as_bytes();
+        let start = if s.len() >= 2 && s[0] == b'0' && (s[1] == b'x' || s[1] == b'X') {
+            2
+        } else {
+            0
+        };
+        assert!(s.len() - start == 2 * N, "invalid length of hex string");
+        let mut ret = [0; N];
+        let mut i = 0;
+        while i < N {
+            ret[i] = (hex_digit(s[start + 2 * i]) << 4) | hex_digit(s[start + 2 * i + 1]);
+            i += 1;
+        }
+        Self(ret)
+    }
+
     pub const fn len(&self) -> usize {
         N
     }
@@ -189,6 +293,82 @@ impl<const N: usize> BytesImpl<N> {
     pub fn to_vec(&self) -> Vec<u8> {
         self.0.to_vec()
     }
+
+    /// Appends the lowercase hex encoding of the bytes to `buf`.
+    pub fn to_hex_into(&self, buf: &mut String) {
+        encode_hex_into(&self.0, buf);
+    }
+
+    /// Like [`from_hex()`](Self::from_hex), but returns an error instead of
+    /// panicking. The string is decoded in a single pass, straight into the
+    /// array.
+    pub fn try_from_hex(s: &str) -> Result<Self, hex::FromHexError> {
+        let digits = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        Self::decode_hex(digits, s.len() - digits.len())
+    }
+
+    // `offset` is where `digits` starts in the string, for the index in the
+    // error
+    fn decode_hex(digits: &str, offset: usize) -> Result<Self, hex::FromHexError> {
+        let bytes = digits.as_bytes();
+        if bytes.len() % 2 != 0 {
+            return Err(hex::FromHexError::OddLength);
+        }
+        if bytes.len() != 2 * N {
+            return Err(hex::FromHexError::InvalidStringLength);
+        }
+        // the digits are checked in order, so an invalid one is always at a
+        // char boundary
+        let digit = |i: usize| match bytes[i] {
+            c @ b'0'..=b'9' => Ok(c - b'0'),
+            c @ b'a'..=b'f' => Ok(c - b'a' + 10),
+            c @ b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err(hex::FromHexError::InvalidHexCharacter {
+                c: digits[i..].chars().next().unwrap_or_default(),
+                index: offset + i,
+            }),
+        };
+        let mut ret = [0; N];
+        for (i, byte) in ret.iter_mut().enumerate() {
+            *byte = (digit(2 * i)? << 4) | digit(2 * i + 1)?;
+        }
+        Ok(Self(ret))
+    }
+}
+
+const fn hex_digit(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        b'A'..=b'F' => c - b'A' + 10,
+        _ => panic!("invalid hex digit"),
+    }
+}
+
+/// A [`Bytes32`] constant, from a hex string with an optional `0x` prefix.
+/// The string is parsed at compile time, so an invalid one fails the build.
+///
+/// ```
+/// use chia_protocol::{bytes32, Bytes32};
+///
+/// const HASH: Bytes32 =
+///     bytes32!("0x0101010101010101010101010101010101010101010101010101010101010101");
+/// assert_eq!(HASH, Bytes32::new([1; 32]));
+/// ```
+///
+/// ```compile_fail
+/// // 31 bytes
+/// let hash = chia_protocol::bytes32!("01010101010101010101010101010101010101010101010101010101010101");
+/// ```
+#[macro_export]
+macro_rules! bytes32 {
+    ($hex:expr) => {{
+        const BYTES: $crate::Bytes32 = $crate::Bytes32::from_hex($hex);
+        BYTES
+    }};
 }
 
 impl<const N: usize> Default for BytesImpl<N> {
@@ -199,13 +379,14 @@ impl<const N: usize> Default for BytesImpl<N> {
 
 impl<const N: usize> fmt::Debug for BytesImpl<N> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        formatter.write_str(&hex::encode(self))
+        write_hex(&self.0, formatter)
     }
 }
 
+// the precision, if any, truncates the hex string. e.g. {:.8}
 impl<const N: usize> fmt::Display for BytesImpl<N> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter.write_str(&hex::encode(self))
+        formatter.pad(&hex::encode(self))
     }
 }
 
@@ -223,37 +404,74 @@ impl<const N: usize> Streamable for BytesImpl<N> {
     }
 }
 
+impl<const N: usize> StreamableDiff for BytesImpl<N> {
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+        diff_leaf(self, other, path, out);
+    }
+
+    fn summary(&self) -> String {
+        bytes_summary(&self.0)
+    }
+}
+
 #[cfg(feature = "py-bindings")]
 impl<const N: usize> ToJsonDict for BytesImpl<N> {
     fn to_json_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
-        Ok(format!("0x{self}").into_pyobject(py)?.into_any().unbind())
+        Ok(json_hex(&self.0).into_pyobject(py)?.into_any().unbind())
     }
 }
 
 #[cfg(feature = "py-bindings")]
-impl<const N: usize> FromJsonDict for BytesImpl<N> {
-    fn from_json_dict(o: &Bound<'_, PyAny>) -> PyResult<Self> {
-        let s: String = o.extract()?;
-        if !s.starts_with("0x") {
+impl<const N: usize> BytesImpl<N> {
+    fn from_json_hex(s: &str) -> PyResult<Self> {
+        let Some(digits) = s.strip_prefix("0x") else {
             return Err(PyValueError::new_err(
                 "bytes object is expected to start with 0x",
             ));
-        }
-        let s = &s[2..];
-        let buf = match Vec::from_hex(s) {
-            Err(_) => {
-                return Err(PyValueError::new_err("invalid hex"));
-            }
-            Ok(v) => v,
         };
-        if buf.len() != N {
-            return Err(PyValueError::new_err(format!(
-                "invalid length {} expected {}",
-                buf.len(),
-                N
-            )));
+        match Self::decode_hex(digits, 2) {
+            Ok(bytes) => Ok(bytes),
+            // a valid hex string of the wrong length
+            Err(hex::FromHexError::InvalidStringLength)
+                if digits.bytes().all(|c| c.is_ascii_hexdigit()) =>
+            {
+                Err(PyValueError::new_err(format!(
+                    "invalid length {} expected {}",
+                    digits.len() / 2,
+                    N
+                )))
+            }
+            Err(_) => Err(PyValueError::new_err("invalid hex")),
         }
-        Ok(buf.try_into().unwrap())
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+impl<const N: usize> FromJsonDict for BytesImpl<N> {
+    fn from_json_dict(o: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let s: String = o.extract()?;
+        Self::from_json_hex(&s)
+    }
+
+    fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+        Self::from_json_hex(json_str(v)?)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for BytesImpl<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_hex(&self.0, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for BytesImpl<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let buf = chia_traits::deserialize_hex_bytes(deserializer)?;
+        let len = buf.len();
+        buf.try_into()
+            .map_err(|_| serde::de::Error::custom(format!("invalid length {len} expected {N}")))
     }
 }
 
@@ -402,19 +620,28 @@ impl<'py, const N: usize> IntoPyObject<'py> for BytesImpl<N> {
 
 #[cfg(feature = "py-bindings")]
 impl<const N: usize> ChiaToPython for BytesImpl<N> {
+    // bytes objects are immutable
+    const CACHEABLE: bool = true;
+
     fn to_python<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        // the python types are looked up once, rather than on every
+        // conversion. Note that statics are shared across all N
+        static BYTES32: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+        static BYTES48: GILOnceCell<Py<PyType>> = GILOnceCell::new();
         if N == 32 {
-            let bytes_module = PyModule::import(py, "chia_rs.sized_bytes")?;
-            let ty = bytes_module.getattr("bytes32")?;
+            let ty = BYTES32.import(py, "chia_rs.sized_bytes", "bytes32")?;
             ty.call1((self.0.into_pyobject(py)?,))
         } else if N == 48 {
-            let bytes_module = PyModule::import(py, "chia_rs.sized_bytes")?;
-            let ty = bytes_module.getattr("bytes48")?;
+            let ty = BYTES48.import(py, "chia_rs.sized_bytes", "bytes48")?;
             ty.call1((self.0.into_pyobject(py)?,))
         } else {
             Ok(PyBytes::new(py, &self.0).into_any())
         }
     }
+
+    fn py_type_name() -> String {
+        format!("bytes{N}")
+    }
 }
 
 #[cfg(feature = "py-bindings")]
@@ -443,6 +670,10 @@ impl ChiaToPython for Bytes {
     fn to_python<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         Ok(PyBytes::new(py, &self.0).into_any())
     }
+
+    fn py_type_name() -> String {
+        "bytes".to_string()
+    }
 }
 
 #[cfg(feature = "py-bindings")]
@@ -458,6 +689,7 @@ impl<'py> FromPyObject<'py> for Bytes {
 mod tests {
     use super::*;
 
+    use chia_traits::{ParseLimit, ParseLimits};
     use clvmr::{
         serde::{node_from_bytes, node_to_bytes},
         Allocator,
@@ -563,6 +795,13 @@ mod tests {
         assert_eq!(buf.as_slice(), &out);
     }
 
+    #[test]
+    fn test_display_bytes32() {
+        let val = Bytes32::new([0xab; 32]);
+        assert_eq!(format!("{val}"), "ab".repeat(32));
+        assert_eq!(format!("{val:.8}"), "abababab");
+    }
+
     #[test]
     fn test_stream_bytes() {
         let val: Bytes = vec![
@@ -600,6 +839,27 @@ mod tests {
         from_bytes_fail::<Bytes>(buf, chia_error::Error::EndOfBuffer);
     }
 
+    #[test]
+    fn test_parse_bytes_limits() {
+        let limits = ParseLimits {
+            max_bytes_len: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            Bytes::parse_with_limits(&[0, 0, 0, 2, 1, 2], limits),
+            Ok(Bytes::from(vec![1, 2]))
+        );
+        assert_eq!(
+            Bytes::parse_with_limits(&[0, 0, 0, 3, 1, 2, 3], limits),
+            Err(chia_error::Error::LimitExceeded(ParseLimit::BytesLen))
+        );
+        // a length prefix claiming 4 GB
+        assert_eq!(
+            Bytes::parse_with_limits(&[0xff, 0xff, 0xff, 0xff], limits),
+            Err(chia_error::Error::LimitExceeded(ParseLimit::BytesLen))
+        );
+    }
+
     #[test]
     fn test_parse_bytes32() {
         let buf = [
@@ -634,6 +894,124 @@ mod tests {
         assert_eq!(expected, hex::encode(node_to_bytes(a, round_trip).unwrap()));
     }
 
+    #[rstest]
+    #[case("0101010101010101010101010101010101010101010101010101010101010101")]
+    #[case("0x0101010101010101010101010101010101010101010101010101010101010101")]
+    #[case("0X0101010101010101010101010101010101010101010101010101010101010101")]
+    fn bytes32_from_hex(#[case] input: &str) {
+        assert_eq!(Bytes32::from_hex(input), Bytes32::new([1; 32]));
+    }
+
+    #[test]
+    fn bytes_from_hex() {
+        const HASH: Bytes32 =
+            crate::bytes32!("ccd5bb71183532bff220ba46c268991a3ff07eb358e8255a65c30a2dce0e5fbb");
+        assert_eq!(
+            HASH.to_vec(),
+            hex::decode("ccd5bb71183532bff220ba46c268991a3ff07eb358e8255a65c30a2dce0e5fbb")
+                .unwrap()
+        );
+        assert_eq!(
+            BytesImpl::<4>::from_hex("0xABCDef09"),
+            BytesImpl::<4>::new([0xab, 0xcd, 0xef, 0x09])
+        );
+        assert_eq!(BytesImpl::<0>::from_hex(""), BytesImpl::<0>::new([]));
+    }
+
+    #[rstest]
+    // 31 bytes
+    #[case("01010101010101010101010101010101010101010101010101010101010101")]
+    // 33 bytes
+    #[case("010101010101010101010101010101010101010101010101010101010101010101")]
+    #[case("0x")]
+    #[case("0g01010101010101010101010101010101010101010101010101010101010101")]
+    #[should_panic(expected = "invalid")]
+    fn bytes32_from_hex_invalid(#[case] input: &str) {
+        let _ = Bytes32::from_hex(input);
+    }
+
+    #[rstest]
+    #[case("0101010101010101010101010101010101010101010101010101010101010101")]
+    #[case("0x0101010101010101010101010101010101010101010101010101010101010101")]
+    #[case("0X0101010101010101010101010101010101010101010101010101010101010101")]
+    fn bytes32_try_from_hex(#[case] input: &str) {
+        assert_eq!(Bytes32::try_from_hex(input), Ok(Bytes32::new([1; 32])));
+        assert_eq!(
+            BytesImpl::<4>::try_from_hex("0xABCDef09"),
+            Ok(BytesImpl::<4>::new([0xab, 0xcd, 0xef, 0x09]))
+        );
+        assert_eq!(
+            BytesImpl::<0>::try_from_hex(""),
+            Ok(BytesImpl::<0>::new([]))
+        );
+    }
+
+    #[rstest]
+    #[case("0", hex::FromHexError::OddLength)]
+    #[case("0x010", hex::FromHexError::OddLength)]
+    #[case("", hex::FromHexError::InvalidStringLength)]
+    #[case("0x", hex::FromHexError::InvalidStringLength)]
+    #[case("010203", hex::FromHexError::InvalidStringLength)]
+    #[case("0102030405", hex::FromHexError::InvalidStringLength)]
+    #[case("0x0x010203", hex::FromHexError::InvalidHexCharacter { c: 'x', index: 3 })]
+    #[case("0g010203", hex::FromHexError::InvalidHexCharacter { c: 'g', index: 1 })]
+    #[case("0x010203 4", hex::FromHexError::InvalidHexCharacter { c: ' ', index: 8 })]
+    #[case("0102\u{e9}45", hex::FromHexError::InvalidHexCharacter { c: '\u{e9}', index: 4 })]
+    fn bytes4_try_from_hex_invalid(#[case] input: &str, #[case] expected: hex::FromHexError) {
+        assert_eq!(BytesImpl::<4>::try_from_hex(input), Err(expected));
+    }
+
+    #[test]
+    fn to_hex_into() {
+        let mut buf = "0x".to_string();
+        Bytes32::new([0xab; 32]).to_hex_into(&mut buf);
+        Bytes::from(vec![0, 1, 0xfe]).to_hex_into(&mut buf);
+        assert_eq!(buf, format!("0x{}0001fe", "ab".repeat(32)));
+
+        // the formats are the same as `hex::encode()`, for any length
+        for len in [0, 1, 63, 64, 65, 200] {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 37) as u8).collect();
+            let mut buf = String::new();
+            Bytes::from(bytes.clone()).to_hex_into(&mut buf);
+            assert_eq!(buf, hex::encode(&bytes));
+            assert_eq!(format!("{:?}", Bytes::from(bytes.clone())), buf);
+            assert_eq!(format!("{}", Bytes::from(bytes)), buf);
+        }
+        let hash = Bytes32::new([0x5a; 32]);
+        assert_eq!(format!("{hash:?}"), hex::encode(hash));
+        assert_eq!(format!("{hash:.8}"), "5a5a5a5a");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let bytes = Bytes::new(vec![0xca, 0xfe]);
+        let json = serde_json::to_string(&bytes).unwrap();
+        assert_eq!(json, "\"0xcafe\"");
+        assert_eq!(serde_json::from_str::<Bytes>(&json).unwrap(), bytes);
+        assert_eq!(
+            serde_json::from_str::<Bytes>("\"0x\"").unwrap(),
+            Bytes::default()
+        );
+
+        let hash = Bytes32::new([0xab; 32]);
+        let json = serde_json::to_string(&hash).unwrap();
+        assert_eq!(json, format!("\"0x{}\"", "ab".repeat(32)));
+        assert_eq!(serde_json::from_str::<Bytes32>(&json).unwrap(), hash);
+        // the prefix is optional
+        assert_eq!(
+            serde_json::from_str::<Bytes32>(&json.replace("0x", "")).unwrap(),
+            hash
+        );
+        assert!(serde_json::from_str::<Bytes32>("\"0xcafe\"").is_err());
+        assert!(serde_json::from_str::<Bytes>("\"0xzz\"").is_err());
+
+        // binary formats get the raw bytes
+        let encoded = bincode::serialize(&hash).unwrap();
+        assert_eq!(encoded[8..], hash[..]);
+        assert_eq!(bincode::deserialize::<Bytes32>(&encoded).unwrap(), hash);
+    }
+
     #[test]
     fn bytes32_roundtrip() {
         let a = &mut Allocator::new();
@@ -661,4 +1039,30 @@ mod tests {
             FromClvmError::ExpectedAtom
         );
     }
+    #[test]
+    fn bytes_from_clvm_bytes() {
+        use clvm_traits::FromClvmBytes;
+
+        // a hint followed by memos, decoded without an allocator
+        let hint = Bytes32::new([7; 32]);
+        let memos = vec![Bytes::from(b"memo".as_slice()), Bytes::default()];
+        let a = &mut Allocator::new();
+        let ptr = (hint, memos.clone()).to_clvm(a).unwrap();
+        let serialized = node_to_bytes(a, ptr).unwrap();
+        assert_eq!(
+            <(Bytes32, Vec<Bytes>)>::from_clvm_bytes(&serialized).unwrap(),
+            (hint, memos)
+        );
+
+        // the hint has the wrong length
+        let ptr = (Bytes::from(vec![7; 31]), ()).to_clvm(a).unwrap();
+        let serialized = node_to_bytes(a, ptr).unwrap();
+        assert_eq!(
+            <(Bytes32, Vec<Bytes>)>::from_clvm_bytes(&serialized).unwrap_err(),
+            FromClvmError::WrongAtomLength {
+                expected: 32,
+                found: 31
+            }
+        );
+    }
 }
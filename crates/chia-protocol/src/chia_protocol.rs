@@ -141,6 +141,9 @@ pub enum ProtocolMessageTypes {
     MempoolItemsRemoved = 105,
     RequestCostInfo = 106,
     RespondCostInfo = 107,
+
+    // Sent in place of the response to a request that failed
+    Error = 255,
 }
 
 #[cfg(feature = "py-bindings")]
@@ -150,6 +153,10 @@ impl chia_traits::ChiaToPython for ProtocolMessageTypes {
             .clone()
             .into_any())
     }
+
+    fn py_type_name() -> String {
+        "int".to_string()
+    }
 }
 
 pub trait ChiaProtocolMessage {
@@ -177,6 +184,10 @@ impl chia_traits::ChiaToPython for NodeType {
             .clone()
             .into_any())
     }
+
+    fn py_type_name() -> String {
+        "int".to_string()
+    }
 }
 
 #[streamable]
@@ -201,3 +212,100 @@ pub struct Handshake {
     // Key value dict to signal support for additional capabilities/features
     capabilities: Vec<(u16, String)>,
 }
+
+/// The message a peer sends in place of the response to a request it failed
+/// to handle, with the same message id as the request. `code` is one of the
+/// [`ProtocolErrorCode`]s, but peers may send codes that aren't known here.
+#[streamable]
+pub struct ProtocolError {
+    code: i16,
+    message: String,
+    data: Option<Bytes>,
+}
+
+impl ChiaProtocolMessage for ProtocolError {
+    fn msg_type() -> ProtocolMessageTypes {
+        ProtocolMessageTypes::Error
+    }
+}
+
+impl ProtocolError {
+    pub fn error_code(&self) -> ProtocolErrorCode {
+        ProtocolErrorCode::from(self.code)
+    }
+}
+
+/// The error codes of a [`ProtocolError`].
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum ProtocolErrorCode {
+    /// The peer failed to handle the request, for an unspecified reason.
+    Unknown,
+    /// The request couldn't be parsed, or isn't allowed on this connection.
+    InvalidProtocolMessage,
+    /// The peer is limiting the rate of requests of this type. The data may
+    /// hold the number of seconds to wait before retrying, as a big-endian
+    /// `u32`.
+    RateLimited,
+    /// The request depends on a capability the peer doesn't have, or that
+    /// wasn't negotiated in the handshake.
+    UnknownCapability,
+    /// A code that isn't known to this version.
+    Other(i16),
+}
+
+impl From<i16> for ProtocolErrorCode {
+    fn from(code: i16) -> Self {
+        match code {
+            1 => Self::Unknown,
+            -4 => Self::InvalidProtocolMessage,
+            -100 => Self::RateLimited,
+            -101 => Self::UnknownCapability,
+            code => Self::Other(code),
+        }
+    }
+}
+
+impl From<ProtocolErrorCode> for i16 {
+    fn from(code: ProtocolErrorCode) -> Self {
+        match code {
+            ProtocolErrorCode::Unknown => 1,
+            ProtocolErrorCode::InvalidProtocolMessage => -4,
+            ProtocolErrorCode::RateLimited => -100,
+            ProtocolErrorCode::UnknownCapability => -101,
+            ProtocolErrorCode::Other(code) => code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chia_traits::Streamable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, ProtocolErrorCode::Unknown)]
+    #[case(-4, ProtocolErrorCode::InvalidProtocolMessage)]
+    #[case(-100, ProtocolErrorCode::RateLimited)]
+    #[case(-101, ProtocolErrorCode::UnknownCapability)]
+    #[case(0, ProtocolErrorCode::Other(0))]
+    #[case(-1, ProtocolErrorCode::Other(-1))]
+    fn test_error_code(#[case] code: i16, #[case] expected: ProtocolErrorCode) {
+        assert_eq!(ProtocolErrorCode::from(code), expected);
+        assert_eq!(i16::from(expected), code);
+    }
+
+    #[test]
+    fn test_protocol_error() {
+        let error = ProtocolError::new(
+            -100,
+            "rate limited".to_string(),
+            Some(vec![0, 0, 0, 5].into()),
+        );
+        let bytes = error.to_bytes().unwrap();
+        assert_eq!(&bytes[..2], &[0xff, 0x9c]);
+        let error = ProtocolError::from_bytes(&bytes).unwrap();
+        assert_eq!(error.error_code(), ProtocolErrorCode::RateLimited);
+        assert_eq!(ProtocolError::msg_type(), ProtocolMessageTypes::Error);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::Bytes100;
 use chia_streamable_macro::streamable;
+use std::fmt;
 
 #[cfg(feature = "py-bindings")]
 use pyo3::prelude::*;
@@ -18,8 +19,96 @@ impl Default for ClassgroupElement {
     }
 }
 
+// flags in the first byte of the compressed form
+const B_SIGN: u8 = 1 << 0;
+const T_SIGN: u8 = 1 << 1;
+const IS_IDENTITY: u8 = 1 << 2;
+const IS_GENERATOR: u8 = 1 << 3;
+
+// the compressed form is sized for a 1024 bit discriminant. These are the
+// sizes (in bytes) of the a' and t' fields, before subtracting g_size
+const A_SIZE: usize = 1024 / 16;
+const T_SIZE: usize = 1024 / 32;
+
 impl ClassgroupElement {
     pub const SIZE: usize = 100;
+
+    /// The generator, which is the output of a VDF with 0 iterations. Same as
+    /// `default()`.
+    pub fn get_default_element() -> Self {
+        Self::default()
+    }
+
+    pub const fn get_size() -> usize {
+        Self::SIZE
+    }
+
+    /// Performs the format checks of the 100 byte compressed form (as
+    /// produced by chiavdf) that can be done without knowing the
+    /// discriminant. An element failing these checks can never be part of a
+    /// valid proof, but passing them doesn't mean the element is valid. That
+    /// requires decompressing it.
+    pub fn is_canonical(&self) -> bool {
+        let data: &[u8] = self.data.as_ref();
+        let flags = data[0];
+
+        if (flags & (IS_IDENTITY | IS_GENERATOR)) != 0 {
+            // the identity and the generator are encoded by their flag alone,
+            // followed by zeros
+            return (flags == IS_IDENTITY || flags == IS_GENERATOR)
+                && data[1..].iter().all(|b| *b == 0);
+        }
+
+        if (flags & !(B_SIGN | T_SIGN)) != 0 {
+            return false;
+        }
+
+        // the layout is: flags, g_size, a', t', g, b0. The sizes of the
+        // integer fields depend on g_size, and they're all little-endian
+        let g_size = data[1] as usize;
+        if g_size >= T_SIZE {
+            return false;
+        }
+        let (a, rest) = data[2..].split_at(A_SIZE - g_size);
+        let (t, rest) = rest.split_at(T_SIZE - g_size);
+        let (g, b0) = rest.split_at(g_size + 1);
+        debug_assert_eq!(b0.len(), g_size + 1);
+
+        // a' is a factor of a, which is positive
+        if a.iter().all(|b| *b == 0) {
+            return false;
+        }
+
+        // g_size is derived from the size of g, so its most significant byte
+        // can't be zero (this also means g is non-zero)
+        if g[g_size] == 0 {
+            return false;
+        }
+
+        // zero has no sign
+        if (flags & T_SIGN) != 0 && t.iter().all(|b| *b == 0) {
+            return false;
+        }
+
+        true
+    }
+}
+
+// the identity and the generator are printed by name, any other element by
+// the first 8 bytes of its compressed form
+impl fmt::Display for ClassgroupElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data: &[u8] = self.data.as_ref();
+        if data[1..].iter().all(|b| *b == 0) {
+            if data[0] == IS_GENERATOR {
+                return f.write_str("generator");
+            }
+            if data[0] == IS_IDENTITY {
+                return f.write_str("identity");
+            }
+        }
+        write!(f, "{}..", hex::encode(&data[..8]))
+    }
 }
 
 #[cfg(feature = "py-bindings")]
@@ -42,12 +131,104 @@ impl ClassgroupElement {
     #[staticmethod]
     #[pyo3(name = "get_default_element")]
     pub fn py_get_default_element() -> ClassgroupElement {
-        Self::default()
+        Self::get_default_element()
     }
 
     #[staticmethod]
     #[pyo3(name = "get_size")]
     pub fn py_get_size() -> i32 {
-        Self::SIZE as i32
+        Self::get_size() as i32
+    }
+
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+
+    #[pyo3(name = "is_canonical")]
+    fn py_is_canonical(&self) -> bool {
+        self.is_canonical()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    // an element in the general compressed form, with the given flags and
+    // g_size. All fields are filled with non-zero values
+    fn element(flags: u8, g_size: u8) -> [u8; 100] {
+        let mut data = [0x55_u8; 100];
+        data[0] = flags;
+        data[1] = g_size;
+        data
+    }
+
+    fn identity() -> [u8; 100] {
+        let mut data = [0_u8; 100];
+        data[0] = IS_IDENTITY;
+        data
+    }
+
+    #[rstest]
+    #[case(ClassgroupElement::default().data.into())]
+    #[case(identity())]
+    #[case(element(0, 0))]
+    #[case(element(B_SIGN, 0))]
+    #[case(element(T_SIGN, 0))]
+    #[case(element(B_SIGN | T_SIGN, 0))]
+    #[case(element(0, 5))]
+    #[case(element(0, 31))]
+    fn test_canonical(#[case] data: [u8; 100]) {
+        assert!(ClassgroupElement::new(data.into()).is_canonical());
+    }
+
+    #[rstest]
+    // unknown flags
+    #[case(element(0x10, 0))]
+    #[case(element(0x80, 0))]
+    // both identity and generator
+    #[case({ let mut d = identity(); d[0] |= IS_GENERATOR; d })]
+    // identity or generator with other flags
+    #[case({ let mut d = identity(); d[0] |= B_SIGN; d })]
+    // identity or generator with trailing data
+    #[case({ let mut d = identity(); d[99] = 1; d })]
+    #[case({ let mut d: [u8; 100] = ClassgroupElement::default().data.into(); d[1] = 1; d })]
+    // g_size too large
+    #[case(element(0, 32))]
+    #[case(element(0, 255))]
+    // a' is zero
+    #[case({ let mut d = element(0, 0); d[2..66].fill(0); d })]
+    // most significant byte of g is zero
+    #[case({ let mut d = element(0, 0); d[98] = 0; d })]
+    #[case({ let mut d = element(0, 3); d[95] = 0; d })]
+    // negative zero
+    #[case({ let mut d = element(T_SIGN, 0); d[66..98].fill(0); d })]
+    fn test_not_canonical(#[case] data: [u8; 100]) {
+        assert!(!ClassgroupElement::new(data.into()).is_canonical());
+    }
+
+    #[test]
+    fn test_default_element() {
+        let default = ClassgroupElement::get_default_element();
+        assert_eq!(default, ClassgroupElement::default());
+        assert_eq!(default.data.as_ref()[0], IS_GENERATOR);
+        assert_eq!(ClassgroupElement::get_size(), 100);
+    }
+
+    #[rstest]
+    #[case(ClassgroupElement::default().data.into(), "generator")]
+    #[case(identity(), "identity")]
+    #[case(element(B_SIGN, 3), "0103555555555555..")]
+    #[case({ let mut d = identity(); d[99] = 1; d }, "0400000000000000..")]
+    fn test_display(#[case] data: [u8; 100], #[case] expected: &str) {
+        assert_eq!(ClassgroupElement::new(data.into()).to_string(), expected);
+    }
+
+    #[test]
+    fn test_positive_zero() {
+        let mut data = element(0, 0);
+        data[66..98].fill(0);
+        assert!(ClassgroupElement::new(data.into()).is_canonical());
     }
 }
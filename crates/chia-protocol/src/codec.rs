@@ -0,0 +1,254 @@
+use ::bytes::BytesMut;
+use chia_traits::chia_error;
+use chia_traits::Streamable;
+use thiserror::Error;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{Message, ProtocolMessageTypes};
+
+/// The largest message a peer is expected to send, including the header. This
+/// matches the limit of the websocket connections in the reference client.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 50 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum CodecError {
+    #[error("unknown message type {0}")]
+    UnknownMessageType(u8),
+
+    #[error("message of {size} bytes exceeds the limit of {max} bytes")]
+    MessageTooLarge { size: usize, max: usize },
+
+    #[error("invalid message: {0}")]
+    InvalidMessage(#[from] chia_error::Error),
+
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Converts between the binary framing used by peers and [`Message`]s. Each
+/// frame is a streamable `Message`: the message type (`u8`), an optional
+/// request id (`u16`) and the length prefixed data.
+///
+/// The decoder can be used on a byte stream, where it waits for a whole frame
+/// to arrive, or on transports that already deliver one message at a time,
+/// such as websockets, with [`MessageCodec::decode_frame()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageCodec {
+    max_message_size: usize,
+}
+
+impl Default for MessageCodec {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_MESSAGE_SIZE)
+    }
+}
+
+impl MessageCodec {
+    pub fn new(max_message_size: usize) -> Self {
+        Self { max_message_size }
+    }
+
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    /// Decodes a frame that holds exactly one message.
+    pub fn decode_frame(&self, frame: &[u8]) -> Result<Message, CodecError> {
+        let mut codec = *self;
+        let mut buf = BytesMut::from(frame);
+        match codec.decode(&mut buf)? {
+            Some(message) if buf.is_empty() => Ok(message),
+            Some(_) => Err(chia_error::Error::InputTooLarge.into()),
+            None => Err(chia_error::Error::EndOfBuffer.into()),
+        }
+    }
+
+    /// Encodes a message into a frame of its own.
+    pub fn encode_frame(&self, message: Message) -> Result<Vec<u8>, CodecError> {
+        let mut codec = *self;
+        let mut buf = BytesMut::new();
+        codec.encode(message, &mut buf)?;
+        Ok(buf.to_vec())
+    }
+
+    fn check_size(self, size: usize) -> Result<(), CodecError> {
+        if size > self.max_message_size {
+            return Err(CodecError::MessageTooLarge {
+                size,
+                max: self.max_message_size,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Decoder for MessageCodec {
+    type Item = Message;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Message>, CodecError> {
+        let Some(&msg_type) = src.first() else {
+            return Ok(None);
+        };
+        // Check the type up front, since the enum conversion can only tell
+        // that it failed, not why.
+        if ProtocolMessageTypes::from_bytes(&[msg_type]).is_err() {
+            return Err(CodecError::UnknownMessageType(msg_type));
+        }
+
+        // msg_type, the optional id and the length of the data
+        let header_size = match src.get(1) {
+            None => return Ok(None),
+            Some(0) => 1 + 1 + 4,
+            Some(1) => 1 + 3 + 4,
+            Some(_) => return Err(chia_error::Error::InvalidOptional.into()),
+        };
+        let Some(len) = src.get(header_size - 4..header_size) else {
+            return Ok(None);
+        };
+
+        // Reject oversized messages before waiting for their data.
+        let data_size = u32::from_be_bytes(len.try_into().unwrap());
+        let size = usize::try_from(data_size)
+            .ok()
+            .and_then(|data_size| data_size.checked_add(header_size))
+            .unwrap_or(usize::MAX);
+        self.check_size(size)?;
+
+        if src.len() < size {
+            src.reserve(size - src.len());
+            return Ok(None);
+        }
+
+        let frame = src.split_to(size);
+        Ok(Some(Message::from_bytes(&frame)?))
+    }
+}
+
+impl Encoder<Message> for MessageCodec {
+    type Error = CodecError;
+
+    fn encode(&mut self, item: Message, dst: &mut BytesMut) -> Result<(), CodecError> {
+        let bytes = item.to_bytes()?;
+        self.check_size(bytes.len())?;
+        dst.extend_from_slice(&bytes);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn message(id: Option<u16>, data: &[u8]) -> Message {
+        Message::new(
+            ProtocolMessageTypes::RequestChildren,
+            id,
+            data.to_vec().into(),
+        )
+    }
+
+    #[rstest]
+    #[case::with_id(message(Some(7), &[1, 2, 3]), "4a01000700000003010203")]
+    #[case::without_id(message(None, &[1, 2, 3]), "4a0000000003010203")]
+    #[case::empty(message(None, &[]), "4a0000000000")]
+    fn test_round_trip(#[case] message: Message, #[case] frame: &str) {
+        let mut codec = MessageCodec::default();
+        let frame = hex::decode(frame).unwrap();
+
+        assert_eq!(codec.encode_frame(message.clone()).unwrap(), frame);
+        assert_eq!(codec.decode_frame(&frame).unwrap(), message);
+
+        // On a byte stream, nothing is returned until the whole frame has
+        // arrived.
+        let mut buf = BytesMut::new();
+        for byte in &frame {
+            assert!(codec.decode(&mut buf).unwrap().is_none());
+            buf.extend_from_slice(&[*byte]);
+        }
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(message));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_stream() {
+        let mut codec = MessageCodec::default();
+        let mut buf = BytesMut::new();
+        codec.encode(message(Some(1), &[1]), &mut buf).unwrap();
+        codec.encode(message(None, &[2, 3]), &mut buf).unwrap();
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(message(Some(1), &[1]))
+        );
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(message(None, &[2, 3]))
+        );
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn test_oversized() {
+        let mut codec = MessageCodec::new(16);
+
+        // 1 + 3 + 4 bytes of header and 8 bytes of data is just within the
+        // limit
+        let frame = codec.encode_frame(message(Some(1), &[0; 8])).unwrap();
+        assert_eq!(
+            codec.decode_frame(&frame).unwrap(),
+            message(Some(1), &[0; 8])
+        );
+
+        let error = codec.encode_frame(message(Some(1), &[0; 9])).unwrap_err();
+        assert!(matches!(
+            error,
+            CodecError::MessageTooLarge { size: 17, max: 16 }
+        ));
+
+        // The header is enough to reject a message, without its data.
+        let mut buf = BytesMut::from(&hex::decode("4a0000001000").unwrap()[..]);
+        let error = codec.decode(&mut buf).unwrap_err();
+        assert!(matches!(
+            error,
+            CodecError::MessageTooLarge {
+                size: 4102,
+                max: 16
+            }
+        ));
+    }
+
+    #[rstest]
+    #[case::unassigned(2)]
+    #[case::after_last(108)]
+    #[case::before_error(254)]
+    #[case::zero(0)]
+    fn test_unknown_message_type(#[case] msg_type: u8) {
+        let mut codec = MessageCodec::default();
+        let mut frame = hex::decode("4a0000000003010203").unwrap();
+        frame[0] = msg_type;
+
+        let error = codec.decode_frame(&frame).unwrap_err();
+        assert!(matches!(error, CodecError::UnknownMessageType(t) if t == msg_type));
+
+        // The type is checked as soon as it arrives.
+        let mut buf = BytesMut::from(&frame[..1]);
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(CodecError::UnknownMessageType(_))
+        ));
+    }
+
+    #[rstest]
+    #[case::invalid_id_flag("4a0200000003010203")]
+    #[case::truncated("4a00000000030102")]
+    #[case::trailing_bytes("4a000000000301020304")]
+    fn test_invalid_frame(#[case] frame: &str) {
+        let codec = MessageCodec::default();
+        let error = codec
+            .decode_frame(&hex::decode(frame).unwrap())
+            .unwrap_err();
+        assert!(matches!(error, CodecError::InvalidMessage(_)));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Bytes32, BytesImpl};
+use crate::{Amount, Bytes32, BytesImpl};
 use chia_sha2::Sha256;
 use chia_streamable_macro::streamable;
 use clvm_traits::{
@@ -13,12 +13,12 @@ use pyo3::prelude::*;
 #[cfg(feature = "py-bindings")]
 use pyo3::types::PyType;
 
-#[streamable]
+#[streamable(cached_getters)]
 #[derive(Copy)]
 pub struct Coin {
     parent_coin_info: Bytes32,
     puzzle_hash: Bytes32,
-    amount: u64,
+    amount: Amount,
 }
 
 impl Coin {
@@ -27,12 +27,13 @@ impl Coin {
         hasher.update(self.parent_coin_info);
         hasher.update(self.puzzle_hash);
 
-        let amount_bytes = self.amount.to_be_bytes();
-        if self.amount >= 0x8000_0000_0000_0000_u64 {
+        let amount = u64::from(self.amount);
+        let amount_bytes = amount.to_be_bytes();
+        if amount >= 0x8000_0000_0000_0000_u64 {
             hasher.update([0_u8]);
             hasher.update(amount_bytes);
         } else {
-            let start = match self.amount {
+            let start = match amount {
                 n if n >= 0x0080_0000_0000_0000_u64 => 0,
                 n if n >= 0x8000_0000_0000_u64 => 1,
                 n if n >= 0x0080_0000_0000_u64 => 2,
@@ -80,7 +81,7 @@ impl<N, E: ClvmEncoder<Node = N>> ToClvm<E> for Coin {
 impl<N, D: ClvmDecoder<Node = N>> FromClvm<D> for Coin {
     fn from_clvm(decoder: &D, node: N) -> Result<Self, FromClvmError> {
         let destructure_list!(parent_coin_info, puzzle_hash, amount) =
-            <match_list!(BytesImpl<32>, BytesImpl<32>, u64)>::from_clvm(decoder, node)?;
+            <match_list!(BytesImpl<32>, BytesImpl<32>, Amount)>::from_clvm(decoder, node)?;
         Ok(Coin {
             parent_coin_info,
             puzzle_hash,
@@ -123,7 +124,7 @@ mod tests {
         let parent_coin = b"---foo---                       ";
         let puzzle_hash = b"---bar---                       ";
 
-        let c = Coin::new(parent_coin.into(), puzzle_hash.into(), amount);
+        let c = Coin::new(parent_coin.into(), puzzle_hash.into(), amount.into());
         let mut sha256 = Sha256::new();
         sha256.update(parent_coin);
         sha256.update(puzzle_hash);
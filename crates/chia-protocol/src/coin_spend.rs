@@ -14,6 +14,15 @@ pub struct CoinSpend {
     solution: Program,
 }
 
+impl CoinSpend {
+    /// The number of bytes this spend serializes to, computed from the sizes
+    /// of its fields rather than by serializing it.
+    pub fn serialized_length(&self) -> usize {
+        // parent coin ID, puzzle hash and amount
+        32 + 32 + 8 + self.puzzle_reveal.len() + self.solution.len()
+    }
+}
+
 #[cfg(feature = "py-bindings")]
 #[pymethods]
 impl CoinSpend {
@@ -25,4 +34,34 @@ impl CoinSpend {
 
         Ok(instance.into_pyobject(py)?.unbind())
     }
+
+    #[pyo3(name = "serialized_length")]
+    fn py_serialized_length(&self) -> usize {
+        self.serialized_length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Amount, Bytes32};
+    use chia_traits::Streamable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("80", "80")]
+    #[case("01", "ff8080")]
+    #[case("ff02ffff0101ff0180", "ff8568656c6c6f80")]
+    fn test_serialized_length(#[case] puzzle: &str, #[case] solution: &str) {
+        let spend = CoinSpend::new(
+            Coin::new(
+                Bytes32::new([1; 32]),
+                Bytes32::new([2; 32]),
+                Amount::new(1337),
+            ),
+            Program::new(hex::decode(puzzle).unwrap().into()),
+            Program::new(hex::decode(solution).unwrap().into()),
+        );
+        assert_eq!(spend.serialized_length(), spend.to_bytes().unwrap().len());
+    }
 }
@@ -0,0 +1,281 @@
+use std::collections::BTreeMap;
+
+use crate::{Bytes32, CoinState};
+
+#[cfg(feature = "py-bindings")]
+use pyo3::prelude::*;
+
+/// How a batch of [`CoinState`] updates differs from a wallet's local view of
+/// the same coins, as computed by [`diff_coin_states`].
+///
+/// Every coin in the batch ends up in exactly one of the lists, each of which
+/// is sorted by coin id. Coins that are only in the local view aren't part of
+/// the delta, since a batch of updates rarely covers every coin.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "py-bindings",
+    pyo3::pyclass(name = "CoinStateDelta", get_all, frozen)
+)]
+pub struct CoinStateDelta {
+    /// Coins that weren't known before.
+    pub created: Vec<CoinState>,
+    /// Known coins that weren't spent before, but are now.
+    pub spent: Vec<CoinState>,
+    /// Known coins whose created or spent height went back to `None`, or to a
+    /// lower height. Anything derived from the old state of these coins has to
+    /// be rolled back.
+    pub reorged: Vec<CoinState>,
+    /// Known coins whose heights moved forward in any other way, such as a
+    /// pending coin being confirmed.
+    pub updated: Vec<CoinState>,
+    /// Known coins whose state didn't change.
+    pub unchanged: Vec<CoinState>,
+}
+
+impl CoinStateDelta {
+    /// Returns true if the batch doesn't change anything.
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty()
+            && self.spent.is_empty()
+            && self.reorged.is_empty()
+            && self.updated.is_empty()
+    }
+
+    /// The highest created or spent height in the batch, which is where the
+    /// next sync can pick up from. Returns `None` if no coin has a height.
+    pub fn max_height(&self) -> Option<u32> {
+        [
+            &self.created,
+            &self.spent,
+            &self.reorged,
+            &self.updated,
+            &self.unchanged,
+        ]
+        .into_iter()
+        .flatten()
+        .flat_map(|state| [state.created_height, state.spent_height])
+        .flatten()
+        .max()
+    }
+}
+
+/// Deduplicates the coin states by coin id. If a coin appears more than once,
+/// the state with the highest spent height (then created height) wins.
+fn by_coin_id(states: &[CoinState]) -> BTreeMap<Bytes32, CoinState> {
+    let mut map = BTreeMap::<Bytes32, CoinState>::new();
+    for state in states {
+        map.entry(state.coin.coin_id())
+            .and_modify(|existing| {
+                if (state.spent_height, state.created_height)
+                    > (existing.spent_height, existing.created_height)
+                {
+                    *existing = *state;
+                }
+            })
+            .or_insert(*state);
+    }
+    map
+}
+
+fn went_back(old: Option<u32>, new: Option<u32>) -> bool {
+    match (old, new) {
+        (Some(old), Some(new)) => new < old,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+/// Compares a batch of coin state updates (`new`) against the states a wallet
+/// already has for those coins (`old`), keyed by coin id.
+///
+/// Either input may contain several states for the same coin, in which case
+/// only the one with the highest heights is considered.
+pub fn diff_coin_states(old: &[CoinState], new: &[CoinState]) -> CoinStateDelta {
+    let old = by_coin_id(old);
+    let mut delta = CoinStateDelta::default();
+
+    for (coin_id, state) in by_coin_id(new) {
+        let Some(previous) = old.get(&coin_id) else {
+            delta.created.push(state);
+            continue;
+        };
+
+        if *previous == state {
+            delta.unchanged.push(state);
+        } else if went_back(previous.created_height, state.created_height)
+            || went_back(previous.spent_height, state.spent_height)
+        {
+            delta.reorged.push(state);
+        } else if previous.spent_height.is_none() && state.spent_height.is_some() {
+            delta.spent.push(state);
+        } else {
+            delta.updated.push(state);
+        }
+    }
+
+    delta
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl CoinStateDelta {
+    #[pyo3(name = "is_empty")]
+    fn py_is_empty(&self) -> bool {
+        self.is_empty()
+    }
+
+    #[pyo3(name = "max_height")]
+    fn py_max_height(&self) -> Option<u32> {
+        self.max_height()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Coin;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use rstest::rstest;
+
+    fn state(i: u8, created_height: Option<u32>, spent_height: Option<u32>) -> CoinState {
+        let coin = Coin::new(Bytes32::new([i; 32]), Bytes32::new([0xff; 32]), 1.into());
+        CoinState::new(coin, spent_height, created_height)
+    }
+
+    #[rstest]
+    #[case::created(None, state(1, Some(10), None), "created")]
+    #[case::created_and_spent(None, state(1, Some(10), Some(12)), "created")]
+    #[case::unchanged(Some(state(1, Some(10), None)), state(1, Some(10), None), "unchanged")]
+    #[case::spent(Some(state(1, Some(10), None)), state(1, Some(10), Some(12)), "spent")]
+    #[case::confirmed(Some(state(1, None, None)), state(1, Some(10), None), "updated")]
+    #[case::respent_later(
+        Some(state(1, Some(10), Some(12))),
+        state(1, Some(10), Some(13)),
+        "updated"
+    )]
+    #[case::unspent(
+        Some(state(1, Some(10), Some(12))),
+        state(1, Some(10), None),
+        "reorged"
+    )]
+    #[case::uncreated(Some(state(1, Some(10), None)), state(1, None, None), "reorged")]
+    #[case::created_earlier(Some(state(1, Some(10), None)), state(1, Some(9), None), "reorged")]
+    #[case::spent_earlier(
+        Some(state(1, Some(10), Some(12))),
+        state(1, Some(10), Some(11)),
+        "reorged"
+    )]
+    fn test_classify(
+        #[case] old: Option<CoinState>,
+        #[case] new: CoinState,
+        #[case] expected: &str,
+    ) {
+        let old: Vec<CoinState> = old.into_iter().collect();
+        let delta = diff_coin_states(&old, &[new]);
+
+        let lists = [
+            ("created", &delta.created),
+            ("spent", &delta.spent),
+            ("reorged", &delta.reorged),
+            ("updated", &delta.updated),
+            ("unchanged", &delta.unchanged),
+        ];
+        for (name, list) in lists {
+            if name == expected {
+                assert_eq!(list, &[new], "{name}");
+            } else {
+                assert!(list.is_empty(), "{name}");
+            }
+        }
+        assert_eq!(delta.is_empty(), expected == "unchanged");
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let old = [state(1, Some(10), Some(12)), state(1, Some(10), None)];
+        let new = [
+            state(1, Some(10), None),
+            state(1, Some(10), Some(12)),
+            state(1, None, None),
+        ];
+        let delta = diff_coin_states(&old, &new);
+        assert_eq!(delta.unchanged, [state(1, Some(10), Some(12))]);
+        assert!(delta.is_empty());
+    }
+
+    #[test]
+    fn test_max_height() {
+        assert_eq!(diff_coin_states(&[], &[]).max_height(), None);
+        assert_eq!(
+            diff_coin_states(&[], &[state(1, None, None)]).max_height(),
+            None
+        );
+
+        let old = [state(2, Some(20), Some(30))];
+        let new = [
+            state(1, Some(10), Some(12)),
+            state(2, Some(20), None),
+            state(3, Some(15), None),
+        ];
+        // the old state of coin 2 doesn't count, it was reorged away
+        assert_eq!(diff_coin_states(&old, &new).max_height(), Some(20));
+    }
+
+    fn random_state(rng: &mut StdRng) -> CoinState {
+        let height = |rng: &mut StdRng| {
+            if rng.gen_bool(0.2) {
+                None
+            } else {
+                Some(rng.gen_range(0..10))
+            }
+        };
+        state(rng.gen_range(0..20), height(rng), height(rng))
+    }
+
+    #[test]
+    fn test_apply_delta() {
+        let mut rng = StdRng::seed_from_u64(1337);
+        for _ in 0..1000 {
+            let old: Vec<CoinState> = (0..rng.gen_range(0..30))
+                .map(|_| random_state(&mut rng))
+                .collect();
+            let new: Vec<CoinState> = (0..rng.gen_range(0..30))
+                .map(|_| random_state(&mut rng))
+                .collect();
+            let delta = diff_coin_states(&old, &new);
+
+            // applying the delta to the old states reproduces the new ones,
+            // and leaves the coins that weren't in the batch alone
+            let mut applied = by_coin_id(&old);
+            for state in [
+                &delta.created,
+                &delta.spent,
+                &delta.reorged,
+                &delta.updated,
+                &delta.unchanged,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                applied.insert(state.coin.coin_id(), *state);
+            }
+
+            let mut expected = by_coin_id(&old);
+            expected.extend(by_coin_id(&new));
+            assert_eq!(applied, expected);
+
+            // the delta is the same no matter the order of the inputs
+            let mut old_shuffled = old.clone();
+            old_shuffled.reverse();
+            let mut new_shuffled = new.clone();
+            new_shuffled.reverse();
+            assert_eq!(diff_coin_states(&old_shuffled, &new_shuffled), delta);
+
+            // and a batch never differs from itself
+            let delta = diff_coin_states(&new, &new);
+            assert!(delta.is_empty());
+            assert_eq!(delta.unchanged.len(), by_coin_id(&new).len());
+        }
+    }
+}
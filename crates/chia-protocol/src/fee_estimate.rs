@@ -1,6 +1,13 @@
 use chia_streamable_macro::streamable;
 
+#[cfg(feature = "py-bindings")]
+use pyo3::prelude::*;
+
+/// A fee rate, in mojos per CLVM cost. It's serialized as a single `u64`,
+/// like chia-blockchain's `FeeRate`. The arithmetic is all integer math with
+/// explicit rounding, so every implementation agrees on the result.
 #[streamable]
+#[derive(Copy, PartialOrd, Ord)]
 pub struct FeeRate {
     // Represents Fee Rate in mojos divided by CLVM Cost.
     // Performs XCH/mojo conversion.
@@ -8,6 +15,82 @@ pub struct FeeRate {
     mojos_per_clvm_cost: u64,
 }
 
+impl FeeRate {
+    pub const ZERO: Self = Self {
+        mojos_per_clvm_cost: 0,
+    };
+
+    /// The fee paid at this rate by a transaction with the specified cost, or
+    /// None if it doesn't fit in a u64.
+    pub fn fee_for_cost(self, cost: u64) -> Option<u64> {
+        self.mojos_per_clvm_cost.checked_mul(cost)
+    }
+
+    /// The rate paid by a transaction with the specified fee and cost,
+    /// rounded down. This is the rate to compare against a minimum rate, since
+    /// a fee only meets a rate if it pays at least the rate for every unit of
+    /// cost. Returns None if the cost is 0.
+    pub fn from_fee_rounding_down(fee: u64, cost: u64) -> Option<Self> {
+        fee.checked_div(cost).map(Self::new)
+    }
+
+    /// The rate paid by a transaction with the specified fee and cost,
+    /// rounded up. The fee for the cost at this rate is at least `fee`.
+    /// Returns None if the cost is 0.
+    pub fn from_fee_rounding_up(fee: u64, cost: u64) -> Option<Self> {
+        if cost == 0 {
+            return None;
+        }
+        Some(Self::new(fee.div_ceil(cost)))
+    }
+
+    /// Whether a transaction with the specified fee and cost pays at least this
+    /// rate. This is exact, it doesn't overflow for any fee and cost.
+    pub fn is_met_by(self, fee: u64, cost: u64) -> bool {
+        u128::from(fee) >= u128::from(self.mojos_per_clvm_cost) * u128::from(cost)
+    }
+}
+
+impl From<u64> for FeeRate {
+    fn from(mojos_per_clvm_cost: u64) -> Self {
+        Self::new(mojos_per_clvm_cost)
+    }
+}
+
+impl From<FeeRate> for u64 {
+    fn from(rate: FeeRate) -> Self {
+        rate.mojos_per_clvm_cost
+    }
+}
+
+// pyo3 doesn't support taking self by value
+#[cfg(feature = "py-bindings")]
+#[allow(clippy::trivially_copy_pass_by_ref)]
+#[pymethods]
+impl FeeRate {
+    #[pyo3(name = "fee_for_cost")]
+    fn py_fee_for_cost(&self, cost: u64) -> Option<u64> {
+        self.fee_for_cost(cost)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_fee_rounding_down")]
+    fn py_from_fee_rounding_down(fee: u64, cost: u64) -> Option<Self> {
+        Self::from_fee_rounding_down(fee, cost)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_fee_rounding_up")]
+    fn py_from_fee_rounding_up(fee: u64, cost: u64) -> Option<Self> {
+        Self::from_fee_rounding_up(fee, cost)
+    }
+
+    #[pyo3(name = "is_met_by")]
+    fn py_is_met_by(&self, fee: u64, cost: u64) -> bool {
+        self.is_met_by(fee, cost)
+    }
+}
+
 #[streamable]
 pub struct FeeEstimate {
     error: Option<String>,
@@ -20,3 +103,112 @@ pub struct FeeEstimateGroup {
     error: Option<String>,
     estimates: Vec<FeeEstimate>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chia_traits::Streamable;
+    use rstest::rstest;
+
+    const MAX: u64 = u64::MAX;
+
+    #[rstest]
+    #[case(0, 0, Some(0))]
+    #[case(0, MAX, Some(0))]
+    #[case(MAX, 0, Some(0))]
+    #[case(1, MAX, Some(MAX))]
+    #[case(MAX, 1, Some(MAX))]
+    #[case(5, 11_000_000_000, Some(55_000_000_000))]
+    #[case(2, MAX / 2, Some(MAX - 1))]
+    #[case(2, MAX / 2 + 1, None)]
+    #[case(3, MAX / 3, Some(MAX))]
+    #[case(3, MAX / 3 + 1, None)]
+    #[case(1 << 32, 1 << 31, Some(1 << 63))]
+    #[case(1 << 32, 1 << 32, None)]
+    fn test_fee_for_cost(#[case] rate: u64, #[case] cost: u64, #[case] expected: Option<u64>) {
+        let rate = FeeRate::new(rate);
+        assert_eq!(rate.fee_for_cost(cost), expected);
+        if let Some(fee) = expected {
+            assert!(rate.is_met_by(fee, cost));
+            if fee > 0 {
+                assert!(!rate.is_met_by(fee - 1, cost));
+            }
+        }
+    }
+
+    // (fee, cost, rounded down, rounded up)
+    #[rstest]
+    #[case(0, 1, 0, 0)]
+    #[case(1, 1, 1, 1)]
+    #[case(1, 2, 0, 1)]
+    #[case(9, 10, 0, 1)]
+    #[case(10, 10, 1, 1)]
+    #[case(11, 10, 1, 2)]
+    #[case(19, 10, 1, 2)]
+    #[case(20, 10, 2, 2)]
+    #[case(54_999_999_999, 11_000_000_000, 4, 5)]
+    #[case(55_000_000_000, 11_000_000_000, 5, 5)]
+    #[case(55_000_000_001, 11_000_000_000, 5, 6)]
+    #[case(MAX, 1, MAX, MAX)]
+    #[case(MAX, 2, MAX / 2, MAX / 2 + 1)]
+    #[case(MAX - 1, 2, MAX / 2, MAX / 2)]
+    #[case(MAX, MAX, 1, 1)]
+    #[case(MAX - 1, MAX, 0, 1)]
+    #[case(1, MAX, 0, 1)]
+    fn test_from_fee(#[case] fee: u64, #[case] cost: u64, #[case] down: u64, #[case] up: u64) {
+        let rate_down = FeeRate::from_fee_rounding_down(fee, cost).unwrap();
+        let rate_up = FeeRate::from_fee_rounding_up(fee, cost).unwrap();
+        assert_eq!(rate_down, FeeRate::new(down));
+        assert_eq!(rate_up, FeeRate::new(up));
+
+        // the fee always meets the rate rounded down, and only meets the rate
+        // rounded up if the division is exact
+        assert!(rate_down.is_met_by(fee, cost));
+        assert_eq!(rate_up.is_met_by(fee, cost), down == up);
+        if let Some(next) = down.checked_add(1) {
+            assert!(!FeeRate::new(next).is_met_by(fee, cost));
+        }
+    }
+
+    #[test]
+    fn test_zero_cost() {
+        assert_eq!(FeeRate::from_fee_rounding_down(1, 0), None);
+        assert_eq!(FeeRate::from_fee_rounding_up(1, 0), None);
+        assert!(FeeRate::new(MAX).is_met_by(0, 0));
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(FeeRate::ZERO < FeeRate::new(1));
+        assert!(FeeRate::new(5) > FeeRate::new(4));
+        assert_eq!(
+            [FeeRate::new(3), FeeRate::new(1), FeeRate::new(2)]
+                .iter()
+                .max(),
+            Some(&FeeRate::new(3))
+        );
+    }
+
+    #[test]
+    fn test_streamable() {
+        // a FeeRate is serialized exactly like a u64
+        let rate = FeeRate::from(1337);
+        let bytes = rate.to_bytes().unwrap();
+        assert_eq!(bytes, 1337_u64.to_bytes().unwrap());
+        assert_eq!(FeeRate::from_bytes(&bytes).unwrap(), rate);
+        assert_eq!(u64::from(rate), 1337);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use chia_traits::WireFormat;
+        let rate = WireFormat(FeeRate::new(1337));
+        let json = serde_json::to_string(&rate).unwrap();
+        assert_eq!(json, "\"0x0000000000000539\"");
+        assert_eq!(
+            serde_json::from_str::<WireFormat<FeeRate>>(&json).unwrap(),
+            rate
+        );
+    }
+}
@@ -4,6 +4,10 @@ use crate::Bytes32;
 use crate::Coin;
 use crate::PoolTarget;
 use chia_bls::G2Element;
+use std::fmt;
+
+#[cfg(feature = "py-bindings")]
+use pyo3::prelude::*;
 
 #[streamable]
 pub struct TransactionsInfo {
@@ -49,3 +53,163 @@ pub struct Foliage {
     foliage_transaction_block_hash: Option<Bytes32>,
     foliage_transaction_block_signature: Option<G2Element>,
 }
+
+// the Display implementations summarize the foliage chain, for logging. Hashes
+// are truncated to their first 4 bytes
+
+impl fmt::Display for TransactionsInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "TransactionsInfo(generator_root: {:.8}.., fees: {}, cost: {}, reward_claims: {})",
+            self.generator_root,
+            self.fees,
+            self.cost,
+            self.reward_claims_incorporated.len()
+        )
+    }
+}
+
+impl fmt::Display for FoliageTransactionBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "FoliageTransactionBlock(prev_transaction_block_hash: {:.8}.., timestamp: {})",
+            self.prev_transaction_block_hash, self.timestamp
+        )
+    }
+}
+
+impl fmt::Display for FoliageBlockData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "FoliageBlockData(unfinished_reward_block_hash: {:.8}.., pool_target: {}, farmer_reward_puzzle_hash: {:.8}..)",
+            self.unfinished_reward_block_hash, self.pool_target, self.farmer_reward_puzzle_hash
+        )
+    }
+}
+
+impl fmt::Display for Foliage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Foliage(prev_block_hash: {:.8}.., reward_block_hash: {:.8}.., ",
+            self.prev_block_hash, self.reward_block_hash
+        )?;
+        match &self.foliage_transaction_block_hash {
+            Some(hash) => write!(f, "foliage_transaction_block_hash: {hash:.8}..)"),
+            None => f.write_str("foliage_transaction_block_hash: none)"),
+        }
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl TransactionsInfo {
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl FoliageTransactionBlock {
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl FoliageBlockData {
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl Foliage {
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Amount;
+
+    fn block_data() -> FoliageBlockData {
+        FoliageBlockData::new(
+            Bytes32::new([0x11; 32]),
+            PoolTarget::new(Bytes32::new([0x22; 32]), 0),
+            None,
+            Bytes32::new([0x33; 32]),
+            Bytes32::default(),
+        )
+    }
+
+    #[test]
+    fn test_display_transactions_info() {
+        let info = TransactionsInfo::new(
+            Bytes32::new([0x44; 32]),
+            Bytes32::default(),
+            G2Element::default(),
+            1000,
+            5_000_000,
+            vec![Coin::new(Bytes32::default(), Bytes32::default(), Amount::new(1)); 2],
+        );
+        assert_eq!(
+            info.to_string(),
+            "TransactionsInfo(generator_root: 44444444.., fees: 1000, cost: 5000000, reward_claims: 2)"
+        );
+    }
+
+    #[test]
+    fn test_display_foliage_transaction_block() {
+        let block = FoliageTransactionBlock::new(
+            Bytes32::new([0x55; 32]),
+            1_700_000_000,
+            Bytes32::default(),
+            Bytes32::default(),
+            Bytes32::default(),
+            Bytes32::default(),
+        );
+        assert_eq!(
+            block.to_string(),
+            "FoliageTransactionBlock(prev_transaction_block_hash: 55555555.., timestamp: 1700000000)"
+        );
+    }
+
+    #[test]
+    fn test_display_foliage_block_data() {
+        assert_eq!(
+            block_data().to_string(),
+            "FoliageBlockData(unfinished_reward_block_hash: 11111111.., pool_target: PoolTarget(puzzle_hash: 22222222.., max_height: none), farmer_reward_puzzle_hash: 33333333..)"
+        );
+    }
+
+    #[test]
+    fn test_display_foliage() {
+        let mut foliage = Foliage::new(
+            Bytes32::new([0x66; 32]),
+            Bytes32::new([0x77; 32]),
+            block_data(),
+            G2Element::default(),
+            None,
+            None,
+        );
+        assert_eq!(
+            foliage.to_string(),
+            "Foliage(prev_block_hash: 66666666.., reward_block_hash: 77777777.., foliage_transaction_block_hash: none)"
+        );
+
+        foliage.foliage_transaction_block_hash = Some(Bytes32::new([0x88; 32]));
+        assert_eq!(
+            foliage.to_string(),
+            "Foliage(prev_block_hash: 66666666.., reward_block_hash: 77777777.., foliage_transaction_block_hash: 88888888..)"
+        );
+    }
+}
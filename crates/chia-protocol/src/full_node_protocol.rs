@@ -3,12 +3,16 @@ use chia_streamable_macro::streamable;
 use crate::EndOfSubSlotBundle;
 use crate::FullBlock;
 use crate::SpendBundle;
+use crate::StreamableIter;
 use crate::TimestampedPeerInfo;
 use crate::UnfinishedBlock;
 use crate::VDFInfo;
 use crate::VDFProof;
 use crate::WeightProof;
 use crate::{Bytes, Bytes32};
+use chia_traits::chia_error::{Error, Result};
+use chia_traits::Streamable;
+use std::io::Cursor;
 
 #[streamable(message)]
 pub struct NewPeak {
@@ -73,6 +77,27 @@ pub struct RespondBlocks {
     blocks: Vec<FullBlock>,
 }
 
+impl RespondBlocks {
+    /// Parses the start and end height of a serialized RespondBlocks message
+    /// and returns an iterator that parses the blocks one at a time. The
+    /// number of blocks must match the height range.
+    pub fn parse_iter(bytes: &[u8]) -> Result<(u32, u32, StreamableIter<'_, FullBlock>)> {
+        let mut input = Cursor::new(bytes);
+        let start_height = u32::parse::<false>(&mut input)?;
+        let end_height = u32::parse::<false>(&mut input)?;
+        let blocks = StreamableIter::new(input)?;
+
+        if end_height < start_height || blocks.remaining() != end_height - start_height + 1 {
+            return Err(Error::Custom(format!(
+                "RespondBlocks has {} blocks for heights {start_height}-{end_height}",
+                blocks.remaining()
+            )));
+        }
+
+        Ok((start_height, end_height, blocks))
+    }
+}
+
 #[streamable(message)]
 pub struct RejectBlocks {
     start_height: u32,
@@ -177,3 +202,57 @@ pub struct RequestUnfinishedBlock2 {
     unfinished_reward_hash: Bytes32,
     foliage_hash: Option<Bytes32>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fullblock::tests::test_block;
+    use rstest::rstest;
+
+    #[test]
+    fn test_parse_iter() {
+        let msg = RespondBlocks::new(
+            10,
+            12,
+            vec![test_block(true), test_block(false), test_block(true)],
+        );
+        let buf = msg.to_bytes().unwrap();
+
+        let (start, end, blocks) = RespondBlocks::parse_iter(&buf).unwrap();
+        assert_eq!((start, end), (10, 12));
+        let blocks = blocks.collect::<Result<Vec<FullBlock>>>().unwrap();
+        assert_eq!(blocks, msg.blocks);
+    }
+
+    #[rstest]
+    #[case(10, 11)]
+    #[case(10, 13)]
+    #[case(12, 10)]
+    fn test_parse_iter_count_mismatch(#[case] start: u32, #[case] end: u32) {
+        // there are 3 blocks, which doesn't match any of the height ranges
+        let blocks = vec![test_block(true), test_block(false), test_block(true)];
+        let buf = RespondBlocks::new(start, end, blocks).to_bytes().unwrap();
+        assert!(matches!(
+            RespondBlocks::parse_iter(&buf),
+            Err(Error::Custom(..))
+        ));
+    }
+
+    #[test]
+    fn test_parse_iter_truncated() {
+        let msg = RespondBlocks::new(0, 1, vec![test_block(true), test_block(true)]);
+        let buf = msg.to_bytes().unwrap();
+
+        // the header is incomplete
+        assert_eq!(
+            RespondBlocks::parse_iter(&buf[..10]).err(),
+            Some(Error::EndOfBuffer)
+        );
+
+        // the second block is incomplete
+        let (_, _, mut blocks) = RespondBlocks::parse_iter(&buf[..buf.len() - 50]).unwrap();
+        assert_eq!(blocks.next(), Some(Ok(msg.blocks[0].clone())));
+        assert_eq!(blocks.next(), Some(Err(Error::EndOfBuffer)));
+        assert_eq!(blocks.next(), None);
+    }
+}
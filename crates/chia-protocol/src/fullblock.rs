@@ -1,15 +1,17 @@
 use chia_streamable_macro::streamable;
 
+use crate::Bytes;
 use crate::Bytes32;
 use crate::Coin;
 use crate::EndOfSubSlotBundle;
+use crate::HeaderBlock;
 use crate::Program;
 use crate::RewardChainBlock;
 use crate::VDFProof;
 use crate::{Foliage, FoliageTransactionBlock, TransactionsInfo};
 use chia_traits::Streamable;
 
-#[streamable]
+#[streamable(cached_hash)]
 pub struct FullBlock {
     finished_sub_slots: Vec<EndOfSubSlotBundle>,
     reward_chain_block: RewardChainBlock,
@@ -58,6 +60,26 @@ impl FullBlock {
         }
     }
 
+    /// Strips the transactions generator (and its ref list) from the block,
+    /// turning it into the HeaderBlock sent to light clients. The
+    /// transactions filter is the encoded BIP158 filter of the block's
+    /// additions and removals, which the caller has to compute.
+    pub fn to_header_block(&self, transactions_filter: Bytes) -> HeaderBlock {
+        HeaderBlock {
+            finished_sub_slots: self.finished_sub_slots.clone(),
+            reward_chain_block: self.reward_chain_block.clone(),
+            challenge_chain_sp_proof: self.challenge_chain_sp_proof.clone(),
+            challenge_chain_ip_proof: self.challenge_chain_ip_proof.clone(),
+            reward_chain_sp_proof: self.reward_chain_sp_proof.clone(),
+            reward_chain_ip_proof: self.reward_chain_ip_proof.clone(),
+            infused_challenge_chain_ip_proof: self.infused_challenge_chain_ip_proof.clone(),
+            foliage: self.foliage.clone(),
+            foliage_transaction_block: self.foliage_transaction_block.clone(),
+            transactions_filter,
+            transactions_info: self.transactions_info.clone(),
+        }
+    }
+
     pub fn is_fully_compactified(&self) -> bool {
         for sub_slot in &self.finished_sub_slots {
             if sub_slot.proofs.challenge_chain_slot_proof.witness_type != 0
@@ -137,4 +159,181 @@ impl FullBlock {
     fn py_is_fully_compactified(&self) -> bool {
         self.is_fully_compactified()
     }
+
+    #[pyo3(name = "to_header_block")]
+    fn py_to_header_block(&self, transactions_filter: Bytes) -> HeaderBlock {
+        self.to_header_block(transactions_filter)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{ClassgroupElement, FoliageBlockData, PoolTarget, ProofOfSpace, VDFInfo};
+    use chia_bls::{G1Element, G2Element};
+    use rstest::rstest;
+
+    fn vdf_info() -> VDFInfo {
+        VDFInfo::new(Bytes32::default(), 1000, ClassgroupElement::default())
+    }
+
+    fn vdf_proof() -> VDFProof {
+        VDFProof::new(0, Bytes::default(), true)
+    }
+
+    pub(crate) fn test_block(transaction_block: bool) -> FullBlock {
+        let proof_of_space = ProofOfSpace::new(
+            Bytes32::new([1; 32]),
+            Some(G1Element::default()),
+            None,
+            G1Element::default(),
+            32,
+            Bytes::new(vec![0x55; 64]),
+        );
+        let reward_chain_block = RewardChainBlock::new(
+            1337,
+            42,
+            123_456_789,
+            3,
+            Bytes32::new([2; 32]),
+            proof_of_space,
+            None,
+            G2Element::default(),
+            vdf_info(),
+            None,
+            G2Element::default(),
+            vdf_info(),
+            None,
+            transaction_block,
+        );
+        let foliage_block_data = FoliageBlockData::new(
+            Bytes32::new([3; 32]),
+            PoolTarget::new(Bytes32::new([4; 32]), 0),
+            None,
+            Bytes32::new([5; 32]),
+            Bytes32::default(),
+        );
+        let foliage_transaction_block = FoliageTransactionBlock::new(
+            Bytes32::new([6; 32]),
+            1_700_000_000,
+            Bytes32::new([7; 32]),
+            Bytes32::new([8; 32]),
+            Bytes32::new([9; 32]),
+            Bytes32::new([10; 32]),
+        );
+        let transactions_info = TransactionsInfo::new(
+            Bytes32::new([11; 32]),
+            Bytes32::new([12; 32]),
+            G2Element::default(),
+            0,
+            0,
+            vec![],
+        );
+        let foliage = Foliage::new(
+            Bytes32::new([13; 32]),
+            Bytes32::new([14; 32]),
+            foliage_block_data,
+            G2Element::default(),
+            transaction_block.then(|| foliage_transaction_block.hash().into()),
+            transaction_block.then(G2Element::default),
+        );
+        FullBlock::new(
+            vec![],
+            reward_chain_block,
+            None,
+            vdf_proof(),
+            None,
+            vdf_proof(),
+            None,
+            foliage,
+            transaction_block.then_some(foliage_transaction_block),
+            transaction_block.then_some(transactions_info),
+            transaction_block.then(|| Program::from(vec![0x80])),
+            if transaction_block {
+                vec![1, 2, 3]
+            } else {
+                vec![]
+            },
+        )
+    }
+
+    #[rstest]
+    fn test_to_header_block(#[values(true, false)] transaction_block: bool) {
+        let block = test_block(transaction_block);
+        let filter = Bytes::new(vec![1, 2, 3, 4]);
+        let header_block = block.to_header_block(filter.clone());
+
+        assert_eq!(header_block.transactions_filter, filter);
+        assert_eq!(header_block.foliage, block.foliage);
+        assert_eq!(header_block.transactions_info, block.transactions_info);
+        assert_eq!(
+            header_block.foliage_transaction_block,
+            block.foliage_transaction_block
+        );
+        assert_eq!(header_block.header_hash(), block.header_hash());
+        assert_eq!(header_block.prev_header_hash(), block.prev_header_hash());
+        assert_eq!(header_block.height(), 42);
+        assert_eq!(header_block.weight(), 1337);
+        assert_eq!(header_block.total_iters(), block.total_iters());
+        assert_eq!(
+            header_block.is_transaction_block(),
+            block.is_transaction_block()
+        );
+        assert_eq!(block.is_transaction_block(), transaction_block);
+    }
+
+    #[test]
+    fn test_header_hash() {
+        // the header hash is the hash of the foliage, it does not depend on
+        // the generator
+        let block = test_block(true);
+        let mut stripped = block.clone();
+        stripped.transactions_generator = None;
+        stripped.transactions_generator_ref_list = vec![];
+        assert_eq!(block.header_hash(), stripped.header_hash());
+        assert_eq!(block.header_hash(), Bytes32::from(block.foliage.hash()));
+        assert_ne!(block.header_hash(), test_block(false).header_hash());
+    }
+
+    #[test]
+    fn test_diff() {
+        let block = test_block(true);
+        assert_eq!(chia_traits::diff(&block, &block.clone()), vec![]);
+
+        // only the mutated field is reported, with its full path
+        let mut other = block.clone();
+        other.foliage.foliage_block_data.pool_target.max_height = 100;
+        assert_eq!(
+            chia_traits::diff(&block, &other),
+            vec![chia_traits::FieldDiff {
+                path: "foliage.foliage_block_data.pool_target.max_height".to_string(),
+                left: "0".to_string(),
+                right: "100".to_string(),
+            }]
+        );
+
+        // hashes are summarized as hex, and a missing optional field is
+        // reported once, rather than every field inside it
+        let mut other = block.clone();
+        other.reward_chain_block.pos_ss_cc_challenge_hash = Bytes32::new([0xff; 32]);
+        other.transactions_info = None;
+        other.transactions_generator_ref_list.push(4);
+        let diffs = chia_traits::diff(&block, &other);
+        let summary: Vec<_> = diffs
+            .iter()
+            .map(|d| (d.path.as_str(), d.left.as_str(), d.right.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "reward_chain_block.pos_ss_cc_challenge_hash",
+                    "02020202020202020202020202020202... (32 bytes)",
+                    "ffffffffffffffffffffffffffffffff... (32 bytes)",
+                ),
+                ("transactions_info", "Some(TransactionsInfo { .. })", "None"),
+                ("transactions_generator_ref_list", "[3 items]", "[4 items]"),
+            ]
+        );
+    }
 }
@@ -0,0 +1,11 @@
+use chia_streamable_macro::streamable;
+
+use crate::TimestampedPeerInfo;
+
+#[streamable(message)]
+pub struct RequestPeersIntroducer {}
+
+#[streamable(message)]
+pub struct RespondPeersIntroducer {
+    peer_list: Vec<TimestampedPeerInfo>,
+}
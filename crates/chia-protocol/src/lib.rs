@@ -1,3 +1,4 @@
+mod amount;
 mod block_record;
 mod bytes;
 mod chia_protocol;
@@ -5,12 +6,14 @@ mod classgroup;
 mod coin;
 mod coin_spend;
 mod coin_state;
+mod coin_state_delta;
 mod end_of_sub_slot_bundle;
 mod fee_estimate;
 mod foliage;
 mod full_node_protocol;
 mod fullblock;
 mod header_block;
+mod introducer_protocol;
 mod peer_info;
 mod pool_target;
 mod program;
@@ -18,6 +21,11 @@ mod proof_of_space;
 mod reward_chain_block;
 mod slots;
 mod spend_bundle;
+mod streamable_iter;
+mod streamable_json_map;
+#[cfg(test)]
+mod streamable_since;
+mod streamable_tuple;
 mod sub_epoch_summary;
 mod unfinished_block;
 mod unfinished_header_block;
@@ -28,7 +36,14 @@ mod weight_proof;
 #[cfg(feature = "py-bindings")]
 mod lazy_node;
 
+#[cfg(feature = "tokio")]
+mod codec;
+
+#[cfg(feature = "arbitrary")]
+mod streamable_registry;
+
 // export shorter names
+pub use crate::amount::*;
 pub use crate::block_record::*;
 pub use crate::bytes::*;
 pub use crate::chia_protocol::*;
@@ -36,12 +51,14 @@ pub use crate::classgroup::*;
 pub use crate::coin::*;
 pub use crate::coin_spend::*;
 pub use crate::coin_state::*;
+pub use crate::coin_state_delta::*;
 pub use crate::end_of_sub_slot_bundle::*;
 pub use crate::fee_estimate::*;
 pub use crate::foliage::*;
 pub use crate::full_node_protocol::*;
 pub use crate::fullblock::*;
 pub use crate::header_block::*;
+pub use crate::introducer_protocol::*;
 pub use crate::peer_info::*;
 pub use crate::pool_target::*;
 pub use crate::program::*;
@@ -49,6 +66,9 @@ pub use crate::proof_of_space::*;
 pub use crate::reward_chain_block::*;
 pub use crate::slots::*;
 pub use crate::spend_bundle::*;
+pub use crate::streamable_iter::*;
+pub use crate::streamable_json_map::*;
+pub use crate::streamable_tuple::*;
 pub use crate::sub_epoch_summary::*;
 pub use crate::unfinished_block::*;
 pub use crate::unfinished_header_block::*;
@@ -58,3 +78,9 @@ pub use crate::weight_proof::*;
 
 #[cfg(feature = "py-bindings")]
 pub use crate::lazy_node::*;
+
+#[cfg(feature = "tokio")]
+pub use crate::codec::*;
+
+#[cfg(feature = "arbitrary")]
+pub use crate::streamable_registry::*;
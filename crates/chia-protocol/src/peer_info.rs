@@ -1,5 +1,11 @@
 use chia_streamable_macro::streamable;
 
+#[streamable]
+pub struct PeerInfo {
+    host: String,
+    port: u16,
+}
+
 #[streamable]
 pub struct TimestampedPeerInfo {
     host: String,
@@ -1,9 +1,70 @@
 use chia_streamable_macro::streamable;
+use std::fmt;
 
 use crate::Bytes32;
 
+#[cfg(feature = "py-bindings")]
+use pyo3::prelude::*;
+
 #[streamable]
 pub struct PoolTarget {
     puzzle_hash: Bytes32,
     max_height: u32, // A max height of 0 means it is valid forever
 }
+
+impl PoolTarget {
+    /// Whether a block at the specified height may pay to this pool target.
+    /// Blocks above `max_height` must not use it (unless it's 0).
+    pub fn is_valid_at(&self, height: u32) -> bool {
+        self.max_height == 0 || height <= self.max_height
+    }
+}
+
+impl fmt::Display for PoolTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PoolTarget(puzzle_hash: {:.8}.., ", self.puzzle_hash)?;
+        if self.max_height == 0 {
+            f.write_str("max_height: none)")
+        } else {
+            write!(f, "max_height: {})", self.max_height)
+        }
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl PoolTarget {
+    #[pyo3(name = "is_valid_at")]
+    fn py_is_valid_at(&self, height: u32) -> bool {
+        self.is_valid_at(height)
+    }
+
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 0, true)]
+    #[case(0, u32::MAX, true)]
+    #[case(100, 99, true)]
+    #[case(100, 100, true)]
+    #[case(100, 101, false)]
+    fn test_is_valid_at(#[case] max_height: u32, #[case] height: u32, #[case] expected: bool) {
+        let target = PoolTarget::new(Bytes32::default(), max_height);
+        assert_eq!(target.is_valid_at(height), expected);
+    }
+
+    #[rstest]
+    #[case(0, "PoolTarget(puzzle_hash: cdcdcdcd.., max_height: none)")]
+    #[case(1000, "PoolTarget(puzzle_hash: cdcdcdcd.., max_height: 1000)")]
+    fn test_display(#[case] max_height: u32, #[case] expected: &str) {
+        let target = PoolTarget::new(Bytes32::new([0xcd; 32]), max_height);
+        assert_eq!(target.to_string(), expected);
+    }
+}
@@ -1,15 +1,18 @@
 use crate::bytes::Bytes;
 use chia_sha2::Sha256;
 use chia_traits::chia_error::{Error, Result};
-use chia_traits::Streamable;
-use clvm_traits::{FromClvm, FromClvmError, ToClvm, ToClvmError};
-use clvmr::allocator::NodePtr;
+use chia_traits::{bytes_summary, diff_leaf, reserve_bytes, FieldDiff, Streamable, StreamableDiff};
+use clvm_traits::{
+    clvm_list, clvm_quote, match_quote, FromClvm, FromClvmError, MatchByte, ToClvm, ToClvmError,
+};
+use clvm_utils::CurriedProgram;
+use clvmr::allocator::{NodePtr, SExp};
 use clvmr::cost::Cost;
 use clvmr::reduction::EvalErr;
 use clvmr::run_program;
 use clvmr::serde::{
-    node_from_bytes, node_from_bytes_backrefs, node_to_bytes, serialized_length_from_bytes,
-    serialized_length_from_bytes_trusted,
+    node_from_bytes, node_from_bytes_backrefs, node_to_bytes, node_to_bytes_limit,
+    serialized_length_from_bytes, serialized_length_from_bytes_trusted,
 };
 use clvmr::{Allocator, ChiaDialect};
 #[cfg(feature = "py-bindings")]
@@ -19,10 +22,57 @@ use pyo3::types::PyType;
 use std::io::Cursor;
 use std::ops::Deref;
 
+/// The heap limit of the allocator used to curry and uncurry programs.
+const CURRY_HEAP_LIMIT: usize = 500_000_000;
+
+/// The largest program [`Program::curry()`] and [`Program::uncurry()`] will
+/// return, in bytes. The input may be serialized with back references, so a
+/// small program can expand into a much larger one once its parts are
+/// serialized on their own.
+pub const MAX_CURRY_SIZE: usize = 10_000_000;
+
 #[cfg_attr(feature = "py-bindings", pyclass, derive(PyStreamable))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Program(Bytes);
 
+/// The ways [`Program::run_with_cost()`] can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError {
+    /// The program isn't valid serialized CLVM.
+    InvalidProgram,
+    /// Running the program exceeded the cost limit.
+    CostExceeded,
+    /// The program raised an exception with the `x` operator. The node is
+    /// the argument passed to it.
+    Raise(NodePtr),
+    /// Any other failure, e.g. an operator called with invalid arguments.
+    Eval(NodePtr, String),
+}
+
+impl std::fmt::Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidProgram => f.write_str("invalid program"),
+            Self::CostExceeded => f.write_str("cost exceeded"),
+            Self::Raise(_) => f.write_str("clvm raise"),
+            Self::Eval(_, msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+impl From<EvalErr> for RunError {
+    fn from(err: EvalErr) -> Self {
+        // these are the messages clvmr uses for these errors
+        match err.1.as_str() {
+            "cost exceeded" => Self::CostExceeded,
+            "clvm raise" => Self::Raise(err.0),
+            _ => Self::Eval(err.0, err.1),
+        }
+    }
+}
+
 impl Default for Program {
     fn default() -> Self {
         Self(vec![0x80].into())
@@ -77,6 +127,103 @@ impl Program {
         let reduction = run_program(a, &dialect, program, arg, max_cost)?;
         Ok((reduction.0, reduction.1))
     }
+
+    /// Deserializes this program into `a` and runs it with `env` as its
+    /// argument, returning the cost and the result. `flags` are the
+    /// `ChiaDialect` flags, e.g. `MEMPOOL_MODE`. The program may be
+    /// serialized with back references.
+    pub fn run_with_cost(
+        &self,
+        a: &mut Allocator,
+        max_cost: Cost,
+        flags: u32,
+        env: NodePtr,
+    ) -> std::result::Result<(Cost, NodePtr), RunError> {
+        let program =
+            node_from_bytes_backrefs(a, self.0.as_ref()).map_err(|_| RunError::InvalidProgram)?;
+        let dialect = ChiaDialect::new(flags);
+        let reduction = run_program(a, &dialect, program, env, max_cost)?;
+        Ok((reduction.0, reduction.1))
+    }
+
+    /// Curries the arguments into this program, i.e. returns the program
+    /// `(a (q . self) (c (q . arg1) (c (q . arg2) 1)))`. The result may not be
+    /// larger than [`MAX_CURRY_SIZE`].
+    pub fn curry(&self, args: &[Program]) -> Result<Program> {
+        let mut a = Allocator::new_limited(CURRY_HEAP_LIMIT);
+        let program = parse_node(&mut a, self)?;
+        let args = args
+            .iter()
+            .map(|arg| parse_node(&mut a, arg))
+            .collect::<Result<Vec<_>>>()?;
+        let curried = curry_node(&mut a, program, &args)?;
+        serialize_node(&a, curried)
+    }
+
+    /// The inverse of [`Program::curry()`]. Returns the inner program and the
+    /// curried arguments. Just like `SerializedProgram.uncurry()` in python,
+    /// a program that isn't curried is returned as is, with no arguments.
+    /// Each of the returned programs may not be larger than
+    /// [`MAX_CURRY_SIZE`].
+    pub fn uncurry(&self) -> Result<(Program, Vec<Program>)> {
+        let mut a = Allocator::new_limited(CURRY_HEAP_LIMIT);
+        let program = parse_node(&mut a, self)?;
+        let Some((program, args)) = uncurry_node(&a, program) else {
+            return Ok((self.clone(), Vec::new()));
+        };
+        Ok((
+            serialize_node(&a, program)?,
+            args.into_iter()
+                .map(|arg| serialize_node(&a, arg))
+                .collect::<Result<_>>()?,
+        ))
+    }
+}
+
+fn parse_node(a: &mut Allocator, program: &Program) -> Result<NodePtr> {
+    node_from_bytes_backrefs(a, program.as_slice()).map_err(|_| Error::InvalidClvm)
+}
+
+fn serialize_node(a: &Allocator, node: NodePtr) -> Result<Program> {
+    let bytes = node_to_bytes_limit(a, node, MAX_CURRY_SIZE)
+        .map_err(|_| Error::Custom(format!("program exceeds {MAX_CURRY_SIZE} bytes")))?;
+    Ok(Program(bytes.into()))
+}
+
+fn curry_node(a: &mut Allocator, program: NodePtr, args: &[NodePtr]) -> Result<NodePtr> {
+    let mut rest = a.one();
+    for arg in args.iter().rev() {
+        rest = clvm_list!(4, clvm_quote!(arg), rest)
+            .to_clvm(a)
+            .map_err(|error| Error::Custom(error.to_string()))?;
+    }
+    CurriedProgram {
+        program,
+        args: rest,
+    }
+    .to_clvm(a)
+    .map_err(|error| Error::Custom(error.to_string()))
+}
+
+/// Matches `(a (q . program) (c (q . arg1) (c (q . arg2) 1)))` and returns the
+/// program and its arguments. Anything that doesn't follow this pattern
+/// exactly isn't considered curried, including an argument list that isn't
+/// terminated by 1.
+fn uncurry_node(a: &Allocator, node: NodePtr) -> Option<(NodePtr, Vec<NodePtr>)> {
+    let curried = CurriedProgram::<NodePtr, NodePtr>::from_clvm(a, node).ok()?;
+    let mut args = Vec::new();
+    let mut rest = curried.args;
+    while let SExp::Pair(..) = a.sexp(rest) {
+        let (_, ((_, arg), (next, ()))) =
+            <(MatchByte<4>, (match_quote!(NodePtr), (NodePtr, ()))) as FromClvm<Allocator>>::from_clvm(
+                a, rest,
+            )
+            .ok()?;
+        args.push(arg);
+        rest = next;
+    }
+    MatchByte::<1>::from_clvm(a, rest).ok()?;
+    Some((curried.program, args))
 }
 
 impl From<Bytes> for Program {
@@ -151,7 +298,7 @@ impl<'a> arbitrary::Arbitrary<'a> for Program {
 use crate::lazy_node::LazyNode;
 
 #[cfg(feature = "py-bindings")]
-use chia_traits::{FromJsonDict, ToJsonDict};
+use chia_traits::{FromJsonDict, JsonValue, ToJsonDict};
 
 #[cfg(feature = "py-bindings")]
 use chia_py_streamable_macro::PyStreamable;
@@ -159,9 +306,6 @@ use chia_py_streamable_macro::PyStreamable;
 #[cfg(feature = "py-bindings")]
 use pyo3::types::{PyList, PyTuple};
 
-#[cfg(feature = "py-bindings")]
-use clvmr::allocator::SExp;
-
 #[cfg(feature = "py-bindings")]
 use pyo3::exceptions::*;
 
@@ -355,7 +499,8 @@ impl Program {
         self._run(py, max_cost, MEMPOOL_MODE, args)
     }
 
-    fn run_with_cost<'a>(
+    #[pyo3(name = "run_with_cost")]
+    fn py_run_with_cost<'a>(
         &self,
         py: Python<'a>,
         max_cost: u64,
@@ -373,7 +518,6 @@ impl Program {
         flags: u32,
         args: &Bound<'_, PyAny>,
     ) -> PyResult<(u64, Bound<'a, PyAny>)> {
-        use clvmr::reduction::Response;
         use std::rc::Rc;
 
         let mut a = Allocator::new_limited(500_000_000);
@@ -388,22 +532,23 @@ impl Program {
         // circular dependency).
         let clvm_args = clvm_serialize(&mut a, args)?;
 
-        let r: Response = (|| -> PyResult<Response> {
-            let program = node_from_bytes_backrefs(&mut a, self.0.as_ref())?;
-            let dialect = ChiaDialect::new(flags);
-
-            Ok(py.allow_threads(|| run_program(&mut a, &dialect, program, clvm_args, max_cost)))
-        })()?;
-        match r {
-            Ok(reduction) => {
-                let val = LazyNode::new(Rc::new(a), reduction.1);
-                Ok((reduction.0, to_program(py, val)?))
-            }
-            Err(eval_err) => {
-                let blob = node_to_bytes(&a, eval_err.0).ok().map(hex::encode);
-                Err(PyValueError::new_err((eval_err.1, blob)))
+        // large programs take a while to deserialize and run, so other
+        // threads can run in the meantime
+        let err = match py.allow_threads(|| self.run_with_cost(&mut a, max_cost, flags, clvm_args))
+        {
+            Ok((cost, result)) => {
+                let val = LazyNode::new(Rc::new(a), result);
+                return Ok((cost, to_program(py, val)?));
             }
-        }
+            Err(err) => err,
+        };
+        let node = match &err {
+            RunError::InvalidProgram => return Err(PyValueError::new_err(err.to_string())),
+            RunError::CostExceeded => a.nil(),
+            RunError::Raise(node) | RunError::Eval(node, _) => *node,
+        };
+        let blob = node_to_bytes(&a, node).ok().map(hex::encode);
+        Err(PyValueError::new_err((err.to_string(), blob)))
     }
 
     fn to_program<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
@@ -414,13 +559,13 @@ impl Program {
         to_program(py, prg)
     }
 
-    fn uncurry<'a>(&self, py: Python<'a>) -> PyResult<(Bound<'a, PyAny>, Bound<'a, PyAny>)> {
-        use clvm_utils::CurriedProgram;
+    #[pyo3(name = "uncurry")]
+    fn py_uncurry<'a>(&self, py: Python<'a>) -> PyResult<(Bound<'a, PyAny>, Bound<'a, PyAny>)> {
         use std::rc::Rc;
 
-        let mut a = Allocator::new_limited(500_000_000);
+        let mut a = Allocator::new_limited(CURRY_HEAP_LIMIT);
         let prg = node_from_bytes_backrefs(&mut a, self.0.as_ref())?;
-        let Ok(uncurried) = CurriedProgram::<NodePtr, NodePtr>::from_clvm(&a, prg) else {
+        let Some((program, curried_args)) = uncurry_node(&a, prg) else {
             let a = Rc::new(a);
             let prg = LazyNode::new(a.clone(), prg);
             let ret = a.nil();
@@ -428,32 +573,28 @@ impl Program {
             return Ok((to_program(py, prg)?, to_program(py, ret)?));
         };
 
-        let mut curried_args = Vec::<NodePtr>::new();
-        let mut args = uncurried.args;
-        loop {
-            if let SExp::Atom = a.sexp(args) {
-                break;
-            }
-            // the args of curried puzzles are in the form of:
-            // (c . ((q . <arg1>) . (<rest> . ())))
-            let (_, ((_, arg), (rest, ()))) =
-                <(
-                    clvm_traits::MatchByte<4>,
-                    (clvm_traits::match_quote!(NodePtr), (NodePtr, ())),
-                ) as FromClvm<Allocator>>::from_clvm(&a, args)
-                .map_err(|error| PyErr::new::<PyTypeError, _>(error.to_string()))?;
-            curried_args.push(arg);
-            args = rest;
-        }
         let mut ret = a.nil();
         for item in curried_args.into_iter().rev() {
             ret = a.new_pair(item, ret).map_err(|_e| Error::EndOfBuffer)?;
         }
         let a = Rc::new(a);
-        let prg = LazyNode::new(a.clone(), uncurried.program);
+        let prg = LazyNode::new(a.clone(), program);
         let ret = LazyNode::new(a, ret);
         Ok((to_program(py, prg)?, to_program(py, ret)?))
     }
+
+    // the arguments are converted the same way as the arguments to run()
+    #[pyo3(name = "curry", signature = (*args))]
+    fn py_curry(&self, args: &Bound<'_, PyTuple>) -> PyResult<Program> {
+        let mut a = Allocator::new_limited(CURRY_HEAP_LIMIT);
+        let program = node_from_bytes_backrefs(&mut a, self.0.as_ref())?;
+        let args = args
+            .iter()
+            .map(|arg| clvm_serialize(&mut a, &arg))
+            .collect::<PyResult<Vec<_>>>()?;
+        let curried = curry_node(&mut a, program, &args)?;
+        Ok(serialize_node(&a, curried)?)
+    }
 }
 
 impl Streamable for Program {
@@ -477,12 +618,23 @@ impl Streamable for Program {
         if buf.len() < len as usize {
             return Err(Error::EndOfBuffer);
         }
+        reserve_bytes(u32::try_from(len).unwrap_or(u32::MAX))?;
         let program = buf[..len as usize].to_vec();
         input.set_position(pos + len);
         Ok(Program(program.into()))
     }
 }
 
+impl StreamableDiff for Program {
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+        diff_leaf(self, other, path, out);
+    }
+
+    fn summary(&self) -> String {
+        bytes_summary(self.0.as_ref())
+    }
+}
+
 #[cfg(feature = "py-bindings")]
 impl ToJsonDict for Program {
     fn to_json_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
@@ -505,7 +657,17 @@ impl Program {
 #[cfg(feature = "py-bindings")]
 impl FromJsonDict for Program {
     fn from_json_dict(o: &Bound<'_, PyAny>) -> PyResult<Self> {
-        let bytes = Bytes::from_json_dict(o)?;
+        Self::checked_from_bytes(Bytes::from_json_dict(o)?)
+    }
+
+    fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+        Self::checked_from_bytes(Bytes::from_json_value(v)?)
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+impl Program {
+    fn checked_from_bytes(bytes: Bytes) -> PyResult<Self> {
         let len =
             serialized_length_from_bytes(bytes.as_slice()).map_err(|_e| Error::EndOfBuffer)?;
         if len as usize != bytes.len() {
@@ -537,6 +699,24 @@ impl ToClvm<Allocator> for Program {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chia_bls::SecretKey;
+    use clvm_utils::{curry_tree_hash, tree_hash_atom, tree_hash_from_bytes};
+    use rstest::rstest;
+
+    const STANDARD_PUZZLE: &str = concat!(
+        "ff02ffff01ff02ffff03ff0bffff01ff02ffff03ffff09ff05ffff1dff0bffff",
+        "1effff0bff0bffff02ff06ffff04ff02ffff04ff17ff8080808080808080ffff",
+        "01ff02ff17ff2f80ffff01ff088080ff0180ffff01ff04ffff04ff04ffff04ff",
+        "05ffff04ffff02ff06ffff04ff02ffff04ff17ff80808080ff80808080ffff02",
+        "ff17ff2f808080ff0180ffff04ffff01ff32ff02ffff03ffff07ff0580ffff01",
+        "ff0bffff0102ffff02ff06ffff04ff02ffff04ff09ff80808080ffff02ff06ff",
+        "ff04ff02ffff04ff0dff8080808080ffff01ff0bffff0101ff058080ff0180ff",
+        "018080",
+    );
+
+    fn program(hex: &str) -> Program {
+        Program::from(hex::decode(hex).expect("hex::decode"))
+    }
 
     #[test]
     fn program_roundtrip() {
@@ -562,4 +742,125 @@ mod tests {
         assert_eq!(cost, 869);
         assert_eq!(a.number(result), 1337.into());
     }
+
+    #[test]
+    fn program_run_with_cost() {
+        let a = &mut Allocator::new();
+
+        // (q . "hello")
+        let prg = program("ff018568656c6c6f");
+        let (cost, result) = prg.run_with_cost(a, 1000, 0, NodePtr::NIL).expect("run");
+        assert_eq!(cost, 20);
+        assert_eq!(a.atom(result).as_ref(), b"hello");
+
+        // (+ 2 5), the environment is (1300 37)
+        let prg = program("ff10ff02ff0580");
+        let env = [1300, 37].to_clvm(a).unwrap();
+        let (cost, result) = prg.run_with_cost(a, 1000, 0, env).expect("run");
+        assert_eq!(cost, 869);
+        assert_eq!(a.number(result), 1337.into());
+        assert_eq!(
+            prg.run_with_cost(a, 868, 0, env).unwrap_err(),
+            RunError::CostExceeded
+        );
+    }
+
+    #[test]
+    fn program_run_with_cost_errors() {
+        let a = &mut Allocator::new();
+
+        // (x (q . 42))
+        let Err(RunError::Raise(node)) =
+            program("ff08ffff012a80").run_with_cost(a, 1000, 0, NodePtr::NIL)
+        else {
+            panic!("expected the program to raise");
+        };
+        assert_eq!(a.number(node), 42.into());
+
+        // (f (q . 42)), the first of an atom
+        let err = program("ff05ffff012a80")
+            .run_with_cost(a, 1000, 0, NodePtr::NIL)
+            .unwrap_err();
+        assert!(matches!(err, RunError::Eval(_, ref msg) if msg == "first of non-cons"));
+
+        // a truncated program
+        assert_eq!(
+            program("ff01").run_with_cost(a, 1000, 0, NodePtr::NIL),
+            Err(RunError::InvalidProgram)
+        );
+    }
+
+    #[rstest]
+    #[case::no_args("8378797a", &[], "ff02ffff018378797aff0180")]
+    #[case::atoms(
+        "8378797a",
+        &["61", "62", "63"],
+        "ff02ffff018378797affff04ffff0161ffff04ffff0162ffff04ffff0163ff0180808080"
+    )]
+    #[case::nil("80", &["80"], "ff02ffff0180ffff04ffff0180ff018080")]
+    #[case::pairs(
+        "ff01ff02ff62ff0480",
+        &["ff0180", "ff01ff0280"],
+        "ff02ffff01ff01ff02ff62ff0480ffff04ffff01ff0180ffff04ffff01ff01ff0280ff01808080"
+    )]
+    fn test_curry_round_trip(#[case] prg: &str, #[case] args: &[&str], #[case] expected: &str) {
+        let prg = program(prg);
+        let args: Vec<Program> = args.iter().map(|arg| program(arg)).collect();
+
+        let curried = prg.curry(&args).expect("curry");
+        assert_eq!(hex::encode(curried.as_slice()), expected);
+
+        let (uncurried, uncurried_args) = curried.uncurry().expect("uncurry");
+        assert_eq!(uncurried, prg);
+        assert_eq!(uncurried_args, args);
+
+        // currying nests, the outer layer is uncurried first
+        let twice = curried.curry(&[program("64")]).expect("curry");
+        assert_eq!(
+            twice.uncurry().expect("uncurry"),
+            (curried, vec![program("64")])
+        );
+    }
+
+    #[rstest]
+    #[case::atom("8378797a")]
+    #[case::not_curried("ff01ff02ff62ff0480")]
+    #[case::nil_terminated("ff02ffff018378797affff04ffff0161ff808080")]
+    #[case::unquoted_arg("ff02ffff018378797affff04ffff0161ffff04ff62ff01808080")]
+    #[case::extra_element("ff02ffff018378797affff04ffff0161ff0180ff8080")]
+    fn test_uncurry_not_curried(#[case] prg: &str) {
+        let prg = program(prg);
+        assert_eq!(prg.uncurry().expect("uncurry"), (prg, vec![]));
+    }
+
+    #[test]
+    fn test_curry_invalid() {
+        let invalid = program("ff01");
+        assert_eq!(invalid.curry(&[]), Err(Error::InvalidClvm));
+        assert_eq!(invalid.uncurry(), Err(Error::InvalidClvm));
+        assert_eq!(
+            program("80").curry(&[program("80"), invalid]),
+            Err(Error::InvalidClvm)
+        );
+    }
+
+    #[test]
+    fn test_curry_standard_puzzle() {
+        let puzzle = program(STANDARD_PUZZLE);
+        let pk = SecretKey::from_seed(&[1; 32]).public_key().to_bytes();
+
+        // the public key is curried in as a 48 byte atom
+        let mut pk_atom = vec![0x80 | 48];
+        pk_atom.extend_from_slice(&pk);
+        let pk_atom = Program::from(pk_atom);
+
+        let curried = puzzle.curry(&[pk_atom.clone()]).expect("curry");
+        let puzzle_hash = tree_hash_from_bytes(puzzle.as_slice()).expect("tree_hash");
+        assert_eq!(
+            tree_hash_from_bytes(curried.as_slice()).expect("tree_hash"),
+            curry_tree_hash(puzzle_hash, &[tree_hash_atom(&pk)])
+        );
+
+        assert_eq!(curried.uncurry().expect("uncurry"), (puzzle, vec![pk_atom]));
+    }
 }
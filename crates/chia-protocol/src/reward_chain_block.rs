@@ -4,6 +4,8 @@ use crate::Bytes32;
 use crate::ProofOfSpace;
 use crate::VDFInfo;
 use chia_bls::G2Element;
+use chia_traits::chia_error::{Error, Result};
+use std::fmt;
 
 #[cfg(feature = "py-bindings")]
 use pyo3::prelude::*;
@@ -53,3 +55,210 @@ impl RewardChainBlock {
         }
     }
 }
+
+impl RewardChainBlock {
+    /// Like `new()`, but fails if the iterations of the VDFs are inconsistent
+    /// with each other, see `validate_iters()`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        weight: u128,
+        height: u32,
+        total_iters: u128,
+        signage_point_index: u8,
+        pos_ss_cc_challenge_hash: Bytes32,
+        proof_of_space: ProofOfSpace,
+        challenge_chain_sp_vdf: Option<VDFInfo>,
+        challenge_chain_sp_signature: G2Element,
+        challenge_chain_ip_vdf: VDFInfo,
+        reward_chain_sp_vdf: Option<VDFInfo>,
+        reward_chain_sp_signature: G2Element,
+        reward_chain_ip_vdf: VDFInfo,
+        infused_challenge_chain_ip_vdf: Option<VDFInfo>,
+        is_transaction_block: bool,
+    ) -> Result<Self> {
+        let ret = Self::new(
+            weight,
+            height,
+            total_iters,
+            signage_point_index,
+            pos_ss_cc_challenge_hash,
+            proof_of_space,
+            challenge_chain_sp_vdf,
+            challenge_chain_sp_signature,
+            challenge_chain_ip_vdf,
+            reward_chain_sp_vdf,
+            reward_chain_sp_signature,
+            reward_chain_ip_vdf,
+            infused_challenge_chain_ip_vdf,
+            is_transaction_block,
+        );
+        ret.validate_iters()?;
+        Ok(ret)
+    }
+
+    /// Checks the invariants between the VDFs of the block, that don't
+    /// require knowing the previous blocks:
+    /// * the signage point VDFs are present unless the signage point is the
+    ///   start of the sub slot (index 0)
+    /// * the challenge chain and reward chain infusion point VDFs have the
+    ///   same number of iterations
+    /// * no VDF has more iterations than the total iterations of the block
+    pub fn validate_iters(&self) -> Result<()> {
+        let has_sp = self.signage_point_index != 0;
+        if self.challenge_chain_sp_vdf.is_some() != has_sp
+            || self.reward_chain_sp_vdf.is_some() != has_sp
+        {
+            return Err(Error::Custom(format!(
+                "signage point VDFs don't match signage point index {}",
+                self.signage_point_index
+            )));
+        }
+
+        let ip_iters = self.challenge_chain_ip_vdf.number_of_iterations;
+        if self.reward_chain_ip_vdf.number_of_iterations != ip_iters {
+            return Err(Error::Custom(format!(
+                "reward chain infusion point VDF iterations {} don't match challenge chain {ip_iters}",
+                self.reward_chain_ip_vdf.number_of_iterations
+            )));
+        }
+
+        let vdfs = [
+            self.challenge_chain_sp_vdf.as_ref(),
+            Some(&self.challenge_chain_ip_vdf),
+            self.reward_chain_sp_vdf.as_ref(),
+            Some(&self.reward_chain_ip_vdf),
+            self.infused_challenge_chain_ip_vdf.as_ref(),
+        ];
+        for vdf in vdfs.into_iter().flatten() {
+            if u128::from(vdf.number_of_iterations) > self.total_iters {
+                return Err(Error::Custom(format!(
+                    "VDF iterations {} exceed total iterations {}",
+                    vdf.number_of_iterations, self.total_iters
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RewardChainBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "RewardChainBlock(height: {}, weight: {}, total_iters: {}, signage_point_index: {}, is_transaction_block: {})",
+            self.height,
+            self.weight,
+            self.total_iters,
+            self.signage_point_index,
+            self.is_transaction_block
+        )
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl RewardChainBlock {
+    #[pyo3(name = "validate_iters")]
+    fn py_validate_iters(&self) -> PyResult<()> {
+        Ok(self.validate_iters()?)
+    }
+
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bytes, ClassgroupElement};
+    use chia_bls::G1Element;
+
+    fn vdf(iters: u64) -> VDFInfo {
+        VDFInfo::new(Bytes32::default(), iters, ClassgroupElement::default())
+    }
+
+    fn block(
+        signage_point_index: u8,
+        cc_sp_vdf: Option<VDFInfo>,
+        rc_sp_vdf: Option<VDFInfo>,
+        cc_ip_iters: u64,
+        rc_ip_iters: u64,
+        icc_ip_vdf: Option<VDFInfo>,
+    ) -> Result<RewardChainBlock> {
+        let proof_of_space = ProofOfSpace::new(
+            Bytes32::new([1; 32]),
+            None,
+            Some(Bytes32::new([2; 32])),
+            G1Element::default(),
+            32,
+            Bytes::new(vec![0x55; 64]),
+        );
+        RewardChainBlock::try_new(
+            1337,
+            42,
+            10_000,
+            signage_point_index,
+            Bytes32::new([3; 32]),
+            proof_of_space,
+            cc_sp_vdf,
+            G2Element::default(),
+            vdf(cc_ip_iters),
+            rc_sp_vdf,
+            G2Element::default(),
+            vdf(rc_ip_iters),
+            icc_ip_vdf,
+            true,
+        )
+    }
+
+    #[test]
+    fn test_valid() {
+        block(0, None, None, 1000, 1000, None).expect("valid");
+        block(3, Some(vdf(500)), Some(vdf(200)), 1000, 1000, None).expect("valid");
+        block(
+            3,
+            Some(vdf(500)),
+            Some(vdf(200)),
+            1000,
+            1000,
+            Some(vdf(3000)),
+        )
+        .expect("valid");
+        block(3, Some(vdf(10_000)), Some(vdf(200)), 10_000, 10_000, None).expect("valid");
+    }
+
+    #[test]
+    fn test_sp_vdf_mismatch() {
+        // index 0 has no signage point VDFs
+        assert!(block(0, Some(vdf(500)), None, 1000, 1000, None).is_err());
+        assert!(block(0, None, Some(vdf(500)), 1000, 1000, None).is_err());
+        // any other index has both
+        assert!(block(3, None, None, 1000, 1000, None).is_err());
+        assert!(block(3, Some(vdf(500)), None, 1000, 1000, None).is_err());
+        assert!(block(3, None, Some(vdf(500)), 1000, 1000, None).is_err());
+    }
+
+    #[test]
+    fn test_ip_vdf_mismatch() {
+        assert!(block(0, None, None, 1000, 1001, None).is_err());
+        assert!(block(0, None, None, 1001, 1000, None).is_err());
+    }
+
+    #[test]
+    fn test_exceeds_total_iters() {
+        assert!(block(0, None, None, 10_001, 10_001, None).is_err());
+        assert!(block(3, Some(vdf(10_001)), Some(vdf(200)), 1000, 1000, None).is_err());
+        assert!(block(3, Some(vdf(500)), Some(vdf(10_001)), 1000, 1000, None).is_err());
+        assert!(block(0, None, None, 1000, 1000, Some(vdf(10_001))).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let rcb = block(3, Some(vdf(500)), Some(vdf(200)), 1000, 1000, None).unwrap();
+        assert_eq!(
+            rcb.to_string(),
+            "RewardChainBlock(height: 42, weight: 1337, total_iters: 10000, signage_point_index: 3, is_transaction_block: true)"
+        );
+    }
+}
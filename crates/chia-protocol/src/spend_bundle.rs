@@ -1,7 +1,10 @@
 use crate::coin_spend::CoinSpend;
+use crate::Amount;
 use crate::Bytes32;
 use crate::Coin;
+use crate::FeeRate;
 use chia_bls::G2Element;
+use chia_sha2::Sha256;
 use chia_streamable_macro::streamable;
 use chia_traits::Streamable;
 use clvm_traits::FromClvm;
@@ -9,7 +12,9 @@ use clvmr::allocator::{NodePtr, SExp};
 use clvmr::cost::Cost;
 use clvmr::op_utils::{first, rest};
 use clvmr::reduction::EvalErr;
+use clvmr::serde::serialized_length_from_bytes;
 use clvmr::Allocator;
+use std::io::Cursor;
 
 #[cfg(feature = "py-bindings")]
 use pyo3::prelude::*;
@@ -22,6 +27,36 @@ pub struct SpendBundle {
     aggregated_signature: G2Element,
 }
 
+/// The CLVM cost assumed per byte of puzzle reveal and solution, by
+/// `SpendBundle::estimate_cost()`. It's about twice the highest execution
+/// cost per generator byte of any mainnet block we test against.
+pub const ESTIMATED_EXECUTION_COST_PER_BYTE: u64 = 10_000;
+
+/// An estimate of the cost of a spend bundle, computed without running any of
+/// its puzzles. See `SpendBundle::estimate_cost()`.
+#[streamable]
+#[derive(Copy)]
+pub struct CostEstimate {
+    // the size of the serialized spend bundle, in bytes
+    serialized_size: u64,
+    // the cost of including that many bytes in a block
+    byte_cost: u64,
+    // the estimated cost of running the puzzles
+    execution_cost: u64,
+    // the sum of the condition costs passed to estimate_cost()
+    condition_cost: u64,
+    // the sum of all of the above
+    cost: u64,
+}
+
+impl CostEstimate {
+    /// The fee to pay for the estimated cost at the specified rate, or None if
+    /// it doesn't fit in a u64.
+    pub fn min_fee(&self, rate: FeeRate) -> Option<u64> {
+        rate.fee_for_cost(self.cost)
+    }
+}
+
 impl SpendBundle {
     pub fn aggregate(spend_bundles: &[SpendBundle]) -> SpendBundle {
         let mut coin_spends = Vec::<CoinSpend>::new();
@@ -40,6 +75,111 @@ impl SpendBundle {
         self.hash().into()
     }
 
+    /// Returns a copy of this spend bundle with the coin spends in canonical
+    /// order, so that parties who agree on the spends also agree on the
+    /// serialized bundle, byte for byte. The spends are sorted by the ID of
+    /// the coin they spend. Ties, which only happen in invalid bundles that
+    /// spend a coin twice, are broken by the puzzle reveal bytes, then by the
+    /// solution bytes. The aggregated signature is left as-is.
+    #[must_use]
+    pub fn normalized(&self) -> SpendBundle {
+        let mut coin_spends: Vec<(Bytes32, &CoinSpend)> = self
+            .coin_spends
+            .iter()
+            .map(|cs| (cs.coin.coin_id(), cs))
+            .collect();
+        coin_spends.sort_by(|(lhs_id, lhs), (rhs_id, rhs)| {
+            lhs_id
+                .cmp(rhs_id)
+                .then_with(|| lhs.puzzle_reveal.as_ref().cmp(rhs.puzzle_reveal.as_ref()))
+                .then_with(|| lhs.solution.as_ref().cmp(rhs.solution.as_ref()))
+        });
+        SpendBundle {
+            coin_spends: coin_spends.into_iter().map(|(_, cs)| cs.clone()).collect(),
+            aggregated_signature: self.aggregated_signature.clone(),
+        }
+    }
+
+    /// A commitment to the coin spends of this bundle, for parties to agree
+    /// on before exchanging signatures. It's the sha256 of the network's
+    /// `genesis_challenge` followed by the serialized coin spends of the
+    /// normalized bundle. The signature isn't included, and neither is the
+    /// order of the spends.
+    pub fn signing_digest(&self, genesis_challenge: &Bytes32) -> Bytes32 {
+        let mut ctx = Sha256::new();
+        ctx.update(genesis_challenge);
+        self.normalized().coin_spends.update_digest(&mut ctx);
+        Bytes32::new(ctx.finalize())
+    }
+
+    /// Estimates the cost of this spend bundle without running it, e.g. to
+    /// suggest a fee in a wallet. The estimate is a heuristic meant to be an
+    /// upper bound of the actual cost, not an exact figure:
+    ///
+    /// * every byte of the serialized spend bundle is charged
+    ///   `per_byte_cost` (`cost_per_byte` in the consensus constants). The
+    ///   spends take up fewer bytes in a block generator.
+    /// * every byte of puzzle reveal and solution is charged
+    ///   `ESTIMATED_EXECUTION_COST_PER_BYTE` of CLVM cost.
+    /// * `condition_costs` has an `(opcode, cost)` pair for every condition
+    ///   the spends are expected to output, e.g. `(51, 1_800_000)` for each
+    ///   `CREATE_COIN`. Their costs are added up.
+    ///
+    /// Puzzles whose cost doesn't scale with their size (like loops) can
+    /// exceed the estimate. The costs saturate at `u64::MAX`.
+    pub fn estimate_cost(
+        &self,
+        per_byte_cost: u64,
+        condition_costs: &[(u16, u64)],
+    ) -> CostEstimate {
+        // the length prefix of the coin spend list and the signature
+        let mut serialized_size: u64 = 4 + 96;
+        let mut program_size: u64 = 0;
+        for cs in &self.coin_spends {
+            serialized_size += cs.serialized_length() as u64;
+            program_size += (cs.puzzle_reveal.len() + cs.solution.len()) as u64;
+        }
+        let byte_cost = serialized_size.saturating_mul(per_byte_cost);
+        let execution_cost = program_size.saturating_mul(ESTIMATED_EXECUTION_COST_PER_BYTE);
+        let condition_cost = condition_costs
+            .iter()
+            .fold(0_u64, |sum, (_opcode, cost)| sum.saturating_add(*cost));
+        CostEstimate {
+            serialized_size,
+            byte_cost,
+            execution_cost,
+            condition_cost,
+            cost: byte_cost
+                .saturating_add(execution_cost)
+                .saturating_add(condition_cost),
+        }
+    }
+
+    /// Parses a serialized spend bundle without copying its puzzle reveals
+    /// and solutions. Each spend is returned as a (coin, puzzle reveal,
+    /// solution) tuple, the way `solution_generator()` takes them, with the
+    /// programs borrowed from `buf`. The aggregated signature is returned
+    /// along with the spends.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_borrowed(
+        buf: &[u8],
+    ) -> chia_traits::Result<(Vec<(Coin, &[u8], &[u8])>, G2Element)> {
+        let mut input = Cursor::new(buf);
+        let count = u32::parse::<false>(&mut input)?;
+        let mut spends = Vec::new();
+        for _ in 0..count {
+            let coin = Coin::parse::<false>(&mut input)?;
+            let puzzle_reveal = borrow_program(&mut input)?;
+            let solution = borrow_program(&mut input)?;
+            spends.push((coin, puzzle_reveal, solution));
+        }
+        let signature = G2Element::parse::<false>(&mut input)?;
+        if input.position() != buf.len() as u64 {
+            return Err(chia_traits::Error::InputTooLarge);
+        }
+        Ok((spends, signature))
+    }
+
     pub fn additions(&self) -> Result<Vec<Coin>, EvalErr> {
         const CREATE_COIN_COST: Cost = 1_800_000;
         const CREATE_COIN: u8 = 51;
@@ -71,8 +211,9 @@ impl SpendBundle {
                     continue;
                 }
                 if buf[0] == CREATE_COIN {
-                    let (puzzle_hash, (amount, _)) = <(Bytes32, (u64, NodePtr))>::from_clvm(&a, c)
-                        .map_err(|_| EvalErr(c, "failed to parse spend".to_string()))?;
+                    let (puzzle_hash, (amount, _)) =
+                        <(Bytes32, (Amount, NodePtr))>::from_clvm(&a, c)
+                            .map_err(|_| EvalErr(c, "failed to parse spend".to_string()))?;
                     ret.push(Coin {
                         parent_coin_info,
                         puzzle_hash,
@@ -89,6 +230,19 @@ impl SpendBundle {
     }
 }
 
+// the serialized CLVM program at the current position, borrowed from the buffer
+fn borrow_program<'a>(input: &mut Cursor<&'a [u8]>) -> chia_traits::Result<&'a [u8]> {
+    let buf: &'a [u8] = input.get_ref();
+    let pos = input.position() as usize;
+    let rest = buf.get(pos..).ok_or(chia_traits::Error::EndOfBuffer)?;
+    let len = serialized_length_from_bytes(rest).map_err(|_e| chia_traits::Error::EndOfBuffer)?;
+    let program = rest
+        .get(..len as usize)
+        .ok_or(chia_traits::Error::EndOfBuffer)?;
+    input.set_position(input.position() + len);
+    Ok(program)
+}
+
 #[cfg(feature = "py-bindings")]
 #[pymethods]
 #[allow(clippy::needless_pass_by_value)]
@@ -130,6 +284,25 @@ impl SpendBundle {
         self.name()
     }
 
+    #[pyo3(name = "normalized")]
+    fn py_normalized(&self) -> Self {
+        self.normalized()
+    }
+
+    #[pyo3(name = "signing_digest")]
+    fn py_signing_digest(&self, genesis_challenge: Bytes32) -> Bytes32 {
+        self.signing_digest(&genesis_challenge)
+    }
+
+    #[pyo3(name = "estimate_cost")]
+    fn py_estimate_cost(
+        &self,
+        per_byte_cost: u64,
+        condition_costs: Vec<(u16, u64)>,
+    ) -> CostEstimate {
+        self.estimate_cost(per_byte_cost, &condition_costs)
+    }
+
     fn removals(&self) -> Vec<Coin> {
         let mut ret = Vec::<Coin>::with_capacity(self.coin_spends.len());
         for cs in &self.coin_spends {
@@ -145,10 +318,20 @@ impl SpendBundle {
     }
 }
 
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl CostEstimate {
+    #[pyo3(name = "min_fee")]
+    fn py_min_fee(&self, rate: FeeRate) -> Option<u64> {
+        self.min_fee(rate)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::Program;
+    use chia_traits::{ParseLimit, ParseLimits};
     use rstest::rstest;
     use std::fs;
 
@@ -184,7 +367,49 @@ mod tests {
             additions[0].puzzle_hash.as_ref(),
             &hex::decode(expect_ph).expect("hex::decode")
         );
-        assert_eq!(additions[0].amount, 1);
+        assert_eq!(additions[0].amount, Amount::MOJO);
+    }
+
+    #[rstest]
+    #[case(&[], &[], 100, 0, 0)]
+    #[case(&["ff0180"], &[], 100 + 72 + 4, 4 * ESTIMATED_EXECUTION_COST_PER_BYTE, 0)]
+    #[case(&["ff0180", "01"], &[(51, 1_800_000), (50, 1_200_000), (51, 1_800_000)], 100 + 2 * 72 + 6, 6 * ESTIMATED_EXECUTION_COST_PER_BYTE, 4_800_000)]
+    fn test_estimate_cost(
+        #[case] puzzles: &[&str],
+        #[case] condition_costs: &[(u16, u64)],
+        #[case] serialized_size: u64,
+        #[case] execution_cost: u64,
+        #[case] condition_cost: u64,
+    ) {
+        let coin_spends = puzzles
+            .iter()
+            .map(|puzzle| {
+                CoinSpend::new(
+                    Coin::new(Bytes32::new([1; 32]), Bytes32::new([2; 32]), Amount::MOJO),
+                    Program::new(hex::decode(puzzle).unwrap().into()),
+                    Program::default(),
+                )
+            })
+            .collect();
+        let bundle = SpendBundle::new(coin_spends, G2Element::default());
+        assert_eq!(bundle.to_bytes().unwrap().len() as u64, serialized_size);
+
+        let estimate = bundle.estimate_cost(12_000, condition_costs);
+        assert_eq!(estimate.serialized_size, serialized_size);
+        assert_eq!(estimate.byte_cost, serialized_size * 12_000);
+        assert_eq!(estimate.execution_cost, execution_cost);
+        assert_eq!(estimate.condition_cost, condition_cost);
+        assert_eq!(
+            estimate.cost,
+            estimate.byte_cost + execution_cost + condition_cost
+        );
+        assert_eq!(estimate.min_fee(FeeRate::new(5)), Some(estimate.cost * 5));
+        assert_eq!(estimate.min_fee(FeeRate::new(u64::MAX)), None);
+
+        // the costs saturate rather than overflow
+        let estimate = bundle.estimate_cost(u64::MAX, &[(51, u64::MAX)]);
+        assert_eq!(estimate.byte_cost, u64::MAX);
+        assert_eq!(estimate.cost, u64::MAX);
     }
 
     fn test_impl<F: Fn(Coin, SpendBundle)>(solution: &str, body: F) {
@@ -198,7 +423,7 @@ mod tests {
                 .unwrap()
                 .try_into()
                 .unwrap(),
-            1,
+            Amount::new(1),
         );
         let spend = CoinSpend::new(
             test_coin,
@@ -232,7 +457,7 @@ ff01\
                     .unwrap()
                     .try_into()
                     .unwrap(),
-                1,
+                Amount::new(1),
             );
             assert_eq!(additions, [new_coin]);
         });
@@ -271,4 +496,152 @@ ffff0101\
             assert_eq!(bundle.additions().unwrap_err().1, "failed to parse spend");
         });
     }
+
+    #[rstest]
+    #[case(ParseLimits { max_vec_len: 1000, ..Default::default() }, ParseLimit::VecLen)]
+    #[case(ParseLimits { max_total_alloc: 1_000_000, ..Default::default() }, ParseLimit::TotalAlloc)]
+    fn test_parse_limits(#[case] limits: ParseLimits, #[case] expected: ParseLimit) {
+        // a spend bundle claiming to have 4 billion coin spends
+        let mut input = vec![0xff; 4];
+        input.extend_from_slice(&G2Element::default().to_bytes());
+        assert_eq!(
+            SpendBundle::parse_with_limits(&input, limits),
+            Err(chia_traits::Error::LimitExceeded(expected))
+        );
+        assert_eq!(
+            SpendBundle::from_bytes(&input),
+            Err(chia_traits::Error::EndOfBuffer)
+        );
+        assert_eq!(
+            SpendBundle::parse_borrowed(&input),
+            Err(chia_traits::Error::EndOfBuffer)
+        );
+    }
+
+    #[rstest]
+    #[case("1000101")]
+    #[case("3000253")]
+    fn test_parse_borrowed(#[case] filename: &str) {
+        let mut buf = fs::read(format!("../../test-bundles/{filename}.bundle")).expect("read file");
+        let bundle = SpendBundle::from_bytes(&buf).expect("parse SpendBundle");
+
+        let (spends, signature) = SpendBundle::parse_borrowed(&buf).expect("parse_borrowed");
+        assert_eq!(signature, bundle.aggregated_signature);
+        let expected: Vec<(Coin, &[u8], &[u8])> = bundle
+            .coin_spends
+            .iter()
+            .map(|cs| (cs.coin, cs.puzzle_reveal.as_ref(), cs.solution.as_ref()))
+            .collect();
+        assert_eq!(spends, expected);
+
+        assert_eq!(
+            SpendBundle::parse_borrowed(&buf[..buf.len() - 1]),
+            Err(chia_traits::Error::EndOfBuffer)
+        );
+        buf.push(0);
+        assert_eq!(
+            SpendBundle::parse_borrowed(&buf),
+            Err(chia_traits::Error::InputTooLarge)
+        );
+    }
+
+    fn signing_test_spends() -> Vec<CoinSpend> {
+        (0_u8..5)
+            .map(|i| {
+                CoinSpend::new(
+                    Coin::new(Bytes32::new([i; 32]), Bytes32::new([2; 32]), Amount::MOJO),
+                    Program::new(vec![1_u8].into()),
+                    Program::new(vec![0x84, i, 2, 3, 4].into()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_normalized() {
+        let spends = signing_test_spends();
+        let mut signature = G2Element::default();
+        signature.aggregate(&chia_bls::sign(
+            &chia_bls::SecretKey::from_seed(&[1; 32]),
+            b"foo",
+        ));
+        let genesis_challenge = Bytes32::new([0xcc; 32]);
+
+        let bundle = SpendBundle::new(spends.clone(), signature.clone());
+        let mut reversed = spends.clone();
+        reversed.reverse();
+        let reversed = SpendBundle::new(reversed, signature.clone());
+        let mut rotated = spends.clone();
+        rotated.rotate_left(2);
+        let rotated = SpendBundle::new(rotated, G2Element::default());
+
+        let normalized = bundle.normalized();
+        assert_eq!(normalized.aggregated_signature, signature);
+        assert_eq!(normalized.coin_spends.len(), spends.len());
+        assert!(normalized
+            .coin_spends
+            .windows(2)
+            .all(|w| w[0].coin.coin_id() <= w[1].coin.coin_id()));
+        assert_eq!(normalized.normalized(), normalized);
+
+        assert_eq!(
+            reversed.normalized().to_bytes().unwrap(),
+            normalized.to_bytes().unwrap()
+        );
+        assert_eq!(
+            reversed.signing_digest(&genesis_challenge),
+            bundle.signing_digest(&genesis_challenge)
+        );
+        // the signature isn't part of the digest
+        assert_eq!(
+            rotated.signing_digest(&genesis_challenge),
+            bundle.signing_digest(&genesis_challenge)
+        );
+        // but the network is
+        assert_ne!(
+            bundle.signing_digest(&Bytes32::new([0xdd; 32])),
+            bundle.signing_digest(&genesis_challenge)
+        );
+    }
+
+    #[test]
+    fn test_normalized_ties() {
+        // spends of the same coin are ordered by puzzle, then by solution
+        let coin = Coin::new(Bytes32::new([1; 32]), Bytes32::new([2; 32]), Amount::MOJO);
+        let spend = |puzzle: u8, solution: u8| {
+            CoinSpend::new(
+                coin,
+                Program::new(vec![puzzle].into()),
+                Program::new(vec![solution].into()),
+            )
+        };
+        let bundle = SpendBundle::new(
+            vec![spend(2, 1), spend(1, 2), spend(1, 1)],
+            G2Element::default(),
+        );
+        assert_eq!(
+            bundle.normalized().coin_spends,
+            vec![spend(1, 1), spend(1, 2), spend(2, 1)]
+        );
+    }
+
+    #[test]
+    fn test_signing_digest_solution() {
+        let genesis_challenge = Bytes32::new([0xcc; 32]);
+        let spends = signing_test_spends();
+        let digest = SpendBundle::new(spends.clone(), G2Element::default())
+            .signing_digest(&genesis_challenge);
+
+        // changing any byte of any solution changes the digest
+        for (i, spend) in spends.iter().enumerate() {
+            for pos in 0..spend.solution.len() {
+                let mut changed = spends.clone();
+                let mut solution = spend.solution.to_vec();
+                solution[pos] ^= 1;
+                changed[i].solution = Program::new(solution.into());
+                let bundle = SpendBundle::new(changed, G2Element::default());
+                assert_ne!(bundle.signing_digest(&genesis_challenge), digest);
+            }
+        }
+    }
 }
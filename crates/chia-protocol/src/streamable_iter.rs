@@ -0,0 +1,153 @@
+use chia_traits::chia_error::{Error, Result};
+use chia_traits::Streamable;
+use std::io::Cursor;
+use std::marker::PhantomData;
+
+/// Parses the items of a serialized list one at a time, instead of
+/// materializing the whole `Vec` up front. This is for large messages, like
+/// `RespondBlocks`, where the caller can validate and drop each item before
+/// the next one is parsed.
+///
+/// The list must be the last field of the buffer. Once all items have been
+/// returned, any bytes left over are reported as `InputTooLarge`. After the
+/// first error the iterator is exhausted.
+pub struct StreamableIter<'a, T> {
+    input: Cursor<&'a [u8]>,
+    remaining: u32,
+    done: bool,
+    _item: PhantomData<T>,
+}
+
+impl<'a, T: Streamable> StreamableIter<'a, T> {
+    /// Reads the length prefix of the list at the cursor's position.
+    pub fn new(mut input: Cursor<&'a [u8]>) -> Result<Self> {
+        let remaining = u32::parse::<false>(&mut input)?;
+        Ok(Self {
+            input,
+            remaining,
+            done: false,
+            _item: PhantomData,
+        })
+    }
+
+    /// The number of items that have not been parsed yet.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+}
+
+impl<T: Streamable> Iterator for StreamableIter<'_, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        if self.remaining == 0 {
+            self.done = true;
+            if self.input.position() == self.input.get_ref().len() as u64 {
+                return None;
+            }
+            return Some(Err(Error::InputTooLarge));
+        }
+
+        self.remaining -= 1;
+        let item = T::parse::<false>(&mut self.input);
+        if item.is_err() {
+            self.done = true;
+        }
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            (0, Some(0))
+        } else {
+            // there may be one more item, reporting trailing bytes
+            (0, Some(self.remaining as usize + 1))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fullblock::tests::test_block;
+    use crate::{Coin, FullBlock};
+    use rstest::rstest;
+
+    fn parse_all<T: Streamable>(buf: &[u8]) -> Vec<Result<T>> {
+        StreamableIter::<T>::new(Cursor::new(buf))
+            .expect("length prefix")
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_list() {
+        let buf = Vec::<Coin>::new().to_bytes().unwrap();
+        assert!(parse_all::<Coin>(&buf).is_empty());
+    }
+
+    #[test]
+    fn test_missing_length_prefix() {
+        let buf = [0_u8, 0, 0];
+        assert_eq!(
+            StreamableIter::<u32>::new(Cursor::new(&buf[..])).err(),
+            Some(Error::EndOfBuffer)
+        );
+    }
+
+    #[test]
+    fn test_matches_vec_parse() {
+        let blocks = vec![test_block(true), test_block(false), test_block(true)];
+        let buf = blocks.to_bytes().unwrap();
+
+        let mut iter = StreamableIter::<FullBlock>::new(Cursor::new(buf.as_slice())).unwrap();
+        assert_eq!(iter.remaining(), 3);
+        for block in &blocks {
+            assert_eq!(&iter.next().unwrap().unwrap(), block);
+        }
+        assert_eq!(iter.remaining(), 0);
+        assert!(iter.next().is_none());
+        assert!(iter.next().is_none());
+    }
+
+    #[rstest]
+    fn test_truncated(#[values(1, 10, 100)] cut: usize) {
+        let blocks = vec![test_block(true), test_block(false)];
+        let buf = blocks.to_bytes().unwrap();
+        let first_len = blocks[0].to_bytes().unwrap().len();
+
+        // cut the buffer in the middle of the second block. The first block is
+        // still returned, the second fails and ends the iteration
+        let truncated = &buf[..buf.len() - cut];
+        assert!(truncated.len() > 4 + first_len);
+        let items = parse_all::<FullBlock>(truncated);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].as_ref().unwrap(), &blocks[0]);
+        assert_eq!(items[1], Err(Error::EndOfBuffer));
+    }
+
+    #[test]
+    fn test_count_too_large() {
+        // the length prefix claims 3 items but there are only 2
+        let mut buf = vec![1_u32, 2].to_bytes().unwrap();
+        buf[3] = 3;
+        assert_eq!(
+            parse_all::<u32>(&buf),
+            vec![Ok(1), Ok(2), Err(Error::EndOfBuffer)]
+        );
+    }
+
+    #[test]
+    fn test_count_too_small() {
+        // the length prefix claims 1 item but there are 2
+        let mut buf = vec![1_u32, 2].to_bytes().unwrap();
+        buf[3] = 1;
+        assert_eq!(
+            parse_all::<u32>(&buf),
+            vec![Ok(1), Err(Error::InputTooLarge)]
+        );
+    }
+}
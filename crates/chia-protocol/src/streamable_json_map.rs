@@ -0,0 +1,34 @@
+use chia_streamable_macro::streamable;
+
+use crate::{Bytes, Bytes32};
+
+/// A struct with a `#[streamable(json_map)]` field, to test its JSON
+/// conversion in the python bindings. It's not part of the protocol.
+#[streamable]
+pub struct JsonMapStreamableTest {
+    #[streamable(json_map)]
+    amounts: Vec<(Bytes32, u64)>,
+    proofs: Vec<(Bytes32, Bytes)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chia_traits::Streamable;
+
+    #[test]
+    fn test_json_map_struct() {
+        let value = JsonMapStreamableTest::new(
+            vec![(Bytes32::new([2; 32]), 1337), (Bytes32::new([1; 32]), 42)],
+            vec![(Bytes32::new([3; 32]), Bytes::from(&[1, 2, 3][..]))],
+        );
+
+        // json_map only affects the JSON conversion, the fields are streamed
+        // like any other list, in order
+        let bytes = value.to_bytes().unwrap();
+        let mut expected = value.amounts.to_bytes().unwrap();
+        expected.extend(value.proofs.to_bytes().unwrap());
+        assert_eq!(bytes, expected);
+        assert_eq!(JsonMapStreamableTest::from_bytes(&bytes).unwrap(), value);
+    }
+}
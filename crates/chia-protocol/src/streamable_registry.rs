@@ -0,0 +1,173 @@
+use arbitrary::{Arbitrary, Unstructured};
+use chia_traits::Streamable;
+
+use crate::*;
+
+/// A protocol type that can be generated from `Arbitrary` and round-tripped
+/// through its serialization, without knowing the concrete type. The name is
+/// the same as the name of the Python class.
+pub struct StreamableType {
+    pub name: &'static str,
+    /// Generates an arbitrary instance of the type and serializes it
+    pub arbitrary: fn(&mut Unstructured<'_>) -> arbitrary::Result<Vec<u8>>,
+    /// Parses the buffer as the type and serializes it again
+    pub round_trip: fn(&[u8]) -> chia_traits::Result<Vec<u8>>,
+}
+
+macro_rules! streamable_types {
+    ($($ty:ident,)*) => {
+        &[$(
+            StreamableType {
+                name: stringify!($ty),
+                arbitrary: |u| {
+                    <$ty as Arbitrary<'_>>::arbitrary(u)?
+                        .to_bytes()
+                        .map_err(|_| arbitrary::Error::IncorrectFormat)
+                },
+                round_trip: |buf| <$ty as Streamable>::from_bytes(buf)?.to_bytes(),
+            },
+        )*]
+    };
+}
+
+/// All protocol types exposed to Python
+pub static STREAMABLE_TYPES: &[StreamableType] = streamable_types!(
+    Program,
+    Message,
+    ClassgroupElement,
+    Coin,
+    CoinSpend,
+    CoinState,
+    EndOfSubSlotBundle,
+    FeeRate,
+    FeeEstimate,
+    FeeEstimateGroup,
+    TransactionsInfo,
+    FoliageTransactionBlock,
+    FoliageBlockData,
+    Foliage,
+    FullBlock,
+    BlockRecord,
+    UnfinishedBlock,
+    HeaderBlock,
+    PoolTarget,
+    ProofOfSpace,
+    RewardChainBlockUnfinished,
+    RewardChainBlock,
+    ChallengeBlockInfo,
+    ChallengeChainSubSlot,
+    InfusedChallengeChainSubSlot,
+    RewardChainSubSlot,
+    SubSlotProofs,
+    SpendBundle,
+    VDFInfo,
+    VDFProof,
+    PuzzleSolutionResponse,
+    SubSlotData,
+    SubEpochChallengeSegment,
+    SubEpochSegments,
+    SubEpochSummary,
+    WeightProof,
+    PeerInfo,
+    TimestampedPeerInfo,
+    RecentChainData,
+    ProofBlockHeader,
+    SubEpochData,
+    Handshake,
+    ProtocolError,
+    // Wallet Protocol
+    RequestPuzzleSolution,
+    RespondPuzzleSolution,
+    RejectPuzzleSolution,
+    SendTransaction,
+    TransactionAck,
+    NewPeakWallet,
+    RequestBlockHeader,
+    RespondBlockHeader,
+    RejectHeaderRequest,
+    RequestRemovals,
+    RespondRemovals,
+    RejectRemovalsRequest,
+    RequestAdditions,
+    RespondAdditions,
+    RejectAdditionsRequest,
+    RespondBlockHeaders,
+    RejectBlockHeaders,
+    RequestBlockHeaders,
+    RequestHeaderBlocks,
+    RejectHeaderBlocks,
+    RespondHeaderBlocks,
+    RegisterForPhUpdates,
+    RespondToPhUpdates,
+    RegisterForCoinUpdates,
+    RespondToCoinUpdates,
+    CoinStateUpdate,
+    RequestChildren,
+    RespondChildren,
+    RequestSesInfo,
+    RespondSesInfo,
+    RequestFeeEstimates,
+    RespondFeeEstimates,
+    // Full Node Protocol
+    NewPeak,
+    NewTransaction,
+    RequestTransaction,
+    RespondTransaction,
+    RequestProofOfWeight,
+    RespondProofOfWeight,
+    RequestBlock,
+    RejectBlock,
+    RequestBlocks,
+    RespondBlocks,
+    RejectBlocks,
+    RespondBlock,
+    NewUnfinishedBlock,
+    RequestUnfinishedBlock,
+    RespondUnfinishedBlock,
+    NewSignagePointOrEndOfSubSlot,
+    RequestSignagePointOrEndOfSubSlot,
+    RespondSignagePoint,
+    RespondEndOfSubSlot,
+    RequestMempoolTransactions,
+    NewCompactVDF,
+    RequestCompactVDF,
+    RespondCompactVDF,
+    RequestPeers,
+    RespondPeers,
+    // Introducer Protocol
+    RequestPeersIntroducer,
+    RespondPeersIntroducer,
+);
+
+pub fn find_streamable_type(name: &str) -> Option<&'static StreamableType> {
+    STREAMABLE_TYPES.iter().find(|t| t.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_unique_names() {
+        let names: HashSet<&str> = STREAMABLE_TYPES.iter().map(|t| t.name).collect();
+        assert_eq!(names.len(), STREAMABLE_TYPES.len());
+        assert_eq!(find_streamable_type("Coin").unwrap().name, "Coin");
+        assert!(find_streamable_type("Foobar").is_none());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data: Vec<u8> = (0..4096_u32).map(|i| (i * 7919 % 251) as u8).collect();
+        for ty in STREAMABLE_TYPES {
+            let mut u = Unstructured::new(&data);
+            let bytes = (ty.arbitrary)(&mut u).unwrap();
+            assert_eq!((ty.round_trip)(&bytes).unwrap(), bytes, "{}", ty.name);
+
+            // trailing garbage is an error
+            let mut corrupt = bytes.clone();
+            corrupt.push(0);
+            assert!((ty.round_trip)(&corrupt).is_err(), "{}", ty.name);
+        }
+    }
+}
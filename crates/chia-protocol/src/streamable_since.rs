@@ -0,0 +1,79 @@
+use chia_streamable_macro::streamable;
+
+use crate::Bytes32;
+
+/// The first version of a message.
+#[streamable]
+pub struct SinceTestV1 {
+    a: u32,
+    b: Option<u8>,
+}
+
+/// The same message, with the fields that were added in later versions.
+#[streamable]
+pub struct SinceTestV3 {
+    a: u32,
+    b: Option<u8>,
+    #[streamable(since = 2)]
+    c: Option<Bytes32>,
+    #[streamable(since = 3)]
+    d: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chia_traits::Streamable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(SinceTestV1::new(1337, None))]
+    #[case(SinceTestV1::new(1337, Some(42)))]
+    fn test_old_to_new(#[case] old: SinceTestV1) {
+        let bytes = old.to_bytes().unwrap();
+        let new = SinceTestV3::from_bytes(&bytes).unwrap();
+        assert_eq!(new, SinceTestV3::new(old.a, old.b, None, None));
+
+        // the fields that aren't set aren't streamed, so the old version can
+        // still parse it, and it has the same hash
+        assert_eq!(new.to_bytes().unwrap(), bytes);
+        assert_eq!(new.hash(), old.hash());
+        assert_eq!(SinceTestV1::from_bytes(&bytes).unwrap(), old);
+    }
+
+    #[rstest]
+    #[case(Some(Bytes32::new([7; 32])), None, 33)]
+    #[case(None, Some("foo".to_string()), 1 + 1 + 4 + 3)]
+    #[case(Some(Bytes32::new([7; 32])), Some("foo".to_string()), 33 + 1 + 4 + 3)]
+    fn test_new_fields(
+        #[case] c: Option<Bytes32>,
+        #[case] d: Option<String>,
+        #[case] extra: usize,
+    ) {
+        let old = SinceTestV1::new(1337, Some(42));
+        let new = SinceTestV3::new(1337, Some(42), c, d);
+        let old_bytes = old.to_bytes().unwrap();
+        let bytes = new.to_bytes().unwrap();
+        assert_eq!(bytes.len(), old_bytes.len() + extra);
+        assert_eq!(&bytes[..old_bytes.len()], old_bytes.as_slice());
+        assert_ne!(new.hash(), old.hash());
+        assert_eq!(SinceTestV3::from_bytes(&bytes).unwrap(), new);
+    }
+
+    #[test]
+    fn test_none_before_some() {
+        // a field that isn't set is streamed as usual if a later one is set
+        let new = SinceTestV3::new(1, None, None, Some(String::new()));
+        assert_eq!(new.to_bytes().unwrap(), [0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 0]);
+    }
+
+    #[rstest]
+    // the input ends in the middle of a field
+    #[case(&[0, 0, 0, 1, 0, 1, 7])]
+    #[case(&[0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 3, b'f'])]
+    // the input doesn't end at all
+    #[case(&[0, 0, 0, 1, 0, 0, 0, 0xff])]
+    fn test_invalid(#[case] bytes: &[u8]) {
+        assert!(SinceTestV3::from_bytes(bytes).is_err());
+    }
+}
@@ -0,0 +1,34 @@
+use chia_streamable_macro::streamable;
+
+use crate::Bytes32;
+
+/// A tuple struct, to test the python bindings `#[streamable]` generates for
+/// them. It's not part of the protocol.
+#[streamable]
+pub struct TupleStreamableTest(Bytes32, u64, Option<String>, Vec<u32>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chia_traits::Streamable;
+
+    #[test]
+    fn test_tuple_struct() {
+        let value = TupleStreamableTest::new(
+            Bytes32::new([1; 32]),
+            1337,
+            Some("foobar".to_string()),
+            vec![1, 2, 3],
+        );
+        assert_eq!(value.1, 1337);
+
+        // the fields are streamed in order, like a named struct
+        let bytes = value.to_bytes().unwrap();
+        let mut expected = [1; 32].to_vec();
+        expected.extend(1337_u64.to_bytes().unwrap());
+        expected.extend(Some("foobar".to_string()).to_bytes().unwrap());
+        expected.extend(vec![1_u32, 2, 3].to_bytes().unwrap());
+        assert_eq!(bytes, expected);
+        assert_eq!(TupleStreamableTest::from_bytes(&bytes).unwrap(), value);
+    }
+}
@@ -2,10 +2,13 @@ use chia_streamable_macro::streamable;
 
 use crate::Bytes32;
 use crate::EndOfSubSlotBundle;
+use crate::FullBlock;
 use crate::Program;
-use crate::RewardChainBlockUnfinished;
-use crate::VDFProof;
 use crate::{Foliage, FoliageTransactionBlock, TransactionsInfo};
+use crate::{RewardChainBlock, RewardChainBlockUnfinished};
+use crate::{VDFInfo, VDFProof};
+use chia_bls::verify;
+use chia_traits::chia_error::{Error, Result};
 use chia_traits::Streamable;
 
 #[streamable]
@@ -22,6 +25,28 @@ pub struct UnfinishedBlock {
     transactions_generator_ref_list: Vec<u32>, // List of block heights of previous generators referenced in this block
 }
 
+// The parts of a block that are only known once it has been infused
+#[streamable]
+pub struct InfusionPoint {
+    challenge_chain_ip_vdf: VDFInfo,
+    challenge_chain_ip_proof: VDFProof,
+    reward_chain_ip_vdf: VDFInfo,
+    reward_chain_ip_proof: VDFProof,
+    infused_challenge_chain_ip_vdf: Option<VDFInfo>, // Iff deficit < 16
+    infused_challenge_chain_ip_proof: Option<VDFProof>, // Iff deficit < 4
+    weight: u128,
+    height: u32,
+    is_transaction_block: bool,
+}
+
+fn check(condition: bool, msg: &str) -> Result<()> {
+    if condition {
+        Ok(())
+    } else {
+        Err(Error::Custom(msg.to_string()))
+    }
+}
+
 impl UnfinishedBlock {
     pub fn prev_header_hash(&self) -> Bytes32 {
         self.foliage.prev_block_hash
@@ -38,6 +63,123 @@ impl UnfinishedBlock {
     pub fn total_iters(&self) -> u128 {
         self.reward_chain_block.total_iters
     }
+
+    /// Turns this block into a `FullBlock` once its infusion point is known.
+    /// If it didn't end up being a transaction block, the transactions parts
+    /// are dropped, just like the full node does.
+    ///
+    /// This only checks that the parts of the block are consistent with each
+    /// other: that the foliage commits to the reward chain block and the
+    /// foliage transaction block, that every VDF has a proof and that the
+    /// foliage signatures are valid for the plot key. It does not validate the
+    /// block against the chain, nor the VDF proofs themselves.
+    pub fn complete(self, infusion: InfusionPoint) -> Result<FullBlock> {
+        let partial_hash = self.partial_hash();
+        check(
+            self.foliage.reward_block_hash == partial_hash
+                && self.foliage.foliage_block_data.unfinished_reward_block_hash == partial_hash,
+            "foliage does not commit to the reward chain block",
+        )?;
+
+        let rc_block = self.reward_chain_block;
+        check(
+            rc_block.challenge_chain_sp_vdf.is_some() == self.challenge_chain_sp_proof.is_some(),
+            "challenge chain signage point VDF and proof mismatch",
+        )?;
+        check(
+            rc_block.reward_chain_sp_vdf.is_some() == self.reward_chain_sp_proof.is_some(),
+            "reward chain signage point VDF and proof mismatch",
+        )?;
+        check(
+            infusion.infused_challenge_chain_ip_vdf.is_some()
+                == infusion.infused_challenge_chain_ip_proof.is_some(),
+            "infused challenge chain VDF and proof mismatch",
+        )?;
+
+        let plot_key = &rc_block.proof_of_space.plot_public_key;
+        let mut foliage = self.foliage;
+        check(
+            verify(
+                &foliage.foliage_block_data_signature,
+                plot_key,
+                foliage.foliage_block_data.hash(),
+            ),
+            "invalid foliage block data signature",
+        )?;
+
+        let (
+            foliage_transaction_block,
+            transactions_info,
+            transactions_generator,
+            transactions_generator_ref_list,
+        ) = if infusion.is_transaction_block {
+            let (Some(ftb), Some(info)) = (self.foliage_transaction_block, self.transactions_info)
+            else {
+                return Err(Error::Custom(
+                    "transaction block is missing its foliage transaction block or transactions info"
+                        .to_string(),
+                ));
+            };
+            let ftb_hash = ftb.hash();
+            check(
+                foliage.foliage_transaction_block_hash == Some(ftb_hash.into()),
+                "foliage does not commit to the foliage transaction block",
+            )?;
+            check(
+                ftb.transactions_info_hash == info.hash().into(),
+                "foliage transaction block does not commit to the transactions info",
+            )?;
+            check(
+                foliage
+                    .foliage_transaction_block_signature
+                    .as_ref()
+                    .is_some_and(|sig| verify(sig, plot_key, ftb_hash)),
+                "invalid foliage transaction block signature",
+            )?;
+            (
+                Some(ftb),
+                Some(info),
+                self.transactions_generator,
+                self.transactions_generator_ref_list,
+            )
+        } else {
+            foliage.foliage_transaction_block_hash = None;
+            foliage.foliage_transaction_block_signature = None;
+            (None, None, None, Vec::new())
+        };
+
+        let reward_chain_block = RewardChainBlock {
+            weight: infusion.weight,
+            height: infusion.height,
+            total_iters: rc_block.total_iters,
+            signage_point_index: rc_block.signage_point_index,
+            pos_ss_cc_challenge_hash: rc_block.pos_ss_cc_challenge_hash,
+            proof_of_space: rc_block.proof_of_space,
+            challenge_chain_sp_vdf: rc_block.challenge_chain_sp_vdf,
+            challenge_chain_sp_signature: rc_block.challenge_chain_sp_signature,
+            challenge_chain_ip_vdf: infusion.challenge_chain_ip_vdf,
+            reward_chain_sp_vdf: rc_block.reward_chain_sp_vdf,
+            reward_chain_sp_signature: rc_block.reward_chain_sp_signature,
+            reward_chain_ip_vdf: infusion.reward_chain_ip_vdf,
+            infused_challenge_chain_ip_vdf: infusion.infused_challenge_chain_ip_vdf,
+            is_transaction_block: infusion.is_transaction_block,
+        };
+
+        Ok(FullBlock {
+            finished_sub_slots: self.finished_sub_slots,
+            reward_chain_block,
+            challenge_chain_sp_proof: self.challenge_chain_sp_proof,
+            challenge_chain_ip_proof: infusion.challenge_chain_ip_proof,
+            reward_chain_sp_proof: self.reward_chain_sp_proof,
+            reward_chain_ip_proof: infusion.reward_chain_ip_proof,
+            infused_challenge_chain_ip_proof: infusion.infused_challenge_chain_ip_proof,
+            foliage,
+            foliage_transaction_block,
+            transactions_info,
+            transactions_generator,
+            transactions_generator_ref_list,
+        })
+    }
 }
 
 #[cfg(feature = "py-bindings")]
@@ -70,4 +212,266 @@ impl UnfinishedBlock {
     fn py_total_iters<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         ChiaToPython::to_python(&self.total_iters(), py)
     }
+
+    #[pyo3(name = "complete")]
+    fn py_complete(&self, infusion: InfusionPoint) -> PyResult<FullBlock> {
+        Ok(self.clone().complete(infusion)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bytes, ClassgroupElement, FoliageBlockData, PoolTarget, ProofOfSpace};
+    use chia_bls::{sign, G2Element, SecretKey};
+    use rstest::rstest;
+
+    fn vdf_info(iters: u8) -> VDFInfo {
+        VDFInfo::new(
+            Bytes32::new([iters; 32]),
+            iters.into(),
+            ClassgroupElement::default(),
+        )
+    }
+
+    fn vdf_proof(witness_type: u8) -> VDFProof {
+        VDFProof::new(witness_type, Bytes::new(vec![witness_type; 8]), false)
+    }
+
+    // a full block whose parts are consistent with each other, signed by the
+    // plot key
+    fn full_block(transaction_block: bool) -> FullBlock {
+        let plot_key = SecretKey::from_seed(&[1; 32]);
+        let proof_of_space = ProofOfSpace::new(
+            Bytes32::new([2; 32]),
+            None,
+            Some(Bytes32::new([3; 32])),
+            plot_key.public_key(),
+            32,
+            Bytes::new(vec![4; 64]),
+        );
+        let reward_chain_block = RewardChainBlock::new(
+            1337,
+            42,
+            123_456_789,
+            3,
+            Bytes32::new([5; 32]),
+            proof_of_space,
+            Some(vdf_info(10)),
+            G2Element::default(),
+            vdf_info(20),
+            Some(vdf_info(30)),
+            G2Element::default(),
+            vdf_info(40),
+            Some(vdf_info(50)),
+            transaction_block,
+        );
+        let partial_hash: Bytes32 = reward_chain_block.get_unfinished().hash().into();
+
+        let transactions_info = TransactionsInfo::new(
+            Bytes32::new([6; 32]),
+            Bytes32::new([7; 32]),
+            G2Element::default(),
+            100,
+            200,
+            Vec::new(),
+        );
+        let foliage_transaction_block = FoliageTransactionBlock::new(
+            Bytes32::new([8; 32]),
+            1_700_000_000,
+            Bytes32::new([9; 32]),
+            Bytes32::new([10; 32]),
+            Bytes32::new([11; 32]),
+            transactions_info.hash().into(),
+        );
+        let ftb_hash = foliage_transaction_block.hash();
+
+        let foliage_block_data = FoliageBlockData::new(
+            partial_hash,
+            PoolTarget::new(Bytes32::new([12; 32]), 0),
+            None,
+            Bytes32::new([13; 32]),
+            Bytes32::default(),
+        );
+        let foliage = Foliage::new(
+            Bytes32::new([14; 32]),
+            partial_hash,
+            foliage_block_data.clone(),
+            sign(&plot_key, foliage_block_data.hash()),
+            transaction_block.then(|| ftb_hash.into()),
+            transaction_block.then(|| sign(&plot_key, ftb_hash)),
+        );
+
+        FullBlock::new(
+            Vec::new(),
+            reward_chain_block,
+            Some(vdf_proof(1)),
+            vdf_proof(2),
+            Some(vdf_proof(3)),
+            vdf_proof(4),
+            Some(vdf_proof(5)),
+            foliage,
+            transaction_block.then_some(foliage_transaction_block),
+            transaction_block.then_some(transactions_info),
+            transaction_block.then(|| Program::from(vec![0x80])),
+            if transaction_block {
+                vec![1, 2, 3]
+            } else {
+                Vec::new()
+            },
+        )
+    }
+
+    fn split(block: &FullBlock) -> (UnfinishedBlock, InfusionPoint) {
+        let unfinished = UnfinishedBlock::new(
+            block.finished_sub_slots.clone(),
+            block.reward_chain_block.get_unfinished(),
+            block.challenge_chain_sp_proof.clone(),
+            block.reward_chain_sp_proof.clone(),
+            block.foliage.clone(),
+            block.foliage_transaction_block.clone(),
+            block.transactions_info.clone(),
+            block.transactions_generator.clone(),
+            block.transactions_generator_ref_list.clone(),
+        );
+        let rc_block = &block.reward_chain_block;
+        let infusion = InfusionPoint::new(
+            rc_block.challenge_chain_ip_vdf.clone(),
+            block.challenge_chain_ip_proof.clone(),
+            rc_block.reward_chain_ip_vdf.clone(),
+            block.reward_chain_ip_proof.clone(),
+            rc_block.infused_challenge_chain_ip_vdf.clone(),
+            block.infused_challenge_chain_ip_proof.clone(),
+            rc_block.weight,
+            rc_block.height,
+            rc_block.is_transaction_block,
+        );
+        (unfinished, infusion)
+    }
+
+    #[rstest]
+    fn test_complete(#[values(true, false)] transaction_block: bool) {
+        let block = full_block(transaction_block);
+        let (unfinished, infusion) = split(&block);
+        assert_eq!(unfinished.is_transaction_block(), transaction_block);
+
+        let completed = unfinished.complete(infusion).expect("complete");
+        assert_eq!(completed.header_hash(), block.header_hash());
+        assert_eq!(completed, block);
+    }
+
+    #[test]
+    fn test_complete_not_transaction_block() {
+        // a block that was made as a transaction block, but didn't end up
+        // being one
+        let block = full_block(true);
+        let (unfinished, mut infusion) = split(&block);
+        infusion.is_transaction_block = false;
+
+        let completed = unfinished.complete(infusion).expect("complete");
+        assert!(!completed.reward_chain_block.is_transaction_block);
+        assert!(!completed.is_transaction_block());
+        assert_eq!(completed.foliage.foliage_transaction_block_signature, None);
+        assert_eq!(completed.foliage_transaction_block, None);
+        assert_eq!(completed.transactions_info, None);
+        assert_eq!(completed.transactions_generator, None);
+        assert!(completed.transactions_generator_ref_list.is_empty());
+        assert_eq!(
+            completed.foliage.foliage_block_data,
+            block.foliage.foliage_block_data
+        );
+    }
+
+    #[rstest]
+    #[case::total_iters(
+        |ub: &mut UnfinishedBlock, _: &mut InfusionPoint| ub.reward_chain_block.total_iters += 1,
+        "foliage does not commit to the reward chain block"
+    )]
+    #[case::reward_block_hash(
+        |ub: &mut UnfinishedBlock, _: &mut InfusionPoint| {
+            ub.foliage.reward_block_hash = Bytes32::default();
+        },
+        "foliage does not commit to the reward chain block"
+    )]
+    #[case::unfinished_reward_block_hash(
+        |ub: &mut UnfinishedBlock, _: &mut InfusionPoint| {
+            ub.foliage.foliage_block_data.unfinished_reward_block_hash = Bytes32::default();
+        },
+        "foliage does not commit to the reward chain block"
+    )]
+    #[case::cc_sp_proof(
+        |ub: &mut UnfinishedBlock, _: &mut InfusionPoint| ub.challenge_chain_sp_proof = None,
+        "challenge chain signage point VDF and proof mismatch"
+    )]
+    #[case::rc_sp_proof(
+        |ub: &mut UnfinishedBlock, _: &mut InfusionPoint| ub.reward_chain_sp_proof = None,
+        "reward chain signage point VDF and proof mismatch"
+    )]
+    #[case::icc_proof(
+        |_: &mut UnfinishedBlock, ip: &mut InfusionPoint| ip.infused_challenge_chain_ip_proof = None,
+        "infused challenge chain VDF and proof mismatch"
+    )]
+    #[case::icc_vdf(
+        |_: &mut UnfinishedBlock, ip: &mut InfusionPoint| ip.infused_challenge_chain_ip_vdf = None,
+        "infused challenge chain VDF and proof mismatch"
+    )]
+    #[case::block_data_signature(
+        |ub: &mut UnfinishedBlock, _: &mut InfusionPoint| {
+            ub.foliage.foliage_block_data_signature = G2Element::default();
+        },
+        "invalid foliage block data signature"
+    )]
+    #[case::missing_transactions_info(
+        |ub: &mut UnfinishedBlock, _: &mut InfusionPoint| ub.transactions_info = None,
+        "transaction block is missing its foliage transaction block or transactions info"
+    )]
+    #[case::foliage_transaction_block(
+        |ub: &mut UnfinishedBlock, _: &mut InfusionPoint| {
+            ub.foliage_transaction_block.as_mut().unwrap().timestamp += 1;
+        },
+        "foliage does not commit to the foliage transaction block"
+    )]
+    #[case::transactions_info(
+        |ub: &mut UnfinishedBlock, _: &mut InfusionPoint| {
+            ub.transactions_info.as_mut().unwrap().fees += 1;
+        },
+        "foliage transaction block does not commit to the transactions info"
+    )]
+    #[case::transaction_block_signature(
+        |ub: &mut UnfinishedBlock, _: &mut InfusionPoint| {
+            ub.foliage.foliage_transaction_block_signature = Some(G2Element::default());
+        },
+        "invalid foliage transaction block signature"
+    )]
+    #[case::missing_transaction_block_signature(
+        |ub: &mut UnfinishedBlock, _: &mut InfusionPoint| {
+            ub.foliage.foliage_transaction_block_signature = None;
+        },
+        "invalid foliage transaction block signature"
+    )]
+    fn test_complete_invalid(
+        #[case] modify: fn(&mut UnfinishedBlock, &mut InfusionPoint),
+        #[case] expect: &str,
+    ) {
+        let (mut unfinished, mut infusion) = split(&full_block(true));
+        modify(&mut unfinished, &mut infusion);
+        assert_eq!(
+            unfinished.complete(infusion).unwrap_err(),
+            Error::Custom(expect.to_string())
+        );
+    }
+
+    #[test]
+    fn test_complete_missing_transactions() {
+        // a block made without transactions can't become a transaction block
+        let (unfinished, mut infusion) = split(&full_block(false));
+        infusion.is_transaction_block = true;
+        assert_eq!(
+            unfinished.complete(infusion).unwrap_err(),
+            Error::Custom(
+                "transaction block is missing its foliage transaction block or transactions info"
+                    .to_string()
+            )
+        );
+    }
 }
@@ -1,8 +1,13 @@
 use chia_streamable_macro::streamable;
+use chia_traits::chia_error::{Error, Result};
 
 use crate::ClassgroupElement;
 use crate::{Bytes, Bytes32};
 
+#[cfg(feature = "py-bindings")]
+use pyo3::prelude::*;
+use std::fmt;
+
 #[streamable]
 pub struct VDFInfo {
     challenge: Bytes32,
@@ -10,9 +15,109 @@ pub struct VDFInfo {
     output: ClassgroupElement,
 }
 
+impl fmt::Display for VDFInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "VDFInfo(challenge: {:.8}.., iterations: {}, output: {})",
+            self.challenge, self.number_of_iterations, self.output
+        )
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl VDFInfo {
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+}
+
 #[streamable]
 pub struct VDFProof {
     witness_type: u8,
     witness: Bytes,
     normalized_to_identity: bool,
 }
+
+// each segment of an n-wesolowski proof is made up of the number of
+// iterations (8 bytes), B (33 bytes) and an intermediate element
+const SEGMENT_SIZE: usize = 8 + 33 + ClassgroupElement::SIZE;
+
+impl VDFProof {
+    /// Checks that the witness type is within bounds and that the size of the
+    /// witness matches it, before handing the proof to the verifier.
+    /// `max_vdf_witness_size` is the corresponding field of the consensus
+    /// constants.
+    pub fn validate_shape(&self, max_vdf_witness_size: u8) -> Result<()> {
+        if u32::from(self.witness_type) + 1 > u32::from(max_vdf_witness_size) {
+            return Err(Error::Custom(format!(
+                "VDF witness type {} exceeds the max witness size {max_vdf_witness_size}",
+                self.witness_type
+            )));
+        }
+        // the witness is the final proof element, preceded by one segment per
+        // witness type
+        let expected = ClassgroupElement::SIZE + self.witness_type as usize * SEGMENT_SIZE;
+        if self.witness.len() != expected {
+            return Err(Error::Custom(format!(
+                "VDF witness size {} does not match witness type {} (expected {expected})",
+                self.witness.len(),
+                self.witness_type
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pymethods]
+impl VDFProof {
+    #[pyo3(name = "validate_shape")]
+    fn py_validate_shape(&self, constants: &Bound<'_, PyAny>) -> PyResult<()> {
+        Ok(self.validate_shape(constants.getattr("MAX_VDF_WITNESS_SIZE")?.extract::<u8>()?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_vdf_info_display() {
+        let info = VDFInfo::new(
+            Bytes32::new([0x1a; 32]),
+            123_456,
+            ClassgroupElement::default(),
+        );
+        assert_eq!(
+            info.to_string(),
+            "VDFInfo(challenge: 1a1a1a1a.., iterations: 123456, output: generator)"
+        );
+    }
+
+    #[rstest]
+    #[case(0, 100, 64, true)]
+    #[case(1, 241, 64, true)]
+    #[case(63, 100 + 63 * 141, 64, true)]
+    #[case(64, 100 + 64 * 141, 64, false)]
+    #[case(1, 241, 1, false)]
+    #[case(0, 100, 1, true)]
+    #[case(0, 0, 64, false)]
+    #[case(0, 99, 64, false)]
+    #[case(0, 101, 64, false)]
+    #[case(0, 241, 64, false)]
+    #[case(1, 100, 64, false)]
+    #[case(2, 241, 64, false)]
+    #[case(255, 100 + 255 * 141, 255, false)]
+    fn test_validate_shape(
+        #[case] witness_type: u8,
+        #[case] witness_len: usize,
+        #[case] max_witness_size: u8,
+        #[case] expected: bool,
+    ) {
+        let proof = VDFProof::new(witness_type, vec![0x11; witness_len].into(), false);
+        assert_eq!(proof.validate_shape(max_witness_size).is_ok(), expected);
+    }
+}
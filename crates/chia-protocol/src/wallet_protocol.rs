@@ -1,4 +1,5 @@
 use chia_streamable_macro::{streamable, Streamable};
+use chia_traits::Streamable as _;
 
 use crate::Coin;
 use crate::CoinState;
@@ -280,6 +281,124 @@ pub struct RespondCoinState {
     coin_states: Vec<CoinState>,
 }
 
+// The serialized size of a response with the given ids and no coin states.
+fn response_header_size(ids: &[Bytes32]) -> usize {
+    // the ids, min_height and the length prefix of the coin states
+    4 + ids.len() * 32 + 4 + 4
+}
+
+// Splits the coin states into chunks such that a response with each chunk
+// serializes to at most max_bytes. Each state is serialized once, to measure
+// it. A state that doesn't fit even on its own gets a chunk of its own.
+fn split_coin_states(
+    header_size: usize,
+    coin_states: &[CoinState],
+    max_bytes: usize,
+) -> Vec<Vec<CoinState>> {
+    let mut ret = Vec::new();
+    let mut chunk = Vec::new();
+    let mut chunk_size = header_size;
+    let mut buf = Vec::new();
+    for state in coin_states {
+        buf.clear();
+        state
+            .stream(&mut buf)
+            .expect("a CoinState can always be serialized");
+        if !chunk.is_empty() && chunk_size + buf.len() > max_bytes {
+            ret.push(std::mem::take(&mut chunk));
+            chunk_size = header_size;
+        }
+        chunk_size += buf.len();
+        chunk.push(*state);
+    }
+    ret.push(chunk);
+    ret
+}
+
+// The full node only returns coins that were created or spent at, or after,
+// the requested height.
+fn created_or_spent_since(state: &CoinState, min_height: u32) -> bool {
+    state.created_height.is_some_and(|h| h >= min_height)
+        || state.spent_height.is_some_and(|h| h >= min_height)
+}
+
+impl RespondToPhUpdates {
+    /// Splits the response into responses for the same puzzle hashes whose
+    /// serialized size is at most `max_bytes` each. The coin states keep their
+    /// order. If the response has no coin states, it's returned as-is. A single
+    /// coin state that doesn't fit the limit is still returned, on its own.
+    pub fn split_for_max_size(self, max_bytes: usize) -> Vec<Self> {
+        let header_size = response_header_size(&self.puzzle_hashes);
+        split_coin_states(header_size, &self.coin_states, max_bytes)
+            .into_iter()
+            .map(|chunk| Self::new(self.puzzle_hashes.clone(), self.min_height, chunk))
+            .collect()
+    }
+
+    /// Drops the coin states that were neither created nor spent at or after
+    /// `min_height`, the same way the full node filters them, and raises
+    /// `min_height` accordingly.
+    pub fn apply_min_height_filter(&mut self, min_height: u32) {
+        self.coin_states
+            .retain(|state| created_or_spent_since(state, min_height));
+        self.min_height = self.min_height.max(min_height);
+    }
+}
+
+impl RespondToCoinUpdates {
+    /// Splits the response into responses for the same coin IDs whose
+    /// serialized size is at most `max_bytes` each. See
+    /// `RespondToPhUpdates::split_for_max_size()`.
+    pub fn split_for_max_size(self, max_bytes: usize) -> Vec<Self> {
+        let header_size = response_header_size(&self.coin_ids);
+        split_coin_states(header_size, &self.coin_states, max_bytes)
+            .into_iter()
+            .map(|chunk| Self::new(self.coin_ids.clone(), self.min_height, chunk))
+            .collect()
+    }
+
+    /// Drops the coin states that were neither created nor spent at or after
+    /// `min_height`, and raises `min_height` accordingly.
+    pub fn apply_min_height_filter(&mut self, min_height: u32) {
+        self.coin_states
+            .retain(|state| created_or_spent_since(state, min_height));
+        self.min_height = self.min_height.max(min_height);
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pyo3::pymethods]
+impl RespondToPhUpdates {
+    #[pyo3(name = "split_for_max_size")]
+    fn py_split_for_max_size(&self, max_bytes: usize) -> Vec<Self> {
+        self.clone().split_for_max_size(max_bytes)
+    }
+
+    // the python class is immutable, so this returns a filtered copy
+    #[pyo3(name = "apply_min_height_filter")]
+    fn py_apply_min_height_filter(&self, min_height: u32) -> Self {
+        let mut ret = self.clone();
+        ret.apply_min_height_filter(min_height);
+        ret
+    }
+}
+
+#[cfg(feature = "py-bindings")]
+#[pyo3::pymethods]
+impl RespondToCoinUpdates {
+    #[pyo3(name = "split_for_max_size")]
+    fn py_split_for_max_size(&self, max_bytes: usize) -> Vec<Self> {
+        self.clone().split_for_max_size(max_bytes)
+    }
+
+    #[pyo3(name = "apply_min_height_filter")]
+    fn py_apply_min_height_filter(&self, min_height: u32) -> Self {
+        let mut ret = self.clone();
+        ret.apply_min_height_filter(min_height);
+        ret
+    }
+}
+
 #[streamable(message)]
 pub struct RejectCoinState {
     reason: RejectStateReason,
@@ -304,6 +423,10 @@ impl chia_traits::ChiaToPython for RejectStateReason {
             .clone()
             .into_any())
     }
+
+    fn py_type_name() -> String {
+        "int".to_string()
+    }
 }
 
 #[repr(u8)]
@@ -324,6 +447,10 @@ impl chia_traits::ChiaToPython for MempoolRemoveReason {
             .clone()
             .into_any())
     }
+
+    fn py_type_name() -> String {
+        "int".to_string()
+    }
 }
 
 #[streamable]
@@ -354,3 +481,123 @@ pub struct RespondCostInfo {
     mempool_fee: u64,
     bump_fee_per_cost: u8,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chia_traits::Streamable;
+    use rstest::rstest;
+
+    fn coin_state(i: u8, created: Option<u32>, spent: Option<u32>) -> CoinState {
+        CoinState::new(
+            Coin::new(
+                Bytes32::new([i; 32]),
+                Bytes32::new([!i; 32]),
+                u64::from(i).into(),
+            ),
+            spent,
+            created,
+        )
+    }
+
+    fn coin_states(count: u8) -> Vec<CoinState> {
+        (0..count)
+            .map(|i| {
+                // mix in states of different serialized sizes
+                let created = (i % 3 != 0).then_some(u32::from(i));
+                let spent = (i % 2 == 0).then_some(u32::from(i) + 10);
+                coin_state(i, created, spent)
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case(0, 1000)]
+    #[case(1, 1000)]
+    #[case(100, 1000)]
+    #[case(100, 200)]
+    #[case(100, 10000)]
+    #[case(100, 10)]
+    fn test_split_ph_updates(#[case] count: u8, #[case] max_bytes: usize) {
+        let ids = vec![Bytes32::new([0xaa; 32]), Bytes32::new([0xbb; 32])];
+        let msg = RespondToPhUpdates::new(ids.clone(), 5, coin_states(count));
+        let parts = msg.clone().split_for_max_size(max_bytes);
+        assert!(!parts.is_empty());
+
+        let mut joined = Vec::new();
+        for part in &parts {
+            assert_eq!(part.puzzle_hashes, ids);
+            assert_eq!(part.min_height, 5);
+            // only a part with a single state may exceed the limit
+            let size = part.to_bytes().unwrap().len();
+            assert!(size <= max_bytes || part.coin_states.len() == 1);
+            joined.extend_from_slice(&part.coin_states);
+        }
+        assert_eq!(joined, msg.coin_states);
+
+        // the header fields on their own are 76 bytes
+        if max_bytes >= 76 + 82 {
+            let total = msg.to_bytes().unwrap().len();
+            if total <= max_bytes {
+                assert_eq!(parts, vec![msg]);
+            }
+            // a state never moves on to the next part if it would have fit
+            for pair in parts.windows(2) {
+                let size = pair[0].to_bytes().unwrap().len();
+                let mut next = Vec::new();
+                pair[1].coin_states[0].stream(&mut next).unwrap();
+                assert!(size + next.len() > max_bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_coin_updates() {
+        let ids = vec![Bytes32::new([0xcc; 32])];
+        let msg = RespondToCoinUpdates::new(ids, 0, coin_states(50));
+        let parts = msg.clone().split_for_max_size(500);
+        assert!(parts.len() > 1);
+        for part in &parts {
+            assert!(part.to_bytes().unwrap().len() <= 500);
+            assert_eq!(part.coin_ids, msg.coin_ids);
+        }
+        let joined: Vec<CoinState> = parts.into_iter().flat_map(|p| p.coin_states).collect();
+        assert_eq!(joined, msg.coin_states);
+    }
+
+    #[rstest]
+    #[case(None, None, 10, false)]
+    #[case(Some(9), None, 10, false)]
+    #[case(Some(10), None, 10, true)]
+    #[case(Some(5), Some(9), 10, false)]
+    #[case(Some(5), Some(10), 10, true)]
+    #[case(None, Some(11), 10, true)]
+    #[case(Some(0), None, 0, true)]
+    fn test_min_height_filter(
+        #[case] created: Option<u32>,
+        #[case] spent: Option<u32>,
+        #[case] min_height: u32,
+        #[case] keep: bool,
+    ) {
+        let state = coin_state(1, created, spent);
+        let mut msg = RespondToPhUpdates::new(vec![], 0, vec![state]);
+        msg.apply_min_height_filter(min_height);
+        assert_eq!(msg.coin_states.len(), usize::from(keep));
+        assert_eq!(msg.min_height, min_height);
+
+        let mut msg = RespondToCoinUpdates::new(vec![], 0, vec![state]);
+        msg.apply_min_height_filter(min_height);
+        assert_eq!(msg.coin_states.len(), usize::from(keep));
+    }
+
+    #[test]
+    fn test_min_height_filter_never_lowers() {
+        let mut msg = RespondToPhUpdates::new(vec![], 20, coin_states(30));
+        msg.apply_min_height_filter(10);
+        assert_eq!(msg.min_height, 20);
+        assert!(msg
+            .coin_states
+            .iter()
+            .all(|s| created_or_spent_since(s, 10)));
+    }
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "serde")]
+
+use std::fmt::Debug;
+
+use chia_bls::G1Element;
+use chia_protocol::{Bytes, Bytes32, Coin, CoinSpend, Program, ProofOfSpace};
+use chia_traits::{Streamable, WireFormat};
+
+fn coin() -> Coin {
+    Coin::new(
+        Bytes32::new([1; 32]),
+        Bytes32::new([2; 32]),
+        1_000_000.into(),
+    )
+}
+
+fn coin_spend() -> CoinSpend {
+    CoinSpend::new(
+        coin(),
+        Program::from(vec![0xff, 0x01, 0x80]),
+        Program::from(vec![0x80]),
+    )
+}
+
+fn proof_of_space() -> ProofOfSpace {
+    ProofOfSpace::new(
+        Bytes32::new([3; 32]),
+        None,
+        Some(Bytes32::new([4; 32])),
+        G1Element::default(),
+        32,
+        Bytes::new(vec![5; 64]),
+    )
+}
+
+fn check<T>(value: T)
+where
+    T: Streamable + Debug + PartialEq,
+{
+    let wire_bytes = value.to_bytes().unwrap();
+    let value = WireFormat(value);
+
+    // bincode stores the Streamable bytes, after a u64 length prefix.
+    let bytes = bincode::serialize(&value).unwrap();
+    assert_eq!(bytes[8..], wire_bytes);
+    assert_eq!(
+        bincode::deserialize::<WireFormat<T>>(&bytes).unwrap(),
+        value
+    );
+
+    // JSON stores the same bytes as a hex string.
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, format!("\"0x{}\"", hex::encode(wire_bytes)));
+    assert_eq!(serde_json::from_str::<WireFormat<T>>(&json).unwrap(), value);
+}
+
+#[test]
+fn test_coin() {
+    check(coin());
+}
+
+#[test]
+fn test_coin_spend() {
+    check(coin_spend());
+}
+
+#[test]
+fn test_proof_of_space() {
+    check(proof_of_space());
+}
+
+#[test]
+fn test_nested() {
+    // The wrapper works inside other serde types, such as a list of stored records.
+    let records = vec![(1_u32, WireFormat(coin())), (2, WireFormat(coin()))];
+    let bytes = bincode::serialize(&records).unwrap();
+    let decoded: Vec<(u32, WireFormat<Coin>)> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(decoded, records);
+}
@@ -0,0 +1,185 @@
+// This is the bech32m encoding, as used by chia addresses and offer files.
+// Unlike BIP-173, there's no limit on the length of the encoded string, since
+// offers routinely exceed the 90 character limit.
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [
+    0x3b6a_57b2,
+    0x2650_8e6d,
+    0x1ea1_19fa,
+    0x3d42_33dd,
+    0x2a14_62b3,
+];
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    let mut chk: u32 = 1;
+    for value in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x01ff_ffff) << 5) ^ u32::from(value);
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    hrp.iter()
+        .map(|b| b >> 5)
+        .chain(std::iter::once(0))
+        .chain(hrp.iter().map(|b| b & 31))
+}
+
+// regroups the bits in `data` from groups of `from` bits to groups of `to`
+// bits. When decoding (i.e. not padding), any left-over bits must be zero
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max_value = (1 << to) - 1;
+    let max_acc = (1 << (from + to - 1)) - 1;
+    let mut ret = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for value in data {
+        let value = u32::from(*value);
+        if (value >> from) != 0 {
+            return None;
+        }
+        acc = ((acc << from) | value) & max_acc;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            ret.push(((acc >> bits) & max_value) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            ret.push(((acc << (to - bits)) & max_value) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max_value) != 0 {
+        return None;
+    }
+    Some(ret)
+}
+
+pub(crate) fn encode(hrp: &str, data: &[u8]) -> String {
+    let data = convert_bits(data, 8, 5, true).expect("8-bit values always fit");
+    let checksum = polymod(
+        hrp_expand(hrp.as_bytes())
+            .chain(data.iter().copied())
+            .chain([0; 6]),
+    ) ^ BECH32M_CONST;
+
+    let mut ret = String::with_capacity(hrp.len() + 1 + data.len() + 6);
+    ret.push_str(hrp);
+    ret.push('1');
+    for d in data {
+        ret.push(CHARSET[d as usize] as char);
+    }
+    for i in 0..6 {
+        ret.push(CHARSET[((checksum >> (5 * (5 - i))) & 31) as usize] as char);
+    }
+    ret
+}
+
+/// Returns the human readable part and the decoded bytes, or None if the
+/// string is not valid bech32m.
+pub(crate) fn decode(input: &str) -> Option<(String, Vec<u8>)> {
+    let input = input.trim();
+    if input.bytes().any(|b| !(33..=126).contains(&b)) {
+        return None;
+    }
+    // mixed case is not allowed
+    if input.bytes().any(|b| b.is_ascii_lowercase())
+        && input.bytes().any(|b| b.is_ascii_uppercase())
+    {
+        return None;
+    }
+    let input = input.to_ascii_lowercase();
+    let pos = input.rfind('1')?;
+    if pos < 1 || pos + 7 > input.len() {
+        return None;
+    }
+    let (hrp, data) = (&input[..pos], &input[pos + 1..]);
+    let data = data
+        .bytes()
+        .map(|c| CHARSET.iter().position(|x| *x == c).map(|v| v as u8))
+        .collect::<Option<Vec<u8>>>()?;
+
+    if polymod(hrp_expand(hrp.as_bytes()).chain(data.iter().copied())) != BECH32M_CONST {
+        return None;
+    }
+    let bytes = convert_bits(&data[..data.len() - 6], 5, 8, false)?;
+    Some((hrp.to_string(), bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    // test vectors from BIP-350
+    #[rstest]
+    #[case("A1LQFN3A")]
+    #[case("a1lqfn3a")]
+    #[case("an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11sg7hg6")]
+    #[case("abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx")]
+    #[case("split1checkupstagehandshakeupstreamerranterredcaperredlc445v")]
+    #[case("?1v759aa")]
+    fn test_valid_checksum(#[case] input: &str) {
+        let data = input.to_ascii_lowercase();
+        let pos = data.rfind('1').unwrap();
+        let values: Vec<u8> = data[pos + 1..]
+            .bytes()
+            .map(|c| CHARSET.iter().position(|x| *x == c).unwrap() as u8)
+            .collect();
+        assert_eq!(
+            polymod(hrp_expand(data[..pos].as_bytes()).chain(values)),
+            BECH32M_CONST
+        );
+    }
+
+    #[rstest]
+    #[case("\u{20}1xj0phk")]
+    #[case("\u{7f}1g6xzxy")]
+    #[case("qyrz8wqd2c9m")]
+    #[case("1qyrz8wqd2c9m")]
+    #[case("y1b0jsk6g")]
+    #[case("lt1igcx5c0")]
+    #[case("in1muywd")]
+    #[case("mm1crxm3i")]
+    #[case("au1s5cgom")]
+    #[case("M1VUXWEZ")]
+    #[case("16plkw9")]
+    #[case("1p2gdwpf")]
+    #[case("aB1qqqqqqqqqqqqqqqq")]
+    fn test_invalid(#[case] input: &str) {
+        assert!(decode(input).is_none());
+    }
+
+    #[rstest]
+    #[case("xch", &[])]
+    #[case("offer", &[0])]
+    #[case("txch", &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10])]
+    #[case("offer", &[0xff; 1000])]
+    fn test_round_trip(#[case] hrp: &str, #[case] data: &[u8]) {
+        let encoded = encode(hrp, data);
+        assert_eq!(decode(&encoded), Some((hrp.to_string(), data.to_vec())));
+        assert_eq!(
+            decode(&encoded.to_ascii_uppercase()),
+            Some((hrp.to_string(), data.to_vec()))
+        );
+    }
+
+    #[test]
+    fn test_address() {
+        // the puzzle hash of the burn address
+        let puzzle_hash =
+            hex::decode("000000000000000000000000000000000000000000000000000000000000dead")
+                .unwrap();
+        let address = "xch1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqm6ks6e8mvy";
+        assert_eq!(encode("xch", &puzzle_hash), address);
+        assert_eq!(decode(address), Some(("xch".to_string(), puzzle_hash)));
+    }
+}
@@ -0,0 +1,409 @@
+use chia_protocol::{Bytes32, Coin, CoinSpend, Program};
+use clvm_traits::{ToClvm, ToClvmError};
+use clvm_utils::{tree_hash, CurriedProgram};
+use clvmr::allocator::SExp;
+use clvmr::chia_dialect::ChiaDialect;
+use clvmr::reduction::EvalErr;
+use clvmr::run_program::run_program;
+use clvmr::serde::{node_from_bytes, node_to_bytes};
+use clvmr::{Allocator, NodePtr};
+use thiserror::Error;
+
+use crate::cat::{CatArgs, CatSolution, CAT_PUZZLE};
+use crate::{CoinProof, LineageProof};
+
+const CREATE_COIN: u32 = 51;
+
+// a CREATE_COIN with this amount reveals the TAIL, it doesn't create a coin
+const TAIL_REVEAL_AMOUNT: i128 = -113;
+
+// the inner puzzles are only run to find the coins they create. Anything
+// more expensive than this can't be included in a block anyway
+const MAX_COST: u64 = 11_000_000_000;
+
+#[derive(Debug, Error)]
+pub enum CatSpendError {
+    #[error("no coins to spend")]
+    NoSpends,
+
+    #[error("the amounts spent and created don't match, the total delta is {0}")]
+    DeltaMismatch(i128),
+
+    #[error("the subtotal of a spend doesn't fit in 64 bits")]
+    SubtotalOverflow,
+
+    #[error("invalid CREATE_COIN condition")]
+    InvalidCondition,
+
+    #[error("failed to run the inner puzzle: {0}")]
+    Eval(String),
+
+    #[error("{0}")]
+    ToClvm(#[from] ToClvmError),
+
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<EvalErr> for CatSpendError {
+    fn from(err: EvalErr) -> Self {
+        Self::Eval(err.1)
+    }
+}
+
+/// The spend of one CAT coin. The inner puzzle and solution are nodes in the
+/// allocator passed to [`CatSpendBuilder::build()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatSpend {
+    pub coin: Coin,
+    pub inner_puzzle: NodePtr,
+    pub inner_solution: NodePtr,
+    /// This is `None` for the eve spend of a CAT, which has to reveal the
+    /// TAIL instead.
+    pub lineage_proof: Option<LineageProof>,
+    /// The amount minted (negative) or melted (positive) by this spend. This
+    /// has to be approved by the TAIL.
+    pub extra_delta: i64,
+}
+
+impl CatSpend {
+    pub fn new(
+        coin: Coin,
+        inner_puzzle: NodePtr,
+        inner_solution: NodePtr,
+        lineage_proof: Option<LineageProof>,
+    ) -> Self {
+        Self {
+            coin,
+            inner_puzzle,
+            inner_solution,
+            lineage_proof,
+            extra_delta: 0,
+        }
+    }
+}
+
+/// Assembles the coin spends of CAT coins that are spent together. Every CAT
+/// spend announces its subtotal to the next one, in a ring, which is how the
+/// CAT puzzle makes sure the amounts add up. This computes the ring, like
+/// `unsigned_spend_bundle_for_spendable_cats()` in the reference wallet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatSpendBuilder {
+    asset_id: Bytes32,
+    spends: Vec<CatSpend>,
+}
+
+impl CatSpendBuilder {
+    pub fn new(asset_id: Bytes32) -> Self {
+        Self {
+            asset_id,
+            spends: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn spend(mut self, spend: CatSpend) -> Self {
+        self.spends.push(spend);
+        self
+    }
+
+    /// Runs the inner puzzles to find the amount of every CAT coin they
+    /// create, and returns the coin spends of the CAT coins, in the order
+    /// they were added. The amounts spent and created (including the extra
+    /// deltas) must add up to zero.
+    pub fn build(&self, a: &mut Allocator) -> Result<Vec<CoinSpend>, CatSpendError> {
+        if self.spends.is_empty() {
+            return Err(CatSpendError::NoSpends);
+        }
+
+        let mut deltas = Vec::with_capacity(self.spends.len());
+        for spend in &self.spends {
+            let created = created_amount(a, spend.inner_puzzle, spend.inner_solution)?;
+            deltas.push(
+                i128::from(spend.coin.amount.get()) - created + i128::from(spend.extra_delta),
+            );
+        }
+        let total: i128 = deltas.iter().sum();
+        if total != 0 {
+            return Err(CatSpendError::DeltaMismatch(total));
+        }
+
+        // the subtotals are shifted so the smallest one is 0
+        let mut subtotals = Vec::with_capacity(deltas.len());
+        let mut subtotal = 0_i128;
+        for delta in &deltas {
+            subtotals.push(subtotal);
+            subtotal += delta;
+        }
+        let offset = *subtotals.iter().min().expect("at least one spend");
+
+        let cat_mod = node_from_bytes(a, &CAT_PUZZLE)?;
+        let inner_puzzle_hashes: Vec<Bytes32> = self
+            .spends
+            .iter()
+            .map(|spend| tree_hash(a, spend.inner_puzzle).into())
+            .collect();
+
+        let len = self.spends.len();
+        let mut coin_spends = Vec::with_capacity(len);
+        for (index, spend) in self.spends.iter().enumerate() {
+            let prev = &self.spends[(index + len - 1) % len];
+            let next_index = (index + 1) % len;
+            let next = &self.spends[next_index];
+
+            let puzzle = CurriedProgram {
+                program: cat_mod,
+                args: CatArgs::new(self.asset_id, spend.inner_puzzle),
+            }
+            .to_clvm(a)?;
+            let solution = CatSolution {
+                inner_puzzle_solution: spend.inner_solution,
+                lineage_proof: spend.lineage_proof,
+                prev_coin_id: prev.coin.coin_id(),
+                this_coin_info: spend.coin,
+                next_coin_proof: CoinProof {
+                    parent_coin_info: next.coin.parent_coin_info,
+                    inner_puzzle_hash: inner_puzzle_hashes[next_index],
+                    amount: next.coin.amount.get(),
+                },
+                prev_subtotal: i64::try_from(subtotals[index] - offset)
+                    .map_err(|_| CatSpendError::SubtotalOverflow)?,
+                extra_delta: spend.extra_delta,
+            }
+            .to_clvm(a)?;
+
+            coin_spends.push(CoinSpend::new(
+                spend.coin,
+                Program::new(node_to_bytes(a, puzzle)?.into()),
+                Program::new(node_to_bytes(a, solution)?.into()),
+            ));
+        }
+        Ok(coin_spends)
+    }
+}
+
+// the sum of the amounts of the coins created by an inner puzzle
+fn created_amount(
+    a: &mut Allocator,
+    inner_puzzle: NodePtr,
+    inner_solution: NodePtr,
+) -> Result<i128, CatSpendError> {
+    let dialect = ChiaDialect::new(0);
+    let conditions = run_program(a, &dialect, inner_puzzle, inner_solution, MAX_COST)?.1;
+
+    let mut total = 0;
+    let mut iter = conditions;
+    while let Some((condition, rest)) = a.next(iter) {
+        iter = rest;
+        let Some((opcode, args)) = a.next(condition) else {
+            return Err(CatSpendError::InvalidCondition);
+        };
+        if a.small_number(opcode) != Some(CREATE_COIN) {
+            continue;
+        }
+        let amount = a
+            .next(args)
+            .and_then(|(_, args)| a.next(args))
+            .ok_or(CatSpendError::InvalidCondition)?
+            .0;
+        if !matches!(a.sexp(amount), SExp::Atom) {
+            return Err(CatSpendError::InvalidCondition);
+        }
+        let amount =
+            i128::try_from(a.number(amount)).map_err(|_| CatSpendError::InvalidCondition)?;
+        if amount != TAIL_REVEAL_AMOUNT {
+            total += amount;
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chia_sha2::Sha256;
+    use clvm_traits::FromClvm;
+    use rstest::rstest;
+    use std::collections::HashSet;
+
+    const CREATE_COIN_ANNOUNCEMENT: u32 = 60;
+    const ASSERT_COIN_ANNOUNCEMENT: u32 = 61;
+    const ASSERT_MY_COIN_ID: u32 = 70;
+
+    const ASSET_ID: Bytes32 = Bytes32::new([0xaa; 32]);
+
+    // (q . ((51 puzzle_hash amount) ...))
+    fn inner_puzzle(a: &mut Allocator, outputs: &[(Bytes32, u64)]) -> NodePtr {
+        let conditions: Vec<_> = outputs
+            .iter()
+            .map(|(ph, amount)| (CREATE_COIN, (*ph, (*amount, ()))))
+            .collect();
+        (1, conditions).to_clvm(a).unwrap()
+    }
+
+    // a CAT coin with the specified inner puzzle, whose parent is a CAT coin
+    // too, as described by the returned lineage proof
+    fn cat_coin(a: &Allocator, inner_puzzle: NodePtr, amount: u64) -> (Coin, LineageProof) {
+        let lineage_proof = LineageProof {
+            parent_parent_coin_info: Bytes32::new([amount as u8; 32]),
+            parent_inner_puzzle_hash: Bytes32::new([0x11; 32]),
+            parent_amount: amount,
+        };
+        let parent = Coin::new(
+            lineage_proof.parent_parent_coin_info,
+            CatArgs::curry_tree_hash(ASSET_ID, lineage_proof.parent_inner_puzzle_hash.into())
+                .into(),
+            lineage_proof.parent_amount.into(),
+        );
+        let puzzle_hash = CatArgs::curry_tree_hash(ASSET_ID, tree_hash(a, inner_puzzle)).into();
+        (
+            Coin::new(parent.coin_id(), puzzle_hash, amount.into()),
+            lineage_proof,
+        )
+    }
+
+    // the (opcode, args) of every condition created by the spend
+    fn run_spend(coin_spend: &CoinSpend) -> (Allocator, Vec<(u32, Vec<NodePtr>)>) {
+        let mut a = Allocator::new();
+        let puzzle = node_from_bytes(&mut a, coin_spend.puzzle_reveal.as_ref()).unwrap();
+        let solution = node_from_bytes(&mut a, coin_spend.solution.as_ref()).unwrap();
+        let conditions = run_program(&mut a, &ChiaDialect::new(0), puzzle, solution, MAX_COST)
+            .expect("CAT spend")
+            .1;
+        let conditions = Vec::<(u32, Vec<NodePtr>)>::from_clvm(&a, conditions).unwrap();
+        (a, conditions)
+    }
+
+    #[rstest]
+    #[case(&[100], &[100])]
+    #[case(&[100], &[40, 60])]
+    #[case(&[100, 200, 300], &[600])]
+    #[case(&[100, 200, 300], &[50, 50, 0, 500])]
+    fn test_ring(#[case] inputs: &[u64], #[case] outputs: &[u64]) {
+        let mut a = Allocator::new();
+        let mut builder = CatSpendBuilder::new(ASSET_ID);
+        let nil = a.nil();
+
+        // the first coin creates all the outputs
+        let outputs: Vec<_> = outputs
+            .iter()
+            .enumerate()
+            .map(|(i, amount)| (Bytes32::new([i as u8; 32]), *amount))
+            .collect();
+        for (i, amount) in inputs.iter().enumerate() {
+            let inner = inner_puzzle(&mut a, if i == 0 { &outputs[..] } else { &[] });
+            let (coin, lineage_proof) = cat_coin(&a, inner, *amount);
+            builder = builder.spend(CatSpend::new(coin, inner, nil, Some(lineage_proof)));
+        }
+        let coin_spends = builder.build(&mut a).expect("build");
+        assert_eq!(coin_spends.len(), inputs.len());
+
+        let mut announcements = HashSet::new();
+        let mut asserted = Vec::new();
+        let mut created = Vec::new();
+        for coin_spend in &coin_spends {
+            let coin_id = coin_spend.coin.coin_id();
+            let (a, conditions) = run_spend(coin_spend);
+            for (opcode, args) in conditions {
+                match opcode {
+                    CREATE_COIN => {
+                        let ph = Bytes32::from_clvm(&a, args[0]).unwrap();
+                        let amount = u64::from_clvm(&a, args[1]).unwrap();
+                        created.push((ph, amount));
+                    }
+                    CREATE_COIN_ANNOUNCEMENT => {
+                        let mut ctx = Sha256::new();
+                        ctx.update(coin_id);
+                        ctx.update(a.atom(args[0]).as_ref());
+                        announcements.insert(Bytes32::new(ctx.finalize()));
+                    }
+                    ASSERT_COIN_ANNOUNCEMENT => {
+                        asserted.push(Bytes32::from_clvm(&a, args[0]).unwrap());
+                    }
+                    ASSERT_MY_COIN_ID => {
+                        assert_eq!(Bytes32::from_clvm(&a, args[0]).unwrap(), coin_id);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // every coin asserts the announcement of the next one in the ring
+        assert_eq!(announcements.len(), inputs.len());
+        assert_eq!(asserted.len(), inputs.len());
+        for announcement in &asserted {
+            assert!(announcements.contains(announcement));
+        }
+
+        // and the coins created by the inner puzzles are wrapped in the CAT
+        let expected: Vec<_> = outputs
+            .iter()
+            .map(|(ph, amount)| {
+                let ph = CatArgs::curry_tree_hash(ASSET_ID, (*ph).into()).into();
+                (ph, *amount)
+            })
+            .collect();
+        assert_eq!(created, expected);
+    }
+
+    #[rstest]
+    #[case(&[100], &[101], 0, Some(-1))]
+    #[case(&[100, 200], &[299], 0, Some(1))]
+    #[case(&[100, 200], &[290], -10, None)]
+    #[case(&[100], &[110], 10, None)]
+    fn test_deltas(
+        #[case] inputs: &[u64],
+        #[case] outputs: &[u64],
+        #[case] extra_delta: i64,
+        #[case] mismatch: Option<i128>,
+    ) {
+        let mut a = Allocator::new();
+        let mut builder = CatSpendBuilder::new(ASSET_ID);
+        let nil = a.nil();
+
+        let outputs: Vec<_> = outputs
+            .iter()
+            .map(|amount| (Bytes32::new([1; 32]), *amount))
+            .collect();
+        for (i, amount) in inputs.iter().enumerate() {
+            let inner = inner_puzzle(&mut a, if i == 0 { &outputs[..] } else { &[] });
+            let (coin, lineage_proof) = cat_coin(&a, inner, *amount);
+            let mut spend = CatSpend::new(coin, inner, nil, Some(lineage_proof));
+            if i == 0 {
+                spend.extra_delta = extra_delta;
+            }
+            builder = builder.spend(spend);
+        }
+
+        match (builder.build(&mut a), mismatch) {
+            (Ok(coin_spends), None) => assert_eq!(coin_spends.len(), inputs.len()),
+            (Err(CatSpendError::DeltaMismatch(delta)), Some(expected)) => {
+                assert_eq!(delta, expected);
+            }
+            (result, _) => panic!("unexpected result {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_no_spends() {
+        let mut a = Allocator::new();
+        assert!(matches!(
+            CatSpendBuilder::new(ASSET_ID).build(&mut a),
+            Err(CatSpendError::NoSpends)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_inner_puzzle() {
+        let mut a = Allocator::new();
+        // (x)
+        let inner = (8, ()).to_clvm(&mut a).unwrap();
+        let nil = a.nil();
+        let (coin, _) = cat_coin(&a, inner, 1);
+        let builder = CatSpendBuilder::new(ASSET_ID).spend(CatSpend::new(coin, inner, nil, None));
+        assert!(matches!(
+            builder.build(&mut a),
+            Err(CatSpendError::Eval(msg)) if msg == "clvm raise"
+        ));
+    }
+}
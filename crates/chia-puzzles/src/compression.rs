@@ -0,0 +1,318 @@
+use std::io::{self, Read};
+
+use thiserror::Error;
+use zstd::bulk::Compressor;
+use zstd::stream::read::Decoder;
+use zstd::zstd_safe;
+
+use crate::cat::{CAT_PUZZLE, CAT_PUZZLE_V1};
+use crate::nft::{
+    NFT_METADATA_UPDATER_PUZZLE, NFT_OWNERSHIP_LAYER_PUZZLE, NFT_ROYALTY_TRANSFER_PUZZLE,
+    NFT_STATE_LAYER_PUZZLE,
+};
+use crate::offer::{SETTLEMENT_PAYMENTS_PUZZLE, SETTLEMENT_PAYMENTS_PUZZLE_V1};
+use crate::singleton::SINGLETON_TOP_LAYER_PUZZLE;
+use crate::standard::STANDARD_PUZZLE;
+
+// The compression level is fixed, so that the same input always compresses to
+// the same bytes (for a given version of zstd).
+const COMPRESSION_LEVEL: i32 = 9;
+
+// The largest window (as a power of two) the decompressor accepts. Compression
+// level 9 never uses a window larger than 4 MiB (2^22), so this covers
+// anything compress_with_dict() produces, while keeping a malicious frame from
+// making the decompressor allocate a window of up to 128 MiB (zstd's default
+// limit).
+const WINDOW_LOG_MAX: u32 = 22;
+
+// The dictionary ID stored in the first byte of the compressed data when no
+// dictionary is used.
+const NO_DICT: u8 = 0;
+
+#[derive(Debug, Error)]
+pub enum CompressionError {
+    #[error("unknown compression dictionary {0}")]
+    UnknownDict(u8),
+
+    #[error("truncated or invalid zstd frame")]
+    InvalidFrame,
+
+    #[error("trailing data after zstd frame")]
+    TrailingData,
+
+    #[error("decompressed data exceeds {0} bytes")]
+    TooLarge(usize),
+
+    #[error("{0}")]
+    Io(#[from] io::Error),
+}
+
+/// The dictionaries that can be used to compress data sent over the wire.
+/// The ID of the dictionary is stored in the compressed data, so the content
+/// of a dictionary must never change. New dictionaries get new IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum KnownDict {
+    /// The standard transaction, CAT (v1 and v2), singleton, NFT and
+    /// settlement payments puzzles.
+    StandardPuzzles = 1,
+}
+
+const STANDARD_PUZZLES_DICT: [&[u8]; 10] = [
+    &STANDARD_PUZZLE,
+    &CAT_PUZZLE_V1,
+    &CAT_PUZZLE,
+    &SETTLEMENT_PAYMENTS_PUZZLE_V1,
+    &SETTLEMENT_PAYMENTS_PUZZLE,
+    &SINGLETON_TOP_LAYER_PUZZLE,
+    &NFT_STATE_LAYER_PUZZLE,
+    &NFT_OWNERSHIP_LAYER_PUZZLE,
+    &NFT_METADATA_UPDATER_PUZZLE,
+    &NFT_ROYALTY_TRANSFER_PUZZLE,
+];
+
+impl KnownDict {
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Self::StandardPuzzles),
+            _ => None,
+        }
+    }
+
+    /// The content of the dictionary. It's used as a raw content dictionary,
+    /// not a trained one.
+    pub fn bytes(self) -> Vec<u8> {
+        match self {
+            Self::StandardPuzzles => STANDARD_PUZZLES_DICT.concat(),
+        }
+    }
+}
+
+/// Compresses `data` into a single zstd frame, optionally using one of the
+/// known dictionaries. The result is prefixed by one byte, the ID of the
+/// dictionary (or 0 if none was used), which is all `decompress_with_dict()`
+/// needs to decompress it.
+pub fn compress_with_dict(data: &[u8], dict: Option<KnownDict>) -> Vec<u8> {
+    let dict_bytes = dict.map(KnownDict::bytes).unwrap_or_default();
+    // none of these can fail, since the parameters are fixed
+    let mut compressor = Compressor::with_dictionary(COMPRESSION_LEVEL, &dict_bytes)
+        .expect("failed to create zstd compressor");
+    compressor
+        .include_checksum(true)
+        .expect("failed to enable zstd checksum");
+    let frame = compressor
+        .compress(data)
+        .expect("failed to compress with zstd");
+
+    let mut ret = Vec::with_capacity(frame.len() + 1);
+    ret.push(dict.map_or(NO_DICT, KnownDict::id));
+    ret.extend(frame);
+    ret
+}
+
+/// The inverse of `compress_with_dict()`. The input must be exactly one
+/// complete zstd frame (after the dictionary ID). Decompression fails if the
+/// output would exceed `max_decompressed_size` bytes, without ever buffering
+/// more than that.
+pub fn decompress_with_dict(
+    data: &[u8],
+    max_decompressed_size: usize,
+) -> Result<Vec<u8>, CompressionError> {
+    let (&id, frame) = data.split_first().ok_or(CompressionError::InvalidFrame)?;
+    let dict_bytes = if id == NO_DICT {
+        Vec::new()
+    } else {
+        KnownDict::from_id(id)
+            .ok_or(CompressionError::UnknownDict(id))?
+            .bytes()
+    };
+
+    let frame_size =
+        zstd_safe::find_frame_compressed_size(frame).map_err(|_| CompressionError::InvalidFrame)?;
+    if frame_size != frame.len() {
+        return Err(CompressionError::TrailingData);
+    }
+
+    // the content size in the frame header is optional, and can't be trusted
+    // anyway, so the output is also bounded while decompressing
+    let max_size = max_decompressed_size as u64;
+    if let Ok(Some(size)) = zstd_safe::get_frame_content_size(frame) {
+        if size > max_size {
+            return Err(CompressionError::TooLarge(max_decompressed_size));
+        }
+    }
+
+    let mut decoder = Decoder::with_dictionary(frame, &dict_bytes)?.single_frame();
+    decoder.window_log_max(WINDOW_LOG_MAX)?;
+    let mut ret = Vec::new();
+    decoder
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut ret)?;
+    if ret.len() > max_decompressed_size {
+        return Err(CompressionError::TooLarge(max_decompressed_size));
+    }
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::io::Write;
+
+    // something that looks like a spend of a standard CAT
+    fn cat_spend() -> Vec<u8> {
+        let mut ret = Vec::new();
+        ret.extend_from_slice(&CAT_PUZZLE);
+        ret.extend_from_slice(&[0x42; 96]);
+        ret.extend_from_slice(&STANDARD_PUZZLE);
+        ret.extend_from_slice(&[0x17; 48]);
+        ret
+    }
+
+    #[rstest]
+    fn test_round_trip(
+        #[values(None, Some(KnownDict::StandardPuzzles))] dict: Option<KnownDict>,
+        #[values(0, 1, 1000)] size: usize,
+    ) {
+        let mut data = cat_spend();
+        data.extend((0..size).map(|i| (i % 251) as u8));
+
+        let compressed = compress_with_dict(&data, dict);
+        assert_eq!(compressed[0], dict.map_or(0, KnownDict::id));
+        assert_eq!(compressed, compress_with_dict(&data, dict));
+        assert_eq!(decompress_with_dict(&compressed, data.len()).unwrap(), data);
+    }
+
+    #[test]
+    fn test_dict_compresses_better() {
+        let data = cat_spend();
+        let plain = compress_with_dict(&data, None);
+        let with_dict = compress_with_dict(&data, Some(KnownDict::StandardPuzzles));
+        assert!(with_dict.len() * 4 < plain.len());
+    }
+
+    #[test]
+    fn test_known_dict_ids() {
+        assert_eq!(
+            KnownDict::from_id(KnownDict::StandardPuzzles.id()),
+            Some(KnownDict::StandardPuzzles)
+        );
+        assert_eq!(KnownDict::from_id(NO_DICT), None);
+        assert_eq!(KnownDict::from_id(2), None);
+    }
+
+    #[test]
+    fn test_empty() {
+        let compressed = compress_with_dict(&[], None);
+        assert_eq!(
+            decompress_with_dict(&compressed, 0).unwrap(),
+            Vec::<u8>::new()
+        );
+        assert!(matches!(
+            decompress_with_dict(&[], 100),
+            Err(CompressionError::InvalidFrame)
+        ));
+    }
+
+    #[rstest]
+    fn test_bomb_guard(#[values(true, false)] include_size: bool) {
+        let data = vec![0_u8; 10_000_000];
+        let mut compressor = Compressor::new(COMPRESSION_LEVEL).unwrap();
+        compressor.include_contentsize(include_size).unwrap();
+        let mut compressed = vec![NO_DICT];
+        compressed.extend(compressor.compress(&data).unwrap());
+        assert!(compressed.len() < 10_000);
+
+        assert!(matches!(
+            decompress_with_dict(&compressed, 1_000_000),
+            Err(CompressionError::TooLarge(1_000_000))
+        ));
+        assert!(matches!(
+            decompress_with_dict(&compressed, data.len() - 1),
+            Err(CompressionError::TooLarge(_))
+        ));
+        assert_eq!(
+            decompress_with_dict(&compressed, data.len()).unwrap().len(),
+            data.len()
+        );
+    }
+
+    #[rstest]
+    #[case(WINDOW_LOG_MAX, true)]
+    #[case(WINDOW_LOG_MAX + 1, false)]
+    fn test_window_size(#[case] window_log: u32, #[case] ok: bool) {
+        use zstd::stream::write::Encoder;
+        use zstd::zstd_safe::CParameter;
+
+        // without a pledged size, the frame header asks for the full window,
+        // regardless of how small the content is
+        let data = cat_spend();
+        let mut encoder = Encoder::new(vec![NO_DICT], COMPRESSION_LEVEL).unwrap();
+        encoder
+            .set_parameter(CParameter::WindowLog(window_log))
+            .unwrap();
+        encoder.write_all(&data).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let result = decompress_with_dict(&compressed, 100_000);
+        if ok {
+            assert_eq!(result.unwrap(), data);
+        } else {
+            assert!(matches!(result, Err(CompressionError::Io(_))));
+        }
+    }
+
+    #[test]
+    fn test_large_window_round_trip() {
+        // large enough for the compressor to use its largest window
+        let data: Vec<u8> = (0..10_000_000_u32).map(|i| (i % 251) as u8).collect();
+        let compressed = compress_with_dict(&data, None);
+        assert_eq!(decompress_with_dict(&compressed, data.len()).unwrap(), data);
+    }
+
+    #[rstest]
+    fn test_truncated(#[values(None, Some(KnownDict::StandardPuzzles))] dict: Option<KnownDict>) {
+        let compressed = compress_with_dict(&cat_spend(), dict);
+        for len in 1..compressed.len() {
+            assert!(matches!(
+                decompress_with_dict(&compressed[..len], 100_000),
+                Err(CompressionError::InvalidFrame)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_trailing_data() {
+        let mut compressed = compress_with_dict(&cat_spend(), None);
+        compressed.push(0);
+        assert!(matches!(
+            decompress_with_dict(&compressed, 100_000),
+            Err(CompressionError::TrailingData)
+        ));
+    }
+
+    #[rstest]
+    #[case(2)]
+    #[case(255)]
+    fn test_unknown_dict(#[case] id: u8) {
+        let mut compressed = compress_with_dict(&cat_spend(), None);
+        compressed[0] = id;
+        assert!(matches!(
+            decompress_with_dict(&compressed, 100_000),
+            Err(CompressionError::UnknownDict(i)) if i == id
+        ));
+    }
+
+    #[test]
+    fn test_wrong_dict() {
+        let data = cat_spend();
+        let mut compressed = compress_with_dict(&data, Some(KnownDict::StandardPuzzles));
+        compressed[0] = NO_DICT;
+        assert!(decompress_with_dict(&compressed, 100_000).is_err());
+    }
+}
@@ -26,16 +26,49 @@ pub trait DeriveSynthetic {
 
 impl DeriveSynthetic for PublicKey {
     fn derive_synthetic_hidden(&self, hidden_puzzle_hash: &[u8; 32]) -> Self {
-        self + &synthetic_offset(self, hidden_puzzle_hash).public_key()
+        self + &calculate_synthetic_offset(self, hidden_puzzle_hash).public_key()
     }
 }
 
 impl DeriveSynthetic for SecretKey {
     fn derive_synthetic_hidden(&self, hidden_puzzle_hash: &[u8; 32]) -> Self {
-        self + &synthetic_offset(&self.public_key(), hidden_puzzle_hash)
+        self + &calculate_synthetic_offset(&self.public_key(), hidden_puzzle_hash)
     }
 }
 
+/// The scalar that's added to a key to make it synthetic. It's the sha256 of
+/// the original public key and the hidden puzzle hash, reduced modulo the
+/// group order.
+pub fn calculate_synthetic_offset(
+    public_key: &PublicKey,
+    hidden_puzzle_hash: &[u8; 32],
+) -> SecretKey {
+    let mut hasher = Sha256::new();
+    hasher.update(public_key.to_bytes());
+    hasher.update(hidden_puzzle_hash);
+    let bytes: [u8; 32] = hasher.finalize();
+    SecretKey::from_bytes(&mod_by_group_order(bytes)).unwrap()
+}
+
+/// The secret key of the synthetic public key for `secret_key`'s public key
+/// and `hidden_puzzle_hash`. Spends of the standard puzzle are signed with it.
+pub fn calculate_synthetic_secret_key(
+    secret_key: &SecretKey,
+    hidden_puzzle_hash: &[u8; 32],
+) -> SecretKey {
+    secret_key.derive_synthetic_hidden(hidden_puzzle_hash)
+}
+
+/// Returns true if `synthetic_key` is the synthetic key of `original_key`
+/// with `hidden_puzzle_hash`.
+pub fn is_synthetic_of(
+    synthetic_key: &PublicKey,
+    original_key: &PublicKey,
+    hidden_puzzle_hash: &[u8; 32],
+) -> bool {
+    original_key.derive_synthetic_hidden(hidden_puzzle_hash) == *synthetic_key
+}
+
 pub fn mod_by_group_order(bytes: [u8; 32]) -> [u8; 32] {
     let value = BigInt::from_signed_bytes_be(bytes.as_slice());
     let group_order = BigInt::from_signed_bytes_be(&GROUP_ORDER_BYTES);
@@ -48,19 +81,11 @@ pub fn mod_by_group_order(bytes: [u8; 32]) -> [u8; 32] {
     byte_vec.try_into().unwrap()
 }
 
-fn synthetic_offset(public_key: &PublicKey, hidden_puzzle_hash: &[u8; 32]) -> SecretKey {
-    let mut hasher = Sha256::new();
-    hasher.update(public_key.to_bytes());
-    hasher.update(hidden_puzzle_hash);
-    let bytes: [u8; 32] = hasher.finalize();
-    SecretKey::from_bytes(&mod_by_group_order(bytes)).unwrap()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use chia_bls::{master_to_wallet_unhardened_intermediate, DerivableKey};
+    use chia_bls::{master_to_wallet_unhardened_intermediate, sign, verify, DerivableKey};
     use hex::ToHex;
     use hex_literal::hex;
 
@@ -132,6 +157,59 @@ mod tests {
                 .derive_unhardened(index as u32)
                 .derive_synthetic();
             assert_eq!(key.to_bytes().encode_hex::<String>(), *hex);
+
+            let key = calculate_synthetic_secret_key(
+                &intermediate.derive_unhardened(index as u32),
+                &DEFAULT_HIDDEN_PUZZLE_HASH.to_bytes(),
+            );
+            assert_eq!(key.to_bytes().encode_hex::<String>(), *hex);
         }
     }
+
+    #[test]
+    fn test_synthetic_offset() {
+        let sk = SecretKey::from_bytes(&hex!(
+            "6bb19282e27bc6e7e397fb19efc2627a412410fdfd13bf14f4ce5bfdce084c71"
+        ))
+        .unwrap();
+        let pk = sk.public_key();
+        let hidden_puzzle_hash = DEFAULT_HIDDEN_PUZZLE_HASH.to_bytes();
+
+        let offset = calculate_synthetic_offset(&pk, &hidden_puzzle_hash);
+        let mut hasher = Sha256::new();
+        hasher.update(pk.to_bytes());
+        hasher.update(hidden_puzzle_hash);
+        assert_eq!(offset.to_bytes(), mod_by_group_order(hasher.finalize()));
+
+        assert_eq!(
+            calculate_synthetic_secret_key(&sk, &hidden_puzzle_hash),
+            &sk + &offset
+        );
+        assert_eq!(pk.derive_synthetic(), &pk + &offset.public_key());
+    }
+
+    #[test]
+    fn test_sign_with_synthetic_key() {
+        let sk = SecretKey::from_seed(&[1; 32]);
+        let pk = sk.public_key();
+        let hidden_puzzle_hash = DEFAULT_HIDDEN_PUZZLE_HASH.to_bytes();
+
+        let synthetic_sk = calculate_synthetic_secret_key(&sk, &hidden_puzzle_hash);
+        let synthetic_pk = pk.derive_synthetic_hidden(&hidden_puzzle_hash);
+        assert_eq!(synthetic_sk.public_key(), synthetic_pk);
+
+        let msg = b"standard puzzle spend";
+        let sig = sign(&synthetic_sk, msg);
+        assert!(verify(&sig, &synthetic_pk, msg));
+        assert!(!verify(&sig, &pk, msg));
+
+        assert!(is_synthetic_of(&synthetic_pk, &pk, &hidden_puzzle_hash));
+        assert!(!is_synthetic_of(&pk, &pk, &hidden_puzzle_hash));
+        assert!(!is_synthetic_of(
+            &synthetic_pk,
+            &synthetic_pk,
+            &hidden_puzzle_hash
+        ));
+        assert!(!is_synthetic_of(&synthetic_pk, &pk, &[0; 32]));
+    }
 }
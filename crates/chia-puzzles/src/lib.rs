@@ -1,7 +1,16 @@
+mod bech32;
+mod cat_spend;
+mod compression;
 mod derive_synthetic;
+mod message;
+mod offer_file;
 mod proof;
 mod puzzles;
 
+pub use cat_spend::*;
+pub use compression::*;
 pub use derive_synthetic::*;
+pub use message::*;
+pub use offer_file::*;
 pub use proof::*;
 pub use puzzles::*;
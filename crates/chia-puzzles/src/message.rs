@@ -0,0 +1,436 @@
+use chia_protocol::{Bytes, Bytes32};
+use clvm_traits::{
+    ClvmDecoder, ClvmEncoder, FromClvm, FromClvmError, MatchByte, Raw, ToClvm, ToClvmError,
+};
+use thiserror::Error;
+
+/// The longest message `SEND_MESSAGE` and `RECEIVE_MESSAGE` accept.
+pub const MAX_MESSAGE_LEN: usize = 1024;
+
+const SEND_MESSAGE: u8 = 66;
+const RECEIVE_MESSAGE: u8 = 67;
+
+/// The properties of a coin that one side of a message commits to. The mode
+/// argument of `SEND_MESSAGE` and `RECEIVE_MESSAGE` packs the sender's mode in
+/// the upper 3 bits and the receiver's mode in the lower 3 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[repr(u8)]
+pub enum MessageMode {
+    None = 0b000,
+    Amount = 0b001,
+    Puzzle = 0b010,
+    PuzzleAmount = 0b011,
+    Parent = 0b100,
+    ParentAmount = 0b101,
+    ParentPuzzle = 0b110,
+    /// Commits to parent, puzzle and amount, which is specified as the coin ID.
+    Coin = 0b111,
+}
+
+impl MessageMode {
+    /// Returns `None` if any bit above the lower 3 is set.
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0b000 => Some(Self::None),
+            0b001 => Some(Self::Amount),
+            0b010 => Some(Self::Puzzle),
+            0b011 => Some(Self::PuzzleAmount),
+            0b100 => Some(Self::Parent),
+            0b101 => Some(Self::ParentAmount),
+            0b110 => Some(Self::ParentPuzzle),
+            0b111 => Some(Self::Coin),
+            _ => None,
+        }
+    }
+
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+
+    /// The number of condition arguments it takes to identify the other side
+    /// of a message with this mode.
+    pub fn arg_count(self) -> usize {
+        match self {
+            Self::Coin => 1,
+            mode => mode.bits().count_ones() as usize,
+        }
+    }
+}
+
+/// The other side of a message, i.e. the receiver of `SEND_MESSAGE` or the
+/// sender of `RECEIVE_MESSAGE`. Its [`MessageMode`] is implied by the variant.
+/// It serializes to the list of condition arguments following the message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum SpendTarget {
+    None,
+    Amount(u64),
+    Puzzle(Bytes32),
+    PuzzleAmount(Bytes32, u64),
+    Parent(Bytes32),
+    ParentAmount(Bytes32, u64),
+    ParentPuzzle(Bytes32, Bytes32),
+    Coin(Bytes32),
+}
+
+impl SpendTarget {
+    pub fn mode(&self) -> MessageMode {
+        match self {
+            Self::None => MessageMode::None,
+            Self::Amount(..) => MessageMode::Amount,
+            Self::Puzzle(..) => MessageMode::Puzzle,
+            Self::PuzzleAmount(..) => MessageMode::PuzzleAmount,
+            Self::Parent(..) => MessageMode::Parent,
+            Self::ParentAmount(..) => MessageMode::ParentAmount,
+            Self::ParentPuzzle(..) => MessageMode::ParentPuzzle,
+            Self::Coin(..) => MessageMode::Coin,
+        }
+    }
+
+    /// Parses the arguments identifying a coin with the specified mode.
+    /// Trailing arguments are ignored, like the node does outside of mempool
+    /// mode.
+    pub fn parse<N, D: ClvmDecoder<Node = N>>(
+        decoder: &D,
+        mode: MessageMode,
+        args: N,
+    ) -> Result<Self, FromClvmError> {
+        Ok(match mode {
+            MessageMode::None => Self::None,
+            MessageMode::Amount => {
+                let (amount, _) = <(u64, Raw<N>)>::from_clvm(decoder, args)?;
+                Self::Amount(amount)
+            }
+            MessageMode::Puzzle => {
+                let (puzzle, _) = <(Bytes32, Raw<N>)>::from_clvm(decoder, args)?;
+                Self::Puzzle(puzzle)
+            }
+            MessageMode::PuzzleAmount => {
+                let (puzzle, (amount, _)) = <(Bytes32, (u64, Raw<N>))>::from_clvm(decoder, args)?;
+                Self::PuzzleAmount(puzzle, amount)
+            }
+            MessageMode::Parent => {
+                let (parent, _) = <(Bytes32, Raw<N>)>::from_clvm(decoder, args)?;
+                Self::Parent(parent)
+            }
+            MessageMode::ParentAmount => {
+                let (parent, (amount, _)) = <(Bytes32, (u64, Raw<N>))>::from_clvm(decoder, args)?;
+                Self::ParentAmount(parent, amount)
+            }
+            MessageMode::ParentPuzzle => {
+                let (parent, (puzzle, _)) =
+                    <(Bytes32, (Bytes32, Raw<N>))>::from_clvm(decoder, args)?;
+                Self::ParentPuzzle(parent, puzzle)
+            }
+            MessageMode::Coin => {
+                let (coin_id, _) = <(Bytes32, Raw<N>)>::from_clvm(decoder, args)?;
+                Self::Coin(coin_id)
+            }
+        })
+    }
+}
+
+impl<N, E: ClvmEncoder<Node = N>> ToClvm<E> for SpendTarget {
+    fn to_clvm(&self, encoder: &mut E) -> Result<N, ToClvmError> {
+        match self {
+            Self::None => ().to_clvm(encoder),
+            Self::Amount(amount) => (amount, ()).to_clvm(encoder),
+            Self::Puzzle(puzzle) | Self::Parent(puzzle) | Self::Coin(puzzle) => {
+                (puzzle, ()).to_clvm(encoder)
+            }
+            Self::PuzzleAmount(hash, amount) | Self::ParentAmount(hash, amount) => {
+                (hash, (amount, ())).to_clvm(encoder)
+            }
+            Self::ParentPuzzle(parent, puzzle) => (parent, (puzzle, ())).to_clvm(encoder),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MessageError {
+    #[error("message is {0} bytes, the maximum is {MAX_MESSAGE_LEN}")]
+    MessageTooLong(usize),
+}
+
+fn check_message(message: &Bytes) -> Result<(), MessageError> {
+    if message.len() > MAX_MESSAGE_LEN {
+        return Err(MessageError::MessageTooLong(message.len()));
+    }
+    Ok(())
+}
+
+// parses (mode message . args), returning the two modes
+fn parse_message<N, D: ClvmDecoder<Node = N>>(
+    decoder: &D,
+    node: N,
+) -> Result<(MessageMode, MessageMode, Bytes, N), FromClvmError> {
+    let (mode, (message, Raw(args))) = <(u8, (Bytes, Raw<N>))>::from_clvm(decoder, node)?;
+    if mode > 0b11_1111 {
+        return Err(FromClvmError::Custom(format!(
+            "invalid message mode {mode}"
+        )));
+    }
+    check_message(&message).map_err(|err| FromClvmError::Custom(err.to_string()))?;
+    let sender = MessageMode::from_bits(mode >> 3).expect("mode is 6 bits");
+    let receiver = MessageMode::from_bits(mode & 0b111).expect("mode is 6 bits");
+    Ok((sender, receiver, message, args))
+}
+
+/// The `SEND_MESSAGE` condition. The sending coin is the one creating the
+/// condition, so only its mode is specified. The receiver must be spent in the
+/// same block, with a matching `RECEIVE_MESSAGE`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct SendMessage {
+    pub sender_mode: MessageMode,
+    pub receiver: SpendTarget,
+    pub message: Bytes,
+}
+
+impl SendMessage {
+    pub fn new(
+        sender_mode: MessageMode,
+        receiver: SpendTarget,
+        message: Bytes,
+    ) -> Result<Self, MessageError> {
+        check_message(&message)?;
+        Ok(Self {
+            sender_mode,
+            receiver,
+            message,
+        })
+    }
+
+    /// The packed mode argument of the condition.
+    pub fn mode(&self) -> u8 {
+        (self.sender_mode.bits() << 3) | self.receiver.mode().bits()
+    }
+}
+
+impl<N, E: ClvmEncoder<Node = N>> ToClvm<E> for SendMessage {
+    fn to_clvm(&self, encoder: &mut E) -> Result<N, ToClvmError> {
+        let args = self.receiver.to_clvm(encoder)?;
+        (
+            MatchByte::<SEND_MESSAGE>,
+            (self.mode(), (&self.message, Raw(args))),
+        )
+            .to_clvm(encoder)
+    }
+}
+
+impl<N, D: ClvmDecoder<Node = N>> FromClvm<D> for SendMessage {
+    fn from_clvm(decoder: &D, node: N) -> Result<Self, FromClvmError> {
+        let (_, Raw(rest)) = <(MatchByte<SEND_MESSAGE>, Raw<N>)>::from_clvm(decoder, node)?;
+        let (sender_mode, receiver_mode, message, args) = parse_message(decoder, rest)?;
+        Ok(Self {
+            sender_mode,
+            receiver: SpendTarget::parse(decoder, receiver_mode, args)?,
+            message,
+        })
+    }
+}
+
+/// The `RECEIVE_MESSAGE` condition. The receiving coin is the one creating the
+/// condition, so only its mode is specified.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ReceiveMessage {
+    pub sender: SpendTarget,
+    pub receiver_mode: MessageMode,
+    pub message: Bytes,
+}
+
+impl ReceiveMessage {
+    pub fn new(
+        sender: SpendTarget,
+        receiver_mode: MessageMode,
+        message: Bytes,
+    ) -> Result<Self, MessageError> {
+        check_message(&message)?;
+        Ok(Self {
+            sender,
+            receiver_mode,
+            message,
+        })
+    }
+
+    /// The packed mode argument of the condition.
+    pub fn mode(&self) -> u8 {
+        (self.sender.mode().bits() << 3) | self.receiver_mode.bits()
+    }
+}
+
+impl<N, E: ClvmEncoder<Node = N>> ToClvm<E> for ReceiveMessage {
+    fn to_clvm(&self, encoder: &mut E) -> Result<N, ToClvmError> {
+        let args = self.sender.to_clvm(encoder)?;
+        (
+            MatchByte::<RECEIVE_MESSAGE>,
+            (self.mode(), (&self.message, Raw(args))),
+        )
+            .to_clvm(encoder)
+    }
+}
+
+impl<N, D: ClvmDecoder<Node = N>> FromClvm<D> for ReceiveMessage {
+    fn from_clvm(decoder: &D, node: N) -> Result<Self, FromClvmError> {
+        let (_, Raw(rest)) = <(MatchByte<RECEIVE_MESSAGE>, Raw<N>)>::from_clvm(decoder, node)?;
+        let (sender_mode, receiver_mode, message, args) = parse_message(decoder, rest)?;
+        Ok(Self {
+            sender: SpendTarget::parse(decoder, sender_mode, args)?,
+            receiver_mode,
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clvmr::serde::node_to_bytes;
+    use clvmr::Allocator;
+    use rstest::rstest;
+
+    const PARENT: Bytes32 = Bytes32::new([1; 32]);
+    const PUZZLE: Bytes32 = Bytes32::new([2; 32]);
+
+    #[rstest]
+    #[case(SpendTarget::None, 0b000, "80")]
+    #[case(SpendTarget::Amount(1337), 0b001, "ff82053980")]
+    #[case(
+        SpendTarget::Puzzle(PUZZLE),
+        0b010,
+        "ffa0020202020202020202020202020202020202020202020202020202020202020280"
+    )]
+    #[case(
+        SpendTarget::PuzzleAmount(PUZZLE, 1337),
+        0b011,
+        "ffa00202020202020202020202020202020202020202020202020202020202020202ff82053980"
+    )]
+    #[case(
+        SpendTarget::Parent(PARENT),
+        0b100,
+        "ffa0010101010101010101010101010101010101010101010101010101010101010180"
+    )]
+    #[case(
+        SpendTarget::ParentAmount(PARENT, 1337),
+        0b101,
+        "ffa00101010101010101010101010101010101010101010101010101010101010101ff82053980"
+    )]
+    #[case(SpendTarget::ParentPuzzle(PARENT, PUZZLE), 0b110, "ffa00101010101010101010101010101010101010101010101010101010101010101ffa0020202020202020202020202020202020202020202020202020202020202020280")]
+    #[case(
+        SpendTarget::Coin(PARENT),
+        0b111,
+        "ffa0010101010101010101010101010101010101010101010101010101010101010180"
+    )]
+    fn test_spend_target(#[case] target: SpendTarget, #[case] mode: u8, #[case] args: &str) {
+        let mut a = Allocator::new();
+        assert_eq!(target.mode().bits(), mode);
+        assert_eq!(MessageMode::from_bits(mode), Some(target.mode()));
+
+        let node = target.to_clvm(&mut a).unwrap();
+        let mut count = 0;
+        let mut list = node;
+        while let Some((_, rest)) = a.next(list) {
+            count += 1;
+            list = rest;
+        }
+        assert_eq!(target.mode().arg_count(), count);
+        assert_eq!(hex::encode(node_to_bytes(&a, node).unwrap()), args);
+        assert_eq!(SpendTarget::parse(&a, target.mode(), node).unwrap(), target);
+    }
+
+    #[test]
+    fn test_invalid_mode_bits() {
+        assert_eq!(MessageMode::from_bits(0b1000), None);
+        assert_eq!(MessageMode::from_bits(0xff), None);
+    }
+
+    #[test]
+    fn test_send_message() {
+        let mut a = Allocator::new();
+        let cond = SendMessage::new(
+            MessageMode::Puzzle,
+            SpendTarget::ParentAmount(PARENT, 1337),
+            Bytes::new(b"foobar".to_vec()),
+        )
+        .unwrap();
+        assert_eq!(cond.mode(), 0b010_101);
+
+        let node = cond.to_clvm(&mut a).unwrap();
+        assert_eq!(
+            hex::encode(node_to_bytes(&a, node).unwrap()),
+            "ff42ff15ff86666f6f626172ffa00101010101010101010101010101010101010101010101010101010101010101ff82053980"
+        );
+        assert_eq!(SendMessage::from_clvm(&a, node).unwrap(), cond);
+
+        // it's not a RECEIVE_MESSAGE
+        assert!(ReceiveMessage::from_clvm(&a, node).is_err());
+    }
+
+    #[test]
+    fn test_receive_message() {
+        let mut a = Allocator::new();
+        let cond = ReceiveMessage::new(
+            SpendTarget::Coin(PUZZLE),
+            MessageMode::None,
+            Bytes::new(b"foobar".to_vec()),
+        )
+        .unwrap();
+        assert_eq!(cond.mode(), 0b111_000);
+
+        let node = cond.to_clvm(&mut a).unwrap();
+        assert_eq!(
+            hex::encode(node_to_bytes(&a, node).unwrap()),
+            "ff43ff38ff86666f6f626172ffa0020202020202020202020202020202020202020202020202020202020202020280"
+        );
+        assert_eq!(ReceiveMessage::from_clvm(&a, node).unwrap(), cond);
+        assert!(SendMessage::from_clvm(&a, node).is_err());
+    }
+
+    #[rstest]
+    #[case(0, true)]
+    #[case(1024, true)]
+    #[case(1025, false)]
+    fn test_message_len(#[case] len: usize, #[case] valid: bool) {
+        let msg = Bytes::new(vec![0x42; len]);
+        let send = SendMessage::new(MessageMode::Coin, SpendTarget::None, msg.clone());
+        let receive = ReceiveMessage::new(SpendTarget::None, MessageMode::Coin, msg);
+        if valid {
+            assert!(send.is_ok());
+            assert!(receive.is_ok());
+        } else {
+            assert_eq!(send, Err(MessageError::MessageTooLong(len)));
+            assert_eq!(receive, Err(MessageError::MessageTooLong(len)));
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects() {
+        let mut a = Allocator::new();
+
+        // a message that's too long
+        let node = (
+            SEND_MESSAGE,
+            (0b111_000_u8, (Bytes::new(vec![0; 1025]), ())),
+        )
+            .to_clvm(&mut a)
+            .unwrap();
+        assert!(SendMessage::from_clvm(&a, node).is_err());
+
+        // a mode with more than 6 bits
+        let node = (SEND_MESSAGE, (0b1_000_000_u8, (Bytes::default(), ())))
+            .to_clvm(&mut a)
+            .unwrap();
+        assert!(SendMessage::from_clvm(&a, node).is_err());
+
+        // too few arguments for the mode
+        let node = (
+            SEND_MESSAGE,
+            (0b000_110_u8, (Bytes::default(), (PARENT, ()))),
+        )
+            .to_clvm(&mut a)
+            .unwrap();
+        assert!(SendMessage::from_clvm(&a, node).is_err());
+    }
+}
@@ -0,0 +1,547 @@
+use chia_protocol::{Bytes32, SpendBundle};
+use chia_traits::{chia_error, Streamable};
+use clvm_traits::{FromClvm, FromClvmError};
+use clvm_utils::{tree_hash, CurriedProgram};
+use clvmr::serde::{node_from_bytes, node_to_bytes};
+use clvmr::{Allocator, NodePtr};
+use miniz_oxide::deflate::core::{
+    compress_to_output, create_comp_flags_from_zip_params, CompressorOxide, TDEFLFlush, TDEFLStatus,
+};
+use miniz_oxide::inflate::core::inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF;
+use miniz_oxide::inflate::core::{decompress, DecompressorOxide};
+use miniz_oxide::inflate::TINFLStatus;
+use miniz_oxide::mz_adler32_oxide;
+use thiserror::Error;
+
+use crate::bech32;
+use crate::cat::{CatArgs, CAT_PUZZLE, CAT_PUZZLE_HASH, CAT_PUZZLE_V1};
+use crate::nft::{
+    NFT_METADATA_UPDATER_PUZZLE, NFT_OWNERSHIP_LAYER_PUZZLE, NFT_ROYALTY_TRANSFER_PUZZLE,
+    NFT_STATE_LAYER_PUZZLE,
+};
+use crate::offer::{
+    NotarizedPayment, SettlementPaymentsSolution, SETTLEMENT_PAYMENTS_PUZZLE,
+    SETTLEMENT_PAYMENTS_PUZZLE_V1,
+};
+use crate::singleton::SINGLETON_TOP_LAYER_PUZZLE;
+use crate::standard::STANDARD_PUZZLE;
+
+// The compression dictionaries, one entry per version. The dictionary for a
+// given version is the concatenation of all entries up to and including that
+// version. These must match the dictionaries used by the reference wallet
+// (chia/wallet/util/puzzle_compression.py) exactly, and can only ever be
+// appended to.
+const ZDICT: [&[&[u8]]; 5] = [
+    &[&STANDARD_PUZZLE, &CAT_PUZZLE_V1],
+    &[
+        &SETTLEMENT_PAYMENTS_PUZZLE_V1,
+        &SINGLETON_TOP_LAYER_PUZZLE,
+        &NFT_STATE_LAYER_PUZZLE,
+        &NFT_OWNERSHIP_LAYER_PUZZLE,
+        &NFT_METADATA_UPDATER_PUZZLE,
+        &NFT_ROYALTY_TRANSFER_PUZZLE,
+    ],
+    &[&CAT_PUZZLE],
+    &[&SETTLEMENT_PAYMENTS_PUZZLE],
+    // version 5 purposefully breaks compatibility with older wallets, without
+    // adding anything to the dictionary
+    &[],
+];
+
+/// The most recent compression version supported.
+pub const MAX_OFFER_COMPRESSION_VERSION: u16 = ZDICT.len() as u16;
+
+// this matches the limit the reference wallet uses when decompressing offers
+const MAX_DECOMPRESSED_SIZE: usize = 6 * 1024 * 1024;
+
+// deflate can only refer back 32 kiB, so only the end of a (larger)
+// dictionary is ever used. This is what zlib does too
+const WINDOW_SIZE: usize = 32 * 1024;
+
+// the zlib header python's zlib.compressobj(zdict=...) writes: deflate with a
+// 32 kiB window, the default compression level and a preset dictionary
+const ZLIB_HEADER: [u8; 2] = [0x78, 0xbb];
+const FDICT: u8 = 0x20;
+
+#[derive(Debug, Error)]
+pub enum OfferError {
+    #[error("invalid bech32m encoding")]
+    InvalidBech32,
+
+    #[error("unsupported compression version {0}")]
+    UnsupportedVersion(u16),
+
+    #[error("compression failed")]
+    Compress,
+
+    #[error("invalid compressed offer")]
+    Decompress,
+
+    #[error("truncated compressed offer")]
+    Truncated,
+
+    #[error("decompressed offer exceeds {MAX_DECOMPRESSED_SIZE} bytes")]
+    TooLarge,
+
+    #[error("trailing data after compressed offer")]
+    TrailingData,
+
+    #[error("{0:?}")]
+    Streamable(#[from] chia_error::Error),
+
+    #[error("invalid CLVM serialization")]
+    InvalidClvm,
+
+    #[error("{0}")]
+    FromClvm(#[from] FromClvmError),
+}
+
+/// An offer file, as exchanged between wallets. An offer is a partial spend
+/// bundle. The payments requested in return are expressed as spends of coins
+/// with a nil parent, whose solutions are the notarized payments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Offer {
+    spend_bundle: SpendBundle,
+}
+
+/// The payments an offer requests, for one asset type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestedPayments {
+    /// The puzzle hash of the settlement coin. This is the settlement payments
+    /// puzzle hash for XCH, or the settlement puzzle wrapped in some outer
+    /// puzzle (e.g. a CAT) for other assets.
+    pub puzzle_hash: Bytes32,
+    /// The CAT asset ID, if the settlement puzzle is wrapped in a CAT.
+    pub asset_id: Option<Bytes32>,
+    pub notarized_payments: Vec<NotarizedPayment>,
+}
+
+impl Offer {
+    pub fn new(spend_bundle: SpendBundle) -> Self {
+        Self { spend_bundle }
+    }
+
+    pub fn spend_bundle(&self) -> &SpendBundle {
+        &self.spend_bundle
+    }
+
+    pub fn into_spend_bundle(self) -> SpendBundle {
+        self.spend_bundle
+    }
+
+    /// Parses a bech32m encoded offer. The human readable part (normally
+    /// "offer") is not checked. Both compressed and uncompressed offers are
+    /// supported.
+    pub fn from_bech32(offer: &str) -> Result<Self, OfferError> {
+        let (_hrp, data) = bech32::decode(offer).ok_or(OfferError::InvalidBech32)?;
+        Self::decompress(&data)
+    }
+
+    pub fn to_bech32(&self, prefix: &str, compression_version: u16) -> Result<String, OfferError> {
+        Ok(bech32::encode(prefix, &self.compress(compression_version)?))
+    }
+
+    /// Serializes and compresses the spend bundle. The result is prefixed by
+    /// the compression version, as a 16 bit big-endian integer.
+    pub fn compress(&self, version: u16) -> Result<Vec<u8>, OfferError> {
+        let zdict = zdict_for_version(version)?;
+        let mut ret = version.to_be_bytes().to_vec();
+        ret.extend(compress_with_zdict(&self.spend_bundle.to_bytes()?, &zdict)?);
+        Ok(ret)
+    }
+
+    /// The inverse of `compress()`. For compatibility with the reference
+    /// wallet, if the input isn't a valid compressed offer, it's parsed as an
+    /// uncompressed spend bundle.
+    pub fn decompress(input: &[u8]) -> Result<Self, OfferError> {
+        match decompress_offer(input) {
+            Ok(spend_bundle) => Ok(Self::new(spend_bundle)),
+            Err(err) => SpendBundle::from_bytes(input)
+                .map(Self::new)
+                .map_err(|_| err),
+        }
+    }
+
+    /// Returns the lowest compression version whose dictionary contains all
+    /// (uncurried) puzzles used in this offer.
+    pub fn lowest_compression_version(&self) -> Result<u16, OfferError> {
+        let mut a = Allocator::new();
+        let mut version = 1;
+        for cs in &self.spend_bundle.coin_spends {
+            let puzzle = node_from_bytes(&mut a, cs.puzzle_reveal.as_ref())
+                .map_err(|_| OfferError::InvalidClvm)?;
+            let puzzle = match CurriedProgram::<NodePtr, NodePtr>::from_clvm(&a, puzzle) {
+                Ok(curried) => curried.program,
+                Err(_) => puzzle,
+            };
+            let puzzle = node_to_bytes(&a, puzzle).map_err(|_| OfferError::InvalidClvm)?;
+            // like the reference wallet, this looks for the puzzle anywhere
+            // in the dictionary, not just for exact matches of a puzzle in it
+            for (i, entry) in ZDICT.iter().enumerate() {
+                let entry = entry.concat();
+                if entry.windows(puzzle.len()).any(|w| w == puzzle.as_slice()) {
+                    version = version.max(i as u16 + 1);
+                }
+            }
+        }
+        Ok(version)
+    }
+
+    /// Returns the payments requested by this offer. These are the spends of
+    /// coins whose parent coin ID is all zeros, and whose solutions are the
+    /// notarized payments.
+    pub fn requested_payments(&self) -> Result<Vec<RequestedPayments>, OfferError> {
+        let mut a = Allocator::new();
+        let mut ret = Vec::new();
+        for cs in &self.spend_bundle.coin_spends {
+            if cs.coin.parent_coin_info != Bytes32::default() {
+                continue;
+            }
+            let puzzle = node_from_bytes(&mut a, cs.puzzle_reveal.as_ref())
+                .map_err(|_| OfferError::InvalidClvm)?;
+            let solution = node_from_bytes(&mut a, cs.solution.as_ref())
+                .map_err(|_| OfferError::InvalidClvm)?;
+
+            let asset_id = match CurriedProgram::<NodePtr, CatArgs<NodePtr>>::from_clvm(&a, puzzle)
+            {
+                Ok(curried) if tree_hash(&a, curried.program) == CAT_PUZZLE_HASH => {
+                    Some(curried.args.asset_id)
+                }
+                _ => None,
+            };
+            let solution = SettlementPaymentsSolution::from_clvm(&a, solution)?;
+            ret.push(RequestedPayments {
+                puzzle_hash: cs.coin.puzzle_hash,
+                asset_id,
+                notarized_payments: solution.notarized_payments,
+            });
+        }
+        Ok(ret)
+    }
+}
+
+impl From<SpendBundle> for Offer {
+    fn from(spend_bundle: SpendBundle) -> Self {
+        Self::new(spend_bundle)
+    }
+}
+
+impl From<Offer> for SpendBundle {
+    fn from(offer: Offer) -> Self {
+        offer.spend_bundle
+    }
+}
+
+fn zdict_for_version(version: u16) -> Result<Vec<u8>, OfferError> {
+    if version == 0 || version > MAX_OFFER_COMPRESSION_VERSION {
+        return Err(OfferError::UnsupportedVersion(version));
+    }
+    Ok(ZDICT[..version as usize]
+        .iter()
+        .flat_map(|entry| entry.iter())
+        .flat_map(|mod_bytes| mod_bytes.iter())
+        .copied()
+        .collect())
+}
+
+fn decompress_offer(input: &[u8]) -> Result<SpendBundle, OfferError> {
+    if input.len() < 2 {
+        return Err(OfferError::Truncated);
+    }
+    let version = u16::from_be_bytes([input[0], input[1]]);
+    let zdict = zdict_for_version(version)?;
+    let bytes = decompress_with_zdict(&input[2..], &zdict)?;
+    Ok(SpendBundle::from_bytes(&bytes)?)
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    mz_adler32_oxide(1, data)
+}
+
+// miniz_oxide doesn't support preset dictionaries, so the zlib framing is done
+// here. The compressor is primed by compressing the dictionary and throwing
+// that output away. The sync flush leaves the output byte aligned, so what
+// follows is a deflate stream whose back-references reach into the
+// dictionary, exactly what zlib produces after deflateSetDictionary().
+fn compress_with_zdict(input: &[u8], zdict: &[u8]) -> Result<Vec<u8>, OfferError> {
+    let window = &zdict[zdict.len().saturating_sub(WINDOW_SIZE)..];
+    // the default compression level, without a zlib header
+    let mut compressor = CompressorOxide::new(create_comp_flags_from_zip_params(6, -15, 0));
+    let (status, _) = compress_to_output(&mut compressor, window, TDEFLFlush::Sync, |_| true);
+    if status != TDEFLStatus::Okay {
+        return Err(OfferError::Compress);
+    }
+
+    let mut output = Vec::with_capacity(input.len() / 2 + 64);
+    output.extend_from_slice(&ZLIB_HEADER);
+    output.extend_from_slice(&adler32(zdict).to_be_bytes());
+    let (status, _) = compress_to_output(&mut compressor, input, TDEFLFlush::Finish, |buf| {
+        output.extend_from_slice(buf);
+        true
+    });
+    if status != TDEFLStatus::Done {
+        return Err(OfferError::Compress);
+    }
+    output.extend_from_slice(&adler32(input).to_be_bytes());
+    Ok(output)
+}
+
+fn decompress_with_zdict(input: &[u8], zdict: &[u8]) -> Result<Vec<u8>, OfferError> {
+    let [cmf, flg, input @ ..] = input else {
+        return Err(OfferError::Truncated);
+    };
+    if cmf & 0x0f != 8 || cmf >> 4 > 7 || u16::from_be_bytes([*cmf, *flg]) % 31 != 0 {
+        return Err(OfferError::Decompress);
+    }
+    // streams that don't use the dictionary are accepted too, just like
+    // zlib.decompressobj(zdict=...) does
+    let (window, input) = if flg & FDICT == 0 {
+        (&[][..], input)
+    } else {
+        let Some((dict_id, input)) = input.split_first_chunk::<4>() else {
+            return Err(OfferError::Truncated);
+        };
+        if u32::from_be_bytes(*dict_id) != adler32(zdict) {
+            return Err(OfferError::Decompress);
+        }
+        (&zdict[zdict.len().saturating_sub(WINDOW_SIZE)..], input)
+    };
+
+    // the dictionary goes in front of the output, where back-references into
+    // it can find it
+    let limit = window.len() + MAX_DECOMPRESSED_SIZE + 1;
+    let mut output = window.to_vec();
+    output.resize(
+        window.len() + input.len().saturating_mul(4).min(MAX_DECOMPRESSED_SIZE),
+        0,
+    );
+
+    let mut decompressor = DecompressorOxide::new();
+    let mut in_pos = 0;
+    let mut out_pos = window.len();
+    loop {
+        let (status, consumed, produced) = decompress(
+            &mut decompressor,
+            &input[in_pos..],
+            &mut output,
+            out_pos,
+            TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+        );
+        in_pos += consumed;
+        out_pos += produced;
+        if out_pos - window.len() > MAX_DECOMPRESSED_SIZE {
+            return Err(OfferError::TooLarge);
+        }
+        match status {
+            TINFLStatus::Done => break,
+            TINFLStatus::HasMoreOutput => {
+                if output.len() >= limit {
+                    return Err(OfferError::TooLarge);
+                }
+                let grow = (out_pos - window.len()).max(1024);
+                output.resize((output.len() + grow).min(limit), 0);
+            }
+            TINFLStatus::FailedCannotMakeProgress | TINFLStatus::NeedsMoreInput => {
+                return Err(OfferError::Truncated);
+            }
+            _ => return Err(OfferError::Decompress),
+        }
+    }
+    output.truncate(out_pos);
+    output.drain(..window.len());
+
+    let Some((checksum, rest)) = input[in_pos..].split_first_chunk::<4>() else {
+        return Err(OfferError::Truncated);
+    };
+    if u32::from_be_bytes(*checksum) != adler32(&output) {
+        return Err(OfferError::Decompress);
+    }
+    if !rest.is_empty() {
+        return Err(OfferError::TrailingData);
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offer::{Payment, SETTLEMENT_PAYMENTS_PUZZLE_HASH};
+    use hex_literal::hex;
+    use rstest::rstest;
+    use std::fs;
+
+    fn read_offer(name: &str) -> String {
+        fs::read_to_string(format!("../../test-offers/{name}.offer"))
+            .expect("test file not found")
+            .trim()
+            .to_string()
+    }
+
+    #[rstest]
+    #[case("xch-for-xch", 4)]
+    #[case("xch-for-xch-v2", 2)]
+    #[case("xch-for-cat", 4)]
+    #[case("xch-for-cat-and-xch", 5)]
+    fn test_round_trip(#[case] name: &str, #[case] version: u16) {
+        let encoded = read_offer(name);
+        let expected = fs::read(format!("../../test-offers/{name}.bundle")).expect("bundle");
+
+        let offer = Offer::from_bech32(&encoded).expect("from_bech32");
+        assert_eq!(offer.spend_bundle().to_bytes().unwrap(), expected);
+        assert_eq!(
+            &offer.compress(version).unwrap()[..2],
+            &version.to_be_bytes()
+        );
+        // the output isn't byte-for-byte the same as zlib's, but it must be
+        // framed the same way, refer to the same dictionary and decompress to
+        // the same spend bundle
+        let compressed = offer.compress(version).unwrap();
+        let zdict = zdict_for_version(version).unwrap();
+        assert_eq!(compressed[2..4], ZLIB_HEADER);
+        assert_eq!(compressed[4..8], adler32(&zdict).to_be_bytes());
+        assert_eq!(Offer::decompress(&compressed).unwrap(), offer);
+        let python = bech32::decode(&encoded).unwrap().1;
+        assert_eq!(python[..8], compressed[..8]);
+
+        // re-encoding with the latest version must still round-trip
+        let latest = offer
+            .to_bech32("offer", MAX_OFFER_COMPRESSION_VERSION)
+            .unwrap();
+        assert_eq!(Offer::from_bech32(&latest).unwrap(), offer);
+    }
+
+    #[test]
+    fn test_uncompressed() {
+        let offer = Offer::from_bech32(&read_offer("uncompressed")).expect("from_bech32");
+        assert_eq!(
+            offer,
+            Offer::from_bech32(&read_offer("xch-for-xch")).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case("xch-for-xch", 4)]
+    #[case("xch-for-cat", 3)]
+    fn test_lowest_compression_version(#[case] name: &str, #[case] expected: u16) {
+        let offer = Offer::from_bech32(&read_offer(name)).unwrap();
+        assert_eq!(offer.lowest_compression_version().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_requested_payments() {
+        let offer = Offer::from_bech32(&read_offer("xch-for-cat-and-xch")).unwrap();
+        let requested = offer.requested_payments().unwrap();
+        assert_eq!(requested.len(), 2);
+
+        let maker_ph = Bytes32::new(hex!(
+            "72138c64ecd8f504233c106d01f9981adcbd37bb27d2fed1b117c351c2209014"
+        ));
+        let cat = &requested[0];
+        assert_eq!(
+            cat.asset_id,
+            Some(Bytes32::new(hex!(
+                "fc4bdb60d16a1a45cb1d0e0a1eafe8db7b9b10485da0a1981726c2d271725806"
+            )))
+        );
+        assert_eq!(
+            cat.puzzle_hash,
+            Bytes32::new(hex!(
+                "db4ac303f1db413239e90d76bb052bcefa27584721eda71407a5eea3e25d9abf"
+            ))
+        );
+        assert_eq!(cat.notarized_payments.len(), 1);
+        let payments = &cat.notarized_payments[0].payments;
+        assert_eq!(payments.len(), 2);
+        assert_eq!(
+            payments[0],
+            Payment::with_memos(maker_ph, 5000, vec![maker_ph.to_vec().into()])
+        );
+        assert_eq!(payments[1].amount, 25);
+        assert_eq!(payments[1].memos, None);
+
+        let xch = &requested[1];
+        assert_eq!(xch.asset_id, None);
+        assert_eq!(xch.puzzle_hash, SETTLEMENT_PAYMENTS_PUZZLE_HASH.into());
+        assert_eq!(
+            xch.notarized_payments[0].payments,
+            vec![Payment::with_memos(
+                maker_ph,
+                1000,
+                vec![maker_ph.to_vec().into()]
+            )]
+        );
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(MAX_OFFER_COMPRESSION_VERSION + 1)]
+    fn test_unsupported_version(#[case] version: u16) {
+        let offer = Offer::from_bech32(&read_offer("xch-for-xch")).unwrap();
+        assert!(matches!(
+            offer.compress(version),
+            Err(OfferError::UnsupportedVersion(v)) if v == version
+        ));
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("offer1")]
+    #[case("offer1qqqqqqqq")]
+    #[case("not an offer")]
+    fn test_invalid(#[case] input: &str) {
+        assert!(Offer::from_bech32(input).is_err());
+    }
+
+    #[test]
+    fn test_corrupt() {
+        let offer = Offer::from_bech32(&read_offer("xch-for-xch")).unwrap();
+        let mut compressed = offer.compress(4).unwrap();
+        // truncated
+        assert!(Offer::decompress(&compressed[..compressed.len() - 10]).is_err());
+        // wrong dictionary
+        compressed[1] = 3;
+        assert!(Offer::decompress(&compressed).is_err());
+    }
+
+    #[test]
+    fn test_trailing_data() {
+        let offer = Offer::from_bech32(&read_offer("xch-for-xch")).unwrap();
+        let mut compressed = offer.compress(4).unwrap();
+        assert_eq!(Offer::decompress(&compressed).unwrap(), offer);
+        compressed.push(0);
+        assert!(matches!(
+            Offer::decompress(&compressed),
+            Err(OfferError::TrailingData)
+        ));
+    }
+
+    #[rstest]
+    #[case(0, false)]
+    #[case(100, false)]
+    #[case(MAX_DECOMPRESSED_SIZE, false)]
+    #[case(MAX_DECOMPRESSED_SIZE + 1, true)]
+    #[case(2 * MAX_DECOMPRESSED_SIZE, true)]
+    fn test_decompressed_size_limit(#[case] size: usize, #[case] too_large: bool) {
+        let zdict = zdict_for_version(MAX_OFFER_COMPRESSION_VERSION).unwrap();
+        let input = vec![0x80; size];
+        let compressed = compress_with_zdict(&input, &zdict).unwrap();
+        match decompress_with_zdict(&compressed, &zdict) {
+            Ok(output) => {
+                assert!(!too_large);
+                assert_eq!(output, input);
+            }
+            Err(OfferError::TooLarge) => assert!(too_large),
+            Err(err) => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[test]
+    fn test_without_dictionary() {
+        // a stream that doesn't use the preset dictionary is still accepted
+        let offer = Offer::from_bech32(&read_offer("xch-for-xch")).unwrap();
+        let bytes = offer.spend_bundle().to_bytes().unwrap();
+        let mut compressed = 4_u16.to_be_bytes().to_vec();
+        compressed.extend(miniz_oxide::deflate::compress_to_vec_zlib(&bytes, 6));
+        assert_eq!(Offer::decompress(&compressed).unwrap(), offer);
+    }
+}
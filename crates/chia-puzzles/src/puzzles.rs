@@ -2,6 +2,7 @@ pub mod cat;
 pub mod did;
 pub mod nft;
 pub mod offer;
+pub mod puzzle_hashes;
 pub mod singleton;
 pub mod standard;
 
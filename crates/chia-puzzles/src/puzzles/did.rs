@@ -1,6 +1,7 @@
 use chia_bls::PublicKey;
-use chia_protocol::Bytes32;
-use clvm_traits::{FromClvm, ToClvm};
+use chia_protocol::{Amount, Bytes32, Coin};
+use chia_sha2::Sha256;
+use clvm_traits::{clvm_list, clvm_quote, ClvmEncoder, FromClvm, ToClvm, ToClvmError};
 use clvm_utils::{CurriedProgram, ToTreeHash, TreeHash};
 use hex_literal::hex;
 
@@ -75,6 +76,65 @@ pub struct DidRecoverySolution {
     pub recovery_coins: Vec<CoinProof>,
     pub public_key: PublicKey,
     pub recovery_list_reveal: Vec<Bytes32>,
+    pub my_id: Bytes32,
+}
+
+/// The hash of a list of DID launcher ids, which is what's curried into the
+/// DID puzzle as the recovery list hash.
+pub fn did_recovery_list_hash(recovery_list: &[Bytes32]) -> Bytes32 {
+    recovery_list.to_vec().tree_hash().into()
+}
+
+/// The message a DID in the recovery list sends to approve the recovery of
+/// another DID. The approving DID creates a coin with this puzzle and an
+/// amount of 0. When spent, it announces the coin ID of the DID being
+/// recovered, and requires a signature of the new inner puzzle hash by the
+/// new public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DidRecoveryMessage {
+    pub recovering_coin_id: Bytes32,
+    pub new_inner_puzzle_hash: Bytes32,
+    pub public_key: PublicKey,
+}
+
+impl DidRecoveryMessage {
+    pub fn new(
+        recovering_coin_id: Bytes32,
+        new_inner_puzzle_hash: Bytes32,
+        public_key: PublicKey,
+    ) -> Self {
+        Self {
+            recovering_coin_id,
+            new_inner_puzzle_hash,
+            public_key,
+        }
+    }
+
+    /// The ID of the message coin, created by the approving DID coin.
+    pub fn coin_id(&self, attestor_coin_id: Bytes32) -> Bytes32 {
+        Coin::new(attestor_coin_id, self.tree_hash().into(), Amount::ZERO).coin_id()
+    }
+
+    /// The ID of the coin announcement the DID being recovered asserts, for
+    /// each approving DID coin.
+    pub fn announcement_id(&self, attestor_coin_id: Bytes32) -> Bytes32 {
+        let mut hasher = Sha256::new();
+        hasher.update(self.coin_id(attestor_coin_id));
+        hasher.update(self.recovering_coin_id);
+        hasher.finalize().into()
+    }
+}
+
+/// The puzzle of the message coin, which is
+/// `(q . ((CREATE_COIN_ANNOUNCEMENT recovering_coin_id) (AGG_SIG_UNSAFE public_key new_inner_puzzle_hash)))`.
+impl<N, E: ClvmEncoder<Node = N>> ToClvm<E> for DidRecoveryMessage {
+    fn to_clvm(&self, encoder: &mut E) -> Result<N, ToClvmError> {
+        clvm_quote!(clvm_list!(
+            clvm_list!(60, self.recovering_coin_id),
+            clvm_list!(49, self.public_key, self.new_inner_puzzle_hash)
+        ))
+        .to_clvm(encoder)
+    }
 }
 
 /// This is the puzzle reveal of the [DID1 standard](https://chialisp.com/dids) puzzle.
@@ -124,7 +184,7 @@ pub const DID_INNER_PUZZLE_HASH: TreeHash = TreeHash::new(hex!(
 
 #[cfg(test)]
 mod tests {
-    use clvm_traits::{clvm_list, match_list};
+    use clvm_traits::match_list;
     use clvmr::{
         run_program,
         serde::{node_from_bytes, node_to_bytes},
@@ -133,13 +193,166 @@ mod tests {
 
     use super::*;
 
-    use crate::assert_puzzle_hash;
+    use chia_bls::SecretKey;
+    use clvm_utils::tree_hash;
+
+    use crate::{assert_puzzle_hash, singleton::SingletonArgs};
 
     #[test]
     fn puzzle_hashes() {
         assert_puzzle_hash!(DID_INNER_PUZZLE => DID_INNER_PUZZLE_HASH);
     }
 
+    #[test]
+    fn curry_tree_hash() {
+        let a = &mut Allocator::new();
+        let inner_puzzle = node_from_bytes(a, &[0x80]).unwrap();
+        let metadata = clvm_list!("name", 42).to_clvm(a).unwrap();
+        let launcher_id = Bytes32::new([1; 32]);
+        let recovery_list_hash = Some(did_recovery_list_hash(&[Bytes32::new([2; 32])]));
+
+        let puzzle = node_from_bytes(a, &DID_INNER_PUZZLE).unwrap();
+        let curried = CurriedProgram {
+            program: puzzle,
+            args: DidArgs::new(
+                inner_puzzle,
+                recovery_list_hash,
+                1,
+                SingletonStruct::new(launcher_id),
+                metadata,
+            ),
+        }
+        .to_clvm(a)
+        .unwrap();
+
+        assert_eq!(
+            DidArgs::curry_tree_hash(
+                tree_hash(a, inner_puzzle),
+                recovery_list_hash,
+                1,
+                SingletonStruct::new(launcher_id),
+                tree_hash(a, metadata),
+            ),
+            tree_hash(a, curried)
+        );
+    }
+
+    #[test]
+    fn recovery_list_hash() {
+        // an empty list hashes like nil
+        assert_eq!(
+            did_recovery_list_hash(&[]),
+            Bytes32::new(hex!(
+                "4bf5122f344554c53bde2ebb8cd2b7e3d1600ad631c385a5d7cce23c7785459a"
+            ))
+        );
+
+        let a = &mut Allocator::new();
+        let recovery_list = vec![Bytes32::new([1; 32]), Bytes32::new([2; 32])];
+        let ptr = recovery_list.to_clvm(a).unwrap();
+        assert_eq!(
+            did_recovery_list_hash(&recovery_list),
+            Bytes32::from(tree_hash(a, ptr))
+        );
+    }
+
+    // runs the DID puzzle in recovery mode, with one approving DID, and
+    // checks that it asserts the announcement of the recovery message
+    #[test]
+    fn did_recovery() {
+        let a = &mut Allocator::new();
+        let attestor_id = Bytes32::new([2; 32]);
+        let my_id = Bytes32::new([3; 32]);
+        let new_inner_puzzle_hash = Bytes32::new([4; 32]);
+        let public_key = SecretKey::from_seed(&[5; 32]).public_key();
+        let recovery_coin = CoinProof {
+            parent_coin_info: Bytes32::new([6; 32]),
+            inner_puzzle_hash: Bytes32::new([7; 32]),
+            amount: 1,
+        };
+
+        let puzzle = node_from_bytes(a, &DID_INNER_PUZZLE).unwrap();
+        let curried = CurriedProgram {
+            program: puzzle,
+            args: DidArgs::new(
+                1,
+                Some(did_recovery_list_hash(&[attestor_id])),
+                1,
+                SingletonStruct::new(Bytes32::new([1; 32])),
+                (),
+            ),
+        }
+        .to_clvm(a)
+        .unwrap();
+        let solution = DidSolution::<()>::Recover(Box::new(DidRecoverySolution {
+            amount: 1,
+            new_inner_puzzle_hash,
+            recovery_coins: vec![recovery_coin],
+            public_key,
+            recovery_list_reveal: vec![attestor_id],
+            my_id,
+        }))
+        .to_clvm(a)
+        .unwrap();
+
+        let output = run_program(a, &ChiaDialect::new(0), curried, solution, u64::MAX)
+            .expect("could not run did puzzle and solution")
+            .1;
+
+        let attestor_coin_id = Coin::new(
+            recovery_coin.parent_coin_info,
+            SingletonArgs::curry_tree_hash(attestor_id, recovery_coin.inner_puzzle_hash.into())
+                .into(),
+            recovery_coin.amount.into(),
+        )
+        .coin_id();
+        let message = DidRecoveryMessage::new(my_id, new_inner_puzzle_hash, public_key);
+        let expected = clvm_list!(
+            clvm_list!(73, 1),
+            clvm_list!(
+                51,
+                new_inner_puzzle_hash,
+                1,
+                clvm_list!(new_inner_puzzle_hash)
+            ),
+            clvm_list!(70, my_id),
+            clvm_list!(61, message.announcement_id(attestor_coin_id)),
+            clvm_list!(49, public_key, new_inner_puzzle_hash)
+        )
+        .to_clvm(a)
+        .unwrap();
+        assert_eq!(
+            hex::encode(node_to_bytes(a, output).unwrap()),
+            hex::encode(node_to_bytes(a, expected).unwrap())
+        );
+
+        // the message coin's puzzle makes the announcement the DID asserts
+        let message_puzzle = message.to_clvm(a).unwrap();
+        let nil = a.nil();
+        let conditions = run_program(a, &ChiaDialect::new(0), message_puzzle, nil, u64::MAX)
+            .unwrap()
+            .1;
+        let expected = clvm_list!(
+            clvm_list!(60, my_id),
+            clvm_list!(49, public_key, new_inner_puzzle_hash)
+        )
+        .to_clvm(a)
+        .unwrap();
+        assert_eq!(
+            node_to_bytes(a, conditions).unwrap(),
+            node_to_bytes(a, expected).unwrap()
+        );
+        assert_eq!(
+            message.coin_id(attestor_coin_id),
+            Coin::new(
+                attestor_coin_id,
+                tree_hash(a, message_puzzle).into(),
+                Amount::ZERO
+            )
+            .coin_id()
+        );
+    }
+
     #[test]
     fn did_solution() {
         let a = &mut Allocator::new();
@@ -0,0 +1,224 @@
+//! Puzzle hashes of the curried standard puzzles, computed directly from the hashes of their
+//! arguments with [`curry_tree_hash`], without allocating the puzzles themselves.
+//!
+//! Every function here is checked against the tree hash of the real curried puzzle, so the
+//! shortcuts can't drift from the puzzles they describe.
+
+use chia_bls::PublicKey;
+use chia_protocol::Bytes32;
+use chia_sha2::Sha256;
+use clvm_utils::{curry_tree_hash, tree_hash_atom, tree_hash_pair, ToTreeHash, TreeHash};
+
+use crate::{
+    cat::CAT_PUZZLE_HASH,
+    offer::{NotarizedPayment, SETTLEMENT_PAYMENTS_PUZZLE_HASH},
+    singleton::{SINGLETON_LAUNCHER_PUZZLE_HASH, SINGLETON_TOP_LAYER_PUZZLE_HASH},
+    standard::STANDARD_PUZZLE_HASH,
+};
+
+/// The puzzle hash of the [standard transaction](https://chialisp.com/standard-transactions)
+/// puzzle, curried with the given synthetic public key.
+pub fn puzzle_hash_for_synthetic_pk(synthetic_key: &PublicKey) -> TreeHash {
+    curry_tree_hash(
+        STANDARD_PUZZLE_HASH,
+        &[tree_hash_atom(&synthetic_key.to_bytes())],
+    )
+}
+
+/// The puzzle hash of a [CAT2](https://chialisp.com/cats) with the given asset id (TAIL hash),
+/// wrapping an inner puzzle with the given hash.
+pub fn cat_puzzle_hash(asset_id: Bytes32, inner_puzzle_hash: TreeHash) -> TreeHash {
+    curry_tree_hash(
+        CAT_PUZZLE_HASH,
+        &[
+            tree_hash_atom(&CAT_PUZZLE_HASH.to_bytes()),
+            tree_hash_atom(&asset_id),
+            inner_puzzle_hash,
+        ],
+    )
+}
+
+/// The hash of the singleton struct `(mod_hash . (launcher_id . launcher_puzzle_hash))`.
+pub fn singleton_struct_hash(launcher_id: Bytes32) -> TreeHash {
+    tree_hash_pair(
+        tree_hash_atom(&SINGLETON_TOP_LAYER_PUZZLE_HASH.to_bytes()),
+        tree_hash_pair(
+            tree_hash_atom(&launcher_id),
+            tree_hash_atom(&SINGLETON_LAUNCHER_PUZZLE_HASH.to_bytes()),
+        ),
+    )
+}
+
+/// The puzzle hash of a [singleton](https://chialisp.com/singletons) with the given launcher id,
+/// wrapping an inner puzzle with the given hash.
+pub fn singleton_puzzle_hash(launcher_id: Bytes32, inner_puzzle_hash: TreeHash) -> TreeHash {
+    curry_tree_hash(
+        SINGLETON_TOP_LAYER_PUZZLE_HASH,
+        &[singleton_struct_hash(launcher_id), inner_puzzle_hash],
+    )
+}
+
+/// The puzzle hash that offered coins are sent to. This is the settlement payments puzzle
+/// itself for XCH, or the settlement payments puzzle wrapped in a CAT for the given asset id.
+pub fn settlement_payments_puzzle_hash(asset_id: Option<Bytes32>) -> TreeHash {
+    match asset_id {
+        Some(asset_id) => cat_puzzle_hash(asset_id, SETTLEMENT_PAYMENTS_PUZZLE_HASH),
+        None => SETTLEMENT_PAYMENTS_PUZZLE_HASH,
+    }
+}
+
+/// The id of the puzzle announcement made when the settlement payments coin with the given
+/// (outer) puzzle hash pays out the notarized payment. The other side of an offer asserts this
+/// announcement, which is how it's guaranteed to be paid.
+pub fn notarized_payment_announcement_id(
+    settlement_puzzle_hash: TreeHash,
+    notarized_payment: &NotarizedPayment,
+) -> Bytes32 {
+    let mut hasher = Sha256::new();
+    hasher.update(settlement_puzzle_hash);
+    hasher.update(notarized_payment.tree_hash());
+    Bytes32::new(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use clvm_traits::{FromClvm, ToClvm};
+    use clvm_utils::{tree_hash, CurriedProgram};
+    use clvmr::{run_program, serde::node_from_bytes, Allocator, ChiaDialect, NodePtr};
+    use rstest::rstest;
+
+    use super::*;
+
+    use crate::{
+        cat::{CatArgs, CAT_PUZZLE},
+        offer::{Payment, SettlementPaymentsSolution, SETTLEMENT_PAYMENTS_PUZZLE},
+        singleton::{SingletonArgs, SingletonStruct, SINGLETON_TOP_LAYER_PUZZLE},
+        standard::{StandardArgs, STANDARD_PUZZLE},
+    };
+
+    fn standard_puzzle(a: &mut Allocator, synthetic_key: PublicKey) -> NodePtr {
+        let program = node_from_bytes(a, &STANDARD_PUZZLE).unwrap();
+        CurriedProgram {
+            program,
+            args: StandardArgs::new(synthetic_key),
+        }
+        .to_clvm(a)
+        .unwrap()
+    }
+
+    fn test_key(seed: u8) -> PublicKey {
+        chia_bls::SecretKey::from_seed(&[seed; 32]).public_key()
+    }
+
+    #[rstest]
+    fn test_puzzle_hash_for_synthetic_pk(#[values(0, 1, 2)] seed: u8) {
+        let synthetic_key = test_key(seed);
+
+        let a = &mut Allocator::new();
+        let puzzle = standard_puzzle(a, synthetic_key);
+
+        assert_eq!(
+            puzzle_hash_for_synthetic_pk(&synthetic_key),
+            tree_hash(a, puzzle)
+        );
+        assert_eq!(
+            puzzle_hash_for_synthetic_pk(&synthetic_key),
+            StandardArgs::curry_tree_hash(synthetic_key)
+        );
+    }
+
+    #[rstest]
+    fn test_cat_puzzle_hash(#[values(0, 1, 2)] seed: u8) {
+        let asset_id = Bytes32::new([seed; 32]);
+
+        let a = &mut Allocator::new();
+        let inner_puzzle = standard_puzzle(a, test_key(seed));
+        let program = node_from_bytes(a, &CAT_PUZZLE).unwrap();
+        let puzzle = CurriedProgram {
+            program,
+            args: CatArgs::new(asset_id, inner_puzzle),
+        }
+        .to_clvm(a)
+        .unwrap();
+
+        assert_eq!(
+            cat_puzzle_hash(asset_id, tree_hash(a, inner_puzzle)),
+            tree_hash(a, puzzle)
+        );
+    }
+
+    #[rstest]
+    fn test_singleton_puzzle_hash(#[values(0, 1, 2)] seed: u8) {
+        let launcher_id = Bytes32::new([seed; 32]);
+
+        let a = &mut Allocator::new();
+        let singleton_struct = SingletonStruct::new(launcher_id).to_clvm(a).unwrap();
+        assert_eq!(
+            singleton_struct_hash(launcher_id),
+            tree_hash(a, singleton_struct)
+        );
+
+        let inner_puzzle = standard_puzzle(a, test_key(seed));
+        let program = node_from_bytes(a, &SINGLETON_TOP_LAYER_PUZZLE).unwrap();
+        let puzzle = CurriedProgram {
+            program,
+            args: SingletonArgs::new(launcher_id, inner_puzzle),
+        }
+        .to_clvm(a)
+        .unwrap();
+
+        assert_eq!(
+            singleton_puzzle_hash(launcher_id, tree_hash(a, inner_puzzle)),
+            tree_hash(a, puzzle)
+        );
+    }
+
+    #[rstest]
+    fn test_settlement_payments(
+        #[values(None, Some(Bytes32::new([7; 32])))] asset_id: Option<Bytes32>,
+    ) {
+        let a = &mut Allocator::new();
+        let settlement = node_from_bytes(a, &SETTLEMENT_PAYMENTS_PUZZLE).unwrap();
+
+        let puzzle = match asset_id {
+            Some(asset_id) => {
+                let program = node_from_bytes(a, &CAT_PUZZLE).unwrap();
+                CurriedProgram {
+                    program,
+                    args: CatArgs::new(asset_id, settlement),
+                }
+                .to_clvm(a)
+                .unwrap()
+            }
+            None => settlement,
+        };
+        let settlement_puzzle_hash = settlement_payments_puzzle_hash(asset_id);
+        assert_eq!(settlement_puzzle_hash, tree_hash(a, puzzle));
+
+        // The settlement payments puzzle announces the tree hash of each notarized payment.
+        let notarized_payment = NotarizedPayment {
+            nonce: Bytes32::new([1; 32]),
+            payments: vec![Payment::new(Bytes32::new([2; 32]), 1000)],
+        };
+        let solution = SettlementPaymentsSolution {
+            notarized_payments: vec![notarized_payment.clone()],
+        }
+        .to_clvm(a)
+        .unwrap();
+        let output = run_program(a, &ChiaDialect::new(0), settlement, solution, u64::MAX)
+            .expect("could not run settlement payments puzzle")
+            .1;
+
+        let conditions = Vec::<NodePtr>::from_clvm(a, output).unwrap();
+        let (opcode, (message, ())) = <(u8, (Bytes32, ()))>::from_clvm(a, conditions[0]).unwrap();
+        assert_eq!(opcode, 62);
+
+        let mut hasher = Sha256::new();
+        hasher.update(tree_hash(a, puzzle));
+        hasher.update(message);
+        assert_eq!(
+            notarized_payment_announcement_id(settlement_puzzle_hash, &notarized_payment),
+            Bytes32::new(hasher.finalize())
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use std::str::FromStr;
 
-use rcgen::{CertificateParams, DistinguishedName, DnType, Ia5String, KeyPair, SanType};
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DistinguishedName, DnType, Ia5String, IsCa,
+    KeyPair, SanType,
+};
 use rsa::{
     pkcs8::{EncodePrivateKey, LineEnding},
     RsaPrivateKey,
@@ -20,33 +23,75 @@ pub struct ChiaCertificate {
 }
 
 impl ChiaCertificate {
+    /// Generates a certificate signed by the public Chia CA, which is what
+    /// full nodes and wallets use to connect to each other.
     pub fn generate() -> Result<ChiaCertificate> {
-        let mut rng = rand::thread_rng();
+        generate_signed_by(&CHIA_CA, &CHIA_CA_KEY_PAIR)
+    }
 
-        let key = RsaPrivateKey::new(&mut rng, 2048)?;
-        let key_pem = key.to_pkcs8_pem(LineEnding::default())?.to_string();
+    /// Generates a certificate signed by `ca`, usually a private CA from
+    /// [`ChiaCertificate::generate_ca()`].
+    pub fn generate_with_ca(ca: &ChiaCertificate) -> Result<ChiaCertificate> {
+        let ca_key_pair = KeyPair::from_pem(&ca.key_pem)?;
+        let ca_cert =
+            CertificateParams::from_ca_cert_pem(&ca.cert_pem)?.self_signed(&ca_key_pair)?;
+        generate_signed_by(&ca_cert, &ca_key_pair)
+    }
+
+    /// Generates a new private CA. Each installation has its own, and the
+    /// services that only talk to each other (such as the farmer and the
+    /// harvester) only accept certificates signed by it.
+    pub fn generate_ca() -> Result<ChiaCertificate> {
+        let (key_pem, key_pair) = generate_key()?;
 
         let mut params = CertificateParams::default();
+        params.distinguished_name = distinguished_name("Chia CA");
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        set_validity(&mut params)?;
 
-        let mut subject = DistinguishedName::new();
-        subject.push(DnType::CommonName, "Chia");
-        subject.push(DnType::OrganizationName, "Chia");
-        subject.push(DnType::OrganizationalUnitName, "Organic Farming Division");
-        params.distinguished_name = subject;
+        let cert_pem = params.self_signed(&key_pair)?.pem();
 
-        params.subject_alt_names = vec![SanType::DnsName(Ia5String::from_str("chia.net")?)];
+        Ok(ChiaCertificate { cert_pem, key_pem })
+    }
+}
 
-        params.not_before = OffsetDateTime::now_utc() - Duration::DAY;
-        params.not_after = PrimitiveDateTime::new(
-            Date::from_calendar_date(2100, Month::August, 2)?,
-            Time::MIDNIGHT,
-        )
-        .assume_utc();
+fn generate_key() -> Result<(String, KeyPair)> {
+    let mut rng = rand::thread_rng();
 
-        let key_pair = KeyPair::from_pem_and_sign_algo(&key_pem, &rcgen::PKCS_RSA_SHA256)?;
-        let cert = params.signed_by(&key_pair, &CHIA_CA, &CHIA_CA_KEY_PAIR)?;
-        let cert_pem = cert.pem();
+    let key = RsaPrivateKey::new(&mut rng, 2048)?;
+    let key_pem = key.to_pkcs8_pem(LineEnding::default())?.to_string();
+    let key_pair = KeyPair::from_pem_and_sign_algo(&key_pem, &rcgen::PKCS_RSA_SHA256)?;
 
-        Ok(ChiaCertificate { cert_pem, key_pem })
-    }
+    Ok((key_pem, key_pair))
+}
+
+fn distinguished_name(common_name: &str) -> DistinguishedName {
+    let mut subject = DistinguishedName::new();
+    subject.push(DnType::CommonName, common_name);
+    subject.push(DnType::OrganizationName, "Chia");
+    subject.push(DnType::OrganizationalUnitName, "Organic Farming Division");
+    subject
+}
+
+fn set_validity(params: &mut CertificateParams) -> Result<()> {
+    params.not_before = OffsetDateTime::now_utc() - Duration::DAY;
+    params.not_after = PrimitiveDateTime::new(
+        Date::from_calendar_date(2100, Month::August, 2)?,
+        Time::MIDNIGHT,
+    )
+    .assume_utc();
+    Ok(())
+}
+
+fn generate_signed_by(ca_cert: &Certificate, ca_key_pair: &KeyPair) -> Result<ChiaCertificate> {
+    let (key_pem, key_pair) = generate_key()?;
+
+    let mut params = CertificateParams::default();
+    params.distinguished_name = distinguished_name("Chia");
+    params.subject_alt_names = vec![SanType::DnsName(Ia5String::from_str("chia.net")?)];
+    set_validity(&mut params)?;
+
+    let cert_pem = params.signed_by(&key_pair, ca_cert, ca_key_pair)?.pem();
+
+    Ok(ChiaCertificate { cert_pem, key_pem })
 }
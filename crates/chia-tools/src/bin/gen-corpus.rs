@@ -142,7 +142,7 @@ fn main() {
                             let coin = Coin {
                                 parent_coin_info,
                                 puzzle_hash,
-                                amount,
+                                amount: amount.into(),
                             };
                             let spend = CoinSpend {
                                 coin,
@@ -0,0 +1,130 @@
+use crate::chia_error::Result;
+use crate::Streamable;
+use chia_sha2::Sha256;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+/// A Streamable value along with its hash, which is computed the first time
+/// it's needed and then reused. The value can only be accessed immutably, so
+/// the cached hash can't go stale. To change the value, take it out with
+/// `into_inner()` and wrap the new value again.
+///
+/// It's serialized and hashed exactly like the wrapped value.
+pub struct CachedStreamableHash<T> {
+    value: T,
+    hash: OnceLock<[u8; 32]>,
+}
+
+impl<T: Streamable> CachedStreamableHash<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            hash: OnceLock::new(),
+        }
+    }
+
+    /// The same as `Streamable::hash()` of the wrapped value.
+    pub fn hash(&self) -> [u8; 32] {
+        *self.hash.get_or_init(|| self.value.hash())
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Streamable> From<T> for CachedStreamableHash<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> Deref for CachedStreamableHash<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Clone> Clone for CachedStreamableHash<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            hash: self.hash.clone(),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for CachedStreamableHash<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for CachedStreamableHash<T> {}
+
+impl<T: Hash> Hash for CachedStreamableHash<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CachedStreamableHash<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: Streamable> Streamable for CachedStreamableHash<T> {
+    fn update_digest(&self, digest: &mut Sha256) {
+        self.value.update_digest(digest);
+    }
+
+    fn stream(&self, out: &mut Vec<u8>) -> Result<()> {
+        self.value.stream(out)
+    }
+
+    fn parse<const TRUSTED: bool>(input: &mut Cursor<&[u8]>) -> Result<Self> {
+        Ok(Self::new(T::parse::<TRUSTED>(input)?))
+    }
+
+    fn hash(&self) -> [u8; 32] {
+        CachedStreamableHash::hash(self)
+    }
+}
+
+/// The instance attribute the python `get_hash()` of types declared with
+/// `#[streamable(cached_hash)]` stores the hash in. Python objects are
+/// immutable, so the hash can't go stale, and objects created from them (e.g.
+/// by `replace()`) start out without it. The rust struct doesn't hold the
+/// cache, rust code can wrap the value in `CachedStreamableHash` instead.
+pub const HASH_CACHE_ATTR: &str = "_cached_hash";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_streamable_hash() {
+        let value = vec![1_u32, 2, 3];
+        let cached = CachedStreamableHash::new(value.clone());
+        assert_eq!(cached.hash.get(), None);
+        assert_eq!(cached.hash(), Streamable::hash(&value));
+        assert_eq!(cached.hash.get(), Some(&Streamable::hash(&value)));
+        assert_eq!(Streamable::hash(&cached), Streamable::hash(&value));
+
+        // it's serialized like the wrapped value
+        let bytes = cached.to_bytes().unwrap();
+        assert_eq!(bytes, value.to_bytes().unwrap());
+        let parsed = CachedStreamableHash::<Vec<u32>>::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed, cached);
+        assert_eq!(parsed.hash.get(), None);
+
+        assert_eq!(*cached, value);
+        assert_eq!(cached.into_inner(), value);
+    }
+}
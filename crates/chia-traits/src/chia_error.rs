@@ -1,3 +1,4 @@
+use crate::parse_limits::ParseLimit;
 use thiserror::Error;
 
 #[derive(Debug, PartialEq, Eq, Clone, Error)]
@@ -18,15 +19,61 @@ pub enum Error {
     InvalidEnum,
     #[error("invalid CLVM serialization")]
     InvalidClvm,
+    #[error("parse limit {0} exceeded")]
+    LimitExceeded(ParseLimit),
     #[error("{0}")]
     Custom(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+// All errors raised to python when parsing or serializing streamable objects
+// derive from StreamableError (which in turn derives from ValueError), so
+// existing code catching ValueError keeps working.
+#[cfg(feature = "py-bindings")]
+pyo3::create_exception!(
+    chia_rs,
+    StreamableError,
+    pyo3::exceptions::PyValueError,
+    "Failed to parse or serialize a streamable object"
+);
+#[cfg(feature = "py-bindings")]
+pyo3::create_exception!(
+    chia_rs,
+    TruncatedError,
+    StreamableError,
+    "The input ended before the object was complete"
+);
+#[cfg(feature = "py-bindings")]
+pyo3::create_exception!(
+    chia_rs,
+    InvalidEnumError,
+    StreamableError,
+    "The input has an invalid enum value"
+);
+#[cfg(feature = "py-bindings")]
+pyo3::create_exception!(
+    chia_rs,
+    TooLargeError,
+    StreamableError,
+    "The input, or a sequence in it, is too large"
+);
+
 #[cfg(feature = "py-bindings")]
 impl From<Error> for pyo3::PyErr {
     fn from(err: Error) -> pyo3::PyErr {
-        pyo3::exceptions::PyValueError::new_err(err.to_string())
+        let message = err.to_string();
+        match err {
+            Error::EndOfBuffer => TruncatedError::new_err(message),
+            Error::InvalidEnum => InvalidEnumError::new_err(message),
+            Error::InputTooLarge | Error::SequenceTooLarge | Error::LimitExceeded(_) => {
+                TooLargeError::new_err(message)
+            }
+            Error::InvalidBool
+            | Error::InvalidOptional
+            | Error::InvalidString
+            | Error::InvalidClvm
+            | Error::Custom(_) => StreamableError::new_err(message),
+        }
     }
 }
@@ -1,13 +1,157 @@
-use pyo3::exceptions::PyValueError;
-use pyo3::types::PyAnyMethods;
+use pyo3::exceptions::{PyKeyError, PyTypeError, PyValueError};
+use pyo3::types::{PyAnyMethods, PyDict, PyDictMethods, PyString, PyStringMethods};
 use pyo3::Bound;
 use pyo3::PyAny;
+use pyo3::PyErr;
 use pyo3::PyResult;
 
+/// A JSON document parsed in Rust, see `FromJsonDict::from_json_value()`.
+pub type JsonValue = serde_json::Value;
+
 pub trait FromJsonDict {
     fn from_json_dict(o: &Bound<'_, PyAny>) -> PyResult<Self>
     where
         Self: Sized;
+
+    /// Like `from_json_dict()`, but parses JSON that was decoded in Rust,
+    /// without going through python objects. This backs `from_json_bytes()`.
+    /// Types that don't implement it can only be parsed by `from_json_dict()`.
+    fn from_json_value(_v: &JsonValue) -> PyResult<Self>
+    where
+        Self: Sized,
+    {
+        Err(PyTypeError::new_err(format!(
+            "{} does not support from_json_bytes(), use from_json_dict() instead",
+            std::any::type_name::<Self>()
+        )))
+    }
+
+    /// Called when the field of this type is missing from the dict, with the
+    /// `KeyError` that raised. Only optional fields may be left out.
+    fn from_missing_field(err: PyErr) -> PyResult<Self>
+    where
+        Self: Sized,
+    {
+        Err(err)
+    }
+}
+
+/// Parses the field `name` of the dict `o`. Like in python chia-blockchain's
+/// streamable, an optional field may be either null or left out entirely.
+pub fn field_from_json_dict<T: FromJsonDict>(o: &Bound<'_, PyAny>, name: &str) -> PyResult<T> {
+    match o.get_item(name) {
+        Ok(v) => T::from_json_dict(&v),
+        Err(err) if err.is_instance_of::<PyKeyError>(o.py()) => T::from_missing_field(err),
+        Err(err) => Err(err),
+    }
+}
+
+/// The `from_json_value()` counterpart of `field_from_json_dict()`.
+pub fn field_from_json_value<T: FromJsonDict>(o: &JsonValue, name: &str) -> PyResult<T> {
+    let JsonValue::Object(map) = o else {
+        return Err(PyTypeError::new_err(format!(
+            "expected a JSON object, got: {o}"
+        )));
+    };
+    match map.get(name) {
+        Some(v) => T::from_json_value(v),
+        None => T::from_missing_field(PyKeyError::new_err(name.to_string())),
+    }
+}
+
+/// Parses a `#[streamable(json_map)]` field. Both the JSON object that
+/// `to_json_dict()` produces and the plain list of `[key, value]` pairs are
+/// accepted. The pairs of an object are sorted by key, the pairs of a list are
+/// kept in the order they're listed in.
+pub fn from_json_map<K, V>(o: &Bound<'_, PyAny>) -> PyResult<Vec<(K, V)>>
+where
+    K: FromJsonDict + Ord,
+    V: FromJsonDict,
+{
+    let Ok(dict) = o.downcast::<PyDict>() else {
+        return <Vec<(K, V)>>::from_json_dict(o);
+    };
+    let mut ret = Vec::with_capacity(dict.len());
+    for (key, value) in dict.iter() {
+        ret.push((K::from_json_dict(&key)?, V::from_json_dict(&value)?));
+    }
+    sort_json_map(ret)
+}
+
+/// The `from_json_value()` counterpart of `from_json_map()`.
+pub fn from_json_map_value<K, V>(v: &JsonValue) -> PyResult<Vec<(K, V)>>
+where
+    K: FromJsonDict + Ord,
+    V: FromJsonDict,
+{
+    let JsonValue::Object(map) = v else {
+        return <Vec<(K, V)>>::from_json_value(v);
+    };
+    let mut ret = Vec::with_capacity(map.len());
+    for (key, value) in map {
+        ret.push((
+            K::from_json_value(&JsonValue::String(key.clone()))?,
+            V::from_json_value(value)?,
+        ));
+    }
+    sort_json_map(ret)
+}
+
+// different strings may parse to the same key, e.g. upper and lower case hex
+fn sort_json_map<K: Ord, V>(mut pairs: Vec<(K, V)>) -> PyResult<Vec<(K, V)>> {
+    pairs.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+    if pairs.windows(2).any(|w| w[0].0 == w[1].0) {
+        return Err(PyValueError::new_err("duplicate key in json_map"));
+    }
+    Ok(pairs)
+}
+
+/// Parses the `#[streamable(json_map)]` field `name` of the dict `o`.
+pub fn json_map_field_from_json_dict<K, V>(
+    o: &Bound<'_, PyAny>,
+    name: &str,
+) -> PyResult<Vec<(K, V)>>
+where
+    K: FromJsonDict + Ord,
+    V: FromJsonDict,
+{
+    from_json_map(&o.get_item(name)?)
+}
+
+/// The `from_json_value()` counterpart of `json_map_field_from_json_dict()`.
+pub fn json_map_field_from_json_value<K, V>(o: &JsonValue, name: &str) -> PyResult<Vec<(K, V)>>
+where
+    K: FromJsonDict + Ord,
+    V: FromJsonDict,
+{
+    let JsonValue::Object(map) = o else {
+        return Err(PyTypeError::new_err(format!(
+            "expected a JSON object, got: {o}"
+        )));
+    };
+    match map.get(name) {
+        Some(v) => from_json_map_value(v),
+        None => Err(PyKeyError::new_err(name.to_string())),
+    }
+}
+
+/// Parses a JSON document into `T`, entirely in Rust.
+pub fn from_json_bytes<T: FromJsonDict>(data: &[u8]) -> PyResult<T> {
+    let value: JsonValue = serde_json::from_slice(data)
+        .map_err(|e| PyValueError::new_err(format!("invalid JSON: {e}")))?;
+    T::from_json_value(&value)
+}
+
+/// The string of a JSON value that's expected to be a string.
+pub fn json_str(v: &JsonValue) -> PyResult<&str> {
+    v.as_str()
+        .ok_or_else(|| PyTypeError::new_err(format!("expected a string, got: {v}")))
+}
+
+/// The elements of a JSON value that's expected to be an array.
+pub fn json_array(v: &JsonValue) -> PyResult<&Vec<JsonValue>> {
+    v.as_array()
+        .ok_or_else(|| PyTypeError::new_err(format!("expected an array, got: {v}")))
 }
 
 impl<T> FromJsonDict for Option<T>
@@ -20,30 +164,94 @@ where
         }
         Ok(Some(<T as FromJsonDict>::from_json_dict(o)?))
     }
+
+    fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+        if v.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(<T as FromJsonDict>::from_json_value(v)?))
+    }
+
+    fn from_missing_field(_err: PyErr) -> PyResult<Self> {
+        Ok(None)
+    }
 }
 
-macro_rules! from_json_primitive {
+// RPC servers may send integers either as numbers or, to avoid losing
+// precision in JavaScript, as decimal strings. Both are accepted. Floats are
+// not, and neither are strings in any other base.
+macro_rules! from_json_int {
     ($t:ty) => {
         impl $crate::from_json_dict::FromJsonDict for $t {
             fn from_json_dict(o: &Bound<'_, PyAny>) -> pyo3::PyResult<Self> {
-                o.extract()
+                match o.downcast::<PyString>() {
+                    Ok(s) => parse_decimal(s.to_str()?),
+                    Err(_) => o.extract(),
+                }
+            }
+
+            fn from_json_value(v: &JsonValue) -> pyo3::PyResult<Self> {
+                match v {
+                    JsonValue::String(s) => parse_decimal(s),
+                    // the number is kept as it was written, so this only
+                    // accepts integers
+                    JsonValue::Number(n) => n.to_string().parse().map_err(|_| {
+                        PyValueError::new_err(format!(
+                            "invalid integer for {}: {n}",
+                            stringify!($t)
+                        ))
+                    }),
+                    _ => Err(PyTypeError::new_err(format!(
+                        "expected an integer, got: {v}"
+                    ))),
+                }
             }
         }
     };
 }
 
-from_json_primitive!(bool);
-from_json_primitive!(u8);
-from_json_primitive!(i8);
-from_json_primitive!(u16);
-from_json_primitive!(i16);
-from_json_primitive!(u32);
-from_json_primitive!(i32);
-from_json_primitive!(u64);
-from_json_primitive!(i64);
-from_json_primitive!(u128);
-from_json_primitive!(i128);
-from_json_primitive!(String);
+fn parse_decimal<T: std::str::FromStr>(s: &str) -> PyResult<T> {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(PyValueError::new_err(format!(
+            "invalid integer string: \"{s}\""
+        )));
+    }
+    s.parse()
+        .map_err(|_| PyValueError::new_err(format!("integer out of range: {s}")))
+}
+
+impl FromJsonDict for bool {
+    fn from_json_dict(o: &Bound<'_, PyAny>) -> PyResult<Self> {
+        o.extract()
+    }
+
+    fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+        v.as_bool()
+            .ok_or_else(|| PyTypeError::new_err(format!("expected a bool, got: {v}")))
+    }
+}
+
+from_json_int!(u8);
+from_json_int!(i8);
+from_json_int!(u16);
+from_json_int!(i16);
+from_json_int!(u32);
+from_json_int!(i32);
+from_json_int!(u64);
+from_json_int!(i64);
+from_json_int!(u128);
+from_json_int!(i128);
+
+impl FromJsonDict for String {
+    fn from_json_dict(o: &Bound<'_, PyAny>) -> PyResult<Self> {
+        o.extract()
+    }
+
+    fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+        Ok(json_str(v)?.to_string())
+    }
+}
 
 impl<T> FromJsonDict for Vec<T>
 where
@@ -56,6 +264,13 @@ where
         }
         Ok(ret)
     }
+
+    fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+        json_array(v)?
+            .iter()
+            .map(<T as FromJsonDict>::from_json_value)
+            .collect()
+    }
 }
 
 impl<T, U> FromJsonDict for (T, U)
@@ -75,6 +290,19 @@ where
             <U as FromJsonDict>::from_json_dict(&o.get_item(1)?)?,
         ))
     }
+
+    fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+        let [t, u] = json_array(v)?.as_slice() else {
+            return Err(PyValueError::new_err(format!(
+                "expected 2 elements, got {}",
+                json_array(v)?.len()
+            )));
+        };
+        Ok((
+            <T as FromJsonDict>::from_json_value(t)?,
+            <U as FromJsonDict>::from_json_value(u)?,
+        ))
+    }
 }
 
 impl<T, U, V> FromJsonDict for (T, U, V)
@@ -96,4 +324,87 @@ where
             <V as FromJsonDict>::from_json_dict(&o.get_item(2)?)?,
         ))
     }
+
+    fn from_json_value(v: &JsonValue) -> PyResult<Self> {
+        let [t, u, w] = json_array(v)?.as_slice() else {
+            return Err(PyValueError::new_err(format!(
+                "expected 3 elements, got {}",
+                json_array(v)?.len()
+            )));
+        };
+        Ok((
+            <T as FromJsonDict>::from_json_value(t)?,
+            <U as FromJsonDict>::from_json_value(u)?,
+            <V as FromJsonDict>::from_json_value(w)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToJsonDict;
+    use pyo3::Python;
+
+    #[test]
+    fn test_optional_field() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let dict = PyDict::new(py);
+            dict.set_item("a", 1).unwrap();
+            dict.set_item("b", py.None()).unwrap();
+
+            // an optional field may be null, or left out
+            let a: Option<u32> = field_from_json_dict(&dict, "a").unwrap();
+            assert_eq!(a, Some(1));
+            let b: Option<u32> = field_from_json_dict(&dict, "b").unwrap();
+            assert_eq!(b, None);
+            let c: Option<u32> = field_from_json_dict(&dict, "c").unwrap();
+            assert_eq!(c, None);
+
+            // only optional fields may be left out
+            let err = field_from_json_dict::<u32>(&dict, "c").unwrap_err();
+            assert!(err.is_instance_of::<PyKeyError>(py));
+        });
+    }
+
+    #[test]
+    fn test_optional_field_value() {
+        let v: JsonValue = serde_json::from_str(r#"{"a": 1, "b": null}"#).unwrap();
+        assert_eq!(
+            field_from_json_value::<Option<u32>>(&v, "a").unwrap(),
+            Some(1)
+        );
+        assert_eq!(field_from_json_value::<Option<u32>>(&v, "b").unwrap(), None);
+        assert_eq!(field_from_json_value::<Option<u32>>(&v, "c").unwrap(), None);
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let err = field_from_json_value::<u32>(&v, "c").unwrap_err();
+            assert!(err.is_instance_of::<PyKeyError>(py));
+        });
+    }
+
+    #[test]
+    fn test_list_of_options() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let value = Some(vec![None, Some(0x1337_u16)]);
+            let list = value.to_json_dict(py).unwrap();
+            let list = list.bind(py);
+            // None items in a list are null, they can't be left out
+            assert_eq!(list.len().unwrap(), 2);
+            assert!(list.get_item(0).unwrap().is_none());
+            assert_eq!(list.get_item(1).unwrap().extract::<u16>().unwrap(), 0x1337);
+            assert_eq!(
+                <Option<Vec<Option<u16>>> as FromJsonDict>::from_json_dict(list).unwrap(),
+                value
+            );
+            assert!(
+                <Option<u16> as FromJsonDict>::from_json_dict(&py.None().into_bound(py))
+                    .unwrap()
+                    .is_none()
+            );
+        });
+    }
 }
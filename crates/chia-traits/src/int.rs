@@ -1,19 +1,36 @@
+use pyo3::intern;
+use pyo3::sync::GILOnceCell;
 use pyo3::{prelude::*, types::*};
 
 /// A custom to-python conversion trait that turns primitive integer types into
 /// the chia-blockchain fixed-width integer types (uint8, int8, etc.)
 pub trait ChiaToPython {
+    /// Getters of `#[streamable(cached_getters)]` types cache the python
+    /// object of fields of this type, see `cached_getter()`. This must only be
+    /// set for types whose python counterpart is immutable.
+    const CACHEABLE: bool = false;
+
     fn to_python<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>>;
+
+    /// The name of the python type `to_python()` returns, the way it's spelled
+    /// in the type stubs, e.g. `Optional[uint32]`.
+    fn py_type_name() -> String;
 }
 
 macro_rules! primitive_int {
     ($t:ty, $name:expr) => {
         impl ChiaToPython for $t {
             fn to_python<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
-                let int_module = PyModule::import(py, "chia_rs.sized_ints")?;
-                let ty = int_module.getattr($name)?;
+                // looking up the type once saves an import and attribute
+                // lookup for every field access
+                static TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+                let ty = TYPE.import(py, "chia_rs.sized_ints", $name)?;
                 ty.call1((self.into_pyobject(py)?.into_any(),))
             }
+
+            fn py_type_name() -> String {
+                $name.to_string()
+            }
         }
     };
 }
@@ -36,6 +53,10 @@ impl<T: ChiaToPython> ChiaToPython for Option<T> {
             None => Ok(py.None().into_bound(py)),
         }
     }
+
+    fn py_type_name() -> String {
+        format!("Optional[{}]", T::py_type_name())
+    }
 }
 
 impl<T: ChiaToPython> ChiaToPython for Vec<T> {
@@ -46,24 +67,40 @@ impl<T: ChiaToPython> ChiaToPython for Vec<T> {
         }
         Ok(ret.into_any())
     }
+
+    fn py_type_name() -> String {
+        format!("list[{}]", T::py_type_name())
+    }
 }
 
 impl ChiaToPython for bool {
     fn to_python<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         Ok(PyBool::new(py, *self).as_any().clone())
     }
+
+    fn py_type_name() -> String {
+        "bool".to_string()
+    }
 }
 
 impl ChiaToPython for String {
     fn to_python<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         Ok(PyString::new(py, self.as_str()).into_any())
     }
+
+    fn py_type_name() -> String {
+        "str".to_string()
+    }
 }
 
 impl<T: ChiaToPython, U: ChiaToPython> ChiaToPython for (T, U) {
     fn to_python<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
         Ok(PyTuple::new(py, [self.0.to_python(py)?, self.1.to_python(py)?])?.into_any())
     }
+
+    fn py_type_name() -> String {
+        format!("tuple[{}, {}]", T::py_type_name(), U::py_type_name())
+    }
 }
 
 impl<T: ChiaToPython, U: ChiaToPython, V: ChiaToPython> ChiaToPython for (T, U, V) {
@@ -78,4 +115,67 @@ impl<T: ChiaToPython, U: ChiaToPython, V: ChiaToPython> ChiaToPython for (T, U,
         )?
         .into_any())
     }
+
+    fn py_type_name() -> String {
+        format!(
+            "tuple[{}, {}, {}]",
+            T::py_type_name(),
+            U::py_type_name(),
+            V::py_type_name()
+        )
+    }
+}
+
+/// The instance attribute the getters of `#[streamable(cached_getters)]` types
+/// keep the python objects of their fields in, as a dict keyed by field name.
+/// Like the cached hash, it's left out of pickles.
+pub const GETTER_CACHE_ATTR: &str = "_cached_fields";
+
+/// Returns the python object of the field `name` of `obj`, from the cache in
+/// the instance `__dict__`. The first time, the field is converted by
+/// `convert` and cached. `name` should be an interned string, to keep the
+/// lookup from allocating.
+pub fn cached_getter<'py>(
+    obj: &Bound<'py, PyAny>,
+    name: &Bound<'py, PyString>,
+    convert: impl FnOnce() -> PyResult<Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = obj.py();
+    let dict = obj
+        .getattr(intern!(py, "__dict__"))?
+        .downcast_into::<PyDict>()?;
+    let cache = if let Some(cache) = dict.get_item(intern!(py, GETTER_CACHE_ATTR))? {
+        cache.downcast_into::<PyDict>()?
+    } else {
+        let cache = PyDict::new(py);
+        dict.set_item(intern!(py, GETTER_CACHE_ATTR), &cache)?;
+        cache
+    };
+    if let Some(value) = cache.get_item(name)? {
+        return Ok(value);
+    }
+    let value = convert()?;
+    cache.set_item(name, &value)?;
+    Ok(value)
+}
+
+// the python type get_hash() returns, see register_hash_type()
+static HASH_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+
+/// Sets the python type that `hash_to_python()` converts hashes to. The
+/// wheel registers `chia_rs.sized_bytes.bytes32` when it's loaded. Only the
+/// first registration takes effect.
+pub fn register_hash_type(py: Python<'_>, ty: &Bound<'_, PyType>) {
+    let _ = HASH_TYPE.set(py, ty.clone().unbind());
+}
+
+/// Converts a sha256 digest, e.g. the result of `get_hash()`, to the type
+/// registered by `register_hash_type()`. If no type has been registered, the
+/// digest is returned as plain `bytes`.
+pub fn hash_to_python<'a>(py: Python<'a>, digest: &[u8; 32]) -> PyResult<Bound<'a, PyAny>> {
+    let digest = PyBytes::new(py, digest);
+    match HASH_TYPE.get(py) {
+        Some(ty) => ty.bind(py).call1((digest,)),
+        None => Ok(digest.into_any()),
+    }
 }
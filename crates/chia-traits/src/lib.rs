@@ -1,5 +1,8 @@
+pub mod cached_hash;
 pub mod chia_error;
+pub mod parse_limits;
 pub mod streamable;
+pub mod streamable_diff;
 
 #[cfg(feature = "py-bindings")]
 pub mod from_json_dict;
@@ -9,9 +12,16 @@ pub use crate::from_json_dict::*;
 pub mod to_json_dict;
 #[cfg(feature = "py-bindings")]
 pub use crate::to_json_dict::*;
+#[cfg(feature = "serde")]
+pub mod wire_format;
+#[cfg(feature = "serde")]
+pub use crate::wire_format::*;
 
+pub use crate::cached_hash::*;
 pub use crate::chia_error::{Error, Result};
+pub use crate::parse_limits::*;
 pub use crate::streamable::*;
+pub use crate::streamable_diff::*;
 
 #[cfg(feature = "py-bindings")]
 pub mod int;
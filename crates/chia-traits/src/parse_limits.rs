@@ -0,0 +1,228 @@
+use crate::chia_error::{Error, Result};
+use std::cell::Cell;
+use std::fmt;
+
+/// Limits on the sizes claimed by the length prefixes of untrusted input.
+/// They are checked before anything is allocated for a collection, so they
+/// bound the memory spent on input that turns out to be invalid. The default
+/// limits don't restrict anything beyond what `from_bytes()` already does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// The maximum number of items in any one list.
+    pub max_vec_len: u32,
+    /// The maximum length of any one byte buffer or string.
+    pub max_bytes_len: u32,
+    /// The maximum sum of the sizes of all lists, byte buffers and strings.
+    /// A list of N items counts as N times the in-memory size of an item.
+    pub max_total_alloc: u64,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_vec_len: u32::MAX,
+            max_bytes_len: u32::MAX,
+            max_total_alloc: u64::MAX,
+        }
+    }
+}
+
+/// The limit that was exceeded, see `Error::LimitExceeded`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseLimit {
+    VecLen,
+    BytesLen,
+    TotalAlloc,
+}
+
+impl fmt::Display for ParseLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::VecLen => "max_vec_len",
+            Self::BytesLen => "max_bytes_len",
+            Self::TotalAlloc => "max_total_alloc",
+        })
+    }
+}
+
+// The limits of the parse_with_limits() call in progress on this thread, if
+// any, along with the number of bytes accounted for so far. Keeping them here
+// means the parse() implementations (including the derived ones) don't need
+// an extra parameter to pass them along.
+#[derive(Clone, Copy)]
+struct ActiveLimits {
+    limits: ParseLimits,
+    total_alloc: u64,
+}
+
+thread_local! {
+    static ACTIVE_LIMITS: Cell<Option<ActiveLimits>> = const { Cell::new(None) };
+}
+
+// Restores the previous limits when dropped, even if parsing panics.
+struct LimitsGuard(Option<ActiveLimits>);
+
+impl Drop for LimitsGuard {
+    fn drop(&mut self) {
+        ACTIVE_LIMITS.with(|active| active.set(self.0));
+    }
+}
+
+/// Runs `f` with `limits` enforced by the `parse()` implementations on this
+/// thread.
+pub fn with_parse_limits<R>(limits: ParseLimits, f: impl FnOnce() -> R) -> R {
+    let _guard = LimitsGuard(ACTIVE_LIMITS.with(|active| {
+        active.replace(Some(ActiveLimits {
+            limits,
+            total_alloc: 0,
+        }))
+    }));
+    f()
+}
+
+// Checks `len` against the limit selected by `max_len`, and adds `size` to the
+// total.
+fn reserve(limit: ParseLimit, max_len: fn(&ParseLimits) -> u32, len: u32, size: u64) -> Result<()> {
+    ACTIVE_LIMITS.with(|active| {
+        let Some(mut state) = active.get() else {
+            return Ok(());
+        };
+        if len > max_len(&state.limits) {
+            return Err(Error::LimitExceeded(limit));
+        }
+        state.total_alloc = state.total_alloc.saturating_add(size);
+        if state.total_alloc > state.limits.max_total_alloc {
+            return Err(Error::LimitExceeded(ParseLimit::TotalAlloc));
+        }
+        active.set(Some(state));
+        Ok(())
+    })
+}
+
+/// Must be called by `parse()` implementations with the length prefix of a
+/// list, before allocating it.
+pub fn reserve_vec(len: u32, item_size: usize) -> Result<()> {
+    reserve(
+        ParseLimit::VecLen,
+        |limits| limits.max_vec_len,
+        len,
+        u64::from(len).saturating_mul(item_size as u64),
+    )
+}
+
+/// Must be called by `parse()` implementations with the length prefix of a
+/// byte buffer or string, before allocating it.
+pub fn reserve_bytes(len: u32) -> Result<()> {
+    reserve(
+        ParseLimit::BytesLen,
+        |limits| limits.max_bytes_len,
+        len,
+        u64::from(len),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Streamable;
+    use rstest::rstest;
+
+    fn limits(max_vec_len: u32, max_bytes_len: u32, max_total_alloc: u64) -> ParseLimits {
+        ParseLimits {
+            max_vec_len,
+            max_bytes_len,
+            max_total_alloc,
+        }
+    }
+
+    #[test]
+    fn test_no_limits() {
+        assert_eq!(reserve_vec(u32::MAX, 1000), Ok(()));
+        assert_eq!(reserve_bytes(u32::MAX), Ok(()));
+    }
+
+    #[test]
+    fn test_default_limits() {
+        let input = [0, 0, 0, 3, 1, 2, 3];
+        assert_eq!(
+            Vec::<u8>::parse_with_limits(&input, ParseLimits::default()).unwrap(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            String::parse_with_limits(&input, ParseLimits::default()).unwrap(),
+            "\u{1}\u{2}\u{3}"
+        );
+    }
+
+    // the length prefixes claim (almost) 4 billion items, but there's no data
+    // after them
+    #[rstest]
+    #[case(limits(1000, u32::MAX, u64::MAX), ParseLimit::VecLen)]
+    #[case(limits(u32::MAX, u32::MAX, 1_000_000), ParseLimit::TotalAlloc)]
+    fn test_adversarial_vec(#[case] limits: ParseLimits, #[case] expected: ParseLimit) {
+        let input = [0xff, 0xff, 0xff, 0xff];
+        assert_eq!(
+            Vec::<u64>::parse_with_limits(&input, limits),
+            Err(Error::LimitExceeded(expected))
+        );
+        assert_eq!(
+            Vec::<Vec<u8>>::parse_with_limits(&input, limits),
+            Err(Error::LimitExceeded(expected))
+        );
+        // without limits, this fails once the input runs out
+        assert_eq!(Vec::<u64>::from_bytes(&input), Err(Error::EndOfBuffer));
+    }
+
+    #[rstest]
+    #[case(limits(u32::MAX, 1000, u64::MAX), ParseLimit::BytesLen)]
+    #[case(limits(u32::MAX, u32::MAX, 1000), ParseLimit::TotalAlloc)]
+    fn test_adversarial_string(#[case] limits: ParseLimits, #[case] expected: ParseLimit) {
+        let input = [0xff, 0xff, 0xff, 0xfe, b'a'];
+        assert_eq!(
+            String::parse_with_limits(&input, limits),
+            Err(Error::LimitExceeded(expected))
+        );
+    }
+
+    #[test]
+    fn test_nested_total_alloc() {
+        // a list of 3 lists of 2 bytes each
+        let input = [
+            0, 0, 0, 3, 0, 0, 0, 2, 1, 2, 0, 0, 0, 2, 3, 4, 0, 0, 0, 2, 5, 6,
+        ];
+        let outer_size = 3 * std::mem::size_of::<Vec<u8>>() as u64;
+        let expected = vec![vec![1, 2], vec![3, 4], vec![5, 6]];
+
+        assert_eq!(
+            Vec::<Vec<u8>>::parse_with_limits(&input, limits(3, 3, outer_size + 6)).unwrap(),
+            expected
+        );
+        assert_eq!(
+            Vec::<Vec<u8>>::parse_with_limits(&input, limits(3, 3, outer_size + 5)),
+            Err(Error::LimitExceeded(ParseLimit::TotalAlloc))
+        );
+        assert_eq!(
+            Vec::<Vec<u8>>::parse_with_limits(&input, limits(2, 3, u64::MAX)),
+            Err(Error::LimitExceeded(ParseLimit::VecLen))
+        );
+    }
+
+    #[test]
+    fn test_limits_are_restored() {
+        let input = [0, 0, 0, 2, 1, 2];
+        let strict = limits(1, 1, 1);
+        assert_eq!(
+            with_parse_limits(limits(10, 10, 100), || {
+                let inner = Vec::<u8>::parse_with_limits(&input, strict);
+                // the outer limits are back in effect afterwards
+                (inner, reserve_vec(10, 10), reserve_vec(10, 1))
+            }),
+            (
+                Err(Error::LimitExceeded(ParseLimit::VecLen)),
+                Ok(()),
+                Err(Error::LimitExceeded(ParseLimit::TotalAlloc))
+            )
+        );
+        assert_eq!(reserve_vec(u32::MAX, 1000), Ok(()));
+    }
+}
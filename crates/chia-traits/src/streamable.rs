@@ -1,4 +1,5 @@
 use crate::chia_error::{Error, Result};
+use crate::parse_limits::{reserve_bytes, reserve_vec, with_parse_limits, ParseLimits};
 use chia_sha2::Sha256;
 use std::io::Cursor;
 use std::mem;
@@ -66,6 +67,14 @@ pub trait Streamable {
             Err(Error::InputTooLarge)
         }
     }
+    /// Like `from_bytes()`, but fails as soon as a length prefix in the input
+    /// exceeds `limits`, before allocating anything for it.
+    fn parse_with_limits(bytes: &[u8], limits: ParseLimits) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        with_parse_limits(limits, || Self::from_bytes(bytes))
+    }
     fn hash(&self) -> [u8; 32] {
         let mut ctx = Sha256::new();
         self.update_digest(&mut ctx);
@@ -125,6 +134,7 @@ impl<T: Streamable> Streamable for Vec<T> {
 
     fn parse<const TRUSTED: bool>(input: &mut Cursor<&[u8]>) -> Result<Self> {
         let len = u32::parse::<TRUSTED>(input)?;
+        reserve_vec(len, mem::size_of::<T>())?;
 
         let mut ret = if mem::size_of::<T>() == 0 {
             Vec::<T>::new()
@@ -160,6 +170,7 @@ impl Streamable for String {
 
     fn parse<const TRUSTED: bool>(input: &mut Cursor<&[u8]>) -> Result<Self> {
         let len = u32::parse::<TRUSTED>(input)?;
+        reserve_bytes(len)?;
         Ok(String::from(
             std::str::from_utf8(read_bytes(input, len as usize)?)
                 .map_err(|_| Error::InvalidString)?,
@@ -522,6 +533,94 @@ fn test_parse_custom_tuple() {
     from_bytes::<TestTuple>(buf, TestTuple("baz".to_string(), 42));
 }
 
+#[cfg(test)]
+#[derive(Streamable, PartialEq, Debug)]
+struct LeadingOption {
+    a: Option<u32>,
+    b: u8,
+}
+
+#[cfg(test)]
+#[derive(Streamable, PartialEq, Debug)]
+struct MiddleOption {
+    a: u8,
+    b: Option<u64>,
+    c: u16,
+}
+
+#[cfg(test)]
+#[derive(Streamable, PartialEq, Debug)]
+struct TrailingOption {
+    a: u8,
+    b: Option<Vec<Option<u16>>>,
+}
+
+#[cfg(test)]
+fn round_trip<T: Streamable + std::fmt::Debug + PartialEq>(value: T, buf: &[u8]) {
+    assert_eq!(stream(&value), buf);
+    from_bytes::<T>(buf, value);
+}
+
+#[test]
+fn test_leading_option() {
+    round_trip(LeadingOption { a: None, b: 7 }, &[0, 7]);
+    round_trip(LeadingOption { a: Some(1), b: 7 }, &[1, 0, 0, 0, 1, 7]);
+}
+
+#[test]
+fn test_middle_option() {
+    round_trip(
+        MiddleOption {
+            a: 1,
+            b: None,
+            c: 2,
+        },
+        &[1, 0, 0, 2],
+    );
+    round_trip(
+        MiddleOption {
+            a: 1,
+            b: Some(3),
+            c: 2,
+        },
+        &[1, 1, 0, 0, 0, 0, 0, 0, 0, 3, 0, 2],
+    );
+}
+
+#[test]
+fn test_trailing_option() {
+    round_trip(TrailingOption { a: 1, b: None }, &[1, 0]);
+    round_trip(
+        TrailingOption {
+            a: 1,
+            b: Some(vec![]),
+        },
+        &[1, 1, 0, 0, 0, 0],
+    );
+    round_trip(
+        TrailingOption {
+            a: 1,
+            b: Some(vec![None]),
+        },
+        &[1, 1, 0, 0, 0, 1, 0],
+    );
+    round_trip(
+        TrailingOption {
+            a: 1,
+            b: Some(vec![None, Some(0x1337)]),
+        },
+        &[1, 1, 0, 0, 0, 2, 0, 1, 0x13, 0x37],
+    );
+}
+
+#[test]
+fn test_parse_invalid_leading_option() {
+    // the presence byte must be 0 or 1
+    from_bytes_fail::<LeadingOption>(&[2, 7], Error::InvalidOptional);
+    from_bytes_fail::<LeadingOption>(&[0xff, 7], Error::InvalidOptional);
+    from_bytes_fail::<LeadingOption>(&[1, 0, 0, 0], Error::EndOfBuffer);
+}
+
 #[cfg(test)]
 fn stream<T: Streamable>(v: &T) -> Vec<u8> {
     let mut buf = Vec::<u8>::new();
@@ -756,3 +855,62 @@ fn test_stream_enum() {
     assert_eq!(stream::<TestEnum>(&TestEnum::B), &[1_u8]);
     assert_eq!(stream::<TestEnum>(&TestEnum::C), &[255_u8]);
 }
+
+#[cfg(test)]
+#[derive(Streamable, Hash, Debug, Clone, Eq, PartialEq)]
+#[repr(u8)]
+enum TestDataEnum {
+    Empty = 0,
+    Value(u32) = 1,
+    Pair(u8, Option<u16>) = 2,
+    Rejection { code: u8, reason: String } = 200,
+}
+
+#[test]
+fn test_data_enum_round_trip() {
+    for (value, buf) in [
+        (TestDataEnum::Empty, &[0][..]),
+        (TestDataEnum::Value(0x0102_0304), &[1, 1, 2, 3, 4]),
+        (TestDataEnum::Pair(7, None), &[2, 7, 0]),
+        (TestDataEnum::Pair(7, Some(0x1337)), &[2, 7, 1, 0x13, 0x37]),
+        (
+            TestDataEnum::Rejection {
+                code: 3,
+                reason: "no".to_string(),
+            },
+            &[200, 3, 0, 0, 0, 2, b'n', b'o'],
+        ),
+    ] {
+        // stream() also checks that update_digest() covers the same bytes
+        assert_eq!(stream(&value), buf);
+        from_bytes::<TestDataEnum>(buf, value.clone());
+        assert_eq!(TestDataEnum::from_bytes(buf).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_parse_data_enum_fail() {
+    // unknown discriminants
+    from_bytes_fail::<TestDataEnum>(&[3, 0, 0, 0, 0], Error::InvalidEnum);
+    from_bytes_fail::<TestDataEnum>(&[255], Error::InvalidEnum);
+    // truncated fields
+    from_bytes_fail::<TestDataEnum>(&[], Error::EndOfBuffer);
+    from_bytes_fail::<TestDataEnum>(&[1, 1, 2, 3], Error::EndOfBuffer);
+    from_bytes_fail::<TestDataEnum>(&[200, 3, 0, 0, 0, 2, b'n'], Error::EndOfBuffer);
+    // the fields are parsed strictly
+    from_bytes_fail::<TestDataEnum>(&[2, 7, 2, 0x13, 0x37], Error::InvalidOptional);
+    assert_eq!(
+        TestDataEnum::from_bytes(&[0, 0]).unwrap_err(),
+        Error::InputTooLarge
+    );
+}
+
+#[test]
+fn test_data_enum_hash() {
+    // the discriminant is part of the hash, not just the fields
+    assert_ne!(
+        TestDataEnum::Value(1).hash(),
+        TestDataEnum::Pair(0, Some(1)).hash()
+    );
+    assert_ne!(TestDataEnum::Empty.hash(), TestDataEnum::Value(0).hash());
+}
@@ -0,0 +1,275 @@
+use crate::cached_hash::CachedStreamableHash;
+use std::fmt;
+
+/// A single difference between two Streamable values. `path` is the location
+/// of the differing field, e.g. `foliage.foliage_block_data.pool_target` or
+/// `transactions_info.reward_claims_incorporated[2].amount`. `left` and `right`
+/// are short descriptions of the two values (long byte strings are truncated).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    pub path: String,
+    pub left: String,
+    pub right: String,
+}
+
+impl FieldDiff {
+    pub fn new<T: StreamableDiff + ?Sized>(path: &str, left: &T, right: &T) -> Self {
+        Self {
+            path: path.to_string(),
+            left: left.summary(),
+            right: right.summary(),
+        }
+    }
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} != {}", self.path, self.left, self.right)
+    }
+}
+
+/// Compares two values of a Streamable type field by field. This is derived
+/// along with `Streamable`. Leaf types (integers, byte strings, keys etc.)
+/// report themselves as a single difference.
+pub trait StreamableDiff {
+    /// Appends the differences between `self` and `other` to `out`, where
+    /// `path` is the location of `self` in the top-level value.
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>);
+
+    /// A short, human readable description of the value.
+    fn summary(&self) -> String;
+}
+
+/// Returns the leaf fields that differ between `a` and `b`, in the order they
+/// are streamed. If the two values are equal, the list is empty.
+pub fn diff<T: StreamableDiff>(a: &T, b: &T) -> Vec<FieldDiff> {
+    let mut out = Vec::new();
+    a.diff_into(b, "", &mut out);
+    out
+}
+
+/// The path of the field `name` in the value at `path`.
+pub fn diff_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+/// Records `left` and `right` as a single difference, unless they're equal.
+pub fn diff_leaf<T: StreamableDiff + PartialEq + ?Sized>(
+    left: &T,
+    right: &T,
+    path: &str,
+    out: &mut Vec<FieldDiff>,
+) {
+    if left != right {
+        out.push(FieldDiff::new(path, left, right));
+    }
+}
+
+// byte strings longer than this are truncated in the summary
+const SUMMARY_BYTES: usize = 16;
+
+/// The hex encoding of `bytes`, truncated to the first few bytes (followed by
+/// the full length) if it's long.
+pub fn bytes_summary(bytes: &[u8]) -> String {
+    let hex = hex::encode(&bytes[..bytes.len().min(SUMMARY_BYTES)]);
+    if bytes.len() > SUMMARY_BYTES {
+        format!("{hex}... ({} bytes)", bytes.len())
+    } else {
+        hex
+    }
+}
+
+macro_rules! diff_primitive {
+    ($t:ty) => {
+        impl StreamableDiff for $t {
+            fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+                diff_leaf(self, other, path, out);
+            }
+
+            fn summary(&self) -> String {
+                self.to_string()
+            }
+        }
+    };
+}
+
+diff_primitive!(u8);
+diff_primitive!(i8);
+diff_primitive!(u16);
+diff_primitive!(i16);
+diff_primitive!(u32);
+diff_primitive!(i32);
+diff_primitive!(u64);
+diff_primitive!(i64);
+diff_primitive!(u128);
+diff_primitive!(i128);
+diff_primitive!(bool);
+
+impl StreamableDiff for String {
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+        diff_leaf(self, other, path, out);
+    }
+
+    fn summary(&self) -> String {
+        const SUMMARY_CHARS: usize = 32;
+        if self.chars().count() > SUMMARY_CHARS {
+            let prefix: String = self.chars().take(SUMMARY_CHARS).collect();
+            format!("{prefix:?}...")
+        } else {
+            format!("{self:?}")
+        }
+    }
+}
+
+impl StreamableDiff for () {
+    fn diff_into(&self, _other: &Self, _path: &str, _out: &mut Vec<FieldDiff>) {}
+
+    fn summary(&self) -> String {
+        "()".to_string()
+    }
+}
+
+impl<T: StreamableDiff> StreamableDiff for Option<T> {
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+        match (self, other) {
+            (Some(left), Some(right)) => left.diff_into(right, path, out),
+            (None, None) => {}
+            _ => out.push(FieldDiff::new(path, self, other)),
+        }
+    }
+
+    fn summary(&self) -> String {
+        match self {
+            Some(value) => format!("Some({})", value.summary()),
+            None => "None".to_string(),
+        }
+    }
+}
+
+/// The items that both lists have are compared by index. If the lengths
+/// differ, that's reported once, for the list itself, rather than for every
+/// extra item.
+impl<T: StreamableDiff> StreamableDiff for Vec<T> {
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+        if self.len() != other.len() {
+            out.push(FieldDiff::new(path, self, other));
+        }
+        for (i, (left, right)) in self.iter().zip(other).enumerate() {
+            left.diff_into(right, &format!("{path}[{i}]"), out);
+        }
+    }
+
+    fn summary(&self) -> String {
+        format!("[{} items]", self.len())
+    }
+}
+
+macro_rules! diff_tuple {
+    ($($t:ident $i:tt),*) => {
+        impl<$($t: StreamableDiff),*> StreamableDiff for ($($t,)*) {
+            fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+                $( self.$i.diff_into(&other.$i, &diff_path(path, stringify!($i)), out); )*
+            }
+
+            fn summary(&self) -> String {
+                let items = [$( self.$i.summary() ),*];
+                format!("({})", items.join(", "))
+            }
+        }
+    };
+}
+
+diff_tuple!(T 0, U 1);
+diff_tuple!(T 0, U 1, V 2);
+diff_tuple!(T 0, U 1, V 2, W 3);
+
+impl<T: StreamableDiff> StreamableDiff for CachedStreamableHash<T> {
+    fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<FieldDiff>) {
+        (**self).diff_into(other, path, out);
+    }
+
+    fn summary(&self) -> String {
+        (**self).summary()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn paths(diffs: &[FieldDiff]) -> Vec<&str> {
+        diffs.iter().map(|d| d.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_equal() {
+        assert_eq!(
+            diff(&vec![(1_u32, Some(2_u8))], &vec![(1, Some(2))]),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_leaf() {
+        assert_eq!(
+            diff(&1_u64, &2_u64),
+            vec![FieldDiff {
+                path: String::new(),
+                left: "1".to_string(),
+                right: "2".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_tuple() {
+        let diffs = diff(&(1_u8, (2_u8, 3_u8)), &(1, (4, 5)));
+        assert_eq!(paths(&diffs), ["1.0", "1.1"]);
+        assert_eq!(diffs[0].to_string(), "1.0: 2 != 4");
+    }
+
+    #[test]
+    fn test_option() {
+        let diffs = diff(&Some(1_u8), &None);
+        assert_eq!(paths(&diffs), [""]);
+        assert_eq!(
+            (diffs[0].left.as_str(), diffs[0].right.as_str()),
+            ("Some(1)", "None")
+        );
+
+        // the values inside are compared directly
+        assert_eq!(paths(&diff(&Some((1_u8, 2_u8)), &Some((1, 3)))), ["1"]);
+    }
+
+    #[test]
+    fn test_vec() {
+        // the length mismatch is reported once, along with the common items
+        let diffs = diff(&vec![1_u8, 2, 3, 4], &vec![1_u8, 5]);
+        assert_eq!(paths(&diffs), ["", "[1]"]);
+        assert_eq!(diffs[0].left, "[4 items]");
+        assert_eq!(diffs[0].right, "[2 items]");
+
+        let diffs = diff(&(vec![(1_u8, 2_u8)], 0_u8), &(vec![(1_u8, 3_u8)], 0_u8));
+        assert_eq!(paths(&diffs), ["0[0].1"]);
+    }
+
+    #[rstest]
+    #[case(&[], "")]
+    #[case(&[0xab, 0xcd], "abcd")]
+    #[case(&[0xcc; 16], "cccccccccccccccccccccccccccccccc")]
+    #[case(&[0xcc; 32], "cccccccccccccccccccccccccccccccc... (32 bytes)")]
+    fn test_bytes_summary(#[case] bytes: &[u8], #[case] expected: &str) {
+        assert_eq!(bytes_summary(bytes), expected);
+    }
+
+    #[test]
+    fn test_string_summary() {
+        assert_eq!("abc".to_string().summary(), "\"abc\"");
+        assert_eq!("a".repeat(40).summary(), format!("{:?}...", "a".repeat(32)));
+    }
+}
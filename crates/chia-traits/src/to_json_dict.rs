@@ -1,5 +1,6 @@
+use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyList;
+use pyo3::types::{PyDict, PyList, PyString};
 use pyo3::BoundObject;
 
 pub trait ToJsonDict {
@@ -25,10 +26,23 @@ to_json_primitive!(u32);
 to_json_primitive!(i32);
 to_json_primitive!(u64);
 to_json_primitive!(i64);
-to_json_primitive!(u128);
-to_json_primitive!(i128);
 to_json_primitive!(String);
 
+// JSON numbers can't hold all 128 bit integers, so these are always
+// emitted as decimal strings.
+macro_rules! to_json_string {
+    ($t:ty) => {
+        impl $crate::to_json_dict::ToJsonDict for $t {
+            fn to_json_dict(&self, py: Python<'_>) -> pyo3::PyResult<PyObject> {
+                Ok(self.to_string().into_pyobject(py)?.into_any().unbind())
+            }
+        }
+    };
+}
+
+to_json_string!(u128);
+to_json_string!(i128);
+
 impl<T: ToJsonDict> ToJsonDict for Vec<T> {
     fn to_json_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
         let list = PyList::empty(py);
@@ -67,3 +81,35 @@ impl<T: ToJsonDict, U: ToJsonDict, W: ToJsonDict> ToJsonDict for (T, U, W) {
         Ok(list.into())
     }
 }
+
+/// The JSON object of a `#[streamable(json_map)]` field. The keys are the
+/// JSON strings of `K` (the 0x-prefixed hex, for bytes), in sorted order.
+/// Since the object can't hold the same key twice, duplicate keys are an
+/// error.
+pub fn to_json_map<K, V>(pairs: &[(K, V)], py: Python<'_>) -> PyResult<PyObject>
+where
+    K: ToJsonDict + Ord,
+    V: ToJsonDict,
+{
+    let mut sorted: Vec<&(K, V)> = pairs.iter().collect();
+    sorted.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+
+    let dict = PyDict::new(py);
+    for (idx, (key, value)) in sorted.iter().enumerate() {
+        let key_json = key.to_json_dict(py)?;
+        if !key_json.bind(py).is_instance_of::<PyString>() {
+            return Err(PyTypeError::new_err(format!(
+                "json_map keys must be strings, got: {}",
+                key_json.bind(py).repr()?
+            )));
+        }
+        if idx > 0 && sorted[idx - 1].0 == *key {
+            return Err(PyValueError::new_err(format!(
+                "duplicate key in json_map: {}",
+                key_json.bind(py).str()?
+            )));
+        }
+        dict.set_item(key_json, value.to_json_dict(py)?)?;
+    }
+    Ok(dict.into())
+}
@@ -0,0 +1,142 @@
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, Serializer};
+
+use crate::Streamable;
+
+/// Serializes any `Streamable` type with serde as its canonical Streamable bytes, so that
+/// what's stored is exactly what goes over the wire.
+///
+/// Binary formats (where `is_human_readable()` is false, such as bincode) get the raw bytes.
+/// Human-readable formats (such as JSON) get the same bytes as a `0x`-prefixed hex string.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct WireFormat<T>(pub T);
+
+impl<T> WireFormat<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for WireFormat<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Streamable> Serialize for WireFormat<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = self.0.to_bytes().map_err(ser::Error::custom)?;
+        serialize_hex_bytes(&bytes, serializer)
+    }
+}
+
+impl<'de, T: Streamable> Deserialize<'de> for WireFormat<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = deserialize_hex_bytes(deserializer)?;
+        T::from_bytes(&bytes)
+            .map(WireFormat)
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Serializes `bytes` the same way [`WireFormat`] serializes Streamable bytes, i.e. as a
+/// `0x`-prefixed hex string in human-readable formats and as raw bytes otherwise. This is
+/// meant for implementing `Serialize` on types that wrap bytes.
+pub fn serialize_hex_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+/// The inverse of [`serialize_hex_bytes()`]. The `0x` prefix is optional.
+pub fn deserialize_hex_bytes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<u8>, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(HexBytesVisitor)
+    } else {
+        deserializer.deserialize_byte_buf(HexBytesVisitor)
+    }
+}
+
+struct HexBytesVisitor;
+
+impl<'de> Visitor<'de> for HexBytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("bytes, or a hex string of them")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        let value = value.strip_prefix("0x").unwrap_or(value);
+        hex::decode(value).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(value)
+    }
+
+    // Some binary formats represent bytes as a sequence of integers.
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json() {
+        let value = WireFormat((1337_u32, "hello".to_string()));
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, "\"0x000005390000000568656c6c6f\"");
+        assert_eq!(
+            serde_json::from_str::<WireFormat<(u32, String)>>(&json).unwrap(),
+            value
+        );
+
+        // The prefix is optional.
+        assert_eq!(
+            serde_json::from_str::<WireFormat<u32>>("\"00000539\"").unwrap(),
+            WireFormat(1337)
+        );
+    }
+
+    #[test]
+    fn test_bincode() {
+        let value = WireFormat((1337_u32, "hello".to_string()));
+        let bytes = bincode::serialize(&value).unwrap();
+
+        // bincode prefixes the bytes with their length as a u64.
+        let wire_bytes = value.0.to_bytes().unwrap();
+        assert_eq!(bytes[..8], (wire_bytes.len() as u64).to_le_bytes());
+        assert_eq!(bytes[8..], wire_bytes);
+
+        assert_eq!(
+            bincode::deserialize::<WireFormat<(u32, String)>>(&bytes).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(serde_json::from_str::<WireFormat<u32>>("\"0x0000\"").is_err());
+        assert!(serde_json::from_str::<WireFormat<u32>>("\"0xzz\"").is_err());
+        assert!(serde_json::from_str::<WireFormat<u32>>("\"0x0000000000\"").is_err());
+    }
+}
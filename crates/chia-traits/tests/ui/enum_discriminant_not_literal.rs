@@ -0,0 +1,9 @@
+use chia_streamable_macro::Streamable;
+
+#[derive(Streamable, Clone, Copy)]
+enum Foo {
+    A = 0,
+    B = 1 + 1,
+}
+
+fn main() {}
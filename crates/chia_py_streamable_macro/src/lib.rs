@@ -3,7 +3,7 @@
 use proc_macro2::{Ident, Span};
 use proc_macro_crate::{crate_name, FoundCrate};
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput, FieldsNamed, FieldsUnnamed};
+use syn::{parse_macro_input, DeriveInput, FieldsNamed, FieldsUnnamed, Index};
 
 fn maybe_upper_fields(py_uppercase: bool, fnames: Vec<Ident>) -> Vec<Ident> {
     if py_uppercase {
@@ -16,7 +16,30 @@ fn maybe_upper_fields(py_uppercase: bool, fnames: Vec<Ident>) -> Vec<Ident> {
     }
 }
 
-#[proc_macro_derive(PyStreamable, attributes(py_uppercase, py_pickle))]
+/// Enums are exposed to Python as their discriminant (an int), which only
+/// works when none of the variants have fields.
+fn unsupported_data_enum(e: &syn::DataEnum, derive: &str) -> Option<proc_macro::TokenStream> {
+    let variant = e
+        .variants
+        .iter()
+        .find(|v| !matches!(v.fields, syn::Fields::Unit))?;
+    Some(
+        syn::Error::new_spanned(
+            variant,
+            format!(
+                "{derive} does not support enums whose variants have fields. Streamable enums \
+                 with fields can only be used from Rust"
+            ),
+        )
+        .to_compile_error()
+        .into(),
+    )
+}
+
+#[proc_macro_derive(
+    PyStreamable,
+    attributes(py_uppercase, py_pickle, py_cached_hash, py_cached_getters)
+)]
 pub fn py_streamable_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let found_crate = crate_name("chia-traits").expect("chia-traits is present in `Cargo.toml`");
 
@@ -34,17 +57,26 @@ pub fn py_streamable_macro(input: proc_macro::TokenStream) -> proc_macro::TokenS
 
     let mut py_uppercase = false;
     let mut py_pickle = false;
+    let mut py_cached_hash = false;
+    let mut py_cached_getters = false;
     for attr in &attrs {
         if attr.path().is_ident("py_uppercase") {
             py_uppercase = true;
         } else if attr.path().is_ident("py_pickle") {
             py_pickle = true;
+        } else if attr.path().is_ident("py_cached_hash") {
+            py_cached_hash = true;
+        } else if attr.path().is_ident("py_cached_getters") {
+            py_cached_getters = true;
         }
     }
 
     let fields = match data {
         syn::Data::Struct(s) => s.fields,
-        syn::Data::Enum(_) => {
+        syn::Data::Enum(e) => {
+            if let Some(error) = unsupported_data_enum(&e, "PyStreamable") {
+                return error;
+            }
             return quote! {
                 impl<'a> pyo3::conversion::FromPyObject<'a> for #ident {
                     fn extract_bound(ob: &pyo3::Bound<'a, pyo3::PyAny>) -> pyo3::PyResult<Self> {
@@ -101,6 +133,10 @@ pub fn py_streamable_macro(input: proc_macro::TokenStream) -> proc_macro::TokenS
             fn to_python<'a>(&self, py: pyo3::Python<'a>) -> pyo3::PyResult<pyo3::Bound<'a, pyo3::PyAny>> {
                 Ok(pyo3::IntoPy::into_py(self.clone(), py).into_bound(py))
             }
+
+            fn py_type_name() -> String {
+                <Self as pyo3::PyTypeInfo>::NAME.to_string()
+            }
         }
     };
 
@@ -185,12 +221,81 @@ pub fn py_streamable_macro(input: proc_macro::TokenStream) -> proc_macro::TokenS
                 }
             }
 
+            #[classmethod]
+            #[pyo3(signature=(data))]
+            pub fn from_json_bytes(cls: &pyo3::Bound<'_, pyo3::types::PyType>, py: pyo3::Python<'_>, data: &[u8]) -> pyo3::PyResult<pyo3::PyObject> {
+                use pyo3::prelude::PyAnyMethods;
+                use pyo3::IntoPy;
+                use pyo3::Bound;
+                let rust_obj = Bound::new(py, #crate_name::from_json_dict::from_json_bytes::<Self>(data)?)?;
+
+                // same as from_json_dict(), child classes are converted by
+                // their from_parent()
+                if rust_obj.is_exact_instance(&cls) {
+                    Ok(rust_obj.into_py(py))
+                } else {
+                    let instance = cls.call_method1("from_parent", (rust_obj.into_py(py),))?;
+                    Ok(instance.into_py(py))
+                }
+            }
+
             pub fn to_json_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::PyObject> {
                 #crate_name::to_json_dict::ToJsonDict::to_json_dict(self, py)
             }
         }
     });
 
+    // with #[streamable(cached_hash)], the hash is stored in the instance
+    // __dict__ the first time it's computed. The object is immutable, so it
+    // can't go stale, and new objects (e.g. from replace()) start without it
+    let get_hash = if py_cached_hash {
+        quote! {
+            pub fn get_hash<'p>(slf: &pyo3::Bound<'p, Self>) -> pyo3::PyResult<pyo3::Bound<'p, pyo3::types::PyAny>> {
+                use pyo3::prelude::PyAnyMethods;
+                use pyo3::types::PyDictMethods;
+                let dict = slf.getattr("__dict__")?;
+                let dict = dict.downcast::<pyo3::types::PyDict>()?;
+                if let Some(hash) = dict.get_item(#crate_name::HASH_CACHE_ATTR)? {
+                    return Ok(hash);
+                }
+                let digest: [u8; 32] = #crate_name::Streamable::hash(&*slf.borrow());
+                let hash = #crate_name::hash_to_python(slf.py(), &digest)?;
+                dict.set_item(#crate_name::HASH_CACHE_ATTR, &hash)?;
+                Ok(hash)
+            }
+        }
+    } else {
+        quote! {
+            pub fn get_hash<'p>(&self, py: pyo3::Python<'p>) -> pyo3::PyResult<pyo3::Bound<'p, pyo3::types::PyAny>> {
+                let mut ctx = chia_sha2::Sha256::new();
+                #crate_name::Streamable::update_digest(self, &mut ctx);
+                let digest: [u8; 32] = ctx.finalize();
+                #crate_name::hash_to_python(py, &digest)
+            }
+        }
+    };
+    // the cached hash and fields are left out of pickles, they're recomputed
+    // when needed
+    let mut cache_attrs = Vec::new();
+    if py_cached_hash {
+        cache_attrs.push(quote!(#crate_name::HASH_CACHE_ATTR));
+    }
+    if py_cached_getters {
+        cache_attrs.push(quote!(#crate_name::GETTER_CACHE_ATTR));
+    }
+    let pickle_dict = if cache_attrs.is_empty() {
+        quote!(let dict = dict.downcast_into::<pyo3::types::PyDict>()?;)
+    } else {
+        quote! {
+            let dict = dict.downcast::<pyo3::types::PyDict>()?.copy()?;
+            #(
+            if dict.contains(#cache_attrs)? {
+                dict.del_item(#cache_attrs)?;
+            }
+            )*
+        }
+    };
+
     let streamable = quote! {
         #[pyo3::pymethods]
         impl #ident {
@@ -284,16 +389,17 @@ pub fn py_streamable_macro(input: proc_macro::TokenStream) -> proc_macro::TokenS
                 }
             }
 
-            pub fn get_hash<'p>(&self, py: pyo3::Python<'p>) -> pyo3::PyResult<pyo3::Bound<'p, pyo3::types::PyAny>> {
-                use pyo3::IntoPy;
-                use pyo3::types::PyModule;
-                use pyo3::prelude::PyAnyMethods;
-                let mut ctx = chia_sha2::Sha256::new();
-                #crate_name::Streamable::update_digest(self, &mut ctx);
-                let bytes_module = PyModule::import_bound(py, "chia_rs.sized_bytes")?;
-                let ty = bytes_module.getattr("bytes32")?;
-                ty.call1((&ctx.finalize().into_py(py),))
+            #get_hash
+
+            /// The fields that differ between `self` and `other`, as
+            /// `(path, left, right)` tuples.
+            pub fn diff(&self, other: &Self) -> Vec<(String, String, String)> {
+                #crate_name::diff(self, other)
+                    .into_iter()
+                    .map(|d| (d.path, d.left, d.right))
+                    .collect()
             }
+
             #[pyo3(name = "to_bytes")]
             pub fn py_to_bytes<'p>(&self, py: pyo3::Python<'p>) -> pyo3::PyResult<pyo3::Bound<'p, pyo3::types::PyBytes>> {
                 let mut writer = Vec::<u8>::new();
@@ -309,12 +415,65 @@ pub fn py_streamable_macro(input: proc_macro::TokenStream) -> proc_macro::TokenS
                 self.py_to_bytes(py)
             }
 
-            pub fn __deepcopy__<'p>(&self, memo: &pyo3::Bound<pyo3::PyAny>) -> pyo3::PyResult<Self> {
-                Ok(self.clone())
+            pub fn __deepcopy__<'p>(slf: &pyo3::Bound<'p, Self>, memo: &pyo3::Bound<'p, pyo3::PyAny>) -> pyo3::PyResult<pyo3::Bound<'p, pyo3::PyAny>> {
+                use pyo3::prelude::PyAnyMethods;
+                use pyo3::types::PyModule;
+                let copy = Self::py_copy_instance(slf)?;
+
+                // register the copy before copying the attributes of child
+                // classes, so references back to this object (e.g. in
+                // cyclic containers) resolve to the copy
+                if !memo.is_none() {
+                    memo.set_item(slf.as_ptr() as usize, &copy)?;
+                }
+                if let Ok(dict) = slf.getattr("__dict__") {
+                    let copy_module = PyModule::import_bound(slf.py(), "copy")?;
+                    let dict = copy_module.getattr("deepcopy")?.call1((dict, memo))?;
+                    copy.getattr("__dict__")?.call_method1("update", (dict,))?;
+                }
+                Ok(copy)
             }
 
-            pub fn __copy__<'p>(&self) -> pyo3::PyResult<Self> {
-                Ok(self.clone())
+            pub fn __copy__<'p>(slf: &pyo3::Bound<'p, Self>) -> pyo3::PyResult<pyo3::Bound<'p, pyo3::PyAny>> {
+                use pyo3::prelude::PyAnyMethods;
+                let copy = Self::py_copy_instance(slf)?;
+                if let Ok(dict) = slf.getattr("__dict__") {
+                    copy.getattr("__dict__")?.call_method1("update", (dict,))?;
+                }
+                Ok(copy)
+            }
+
+            // pickles are restored by from_bytes(), so instances of child
+            // classes are converted by their from_parent(), like when copying
+            pub fn __reduce__<'p>(slf: &pyo3::Bound<'p, Self>) -> pyo3::PyResult<pyo3::Bound<'p, pyo3::types::PyTuple>> {
+                use pyo3::prelude::PyAnyMethods;
+                use pyo3::types::PyDictMethods;
+                use pyo3::IntoPy;
+                let py = slf.py();
+                let from_bytes = slf.get_type().getattr("from_bytes")?;
+                let args = (slf.borrow().py_to_bytes(py)?,).into_py(py);
+                if let Ok(dict) = slf.getattr("__dict__") {
+                    #pickle_dict
+                    if !dict.is_empty() {
+                        return Ok(pyo3::types::PyTuple::new_bound(py, [from_bytes.into_py(py), args, dict.into_py(py)]));
+                    }
+                }
+                Ok(pyo3::types::PyTuple::new_bound(py, [from_bytes.into_py(py), args]))
+            }
+        }
+
+        impl #ident {
+            // clones the rust object into a new python object of the same
+            // type as slf. Child classes are converted by their from_parent(),
+            // like in from_bytes()
+            fn py_copy_instance<'p>(slf: &pyo3::Bound<'p, Self>) -> pyo3::PyResult<pyo3::Bound<'p, pyo3::PyAny>> {
+                use pyo3::prelude::PyAnyMethods;
+                let rust_obj = pyo3::Bound::new(slf.py(), slf.borrow().clone())?.into_any();
+                if slf.is_exact_instance_of::<Self>() {
+                    Ok(rust_obj)
+                } else {
+                    slf.get_type().call_method1("from_parent", (rust_obj,))
+                }
             }
         }
     };
@@ -324,6 +483,9 @@ pub fn py_streamable_macro(input: proc_macro::TokenStream) -> proc_macro::TokenS
         let pickle = quote! {
             #[pyo3::pymethods]
             impl #ident {
+                // new pickles are created by __reduce__(), this is for
+                // restoring the ones created by __getstate__() and
+                // __getnewargs__()
                 pub fn __setstate__(
                     &mut self,
                     state: &pyo3::Bound<pyo3::types::PyBytes>,
@@ -356,7 +518,7 @@ pub fn py_streamable_macro(input: proc_macro::TokenStream) -> proc_macro::TokenS
     py_protocol.into()
 }
 
-#[proc_macro_derive(PyJsonDict, attributes(py_uppercase))]
+#[proc_macro_derive(PyJsonDict, attributes(py_uppercase, json_map))]
 pub fn py_json_dict_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let found_crate = crate_name("chia-traits").expect("chia-traits is present in `Cargo.toml`");
 
@@ -381,7 +543,10 @@ pub fn py_json_dict_macro(input: proc_macro::TokenStream) -> proc_macro::TokenSt
 
     let fields = match data {
         syn::Data::Struct(s) => s.fields,
-        syn::Data::Enum(_) => {
+        syn::Data::Enum(e) => {
+            if let Some(error) = unsupported_data_enum(&e, "PyJsonDict") {
+                return error;
+            }
             return quote! {
                 impl #crate_name::to_json_dict::ToJsonDict for #ident {
                     fn to_json_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::PyObject> {
@@ -394,6 +559,11 @@ pub fn py_json_dict_macro(input: proc_macro::TokenStream) -> proc_macro::TokenSt
                         let v = <u8 as #crate_name::from_json_dict::FromJsonDict>::from_json_dict(o)?;
                         <Self as #crate_name::Streamable>::parse::<false>(&mut std::io::Cursor::<&[u8]>::new(&[v])).map_err(|e| e.into())
                     }
+
+                    fn from_json_value(v: &#crate_name::from_json_dict::JsonValue) -> pyo3::PyResult<Self> {
+                        let v = <u8 as #crate_name::from_json_dict::FromJsonDict>::from_json_value(v)?;
+                        <Self as #crate_name::Streamable>::parse::<false>(&mut std::io::Cursor::<&[u8]>::new(&[v])).map_err(|e| e.into())
+                    }
                 }
             }
             .into();
@@ -409,35 +579,120 @@ pub fn py_json_dict_macro(input: proc_macro::TokenStream) -> proc_macro::TokenSt
         syn::Fields::Named(FieldsNamed { named, .. }) => {
             let mut fnames = Vec::<Ident>::new();
             let mut ftypes = Vec::<syn::Type>::new();
+            let mut json_maps = Vec::<bool>::new();
             for f in &named {
                 fnames.push(f.ident.as_ref().unwrap().clone());
                 ftypes.push(f.ty.clone());
+                json_maps.push(f.attrs.iter().any(|a| a.path().is_ident("json_map")));
             }
 
             let fnames_maybe_upper = maybe_upper_fields(py_uppercase, fnames.clone());
 
+            // fields marked with #[json_map] (see `#[streamable(json_map)]`)
+            // are lists of (key, value) pairs, represented as a JSON object
+            let mut to_items = Vec::new();
+            let mut from_items = Vec::new();
+            let mut from_value_items = Vec::new();
+            for (((fname, key), ftype), json_map) in fnames
+                .iter()
+                .zip(&fnames_maybe_upper)
+                .zip(&ftypes)
+                .zip(json_maps)
+            {
+                if json_map {
+                    to_items.push(quote! {
+                        ret.set_item(stringify!(#key), #crate_name::to_json_dict::to_json_map(&self.#fname, py)?)?;
+                    });
+                    from_items.push(quote! {
+                        #fname: #crate_name::from_json_dict::json_map_field_from_json_dict(o, stringify!(#key))?,
+                    });
+                    from_value_items.push(quote! {
+                        #fname: #crate_name::from_json_dict::json_map_field_from_json_value(o, stringify!(#key))?,
+                    });
+                } else {
+                    to_items.push(quote! {
+                        ret.set_item(stringify!(#key), self.#fname.to_json_dict(py)?)?;
+                    });
+                    from_items.push(quote! {
+                        #fname: #crate_name::from_json_dict::field_from_json_dict::<#ftype>(o, stringify!(#key))?,
+                    });
+                    from_value_items.push(quote! {
+                        #fname: #crate_name::from_json_dict::field_from_json_value::<#ftype>(o, stringify!(#key))?,
+                    });
+                }
+            }
+
             py_protocol.extend( quote! {
 
                 impl #crate_name::to_json_dict::ToJsonDict for #ident {
                     fn to_json_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::PyObject> {
                         use pyo3::prelude::PyDictMethods;
                         let ret = pyo3::types::PyDict::new_bound(py);
-                        #(ret.set_item(stringify!(#fnames_maybe_upper), self.#fnames.to_json_dict(py)?)?);*;
+                        #(#to_items)*
                         Ok(ret.into())
                     }
                 }
 
                 impl #crate_name::from_json_dict::FromJsonDict for #ident {
                     fn from_json_dict(o: &pyo3::Bound<pyo3::PyAny>) -> pyo3::PyResult<Self> {
-                        use pyo3::prelude::PyAnyMethods;
                         Ok(Self{
-                            #(#fnames: <#ftypes as #crate_name::from_json_dict::FromJsonDict>::from_json_dict(&o.get_item(stringify!(#fnames_maybe_upper))?)?,)*
+                            #(#from_items)*
+                        })
+                    }
+
+                    fn from_json_value(o: &#crate_name::from_json_dict::JsonValue) -> pyo3::PyResult<Self> {
+                        Ok(Self{
+                            #(#from_value_items)*
                         })
                     }
                 }
             });
         }
-        _ => {
+        syn::Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
+            // tuple structs are represented as a JSON list
+            let findices: Vec<Index> = (0..unnamed.len()).map(Index::from).collect();
+            let ftypes: Vec<syn::Type> = unnamed.iter().map(|f| f.ty.clone()).collect();
+            let len = unnamed.len();
+
+            py_protocol.extend(quote! {
+
+                impl #crate_name::to_json_dict::ToJsonDict for #ident {
+                    fn to_json_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::PyObject> {
+                        use pyo3::prelude::PyListMethods;
+                        let ret = pyo3::types::PyList::empty(py);
+                        #(ret.append(self.#findices.to_json_dict(py)?)?;)*
+                        Ok(ret.into())
+                    }
+                }
+
+                impl #crate_name::from_json_dict::FromJsonDict for #ident {
+                    fn from_json_dict(o: &pyo3::Bound<pyo3::PyAny>) -> pyo3::PyResult<Self> {
+                        use pyo3::prelude::PyAnyMethods;
+                        if o.len()? != #len {
+                            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                                "expected {} elements, got {}", #len, o.len()?
+                            )));
+                        }
+                        Ok(Self(
+                            #(<#ftypes as #crate_name::from_json_dict::FromJsonDict>::from_json_dict(&o.get_item(#findices)?)?,)*
+                        ))
+                    }
+
+                    fn from_json_value(o: &#crate_name::from_json_dict::JsonValue) -> pyo3::PyResult<Self> {
+                        let items = #crate_name::from_json_dict::json_array(o)?;
+                        if items.len() != #len {
+                            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                                "expected {} elements, got {}", #len, items.len()
+                            )));
+                        }
+                        Ok(Self(
+                            #(<#ftypes as #crate_name::from_json_dict::FromJsonDict>::from_json_value(&items[#findices])?,)*
+                        ))
+                    }
+                }
+            });
+        }
+        syn::Fields::Unit => {
             panic!("PyJsonDict only supports structs");
         }
     }
@@ -452,9 +707,12 @@ pub fn py_getters_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStre
     } = parse_macro_input!(input);
 
     let mut py_uppercase = false;
+    let mut py_cached_getters = false;
     for attr in &attrs {
         if attr.path().is_ident("py_uppercase") {
             py_uppercase = true;
+        } else if attr.path().is_ident("py_cached_getters") {
+            py_cached_getters = true;
         }
     }
 
@@ -462,10 +720,6 @@ pub fn py_getters_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStre
         panic!("python binding only support struct");
     };
 
-    let syn::Fields::Named(FieldsNamed { named, .. }) = s.fields else {
-        panic!("python binding only support struct");
-    };
-
     let found_crate = crate_name("chia-traits").expect("chia-traits is present in `Cargo.toml`");
 
     let crate_name = match found_crate {
@@ -476,6 +730,14 @@ pub fn py_getters_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStre
         }
     };
 
+    let named = match s.fields {
+        syn::Fields::Named(FieldsNamed { named, .. }) => named,
+        syn::Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
+            return tuple_struct_getters(&crate_name, &ident, &unnamed);
+        }
+        syn::Fields::Unit => panic!("python binding only support struct"),
+    };
+
     let mut fnames = Vec::<Ident>::new();
     let mut ftypes = Vec::<syn::Type>::new();
     for f in named {
@@ -485,9 +747,28 @@ pub fn py_getters_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStre
 
     let fnames_maybe_upper = maybe_upper_fields(py_uppercase, fnames.clone());
 
-    let ret = quote! {
-        #[pyo3::pymethods]
-        impl #ident {
+    // with #[streamable(cached_getters)], the python objects of the fields
+    // whose type is CACHEABLE are kept in the instance __dict__, so repeated
+    // accesses return the same object rather than allocating a new one
+    let getters = if py_cached_getters {
+        quote! {
+            #(
+            #[getter]
+            fn #fnames_maybe_upper<'a> (slf: &pyo3::Bound<'a, Self>) -> pyo3::PyResult<pyo3::Bound<'a, pyo3::PyAny>> {
+                use pyo3::prelude::PyAnyMethods;
+                let py = slf.py();
+                if <#ftypes as #crate_name::ChiaToPython>::CACHEABLE {
+                    #crate_name::cached_getter(slf.as_any(), pyo3::intern!(py, stringify!(#fnames_maybe_upper)), || {
+                        #crate_name::ChiaToPython::to_python(&slf.get().#fnames, py)
+                    })
+                } else {
+                    #crate_name::ChiaToPython::to_python(&slf.get().#fnames, py)
+                }
+            }
+            )*
+        }
+    } else {
+        quote! {
             #(
             #[getter]
             fn #fnames_maybe_upper<'a> (&self, py: pyo3::Python<'a>) -> pyo3::PyResult<pyo3::Bound<'a, pyo3::PyAny>> {
@@ -497,5 +778,78 @@ pub fn py_getters_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStre
         }
     };
 
+    let ret = quote! {
+        #[pyo3::pymethods]
+        impl #ident {
+            #getters
+
+            // returns the (name, python type) of every field, in streamable
+            // order. This allows generic introspection without dataclasses
+            #[classmethod]
+            fn fields(_cls: &pyo3::Bound<'_, pyo3::types::PyType>) -> Vec<(&'static str, String)> {
+                vec![ #( (stringify!(#fnames_maybe_upper), <#ftypes as #crate_name::ChiaToPython>::py_type_name()) ),* ]
+            }
+        }
+    };
+
+    ret.into()
+}
+
+/// Tuple structs behave like python tuples. They're constructed from their
+/// fields in order, and they're indexed instead of having named getters. The
+/// constructor is generated here rather than by PyStreamable, since wrapper
+/// types (like `Program` and the BLS types) derive PyStreamable but implement
+/// their own constructors.
+fn tuple_struct_getters(
+    crate_name: &proc_macro2::TokenStream,
+    ident: &Ident,
+    unnamed: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+) -> proc_macro::TokenStream {
+    let fnames: Vec<Ident> = (0..unnamed.len())
+        .map(|i| Ident::new(&format!("field_{i}"), Span::call_site()))
+        .collect();
+    let findices: Vec<Index> = (0..unnamed.len()).map(Index::from).collect();
+    let positions: Vec<proc_macro2::Literal> = (0..unnamed.len())
+        .map(proc_macro2::Literal::usize_unsuffixed)
+        .collect();
+    let ftypes: Vec<syn::Type> = unnamed.iter().map(|f| f.ty.clone()).collect();
+    let len = unnamed.len();
+
+    let ret = quote! {
+        #[pyo3::pymethods]
+        impl #ident {
+            #[allow(too_many_arguments)]
+            #[new]
+            #[pyo3(signature = (#(#fnames),*))]
+            pub fn py_new ( #(#fnames : #ftypes),* ) -> Self {
+                Self( #(#fnames),* )
+            }
+
+            fn __len__(&self) -> usize {
+                #len
+            }
+
+            // negative indices count from the end, like for python tuples
+            fn __getitem__<'a>(&self, py: pyo3::Python<'a>, index: isize) -> pyo3::PyResult<pyo3::Bound<'a, pyo3::PyAny>> {
+                let position = if index < 0 {
+                    index.checked_add_unsigned(#len)
+                } else {
+                    Some(index)
+                };
+                match position.and_then(|p| usize::try_from(p).ok()) {
+                    #( Some(#positions) => #crate_name::ChiaToPython::to_python(&self.#findices, py), )*
+                    _ => Err(pyo3::exceptions::PyIndexError::new_err("index out of range")),
+                }
+            }
+
+            // returns the (name, python type) of every field, in streamable
+            // order. The names are the ones the constructor takes
+            #[classmethod]
+            fn fields(_cls: &pyo3::Bound<'_, pyo3::types::PyType>) -> Vec<(&'static str, String)> {
+                vec![ #( (stringify!(#fnames), <#ftypes as #crate_name::ChiaToPython>::py_type_name()) ),* ]
+            }
+        }
+    };
+
     ret.into()
 }
@@ -1,13 +1,17 @@
 #![allow(clippy::missing_panics_doc)]
 
 use proc_macro::TokenStream;
-use proc_macro2::{Ident, Span};
+use proc_macro2::{Ident, Literal, Span};
 use proc_macro_crate::{crate_name, FoundCrate};
 use quote::quote;
-use syn::token::Pub;
+use std::iter::zip;
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::token::{Comma, Pub};
 use syn::{
-    parse_macro_input, Data, DeriveInput, Expr, Fields, FieldsNamed, FieldsUnnamed, Index, Lit,
-    Type, Visibility,
+    parse_macro_input, parse_quote, Data, DeriveInput, Expr, ExprLit, Fields, FieldsNamed,
+    FieldsUnnamed, GenericArgument, Index, Lit, LitInt, Member, Meta, MetaNameValue, PathArguments,
+    Type, Variant, Visibility,
 };
 
 #[proc_macro_attribute]
@@ -23,8 +27,12 @@ pub fn streamable(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
 
-    let is_message = &attr.to_string() == "message";
-    let is_subclass = &attr.to_string() == "subclass";
+    let attr = attr.to_string();
+    let attr_flags: Vec<&str> = attr.split(',').map(str::trim).collect();
+    let is_message = attr_flags.contains(&"message");
+    let is_subclass = attr_flags.contains(&"subclass");
+    let cached_hash = attr_flags.contains(&"cached_hash");
+    let cached_getters = attr_flags.contains(&"cached_getters");
 
     let mut input: DeriveInput = parse_macro_input!(item);
     let name = input.ident.clone();
@@ -35,9 +43,17 @@ pub fn streamable(attr: TokenStream, item: TokenStream) -> TokenStream {
     if let Data::Struct(data) = &mut input.data {
         let mut field_names = Vec::new();
         let mut field_types = Vec::new();
+        let mut last_since = None;
 
         for (i, field) in data.fields.iter_mut().enumerate() {
             field.vis = Visibility::Public(Pub::default());
+            let since = match field_attrs(field) {
+                Ok(since) => since,
+                Err(error) => return error.to_compile_error().into(),
+            };
+            if let Err(error) = check_since(field, since, &mut last_since) {
+                return error.to_compile_error().into();
+            }
             field_names.push(Ident::new(
                 &field
                     .ident
@@ -86,18 +102,38 @@ pub fn streamable(attr: TokenStream, item: TokenStream) -> TokenStream {
             });
         }
     } else {
-        panic!("only structs are supported");
+        return syn::Error::new(name.span(), "only structs are supported")
+            .to_compile_error()
+            .into();
     }
 
     let main_derives = quote! {
         #[derive(chia_streamable_macro::Streamable, Hash, Debug, Clone, Eq, PartialEq)]
     };
 
-    let class_attrs = if is_subclass {
-        quote!(frozen, subclass)
-    } else {
-        quote!(frozen)
-    };
+    // the module is where pickle looks the class up, see __reduce__()
+    let mut class_attrs = quote!(frozen, module = "chia_rs");
+    if is_subclass {
+        class_attrs.extend(quote!(, subclass));
+    }
+    // tuple structs behave like python tuples, see PyGetters
+    if matches!(&input.data, Data::Struct(s) if matches!(s.fields, Fields::Unnamed(..))) {
+        class_attrs.extend(quote!(, sequence));
+    }
+    // the python get_hash() caches the hash in the instance __dict__, which
+    // keeps the cache out of the rust struct
+    let mut py_helpers = quote!();
+    if cached_hash {
+        py_helpers.extend(quote!(, py_cached_hash));
+    }
+    // the getters cache the python objects of fixed-size bytes fields in the
+    // instance __dict__ too, see ChiaToPython::CACHEABLE
+    if cached_getters {
+        py_helpers.extend(quote!(, py_cached_getters));
+    }
+    if cached_hash || cached_getters {
+        class_attrs.extend(quote!(, dict));
+    }
 
     // If you're calling the macro from `chia-protocol`, enable Python bindings and arbitrary conditionally.
     // Otherwise, you're calling it from an external crate which doesn't have this infrastructure setup.
@@ -109,7 +145,7 @@ pub fn streamable(attr: TokenStream, item: TokenStream) -> TokenStream {
                     chia_py_streamable_macro::PyJsonDict,
                     chia_py_streamable_macro::PyStreamable,
                     chia_py_streamable_macro::PyGetters
-                )
+                ) #py_helpers
             )]
             #main_derives
             #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -126,7 +162,346 @@ pub fn streamable(attr: TokenStream, item: TokenStream) -> TokenStream {
     .into()
 }
 
-#[proc_macro_derive(Streamable)]
+/// Handles the `#[streamable(...)]` attributes of a field, and returns the
+/// version of `since`, if any.
+///
+/// `#[streamable(json_map)]` on a field of type `Vec<(K, V)>` makes the
+/// field a JSON object in `to_json_dict()`, keyed by the (hex) JSON string of
+/// `K`. It's replaced by the `json_map` helper attribute, which `PyJsonDict`
+/// picks up.
+///
+/// `#[streamable(since = N)]` on an `Option` field means it was added in
+/// version `N` of the type. When parsing, the field (and all the ones after
+/// it) is `None` if the input ends right before it, so that the bytes of
+/// earlier versions can still be parsed. When streaming, trailing fields that
+/// are `None` are left out, so that earlier versions can parse the bytes. This
+/// only works for types that are parsed on their own, such as protocol
+/// messages, since the end of the input is where the type ends. It's replaced
+/// by the `since` helper attribute, which `Streamable` picks up.
+fn field_attrs(field: &mut syn::Field) -> syn::Result<Option<u32>> {
+    let mut json_map = false;
+    let mut since = None;
+    let mut error = None;
+    field.attrs.retain(|attr| {
+        if !attr.path().is_ident("streamable") {
+            return true;
+        }
+        match attr.parse_args::<Meta>() {
+            Ok(Meta::Path(path)) if path.is_ident("json_map") => json_map = true,
+            Ok(Meta::NameValue(MetaNameValue {
+                path,
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Int(version),
+                        ..
+                    }),
+                ..
+            })) if path.is_ident("since") => match version.base10_parse::<u32>() {
+                Ok(version) => since = Some(version),
+                Err(e) => error = Some(e),
+            },
+            _ => {
+                error = Some(syn::Error::new_spanned(
+                    attr,
+                    "unknown streamable field attribute, expected `json_map` or `since = N`",
+                ));
+            }
+        }
+        false
+    });
+    if let Some(error) = error {
+        return Err(error);
+    }
+    if field.ident.is_none() && (json_map || since.is_some()) {
+        return Err(syn::Error::new_spanned(
+            &field.ty,
+            "json_map and since are only supported on named fields",
+        ));
+    }
+    if json_map {
+        field.attrs.push(parse_quote!(#[json_map]));
+    }
+    if let Some(version) = since {
+        if option_inner(&field.ty).is_none() {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "since is only supported on Option fields",
+            ));
+        }
+        let version = Literal::u32_unsuffixed(version);
+        field.attrs.push(parse_quote!(#[since(#version)]));
+    }
+    Ok(since)
+}
+
+/// Fields with `since` must come after all the fields without it, in the
+/// order they were added. `last_since` is the version of the previous field.
+fn check_since(
+    field: &syn::Field,
+    since: Option<u32>,
+    last_since: &mut Option<u32>,
+) -> syn::Result<()> {
+    match (*last_since, since) {
+        (Some(_), None) => Err(syn::Error::new_spanned(
+            field,
+            "fields without `since` must come before the ones with it",
+        )),
+        (Some(last), Some(version)) if version < last => Err(syn::Error::new_spanned(
+            field,
+            "fields with `since` must be in the order of their versions",
+        )),
+        _ => {
+            *last_since = since;
+            Ok(())
+        }
+    }
+}
+
+/// The version of the `since` helper attribute of a field, if any.
+fn since_attr(field: &syn::Field) -> syn::Result<Option<u32>> {
+    for attr in &field.attrs {
+        if attr.path().is_ident("since") {
+            return attr.parse_args::<LitInt>()?.base10_parse().map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// Enums are streamed as a single byte (followed by the fields of the variant,
+/// if it has any), so every variant needs an explicit discriminant that fits in
+/// a u8. Enums with fields also need `#[repr(u8)]` for that.
+fn enum_discriminant(v: &Variant) -> syn::Result<u8> {
+    let Some((_, expr)) = &v.discriminant else {
+        return Err(syn::Error::new_spanned(
+            v,
+            "Streamable enums require an explicit discriminant on every variant",
+        ));
+    };
+    let Expr::Lit(ExprLit {
+        lit: Lit::Int(i), ..
+    }) = expr
+    else {
+        return Err(syn::Error::new_spanned(
+            expr,
+            "Streamable enum discriminants must be integer literals",
+        ));
+    };
+    i.base10_parse::<u8>()
+        .map_err(|_| syn::Error::new_spanned(i, "Streamable enum discriminants must fit in a u8"))
+}
+
+/// Returns `T` if `ty` is `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+/// Finds an `Option<Option<T>>` anywhere in `ty`. These are not supported,
+/// since `None` and `Some(None)` would both be null in JSON.
+fn find_nested_option(ty: &Type) -> Option<&Type> {
+    if option_inner(ty).and_then(option_inner).is_some() {
+        return Some(ty);
+    }
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .iter()
+            .filter_map(|segment| match &segment.arguments {
+                PathArguments::AngleBracketed(args) => Some(args),
+                _ => None,
+            })
+            .flat_map(|args| &args.args)
+            .find_map(|arg| match arg {
+                GenericArgument::Type(inner) => find_nested_option(inner),
+                _ => None,
+            }),
+        Type::Tuple(tuple) => tuple.elems.iter().find_map(find_nested_option),
+        Type::Array(array) => find_nested_option(&array.elem),
+        Type::Paren(paren) => find_nested_option(&paren.elem),
+        Type::Group(group) => find_nested_option(&group.elem),
+        _ => None,
+    }
+}
+
+/// Enums whose variants have fields are streamed as the discriminant of the
+/// variant, followed by its fields, in order.
+fn data_enum(
+    crate_name: &proc_macro2::TokenStream,
+    ident: &Ident,
+    variants: &Punctuated<Variant, Comma>,
+    values: &[u8],
+) -> TokenStream {
+    let mut digest_arms = Vec::new();
+    let mut stream_arms = Vec::new();
+    let mut parse_arms = Vec::new();
+
+    for (v, value) in variants.iter().zip(values) {
+        let name = &v.ident;
+        let mut bindings = Vec::<Ident>::new();
+        let mut ftypes = Vec::<Type>::new();
+        for (i, f) in v.fields.iter().enumerate() {
+            if let Some(nested) = find_nested_option(&f.ty) {
+                return syn::Error::new_spanned(
+                    nested,
+                    "Streamable does not support `Option<Option<T>>`, since `None` and \
+                     `Some(None)` can't be told apart in JSON",
+                )
+                .to_compile_error()
+                .into();
+            }
+            bindings.push(Ident::new(&format!("field_{i}"), Span::mixed_site()));
+            ftypes.push(f.ty.clone());
+        }
+
+        let (pattern, constructor) = match &v.fields {
+            Fields::Named(FieldsNamed { named, .. }) => {
+                let fnames: Vec<_> = named.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+                (
+                    quote!( Self::#name { #( #fnames: #bindings ),* } ),
+                    quote!( Self::#name { #( #fnames: <#ftypes as #crate_name::Streamable>::parse::<TRUSTED>(input)?, )* } ),
+                )
+            }
+            Fields::Unnamed(..) => (
+                quote!( Self::#name( #( #bindings ),* ) ),
+                quote!( Self::#name( #( <#ftypes as #crate_name::Streamable>::parse::<TRUSTED>(input)?, )* ) ),
+            ),
+            Fields::Unit => (quote!(Self::#name), quote!(Self::#name)),
+        };
+
+        digest_arms.push(quote! {
+            #pattern => {
+                <u8 as #crate_name::Streamable>::update_digest(&#value, digest);
+                #( #crate_name::Streamable::update_digest(#bindings, digest); )*
+            }
+        });
+        stream_arms.push(quote! {
+            #pattern => {
+                <u8 as #crate_name::Streamable>::stream(&#value, out)?;
+                #( #crate_name::Streamable::stream(#bindings, out)?; )*
+            }
+        });
+        parse_arms.push(quote!( #value => Ok(#constructor), ));
+    }
+
+    quote! {
+        impl #crate_name::Streamable for #ident {
+            fn update_digest(&self, digest: &mut chia_sha2::Sha256) {
+                match self {
+                    #( #digest_arms )*
+                }
+            }
+            fn stream(&self, out: &mut Vec<u8>) -> #crate_name::chia_error::Result<()> {
+                match self {
+                    #( #stream_arms )*
+                }
+                Ok(())
+            }
+            fn parse<const TRUSTED: bool>(input: &mut std::io::Cursor<&[u8]>) -> #crate_name::chia_error::Result<Self> {
+                match <u8 as #crate_name::Streamable>::parse::<TRUSTED>(input)? {
+                    #( #parse_arms )*
+                    _ => Err(#crate_name::chia_error::Error::InvalidEnum),
+                }
+            }
+        }
+    }
+    .into()
+}
+
+/// The `StreamableDiff` impl, which compares structs field by field. Enums
+/// are compared by variant first, and then field by field if the variants
+/// match.
+fn diff_impl(
+    crate_name: &proc_macro2::TokenStream,
+    ident: &Ident,
+    data: &Data,
+) -> proc_macro2::TokenStream {
+    let (diff, summary) = match data {
+        Data::Struct(s) => {
+            let members: Vec<Member> = s.fields.members().collect();
+            let paths: Vec<String> = members.iter().map(member_name).collect();
+            let summary = format!("{ident} {{ .. }}");
+            let diff = if members.is_empty() {
+                quote!(let _ = (other, path, out);)
+            } else {
+                quote! {
+                    #( #crate_name::StreamableDiff::diff_into(&self.#members, &other.#members, &#crate_name::diff_path(path, #paths), out); )*
+                }
+            };
+            (diff, quote!(#summary.to_string()))
+        }
+        Data::Enum(e) => {
+            let mut arms = Vec::new();
+            let mut names = Vec::new();
+            for v in &e.variants {
+                let name = &v.ident;
+                let members: Vec<Member> = v.fields.members().collect();
+                let paths: Vec<String> = members.iter().map(member_name).collect();
+                let left: Vec<Ident> = (0..members.len())
+                    .map(|i| Ident::new(&format!("left_{i}"), Span::mixed_site()))
+                    .collect();
+                let right: Vec<Ident> = (0..members.len())
+                    .map(|i| Ident::new(&format!("right_{i}"), Span::mixed_site()))
+                    .collect();
+                arms.push(quote! {
+                    (Self::#name { #( #members: #left, )* .. }, Self::#name { #( #members: #right, )* .. }) => {
+                        #( #crate_name::StreamableDiff::diff_into(#left, #right, &#crate_name::diff_path(path, #paths), out); )*
+                    }
+                });
+                names.push(name);
+            }
+            let strings: Vec<String> = names.iter().map(|name| name.unraw().to_string()).collect();
+            (
+                quote! {
+                    match (self, other) {
+                        #( #arms )*
+                        _ => out.push(#crate_name::FieldDiff::new(path, self, other)),
+                    }
+                },
+                quote! {
+                    match self {
+                        #( Self::#names { .. } => #strings, )*
+                    }
+                    .to_string()
+                },
+            )
+        }
+        Data::Union(..) => return quote!(),
+    };
+
+    quote! {
+        #[allow(unreachable_patterns, clippy::match_same_arms)]
+        impl #crate_name::StreamableDiff for #ident {
+            fn diff_into(&self, other: &Self, path: &str, out: &mut Vec<#crate_name::FieldDiff>) {
+                #diff
+            }
+            fn summary(&self) -> String {
+                #summary
+            }
+        }
+    }
+}
+
+fn member_name(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.unraw().to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+#[proc_macro_derive(Streamable, attributes(json_map, since))]
 pub fn chia_streamable_macro(input: TokenStream) -> TokenStream {
     let found_crate = crate_name("chia-traits").expect("chia-traits is present in `Cargo.toml`");
 
@@ -139,29 +514,28 @@ pub fn chia_streamable_macro(input: TokenStream) -> TokenStream {
     };
 
     let DeriveInput { ident, data, .. } = parse_macro_input!(input);
+    let diff = diff_impl(&crate_name, &ident, &data);
 
     let mut fnames = Vec::<Ident>::new();
     let mut findices = Vec::<Index>::new();
     let mut ftypes = Vec::<Type>::new();
+    // whether each named field has a `since` attribute
+    let mut fgated = Vec::<bool>::new();
     match data {
         Data::Enum(e) => {
             let mut names = Vec::<Ident>::new();
             let mut values = Vec::<u8>::new();
             for v in &e.variants {
                 names.push(v.ident.clone());
-                let Some((_, expr)) = &v.discriminant else {
-                    panic!("unsupported enum");
-                };
-                let Expr::Lit(l) = expr else {
-                    panic!("unsupported enum (no literal)");
-                };
-                let Lit::Int(i) = &l.lit else {
-                    panic!("unsupported enum (literal is not integer)");
-                };
-                values.push(
-                    i.base10_parse::<u8>()
-                        .expect("unsupported enum (value not u8)"),
-                );
+                match enum_discriminant(v) {
+                    Ok(value) => values.push(value),
+                    Err(error) => return error.to_compile_error().into(),
+                }
+            }
+            if e.variants.iter().any(|v| !matches!(v.fields, Fields::Unit)) {
+                let mut ret = data_enum(&crate_name, &ident, &e.variants, &values);
+                ret.extend(TokenStream::from(diff));
+                return ret;
             }
             let ret = quote! {
                 impl #crate_name::Streamable for #ident {
@@ -179,11 +553,14 @@ pub fn chia_streamable_macro(input: TokenStream) -> TokenStream {
                         }
                     }
                 }
+                #diff
             };
             return ret.into();
         }
-        Data::Union(_) => {
-            panic!("Streamable does not support Unions");
+        Data::Union(u) => {
+            return syn::Error::new(u.union_token.span, "Streamable does not support unions")
+                .to_compile_error()
+                .into();
         }
         Data::Struct(s) => match s.fields {
             Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
@@ -197,12 +574,32 @@ pub fn chia_streamable_macro(input: TokenStream) -> TokenStream {
                 for f in &named {
                     fnames.push(f.ident.as_ref().unwrap().clone());
                     ftypes.push(f.ty.clone());
+                    match since_attr(f) {
+                        Ok(since) => fgated.push(since.is_some()),
+                        Err(error) => return error.to_compile_error().into(),
+                    }
                 }
             }
         },
     };
 
-    if !fnames.is_empty() {
+    for ty in &ftypes {
+        if let Some(nested) = find_nested_option(ty) {
+            return syn::Error::new_spanned(
+                nested,
+                "Streamable does not support `Option<Option<T>>`, since `None` and `Some(None)` \
+                 can't be told apart in JSON",
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
+    if fgated.contains(&true) {
+        let mut ret = gated_struct(&crate_name, &ident, &fnames, &ftypes, &fgated);
+        ret.extend(TokenStream::from(diff));
+        ret
+    } else if !fnames.is_empty() {
         let ret = quote! {
             impl #crate_name::Streamable for #ident {
                 fn update_digest(&self, digest: &mut chia_sha2::Sha256) {
@@ -216,6 +613,7 @@ pub fn chia_streamable_macro(input: TokenStream) -> TokenStream {
                     Ok(Self { #( #fnames: <#ftypes as #crate_name::Streamable>::parse::<TRUSTED>(input)?, )* })
                 }
             }
+            #diff
         };
         ret.into()
     } else if !findices.is_empty() {
@@ -232,6 +630,7 @@ pub fn chia_streamable_macro(input: TokenStream) -> TokenStream {
                     Ok(Self( #( <#ftypes as #crate_name::Streamable>::parse::<TRUSTED>(input)?, )* ))
                 }
             }
+            #diff
         };
         ret.into()
     } else {
@@ -246,7 +645,194 @@ pub fn chia_streamable_macro(input: TokenStream) -> TokenStream {
                     Ok(Self{})
                 }
             }
+            #diff
         };
         ret.into()
     }
 }
+
+/// Structs with `since` fields. The gated fields that are `None` at the end
+/// of the struct aren't streamed (or hashed), and the ones at the end of the
+/// input are parsed as `None`.
+fn gated_struct(
+    crate_name: &proc_macro2::TokenStream,
+    ident: &Ident,
+    fnames: &[Ident],
+    ftypes: &[Type],
+    fgated: &[bool],
+) -> TokenStream {
+    let gated_names: Vec<&Ident> = zip(fnames, fgated)
+        .filter_map(|(name, gated)| gated.then_some(name))
+        .collect();
+
+    let mut digest = Vec::new();
+    let mut stream = Vec::new();
+    let mut parse = Vec::new();
+    let mut gated_index = 0_usize;
+    for ((name, ty), gated) in zip(zip(fnames, ftypes), fgated) {
+        if *gated {
+            let index = Literal::usize_unsuffixed(gated_index);
+            gated_index += 1;
+            digest.push(quote! {
+                if #index < streamed {
+                    self.#name.update_digest(digest);
+                }
+            });
+            stream.push(quote! {
+                if #index < streamed {
+                    self.#name.stream(out)?;
+                }
+            });
+            parse.push(quote! {
+                #name: if input.position() as usize == input.get_ref().len() {
+                    None
+                } else {
+                    <#ty as #crate_name::Streamable>::parse::<TRUSTED>(input)?
+                },
+            });
+        } else {
+            digest.push(quote!( self.#name.update_digest(digest); ));
+            stream.push(quote!( self.#name.stream(out)?; ));
+            parse
+                .push(quote!( #name: <#ty as #crate_name::Streamable>::parse::<TRUSTED>(input)?, ));
+        }
+    }
+
+    // the number of gated fields that are streamed, i.e. up to the last one
+    // that's set
+    let streamed = quote! {
+        let streamed = [ #( self.#gated_names.is_some() ),* ]
+            .iter()
+            .rposition(|set| *set)
+            .map_or(0, |i| i + 1);
+    };
+
+    quote! {
+        impl #crate_name::Streamable for #ident {
+            fn update_digest(&self, digest: &mut chia_sha2::Sha256) {
+                #streamed
+                #( #digest )*
+            }
+            fn stream(&self, out: &mut Vec<u8>) -> #crate_name::chia_error::Result<()> {
+                #streamed
+                #( #stream )*
+                Ok(())
+            }
+            fn parse<const TRUSTED: bool>(input: &mut std::io::Cursor<&[u8]>) -> #crate_name::chia_error::Result<Self> {
+                // the fields are parsed in order
+                Ok(Self { #( #parse )* })
+            }
+        }
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+    use syn::parse::Parser;
+
+    fn nested(ty: &Type) -> Option<String> {
+        find_nested_option(ty).map(|t| t.to_token_stream().to_string().replace(' ', ""))
+    }
+
+    #[test]
+    fn test_json_map_attr() {
+        let mut field = syn::Field::parse_named
+            .parse2(quote!(#[streamable(json_map)] coins: Vec<(Bytes32, u64)>))
+            .unwrap();
+        assert_eq!(field_attrs(&mut field).unwrap(), None);
+        assert_eq!(field.attrs.len(), 1);
+        assert!(field.attrs[0].path().is_ident("json_map"));
+
+        // other attributes are left alone
+        let mut field = syn::Field::parse_named
+            .parse2(quote!(#[doc = "coins"] coins: Vec<(Bytes32, u64)>))
+            .unwrap();
+        assert_eq!(field_attrs(&mut field).unwrap(), None);
+        assert_eq!(field.attrs.len(), 1);
+        assert!(field.attrs[0].path().is_ident("doc"));
+
+        let mut field = syn::Field::parse_named
+            .parse2(quote!(#[streamable(json_list)] coins: Vec<(Bytes32, u64)>))
+            .unwrap();
+        assert!(field_attrs(&mut field).is_err());
+
+        let mut field = syn::Field::parse_unnamed
+            .parse2(quote!(#[streamable(json_map)] Vec<(Bytes32, u64)>))
+            .unwrap();
+        assert!(field_attrs(&mut field).is_err());
+    }
+
+    #[test]
+    fn test_since_attr() {
+        let mut field = syn::Field::parse_named
+            .parse2(quote!(#[streamable(since = 2)] fee: Option<u64>))
+            .unwrap();
+        assert_eq!(field_attrs(&mut field).unwrap(), Some(2));
+        assert_eq!(field.attrs.len(), 1);
+        assert_eq!(since_attr(&field).unwrap(), Some(2));
+
+        // since is only supported on named Option fields
+        for field in [
+            quote!(#[streamable(since = 2)] fee: u64),
+            quote!(#[streamable(since = "2")] fee: Option<u64>),
+            quote!(#[streamable(since = -1)] fee: Option<u64>),
+            quote!(#[streamable(since)] fee: Option<u64>),
+        ] {
+            let mut field = syn::Field::parse_named.parse2(field).unwrap();
+            assert!(field_attrs(&mut field).is_err());
+        }
+        let mut field = syn::Field::parse_unnamed
+            .parse2(quote!(#[streamable(since = 2)] Option<u64>))
+            .unwrap();
+        assert!(field_attrs(&mut field).is_err());
+    }
+
+    #[test]
+    fn test_check_since() {
+        let field = syn::Field::parse_named
+            .parse2(quote!(fee: Option<u64>))
+            .unwrap();
+        for (versions, valid) in [
+            (&[None, None, Some(2), Some(2), Some(3)][..], true),
+            (&[None, Some(2), None], false),
+            (&[Some(3), Some(2)], false),
+        ] {
+            let mut last_since = None;
+            let result = versions
+                .iter()
+                .try_for_each(|since| check_since(&field, *since, &mut last_since));
+            assert_eq!(result.is_ok(), valid);
+        }
+    }
+
+    #[test]
+    fn test_nested_option() {
+        assert_eq!(nested(&parse_quote!(u8)), None);
+        assert_eq!(nested(&parse_quote!(Option<u8>)), None);
+        assert_eq!(nested(&parse_quote!(Option<Vec<Option<Bytes32>>>)), None);
+        assert_eq!(nested(&parse_quote!(Vec<(Bytes32, Option<Coin>)>)), None);
+        assert_eq!(
+            nested(&parse_quote!(Option<Option<u8>>)),
+            Some("Option<Option<u8>>".to_string())
+        );
+        assert_eq!(
+            nested(&parse_quote!(std::option::Option<Option<u8>>)),
+            Some("std::option::Option<Option<u8>>".to_string())
+        );
+        assert_eq!(
+            nested(&parse_quote!(Vec<Option<Option<Bytes32>>>)),
+            Some("Option<Option<Bytes32>>".to_string())
+        );
+        assert_eq!(
+            nested(&parse_quote!(Option<(u8, Option<Option<u8>>)>)),
+            Some("Option<Option<u8>>".to_string())
+        );
+        assert_eq!(
+            nested(&parse_quote!([Option<Option<u8>>; 2])),
+            Some("Option<Option<u8>>".to_string())
+        );
+    }
+}
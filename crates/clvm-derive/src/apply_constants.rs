@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream;
 use quote::ToTokens;
-use syn::{Data, DeriveInput, Fields};
+use syn::{Data, DeriveInput, Field, Fields};
 
 use crate::parser::parse_clvm_options;
 
@@ -27,7 +27,7 @@ fn remove_fields(fields: &mut Fields) {
                 .named
                 .clone()
                 .into_iter()
-                .filter(|field| parse_clvm_options(&field.attrs).constant.is_none());
+                .filter(|field| !is_constant(field));
 
             fields.named = retained_fields.collect();
         }
@@ -36,10 +36,15 @@ fn remove_fields(fields: &mut Fields) {
                 .unnamed
                 .clone()
                 .into_iter()
-                .filter(|field| parse_clvm_options(&field.attrs).constant.is_none());
+                .filter(|field| !is_constant(field));
 
             fields.unnamed = retained_fields.collect();
         }
         Fields::Unit => {}
     }
 }
+
+// Invalid options are left for the derive macros to report.
+fn is_constant(field: &Field) -> bool {
+    parse_clvm_options(&field.attrs).is_ok_and(|options| options.constant.is_some())
+}
@@ -1,6 +1,6 @@
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens};
-use syn::{parse_quote, DeriveInput, GenericParam, Ident};
+use syn::{parse_quote, DeriveInput, GenericParam, Ident, Lifetime};
 
 use crate::{
     crate_name,
@@ -9,18 +9,59 @@ use crate::{
 };
 
 pub fn from_clvm(ast: DeriveInput) -> TokenStream {
-    let parsed = parse("FromClvm", &ast);
+    let parsed = match parse("FromClvm", &ast) {
+        Ok(parsed) => parsed,
+        Err(error) => return error.to_compile_error(),
+    };
     let node_name = Ident::new("Node", Span::mixed_site());
     let decoder_name = Ident::new("D", Span::mixed_site());
 
-    match parsed {
-        ParsedInfo::Struct(struct_info) => {
-            impl_for_struct(ast, struct_info, &node_name, &decoder_name)
+    // If any field borrows from the decoder, we implement `FromClvmRef` instead of `FromClvm`.
+    // The borrow is tied to the type's first lifetime parameter.
+    let borrows = match &parsed {
+        ParsedInfo::Struct(struct_info) => struct_info.fields.iter().any(|field| field.borrow),
+        ParsedInfo::Enum(enum_info) => enum_info
+            .variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .any(|field| field.borrow),
+    };
+
+    let lifetime = if borrows {
+        match ast.generics.lifetimes().next() {
+            Some(param) => Some(param.lifetime.clone()),
+            None => {
+                return syn::Error::new(
+                    ast.ident.span(),
+                    "types with `borrow` fields must have a lifetime parameter to borrow for",
+                )
+                .to_compile_error()
+            }
         }
-        ParsedInfo::Enum(enum_info) => impl_for_enum(ast, &enum_info, &node_name, &decoder_name),
+    } else {
+        None
+    };
+
+    let names = Names {
+        node: &node_name,
+        decoder: &decoder_name,
+        lifetime: lifetime.as_ref(),
+    };
+
+    match parsed {
+        ParsedInfo::Struct(struct_info) => impl_for_struct(ast, struct_info, &names),
+        ParsedInfo::Enum(enum_info) => impl_for_enum(ast, &enum_info, &names),
     }
 }
 
+/// The identifiers used throughout the generated implementation.
+struct Names<'a> {
+    node: &'a Ident,
+    decoder: &'a Ident,
+    /// The lifetime of the borrow, if the type implements `FromClvmRef`.
+    lifetime: Option<&'a Lifetime>,
+}
+
 struct ParsedFields {
     decoded_names: Vec<Ident>,
     decoded_values: Vec<TokenStream>,
@@ -29,10 +70,11 @@ struct ParsedFields {
 
 fn field_parser_fn_body(
     crate_name: &Ident,
-    decoder_name: &Ident,
+    names: &Names<'_>,
     fields: &[FieldInfo],
     repr: Repr,
 ) -> ParsedFields {
+    let decoder_name = names.decoder;
     let mut body = TokenStream::new();
 
     // Generate temporary names for the fields, used in the function body.
@@ -98,8 +140,14 @@ fn field_parser_fn_body(
         let ty = &field.ty;
 
         // This handles the actual decoding of the field's value.
-        let mut decoded_value = quote! {
-            <#ty as #crate_name::FromClvm<#decoder_name>>::from_clvm(decoder, #ident)
+        let mut decoded_value = if let Some(lifetime) = names.lifetime.filter(|_| field.borrow) {
+            quote! {
+                <#ty as #crate_name::FromClvmRef<#lifetime, #decoder_name>>::from_clvm_ref(decoder, #ident)
+            }
+        } else {
+            quote! {
+                <#ty as #crate_name::FromClvm<#decoder_name>>::from_clvm(decoder, #ident)
+            }
         };
 
         if let Some(default) = &field.optional_with_default {
@@ -133,6 +181,25 @@ fn field_parser_fn_body(
                     ));
                 }
             });
+        } else if let Some(validate) = &field.validate {
+            // If the field has a validator, we need to decode the value up front and check it before continuing.
+            // The default value, if the field isn't present, is checked as well.
+            body.extend(quote! {
+                let #ident: #ty = #decoded_value;
+
+                if let ::std::result::Result::Err(error) = (#validate)(&#ident) {
+                    return Err(#crate_name::FromClvmError::Custom(
+                        format!(
+                            "invalid value for field `{}`: {}",
+                            stringify!(#field_ident),
+                            error,
+                        )
+                    ));
+                }
+            });
+
+            decoded_names.push(field_ident);
+            decoded_values.push(quote!(#ident));
         } else {
             // Otherwise, we can include the field name and decoded value in the constructor.
             decoded_names.push(field_ident);
@@ -188,24 +255,14 @@ fn check_rest_value(crate_name: &Ident, repr: Repr) -> TokenStream {
     }
 }
 
-fn impl_for_struct(
-    ast: DeriveInput,
-    struct_info: StructInfo,
-    node_name: &Ident,
-    decoder_name: &Ident,
-) -> TokenStream {
+fn impl_for_struct(ast: DeriveInput, struct_info: StructInfo, names: &Names<'_>) -> TokenStream {
     let crate_name = crate_name(struct_info.crate_name);
 
     let ParsedFields {
         decoded_names,
         decoded_values,
         mut body,
-    } = field_parser_fn_body(
-        &crate_name,
-        decoder_name,
-        &struct_info.fields,
-        struct_info.repr,
-    );
+    } = field_parser_fn_body(&crate_name, names, &struct_info.fields, struct_info.repr);
 
     // Generate the constructor for the return value, if all parsing was successful.
     match struct_info.kind {
@@ -226,21 +283,17 @@ fn impl_for_struct(
         }
     }
 
-    trait_impl(ast, &crate_name, node_name, decoder_name, &body)
+    trait_impl(ast, &crate_name, names, &body)
 }
 
-fn impl_for_enum(
-    ast: DeriveInput,
-    enum_info: &EnumInfo,
-    node_name: &Ident,
-    decoder_name: &Ident,
-) -> TokenStream {
+fn impl_for_enum(ast: DeriveInput, enum_info: &EnumInfo, names: &Names<'_>) -> TokenStream {
     let crate_name = crate_name(enum_info.crate_name.clone());
+    let decoder_name = names.decoder;
 
     let mut body = TokenStream::new();
 
     if enum_info.is_untagged {
-        let variant_parsers = enum_variant_parsers(&crate_name, node_name, decoder_name, enum_info);
+        let variant_parsers = enum_variant_parsers(&crate_name, names, enum_info);
 
         // If the enum is untagged, we need to try each variant parser until one succeeds.
         for parser in variant_parsers {
@@ -282,8 +335,7 @@ fn impl_for_enum(
                 }
             });
         } else {
-            let variant_parsers =
-                enum_variant_parsers(&crate_name, node_name, decoder_name, enum_info);
+            let variant_parsers = enum_variant_parsers(&crate_name, names, enum_info);
 
             let decode_next = match enum_info.default_repr {
                 Repr::Atom | Repr::Transparent => unreachable!(),
@@ -315,15 +367,15 @@ fn impl_for_enum(
         }
     }
 
-    trait_impl(ast, &crate_name, node_name, decoder_name, &body)
+    trait_impl(ast, &crate_name, names, &body)
 }
 
 fn enum_variant_parsers(
     crate_name: &Ident,
-    node_name: &Ident,
-    decoder_name: &Ident,
+    names: &Names<'_>,
     enum_info: &EnumInfo,
 ) -> Vec<TokenStream> {
+    let node_name = names.node;
     let mut variant_parsers = Vec::new();
 
     for variant in &enum_info.variants {
@@ -334,7 +386,7 @@ fn enum_variant_parsers(
             decoded_names,
             decoded_values,
             mut body,
-        } = field_parser_fn_body(crate_name, decoder_name, &variant.fields, repr);
+        } = field_parser_fn_body(crate_name, names, &variant.fields, repr);
 
         match variant.kind {
             VariantKind::Unit => {
@@ -367,15 +419,16 @@ fn enum_variant_parsers(
     variant_parsers
 }
 
-// This generates the `FromClvm` trait implementation and augments generics with the `FromClvm` bound.
+// This generates the `FromClvm` (or `FromClvmRef`) trait implementation and augments generics with the `FromClvm` bound.
 fn trait_impl(
     mut ast: DeriveInput,
     crate_name: &Ident,
-    node_name: &Ident,
-    decoder_name: &Ident,
+    names: &Names<'_>,
     body: &TokenStream,
 ) -> TokenStream {
     let type_name = ast.ident;
+    let node_name = names.node;
+    let decoder_name = names.decoder;
 
     // Every generic type must implement `FromClvm` as well in order for the derived type to implement `FromClvm`.
     // This isn't always perfect, but it's how derive macros work.
@@ -399,15 +452,30 @@ fn trait_impl(
     let (impl_generics, _, _) = ast.generics.split_for_impl();
 
     // Generate the final trait implementation.
-    quote! {
-        #[automatically_derived]
-        impl #impl_generics #crate_name::FromClvm<#decoder_name>
-        for #type_name #ty_generics #where_clause {
-            fn from_clvm(
-                decoder: &#decoder_name,
-                mut node: #node_name,
-            ) -> ::std::result::Result<Self, #crate_name::FromClvmError> {
-                #body
+    if let Some(lifetime) = names.lifetime {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #crate_name::FromClvmRef<#lifetime, #decoder_name>
+            for #type_name #ty_generics #where_clause {
+                fn from_clvm_ref(
+                    decoder: &#lifetime #decoder_name,
+                    mut node: #node_name,
+                ) -> ::std::result::Result<Self, #crate_name::FromClvmError> {
+                    #body
+                }
+            }
+        }
+    } else {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #crate_name::FromClvm<#decoder_name>
+            for #type_name #ty_generics #where_clause {
+                fn from_clvm(
+                    decoder: &#decoder_name,
+                    mut node: #node_name,
+                ) -> ::std::result::Result<Self, #crate_name::FromClvmError> {
+                    #body
+                }
             }
         }
     }
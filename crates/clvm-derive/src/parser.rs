@@ -17,12 +17,21 @@ pub enum ParsedInfo {
     Enum(EnumInfo),
 }
 
-pub fn parse(derive: &'static str, ast: &DeriveInput) -> ParsedInfo {
-    let options = parse_clvm_options(&ast.attrs);
+pub fn parse(derive: &'static str, ast: &DeriveInput) -> syn::Result<ParsedInfo> {
+    let options = parse_clvm_options(&ast.attrs)?;
 
     match &ast.data {
-        Data::Struct(data_struct) => ParsedInfo::Struct(parse_struct(options, data_struct)),
-        Data::Enum(data_enum) => ParsedInfo::Enum(parse_enum(options, data_enum)),
-        Data::Union(..) => panic!("cannot derive `{derive}` for a union"),
+        Data::Struct(data_struct) => Ok(ParsedInfo::Struct(parse_struct(
+            options,
+            &ast.ident,
+            data_struct,
+        )?)),
+        Data::Enum(data_enum) => Ok(ParsedInfo::Enum(parse_enum(
+            options, &ast.ident, data_enum,
+        )?)),
+        Data::Union(data_union) => Err(syn::Error::new(
+            data_union.union_token.span,
+            format!("cannot derive `{derive}` for a union"),
+        )),
     }
 }
@@ -1,5 +1,6 @@
 use std::fmt;
 
+use proc_macro2::Span;
 use syn::{
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
@@ -22,10 +23,13 @@ pub enum Repr {
 }
 
 impl Repr {
-    pub fn expect(repr: Option<Repr>) -> Repr {
-        repr.expect(
-            "missing either `list`, `curry`, `solution`, `transparent`, or `atom` in `clvm` attribute options",
-        )
+    pub fn expect(repr: Option<Repr>, span: Span) -> syn::Result<Repr> {
+        repr.ok_or_else(|| {
+            syn::Error::new(
+                span,
+                "missing either `list`, `curry`, `solution`, `transparent`, or `atom` in `clvm` attribute options",
+            )
+        })
     }
 }
 
@@ -60,53 +64,94 @@ pub struct ClvmOptions {
     pub default: Option<Option<Expr>>,
     /// Whether the field is a rest field, which will consume the rest of the CLVM object.
     pub rest: bool,
+    /// Whether the field borrows from the decoder, using `FromClvmRef` instead of `FromClvm`.
+    pub borrow: bool,
+    /// A function which is called with a reference to the decoded value of the field.
+    /// If it returns an error, decoding fails with an error naming the field.
+    pub validate: Option<Expr>,
+    /// The option names as they were written, so errors can point at the offending option.
+    idents: Vec<Ident>,
+}
+
+impl ClvmOptions {
+    /// Creates an error pointing at the given option, if it was set.
+    pub fn error(&self, option: &str, message: impl fmt::Display) -> syn::Error {
+        let span = self
+            .idents
+            .iter()
+            .find(|ident| *ident == option)
+            .map_or_else(Span::call_site, Ident::span);
+        syn::Error::new(span, message)
+    }
 }
 
+const VALID_OPTIONS: &str = "`list`, `solution`, `curry`, `transparent`, `atom`, `untagged`, `constant`, `crate_name`, `default`, `rest`, `borrow`, or `validate`";
+
 /// All of the possible options of the `clvm` attribute.
-enum ClvmOption {
+enum ClvmOptionKind {
     Repr(Repr),
     Constant(Expr),
     CrateName(Ident),
     Untagged,
     Default(Option<Expr>),
     Rest,
+    Borrow,
+    Validate(Expr),
+}
+
+/// A single option of the `clvm` attribute, along with its name for error reporting.
+struct ClvmOption {
+    ident: Ident,
+    kind: ClvmOptionKind,
 }
 
 impl Parse for ClvmOption {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
         let ident = input.parse::<Ident>()?;
 
-        match ident.to_string().as_str() {
-            "list" => Ok(Self::Repr(Repr::List)),
-            "solution" => Ok(Self::Repr(Repr::Solution)),
-            "curry" => Ok(Self::Repr(Repr::Curry)),
-            "transparent" => Ok(Self::Repr(Repr::Transparent)),
-            "atom" => Ok(Self::Repr(Repr::Atom)),
-            "untagged" => Ok(Self::Untagged),
+        let kind = match ident.to_string().as_str() {
+            "list" => ClvmOptionKind::Repr(Repr::List),
+            "solution" => ClvmOptionKind::Repr(Repr::Solution),
+            "curry" => ClvmOptionKind::Repr(Repr::Curry),
+            "transparent" => ClvmOptionKind::Repr(Repr::Transparent),
+            "atom" => ClvmOptionKind::Repr(Repr::Atom),
+            "untagged" => ClvmOptionKind::Untagged,
             "constant" => {
                 input.parse::<Token![=]>()?;
-                Ok(Self::Constant(input.parse()?))
+                ClvmOptionKind::Constant(input.parse()?)
             }
             "crate_name" => {
                 input.parse::<Token![=]>()?;
-                Ok(Self::CrateName(input.parse()?))
+                ClvmOptionKind::CrateName(input.parse()?)
             }
             "default" => {
                 if input.peek(Token![=]) {
                     input.parse::<Token![=]>()?;
-                    Ok(Self::Default(Some(input.parse()?)))
+                    ClvmOptionKind::Default(Some(input.parse()?))
                 } else {
-                    Ok(Self::Default(None))
+                    ClvmOptionKind::Default(None)
                 }
             }
-            "rest" => Ok(Self::Rest),
-            _ => Err(syn::Error::new(ident.span(), "unknown argument")),
-        }
+            "rest" => ClvmOptionKind::Rest,
+            "borrow" => ClvmOptionKind::Borrow,
+            "validate" => {
+                input.parse::<Token![=]>()?;
+                ClvmOptionKind::Validate(input.parse()?)
+            }
+            name => {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("unknown `clvm` option `{name}`, expected one of {VALID_OPTIONS}"),
+                ))
+            }
+        };
+
+        Ok(Self { ident, kind })
     }
 }
 
 /// Parses the `clvm` attribute options and `repr` option from the given attributes.
-pub fn parse_clvm_options(attrs: &[Attribute]) -> ClvmOptions {
+pub fn parse_clvm_options(attrs: &[Attribute]) -> syn::Result<ClvmOptions> {
     let mut options = ClvmOptions {
         repr: None,
         constant: None,
@@ -115,6 +160,9 @@ pub fn parse_clvm_options(attrs: &[Attribute]) -> ClvmOptions {
         crate_name: None,
         default: None,
         rest: false,
+        borrow: false,
+        validate: None,
+        idents: Vec::new(),
     };
 
     for attr in attrs {
@@ -123,7 +171,10 @@ pub fn parse_clvm_options(attrs: &[Attribute]) -> ClvmOptions {
         };
 
         if ident == "repr" {
-            let repr = attr.parse_args::<Ident>().unwrap();
+            // Reprs with multiple items, such as `#[repr(C, packed)]`, aren't discriminant types.
+            let Ok(repr) = attr.parse_args::<Ident>() else {
+                continue;
+            };
             let text = repr.to_string();
             let text = text.as_str();
 
@@ -144,42 +195,36 @@ pub fn parse_clvm_options(attrs: &[Attribute]) -> ClvmOptions {
             continue;
         }
 
-        let parsed_options = attr
-            .parse_args_with(Punctuated::<ClvmOption, Token![,]>::parse_terminated)
-            .unwrap_or_else(|error| panic!("failed to parse `clvm` attribute options: {error}"));
-
-        for option in parsed_options {
-            match option {
-                ClvmOption::Untagged => {
-                    assert!(!options.untagged, "duplicate `untagged` option");
-                    options.untagged = true;
-                }
-                ClvmOption::Repr(repr) => {
-                    assert!(options.repr.is_none(), "duplicate repr option `{repr}`");
-                    options.repr = Some(repr);
-                }
-                ClvmOption::Constant(value) => {
-                    assert!(options.constant.is_none(), "duplicate `constant` option");
-                    options.constant = Some(value);
-                }
-                ClvmOption::CrateName(crate_name) => {
-                    assert!(
-                        options.crate_name.is_none(),
-                        "duplicate `crate_name` option"
-                    );
-                    options.crate_name = Some(crate_name);
-                }
-                ClvmOption::Default(default) => {
-                    assert!(options.default.is_none(), "duplicate `default` option");
-                    options.default = Some(default);
-                }
-                ClvmOption::Rest => {
-                    assert!(!options.rest, "duplicate `rest` option");
-                    options.rest = true;
+        let parsed_options =
+            attr.parse_args_with(Punctuated::<ClvmOption, Token![,]>::parse_terminated)?;
+
+        for ClvmOption { ident, kind } in parsed_options {
+            let is_repr = matches!(kind, ClvmOptionKind::Repr(..));
+            let duplicate = match kind {
+                ClvmOptionKind::Untagged => std::mem::replace(&mut options.untagged, true),
+                ClvmOptionKind::Repr(repr) => options.repr.replace(repr).is_some(),
+                ClvmOptionKind::Constant(value) => options.constant.replace(value).is_some(),
+                ClvmOptionKind::CrateName(crate_name) => {
+                    options.crate_name.replace(crate_name).is_some()
                 }
+                ClvmOptionKind::Default(default) => options.default.replace(default).is_some(),
+                ClvmOptionKind::Rest => std::mem::replace(&mut options.rest, true),
+                ClvmOptionKind::Borrow => std::mem::replace(&mut options.borrow, true),
+                ClvmOptionKind::Validate(validate) => options.validate.replace(validate).is_some(),
+            };
+
+            if duplicate {
+                let message = if is_repr {
+                    format!("duplicate repr option `{ident}`")
+                } else {
+                    format!("duplicate `{ident}` option")
+                };
+                return Err(syn::Error::new(ident.span(), message));
             }
+
+            options.idents.push(ident);
         }
     }
 
-    options
+    Ok(options)
 }
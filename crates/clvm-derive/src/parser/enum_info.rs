@@ -10,26 +10,37 @@ pub struct EnumInfo {
     pub crate_name: Option<Ident>,
 }
 
-pub fn parse_enum(mut options: ClvmOptions, data_enum: &DataEnum) -> EnumInfo {
-    assert!(
-        options.constant.is_none(),
-        "`constant` only applies to fields"
-    );
+pub fn parse_enum(
+    mut options: ClvmOptions,
+    name: &Ident,
+    data_enum: &DataEnum,
+) -> syn::Result<EnumInfo> {
+    if options.constant.is_some() {
+        return Err(options.error("constant", "`constant` only applies to fields"));
+    }
+
+    if options.default.is_some() {
+        return Err(options.error("default", "`default` only applies to fields"));
+    }
 
-    assert!(
-        options.default.is_none(),
-        "`default` only applies to fields"
-    );
+    if options.rest {
+        return Err(options.error("rest", "`rest` only applies to fields"));
+    }
+
+    if options.borrow {
+        return Err(options.error("borrow", "`borrow` only applies to fields"));
+    }
 
-    assert!(!options.rest, "`rest` only applies to fields");
+    if options.validate.is_some() {
+        return Err(options.error("validate", "`validate` only applies to fields"));
+    }
 
-    let repr = Repr::expect(options.repr);
+    let repr = Repr::expect(options.repr, name.span())?;
 
     if repr == Repr::Transparent {
-        assert!(
-            !options.untagged,
-            "`transparent` enums are implicitly untagged"
-        );
+        if options.untagged {
+            return Err(options.error("untagged", "`transparent` enums are implicitly untagged"));
+        }
 
         options.untagged = true;
     }
@@ -37,33 +48,43 @@ pub fn parse_enum(mut options: ClvmOptions, data_enum: &DataEnum) -> EnumInfo {
     let mut variants = Vec::new();
 
     for variant in &data_enum.variants {
-        let variant_options = parse_clvm_options(&variant.attrs);
+        let variant_options = parse_clvm_options(&variant.attrs)?;
         let variant_repr = variant_options.repr;
 
-        assert!(
-            !(repr == Repr::Atom && variant_repr.is_some()),
-            "cannot override `atom` representation for individual enum variants"
-        );
-
-        assert!(
-            repr != Repr::Atom || variant.fields.is_empty(),
-            "cannot have fields in an `atom` enum variant"
-        );
+        if let Some(variant_repr) = variant_repr {
+            if repr == Repr::Atom {
+                return Err(variant_options.error(
+                    &variant_repr.to_string(),
+                    "cannot override `atom` representation for individual enum variants",
+                ));
+            }
+
+            if !options.untagged {
+                return Err(variant_options.error(
+                    &variant_repr.to_string(),
+                    "cannot specify representation for individual enum variants in a tagged enum",
+                ));
+            }
+        }
 
-        assert!(
-            options.untagged || variant_repr.is_none(),
-            "cannot specify representation for individual enum variants in a tagged enum"
-        );
+        if repr == Repr::Atom && !variant.fields.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &variant.fields,
+                "cannot have fields in an `atom` enum variant",
+            ));
+        }
 
-        let mut variant_info = parse_variant(&variant_options, variant);
+        let mut variant_info = parse_variant(&variant_options, variant)?;
 
         if (repr == Repr::Transparent && variant_repr.is_none())
             || variant_repr == Some(Repr::Transparent)
         {
-            assert!(
-                variant_info.fields.len() == 1,
-                "`transparent` enum variants must have exactly one field"
-            );
+            if variant_info.fields.len() != 1 {
+                return Err(syn::Error::new(
+                    variant.ident.span(),
+                    "`transparent` enum variants must have exactly one field",
+                ));
+            }
 
             variant_info.fields[0].rest = true;
             variant_info.repr = Some(Repr::List);
@@ -72,11 +93,11 @@ pub fn parse_enum(mut options: ClvmOptions, data_enum: &DataEnum) -> EnumInfo {
         variants.push(variant_info);
     }
 
-    EnumInfo {
+    Ok(EnumInfo {
         variants,
         discriminant_type: options.enum_repr,
         is_untagged: options.untagged,
         default_repr: repr,
         crate_name: options.crate_name,
-    }
+    })
 }
@@ -1,4 +1,4 @@
-use syn::{spanned::Spanned, Expr, FieldsNamed, FieldsUnnamed, Ident, Type};
+use syn::{spanned::Spanned, Expr, Field, FieldsNamed, FieldsUnnamed, Ident, Type};
 
 use super::{parse_clvm_options, ClvmOptions};
 
@@ -8,68 +8,56 @@ pub struct FieldInfo {
     pub constant: Option<Expr>,
     pub optional_with_default: Option<Option<Expr>>,
     pub rest: bool,
+    pub borrow: bool,
+    pub validate: Option<Expr>,
 }
 
-pub fn parse_named_fields(fields: &FieldsNamed) -> Vec<FieldInfo> {
-    let mut items = Vec::new();
-
-    let mut rest = false;
-    let mut optional = false;
-
-    for field in &fields.named {
-        let ident = field.ident.clone().unwrap();
-        let ty = field.ty.clone();
-
-        let options = parse_clvm_options(&field.attrs);
-        check_field_options(&options);
-
-        assert!(
-            !rest,
-            "nothing can come after the `rest` field, since it consumes all arguments"
-        );
-
-        assert!(
-            !optional,
-            "only the last field can be optional, to prevent ambiguity"
-        );
-
-        rest = options.rest;
-        optional = options.default.is_some();
-
-        items.push(FieldInfo {
-            ident,
-            ty,
-            constant: options.constant,
-            optional_with_default: options.default,
-            rest: options.rest,
-        });
-    }
+pub fn parse_named_fields(fields: &FieldsNamed) -> syn::Result<Vec<FieldInfo>> {
+    parse_fields(
+        fields
+            .named
+            .iter()
+            .map(|field| (field.ident.clone().unwrap(), field)),
+    )
+}
 
-    items
+pub fn parse_unnamed_fields(fields: &FieldsUnnamed) -> syn::Result<Vec<FieldInfo>> {
+    parse_fields(
+        fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, field)| (Ident::new(&format!("field_{i}"), field.span()), field)),
+    )
 }
 
-pub fn parse_unnamed_fields(fields: &FieldsUnnamed) -> Vec<FieldInfo> {
+fn parse_fields<'a>(
+    fields: impl Iterator<Item = (Ident, &'a Field)>,
+) -> syn::Result<Vec<FieldInfo>> {
     let mut items = Vec::new();
 
     let mut rest = false;
     let mut optional = false;
 
-    for (i, field) in fields.unnamed.iter().enumerate() {
-        let ident = Ident::new(&format!("field_{i}"), field.span());
+    for (ident, field) in fields {
         let ty = field.ty.clone();
 
-        let options = parse_clvm_options(&field.attrs);
-        check_field_options(&options);
+        let options = parse_clvm_options(&field.attrs)?;
+        check_field_options(&options)?;
 
-        assert!(
-            !rest,
-            "nothing can come after the `rest` field, since it consumes all arguments"
-        );
+        if rest {
+            return Err(syn::Error::new_spanned(
+                field,
+                "nothing can come after the `rest` field, since it consumes all arguments",
+            ));
+        }
 
-        assert!(
-            !optional,
-            "only the last field can be optional, to prevent ambiguity"
-        );
+        if optional {
+            return Err(syn::Error::new_spanned(
+                field,
+                "only the last field can be optional, to prevent ambiguity",
+            ));
+        }
 
         rest = options.rest;
         optional = options.default.is_some();
@@ -80,33 +68,51 @@ pub fn parse_unnamed_fields(fields: &FieldsUnnamed) -> Vec<FieldInfo> {
             constant: options.constant,
             optional_with_default: options.default,
             rest: options.rest,
+            borrow: options.borrow,
+            validate: options.validate,
         });
     }
 
-    items
+    Ok(items)
 }
 
-fn check_field_options(options: &ClvmOptions) {
-    assert!(!options.untagged, "`untagged` only applies to enums");
+fn check_field_options(options: &ClvmOptions) -> syn::Result<()> {
+    if options.untagged {
+        return Err(options.error("untagged", "`untagged` only applies to enums"));
+    }
 
-    assert!(options.enum_repr.is_none(), "`repr` only applies to enums");
+    if let Some(enum_repr) = &options.enum_repr {
+        return Err(syn::Error::new(
+            enum_repr.span(),
+            "`repr` only applies to enums",
+        ));
+    }
 
     if let Some(repr) = options.repr {
-        panic!("`{repr}` can't be set on individual fields");
+        return Err(options.error(
+            &repr.to_string(),
+            format!("`{repr}` can't be set on individual fields"),
+        ));
+    }
+
+    if options.crate_name.is_some() {
+        return Err(options.error(
+            "crate_name",
+            "`crate_name` can't be set on individual fields",
+        ));
     }
 
-    assert!(
-        options.crate_name.is_none(),
-        "`crate_name` can't be set on individual fields"
-    );
+    if options.default.is_some() && options.constant.is_some() {
+        return Err(options.error("constant", "`default` can't be used with `constant` set"));
+    }
 
-    assert!(
-        !(options.default.is_some() && options.constant.is_some()),
-        "`default` can't be used with `constant` set"
-    );
+    if options.default.is_some() && options.rest {
+        return Err(options.error("rest", "`default` can't be used with `rest` option set"));
+    }
+
+    if options.validate.is_some() && options.constant.is_some() {
+        return Err(options.error("validate", "`validate` can't be used with `constant` set"));
+    }
 
-    assert!(
-        !(options.default.is_some() && options.rest),
-        "`default` can't be used with `rest` option set"
-    );
+    Ok(())
 }
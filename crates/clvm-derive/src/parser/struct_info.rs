@@ -16,52 +16,70 @@ pub enum StructKind {
     Named,
 }
 
-pub fn parse_struct(options: ClvmOptions, data_struct: &DataStruct) -> StructInfo {
-    assert!(!options.untagged, "`untagged` only applies to enums");
+pub fn parse_struct(
+    options: ClvmOptions,
+    name: &Ident,
+    data_struct: &DataStruct,
+) -> syn::Result<StructInfo> {
+    if options.untagged {
+        return Err(options.error("untagged", "`untagged` only applies to enums"));
+    }
 
-    assert!(options.enum_repr.is_none(), "`repr` only applies to enums");
+    if let Some(enum_repr) = &options.enum_repr {
+        return Err(syn::Error::new(
+            enum_repr.span(),
+            "`repr` only applies to enums",
+        ));
+    }
 
-    assert!(
-        options.constant.is_none(),
-        "`constant` only applies to fields"
-    );
+    if options.constant.is_some() {
+        return Err(options.error("constant", "`constant` only applies to fields"));
+    }
+
+    if options.default.is_some() {
+        return Err(options.error("default", "`default` only applies to fields"));
+    }
 
-    assert!(
-        options.default.is_none(),
-        "`default` only applies to fields"
-    );
+    if options.rest {
+        return Err(options.error("rest", "`rest` only applies to fields"));
+    }
 
-    assert!(!options.rest, "`rest` only applies to fields");
+    if options.borrow {
+        return Err(options.error("borrow", "`borrow` only applies to fields"));
+    }
 
-    let mut repr = Repr::expect(options.repr);
+    if options.validate.is_some() {
+        return Err(options.error("validate", "`validate` only applies to fields"));
+    }
 
-    assert!(
-        repr != Repr::Atom,
-        "`atom` is not a valid representation for structs"
-    );
+    let mut repr = Repr::expect(options.repr, name.span())?;
 
-    let crate_name = options.crate_name;
+    if repr == Repr::Atom {
+        return Err(options.error("atom", "`atom` is not a valid representation for structs"));
+    }
 
     let (kind, mut fields) = match &data_struct.fields {
         Fields::Unit => (StructKind::Unit, Vec::new()),
-        Fields::Named(fields) => (StructKind::Named, parse_named_fields(fields)),
-        Fields::Unnamed(fields) => (StructKind::Unnamed, parse_unnamed_fields(fields)),
+        Fields::Named(fields) => (StructKind::Named, parse_named_fields(fields)?),
+        Fields::Unnamed(fields) => (StructKind::Unnamed, parse_unnamed_fields(fields)?),
     };
 
     if repr == Repr::Transparent {
-        assert!(
-            fields.len() == 1,
-            "`transparent` structs must have exactly one field"
-        );
+        if fields.len() != 1 {
+            return Err(syn::Error::new(
+                name.span(),
+                "`transparent` structs must have exactly one field",
+            ));
+        }
 
         fields[0].rest = true;
         repr = Repr::List;
     }
 
-    StructInfo {
+    Ok(StructInfo {
         kind,
         fields,
         repr,
-        crate_name,
-    }
+        crate_name: options.crate_name,
+    })
 }
@@ -17,49 +17,68 @@ pub enum VariantKind {
     Named,
 }
 
-pub fn parse_variant(options: &ClvmOptions, variant: &Variant) -> VariantInfo {
-    assert!(!options.untagged, "`untagged` only applies to enums");
+pub fn parse_variant(options: &ClvmOptions, variant: &Variant) -> syn::Result<VariantInfo> {
+    if options.untagged {
+        return Err(options.error("untagged", "`untagged` only applies to enums"));
+    }
+
+    if let Some(enum_repr) = &options.enum_repr {
+        return Err(syn::Error::new(
+            enum_repr.span(),
+            "`repr` only applies to enums",
+        ));
+    }
 
-    assert!(options.enum_repr.is_none(), "`repr` only applies to enums");
+    if options.constant.is_some() {
+        return Err(options.error("constant", "`constant` only applies to fields"));
+    }
+
+    if options.crate_name.is_some() {
+        return Err(options.error(
+            "crate_name",
+            "`crate_name` can't be set on individual enum variants",
+        ));
+    }
 
-    assert!(
-        options.constant.is_none(),
-        "`constant` only applies to fields"
-    );
+    if options.default.is_some() {
+        return Err(options.error("default", "`default` only applies to fields"));
+    }
 
-    assert!(
-        options.crate_name.is_none(),
-        "`crate_name` can't be set on individual enum variants"
-    );
+    if options.rest {
+        return Err(options.error("rest", "`rest` only applies to fields"));
+    }
 
-    assert!(
-        options.default.is_none(),
-        "`default` only applies to fields"
-    );
+    if options.borrow {
+        return Err(options.error("borrow", "`borrow` only applies to fields"));
+    }
 
-    assert!(!options.rest, "`rest` only applies to fields");
+    if options.validate.is_some() {
+        return Err(options.error("validate", "`validate` only applies to fields"));
+    }
 
     let name = variant.ident.clone();
     let discriminant = variant.discriminant.clone().map(|(_, expr)| expr);
 
     let repr = options.repr;
 
-    assert!(
-        !(repr == Some(Repr::Atom)),
-        "`atom` is not a valid representation for individual enum variants"
-    );
+    if repr == Some(Repr::Atom) {
+        return Err(options.error(
+            "atom",
+            "`atom` is not a valid representation for individual enum variants",
+        ));
+    }
 
     let (kind, fields) = match &variant.fields {
         Fields::Unit => (VariantKind::Unit, Vec::new()),
-        Fields::Named(fields) => (VariantKind::Named, parse_named_fields(fields)),
-        Fields::Unnamed(fields) => (VariantKind::Unnamed, parse_unnamed_fields(fields)),
+        Fields::Named(fields) => (VariantKind::Named, parse_named_fields(fields)?),
+        Fields::Unnamed(fields) => (VariantKind::Unnamed, parse_unnamed_fields(fields)?),
     };
 
-    VariantInfo {
+    Ok(VariantInfo {
         kind,
         name,
         fields,
         discriminant,
         repr,
-    }
+    })
 }
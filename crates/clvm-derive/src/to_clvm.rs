@@ -9,7 +9,10 @@ use crate::{
 };
 
 pub fn to_clvm(ast: DeriveInput) -> TokenStream {
-    let parsed = parse("ToClvm", &ast);
+    let parsed = match parse("ToClvm", &ast) {
+        Ok(parsed) => parsed,
+        Err(error) => return error.to_compile_error(),
+    };
     let node_name = Ident::new("Node", Span::mixed_site());
     let encoder_name = Ident::new("E", Span::mixed_site());
 
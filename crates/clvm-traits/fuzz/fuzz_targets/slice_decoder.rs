@@ -0,0 +1,31 @@
+#![no_main]
+
+use std::fmt::Debug;
+
+use clvm_traits::{FromClvm, FromClvmBytes, SliceDecoder};
+use clvmr::{serde::node_from_bytes, Allocator};
+use libfuzzer_sys::fuzz_target;
+
+// If the slice decoder accepts the serialization, the allocator must accept it too,
+// and both must decode it the same way.
+fn check<T>(data: &[u8])
+where
+    T: Debug + PartialEq + FromClvmBytes + FromClvm<Allocator>,
+{
+    let actual = T::from_clvm_bytes(data);
+    if SliceDecoder::new(data).root().is_err() {
+        return;
+    }
+    let mut a = Allocator::new();
+    let node = node_from_bytes(&mut a, data).expect("valid serialization");
+    assert_eq!(actual, T::from_clvm(&a, node));
+}
+
+fuzz_target!(|data: &[u8]| {
+    check::<u64>(data);
+    check::<i32>(data);
+    check::<String>(data);
+    check::<Vec<u8>>(data);
+    check::<Option<(u32, Vec<Option<String>>)>>(data);
+    check::<((u8, u8), [i64; 2])>(data);
+});
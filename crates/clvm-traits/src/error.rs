@@ -25,6 +25,9 @@ pub enum FromClvmError {
     #[error("expected pair")]
     ExpectedPair,
 
+    #[error("invalid serialization")]
+    InvalidSerialization,
+
     #[error("{0}")]
     Custom(String),
 }
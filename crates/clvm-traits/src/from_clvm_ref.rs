@@ -0,0 +1,176 @@
+use std::borrow::Cow;
+
+use clvmr::Atom;
+
+use crate::{ClvmDecoder, FromClvm, FromClvmError};
+
+/// Decodes a value which may borrow from the decoder's atom storage for the lifetime `'a`.
+///
+/// This is the borrowed counterpart of [`FromClvm`]. It's useful for large atoms such as
+/// metadata strings, which would otherwise be copied out of the allocator just to be read.
+/// The derive macro implements this trait instead of `FromClvm` if any field is marked
+/// with `#[clvm(borrow)]`.
+///
+/// Atoms that the decoder stores inline, such as small integers in an `Allocator`,
+/// can't be borrowed. Decoding those as a [`Cow`] copies them into an owned value.
+pub trait FromClvmRef<'a, D>: Sized
+where
+    D: ClvmDecoder,
+{
+    fn from_clvm_ref(decoder: &'a D, node: D::Node) -> Result<Self, FromClvmError>;
+}
+
+impl<'a, N, D: ClvmDecoder<Node = N>> FromClvmRef<'a, D> for Atom<'a> {
+    fn from_clvm_ref(decoder: &'a D, node: N) -> Result<Self, FromClvmError> {
+        decoder.decode_atom(&node)
+    }
+}
+
+impl<'a, N, D: ClvmDecoder<Node = N>> FromClvmRef<'a, D> for Cow<'a, [u8]> {
+    fn from_clvm_ref(decoder: &'a D, node: N) -> Result<Self, FromClvmError> {
+        match decoder.decode_atom(&node)? {
+            Atom::Borrowed(bytes) => Ok(Cow::Borrowed(bytes)),
+            atom @ Atom::U32(..) => Ok(Cow::Owned(atom.as_ref().to_vec())),
+        }
+    }
+}
+
+impl<'a, N, D: ClvmDecoder<Node = N>> FromClvmRef<'a, D> for Cow<'a, str> {
+    fn from_clvm_ref(decoder: &'a D, node: N) -> Result<Self, FromClvmError> {
+        match Cow::<'a, [u8]>::from_clvm_ref(decoder, node)? {
+            Cow::Borrowed(bytes) => match std::str::from_utf8(bytes) {
+                Ok(text) => Ok(Cow::Borrowed(text)),
+                // Only copy on the error path, to report it the same way as `String` does.
+                Err(_) => Err(String::from_utf8(bytes.to_vec()).unwrap_err().into()),
+            },
+            Cow::Owned(bytes) => Ok(Cow::Owned(String::from_utf8(bytes)?)),
+        }
+    }
+}
+
+impl<'a, N, D: ClvmDecoder<Node = N>, A, B> FromClvmRef<'a, D> for (A, B)
+where
+    A: FromClvmRef<'a, D>,
+    B: FromClvmRef<'a, D>,
+{
+    fn from_clvm_ref(decoder: &'a D, node: N) -> Result<Self, FromClvmError> {
+        let (first, rest) = decoder.decode_pair(&node)?;
+        let first = A::from_clvm_ref(decoder, first)?;
+        let rest = B::from_clvm_ref(decoder, rest)?;
+        Ok((first, rest))
+    }
+}
+
+impl<'a, N, D: ClvmDecoder<Node = N>, T> FromClvmRef<'a, D> for Vec<T>
+where
+    T: FromClvmRef<'a, D>,
+{
+    fn from_clvm_ref(decoder: &'a D, mut node: N) -> Result<Self, FromClvmError> {
+        let mut items = Vec::new();
+        loop {
+            if let Ok((first, rest)) = decoder.decode_pair(&node) {
+                items.push(T::from_clvm_ref(decoder, first)?);
+                node = rest;
+            } else {
+                let bytes = decoder.decode_atom(&node)?;
+                if bytes.as_ref().is_empty() {
+                    return Ok(items);
+                }
+
+                return Err(FromClvmError::WrongAtomLength {
+                    expected: 0,
+                    found: bytes.as_ref().len(),
+                });
+            }
+        }
+    }
+}
+
+impl<'a, N, D: ClvmDecoder<Node = N>, T> FromClvmRef<'a, D> for Option<T>
+where
+    T: FromClvmRef<'a, D>,
+{
+    fn from_clvm_ref(decoder: &'a D, node: N) -> Result<Self, FromClvmError> {
+        if let Ok(atom) = decoder.decode_atom(&node) {
+            if atom.as_ref().is_empty() {
+                return Ok(None);
+            }
+        }
+        Ok(Some(T::from_clvm_ref(decoder, node)?))
+    }
+}
+
+/// A wrapper to decode an owned [`FromClvm`] value where a [`FromClvmRef`] is expected,
+/// such as an integer inside of a borrowed pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Owned<T>(pub T);
+
+impl<'a, N, D: ClvmDecoder<Node = N>, T> FromClvmRef<'a, D> for Owned<T>
+where
+    T: FromClvm<D>,
+{
+    fn from_clvm_ref(decoder: &'a D, node: N) -> Result<Self, FromClvmError> {
+        Ok(Self(T::from_clvm(decoder, node)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clvmr::{serde::node_from_bytes, Allocator};
+
+    use super::*;
+
+    fn decode<'a, T>(a: &'a mut Allocator, hex: &str) -> Result<T, FromClvmError>
+    where
+        T: FromClvmRef<'a, Allocator>,
+    {
+        let actual = node_from_bytes(a, &hex::decode(hex).unwrap()).unwrap();
+        T::from_clvm_ref(a, actual)
+    }
+
+    #[test]
+    fn test_bytes() {
+        let a = &mut Allocator::new();
+        let bytes = decode::<Cow<'_, [u8]>>(a, "8568656c6c6f").unwrap();
+        assert!(matches!(bytes, Cow::Borrowed(b"hello")));
+
+        // Small atoms are stored inline, so they have to be copied.
+        let a = &mut Allocator::new();
+        let bytes = decode::<Cow<'_, [u8]>>(a, "01").unwrap();
+        assert!(matches!(bytes, Cow::Owned(ref bytes) if bytes == &[1]));
+
+        let a = &mut Allocator::new();
+        assert_eq!(
+            decode::<Cow<'_, [u8]>>(a, "ff8080"),
+            Err(FromClvmError::ExpectedAtom)
+        );
+    }
+
+    #[test]
+    fn test_str() {
+        let a = &mut Allocator::new();
+        let text = decode::<Cow<'_, str>>(a, "8568656c6c6f").unwrap();
+        assert!(matches!(text, Cow::Borrowed("hello")));
+
+        let a = &mut Allocator::new();
+        assert!(matches!(
+            decode::<Cow<'_, str>>(a, "82ffff"),
+            Err(FromClvmError::InvalidUtf8(..))
+        ));
+    }
+
+    #[test]
+    fn test_list() {
+        let a = &mut Allocator::new();
+        let items = decode::<Vec<Cow<'_, str>>>(a, "ff8568656c6c6fff85776f726c6480").unwrap();
+        assert_eq!(items, ["hello", "world"]);
+        assert!(items.iter().all(|item| matches!(item, Cow::Borrowed(..))));
+
+        let a = &mut Allocator::new();
+        let pair = decode::<(Cow<'_, str>, Owned<u32>)>(a, "ff8568656c6c6f8200ff").unwrap();
+        assert_eq!(pair, (Cow::Borrowed("hello"), Owned(255)));
+
+        let a = &mut Allocator::new();
+        assert_eq!(decode::<Option<Cow<'_, str>>>(a, "80").unwrap(), None);
+    }
+}
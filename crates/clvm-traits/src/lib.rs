@@ -13,9 +13,11 @@ mod clvm_decoder;
 mod clvm_encoder;
 mod error;
 mod from_clvm;
+mod from_clvm_ref;
 mod int_encoding;
 mod macros;
 mod match_byte;
+mod slice_decoder;
 mod to_clvm;
 mod wrappers;
 
@@ -23,8 +25,10 @@ pub use clvm_decoder::*;
 pub use clvm_encoder::*;
 pub use error::*;
 pub use from_clvm::*;
+pub use from_clvm_ref::*;
 pub use int_encoding::*;
 pub use match_byte::*;
+pub use slice_decoder::*;
 pub use to_clvm::*;
 pub use wrappers::*;
 
@@ -43,17 +47,21 @@ mod derive_tests {
 
     fn check<T>(value: &T, expected: &str)
     where
-        T: Debug + PartialEq + ToClvm<Allocator> + FromClvm<Allocator>,
+        T: Debug + PartialEq + ToClvm<Allocator> + FromClvm<Allocator> + FromClvmBytes,
     {
         let a = &mut Allocator::new();
 
         let ptr = value.to_clvm(a).unwrap();
 
         let actual = node_to_bytes(a, ptr).unwrap();
-        assert_eq!(expected, hex::encode(actual));
+        assert_eq!(expected, hex::encode(&actual));
 
         let round_trip = T::from_clvm(a, ptr).unwrap();
         assert_eq!(value, &round_trip);
+
+        // Decoding straight from the serialization must give the same value.
+        let from_bytes = T::from_clvm_bytes(&actual).unwrap();
+        assert_eq!(value, &from_bytes);
     }
 
     fn coerce_into<A, B>(value: A) -> B
@@ -188,6 +196,67 @@ mod derive_tests {
         check(&Struct("XYZ".to_string()), "8358595a");
     }
 
+    #[test]
+    fn test_transparent_struct() {
+        #[derive(Debug, ToClvm, FromClvm, PartialEq)]
+        #[clvm(transparent)]
+        struct Amount(u64);
+
+        #[derive(Debug, ToClvm, FromClvm, PartialEq)]
+        #[clvm(transparent)]
+        struct Named {
+            amount: u64,
+        }
+
+        // Encodes exactly like the inner value.
+        check(&1000_u64, "8203e8");
+        check(&Amount(1000), "8203e8");
+        check(&Named { amount: 1000 }, "8203e8");
+
+        assert_eq!(coerce_into::<Amount, u64>(Amount(1000)), 1000);
+        assert_eq!(coerce_into::<u64, Named>(1000), Named { amount: 1000 });
+    }
+
+    #[test]
+    fn test_transparent_generic() {
+        #[derive(Debug, ToClvm, FromClvm, PartialEq)]
+        #[clvm(transparent)]
+        struct Wrapper<T>(T);
+
+        check(&Wrapper(1000_u64), "8203e8");
+        check(&Wrapper((1_u8, 2_u8)), "ff0102");
+        check(&Wrapper(Wrapper("XYZ".to_string())), "8358595a");
+    }
+
+    #[test]
+    fn test_transparent_in_curry() {
+        #[derive(Debug, ToClvm, FromClvm, PartialEq)]
+        #[clvm(transparent)]
+        struct Amount(u64);
+
+        #[derive(Debug, ToClvm, FromClvm, PartialEq)]
+        #[clvm(transparent)]
+        struct Delta {
+            value: i32,
+        }
+
+        #[derive(Debug, ToClvm, FromClvm, PartialEq)]
+        #[clvm(curry)]
+        struct Struct {
+            a: Amount,
+            b: Delta,
+        }
+
+        // The same as `test_curry_struct`, which uses the bare values.
+        check(
+            &Struct {
+                a: Amount(52),
+                b: Delta { value: -32 },
+            },
+            "ff04ffff0134ffff04ffff0181e0ff018080",
+        );
+    }
+
     #[test]
     fn test_optional() {
         #[derive(Debug, ToClvm, FromClvm, PartialEq)]
@@ -365,6 +434,201 @@ mod derive_tests {
         );
     }
 
+    fn decode<T>(value: &T) -> Result<T, FromClvmError>
+    where
+        T: Debug + PartialEq + ToClvm<Allocator> + FromClvm<Allocator> + FromClvmBytes,
+    {
+        let a = &mut Allocator::new();
+        let ptr = value.to_clvm(a).unwrap();
+        let result = T::from_clvm(a, ptr);
+
+        // Decoding straight from the serialization must fail the same way.
+        let bytes = node_to_bytes(a, ptr).unwrap();
+        assert_eq!(T::from_clvm_bytes(&bytes), result);
+
+        result
+    }
+
+    fn validate_error(field: &str, message: &str) -> FromClvmError {
+        FromClvmError::Custom(format!("invalid value for field `{field}`: {message}"))
+    }
+
+    fn at_most_3(items: &[u8]) -> Result<(), String> {
+        if items.len() > 3 {
+            return Err(format!("expected at most 3 items, found {}", items.len()));
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    fn non_zero(value: &u32) -> Result<(), &'static str> {
+        if *value == 0 {
+            return Err("must not be zero");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() {
+        #[derive(Debug, ToClvm, FromClvm, PartialEq)]
+        #[clvm(list)]
+        struct Struct {
+            a: u8,
+            #[clvm(validate = at_most_3)]
+            b: Vec<u8>,
+        }
+
+        check(
+            &Struct {
+                a: 1,
+                b: vec![1, 2, 3],
+            },
+            "ff01ffff01ff02ff038080",
+        );
+
+        // Encoding isn't validated, only decoding.
+        let value = Struct {
+            a: 1,
+            b: vec![1, 2, 3, 4],
+        };
+        assert_eq!(
+            decode(&value).unwrap_err(),
+            validate_error("b", "expected at most 3 items, found 4")
+        );
+    }
+
+    #[test]
+    fn test_validate_default_and_rest() {
+        #[derive(Debug, ToClvm, FromClvm, PartialEq)]
+        #[clvm(list)]
+        struct Optional {
+            #[clvm(validate = non_zero)]
+            a: u32,
+            #[clvm(default = 1, validate = non_zero)]
+            b: u32,
+        }
+
+        #[derive(Debug, ToClvm, FromClvm, PartialEq)]
+        #[clvm(list)]
+        struct Rest {
+            a: u32,
+            #[clvm(rest, validate = at_most_3)]
+            b: Vec<u8>,
+        }
+
+        check(&Optional { a: 5, b: 1 }, "ff0580");
+        check(&Optional { a: 5, b: 6 }, "ff05ff0680");
+        assert_eq!(
+            decode(&Optional { a: 0, b: 6 }).unwrap_err(),
+            validate_error("a", "must not be zero")
+        );
+        assert_eq!(
+            decode(&Optional { a: 5, b: 0 }).unwrap_err(),
+            validate_error("b", "must not be zero")
+        );
+
+        check(
+            &Rest {
+                a: 0,
+                b: vec![1, 2],
+            },
+            "ff80ff01ff0280",
+        );
+        assert_eq!(
+            decode(&Rest {
+                a: 0,
+                b: vec![1, 2, 3, 4]
+            })
+            .unwrap_err(),
+            validate_error("b", "expected at most 3 items, found 4")
+        );
+    }
+
+    #[test]
+    fn test_validate_enum() {
+        #[derive(Debug, ToClvm, FromClvm, PartialEq)]
+        #[clvm(list)]
+        enum Enum {
+            A(#[clvm(validate = non_zero)] u32),
+            B {
+                #[clvm(validate = at_most_3)]
+                items: Vec<u8>,
+            },
+            C(u32),
+        }
+
+        check(&Enum::A(1), "ff80ff0180");
+        check(&Enum::C(0), "ff02ff8080");
+        assert_eq!(
+            decode(&Enum::A(0)).unwrap_err(),
+            validate_error("field_0", "must not be zero")
+        );
+        assert_eq!(
+            decode(&Enum::B {
+                items: vec![1, 2, 3, 4]
+            })
+            .unwrap_err(),
+            validate_error("items", "expected at most 3 items, found 4")
+        );
+    }
+
+    #[test]
+    fn test_validate_untagged() {
+        // Untagged enums fall through to the next variant if validation fails.
+        #[derive(Debug, ToClvm, FromClvm, PartialEq)]
+        #[clvm(list, untagged)]
+        enum Untagged {
+            NonZero(#[clvm(validate = non_zero)] u32),
+            Any(u32),
+        }
+
+        assert_eq!(
+            coerce_into::<Untagged, Untagged>(Untagged::NonZero(5)),
+            Untagged::NonZero(5)
+        );
+        assert_eq!(
+            coerce_into::<Untagged, Untagged>(Untagged::NonZero(0)),
+            Untagged::Any(0)
+        );
+    }
+
+    #[cfg(feature = "chia-bls")]
+    #[test]
+    fn test_validate_curry() {
+        use chia_bls::{PublicKey, SecretKey};
+
+        fn not_infinity(public_key: &PublicKey) -> Result<(), &'static str> {
+            if public_key.is_inf() {
+                return Err("public key is the point at infinity");
+            }
+            Ok(())
+        }
+
+        #[derive(Debug, ToClvm, FromClvm, PartialEq)]
+        #[clvm(curry)]
+        struct Args {
+            #[clvm(validate = not_infinity)]
+            synthetic_key: PublicKey,
+            amount: u64,
+        }
+
+        let synthetic_key = SecretKey::from_seed(&[1; 32]).public_key();
+        let args = Args {
+            synthetic_key,
+            amount: 1000,
+        };
+        assert_eq!(decode(&args).unwrap(), args);
+
+        let args = Args {
+            synthetic_key: PublicKey::default(),
+            amount: 1000,
+        };
+        assert_eq!(
+            decode(&args).unwrap_err(),
+            validate_error("synthetic_key", "public key is the point at infinity")
+        );
+    }
+
     #[test]
     fn test_custom_crate_name() {
         use clvm_traits as clvm_traits2;
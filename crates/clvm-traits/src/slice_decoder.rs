@@ -0,0 +1,256 @@
+use clvmr::Atom;
+
+use crate::{ClvmDecoder, FromClvm, FromClvmError};
+
+/// A [`ClvmDecoder`] that reads values directly from their serialized form, without an
+/// [`Allocator`](clvmr::Allocator).
+///
+/// Atoms are borrowed from the serialized bytes, so decoding them doesn't allocate.
+/// This is useful for small values such as hints and memos, where building an allocator
+/// just to decode a few atoms would be most of the work.
+///
+/// Only the plain serialization is supported, not back-references. Since each pair is
+/// walked to find where its first item ends, decoding a value whose first items are
+/// deeply nested trees is slower than with an allocator.
+#[derive(Debug, Clone, Copy)]
+pub struct SliceDecoder<'a> {
+    bytes: &'a [u8],
+}
+
+/// A node in a [`SliceDecoder`], which is the serialization of the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SliceNode<'a>(&'a [u8]);
+
+impl<'a> SliceNode<'a> {
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+impl<'a> SliceDecoder<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns the node that the bytes are the serialization of. It fails if the
+    /// serialization is invalid, or if there are any bytes left over after it.
+    pub fn root(&self) -> Result<SliceNode<'a>, FromClvmError> {
+        if node_len(self.bytes)? != self.bytes.len() {
+            return Err(FromClvmError::InvalidSerialization);
+        }
+        Ok(SliceNode(self.bytes))
+    }
+}
+
+impl<'a> ClvmDecoder for SliceDecoder<'a> {
+    type Node = SliceNode<'a>;
+
+    fn decode_atom(&self, node: &Self::Node) -> Result<Atom<'_>, FromClvmError> {
+        if node.0.first() == Some(&PAIR) {
+            return Err(FromClvmError::ExpectedAtom);
+        }
+        let bytes = node.0;
+        let (start, end) = atom_bounds(bytes)?;
+        Ok(Atom::Borrowed(&bytes[start..end]))
+    }
+
+    fn decode_pair(&self, node: &Self::Node) -> Result<(Self::Node, Self::Node), FromClvmError> {
+        if node.0.first() != Some(&PAIR) {
+            return Err(FromClvmError::ExpectedPair);
+        }
+        let (first, rest) = node.0[1..].split_at(node_len(&node.0[1..])?);
+        Ok((SliceNode(first), SliceNode(rest)))
+    }
+}
+
+impl<'a> FromClvm<SliceDecoder<'a>> for SliceNode<'a> {
+    fn from_clvm(_decoder: &SliceDecoder<'a>, node: SliceNode<'a>) -> Result<Self, FromClvmError> {
+        Ok(node)
+    }
+}
+
+/// Decodes a value directly from its serialized CLVM, using a [`SliceDecoder`].
+pub trait FromClvmBytes: Sized {
+    fn from_clvm_bytes(bytes: &[u8]) -> Result<Self, FromClvmError>;
+}
+
+impl<T> FromClvmBytes for T
+where
+    T: for<'a> FromClvm<SliceDecoder<'a>>,
+{
+    fn from_clvm_bytes(bytes: &[u8]) -> Result<Self, FromClvmError> {
+        let decoder = SliceDecoder::new(bytes);
+        let root = decoder.root()?;
+        T::from_clvm(&decoder, root)
+    }
+}
+
+const PAIR: u8 = 0xff;
+const BACK_REFERENCE: u8 = 0xfe;
+const MAX_SINGLE_BYTE: u8 = 0x7f;
+
+// The same limit on the size of an atom as when deserializing into an allocator.
+const MAX_ATOM_SIZE: u64 = 0x4_0000_0000;
+
+// Returns the start and end of the atom that bytes starts with, in bytes.
+fn atom_bounds(bytes: &[u8]) -> Result<(usize, usize), FromClvmError> {
+    let Some(&first) = bytes.first() else {
+        return Err(FromClvmError::InvalidSerialization);
+    };
+    if first <= MAX_SINGLE_BYTE {
+        return Ok((0, 1));
+    }
+
+    // The number of leading ones is the number of bytes of the size, including the first.
+    let size_len = first.leading_ones() as usize;
+    if size_len > 6 || bytes.len() < size_len {
+        return Err(FromClvmError::InvalidSerialization);
+    }
+    let mut size = u64::from(first & (0xff >> size_len));
+    for byte in &bytes[1..size_len] {
+        size = (size << 8) | u64::from(*byte);
+    }
+    if size >= MAX_ATOM_SIZE || size > (bytes.len() - size_len) as u64 {
+        return Err(FromClvmError::InvalidSerialization);
+    }
+    Ok((size_len, size_len + size as usize))
+}
+
+// Returns the length of the serialization of the node that bytes starts with.
+fn node_len(bytes: &[u8]) -> Result<usize, FromClvmError> {
+    let mut pos = 0;
+    // The number of nodes left to read. A pair replaces itself with its two items.
+    let mut pending = 1_usize;
+    while pending > 0 {
+        match bytes.get(pos) {
+            Some(&PAIR) => {
+                pos += 1;
+                pending += 1;
+            }
+            Some(&BACK_REFERENCE) | None => return Err(FromClvmError::InvalidSerialization),
+            Some(_) => {
+                pos += atom_bounds(&bytes[pos..])?.1;
+                pending -= 1;
+            }
+        }
+    }
+    Ok(pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use clvmr::{serde::node_from_bytes, Allocator};
+
+    use super::*;
+
+    fn decode<T>(hex: &str) -> Result<T, FromClvmError>
+    where
+        T: FromClvmBytes,
+    {
+        T::from_clvm_bytes(&hex::decode(hex).unwrap())
+    }
+
+    // Decodes the value both with a slice decoder and an allocator, which must agree.
+    fn check<T>(hex: &str) -> Result<T, FromClvmError>
+    where
+        T: Debug + PartialEq + FromClvmBytes + FromClvm<Allocator>,
+    {
+        let bytes = hex::decode(hex).unwrap();
+        let a = &mut Allocator::new();
+        let node = node_from_bytes(a, &bytes).unwrap();
+        let expected = T::from_clvm(a, node);
+        let actual = T::from_clvm_bytes(&bytes);
+        assert_eq!(actual, expected);
+        actual
+    }
+
+    #[test]
+    fn test_atoms() {
+        assert_eq!(check::<u32>("80"), Ok(0));
+        assert_eq!(check::<u32>("01"), Ok(1));
+        assert_eq!(check::<u32>("8200ff"), Ok(255));
+        assert_eq!(check::<i32>("81ff"), Ok(-1));
+        assert_eq!(check::<u64>("8900ffffffffffffffff"), Ok(u64::MAX));
+        assert!(check::<u8>("820100").is_err());
+        assert_eq!(check::<bool>("01"), Ok(true));
+        assert_eq!(check::<String>("8568656c6c6f"), Ok("hello".to_string()));
+        assert_eq!(check::<()>("80"), Ok(()));
+        assert_eq!(check::<u32>("ff8080"), Err(FromClvmError::ExpectedAtom));
+
+        // An atom with a two byte size.
+        let hello = "68656c6c6f".repeat(20);
+        assert_eq!(
+            check::<String>(&format!("c064{hello}")),
+            Ok("hello".repeat(20))
+        );
+    }
+
+    #[test]
+    fn test_pairs() {
+        assert_eq!(check::<(u8, u8)>("ff0102"), Ok((1, 2)));
+        assert_eq!(check::<Vec<u8>>("ff01ff02ff0380"), Ok(vec![1, 2, 3]));
+        assert_eq!(check::<Vec<u8>>("80"), Ok(vec![]));
+        assert_eq!(
+            check::<Vec<u8>>("ff01ff0203"),
+            Err(FromClvmError::WrongAtomLength {
+                expected: 0,
+                found: 1
+            })
+        );
+        assert_eq!(check::<[u8; 2]>("ff01ff0280"), Ok([1, 2]));
+        assert_eq!(check::<Option<u8>>("80"), Ok(None));
+        assert_eq!(check::<Option<u8>>("05"), Ok(Some(5)));
+        assert_eq!(
+            check::<(Vec<u8>, Vec<String>)>("ffff01ff0280ff8361626380"),
+            Ok((vec![1, 2], vec!["abc".to_string()]))
+        );
+        assert_eq!(check::<(u8, u8)>("01"), Err(FromClvmError::ExpectedPair));
+    }
+
+    #[test]
+    fn test_borrowed_atoms() {
+        let bytes = hex::decode("ff8568656c6c6fff0180").unwrap();
+        let decoder = SliceDecoder::new(&bytes);
+        let root = decoder.root().unwrap();
+        let (first, rest) = decoder.decode_pair(&root).unwrap();
+        let Atom::Borrowed(hello) = decoder.decode_atom(&first).unwrap() else {
+            panic!("expected a borrowed atom");
+        };
+        assert_eq!(hello, b"hello");
+        assert!(std::ptr::eq(hello, &bytes[2..7]));
+        assert_eq!(rest.as_bytes(), &bytes[7..]);
+
+        // Even small atoms are borrowed.
+        let (one, _) = decoder.decode_pair(&rest).unwrap();
+        assert!(matches!(decoder.decode_atom(&one), Ok(Atom::Borrowed([1]))));
+    }
+
+    #[test]
+    fn test_invalid_serialization() {
+        for hex in [
+            // Empty input.
+            "", // Truncated atoms and pairs.
+            "82ff", "c0", "ff01", "ff", // Back-references aren't supported.
+            "fe01", "ff01fe02", // Trailing bytes.
+            "0101", "ff010280",
+        ] {
+            assert_eq!(
+                decode::<SliceDecoderTest>(hex),
+                Err(FromClvmError::InvalidSerialization),
+                "{hex}"
+            );
+        }
+    }
+
+    // Accepts any node, to test the serialization itself.
+    #[derive(Debug, PartialEq)]
+    struct SliceDecoderTest;
+
+    impl<D: ClvmDecoder> FromClvm<D> for SliceDecoderTest {
+        fn from_clvm(_decoder: &D, _node: D::Node) -> Result<Self, FromClvmError> {
+            Ok(Self)
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::{rc::Rc, sync::Arc};
+use std::{borrow::Cow, rc::Rc, sync::Arc};
 
 use clvmr::Atom;
 use num_bigint::BigInt;
@@ -159,6 +159,20 @@ impl<N, E: ClvmEncoder<Node = N>> ToClvm<E> for String {
     }
 }
 
+impl<N, E: ClvmEncoder<Node = N>> ToClvm<E> for Cow<'_, str> {
+    fn to_clvm(&self, encoder: &mut E) -> Result<N, ToClvmError> {
+        self.as_ref().to_clvm(encoder)
+    }
+}
+
+/// Unlike `&[u8]` and `Vec<u8>`, which are encoded as lists of integers,
+/// this is encoded as a single atom, to match `FromClvmRef`.
+impl<N, E: ClvmEncoder<Node = N>> ToClvm<E> for Cow<'_, [u8]> {
+    fn to_clvm(&self, encoder: &mut E) -> Result<N, ToClvmError> {
+        encoder.encode_atom(Atom::Borrowed(self))
+    }
+}
+
 #[cfg(feature = "chia-bls")]
 impl<N, E: ClvmEncoder<Node = N>> ToClvm<E> for chia_bls::PublicKey {
     fn to_clvm(&self, encoder: &mut E) -> Result<N, ToClvmError> {
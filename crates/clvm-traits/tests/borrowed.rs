@@ -0,0 +1,126 @@
+#![cfg(feature = "derive")]
+// the allocation counting global allocator needs unsafe code
+#![allow(unsafe_code)]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    borrow::Cow,
+    cell::Cell,
+};
+
+use clvm_traits::{FromClvm, FromClvmRef, ToClvm};
+use clvmr::Allocator;
+
+/// Counts the allocations made by the current thread, so tests running in parallel don't interfere.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count_allocation() {
+    // This can fail while the thread is being torn down, in which case we don't care.
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_allocation();
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let value = f();
+    (value, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[derive(Debug, PartialEq, Eq, ToClvm, FromClvm)]
+#[clvm(list)]
+struct NftMetadata<'a> {
+    #[clvm(borrow)]
+    data_uri: Cow<'a, str>,
+    #[clvm(borrow)]
+    data_hash: Cow<'a, [u8]>,
+    #[clvm(borrow)]
+    metadata_uri: Cow<'a, str>,
+    #[clvm(borrow)]
+    metadata_hash: Cow<'a, [u8]>,
+    #[clvm(borrow)]
+    license_uri: Cow<'a, str>,
+    #[clvm(borrow)]
+    license_hash: Cow<'a, [u8]>,
+    edition_number: u64,
+    edition_total: u64,
+}
+
+fn metadata() -> NftMetadata<'static> {
+    NftMetadata {
+        data_uri: Cow::Borrowed("https://example.com/nft/data.png"),
+        data_hash: Cow::Owned(vec![0x11; 32]),
+        metadata_uri: Cow::Borrowed("https://example.com/nft/metadata.json"),
+        metadata_hash: Cow::Owned(vec![0x22; 32]),
+        license_uri: Cow::Borrowed("https://example.com/nft/license.pdf"),
+        license_hash: Cow::Owned(vec![0x33; 32]),
+        edition_number: 1,
+        edition_total: 100,
+    }
+}
+
+#[test]
+fn test_borrowed_nft_metadata() {
+    let a = &mut Allocator::new();
+    let expected = metadata();
+    let ptr = expected.to_clvm(a).unwrap();
+    let a: &Allocator = a;
+
+    let (decoded, count) = allocations(|| NftMetadata::from_clvm_ref(a, ptr).unwrap());
+    assert_eq!(count, 0);
+    assert_eq!(decoded, expected);
+
+    for field in [
+        &decoded.data_uri,
+        &decoded.metadata_uri,
+        &decoded.license_uri,
+    ] {
+        assert!(matches!(field, Cow::Borrowed(..)));
+    }
+    for field in [
+        &decoded.data_hash,
+        &decoded.metadata_hash,
+        &decoded.license_hash,
+    ] {
+        assert!(matches!(field, Cow::Borrowed(..)));
+    }
+}
+
+#[test]
+fn test_borrowed_uris() {
+    let a = &mut Allocator::new();
+    let uris = ["https://example.com/a.png", "https://example.com/b.png"];
+    let ptr = uris.to_clvm(a).unwrap();
+    let a: &Allocator = a;
+
+    // Only the list itself is allocated, not the strings in it.
+    let (decoded, count) = allocations(|| Vec::<Cow<'_, str>>::from_clvm_ref(a, ptr).unwrap());
+    assert_eq!(count, 1);
+    assert_eq!(decoded, uris);
+
+    // Make sure the counter actually observes the copies made by `FromClvm`.
+    let (decoded, count) = allocations(|| Vec::<String>::from_clvm(a, ptr).unwrap());
+    assert_eq!(count, 3);
+    assert_eq!(decoded, uris);
+}
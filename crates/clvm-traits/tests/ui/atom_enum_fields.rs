@@ -0,0 +1,10 @@
+use clvm_traits::ToClvm;
+
+#[derive(ToClvm)]
+#[clvm(atom)]
+enum Foo {
+    A,
+    B(u8),
+}
+
+fn main() {}
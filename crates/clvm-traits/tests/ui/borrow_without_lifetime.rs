@@ -0,0 +1,12 @@
+use std::borrow::Cow;
+
+use clvm_traits::FromClvm;
+
+#[derive(FromClvm)]
+#[clvm(list)]
+struct Foo {
+    #[clvm(borrow)]
+    a: Cow<'static, str>,
+}
+
+fn main() {}
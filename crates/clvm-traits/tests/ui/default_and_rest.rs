@@ -0,0 +1,11 @@
+use clvm_traits::ToClvm;
+
+#[derive(ToClvm)]
+#[clvm(list)]
+struct Foo {
+    a: u8,
+    #[clvm(default, rest)]
+    b: Option<u8>,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use clvm_traits::ToClvm;
+
+#[derive(ToClvm)]
+#[clvm(list)]
+struct Foo {
+    #[clvm(rest)]
+    a: u8,
+    b: u8,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use clvm_traits::ToClvm;
+
+#[derive(ToClvm)]
+struct Foo {
+    a: u8,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use clvm_traits::ToClvm;
+
+#[derive(ToClvm)]
+#[clvm(transparent)]
+struct Foo {
+    a: u8,
+    b: u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use clvm_traits::ToClvm;
+
+#[derive(ToClvm)]
+#[clvm(list)]
+union Foo {
+    a: u8,
+}
+
+fn main() {}
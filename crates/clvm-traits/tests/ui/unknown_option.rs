@@ -0,0 +1,9 @@
+use clvm_traits::ToClvm;
+
+#[derive(ToClvm)]
+#[clvm(lsit)]
+struct Foo {
+    a: u8,
+}
+
+fn main() {}
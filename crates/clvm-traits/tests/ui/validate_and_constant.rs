@@ -0,0 +1,14 @@
+use clvm_traits::FromClvm;
+
+#[derive(FromClvm)]
+#[clvm(list)]
+struct Foo {
+    #[clvm(constant = 1, validate = check)]
+    a: u8,
+}
+
+fn check(_value: &u8) -> Result<(), String> {
+    Ok(())
+}
+
+fn main() {}
@@ -1,24 +1,77 @@
-use crate::{tree_hash_atom, tree_hash_pair, TreeHash};
+use hex_literal::hex;
 
-pub fn curry_tree_hash(program_hash: TreeHash, arg_hashes: &[TreeHash]) -> TreeHash {
-    let nil = tree_hash_atom(&[]);
-    let op_q = tree_hash_atom(&[1]);
-    let op_a = tree_hash_atom(&[2]);
-    let op_c = tree_hash_atom(&[4]);
+use crate::{tree_hash_pair, TreeHash, NIL_TREE_HASH, ONE_TREE_HASH};
+
+const OP_Q_TREE_HASH: TreeHash = ONE_TREE_HASH;
+
+const OP_A_TREE_HASH: TreeHash = TreeHash::new(hex!(
+    "a12871fee210fb8619291eaea194581cbd2531e4b23759d225f6806923f63222"
+));
 
-    let quoted_program = tree_hash_pair(op_q, program_hash);
-    let mut quoted_args = tree_hash_atom(&[1]);
+const OP_C_TREE_HASH: TreeHash = TreeHash::new(hex!(
+    "a8d5dd63fba471ebcb1f3e8f7c1e1879b7152a6e7298a91ce119a63400ade7c5"
+));
+
+pub fn curry_tree_hash(program_hash: TreeHash, arg_hashes: &[TreeHash]) -> TreeHash {
+    let quoted_program = tree_hash_pair(OP_Q_TREE_HASH, program_hash);
+    // the atom 1, i.e. the environment, has the same hash as the quote operator
+    let mut quoted_args = ONE_TREE_HASH;
 
     for &arg_hash in arg_hashes.iter().rev() {
-        let quoted_arg = tree_hash_pair(op_q, arg_hash);
-        let terminated_args = tree_hash_pair(quoted_args, nil);
+        let quoted_arg = tree_hash_pair(OP_Q_TREE_HASH, arg_hash);
+        let terminated_args = tree_hash_pair(quoted_args, NIL_TREE_HASH);
         let terminated_args = tree_hash_pair(quoted_arg, terminated_args);
-        quoted_args = tree_hash_pair(op_c, terminated_args);
+        quoted_args = tree_hash_pair(OP_C_TREE_HASH, terminated_args);
     }
 
-    let terminated_args = tree_hash_pair(quoted_args, nil);
+    let terminated_args = tree_hash_pair(quoted_args, NIL_TREE_HASH);
     let program_and_args = tree_hash_pair(quoted_program, terminated_args);
-    tree_hash_pair(op_a, program_and_args)
+    tree_hash_pair(OP_A_TREE_HASH, program_and_args)
+}
+
+/// Computes the tree hash of a puzzle curried with some fixed arguments
+/// followed by some final arguments that vary between calls, e.g. the CAT
+/// puzzle curried with the same TAIL and many different inner puzzles. The
+/// hashes that only depend on the puzzle and the fixed arguments are computed
+/// once, in `new()`.
+#[derive(Debug, Clone)]
+pub struct CurriedHasher {
+    quoted_program: TreeHash,
+    quoted_fixed_args: Vec<TreeHash>,
+}
+
+impl CurriedHasher {
+    pub fn new(program_hash: TreeHash, fixed_arg_hashes: &[TreeHash]) -> Self {
+        Self {
+            quoted_program: tree_hash_pair(OP_Q_TREE_HASH, program_hash),
+            quoted_fixed_args: fixed_arg_hashes
+                .iter()
+                .map(|&arg_hash| tree_hash_pair(OP_Q_TREE_HASH, arg_hash))
+                .collect(),
+        }
+    }
+
+    /// The same as `curry_tree_hash()` of the puzzle with the fixed arguments
+    /// followed by `arg_hashes`.
+    pub fn hash_with_final_args(&self, arg_hashes: &[TreeHash]) -> TreeHash {
+        // the atom 1, i.e. the environment, has the same hash as the quote operator
+        let mut quoted_args = ONE_TREE_HASH;
+
+        for quoted_arg in arg_hashes
+            .iter()
+            .rev()
+            .map(|&arg_hash| tree_hash_pair(OP_Q_TREE_HASH, arg_hash))
+            .chain(self.quoted_fixed_args.iter().rev().copied())
+        {
+            let terminated_args = tree_hash_pair(quoted_args, NIL_TREE_HASH);
+            let terminated_args = tree_hash_pair(quoted_arg, terminated_args);
+            quoted_args = tree_hash_pair(OP_C_TREE_HASH, terminated_args);
+        }
+
+        let terminated_args = tree_hash_pair(quoted_args, NIL_TREE_HASH);
+        let program_and_args = tree_hash_pair(self.quoted_program, terminated_args);
+        tree_hash_pair(OP_A_TREE_HASH, program_and_args)
+    }
 }
 
 #[cfg(test)]
@@ -26,11 +79,20 @@ mod tests {
     use clvm_traits::{clvm_curried_args, ToClvm};
     use clvmr::Allocator;
     use hex::ToHex;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
 
-    use crate::{tree_hash, CurriedProgram};
+    use crate::{tree_hash, tree_hash_atom, CurriedProgram};
 
     use super::*;
 
+    #[test]
+    fn test_operator_hashes() {
+        assert_eq!(OP_Q_TREE_HASH, tree_hash_atom(&[1]));
+        assert_eq!(OP_A_TREE_HASH, tree_hash_atom(&[2]));
+        assert_eq!(OP_C_TREE_HASH, tree_hash_atom(&[4]));
+    }
+
     #[test]
     fn test_equivalence() {
         let mut a = Allocator::new();
@@ -53,4 +115,28 @@ mod tests {
             curry_tree_hash_result.encode_hex::<String>()
         );
     }
+
+    #[test]
+    fn test_curried_hasher() {
+        let mut rng = StdRng::seed_from_u64(1337);
+        let program_hash = TreeHash::new(rng.gen());
+
+        for num_fixed in 0..5 {
+            for num_final in 0..3 {
+                for _ in 0..10 {
+                    let fixed: Vec<TreeHash> =
+                        (0..num_fixed).map(|_| TreeHash::new(rng.gen())).collect();
+                    let last: Vec<TreeHash> =
+                        (0..num_final).map(|_| TreeHash::new(rng.gen())).collect();
+                    let all: Vec<TreeHash> = fixed.iter().chain(&last).copied().collect();
+
+                    let hasher = CurriedHasher::new(program_hash, &fixed);
+                    assert_eq!(
+                        hasher.hash_with_final_args(&last),
+                        curry_tree_hash(program_hash, &all)
+                    );
+                }
+            }
+        }
+    }
 }
@@ -1,6 +1,8 @@
 use chia_sha2::Sha256;
 use clvmr::allocator::{Allocator, NodePtr, SExp};
 use clvmr::serde::node_from_bytes_backrefs_record;
+use hex_literal::hex;
+use std::array::TryFromSliceError;
 use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 use std::{fmt, io};
@@ -8,15 +10,39 @@ use std::{fmt, io};
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TreeHash([u8; 32]);
 
+/// The tree hash of nil (the empty atom), `sha256(1)`.
+pub const NIL_TREE_HASH: TreeHash = TreeHash::new(hex!(
+    "4bf5122f344554c53bde2ebb8cd2b7e3d1600ad631c385a5d7cce23c7785459a"
+));
+
+/// The tree hash of the atom `1`, `sha256(1 1)`. This is also the hash of the
+/// quote operator, and of the path to the whole environment.
+pub const ONE_TREE_HASH: TreeHash = TreeHash::new(hex!(
+    "9dcf97a184f32623d11a73124ceb99a5709b083721e878a16d78f596718ba7b2"
+));
+
 impl TreeHash {
+    /// All zeros. This isn't the hash of anything, but it's useful as a
+    /// placeholder.
+    pub const ZERO: Self = Self([0; 32]);
+
     pub const fn new(hash: [u8; 32]) -> Self {
         Self(hash)
     }
 
+    /// Fails unless `bytes` is exactly 32 bytes long.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TryFromSliceError> {
+        Ok(Self(bytes.try_into()?))
+    }
+
     pub const fn to_bytes(&self) -> [u8; 32] {
         self.0
     }
 
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
     pub fn to_vec(&self) -> Vec<u8> {
         self.0.to_vec()
     }
@@ -46,6 +72,14 @@ impl From<TreeHash> for [u8; 32] {
     }
 }
 
+impl TryFrom<&[u8]> for TreeHash {
+    type Error = TryFromSliceError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(bytes)
+    }
+}
+
 impl AsRef<[u8]> for TreeHash {
     fn as_ref(&self) -> &[u8] {
         &self.0
@@ -60,6 +94,70 @@ impl Deref for TreeHash {
     }
 }
 
+/// Human-readable formats (such as JSON) get a `0x`-prefixed hex string,
+/// binary formats get the raw bytes.
+#[cfg(feature = "serde")]
+impl serde::Serialize for TreeHash {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&format!("0x{self}"))
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TreeHash {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(TreeHashVisitor)
+        } else {
+            deserializer.deserialize_bytes(TreeHashVisitor)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct TreeHashVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for TreeHashVisitor {
+    type Value = TreeHash;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("32 bytes, or a hex string of them")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<TreeHash, E> {
+        let value = value.strip_prefix("0x").unwrap_or(value);
+        let mut hash = [0; 32];
+        hex::decode_to_slice(value, &mut hash).map_err(E::custom)?;
+        Ok(TreeHash(hash))
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, value: &[u8]) -> Result<TreeHash, E> {
+        let hash = value
+            .try_into()
+            .map_err(|_| E::invalid_length(value.len(), &self))?;
+        Ok(TreeHash(hash))
+    }
+
+    // Some binary formats represent bytes as a sequence of integers.
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<TreeHash, A::Error> {
+        let mut hash = [0; 32];
+        for (i, byte) in hash.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| serde::de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(serde::de::Error::invalid_length(33, &self));
+        }
+        Ok(TreeHash(hash))
+    }
+}
+
 enum TreeOp {
     SExp(NodePtr),
     Cons,
@@ -390,3 +488,110 @@ fn test_tree_hash_atom() {
         test_sha256_atom(&[0xff, val]);
     }
 }
+
+#[test]
+fn test_tree_hash_fmt() {
+    let hash = tree_hash_atom(&[]);
+    let hex = "4bf5122f344554c53bde2ebb8cd2b7e3d1600ad631c385a5d7cce23c7785459a";
+    assert_eq!(hash.to_string(), hex);
+    assert_eq!(format!("{hash:?}"), format!("TreeHash({hex})"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_tree_hash_serde() {
+    let hash = tree_hash_atom(&[1, 2, 3]);
+    let json = serde_json::to_string(&hash).unwrap();
+    assert_eq!(json, format!("\"0x{hash}\""));
+    assert_eq!(serde_json::from_str::<TreeHash>(&json).unwrap(), hash);
+
+    // the prefix is optional
+    let json = format!("\"{hash}\"");
+    assert_eq!(serde_json::from_str::<TreeHash>(&json).unwrap(), hash);
+
+    assert!(serde_json::from_str::<TreeHash>("\"0x1234\"").is_err());
+
+    let bytes = bincode::serialize(&hash).unwrap();
+    assert_eq!(bytes[8..], hash.to_bytes());
+    assert_eq!(bincode::deserialize::<TreeHash>(&bytes).unwrap(), hash);
+}
+
+#[test]
+fn test_tree_hash_consts() {
+    assert_eq!(NIL_TREE_HASH, tree_hash_atom(&[]));
+    assert_eq!(ONE_TREE_HASH, tree_hash_atom(&[1]));
+    assert_eq!(TreeHash::ZERO.to_bytes(), [0; 32]);
+}
+
+#[test]
+fn test_tree_hash_from_bytes_slice() {
+    let hash = tree_hash_atom(&[1, 2, 3]);
+    assert_eq!(TreeHash::from_bytes(&hash).unwrap(), hash);
+    assert_eq!(
+        TreeHash::try_from(hash.as_bytes().as_slice()).unwrap(),
+        hash
+    );
+    assert_eq!(TreeHash::new(hash.to_bytes()), hash);
+    assert_eq!(TreeHash::from_bytes(&hash.to_vec()).unwrap(), hash);
+
+    assert!(TreeHash::from_bytes(&[]).is_err());
+    assert!(TreeHash::from_bytes(&[0; 31]).is_err());
+    assert!(TreeHash::from_bytes(&[0; 33]).is_err());
+}
+
+#[cfg(test)]
+fn reference_tree_hash(a: &Allocator, node: NodePtr) -> [u8; 32] {
+    let mut sha256 = Sha256::new();
+    match a.sexp(node) {
+        SExp::Atom => {
+            sha256.update([1_u8]);
+            sha256.update(a.atom(node));
+        }
+        SExp::Pair(left, right) => {
+            sha256.update([2_u8]);
+            sha256.update(reference_tree_hash(a, left));
+            sha256.update(reference_tree_hash(a, right));
+        }
+    }
+    sha256.finalize()
+}
+
+#[cfg(test)]
+fn random_tree(a: &mut Allocator, rng: &mut impl rand::Rng, depth: u32) -> NodePtr {
+    if depth == 0 || rng.gen_range(0..3) == 0 {
+        let len = rng.gen_range(0..40);
+        let buf: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        return a.new_atom(&buf).unwrap();
+    }
+    let left = random_tree(a, rng, depth - 1);
+    // reuse the left subtree some of the time, to exercise the cache
+    let right = if rng.gen_range(0..4) == 0 {
+        left
+    } else {
+        random_tree(a, rng, depth - 1)
+    };
+    a.new_pair(left, right).unwrap()
+}
+
+#[test]
+fn test_tree_hash_random() {
+    use clvmr::serde::{node_to_bytes, node_to_bytes_backrefs};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let mut rng = StdRng::seed_from_u64(1337);
+    for _ in 0..200 {
+        let mut a = Allocator::new();
+        let node = random_tree(&mut a, &mut rng, 8);
+        let expected = TreeHash::new(reference_tree_hash(&a, node));
+
+        assert_eq!(tree_hash(&a, node), expected);
+        assert_eq!(TreeHash::from_bytes(&expected).unwrap(), expected);
+
+        let buf = node_to_bytes(&a, node).unwrap();
+        assert_eq!(tree_hash_from_bytes(&buf).unwrap(), expected);
+
+        let buf = node_to_bytes_backrefs(&a, node).unwrap();
+        assert_eq!(tree_hash_from_bytes(&buf).unwrap(), expected);
+    }
+}
@@ -0,0 +1,33 @@
+#!/usr/bin/env python3
+
+# compares from_json_dict() (with python's json module) against
+# from_json_bytes() on a large RespondAdditions message
+
+import json
+from random import Random
+from time import perf_counter
+
+from chia_rs import RespondAdditions
+from test_json_bytes import make_respond_additions
+
+NUM_COINS = 2000
+ITERATIONS = 20
+
+msg = make_respond_additions(Random(1337), NUM_COINS)
+data = json.dumps(msg.to_json_dict()).encode()
+print(f"RespondAdditions with {NUM_COINS} coins, {len(data)} bytes of JSON")
+
+start = perf_counter()
+for _ in range(ITERATIONS):
+    ret = RespondAdditions.from_json_dict(json.loads(data))
+    assert ret == msg
+dict_time = (perf_counter() - start) / ITERATIONS
+print(f"json.loads() + from_json_dict(): {dict_time * 1000:0.2f} ms")
+
+start = perf_counter()
+for _ in range(ITERATIONS):
+    ret = RespondAdditions.from_json_bytes(data)
+    assert ret == msg
+bytes_time = (perf_counter() - start) / ITERATIONS
+print(f"from_json_bytes():               {bytes_time * 1000:0.2f} ms")
+print(f"speed-up: {dict_time / bytes_time:0.1f}x")
@@ -1,11 +1,17 @@
 from typing import Optional
 from chia_rs import (
     additions_and_removals,
+    additions_and_removals_filtered,
+    block_coin_report,
+    BlockCoinReport,
     ALLOW_BACKREFS,
+    ValidationError,
 )
+from chia_rs.sized_bytes import bytes32
 from run_gen import DEFAULT_CONSTANTS
 from pathlib import Path
 import glob
+import pytest
 
 
 def test_additions_and_removals() -> None:
@@ -88,3 +94,124 @@ def test_additions_and_removals() -> None:
             assert expected_removals == set()
         except ValueError as e:
             assert "FAILED: " in test_file
+
+
+def test_block_coin_report() -> None:
+
+    farmer_ph = bytes32(b"\x01" * 32)
+    pool_ph = bytes32(b"\x02" * 32)
+
+    for g in sorted(glob.glob("generator-tests/*.txt")):
+        print(f"{Path(g).name}")
+
+        test_file = open(g, "r").read()
+        generator_hex, test_file = test_file.split("\n", 1)
+        generator = bytes.fromhex(generator_hex)
+
+        # only valid blocks may be passed to block_coin_report()
+        if "FAILED: " in test_file.split("STRICT:", 1)[0]:
+            continue
+
+        block_refs = []
+        try:
+            with open(g.replace(".txt", ".env"), "r") as f:
+                block_refs = [bytes.fromhex(f.read())]
+        except OSError:
+            pass
+
+        additions, removals = additions_and_removals(
+            generator, block_refs, ALLOW_BACKREFS, DEFAULT_CONSTANTS
+        )
+        report = block_coin_report(
+            generator,
+            block_refs,
+            ALLOW_BACKREFS,
+            1000,
+            farmer_ph,
+            pool_ph,
+            DEFAULT_CONSTANTS,
+        )
+
+        assert report.height == 1000
+        assert report.removals == removals
+        assert [(a.coin, a.hint) for a in report.additions] == additions
+        for a in report.additions:
+            if a.hint is not None:
+                assert a.memos[0] == a.hint
+
+        fees = sum(c.amount for c in removals) - sum(c.amount for c, _ in additions)
+        assert report.fees == fees
+        assert len(report.reward_coins) == 2
+        assert report.reward_coins[0].puzzle_hash == pool_ph
+        assert report.reward_coins[0].amount == 1750000000000
+        assert report.reward_coins[1].puzzle_hash == farmer_ph
+        assert report.reward_coins[1].amount == 250000000000 + fees
+
+        # the report can be persisted as-is
+        assert BlockCoinReport.from_bytes(bytes(report)) == report
+
+
+def test_additions_and_removals_filtered() -> None:
+
+    for g in sorted(glob.glob("generator-tests/*.txt")):
+        print(f"{Path(g).name}")
+
+        test_file = open(g, "r").read()
+        generator_hex, test_file = test_file.split("\n", 1)
+        generator = bytes.fromhex(generator_hex)
+
+        # only valid blocks may be passed to additions_and_removals()
+        if "FAILED: " in test_file.split("STRICT:", 1)[0]:
+            continue
+
+        block_refs = []
+        try:
+            with open(g.replace(".txt", ".env"), "r") as f:
+                block_refs = [bytes.fromhex(f.read())]
+        except OSError:
+            pass
+
+        additions, removals = additions_and_removals(
+            generator, block_refs, ALLOW_BACKREFS, DEFAULT_CONSTANTS
+        )
+        fees = sum(c.amount for c in removals) - sum(c.amount for c, _ in additions)
+
+        # watch every other puzzle hash and hint
+        puzzle_hashes = [c.puzzle_hash for c, _ in additions]
+        puzzle_hashes += [c.puzzle_hash for c in removals]
+        hints = [h for _, h in additions if h is not None and len(h) == 32]
+        watch_puzzle_hashes = frozenset(puzzle_hashes[::2])
+        watch_hints = set(bytes32(h) for h in hints[::2])
+
+        filtered_additions, filtered_removals, filtered_fees = (
+            additions_and_removals_filtered(
+                generator,
+                block_refs,
+                ALLOW_BACKREFS,
+                DEFAULT_CONSTANTS,
+                watch_puzzle_hashes,
+                watch_hints,
+            )
+        )
+        assert filtered_additions == [
+            (c, h)
+            for c, h in additions
+            if c.puzzle_hash in watch_puzzle_hashes or h in watch_hints
+        ]
+        assert filtered_removals == [
+            c for c in removals if c.puzzle_hash in watch_puzzle_hashes
+        ]
+        # the fees cover the whole block
+        assert filtered_fees == fees
+
+
+def test_additions_and_removals_error() -> None:
+    # (x) raises
+    with pytest.raises(ValidationError) as excinfo:
+        additions_and_removals(
+            bytes.fromhex("ff0880"), [], ALLOW_BACKREFS, DEFAULT_CONSTANTS
+        )
+    # the message is the same as the ValueError earlier versions raised
+    assert isinstance(excinfo.value, ValueError)
+    assert excinfo.value.args[0].startswith("additions_and_removals() failed: ")
+    assert excinfo.value.message == "GeneratorRuntimeError"
@@ -1,5 +1,7 @@
 from typing import Optional, Any, Callable
 
+import pytest
+
 import sys
 import time
 from chia_rs import BlockRecord, ClassgroupElement
@@ -129,3 +131,54 @@ def wrap_call(expr: str, br: Any) -> str:
         return f"V:{ret}"
     except Exception as e:
         return f"E:{e}"
+
+
+def test_json_integers() -> None:
+    rng = Random()
+    rng.seed(1337)
+    for _ in range(20):
+        br = get_block_record(rng)
+        d = br.to_json_dict()
+        # 128 bit integers are always strings, since JSON numbers can't hold
+        # all of them
+        assert d["weight"] == str(br.weight)
+        assert d["total_iters"] == str(br.total_iters)
+        assert isinstance(d["height"], int)
+        assert BlockRecord.from_json_dict(d) == br
+
+        # RPC servers may send integers either as numbers or as strings
+        as_strings = dict(d)
+        for key in ("height", "required_iters", "sub_slot_iters", "timestamp"):
+            as_strings[key] = str(d[key])
+        assert BlockRecord.from_json_dict(as_strings) == br
+
+        as_numbers = dict(d)
+        for key in ("weight", "total_iters"):
+            as_numbers[key] = int(d[key])
+        assert BlockRecord.from_json_dict(as_numbers) == br
+
+
+@pytest.mark.parametrize(
+    "key,value",
+    [
+        ("height", 1.0),
+        ("required_iters", 1e3),
+        ("weight", 10.0),
+        ("height", "1.5"),
+        ("height", "1e3"),
+        ("height", "0x10"),
+        ("height", ""),
+        ("height", " 1"),
+        ("height", "+1"),
+        ("height", "-1"),
+        ("height", str(2**32)),
+        ("weight", str(2**128)),
+    ],
+)
+def test_json_invalid_integers(key: str, value: Any) -> None:
+    rng = Random()
+    rng.seed(1337)
+    d = get_block_record(rng).to_json_dict()
+    d[key] = value
+    with pytest.raises((TypeError, ValueError)):
+        BlockRecord.from_json_dict(d)
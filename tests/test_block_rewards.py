@@ -0,0 +1,112 @@
+import pytest
+
+from chia_rs import (
+    Coin,
+    SpendBundleConditions,
+    ValidationError,
+    calculate_base_farmer_reward,
+    calculate_pool_reward,
+    compute_block_fees,
+    expected_reward_coins,
+    validate_block_fees,
+    validate_reward_coins,
+)
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint64
+
+BLOCKS_PER_YEAR = 1681920
+MAINNET_GENESIS_CHALLENGE = bytes32.fromhex(
+    "ccd5bb71183532bff220ba46c268991a3ff07eb358e8255a65c30a2dce0e5fbb"
+)
+
+pool_ph = bytes32(b"\x01" * 32)
+farmer_ph = bytes32(b"\x02" * 32)
+
+
+@pytest.mark.parametrize(
+    "height,pool,farmer",
+    [
+        (0, 18375000 * 10**12, 2625000 * 10**12),
+        (1, 1750000000000, 250000000000),
+        (3 * BLOCKS_PER_YEAR - 1, 1750000000000, 250000000000),
+        (3 * BLOCKS_PER_YEAR, 875000000000, 125000000000),
+        (6 * BLOCKS_PER_YEAR - 1, 875000000000, 125000000000),
+        (6 * BLOCKS_PER_YEAR, 437500000000, 62500000000),
+        (9 * BLOCKS_PER_YEAR - 1, 437500000000, 62500000000),
+        (9 * BLOCKS_PER_YEAR, 218750000000, 31250000000),
+        (12 * BLOCKS_PER_YEAR - 1, 218750000000, 31250000000),
+        (12 * BLOCKS_PER_YEAR, 109375000000, 15625000000),
+        (2**32 - 1, 109375000000, 15625000000),
+    ],
+)
+def test_reward_schedule(height: int, pool: int, farmer: int) -> None:
+    assert calculate_pool_reward(height) == pool
+    assert calculate_base_farmer_reward(height) == farmer
+
+    coins = expected_reward_coins(
+        height, pool_ph, farmer_ph, 1234, MAINNET_GENESIS_CHALLENGE
+    )
+    height_bytes = height.to_bytes(16, "big")
+    assert coins == [
+        Coin(
+            bytes32(MAINNET_GENESIS_CHALLENGE[:16] + height_bytes),
+            pool_ph,
+            uint64(pool),
+        ),
+        Coin(
+            bytes32(MAINNET_GENESIS_CHALLENGE[16:] + height_bytes),
+            farmer_ph,
+            uint64(farmer + 1234),
+        ),
+    ]
+    validate_reward_coins(coins, coins)
+    validate_reward_coins(list(reversed(coins)), coins)
+
+
+def test_invalid_reward_coins() -> None:
+    coins = expected_reward_coins(
+        1000, pool_ph, farmer_ph, 10, MAINNET_GENESIS_CHALLENGE
+    )
+    without_fees = expected_reward_coins(
+        1000, pool_ph, farmer_ph, 0, MAINNET_GENESIS_CHALLENGE
+    )
+    for claimed in [coins[:1], coins + coins[1:], without_fees]:
+        with pytest.raises(ValidationError) as e:
+            validate_reward_coins(claimed, coins)
+        assert e.value.code == 54  # INVALID_REWARD_COINS
+
+
+def conditions(
+    removal_amount: int, addition_amount: int, reserve_fee: int
+) -> SpendBundleConditions:
+    return SpendBundleConditions(
+        [],
+        reserve_fee,
+        0,
+        0,
+        None,
+        None,
+        [],
+        0,
+        removal_amount,
+        addition_amount,
+        False,
+    )
+
+
+def test_block_fees() -> None:
+    conds = conditions(1000, 600, 100)
+    assert compute_block_fees(conds) == 400
+    validate_block_fees(conds, 400)
+
+    with pytest.raises(ValidationError) as e:
+        validate_block_fees(conds, 399)
+    assert e.value.code == 37  # INVALID_BLOCK_FEE_AMOUNT
+
+    with pytest.raises(ValidationError) as e:
+        compute_block_fees(conditions(1000, 1001, 0))
+    assert e.value.code == 20  # MINTING_COIN
+
+    with pytest.raises(ValidationError) as e:
+        compute_block_fees(conditions(1000, 600, 401))
+    assert e.value.code == 48  # RESERVE_FEE_CONDITION_FAILED
@@ -0,0 +1,83 @@
+import pytest
+
+from chia_rs import block_to_block_record, calculate_deficit
+from chia_rs.sized_ints import uint8
+from run_gen import DEFAULT_CONSTANTS
+from test_cached_hash import make_block
+
+SUB_SLOT_ITERS = DEFAULT_CONSTANTS.SUB_SLOT_ITERS_STARTING
+REQUIRED_ITERS = 500
+MAX_DEFICIT = DEFAULT_CONSTANTS.MIN_BLOCKS_PER_CHALLENGE_BLOCK
+
+
+def test_block_to_block_record() -> None:
+    genesis = make_block(0)
+    record = block_to_block_record(
+        DEFAULT_CONSTANTS, genesis, REQUIRED_ITERS, SUB_SLOT_ITERS, None, None
+    )
+    assert record.header_hash == genesis.header_hash
+    assert record.prev_hash == genesis.prev_header_hash
+    assert record.height == 0
+    assert record.sub_slot_iters == SUB_SLOT_ITERS
+    assert record.required_iters == REQUIRED_ITERS
+    assert record.deficit == MAX_DEFICIT - 1
+    assert not record.overflow
+    assert not record.is_transaction_block
+    assert record.finished_challenge_slot_hashes == [
+        DEFAULT_CONSTANTS.GENESIS_CHALLENGE
+    ]
+    assert record.sub_epoch_summary_included is None
+
+    block = make_block(1)
+    block = block.replace(
+        foliage=block.foliage.replace(prev_block_hash=genesis.header_hash)
+    )
+    next_record = block_to_block_record(
+        DEFAULT_CONSTANTS, block, REQUIRED_ITERS, SUB_SLOT_ITERS, record, None
+    )
+    assert next_record.prev_hash == genesis.header_hash
+    assert next_record.deficit == MAX_DEFICIT - 2
+    assert next_record.finished_challenge_slot_hashes is None
+
+    # the previous record has to be the one of the previous block
+    with pytest.raises(ValueError):
+        block_to_block_record(
+            DEFAULT_CONSTANTS,
+            make_block(1),
+            REQUIRED_ITERS,
+            SUB_SLOT_ITERS,
+            record,
+            None,
+        )
+    with pytest.raises(ValueError):
+        block_to_block_record(
+            DEFAULT_CONSTANTS, block, REQUIRED_ITERS, SUB_SLOT_ITERS, None, None
+        )
+    # required iters must be within the signage point interval
+    with pytest.raises(ValueError):
+        block_to_block_record(
+            DEFAULT_CONSTANTS, genesis, 0, SUB_SLOT_ITERS, None, None
+        )
+
+
+@pytest.mark.parametrize(
+    "prev_deficit, overflow, num_sub_slots, expected",
+    [
+        (10, False, 0, 9),
+        (MAX_DEFICIT, True, 0, MAX_DEFICIT),
+        (MAX_DEFICIT, True, 1, MAX_DEFICIT - 1),
+        (0, False, 0, 0),
+        (0, True, 1, MAX_DEFICIT),
+        (0, False, 1, MAX_DEFICIT - 1),
+    ],
+)
+def test_calculate_deficit(
+    prev_deficit: int, overflow: bool, num_sub_slots: int, expected: int
+) -> None:
+    genesis = block_to_block_record(
+        DEFAULT_CONSTANTS, make_block(0), REQUIRED_ITERS, SUB_SLOT_ITERS, None, None
+    )
+    prev = genesis.replace(deficit=uint8(prev_deficit))
+    deficit = calculate_deficit(DEFAULT_CONSTANTS, 1, prev, overflow, num_sub_slots)
+    assert deficit == expected
+    assert calculate_deficit(DEFAULT_CONSTANTS, 0, None, overflow, 0) == MAX_DEFICIT - 1
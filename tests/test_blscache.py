@@ -18,6 +18,7 @@ from chia.util.hash import std_hash
 from chia.util.lru_cache import LRUCache
 from chia.types.blockchain_format.program import Program as ChiaProgram
 import pytest
+import time
 
 
 DEFAULT_CONSTANTS = ConsensusConstants(
@@ -280,6 +281,71 @@ def test_bad_cache_size():
     )
 
 
+def test_cache_policy():
+    sks = [AugSchemeMPL.key_gen(bytes([i]) * 32) for i in range(5)]
+    msg = b"foobar"
+
+    # about 3 entries worth of memory
+    bls_cache = BLSCache(size_bytes=3 * 700)
+    for sk in sks:
+        assert bls_cache.aggregate_verify(
+            [sk.get_g1()], [msg], AugSchemeMPL.sign(sk, msg)
+        )
+    assert 1 <= bls_cache.len() < 5
+
+    stats = bls_cache.stats()
+    assert stats.hits == 0
+    assert stats.misses == 5
+    assert stats.evictions == 5 - bls_cache.len()
+
+    assert bls_cache.aggregate_verify(
+        [sks[-1].get_g1()], [msg], AugSchemeMPL.sign(sks[-1], msg)
+    )
+    assert bls_cache.stats().hits == 1
+
+    # entries can be evicted by their keys
+    keys = [key for key, _ in bls_cache.items()]
+    bls_cache.evict_hashes(keys[:1])
+    assert bls_cache.len() == len(keys) - 1
+    with pytest.raises(ValueError):
+        bls_cache.evict_hashes([b"foobar"])
+
+
+def test_cache_ttl():
+    sk = AugSchemeMPL.key_gen(b"a" * 32)
+    sig = AugSchemeMPL.sign(sk, b"foobar")
+
+    bls_cache = BLSCache(ttl=0.1)
+    assert bls_cache.aggregate_verify([sk.get_g1()], [b"foobar"], sig)
+    assert bls_cache.aggregate_verify([sk.get_g1()], [b"foobar"], sig)
+    assert bls_cache.stats().hits == 1
+
+    time.sleep(0.1)
+    assert bls_cache.aggregate_verify([sk.get_g1()], [b"foobar"], sig)
+    stats = bls_cache.stats()
+    assert stats.hits == 1
+    assert stats.misses == 2
+    assert stats.evictions == 1
+
+
+def test_bad_cache_policy():
+    with pytest.raises(ValueError, match="Cannot specify both size and size_bytes."):
+        BLSCache(10, size_bytes=10000)
+
+    with pytest.raises(ValueError):
+        BLSCache(size_bytes=0)
+
+    with pytest.raises(ValueError, match="The ttl must be positive."):
+        BLSCache(ttl=0)
+
+    with pytest.raises(ValueError, match="The ttl must be positive."):
+        BLSCache(ttl=-1.0)
+
+    # the size is the only positional argument
+    with pytest.raises(TypeError):
+        BLSCache(10, 10000)  # type: ignore[misc]
+
+
 def test_validate_clvm_and_sig():
     cache = BLSCache()
     puz_reveal = Program.to(1)
@@ -323,3 +389,43 @@ def test_validate_clvm_and_sig():
     assert sbc is not None
     assert additions is not None
     assert duration is not None
+
+
+def test_serialize():
+    cached_bls = BLSCache()
+    n_keys = 5
+    seed = b"a" * 31
+    sks = [AugSchemeMPL.key_gen(seed + bytes([i])) for i in range(n_keys)]
+    pks = [sk.get_g1() for sk in sks]
+    msgs = [bytes([i]) * 32 for i in range(n_keys)]
+    aggsig = AugSchemeMPL.aggregate(
+        [AugSchemeMPL.sign(sk, msg) for sk, msg in zip(sks, msgs)]
+    )
+    assert cached_bls.aggregate_verify(pks, msgs, aggsig)
+
+    blob = cached_bls.serialize()
+    assert blob[0] == 1
+    assert len(blob) == 1 + n_keys * (32 + GTElement.SIZE)
+
+    restored = BLSCache.deserialize(blob)
+    assert restored.items() == cached_bls.items()
+    assert restored.aggregate_verify(pks, msgs, aggsig)
+    stats = restored.stats()
+    assert stats.hits == n_keys
+    assert stats.misses == 0
+
+    # only the newest entries are kept
+    restored = BLSCache.deserialize(blob, 2)
+    assert restored.items() == cached_bls.items()[-2:]
+
+    for key, value in cached_bls.items():
+        assert GTElement.from_canonical_bytes(value.to_canonical_bytes()) == value
+
+    with pytest.raises(ValueError):
+        BLSCache.deserialize(b"")
+    with pytest.raises(ValueError):
+        BLSCache.deserialize(b"\x02" + blob[1:])
+    with pytest.raises(ValueError):
+        BLSCache.deserialize(blob[:-1])
+    with pytest.raises(ValueError):
+        BLSCache.deserialize(blob[:-1] + bytes([blob[-1] ^ 1]))
@@ -107,6 +107,20 @@ def test_bls() -> None:
 
         assert bytes(aggsig1) == bytes(aggsig2)
 
+        ####  PopSchemeMPL  ####
+        msg = randbytes(100)
+        pop_sig1 = blspy.PopSchemeMPL.sign(sk1, msg)
+        pop_sig2 = chia_rs.PopSchemeMPL.sign(sk2, msg)
+        assert bytes(pop_sig1) == bytes(pop_sig2)
+        assert chia_rs.PopSchemeMPL.verify(pk2, msg, pop_sig2)
+        assert chia_rs.PopSchemeMPL.fast_aggregate_verify([pk2], msg, pop_sig2)
+
+        proof1 = blspy.PopSchemeMPL.pop_prove(sk1)
+        proof2 = chia_rs.PopSchemeMPL.pop_prove(sk2)
+        assert bytes(proof1) == bytes(proof2)
+        assert chia_rs.PopSchemeMPL.pop_verify(pk2, proof2)
+        assert not chia_rs.PopSchemeMPL.pop_verify(pk2, pop_sig2)
+
         ####  pair()  ####
         pair1 = pk1.pair(sig1)
         pair2 = pk2.pair(sig2)
@@ -230,6 +244,7 @@ from chia_rs import (
     AugSchemeMPL,
     G1Element,
     G2Element,
+    PopSchemeMPL,
     PrivateKey,
 )
 
@@ -249,7 +264,7 @@ def test_schemes() -> None:
     assert sk == PrivateKey.from_bytes(bytes(sk))
     assert pk == G1Element.from_bytes(bytes(pk))
 
-    for Scheme in [AugSchemeMPL]:
+    for Scheme in [AugSchemeMPL, PopSchemeMPL]:
         sig = Scheme.sign(sk, msg)
         assert sig == G2Element.from_bytes(bytes(sig))
         assert Scheme.verify(pk, msg, sig)
@@ -516,28 +531,28 @@ def test_readme() -> None:
     )
     assert ok
 
-    # pop_sig1: G2Element = PopSchemeMPL.sign(sk1, message)
-    # pop_sig2: G2Element = PopSchemeMPL.sign(sk2, message)
-    # pop_sig3: G2Element = PopSchemeMPL.sign(sk3, message)
-    # pop1: G2Element = PopSchemeMPL.pop_prove(sk1)
-    # pop2: G2Element = PopSchemeMPL.pop_prove(sk2)
-    # pop3: G2Element = PopSchemeMPL.pop_prove(sk3)
+    pop_sig1: G2Element = PopSchemeMPL.sign(sk1, message)
+    pop_sig2: G2Element = PopSchemeMPL.sign(sk2, message)
+    pop_sig3: G2Element = PopSchemeMPL.sign(sk3, message)
+    pop1: G2Element = PopSchemeMPL.pop_prove(sk1)
+    pop2: G2Element = PopSchemeMPL.pop_prove(sk2)
+    pop3: G2Element = PopSchemeMPL.pop_prove(sk3)
 
-    # ok = PopSchemeMPL.pop_verify(pk1, pop1)
-    # assert ok
-    # ok = PopSchemeMPL.pop_verify(pk2, pop2)
-    # assert ok
-    # ok = PopSchemeMPL.pop_verify(pk3, pop3)
-    # assert ok
+    ok = PopSchemeMPL.pop_verify(pk1, pop1)
+    assert ok
+    ok = PopSchemeMPL.pop_verify(pk2, pop2)
+    assert ok
+    ok = PopSchemeMPL.pop_verify(pk3, pop3)
+    assert ok
 
-    # pop_sig_agg: G2Element = PopSchemeMPL.aggregate([pop_sig1, pop_sig2, pop_sig3])
+    pop_sig_agg: G2Element = PopSchemeMPL.aggregate([pop_sig1, pop_sig2, pop_sig3])
 
-    # ok = PopSchemeMPL.fast_aggregate_verify([pk1, pk2, pk3], message, pop_sig_agg)
-    # assert ok
+    ok = PopSchemeMPL.fast_aggregate_verify([pk1, pk2, pk3], message, pop_sig_agg)
+    assert ok
 
-    # pop_agg_pk: G1Element = pk1 + pk2 + pk3
-    # ok = PopSchemeMPL.verify(pop_agg_pk, message, pop_sig_agg)
-    # assert ok
+    pop_agg_pk: G1Element = pk1 + pk2 + pk3
+    ok = PopSchemeMPL.verify(pop_agg_pk, message, pop_sig_agg)
+    assert ok
 
     # pop_agg_sk: PrivateKey = PrivateKey.aggregate([sk1, sk2, sk3])
     # ok = PopSchemeMPL.sign(pop_agg_sk, message) == pop_sig_agg
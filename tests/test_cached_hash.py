@@ -0,0 +1,108 @@
+import copy
+from hashlib import sha256
+
+from chia_rs import (
+    ClassgroupElement,
+    Foliage,
+    FoliageBlockData,
+    FullBlock,
+    G1Element,
+    G2Element,
+    PoolTarget,
+    ProofOfSpace,
+    RewardChainBlock,
+    VDFInfo,
+    VDFProof,
+)
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint8, uint32, uint64, uint128
+
+
+def vdf_info() -> VDFInfo:
+    return VDFInfo(
+        bytes32.zeros, uint64(1000), ClassgroupElement.get_default_element()
+    )
+
+
+def vdf_proof() -> VDFProof:
+    return VDFProof(uint8(0), b"", True)
+
+
+def make_block(height: int) -> FullBlock:
+    pos = ProofOfSpace(
+        bytes32(b"\x01" * 32),
+        G1Element(),
+        None,
+        G1Element(),
+        uint8(32),
+        b"\x55" * 64,
+    )
+    rcb = RewardChainBlock(
+        uint128(1337),
+        uint32(height),
+        uint128(123456789),
+        uint8(3),
+        bytes32(b"\x02" * 32),
+        pos,
+        None,
+        G2Element(),
+        vdf_info(),
+        None,
+        G2Element(),
+        vdf_info(),
+        None,
+        False,
+    )
+    foliage = Foliage(
+        bytes32(b"\x0d" * 32),
+        bytes32(b"\x0e" * 32),
+        FoliageBlockData(
+            bytes32(b"\x03" * 32),
+            PoolTarget(bytes32(b"\x04" * 32), uint32(0)),
+            None,
+            bytes32(b"\x05" * 32),
+            bytes32.zeros,
+        ),
+        G2Element(),
+        None,
+        None,
+    )
+    return FullBlock(
+        [],
+        rcb,
+        None,
+        vdf_proof(),
+        None,
+        vdf_proof(),
+        None,
+        foliage,
+        None,
+        None,
+        None,
+        [],
+    )
+
+
+def test_cached_hash() -> None:
+    block = make_block(42)
+    expected = bytes32(sha256(bytes(block)).digest())
+    assert block.get_hash() == expected
+    # the second call is served from the cache
+    assert block.get_hash() == expected
+
+    # the cache is not exposed
+    assert "hash_cache" not in repr(block)
+    assert not hasattr(block, "hash_cache")
+
+    # copies and replaced objects don't inherit a stale hash
+    other = block.replace(reward_chain_block=make_block(43).reward_chain_block)
+    assert other.get_hash() == bytes32(sha256(bytes(other)).digest())
+    assert other.get_hash() != expected
+    assert copy.deepcopy(block).get_hash() == expected
+    assert FullBlock.from_bytes(bytes(block)).get_hash() == expected
+    assert block == FullBlock.from_bytes(bytes(block))
+
+    # the header block is cached too
+    header = block.to_header_block(b"")
+    assert header.get_hash() == bytes32(sha256(bytes(header)).digest())
+    assert header.get_hash() == header.get_hash()
@@ -0,0 +1,103 @@
+from hashlib import sha256
+
+import pytest
+
+from chia_rs import (
+    ALLOW_BACKREFS,
+    Coin,
+    FoliageTransactionBlock,
+    HeaderBlock,
+    MerkleSet,
+    additions_and_removals,
+    verify_additions_proofs,
+    verify_removals_proofs,
+)
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint64
+from run_gen import DEFAULT_CONSTANTS
+from test_cached_hash import make_block
+
+
+def hash_coin_ids(coin_ids: list[bytes32]) -> bytes32:
+    if len(coin_ids) == 1:
+        return bytes32(sha256(coin_ids[0]).digest())
+    return bytes32(sha256(b"".join(sorted(coin_ids, reverse=True))).digest())
+
+
+def mainnet_block() -> tuple[dict[bytes32, list[Coin]], list[Coin]]:
+    with open("generator-tests/block-834768.txt") as f:
+        generator = bytes.fromhex(f.readline().strip())
+    additions, removals = additions_and_removals(
+        generator, [], ALLOW_BACKREFS, DEFAULT_CONSTANTS
+    )
+    by_puzzle_hash: dict[bytes32, list[Coin]] = {}
+    for coin, _ in additions:
+        by_puzzle_hash.setdefault(coin.puzzle_hash, []).append(coin)
+    return by_puzzle_hash, removals
+
+
+def header_block(additions_root: bytes32, removals_root: bytes32) -> HeaderBlock:
+    tx_block = FoliageTransactionBlock(
+        bytes32.zeros,
+        uint64(1700000000),
+        bytes32.zeros,
+        additions_root,
+        removals_root,
+        bytes32.zeros,
+    )
+    block = make_block(834768).replace(foliage_transaction_block=tx_block)
+    return block.to_header_block(b"")
+
+
+def test_verify_proofs() -> None:
+    additions, removals = mainnet_block()
+    leafs = []
+    for ph, coins in additions.items():
+        leafs.append(ph)
+        leafs.append(hash_coin_ids([c.name() for c in coins]))
+    additions_set = MerkleSet(leafs)
+    removals_set = MerkleSet([c.name() for c in removals])
+    header = header_block(additions_set.get_root(), removals_set.get_root())
+
+    # all coins of the block, without proofs
+    verify_additions_proofs(header, list(additions.items()))
+    verify_removals_proofs(header, [(c.name(), c) for c in removals])
+    with pytest.raises(ValueError, match="additions root mismatch"):
+        verify_additions_proofs(header, list(additions.items())[1:])
+    with pytest.raises(ValueError, match="removals root mismatch"):
+        verify_removals_proofs(header, [(c.name(), c) for c in removals[1:]])
+
+    # a subset of the coins, with proofs
+    ph, coins = next(iter(additions.items()))
+    missing_ph = bytes32(b"\x42" * 32)
+    add_proofs = [
+        (
+            ph,
+            additions_set.is_included_already_hashed(ph)[1],
+            additions_set.is_included_already_hashed(
+                hash_coin_ids([c.name() for c in coins])
+            )[1],
+        ),
+        (missing_ph, additions_set.is_included_already_hashed(missing_ph)[1], None),
+    ]
+    verify_additions_proofs(header, [(ph, coins), (missing_ph, [])], add_proofs)
+    with pytest.raises(ValueError, match="invalid merkle proof"):
+        verify_additions_proofs(header, [(ph, []), (missing_ph, [])], add_proofs)
+
+    spent = removals[0]
+    unspent = Coin(bytes32(b"\x01" * 32), bytes32(b"\x02" * 32), uint64(1))
+    rem_proofs = [
+        (c.name(), removals_set.is_included_already_hashed(c.name())[1])
+        for c in [spent, unspent]
+    ]
+    verify_removals_proofs(
+        header, [(spent.name(), spent), (unspent.name(), None)], rem_proofs
+    )
+    with pytest.raises(ValueError, match="invalid merkle proof"):
+        verify_removals_proofs(
+            header, [(spent.name(), spent), (unspent.name(), unspent)], rem_proofs
+        )
+
+    # only transaction blocks have additions and removals
+    with pytest.raises(ValueError, match="not a transaction block"):
+        verify_additions_proofs(make_block(1).to_header_block(b""), [])
@@ -0,0 +1,72 @@
+from random import Random
+from typing import Optional
+
+from chia_rs import Coin, CoinState, CoinStateDelta, diff_coin_states
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint32, uint64
+
+
+def state(i: int, created: Optional[int], spent: Optional[int]) -> CoinState:
+    coin = Coin(bytes32(bytes([i] * 32)), bytes32(b"\xff" * 32), uint64(1))
+    return CoinState(
+        coin,
+        None if spent is None else uint32(spent),
+        None if created is None else uint32(created),
+    )
+
+
+def all_states(delta: CoinStateDelta) -> list[CoinState]:
+    return (
+        delta.created + delta.spent + delta.reorged + delta.updated + delta.unchanged
+    )
+
+
+def test_diff_coin_states() -> None:
+    old = [state(1, 10, None), state(2, 10, None), state(3, 10, 12), state(4, 5, None)]
+    new = [
+        state(1, 10, None),
+        state(2, 10, 15),
+        state(3, 10, None),
+        # duplicates keep the highest heights
+        state(5, 20, None),
+        state(5, 20, 21),
+    ]
+    delta = diff_coin_states(old, new)
+    assert delta.unchanged == [state(1, 10, None)]
+    assert delta.spent == [state(2, 10, 15)]
+    assert delta.reorged == [state(3, 10, None)]
+    assert delta.created == [state(5, 20, 21)]
+    assert delta.updated == []
+    assert not delta.is_empty()
+    assert delta.max_height() == 21
+
+    assert diff_coin_states(new, new).is_empty()
+    assert diff_coin_states([], []).max_height() is None
+
+
+def test_apply_delta() -> None:
+    rng = Random(1337)
+
+    def random_height() -> Optional[int]:
+        return None if rng.random() < 0.2 else rng.randrange(10)
+
+    def random_states() -> list[CoinState]:
+        return [
+            state(rng.randrange(20), random_height(), random_height())
+            for _ in range(rng.randrange(30))
+        ]
+
+    for _ in range(200):
+        old = random_states()
+        new = random_states()
+        delta = diff_coin_states(old, new)
+
+        # applying the delta to the old states reproduces the new ones
+        applied = {s.coin.name(): s for s in diff_coin_states([], old).created}
+        for s in all_states(delta):
+            applied[s.coin.name()] = s
+
+        expected = {s.coin.name(): s for s in diff_coin_states([], old).created}
+        for s in diff_coin_states([], new).created:
+            expected[s.coin.name()] = s
+        assert applied == expected
@@ -0,0 +1,67 @@
+from typing import Optional
+
+import pytest
+
+from chia_rs import (
+    KNOWN_DICT_STANDARD_PUZZLES,
+    compress_with_dict,
+    decompress_with_dict,
+)
+
+# the standard transaction puzzle, which is part of the standard puzzles
+# dictionary
+STANDARD_PUZZLE = bytes.fromhex(
+    "ff02ffff01ff02ffff03ff0bffff01ff02ffff03ffff09ff05ffff1dff0bffff1effff0bff0bff"
+    "ff02ff06ffff04ff02ffff04ff17ff8080808080808080ffff01ff02ff17ff2f80ffff01ff0880"
+    "80ff0180ffff01ff04ffff04ff04ffff04ff05ffff04ffff02ff06ffff04ff02ffff04ff17ff80"
+    "808080ff80808080ffff02ff17ff2f808080ff0180ffff04ffff01ff32ff02ffff03ffff07ff05"
+    "80ffff01ff0bffff0102ffff02ff06ffff04ff02ffff04ff09ff80808080ffff02ff06ffff04ff"
+    "02ffff04ff0dff8080808080ffff01ff0bffff0101ff058080ff0180ff018080"
+)
+
+
+@pytest.mark.parametrize("dict_id", [None, KNOWN_DICT_STANDARD_PUZZLES])
+@pytest.mark.parametrize("size", [0, 1, 1000])
+def test_round_trip(dict_id: Optional[int], size: int) -> None:
+    data = STANDARD_PUZZLE + bytes(i % 251 for i in range(size))
+    compressed = compress_with_dict(data, dict_id)
+    assert compressed[0] == (dict_id or 0)
+    assert compressed == compress_with_dict(data, dict_id)
+    assert decompress_with_dict(compressed, len(data)) == data
+
+
+def test_dict_compresses_better() -> None:
+    data = STANDARD_PUZZLE + bytes([0x42] * 96)
+    plain = compress_with_dict(data)
+    with_dict = compress_with_dict(data, KNOWN_DICT_STANDARD_PUZZLES)
+    assert len(with_dict) < len(plain)
+
+
+def test_bomb_guard() -> None:
+    data = bytes(10_000_000)
+    compressed = compress_with_dict(data)
+    assert len(compressed) < 10_000
+    with pytest.raises(ValueError, match="exceeds 1000000 bytes"):
+        decompress_with_dict(compressed, 1_000_000)
+    assert decompress_with_dict(compressed, len(data)) == data
+
+
+def test_truncated() -> None:
+    compressed = compress_with_dict(STANDARD_PUZZLE, KNOWN_DICT_STANDARD_PUZZLES)
+    for length in range(len(compressed)):
+        with pytest.raises(ValueError):
+            decompress_with_dict(compressed[:length], 100_000)
+
+
+def test_trailing_data() -> None:
+    compressed = compress_with_dict(STANDARD_PUZZLE)
+    with pytest.raises(ValueError, match="trailing data"):
+        decompress_with_dict(compressed + b"\x00", 100_000)
+
+
+def test_unknown_dict() -> None:
+    with pytest.raises(ValueError, match="unknown compression dictionary"):
+        compress_with_dict(STANDARD_PUZZLE, 255)
+    compressed = compress_with_dict(STANDARD_PUZZLE)
+    with pytest.raises(ValueError, match="unknown compression dictionary"):
+        decompress_with_dict(b"\xff" + compressed[1:], 100_000)
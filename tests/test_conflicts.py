@@ -0,0 +1,72 @@
+from chia_rs import (
+    Coin,
+    SpendConditions,
+    SpendBundleConditions,
+    conflicts_with,
+)
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint64
+
+
+def spend(coin: Coin, create_coin: list[tuple[bytes32, int]]) -> SpendConditions:
+    return SpendConditions(
+        coin.name(),
+        coin.parent_coin_info,
+        coin.puzzle_hash,
+        coin.amount,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        [(puzzle_hash, amount, None) for puzzle_hash, amount in create_coin],
+        [],
+        [],
+        [],
+        [],
+        [],
+        [],
+        [],
+        0,
+    )
+
+
+def bundle(spends: list[SpendConditions], fee: int) -> SpendBundleConditions:
+    return SpendBundleConditions(spends, fee, 0, 0, None, None, [], 0, 0, 0, True)
+
+
+ph = bytes32(b"\x01" * 32)
+coin1 = Coin(bytes32(b"\x02" * 32), ph, uint64(1000))
+coin2 = Coin(bytes32(b"\x03" * 32), ph, uint64(1000))
+
+
+def test_fee_replacement() -> None:
+    original = bundle([spend(coin1, [(ph, 900)])], 100)
+    replacement = bundle([spend(coin1, [(ph, 900)]), spend(coin2, [])], 1100)
+
+    conflict = conflicts_with(original, replacement)
+    assert not conflict.is_empty()
+    assert conflict.shared_spends == [coin1.name()]
+    assert conflict.shared_additions == [Coin(coin1.name(), ph, uint64(900)).name()]
+    assert conflict.a_depends_on_b == []
+    assert conflict.b_depends_on_a == []
+
+
+def test_dependent() -> None:
+    a = bundle([spend(coin1, [(ph, 1000)])], 0)
+    output = Coin(coin1.name(), ph, uint64(1000))
+    b = bundle([spend(output, [])], 1000)
+
+    conflict = conflicts_with(a, b)
+    assert conflict.shared_spends == []
+    assert conflict.b_depends_on_a == [output.name()]
+
+    conflict = conflicts_with(b, a)
+    assert conflict.a_depends_on_b == [output.name()]
+
+
+def test_independent() -> None:
+    a = bundle([spend(coin1, [(ph, 1000)])], 0)
+    b = bundle([spend(coin2, [(ph, 1000)])], 0)
+    assert conflicts_with(a, b).is_empty()
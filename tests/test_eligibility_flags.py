@@ -0,0 +1,50 @@
+from chia_rs import (
+    ELIGIBLE_FOR_DEDUP,
+    ELIGIBLE_FOR_FF,
+    AugSchemeMPL,
+    Coin,
+    CoinSpend,
+    G2Element,
+    Program,
+    SpendBundle,
+    get_conditions_from_spendbundle,
+)
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint64
+from run_gen import DEFAULT_CONSTANTS
+import pytest
+
+
+# the puzzle is the identity function, so the solution is the list of
+# conditions. The output has the same puzzle hash and amount as the spent coin
+@pytest.mark.parametrize(
+    "amount,agg_sig_me,dedup,fast_forward",
+    [
+        (1000, False, True, False),
+        (1001, True, False, False),
+        (1001, False, True, True),
+    ],
+)
+def test_eligibility_flags(
+    amount: int, agg_sig_me: bool, dedup: bool, fast_forward: bool
+) -> None:
+    puzzle = Program.to(1)
+    puzzle_hash = puzzle.get_tree_hash()
+    conditions: list[list[object]] = [[51, puzzle_hash, amount]]
+    if agg_sig_me:
+        pk = AugSchemeMPL.key_gen(b"\x01" * 32).get_g1()
+        conditions.append([50, bytes(pk), b"hello"])
+
+    coin = Coin(bytes32(b"\x01" * 32), puzzle_hash, uint64(amount))
+    spend = CoinSpend(coin, puzzle, Program.to(conditions))
+    bundle = SpendBundle([spend], G2Element())
+    conds = get_conditions_from_spendbundle(
+        bundle, DEFAULT_CONSTANTS.MAX_BLOCK_COST_CLVM, DEFAULT_CONSTANTS, 0
+    )
+
+    assert len(conds.spends) == 1
+    spend_conds = conds.spends[0]
+    assert spend_conds.eligible_for_dedup == dedup
+    assert spend_conds.eligible_for_fast_forward == fast_forward
+    assert ((spend_conds.flags & ELIGIBLE_FOR_DEDUP) != 0) == dedup
+    assert ((spend_conds.flags & ELIGIBLE_FOR_FF) != 0) == fast_forward
@@ -0,0 +1,22 @@
+from chia_rs import FeeEstimate, FeeRate
+from chia_rs.sized_ints import uint64
+
+
+def test_fee_rate() -> None:
+    rate = FeeRate(uint64(5))
+    assert rate.fee_for_cost(11000000000) == 55000000000
+    assert rate.fee_for_cost(2**63) is None
+    assert rate.is_met_by(55000000000, 11000000000)
+    assert not rate.is_met_by(54999999999, 11000000000)
+
+    assert FeeRate.from_fee_rounding_down(54999999999, 11000000000) == FeeRate(
+        uint64(4)
+    )
+    assert FeeRate.from_fee_rounding_up(54999999999, 11000000000) == rate
+    assert FeeRate.from_fee_rounding_down(1, 0) is None
+    assert FeeRate.from_fee_rounding_up(1, 0) is None
+
+    # a FeeRate is serialized like a uint64
+    assert bytes(rate) == bytes(uint64(5))
+    estimate = FeeEstimate(None, uint64(1700000000), rate)
+    assert FeeEstimate.from_bytes(bytes(estimate)).estimated_fee_rate == rate
@@ -0,0 +1,148 @@
+import json
+from typing import Any
+from random import Random
+
+import pytest
+
+from chia_rs import (
+    BlockRecord,
+    Coin,
+    CoinState,
+    RespondAdditions,
+    SpendBundle,
+)
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint32, uint64
+from test_block_record_fidelity import get_block_record
+
+
+def make_respond_additions(rng: Random, num_coins: int) -> RespondAdditions:
+    coins: list[tuple[bytes32, list[Coin]]] = []
+    for _ in range(num_coins // 4):
+        ph = bytes32.random(rng)
+        coins.append(
+            (
+                ph,
+                [
+                    Coin(bytes32.random(rng), ph, uint64(rng.randint(0, 2**64 - 1)))
+                    for _ in range(4)
+                ],
+            )
+        )
+    proofs = [
+        (ph, bytes(rng.randbytes(64)), None if i % 2 else bytes(rng.randbytes(32)))
+        for i, (ph, _) in enumerate(coins)
+    ]
+    return RespondAdditions(uint32(123456), bytes32.random(rng), coins, proofs)
+
+
+def from_bytes_and_dict(ty: Any, d: Any) -> Any:
+    # from_json_bytes() must agree with from_json_dict() on the same document
+    from_dict = ty.from_json_dict(d)
+    from_bytes = ty.from_json_bytes(json.dumps(d).encode())
+    assert from_dict == from_bytes
+    return from_bytes
+
+
+def test_respond_additions() -> None:
+    rng = Random(1337)
+    msg = make_respond_additions(rng, 2000)
+    assert from_bytes_and_dict(RespondAdditions, msg.to_json_dict()) == msg
+
+
+def test_block_record() -> None:
+    rng = Random(1337)
+    for _ in range(20):
+        br = get_block_record(rng)
+        d = br.to_json_dict()
+        assert from_bytes_and_dict(BlockRecord, d) == br
+
+        # integers may be numbers or strings, the same as from_json_dict()
+        for key in ("height", "required_iters", "sub_slot_iters", "timestamp"):
+            d[key] = str(d[key])
+        for key in ("weight", "total_iters"):
+            d[key] = int(d[key])
+        assert from_bytes_and_dict(BlockRecord, d) == br
+
+
+def test_spend_bundle() -> None:
+    buf = open("test-bundles/3000253.bundle", "rb").read()
+    bundle = SpendBundle.from_bytes(buf)
+    assert from_bytes_and_dict(SpendBundle, bundle.to_json_dict()) == bundle
+
+
+def test_missing_optional() -> None:
+    state = CoinState(
+        Coin(bytes32(b"\x01" * 32), bytes32(b"\x02" * 32), uint64(1)), None, uint32(5)
+    )
+    d = state.to_json_dict()
+    del d["spent_height"]
+    assert from_bytes_and_dict(CoinState, d) == state
+
+    # only optional fields may be left out
+    del d["coin"]
+    with pytest.raises(KeyError):
+        CoinState.from_json_bytes(json.dumps(d).encode())
+
+
+# SpendBundle is the only class that can be subclassed
+class DerivedSpendBundle(SpendBundle):
+    pass
+
+
+def test_derived_class() -> None:
+    buf = open("test-bundles/3000253.bundle", "rb").read()
+    bundle = SpendBundle.from_bytes(buf)
+    ret = DerivedSpendBundle.from_json_bytes(
+        json.dumps(bundle.to_json_dict()).encode()
+    )
+    assert isinstance(ret, DerivedSpendBundle)
+    assert ret == bundle
+
+
+ZEROS = "0x" + "00" * 32
+
+
+def coin_json(**kwargs: Any) -> bytes:
+    d: dict[str, Any] = {"parent_coin_info": ZEROS, "puzzle_hash": ZEROS, "amount": 1}
+    d.update(kwargs)
+    return json.dumps(d).encode()
+
+
+@pytest.mark.parametrize(
+    "data",
+    [
+        b"",
+        b"{",
+        b"[]",
+        b'{"parent_coin_info": 1}',
+        coin_json(parent_coin_info="0x00"),
+        coin_json(puzzle_hash="00" * 32),
+        coin_json(puzzle_hash="0xzz" + "00" * 31),
+        coin_json(amount=1.0),
+        coin_json(amount="0x1"),
+        coin_json(amount=-1),
+        coin_json(amount=2**64),
+        coin_json(amount=None),
+    ],
+)
+def test_invalid(data: bytes) -> None:
+    with pytest.raises((KeyError, TypeError, ValueError)):
+        Coin.from_json_bytes(data)
+
+
+def test_valid_coin() -> None:
+    coin = Coin(bytes32(b"\x00" * 32), bytes32(b"\x00" * 32), uint64(1))
+    assert Coin.from_json_bytes(coin_json()) == coin
+    # unknown fields are ignored, like in from_json_dict()
+    assert Coin.from_json_bytes(coin_json(foobar=1)) == coin
+
+
+def test_large_integers() -> None:
+    rng = Random(1)
+    br = get_block_record(rng)
+    d = br.to_json_dict()
+    # unlike python's json module, the 128 bit integers are never rounded
+    # through a float
+    text = json.dumps(d).replace(f'"{d["weight"]}"', str(br.weight))
+    assert BlockRecord.from_json_bytes(text.encode()) == br
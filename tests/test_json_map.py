@@ -0,0 +1,68 @@
+import json
+
+import pytest
+
+from chia_rs import JsonMapStreamableTest
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint64
+
+
+def test_json_map() -> None:
+    a = bytes32(b"\x01" * 32)
+    b = bytes32(b"\x02" * 32)
+    value = JsonMapStreamableTest(
+        [(b, uint64(1337)), (a, uint64(42))], [(b, b"\x01\x02")]
+    )
+
+    # the json_map field is an object keyed by the hex of the key, in sorted
+    # order. Other lists of pairs are unaffected
+    json_dict = value.to_json_dict()
+    assert json_dict == {
+        "amounts": {"0x" + "01" * 32: 42, "0x" + "02" * 32: 1337},
+        "proofs": [["0x" + "02" * 32, "0x0102"]],
+    }
+    assert list(json_dict["amounts"].keys()) == ["0x" + "01" * 32, "0x" + "02" * 32]
+
+    # parsing the object form sorts the pairs by key
+    sorted_value = JsonMapStreamableTest(
+        [(a, uint64(42)), (b, uint64(1337))], [(b, b"\x01\x02")]
+    )
+    assert JsonMapStreamableTest.from_json_dict(json_dict) == sorted_value
+    assert (
+        JsonMapStreamableTest.from_json_bytes(json.dumps(json_dict).encode())
+        == sorted_value
+    )
+
+    # the legacy list of pairs is accepted too, and keeps its order
+    legacy = {
+        "amounts": [["0x" + "02" * 32, 1337], ["0x" + "01" * 32, 42]],
+        "proofs": json_dict["proofs"],
+    }
+    assert JsonMapStreamableTest.from_json_dict(legacy) == value
+    assert JsonMapStreamableTest.from_json_bytes(json.dumps(legacy).encode()) == value
+
+
+def test_json_map_duplicate_keys() -> None:
+    a = bytes32(b"\x01" * 32)
+    value = JsonMapStreamableTest([(a, uint64(1)), (a, uint64(2))], [])
+
+    # an object can't hold the same key twice
+    with pytest.raises(ValueError, match="duplicate key"):
+        value.to_json_dict()
+
+    # the same key, in lower and upper case hex
+    json_dict = {
+        "amounts": {"0x" + "ab" * 32: 1, "0x" + "AB" * 32: 2},
+        "proofs": [],
+    }
+    with pytest.raises(ValueError, match="duplicate key"):
+        JsonMapStreamableTest.from_json_dict(json_dict)
+    with pytest.raises(ValueError, match="duplicate key"):
+        JsonMapStreamableTest.from_json_bytes(json.dumps(json_dict).encode())
+
+
+def test_json_map_invalid() -> None:
+    with pytest.raises(KeyError):
+        JsonMapStreamableTest.from_json_dict({"proofs": []})
+    with pytest.raises(ValueError):
+        JsonMapStreamableTest.from_json_dict({"amounts": {"0x1234": 1}, "proofs": []})
@@ -0,0 +1,75 @@
+from chia_rs import (
+    Coin,
+    MempoolCandidate,
+    SpendConditions,
+    SpendBundleConditions,
+    select_spends,
+)
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint64
+
+
+def spend(coin: Coin, create_coin: list[tuple[bytes32, int]]) -> SpendConditions:
+    return SpendConditions(
+        coin.name(),
+        coin.parent_coin_info,
+        coin.puzzle_hash,
+        coin.amount,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        [(puzzle_hash, amount, None) for puzzle_hash, amount in create_coin],
+        [],
+        [],
+        [],
+        [],
+        [],
+        [],
+        [],
+        0,
+    )
+
+
+def candidate(
+    i: int, fee: int, cost: int, spends: list[SpendConditions]
+) -> MempoolCandidate:
+    conds = SpendBundleConditions(spends, fee, 0, 0, None, None, [], cost, 0, 0, True)
+    return MempoolCandidate(bytes32(bytes([i]) * 32), fee, cost, conds)
+
+
+ph = bytes32(b"\x01" * 32)
+coin1 = Coin(bytes32(b"\x02" * 32), ph, uint64(1000))
+coin2 = Coin(bytes32(b"\x03" * 32), ph, uint64(1000))
+output = Coin(coin1.name(), ph, uint64(1000))
+
+
+def test_select_spends() -> None:
+    parent = candidate(1, 0, 100, [spend(coin1, [(ph, 1000)])])
+    child = candidate(2, 1000, 100, [spend(output, [])])
+    other = candidate(3, 400, 100, [spend(coin2, [])])
+    items = [parent, child, other]
+
+    # the child pulls in its parent, which pays no fee
+    selection = select_spends(items, 200)
+    assert selection.ids == [parent.id, child.id]
+    assert selection.fee == 1000
+    assert selection.cost == 200
+
+    # the child doesn't fit without its parent
+    selection = select_spends(items, 199)
+    assert selection.ids == [other.id]
+    assert selection.fee == 400
+    assert selection.cost == 100
+
+    assert select_spends(items, 300).ids == [parent.id, child.id, other.id]
+    assert select_spends(items, 0).ids == []
+    assert select_spends([], 1000).ids == []
+
+
+def test_double_spend() -> None:
+    original = candidate(1, 100, 100, [spend(coin1, [(ph, 900)])])
+    replacement = candidate(2, 300, 100, [spend(coin1, [(ph, 800)])])
+    assert select_spends([original, replacement], 1000).ids == [replacement.id]
@@ -0,0 +1,78 @@
+import math
+from hashlib import sha256
+from typing import Optional
+
+import pytest
+
+from chia_rs import MerkleProof, MerkleTree, Program
+from chia_rs.sized_bytes import bytes32
+
+
+# a copy of MerkleTree in chia-blockchain (chia/wallet/util/merkle_tree.py),
+# which the rust implementation is expected to match
+def py_root(leaves: list[bytes32]) -> bytes32:
+    if len(leaves) == 1:
+        return bytes32(sha256(b"\x01" + leaves[0]).digest())
+    mid = math.ceil(len(leaves) / 2)
+    left = py_root(leaves[:mid])
+    right = py_root(leaves[mid:])
+    return bytes32(sha256(b"\x02" + left + right).digest())
+
+
+def py_proof(
+    leaves: list[bytes32], leaf: bytes32
+) -> Optional[tuple[int, list[bytes32]]]:
+    if len(leaves) == 1:
+        return (0, []) if leaves[0] == leaf else None
+    mid = math.ceil(len(leaves) / 2)
+    left = py_proof(leaves[:mid], leaf)
+    if left is not None:
+        return (left[0], left[1] + [py_root(leaves[mid:])])
+    right = py_proof(leaves[mid:], leaf)
+    if right is not None:
+        path = right[0] | (1 << len(right[1]))
+        return (path, right[1] + [py_root(leaves[:mid])])
+    return None
+
+
+def leaves(count: int) -> list[bytes32]:
+    return [bytes32(sha256(i.to_bytes(4, "big")).digest()) for i in range(count)]
+
+
+@pytest.mark.parametrize("count", [1, 2, 3, 4, 5, 7, 8, 9, 17, 100])
+def test_merkle_tree(count: int) -> None:
+    tree = MerkleTree(leaves(count))
+    assert tree.leaves == leaves(count)
+    assert tree.root() == py_root(leaves(count))
+
+    for index, leaf in enumerate(tree.leaves):
+        proof = tree.proof_for(index)
+        assert (proof.path, proof.hashes) == py_proof(tree.leaves, leaf)
+        assert proof.verify(tree.root(), leaf)
+        assert not proof.verify(tree.root(), bytes32(b"\x00" * 32))
+
+    with pytest.raises(ValueError):
+        tree.proof_for(count)
+
+
+def test_merkle_tree_odd() -> None:
+    # the left sub-tree gets the extra leaf, and the last leaf is promoted
+    # rather than duplicated, so its proof is shorter
+    a, b, c = leaves(3)
+    tree = MerkleTree([a, b, c])
+    assert [len(tree.proof_for(i).hashes) for i in range(3)] == [2, 2, 1]
+    assert tree.proof_for(2).path == 1
+
+
+def test_merkle_tree_empty() -> None:
+    with pytest.raises(ValueError):
+        MerkleTree([])
+
+
+def test_merkle_proof_program() -> None:
+    # the merkle puzzles take the proof as (path . hashes)
+    tree = MerkleTree(leaves(5))
+    for index in range(5):
+        proof = tree.proof_for(index)
+        assert proof.to_program() == Program.to((proof.path, proof.hashes))
+        assert MerkleProof(proof.path, proof.hashes) == proof
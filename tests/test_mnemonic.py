@@ -0,0 +1,73 @@
+import pytest
+
+from chia_rs import (
+    PrivateKey,
+    generate_mnemonic,
+    mnemonic_from_entropy,
+    mnemonic_to_entropy,
+    mnemonic_to_seed,
+    validate_mnemonic,
+)
+
+# https://github.com/trezor/python-mnemonic/blob/master/vectors.json
+VECTORS = [
+    (
+        "00000000000000000000000000000000",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+    ),
+    (
+        "9e885d952ad362caeb4efe34a8e91bd2",
+        "ozone drill grab fiber curtain grace pudding thank cruise elder eight picnic",
+        "274ddc525802f7c828d8ef7ddbcdc5304e87ac3535913611fbbfa986d0c9e5476c91689f9c8a54fd55bd38606aa6a8595ad213d4c9c9f9aca3fb217069a41028",
+    ),
+    (
+        "68a79eaca2324873eacc50cb9c6eca8cc68ea5d936f98787c60c7ebc74e6ce7c",
+        "hamster diagram private dutch cause delay private meat slide toddler razor book happy fancy gospel tennis maple dilemma loan word shrug inflict delay length",
+        "64c87cde7e12ecf6704ab95bb1408bef047c22db4cc7491c4271d170a1b213d20b385bc1588d9c7b38f1b39d415665b8a9030c9ec653d75e65f847d8fc1fc440",
+    ),
+]
+
+
+@pytest.mark.parametrize("entropy,phrase,seed", VECTORS)
+def test_bip39_vectors(entropy: str, phrase: str, seed: str) -> None:
+    assert mnemonic_from_entropy(bytes.fromhex(entropy)) == phrase
+    assert mnemonic_to_entropy(phrase) == bytes.fromhex(entropy)
+    validate_mnemonic(phrase)
+    assert mnemonic_to_seed(phrase, "TREZOR") == bytes.fromhex(seed)
+
+
+def test_keychain_fingerprint() -> None:
+    phrase = (
+        "all hour make first leader extend hole alien behind guard gospel lava "
+        "path output census museum junior mass reopen famous sing advance salt reform"
+    )
+    sk = PrivateKey.from_mnemonic(phrase)
+    assert sk == PrivateKey.from_seed(mnemonic_to_seed(phrase))
+    assert (
+        str(sk) == "52d75c4707e39595b27314547f9723e5530c01198af3fc5849d9a7af65631efb"
+    )
+    assert sk.get_g1().get_fingerprint() == 651010559
+
+
+def test_generate() -> None:
+    phrase = generate_mnemonic()
+    assert len(phrase.split(" ")) == 24
+    validate_mnemonic(phrase)
+    assert generate_mnemonic() != phrase
+
+
+@pytest.mark.parametrize(
+    "phrase",
+    [
+        "",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abou",
+    ],
+)
+def test_invalid(phrase: str) -> None:
+    with pytest.raises(ValueError):
+        validate_mnemonic(phrase)
+    with pytest.raises(ValueError):
+        PrivateKey.from_mnemonic(phrase)
@@ -0,0 +1,142 @@
+import pytest
+from chia_rs import (
+    calculate_ip_iters,
+    calculate_iterations_quality,
+    calculate_prefix_bits,
+    calculate_sp_interval_iters,
+    calculate_sp_iters,
+    is_overflow_block,
+    is_valid_partial,
+    max_partial_difficulty,
+    passes_plot_filter,
+)
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint8, uint16, uint32
+from run_gen import DEFAULT_CONSTANTS
+
+# the constants used by chia-blockchain's test_pot_iterations.py
+test_constants = DEFAULT_CONSTANTS.replace(
+    NUM_SPS_SUB_SLOT=uint32(32), SUB_SLOT_TIME_TARGET=uint16(300)
+)
+
+
+def test_is_overflow_block() -> None:
+    assert not is_overflow_block(test_constants, uint8(27))
+    assert not is_overflow_block(test_constants, uint8(28))
+    assert is_overflow_block(test_constants, uint8(29))
+    assert is_overflow_block(test_constants, uint8(30))
+    assert is_overflow_block(test_constants, uint8(31))
+    with pytest.raises(ValueError):
+        assert is_overflow_block(test_constants, uint8(32))
+
+
+def test_calculate_sp_iters() -> None:
+    ssi = 100001 * 64 * 4
+    with pytest.raises(ValueError):
+        calculate_sp_iters(test_constants, ssi, uint8(32))
+    calculate_sp_iters(test_constants, ssi, uint8(31))
+
+
+def test_calculate_ip_iters() -> None:
+    ssi = 100001 * 64 * 4
+    sp_interval_iters = ssi // test_constants.NUM_SPS_SUB_SLOT
+    assert calculate_sp_interval_iters(test_constants, ssi) == sp_interval_iters
+
+    with pytest.raises(ValueError):
+        # Invalid signage point index
+        calculate_ip_iters(test_constants, ssi, uint8(123), 100000)
+
+    sp_iters = sp_interval_iters * 13
+
+    with pytest.raises(ValueError):
+        # required_iters too high
+        calculate_ip_iters(test_constants, ssi, 13, sp_interval_iters)
+
+    with pytest.raises(ValueError):
+        # required_iters too low (0)
+        calculate_ip_iters(test_constants, ssi, 13, 0)
+
+    extra = test_constants.NUM_SP_INTERVALS_EXTRA
+    for required_iters in [sp_interval_iters - 1, 1, int(ssi * 4 / 300)]:
+        ip_iters = calculate_ip_iters(test_constants, ssi, uint8(13), required_iters)
+        assert ip_iters == sp_iters + extra * sp_interval_iters + required_iters
+        assert sp_iters < ip_iters
+
+    # Overflow
+    sp_iters = sp_interval_iters * (test_constants.NUM_SPS_SUB_SLOT - 1)
+    ip_iters = calculate_ip_iters(
+        test_constants,
+        ssi,
+        test_constants.NUM_SPS_SUB_SLOT - 1,
+        required_iters,
+    )
+    assert ip_iters == (sp_iters + extra * sp_interval_iters + required_iters) % ssi
+    assert sp_iters > ip_iters
+
+
+def test_calculate_iterations_quality() -> None:
+    iters = calculate_iterations_quality(
+        2**67, bytes32([1] * 32), uint8(32), 7, bytes32([2] * 32)
+    )
+    assert iters == 7172079287
+
+
+@pytest.mark.parametrize(
+    "quality, size, sp_hash, expected",
+    [
+        (bytes32([1] * 32), 32, bytes32([2] * 32), 1),
+        (bytes32([1] * 32), 35, bytes32([2] * 32), 10),
+        (bytes32([5] * 32), 32, bytes32([6] * 32), 1),
+    ],
+)
+def test_max_partial_difficulty(
+    quality: bytes32, size: int, sp_hash: bytes32, expected: int
+) -> None:
+    difficulty = max_partial_difficulty(test_constants, quality, uint8(size), sp_hash)
+    assert difficulty == expected
+
+    # this is the check the pool reference server does on each partial
+    iters_limit = calculate_sp_interval_iters(
+        test_constants, test_constants.POOL_SUB_SLOT_ITERS
+    )
+    for d in [difficulty, difficulty + 1]:
+        required_iters = calculate_iterations_quality(
+            test_constants.DIFFICULTY_CONSTANT_FACTOR, quality, uint8(size), d, sp_hash
+        )
+        valid = is_valid_partial(test_constants, quality, uint8(size), d, sp_hash)
+        assert valid == (required_iters < iters_limit)
+        assert valid == (d == difficulty)
+
+
+def test_partial_sub_slot_iters() -> None:
+    quality = bytes32([1] * 32)
+    sp_hash = bytes32([2] * 32)
+    # the blockchain's sub-slot iterations are much lower than the pool's
+    ssi = 100001 * 64 * 4
+    assert max_partial_difficulty(test_constants, quality, uint8(32), sp_hash, ssi) == 0
+    assert not is_valid_partial(test_constants, quality, uint8(32), 1, sp_hash, ssi)
+
+
+@pytest.mark.parametrize(
+    "height, expected",
+    [
+        (0, 9),
+        (5495999, 9),
+        (5496000, 8),
+        (10542000, 7),
+        (15592000, 6),
+        (20643000, 5),
+    ],
+)
+def test_calculate_prefix_bits(height: int, expected: int) -> None:
+    assert calculate_prefix_bits(DEFAULT_CONSTANTS, height) == expected
+
+
+def test_passes_plot_filter() -> None:
+    sp = bytes32([3] * 32)
+    # the filter input has 8 leading zero bits, which passes once the filter
+    # is reduced at the hard fork
+    plot_id = bytes32([0] * 32)
+    challenge = bytes32([200] * 32)
+    assert not passes_plot_filter(DEFAULT_CONSTANTS, 0, plot_id, challenge, sp)
+    assert passes_plot_filter(DEFAULT_CONSTANTS, 5496000, plot_id, challenge, sp)
@@ -1,3 +1,5 @@
+import pytest
+
 from chia_rs import run_chia_program, Program
 from chia_rs.sized_bytes import bytes32
 
@@ -26,6 +28,14 @@ def test_raise_program() -> None:
         assert f"{e}" == "('clvm raise', '86666f6f626172')"
 
 
+def test_cost_exceeded_program() -> None:
+    # (q . "foobar")
+    prg = Program.fromhex("ff0186666f6f626172")
+    with pytest.raises(ValueError) as e:
+        prg.run_with_cost(10, [])
+    assert e.value.args == ("cost exceeded", "80")
+
+
 def test_repr() -> None:
     temp = Program.to([8, (1, "foo")])
     assert f"{temp}" == "Program(ff08ffff0183666f6f80)"
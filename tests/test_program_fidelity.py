@@ -117,6 +117,32 @@ def test_uncurry() -> None:
         assert py_prg.uncurry() == rust_prg.uncurry()
 
 
+def test_curry() -> None:
+
+    rnd = Random()
+    for _ in range(1000):
+        py_prg = ChiaProgram.to(rand_object(rnd))
+        rust_prg = chia_rs.Program.from_program(py_prg)
+        args = [ChiaProgram.to(rand_object(rnd)) for _ in range(rnd.randint(0, 3))]
+
+        py_curried = py_prg.curry(*args)
+        rust_curried = rust_prg.curry(*args)
+        assert bytes(py_curried) == bytes(rust_curried)
+        assert py_curried.uncurry() == rust_curried.uncurry()
+
+        # Program arguments are curried in as the programs they represent
+        rust_args = [chia_rs.Program.from_program(arg) for arg in args]
+        assert bytes(rust_prg.curry(*rust_args)) == bytes(py_curried)
+
+
+def test_uncurry_not_curried() -> None:
+    # the argument list must be terminated by 1
+    py_prg = ChiaProgram.to([2, (1, b"xyz"), [4, (1, b"a"), []]])
+    rust_prg = chia_rs.Program.from_program(py_prg)
+    assert py_prg.uncurry() == (py_prg, ChiaProgram.to(0))
+    assert py_prg.uncurry() == rust_prg.uncurry()
+
+
 def test_round_trip() -> None:
 
     rnd = Random()
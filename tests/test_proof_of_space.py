@@ -0,0 +1,59 @@
+from hashlib import sha256
+
+import pytest
+
+from chia_rs import (
+    AugSchemeMPL,
+    G1Element,
+    calculate_plot_id_ph,
+    calculate_plot_id_pk,
+    generate_plot_public_key,
+)
+from chia_rs.sized_bytes import bytes32
+
+
+# these are the python implementations from chia-blockchain, which the rust
+# ones must agree with
+def py_calculate_plot_id_pk(pool_pk: G1Element, plot_pk: G1Element) -> bytes32:
+    return bytes32(sha256(bytes(pool_pk) + bytes(plot_pk)).digest())
+
+
+def py_calculate_plot_id_ph(pool_contract_ph: bytes32, plot_pk: G1Element) -> bytes32:
+    return bytes32(sha256(bytes(pool_contract_ph) + bytes(plot_pk)).digest())
+
+
+def py_generate_plot_public_key(
+    local_pk: G1Element, farmer_pk: G1Element, include_taproot: bool = False
+) -> G1Element:
+    if include_taproot:
+        taproot_message = (
+            bytes(local_pk + farmer_pk) + bytes(local_pk) + bytes(farmer_pk)
+        )
+        taproot_sk = AugSchemeMPL.key_gen(sha256(taproot_message).digest())
+        return local_pk + farmer_pk + taproot_sk.get_g1()
+    return local_pk + farmer_pk
+
+
+@pytest.mark.parametrize("seed", range(5))
+def test_plot_id(seed: int) -> None:
+    pool_pk = AugSchemeMPL.key_gen(bytes([seed] * 32)).get_g1()
+    plot_pk = AugSchemeMPL.key_gen(bytes([seed + 100] * 32)).get_g1()
+    pool_contract_ph = bytes32(bytes([seed + 200] * 32))
+
+    plot_id = calculate_plot_id_pk(pool_pk, plot_pk)
+    assert type(plot_id) is bytes32
+    assert plot_id == py_calculate_plot_id_pk(pool_pk, plot_pk)
+    assert calculate_plot_id_ph(pool_contract_ph, plot_pk) == py_calculate_plot_id_ph(
+        pool_contract_ph, plot_pk
+    )
+
+
+@pytest.mark.parametrize("seed", range(5))
+@pytest.mark.parametrize("include_taproot", [False, True])
+def test_generate_plot_public_key(seed: int, include_taproot: bool) -> None:
+    local_pk = AugSchemeMPL.key_gen(bytes([seed] * 32)).get_g1()
+    farmer_pk = AugSchemeMPL.key_gen(bytes([seed + 100] * 32)).get_g1()
+
+    plot_pk = generate_plot_public_key(local_pk, farmer_pk, include_taproot)
+    assert plot_pk == py_generate_plot_public_key(local_pk, farmer_pk, include_taproot)
+    assert generate_plot_public_key(local_pk, farmer_pk) == local_pk + farmer_pk
@@ -0,0 +1,34 @@
+from chia_rs import ClassgroupElement, PoolTarget, VDFInfo
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint32, uint64
+
+
+def test_classgroup_default_element() -> None:
+    default = ClassgroupElement.get_default_element()
+    assert bytes(default)[0] == 0x08
+    assert bytes(default)[1:] == b"\x00" * 99
+    assert ClassgroupElement.get_size() == 100
+    assert str(default) == "generator"
+
+
+def test_vdf_info_str() -> None:
+    info = VDFInfo(
+        bytes32(b"\x1a" * 32), uint64(123456), ClassgroupElement.get_default_element()
+    )
+    assert (
+        str(info)
+        == "VDFInfo(challenge: 1a1a1a1a.., iterations: 123456, output: generator)"
+    )
+    # repr() still prints all fields
+    assert "1a" * 32 in repr(info)
+
+
+def test_pool_target() -> None:
+    forever = PoolTarget(bytes32(b"\xcd" * 32), uint32(0))
+    assert str(forever) == "PoolTarget(puzzle_hash: cdcdcdcd.., max_height: none)"
+    assert forever.is_valid_at(1000000)
+
+    target = PoolTarget(bytes32(b"\xcd" * 32), uint32(1000))
+    assert str(target) == "PoolTarget(puzzle_hash: cdcdcdcd.., max_height: 1000)"
+    assert target.is_valid_at(1000)
+    assert not target.is_valid_at(1001)
@@ -0,0 +1,44 @@
+from typing import Optional
+
+from chia_rs import Coin, CoinState, RespondToCoinUpdates, RespondToPhUpdates
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint32, uint64
+
+
+def coin_state(i: int, created: Optional[int], spent: Optional[int]) -> CoinState:
+    coin = Coin(bytes32([i] * 32), bytes32([255 - i] * 32), uint64(i))
+    return CoinState(
+        coin,
+        None if spent is None else uint32(spent),
+        None if created is None else uint32(created),
+    )
+
+
+def test_split_for_max_size() -> None:
+    states = [coin_state(i, i, i + 10 if i % 2 else None) for i in range(100)]
+    msg = RespondToPhUpdates([bytes32(b"\xaa" * 32)], uint32(0), states)
+    parts = msg.split_for_max_size(1000)
+    assert len(parts) > 1
+    joined: list[CoinState] = []
+    for part in parts:
+        assert len(bytes(part)) <= 1000
+        assert part.puzzle_hashes == msg.puzzle_hashes
+        joined.extend(part.coin_states)
+    assert joined == states
+
+    assert msg.split_for_max_size(100000) == [msg]
+
+
+def test_apply_min_height_filter() -> None:
+    states = [
+        coin_state(1, 5, None),
+        coin_state(2, 5, 12),
+        coin_state(3, 10, None),
+        coin_state(4, None, None),
+    ]
+    msg = RespondToCoinUpdates([bytes32(b"\xbb" * 32)], uint32(0), states)
+    filtered = msg.apply_min_height_filter(10)
+    assert filtered.coin_states == states[1:3]
+    assert filtered.min_height == 10
+    # the original is unchanged
+    assert msg.coin_states == states
@@ -1,9 +1,21 @@
+import threading
+
 from chia_rs import (
+    AllocatorMetrics,
+    CancellationToken,
+    Coin,
+    ConditionsCache,
     run_block_generator,
     run_block_generator2,
+    run_block_generator_for_height,
+    run_block_generator_for_height_cached,
+    run_block_generator_for_height_cancellable,
+    run_block_generator_for_height_with_metrics,
+    solution_generator,
     G2Element,
     DONT_VALIDATE_SIGNATURE,
 )
+from chia_rs.sized_bytes import bytes32
 from run_gen import print_spend_bundle_conditions, DEFAULT_CONSTANTS
 
 
@@ -104,3 +116,243 @@ def test_run_block_generator_cost() -> None:
     # BLOCK_COST_EXCEEDS_MAX = 23
     assert err == 23
     assert conds is None
+
+
+def test_run_block_generator_for_height() -> None:
+    original_consensus_cost = 635805370
+    hard_fork_consensus_cost = 596498808
+
+    generator = bytes.fromhex(
+        open("generator-tests/block-834768.txt", "r").read().split("\n")[0]
+    )
+
+    hard_fork_height = DEFAULT_CONSTANTS.HARD_FORK_HEIGHT
+    for height, cost in [
+        (hard_fork_height - 1, original_consensus_cost),
+        (hard_fork_height, hard_fork_consensus_cost),
+    ]:
+        err, conds = run_block_generator_for_height(
+            generator,
+            [],
+            cost,
+            DONT_VALIDATE_SIGNATURE,
+            height,
+            G2Element(),
+            None,
+            DEFAULT_CONSTANTS,
+        )
+        assert err is None
+        assert conds is not None
+        assert conds.cost == cost
+
+        # BLOCK_COST_EXCEEDS_MAX = 23
+        err, conds = run_block_generator_for_height(
+            generator,
+            [],
+            cost - 1,
+            DONT_VALIDATE_SIGNATURE,
+            height,
+            G2Element(),
+            None,
+            DEFAULT_CONSTANTS,
+        )
+        assert err == 23
+        assert conds is None
+
+
+def test_run_block_generator_with_metrics() -> None:
+    height = DEFAULT_CONSTANTS.HARD_FORK_HEIGHT
+
+    def run(name: str) -> tuple[int, AllocatorMetrics]:
+        generator = bytes.fromhex(
+            open(f"generator-tests/{name}.txt", "r").read().split("\n")[0]
+        )
+        err, conds, metrics = run_block_generator_for_height_with_metrics(
+            generator,
+            [],
+            11000000000,
+            DONT_VALIDATE_SIGNATURE,
+            height,
+            G2Element(),
+            None,
+            DEFAULT_CONSTANTS,
+        )
+        assert err is None
+        assert conds is not None
+        assert metrics is not None
+
+        # the metrics don't affect the result
+        _, expected = run_block_generator_for_height(
+            generator,
+            [],
+            11000000000,
+            DONT_VALIDATE_SIGNATURE,
+            height,
+            G2Element(),
+            None,
+            DEFAULT_CONSTANTS,
+        )
+        # create_coin is printed in sorted order, its order in conds isn't
+        # stable between runs
+        assert print_spend_bundle_conditions(conds) == print_spend_bundle_conditions(
+            expected
+        )
+        return len(generator), metrics
+
+    small_len, small = run("block-834760")
+    large_len, large = run("block-c2a8df0d")
+    assert small_len < large_len
+    assert small.atoms < large.atoms
+    assert small.small_atoms < large.small_atoms
+    assert small.pairs < large.pairs
+    assert small.heap_size < large.heap_size
+
+    # a failing generator doesn't report metrics
+    err, conds, metrics = run_block_generator_for_height_with_metrics(
+        bytes.fromhex(
+            open("generator-tests/block-834760.txt", "r").read().split("\n")[0]
+        ),
+        [],
+        1,
+        DONT_VALIDATE_SIGNATURE,
+        height,
+        G2Element(),
+        None,
+        DEFAULT_CONSTANTS,
+    )
+    assert err is not None
+    assert conds is None
+    assert metrics is None
+
+
+def test_run_block_generator_cached() -> None:
+    generator = bytes.fromhex(
+        open("generator-tests/block-834768.txt", "r").read().split("\n")[0]
+    )
+    cache = ConditionsCache(max_entries=10)
+    hard_fork_height = DEFAULT_CONSTANTS.HARD_FORK_HEIGHT
+
+    def run(height: int) -> None:
+        err, conds = run_block_generator_for_height_cached(
+            generator,
+            [],
+            11000000000,
+            DONT_VALIDATE_SIGNATURE,
+            height,
+            G2Element(),
+            None,
+            cache,
+            DEFAULT_CONSTANTS,
+        )
+        assert err is None
+        _, expected = run_block_generator_for_height(
+            generator,
+            [],
+            11000000000,
+            DONT_VALIDATE_SIGNATURE,
+            height,
+            G2Element(),
+            None,
+            DEFAULT_CONSTANTS,
+        )
+        assert print_spend_bundle_conditions(conds) == print_spend_bundle_conditions(
+            expected
+        )
+
+    run(hard_fork_height)
+    run(hard_fork_height)
+    stats = cache.stats()
+    assert (stats.hits, stats.misses, stats.evictions) == (1, 1, 0)
+    assert cache.len() == 1
+    assert cache.size_bytes() > 0
+
+    # the flags differ on the other side of the hard fork
+    run(hard_fork_height - 1)
+    stats = cache.stats()
+    assert (stats.hits, stats.misses, stats.evictions) == (1, 2, 0)
+    assert cache.len() == 2
+
+    cache.clear()
+    assert cache.len() == 0
+
+
+# this puzzle counts down from 100000 to 0 and then returns no conditions
+LOOP_PUZZLE = bytes.fromhex(
+    "ff02ffff01ff02ffff03ff05ffff01ff02ff02ffff04ff02ffff04ffff11ff05ffff010180"
+    "ff80808080ffff01ff018080ff0180ffff04ffff01ff02ffff03ff05ffff01ff02ff02ffff"
+    "04ff02ffff04ffff11ff05ffff010180ff80808080ffff01ff018080ff0180ffff04ffff01"
+    "830186a0ff80808080"
+)
+
+
+ZERO_HASH = bytes32(bytes(32))
+
+
+def loop_generator(num_spends: int) -> bytes:
+    return solution_generator(
+        [
+            (Coin(bytes32(bytes([i] * 32)), ZERO_HASH, 1), LOOP_PUZZLE, b"\x80")
+            for i in range(num_spends)
+        ]
+    )
+
+
+def test_run_block_generator_cancellable() -> None:
+    height = DEFAULT_CONSTANTS.HARD_FORK_HEIGHT
+    generator = loop_generator(2)
+
+    token = CancellationToken()
+    err, conds = run_block_generator_for_height_cancellable(
+        generator,
+        [],
+        11000000000,
+        DONT_VALIDATE_SIGNATURE,
+        height,
+        G2Element(),
+        None,
+        DEFAULT_CONSTANTS,
+        token,
+    )
+    assert err is None
+    assert conds is not None
+    assert not token.is_cancelled()
+
+    _, expected = run_block_generator_for_height(
+        generator,
+        [],
+        11000000000,
+        DONT_VALIDATE_SIGNATURE,
+        height,
+        G2Element(),
+        None,
+        DEFAULT_CONSTANTS,
+    )
+    assert conds == expected
+
+
+def test_run_block_generator_cancel_from_thread() -> None:
+    height = DEFAULT_CONSTANTS.HARD_FORK_HEIGHT
+    # running all of these spends takes much longer than the timer. If
+    # cancellation didn't work, this would fail with BLOCK_COST_EXCEEDS_MAX
+    generator = loop_generator(250)
+
+    token = CancellationToken()
+    timer = threading.Timer(0.05, token.cancel)
+    timer.start()
+    err, conds = run_block_generator_for_height_cancellable(
+        generator,
+        [],
+        11000000000,
+        DONT_VALIDATE_SIGNATURE,
+        height,
+        G2Element(),
+        None,
+        DEFAULT_CONSTANTS,
+        token,
+    )
+    timer.join()
+
+    # CANCELLED = 149
+    assert err == 149
+    assert conds is None
+    assert token.is_cancelled()
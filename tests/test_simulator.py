@@ -0,0 +1,65 @@
+from chia_rs import Simulator, SpendBundle, CoinSpend, Coin, CoinState, Program
+from chia_rs import G2Element, ValidationError
+from chia_rs.sized_bytes import bytes32
+from run_gen import DEFAULT_CONSTANTS
+import pytest
+
+# the puzzle "1" returns its solution as the conditions
+PUZZLE = Program.to(1)
+PUZZLE_HASH = bytes32(PUZZLE.get_tree_hash())
+CREATE_COIN = 51
+
+
+def spend(coin: Coin, conditions: list) -> SpendBundle:
+    return SpendBundle([CoinSpend(coin, PUZZLE, Program.to(conditions))], G2Element())
+
+
+def test_create_and_spend() -> None:
+    sim = Simulator(DEFAULT_CONSTANTS)
+    assert sim.height == 0
+
+    rewards = sim.farm_block(PUZZLE_HASH)
+    assert sim.height == 1
+    assert [c.amount for c in rewards] == [1750000000000, 250000000000]
+    assert sim.unspent_coins(PUZZLE_HASH) == sorted(rewards, key=lambda c: c.name())
+
+    parent = rewards[0]
+    child = Coin(parent.name(), PUZZLE_HASH, parent.amount)
+    cost = sim.push_bundle(spend(parent, [[CREATE_COIN, PUZZLE_HASH, parent.amount]]))
+    assert cost > 0
+    assert sim.height == 2
+    assert sim.coin_state(parent.name()) == CoinState(parent, 2, 1)
+    assert sim.coin_state(child.name()) == CoinState(child, None, 2)
+
+    # spending the same coin again fails, and leaves the chain unchanged
+    with pytest.raises(ValidationError) as excinfo:
+        sim.push_bundle(spend(parent, []))
+    assert excinfo.value.message == "DoubleSpend"
+    # the arguments are the same as the ValueError raised by earlier versions
+    assert excinfo.value.args == ("ValidationError", excinfo.value.code)
+    assert isinstance(excinfo.value, ValueError)
+    assert sim.height == 2
+
+    sim.push_bundle(spend(child, []))
+    assert sim.coin_state(child.name()) == CoinState(child, 3, 2)
+
+
+def test_rewind() -> None:
+    sim = Simulator(DEFAULT_CONSTANTS)
+    coin = sim.farm_block(PUZZLE_HASH)[0]
+    sim.pass_time(100)
+    later = sim.farm_block(PUZZLE_HASH)[0]
+    sim.push_bundle(spend(coin, []))
+    assert sim.height == 3
+    assert sim.coin_state(coin.name()) == CoinState(coin, 3, 1)
+
+    sim.rewind(1)
+    assert sim.height == 1
+    assert sim.coin_state(coin.name()) == CoinState(coin, None, 1)
+    assert sim.coin_state(later.name()) is None
+
+    # the simulator is deterministic
+    sim.pass_time(100)
+    assert sim.farm_block(PUZZLE_HASH)[0] == later
+    other = Simulator(DEFAULT_CONSTANTS)
+    assert other.farm_block(PUZZLE_HASH)[0] == coin
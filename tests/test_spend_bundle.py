@@ -1,5 +1,9 @@
+import copy
+import pickle
 from typing import Any
-from chia_rs import SpendBundle
+from chia_rs import CoinSpend, FeeRate, Program, SpendBundle
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint64
 import pytest
 
 expected_add1 = """\
@@ -212,6 +216,55 @@ def test_spend_bundle(
     assert rem == expected_rem
 
 
+@pytest.mark.parametrize("input_file", ["3000253", "1000101"])
+def test_estimate_cost(input_file: str) -> None:
+    buf = open(f"test-bundles/{input_file}.bundle", "rb").read()
+    bundle = SpendBundle.from_bytes(buf)
+
+    for cs in bundle.coin_spends:
+        assert cs.serialized_length() == len(bytes(cs))
+
+    create_coin = (51, 1800000)
+    estimate = bundle.estimate_cost(12000, [create_coin] * len(bundle.additions()))
+    assert estimate.serialized_size == len(buf)
+    assert estimate.byte_cost == len(buf) * 12000
+    assert estimate.condition_cost == len(bundle.additions()) * 1800000
+    assert estimate.execution_cost > 0
+    assert estimate.cost == (
+        estimate.byte_cost + estimate.execution_cost + estimate.condition_cost
+    )
+    assert estimate.min_fee(FeeRate(uint64(5))) == estimate.cost * 5
+    assert estimate.min_fee(FeeRate(uint64(2**64 - 1))) is None
+
+
+
+def test_signing_digest() -> None:
+    buf = open("test-bundles/3000253.bundle", "rb").read()
+    bundle = SpendBundle.from_bytes(buf)
+    genesis_challenge = bytes32(b"\xcc" * 32)
+    digest = bundle.signing_digest(genesis_challenge)
+
+    normalized = bundle.normalized()
+    ids = [cs.coin.name() for cs in normalized.coin_spends]
+    assert ids == sorted(ids)
+    assert normalized.aggregated_signature == bundle.aggregated_signature
+
+    reversed_bundle = SpendBundle(
+        list(reversed(bundle.coin_spends)), bundle.aggregated_signature
+    )
+    assert reversed_bundle.normalized() == normalized
+    assert reversed_bundle.signing_digest(genesis_challenge) == digest
+
+    assert bundle.signing_digest(bytes32(b"\xdd" * 32)) != digest
+
+    first = bundle.coin_spends[0]
+    changed = CoinSpend(first.coin, first.puzzle_reveal, Program.from_bytes(b"\x80"))
+    changed_bundle = SpendBundle(
+        [changed] + bundle.coin_spends[1:], bundle.aggregated_signature
+    )
+    assert changed_bundle.signing_digest(genesis_challenge) != digest
+
+
 class NewAndImprovedSpendBundle(SpendBundle):
     test_bool = True
 
@@ -242,3 +295,50 @@ def test_derive_class():
     assert test.new_function()
 
     test = NewAndImprovedSpendBundle.parse_rust(obj_bytes)
+
+
+class AnnotatedSpendBundle(SpendBundle):
+    note: str
+    related: list[SpendBundle]
+
+
+def test_copy_derived_class() -> None:
+    buf = open("test-bundles/3000253.bundle", "rb").read()
+    bundle = AnnotatedSpendBundle.from_bytes(buf)
+    bundle.note = "extra"
+    # a container referencing the object itself
+    bundle.related = [bundle]
+
+    for clone in [
+        copy.copy(bundle),
+        copy.deepcopy(bundle),
+        pickle.loads(pickle.dumps(bundle)),
+    ]:
+        assert type(clone) is AnnotatedSpendBundle
+        assert clone is not bundle
+        assert clone == bundle
+        assert clone.note == "extra"
+
+    # a shallow copy shares the attributes
+    assert copy.copy(bundle).related is bundle.related
+
+    # a deep copy registers itself in the memo dict, so the cycle points back
+    # to the copy rather than to a second copy
+    clone = copy.deepcopy(bundle)
+    assert clone.related is not bundle.related
+    assert clone.related[0] is clone
+    clone = pickle.loads(pickle.dumps(bundle))
+    assert clone.related[0] is clone
+
+    pair = copy.deepcopy([bundle, bundle])
+    assert pair[0] is pair[1]
+
+    # instances of the rust class don't go through from_parent()
+    plain = SpendBundle.from_bytes(buf)
+    for clone in [
+        copy.copy(plain),
+        copy.deepcopy(plain),
+        pickle.loads(pickle.dumps(plain)),
+    ]:
+        assert type(clone) is SpendBundle
+        assert clone == plain
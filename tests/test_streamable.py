@@ -6,12 +6,19 @@ from chia_rs import (
     G2Element,
     Program,
     AugSchemeMPL,
+    CoinState,
+    ConsensusConstants,
+    RequestPuzzleSolution,
+    diff_json,
 )
-from chia_rs.sized_ints import uint64
+from chia_rs.sized_ints import uint32, uint64
 from chia_rs.sized_bytes import bytes32
 import pytest
 import copy
+import json
+import pickle
 import random
+import tracemalloc
 
 rng = random.Random(1337)
 sk = AugSchemeMPL.key_gen(bytes32.random(rng))
@@ -194,6 +201,30 @@ def test_from_json_spend() -> None:
     assert a == b
 
 
+def test_json_spend_bundle_conditions() -> None:
+
+    a = SpendBundleConditions(
+        [], 1000, 1337, 42, 100, None, [(pk, b"msg")], 12345678, 123, 456, True
+    )
+
+    # this is the same schema the serde support in chia-consensus produces
+    d = a.to_json_dict()
+    assert d == {
+        "spends": [],
+        "reserve_fee": 1000,
+        "height_absolute": 1337,
+        "seconds_absolute": 42,
+        "before_height_absolute": 100,
+        "before_seconds_absolute": None,
+        "agg_sig_unsafe": [["0x" + bytes(pk).hex(), "0x6d7367"]],
+        "cost": 12345678,
+        "removal_amount": 123,
+        "addition_amount": 456,
+        "validated_signature": True,
+    }
+    assert SpendBundleConditions.from_json_dict(d) == a
+
+
 def test_from_json_spend_set_optional() -> None:
 
     a = SpendConditions(
@@ -403,8 +434,9 @@ def test_json_spend_bundle_conditions() -> None:
         "before_seconds_absolute": None,
         "agg_sig_unsafe": [["0x" + bytes(pk).hex(), "0x6d7367"]],
         "cost": 12345678,
-        "removal_amount": 123,
-        "addition_amount": 456,
+        # u128 fields are emitted as strings
+        "removal_amount": "123",
+        "addition_amount": "456",
         "validated_signature": False,
     }
 
@@ -521,6 +553,26 @@ def test_coin_parse_rust() -> None:
     assert c1 == Coin(coin, ph2, uint64(0xFFFFFFFFFFFFFFFF))
 
 
+def test_json_missing_optional() -> None:
+    c = Coin(coin, ph, uint64(1))
+    state = CoinState(c, None, uint32(5))
+    d = state.to_json_dict()
+    # None is always written as null
+    assert d["spent_height"] is None
+    assert CoinState.from_json_dict(d) == state
+
+    # but optional fields may also be left out
+    del d["spent_height"]
+    assert CoinState.from_json_dict(d) == state
+
+    # other fields may not
+    del d["created_height"]
+    assert CoinState.from_json_dict(d) == CoinState(c, None, None)
+    del d["coin"]
+    with pytest.raises(KeyError):
+        CoinState.from_json_dict(d)
+
+
 def sha2(buf: bytes) -> bytes:
     from hashlib import sha256
 
@@ -540,6 +592,53 @@ def test_coin_get_hash() -> None:
     c3 = Coin(coin, ph2, uint64(0xFFFFFFFFFFFFFFFF))
     assert sha2(c3.to_bytes()) == c3.get_hash()
 
+    assert type(c1.get_hash()) is bytes32
+    assert c1.get_hash() == bytes32(sha2(c1.to_bytes()))
+
+
+# loads the extension module on its own, without the chia_rs python package
+# (and its sized_bytes module), and prints a coin's hash and its type
+STANDALONE_GET_HASH = """
+import sys
+from importlib.machinery import ExtensionFileLoader
+from importlib.util import module_from_spec, spec_from_loader
+
+sys.modules["chia_rs"] = None
+sys.modules["chia_rs.sized_bytes"] = None
+loader = ExtensionFileLoader("chia_rs", sys.argv[1])
+chia_rs = module_from_spec(spec_from_loader("chia_rs", loader))
+loader.exec_module(chia_rs)
+
+coin = chia_rs.Coin(bytes.fromhex(sys.argv[2]), bytes.fromhex(sys.argv[3]), 1000000)
+print(type(coin.get_hash()).__name__, coin.get_hash().hex())
+"""
+
+
+def test_get_hash_standalone() -> None:
+    import subprocess
+    import sys
+    import chia_rs.chia_rs
+
+    output = subprocess.run(
+        [
+            sys.executable,
+            "-c",
+            STANDALONE_GET_HASH,
+            chia_rs.chia_rs.__file__,
+            coin.hex(),
+            ph.hex(),
+        ],
+        check=True,
+        capture_output=True,
+        text=True,
+    ).stdout
+    ty, digest = output.split()
+
+    # without the bytes32 type, the hash is returned as plain bytes
+    assert ty == "bytes"
+    c1 = Coin(coin, ph, uint64(1000000))
+    assert bytes.fromhex(digest) == c1.get_hash()
+
 
 def test_g1_element() -> None:
 
@@ -645,3 +744,80 @@ def test_program() -> None:
     # garbage at the end of the serialization
     with pytest.raises(ValueError, match="invalid CLVM serialization"):
         Program.from_json_dict("0xff808080")
+
+
+def test_fields() -> None:
+    assert Coin.fields() == [
+        ("parent_coin_info", "bytes32"),
+        ("puzzle_hash", "bytes32"),
+        ("amount", "uint64"),
+    ]
+    assert CoinState.fields() == [
+        ("coin", "Coin"),
+        ("spent_height", "Optional[uint32]"),
+        ("created_height", "Optional[uint32]"),
+    ]
+
+    # the field names match the attribute names, even for upper-case ones
+    c = Coin(parent, ph, uint64(1337))
+    for name, _ in Coin.fields():
+        assert getattr(c, name) is not None
+    for name, _ in ConsensusConstants.fields():
+        assert name.isupper()
+
+
+def test_getter_allocations() -> None:
+    c = Coin(parent, ph, uint64(1337))
+    # Coin caches its bytes32 fields, RequestPuzzleSolution doesn't
+    request = RequestPuzzleSolution(ph, uint32(1))
+
+    # the cached fields return the same object every time
+    assert c.parent_coin_info is c.parent_coin_info
+    assert c.puzzle_hash is c.puzzle_hash
+    assert c.puzzle_hash == request.coin_name
+    assert request.coin_name is not request.coin_name
+
+    tracemalloc.start()
+    try:
+        start, _ = tracemalloc.get_traced_memory()
+        cached = [c.puzzle_hash for _ in range(1000)]
+        middle, _ = tracemalloc.get_traced_memory()
+        converted = [request.coin_name for _ in range(1000)]
+        end, _ = tracemalloc.get_traced_memory()
+    finally:
+        tracemalloc.stop()
+    assert len(cached) == len(converted)
+
+    # holding on to the results of the cached getter only costs the list,
+    # every uncached access allocates a new bytes32
+    assert (middle - start) * 4 < end - middle
+
+    # the cache is left out of pickles
+    assert len(c.__reduce__()) == 2
+    assert pickle.loads(pickle.dumps(c)) == c
+
+
+
+def test_diff() -> None:
+    state = CoinState(Coin(parent, ph, uint64(1337)), None, uint32(5))
+    assert state.diff(state) == []
+    assert json.loads(diff_json(state, state)) == []
+
+    other = CoinState(Coin(parent, ph2, uint64(1)), uint32(7), uint32(5))
+    expected = [
+        (
+            "coin.puzzle_hash",
+            "6162" * 8 + "... (32 bytes)",
+            "6364" * 8 + "... (32 bytes)",
+        ),
+        ("coin.amount", "1337", "1"),
+        ("spent_height", "None", "Some(7)"),
+    ]
+    assert state.diff(other) == expected
+    assert json.loads(diff_json(state, other)) == [
+        {"path": path, "left": left, "right": right} for path, left, right in expected
+    ]
+
+    # both values must have the same type
+    with pytest.raises(TypeError):
+        diff_json(state, state.coin)
@@ -0,0 +1,106 @@
+import importlib
+from hashlib import sha256
+import json
+import os
+from pathlib import Path
+from types import ModuleType
+from typing import Any, Optional
+
+import pytest
+
+import chia_rs
+
+# The corpus is generated by:
+#   cargo run -p chia-tools --bin gen-streamable-corpus -- streamable-corpus.jsonl
+corpus_path = Path(
+    os.environ.get(
+        "STREAMABLE_CORPUS",
+        Path(__file__).parent.parent / "streamable-corpus.jsonl",
+    )
+)
+
+# modules in chia-blockchain that may define their own (python) streamable
+# version of the types in the corpus
+chia_modules = [
+    "chia.protocols.full_node_protocol",
+    "chia.protocols.introducer_protocol",
+    "chia.protocols.shared_protocol",
+    "chia.protocols.wallet_protocol",
+    "chia.server.outbound_message",
+    "chia.consensus.block_record",
+    "chia.types.blockchain_format.foliage",
+    "chia.types.blockchain_format.pool_target",
+    "chia.types.blockchain_format.proof_of_space",
+    "chia.types.blockchain_format.reward_chain_block",
+    "chia.types.blockchain_format.slots",
+    "chia.types.blockchain_format.sub_epoch_summary",
+    "chia.types.blockchain_format.vdf",
+    "chia.types.coin_spend",
+    "chia.types.end_of_slot_bundle",
+    "chia.types.full_block",
+    "chia.types.header_block",
+    "chia.types.peer_info",
+    "chia.types.spend_bundle",
+    "chia.types.unfinished_block",
+    "chia.types.weight_proof",
+]
+
+
+def load_corpus() -> list[tuple[str, bytes]]:
+    if not corpus_path.exists():
+        return []
+    ret = []
+    with open(corpus_path) as f:
+        for line in f:
+            entry = json.loads(line)
+            ret.append((entry["type"], bytes.fromhex(entry["hex"])))
+    return ret
+
+
+def import_modules() -> list[ModuleType]:
+    ret = []
+    for name in chia_modules:
+        try:
+            ret.append(importlib.import_module(name))
+        except ImportError:
+            pass
+    return ret
+
+
+corpus = load_corpus()
+modules = import_modules()
+
+
+def python_class(name: str) -> Optional[Any]:
+    rust_class = getattr(chia_rs, name)
+    for m in modules:
+        cls = getattr(m, name, None)
+        if cls is not None and cls is not rust_class and hasattr(cls, "from_bytes"):
+            return cls
+    return None
+
+
+@pytest.mark.skipif(not corpus, reason="streamable corpus not generated")
+@pytest.mark.parametrize(
+    "name,blob", corpus, ids=[f"{name}-{i}" for i, (name, _) in enumerate(corpus)]
+)
+def test_streamable_corpus(name: str, blob: bytes) -> None:
+    cls = getattr(chia_rs, name)
+    obj = cls.from_bytes(blob)
+    assert bytes(obj) == blob
+    assert cls.parse_rust(blob) == (obj, len(blob))
+    assert obj.get_hash() == sha256(blob).digest()
+
+    # the python constructor takes the fields in streamable order
+    if hasattr(cls, "fields"):
+        fields = [getattr(obj, f) for f, _ in cls.fields()]
+        assert cls(*fields) == obj
+
+    if hasattr(obj, "to_json_dict"):
+        assert cls.from_json_dict(obj.to_json_dict()) == obj
+
+    # if chia-blockchain has its own version of this type, it must agree on
+    # the serialization
+    py_cls = python_class(name)
+    if py_cls is not None:
+        assert bytes(py_cls.from_bytes(blob)) == blob
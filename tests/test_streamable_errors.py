@@ -0,0 +1,51 @@
+import pytest
+
+from chia_rs import (
+    Coin,
+    InvalidEnumError,
+    Program,
+    RejectCoinState,
+    StreamableError,
+    TooLargeError,
+    TruncatedError,
+)
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint64
+
+COIN = bytes(Coin(bytes32(b"\x01" * 32), bytes32(b"\x02" * 32), uint64(3)))
+
+
+def test_hierarchy() -> None:
+    for err in [TruncatedError, InvalidEnumError, TooLargeError]:
+        assert issubclass(err, StreamableError)
+    assert issubclass(StreamableError, ValueError)
+
+
+@pytest.mark.parametrize("length", [0, 1, 32, 64, len(COIN) - 1])
+def test_truncated(length: int) -> None:
+    with pytest.raises(TruncatedError, match="unexpected end of buffer"):
+        Coin.from_bytes(COIN[:length])
+    with pytest.raises(TruncatedError):
+        Coin.parse_rust(COIN[:length])
+
+
+def test_truncated_program() -> None:
+    with pytest.raises(TruncatedError):
+        Program.from_bytes(bytes.fromhex("ff80"))
+    # existing code catching ValueError keeps working
+    with pytest.raises(ValueError, match="unexpected end of buffer"):
+        Program.from_bytes(bytes.fromhex("ff80"))
+
+
+@pytest.mark.parametrize("reason", [2, 3, 255])
+def test_invalid_enum(reason: int) -> None:
+    assert RejectCoinState.from_bytes(b"\x00").reason == 0
+    with pytest.raises(InvalidEnumError, match="invalid enum value"):
+        RejectCoinState.from_bytes(bytes([reason]))
+    with pytest.raises(ValueError):
+        RejectCoinState.from_bytes(bytes([reason]))
+
+
+def test_trailing_data() -> None:
+    with pytest.raises(TooLargeError, match="input buffer too large"):
+        Coin.from_bytes(COIN + b"\x00")
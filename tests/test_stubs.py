@@ -0,0 +1,34 @@
+import ast
+from pathlib import Path
+
+import chia_rs.chia_rs
+
+stub_file = Path(__file__).resolve().parent.parent / "wheel/python/chia_rs/chia_rs.pyi"
+
+
+def stub_names() -> set[str]:
+    tree = ast.parse(stub_file.read_text())
+    ret: set[str] = set()
+    for node in tree.body:
+        if isinstance(node, (ast.FunctionDef, ast.ClassDef)):
+            ret.add(node.name)
+        elif isinstance(node, ast.AnnAssign) and isinstance(node.target, ast.Name):
+            ret.add(node.target.id)
+        elif isinstance(node, ast.Assign):
+            for target in node.targets:
+                if isinstance(target, ast.Name):
+                    ret.add(target.id)
+    return ret
+
+
+def test_every_export_has_a_stub() -> None:
+    exported = {name for name in dir(chia_rs.chia_rs) if not name.startswith("_")}
+    missing = exported - stub_names()
+    assert missing == set(), f"missing from chia_rs.pyi: {sorted(missing)}"
+
+
+def test_every_stub_is_exported() -> None:
+    # type aliases only exist in the stub file
+    stubs = {name for name in stub_names() if not name.startswith("_")}
+    extra = stubs - set(dir(chia_rs.chia_rs)) - {"ReadableBuffer"}
+    assert extra == set(), f"not exported by chia_rs: {sorted(extra)}"
@@ -0,0 +1,85 @@
+import hashlib
+
+import pytest
+
+from chia_rs import (
+    AugSchemeMPL,
+    PrivateKey,
+    calculate_synthetic_offset,
+    calculate_synthetic_public_key,
+    calculate_synthetic_secret_key,
+    is_synthetic_of,
+)
+from chia_rs.sized_bytes import bytes32
+
+DEFAULT_HIDDEN_PUZZLE_HASH = bytes32.fromhex(
+    "711d6c4e32c92e53179b199484cf8c897542bc57f2b22582799f9d657eec4699"
+)
+GROUP_ORDER = 0x73EDA753299D7D483339D80809A1D80553BDA402FFFE5BFEFFFFFFFF00000001
+
+MASTER_SK = PrivateKey.from_bytes(
+    bytes.fromhex("6bb19282e27bc6e7e397fb19efc2627a412410fdfd13bf14f4ce5bfdce084c71")
+)
+
+
+def wallet_sk(index: int) -> PrivateKey:
+    sk = MASTER_SK
+    for i in [12381, 8444, 2, index]:
+        sk = AugSchemeMPL.derive_child_sk_unhardened(sk, i)
+    return sk
+
+
+@pytest.mark.parametrize(
+    "index,synthetic_sk,synthetic_pk",
+    [
+        (
+            0,
+            "64c91fe4534fc21c36096be012e0e14de484180a1a510783367bcd5ccecaad0c",
+            "b0c8cf08fdbe7fdb7bb1795740153b944c32364b100c372a05833554cb97794563b096cb5f57bfa09f38d7aebb48704e",
+        ),
+        (
+            1,
+            "13a0f95de0dd347c769ee79e9828a698bfe53429233375e891f05b4e0eaa8219",
+            "8b1b92da63fdf8c4b53349da2fdd84685303587653f1a75826a56a97ea50b86ca8a0fbf6a5d6605c70b6be324bc59c85",
+        ),
+        (
+            15,
+            "68fe97fadd389f4051b6bd0c320f17d08ba7b4951b33d525b1f23d38226e5d0a",
+            "b3b4ceea11bbc6fafb5800caa593385644a3262245357e5013be5c1cf622bf7cb0b667e586269c346459c3b5faf0eaef",
+        ),
+    ],
+)
+def test_known_synthetic_keys(index: int, synthetic_sk: str, synthetic_pk: str) -> None:
+    sk = wallet_sk(index)
+    pk = sk.get_g1()
+
+    assert bytes(
+        calculate_synthetic_secret_key(sk, DEFAULT_HIDDEN_PUZZLE_HASH)
+    ) == bytes.fromhex(synthetic_sk)
+    synthetic = calculate_synthetic_public_key(pk, DEFAULT_HIDDEN_PUZZLE_HASH)
+    assert bytes(synthetic) == bytes.fromhex(synthetic_pk)
+    assert is_synthetic_of(synthetic, pk, DEFAULT_HIDDEN_PUZZLE_HASH)
+
+    # the offset is the sha256 of the public key and the hidden puzzle hash,
+    # as an int modulo the group order
+    digest = hashlib.sha256(bytes(pk) + DEFAULT_HIDDEN_PUZZLE_HASH).digest()
+    offset = calculate_synthetic_offset(pk, DEFAULT_HIDDEN_PUZZLE_HASH)
+    assert offset == int.from_bytes(digest, "big", signed=True) % GROUP_ORDER
+
+
+def test_sign_with_synthetic_key() -> None:
+    sk = AugSchemeMPL.key_gen(bytes([1] * 32))
+    pk = sk.get_g1()
+
+    synthetic_sk = calculate_synthetic_secret_key(sk, DEFAULT_HIDDEN_PUZZLE_HASH)
+    synthetic_pk = calculate_synthetic_public_key(pk, DEFAULT_HIDDEN_PUZZLE_HASH)
+    assert synthetic_sk.get_g1() == synthetic_pk
+
+    msg = b"standard puzzle spend"
+    sig = AugSchemeMPL.sign(synthetic_sk, msg)
+    assert AugSchemeMPL.verify(synthetic_pk, msg, sig)
+    assert not AugSchemeMPL.verify(pk, msg, sig)
+
+    assert is_synthetic_of(synthetic_pk, pk, DEFAULT_HIDDEN_PUZZLE_HASH)
+    assert not is_synthetic_of(pk, pk, DEFAULT_HIDDEN_PUZZLE_HASH)
+    assert not is_synthetic_of(synthetic_pk, pk, bytes32([0] * 32))
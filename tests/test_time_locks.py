@@ -0,0 +1,130 @@
+from typing import Optional
+
+import pytest
+
+from chia_rs import (
+    SpendBundleConditions,
+    SpendConditions,
+    ValidationError,
+    satisfiable_at,
+)
+from chia_rs.sized_bytes import bytes32
+
+COIN_ID = bytes32(b"\x01" * 32)
+EPHEMERAL_ID = bytes32(b"\x02" * 32)
+# the coin was confirmed at height 100, timestamp 10000
+CONFIRM_INFO = {COIN_ID: (100, 10000)}
+
+ASSERT_HEIGHT_ABSOLUTE_FAILED = 14
+ASSERT_SECONDS_ABSOLUTE_FAILED = 15
+ASSERT_SECONDS_RELATIVE_FAILED = 105
+ASSERT_BEFORE_HEIGHT_ABSOLUTE_FAILED = 130
+ASSERT_BEFORE_HEIGHT_RELATIVE_FAILED = 131
+EPHEMERAL_RELATIVE_CONDITION = 141
+
+
+def spend(
+    coin_id: bytes32,
+    seconds_relative: Optional[int] = None,
+    before_height_relative: Optional[int] = None,
+) -> SpendConditions:
+    return SpendConditions(
+        coin_id,
+        bytes32(b"\x00" * 32),
+        bytes32(b"\x00" * 32),
+        1,
+        None,
+        seconds_relative,
+        before_height_relative,
+        None,
+        None,
+        None,
+        [],
+        [],
+        [],
+        [],
+        [],
+        [],
+        [],
+        [],
+        0,
+    )
+
+
+def bundle(
+    spends: list[SpendConditions],
+    height_absolute: int = 0,
+    seconds_absolute: int = 0,
+    before_height_absolute: Optional[int] = None,
+) -> SpendBundleConditions:
+    return SpendBundleConditions(
+        spends,
+        0,
+        height_absolute,
+        seconds_absolute,
+        before_height_absolute,
+        None,
+        [],
+        0,
+        0,
+        0,
+        True,
+    )
+
+
+@pytest.mark.parametrize(
+    "conds,height,timestamp,expected",
+    [
+        (bundle([spend(COIN_ID)]), 0, 0, None),
+        (
+            bundle([spend(COIN_ID)], height_absolute=200),
+            199,
+            0,
+            ASSERT_HEIGHT_ABSOLUTE_FAILED,
+        ),
+        (bundle([spend(COIN_ID)], height_absolute=200), 200, 0, None),
+        (
+            bundle([spend(COIN_ID)], seconds_absolute=20000),
+            0,
+            19999,
+            ASSERT_SECONDS_ABSOLUTE_FAILED,
+        ),
+        (bundle([spend(COIN_ID)], before_height_absolute=200), 199, 0, None),
+        (
+            bundle([spend(COIN_ID)], before_height_absolute=200),
+            200,
+            0,
+            ASSERT_BEFORE_HEIGHT_ABSOLUTE_FAILED,
+        ),
+        (
+            bundle([spend(COIN_ID, seconds_relative=100)]),
+            0,
+            10099,
+            ASSERT_SECONDS_RELATIVE_FAILED,
+        ),
+        (bundle([spend(COIN_ID, seconds_relative=100)]), 0, 10100, None),
+        (bundle([spend(COIN_ID, before_height_relative=10)]), 109, 0, None),
+        (
+            bundle([spend(COIN_ID, before_height_relative=10)]),
+            110,
+            0,
+            ASSERT_BEFORE_HEIGHT_RELATIVE_FAILED,
+        ),
+        (bundle([spend(COIN_ID), spend(EPHEMERAL_ID)]), 0, 0, None),
+        (
+            bundle([spend(COIN_ID), spend(EPHEMERAL_ID, seconds_relative=0)]),
+            0,
+            0,
+            EPHEMERAL_RELATIVE_CONDITION,
+        ),
+    ],
+)
+def test_satisfiable_at(
+    conds: SpendBundleConditions, height: int, timestamp: int, expected: Optional[int]
+) -> None:
+    if expected is None:
+        satisfiable_at(conds, height, timestamp, CONFIRM_INFO)
+    else:
+        with pytest.raises(ValidationError) as e:
+            satisfiable_at(conds, height, timestamp, CONFIRM_INFO)
+        assert e.value.code == expected
@@ -0,0 +1,43 @@
+import os
+import subprocess
+import sys
+
+import pytest
+from chia_rs import enable_tracing
+
+# a block generator with a single spend of a coin with amount 123, whose
+# puzzle returns (ASSERT_MY_AMOUNT 100)
+PUZZLE = "ff01ffff49ff648080"
+SPEND = "ffa0" + "01" * 32 + "ff" + PUZZLE + "ff7bff8080"
+GENERATOR = "ff01ffff" + SPEND + "8080"
+
+SCRIPT = f"""
+from chia_rs import enable_tracing, run_block_generator2, G2Element
+from run_gen import DEFAULT_CONSTANTS
+
+enable_tracing("debug")
+err, conds = run_block_generator2(
+    bytes.fromhex("{GENERATOR}"), [], 11000000000, 0, G2Element(), None, DEFAULT_CONSTANTS
+)
+assert conds is None
+"""
+
+
+def test_enable_tracing() -> None:
+    # the subscriber is global, so this runs in a process of its own
+    result = subprocess.run(
+        [sys.executable, "-c", SCRIPT],
+        cwd=os.path.dirname(__file__),
+        capture_output=True,
+        text=True,
+    )
+    assert result.returncode == 0, result.stderr
+    assert "error=AssertMyAmountFailed" in result.stderr
+    assert "asserted=100" in result.stderr
+    assert "coin_amount=123" in result.stderr
+    assert "run_block_generator2" in result.stderr
+
+
+def test_invalid_level() -> None:
+    with pytest.raises(ValueError, match="invalid tracing level"):
+        enable_tracing("loud")
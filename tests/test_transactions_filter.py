@@ -0,0 +1,120 @@
+from hashlib import sha256
+
+import pytest
+from chiabip158 import PyBIP158
+
+from chia_rs import (
+    ALLOW_BACKREFS,
+    Coin,
+    additions_and_removals,
+    compute_transactions_filter,
+    verify_transactions_filter,
+)
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint64
+from run_gen import DEFAULT_CONSTANTS
+
+
+def coin(puzzle_hash: int) -> Coin:
+    return Coin(bytes32(b"\xaa" * 32), bytes32(bytes([puzzle_hash] * 32)), uint64(1))
+
+
+def coin_id(i: int) -> bytes32:
+    return bytes32(bytes([i] * 32))
+
+
+# this is how chia-blockchain computes the transactions filter of a block
+def bip158_filter(additions: list[Coin], removals: list[bytes32]) -> bytes:
+    items = [bytearray(c.puzzle_hash) for c in additions]
+    items += [bytearray(name) for name in removals]
+    return bytes(PyBIP158(items).GetEncoded())
+
+
+def test_empty_filter() -> None:
+    f = compute_transactions_filter([], [])
+    assert f == b"\x00"
+    assert (
+        sha256(f).hexdigest()
+        == "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d"
+    )
+
+
+@pytest.mark.parametrize(
+    "additions, removals, expected",
+    [
+        ([1], [], "0123c660"),
+        ([], [1], "0123c660"),
+        ([1, 2], [], "02478cc8cfa480"),
+        ([1, 2], [3], "036b5331377690c00a"),
+        ([3, 1], [2], "036b5331377690c00a"),
+        ([1, 1, 2], [3, 2], "036b5331377690c00a"),
+    ],
+)
+def test_transactions_filter(
+    additions: list[int], removals: list[int], expected: str
+) -> None:
+    f = compute_transactions_filter(
+        [coin(ph) for ph in additions], [coin_id(i) for i in removals]
+    )
+    assert f.hex() == expected
+    assert f == bip158_filter(
+        [coin(ph) for ph in additions], [coin_id(i) for i in removals]
+    )
+    verify_transactions_filter(
+        f, [coin(ph) for ph in additions], [coin_id(i) for i in removals]
+    )
+
+
+@pytest.mark.parametrize(
+    "filter", ["", "01", "0123c6", "0000", "0123c66000", "0123c661", "fdffff00"]
+)
+def test_invalid_filter(filter: str) -> None:
+    with pytest.raises(ValueError, match="invalid transactions filter"):
+        verify_transactions_filter(bytes.fromhex(filter), [], [])
+
+
+def test_missing_items() -> None:
+    f = compute_transactions_filter([coin(1)], [coin_id(3)])
+    verify_transactions_filter(f, [coin(1)], [coin_id(3)])
+    with pytest.raises(ValueError, match="missing from the transactions filter"):
+        verify_transactions_filter(f, [coin(1), coin(2)], [coin_id(3)])
+    with pytest.raises(ValueError, match="missing from the transactions filter"):
+        verify_transactions_filter(f, [coin(1)], [coin_id(4)])
+
+
+@pytest.mark.parametrize("name", ["block-834752", "block-834760", "block-834768"])
+def test_generator_block(name: str) -> None:
+    with open(f"generator-tests/{name}.txt") as f:
+        generator = bytes.fromhex(f.read().split("\n", 1)[0])
+
+    additions, removals = additions_and_removals(
+        generator, [], ALLOW_BACKREFS, DEFAULT_CONSTANTS
+    )
+    added = [c for c, _ in additions]
+    removed = [c.name() for c in removals]
+
+    f = compute_transactions_filter(added, removed)
+    assert f == bip158_filter(added, removed)
+    verify_transactions_filter(f, added, removed)
+
+    # a node can't hide a spend by leaving it out of the filter
+    f = compute_transactions_filter(added, removed[1:])
+    with pytest.raises(ValueError, match="missing from the transactions filter"):
+        verify_transactions_filter(f, added, removed)
+
+
+@pytest.mark.parametrize("name", ["block-834752", "block-834760", "block-834768"])
+def test_verify_bip158_filter(name: str) -> None:
+    with open(f"generator-tests/{name}.txt") as f:
+        generator = bytes.fromhex(f.read().split("\n", 1)[0])
+
+    additions, removals = additions_and_removals(
+        generator, [], ALLOW_BACKREFS, DEFAULT_CONSTANTS
+    )
+    added = [c for c, _ in additions]
+    removed = [c.name() for c in removals]
+
+    # the filters chia-blockchain builds must pass verification
+    verify_transactions_filter(bip158_filter(added, removed), added, removed)
+    with pytest.raises(ValueError, match="missing from the transactions filter"):
+        verify_transactions_filter(bip158_filter(added, removed[1:]), added, removed)
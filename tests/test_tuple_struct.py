@@ -0,0 +1,65 @@
+import pytest
+
+from chia_rs import TupleStreamableTest
+from chia_rs.sized_bytes import bytes32
+from chia_rs.sized_ints import uint32, uint64
+
+
+def test_tuple_struct() -> None:
+    value = TupleStreamableTest(
+        bytes32(b"\x01" * 32), uint64(1337), "foobar", [uint32(1), uint32(2)]
+    )
+
+    # it behaves like a tuple
+    assert len(value) == 4
+    assert value[0] == bytes32(b"\x01" * 32)
+    assert value[1] == 1337
+    assert value[2] == "foobar"
+    assert value[3] == [1, 2]
+    assert value[-1] == value[3]
+    assert value[-4] == value[0]
+    with pytest.raises(IndexError):
+        value[4]
+    with pytest.raises(IndexError):
+        value[-5]
+    assert list(value) == [value[0], value[1], value[2], value[3]]
+    assert TupleStreamableTest(*value) == value
+
+    # the constructor takes keyword arguments too
+    assert (
+        TupleStreamableTest(
+            field_0=bytes32(b"\x01" * 32),
+            field_1=uint64(1337),
+            field_2="foobar",
+            field_3=[uint32(1), uint32(2)],
+        )
+        == value
+    )
+    assert [name for name, _ in TupleStreamableTest.fields()] == [
+        "field_0",
+        "field_1",
+        "field_2",
+        "field_3",
+    ]
+
+    # the fields are streamed in order
+    blob = bytes(value)
+    assert blob == (
+        b"\x01" * 32
+        + bytes(uint64(1337))
+        + b"\x01\x00\x00\x00\x06foobar"
+        + b"\x00\x00\x00\x02\x00\x00\x00\x01\x00\x00\x00\x02"
+    )
+    assert TupleStreamableTest.from_bytes(blob) == value
+    assert TupleStreamableTest.parse_rust(blob) == (value, len(blob))
+
+    # and it's represented as a list in JSON
+    json_dict = value.to_json_dict()
+    assert json_dict == ["0x" + "01" * 32, 1337, "foobar", [1, 2]]
+    assert TupleStreamableTest.from_json_dict(json_dict) == value
+    assert (
+        TupleStreamableTest.from_json_bytes(b'["0x' + b"01" * 32 + b'", 1337, null, []]')
+        == TupleStreamableTest(bytes32(b"\x01" * 32), uint64(1337), None, [])
+    )
+    with pytest.raises(ValueError, match="expected 4 elements, got 3"):
+        TupleStreamableTest.from_json_dict(json_dict[:3])
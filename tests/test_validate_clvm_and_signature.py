@@ -1,4 +1,5 @@
-from chia_rs import validate_clvm_and_signature
+from chia_rs import validate_clvm_and_signature, validate_serialized_clvm_and_signature
+from chia_rs import SignatureError, ValidationError
 from chia_rs import SpendBundle, CoinSpend, Coin, Program, PrivateKey, AugSchemeMPL
 from run_gen import DEFAULT_CONSTANTS
 import pytest
@@ -45,12 +46,18 @@ def test_validate_clvm_and_signature():
     sig = AugSchemeMPL.sign(sk, result)
     spend_bundle = SpendBundle([spend], sig)
 
-    with pytest.raises(TypeError) as excinfo:
+    with pytest.raises(SignatureError) as excinfo:
         validate_clvm_and_signature(
             spend_bundle, DEFAULT_CONSTANTS.MAX_BLOCK_COST_CLVM, DEFAULT_CONSTANTS, 246
         )
     error_code = excinfo.value.args[0]
     assert error_code == 7  # 7 = BadAggregateSignature
+    assert excinfo.value.code == 7
+    assert excinfo.value.message == "BadAggregateSignature"
+    assert isinstance(excinfo.value, ValidationError)
+    # earlier versions raised a plain TypeError here
+    assert isinstance(excinfo.value, TypeError)
+    assert isinstance(excinfo.value, ValueError)
 
     # Invalid key
     sk_hex = (
@@ -62,9 +69,63 @@ def test_validate_clvm_and_signature():
     sig = AugSchemeMPL.sign(sk, result)
     spend_bundle = SpendBundle([spend], sig)
 
-    with pytest.raises(TypeError) as excinfo:
+    with pytest.raises(SignatureError) as excinfo:
         validate_clvm_and_signature(
             spend_bundle, DEFAULT_CONSTANTS.MAX_BLOCK_COST_CLVM, DEFAULT_CONSTANTS, 246
         )
     error_code = excinfo.value.args[0]
     assert error_code == 7  # 7 = BadAggregateSignature
+    assert excinfo.value.code == 7
+    assert excinfo.value.message == "BadAggregateSignature"
+    assert isinstance(excinfo.value, ValidationError)
+    # earlier versions raised a plain TypeError here
+    assert isinstance(excinfo.value, TypeError)
+    assert isinstance(excinfo.value, ValueError)
+
+
+def test_validate_serialized_clvm_and_signature():
+    sk = PrivateKey.from_bytes(
+        bytes.fromhex(
+            "52d75c4707e39595b27314547f9723e5530c01198af3fc5849d9a7af65631efb"
+        )
+    )
+    test_coin = Coin(b"\x44" * 32, Program.to(1).get_tree_hash(), 1)
+    # ((50 <public key> "hello"))
+    solution = Program.from_bytes(
+        bytes.fromhex(
+            "ffff32ffb0997cc43ed8788f841fcf3071f6f212b89ba494b6ebaf1bda88c3f9de9d968a61f3b7284a5ee13889399ca71a026549a2ff8568656c6c6f8080"
+        )
+    )
+    spend = CoinSpend(test_coin, Program.to(1), solution)
+    msg = b"hello" + test_coin.name() + DEFAULT_CONSTANTS.AGG_SIG_ME_ADDITIONAL_DATA
+    spend_bundle = SpendBundle([spend], AugSchemeMPL.sign(sk, msg))
+    max_cost = DEFAULT_CONSTANTS.MAX_BLOCK_COST_CLVM
+
+    conds, pairs, _ = validate_clvm_and_signature(
+        spend_bundle, max_cost, DEFAULT_CONSTANTS, 1
+    )
+    buf = bytes(spend_bundle)
+    # a non-contiguous buffer is copied rather than borrowed
+    strided = memoryview(bytes(b for c in buf for b in (c, 0)))[::2]
+    for blob in (buf, bytearray(buf), memoryview(buf), strided):
+        result = validate_serialized_clvm_and_signature(
+            blob, max_cost, DEFAULT_CONSTANTS, 1
+        )
+        assert result[0] == conds
+        assert result[1] == pairs
+
+    with pytest.raises(ValidationError) as excinfo:
+        validate_serialized_clvm_and_signature(buf[:-1], max_cost, DEFAULT_CONSTANTS, 1)
+    assert excinfo.value.args[0] == 126  # 126 = InvalidSpendBundle
+    assert excinfo.value.code == 126
+    assert excinfo.value.message == "InvalidSpendBundle"
+    assert isinstance(excinfo.value, TypeError)
+
+    bad_bundle = SpendBundle([spend], AugSchemeMPL.sign(sk, b"goodbye"))
+    with pytest.raises(SignatureError) as excinfo:
+        validate_serialized_clvm_and_signature(
+            bytes(bad_bundle), max_cost, DEFAULT_CONSTANTS, 1
+        )
+    assert excinfo.value.args[0] == 7  # 7 = BadAggregateSignature
+    assert excinfo.value.code == 7
+    assert excinfo.value.message == "BadAggregateSignature"
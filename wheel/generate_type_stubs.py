@@ -1,16 +1,22 @@
 from pathlib import Path
 from typing import Optional, TextIO
 from glob import glob
+import re
 
 output_file = Path(__file__).parent.resolve() / "python" / "chia_rs" / "chia_rs.pyi"
 crates_dir = Path(__file__).parent.parent.resolve() / "crates"
 input_dir = crates_dir / "chia-protocol" / "src"
+wheel_src_dir = Path(__file__).parent.resolve() / "src"
 
 # enums are exposed to python as int
 enums = set(
     ["NodeType", "ProtocolMessageTypes", "RejectStateReason", "MempoolRemoveReason"]
 )
 
+# #[streamable] tuple structs, which behave like python tuples. Filled in by
+# parse_rust_source()
+tuple_structs: set[str] = set()
+
 
 def transform_type(m: str) -> str:
     n, t = m.split(":")
@@ -30,6 +36,8 @@ def print_class(
     extra: Optional[list[str]] = None,
     martial_for_json_hint: Optional[str] = None,
     unmartial_from_json_hint: Optional[str] = None,
+    has_getters: bool = True,
+    tuple_struct: bool = False,
 ):
     def add_indent(x: str):
         return "\n    " + x
@@ -47,10 +55,21 @@ def print_class(
         replace_param_name, replace_type = m.split(":")
         if replace_param_name.startswith("a") and replace_param_name[1:].isnumeric():
             continue
+        if tuple_struct:
+            continue
         all_replace_parameters.append(
             f"{replace_param_name}: Union[{replace_type}, _Unspec] = _Unspec()"
         )
 
+    # the fields of a tuple struct are only accessible by index
+    if tuple_struct:
+        members = [
+            "def __len__(self) -> int: ...",
+            "def __getitem__(self, index: int) -> Any: ...",
+        ]
+    else:
+        members = list(members)
+
     if extra is not None:
         members.extend(extra)
 
@@ -80,12 +99,22 @@ class {name}:{"".join(map(add_indent, members))}
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: {name}) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> {martial_for_json_hint}: ...
     @classmethod
     def from_json_dict(cls, json_dict: {unmartial_from_json_hint}) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
 """
     )
 
+    if has_getters:
+        file.write(
+            """    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
+"""
+        )
+
     if len(all_replace_parameters) > 0:
         indent = ",\n        "
         file.write(
@@ -104,6 +133,7 @@ def rust_type_to_python(t: str) -> str:
         .replace("Option", "Optional")
         .replace("Bytes", "bytes")
         .replace("String", "str")
+        .replace("Amount", "u64")
         .replace("u8", "uint8")
         .replace("u16", "uint16")
         .replace("u32", "uint32")
@@ -125,9 +155,15 @@ def parse_rust_source(filename: str, upper_case: bool) -> list[tuple[str, list[s
     ret: list[tuple[str, list[str]]] = []
     in_struct: Optional[str] = None
     members: list[str] = []
+    streamable = False
     with open(filename) as f:
         for line in f:
             if not in_struct:
+                if line.startswith("#[streamable"):
+                    streamable = True
+                    continue
+                elif not line.startswith("#"):
+                    was_streamable, streamable = streamable, False
                 if line.startswith("pub struct ") and "{" in line:
                     in_struct = line.split("pub struct ")[1].split("{")[0].strip()
                 elif line.startswith("streamable_struct!") and "{" in line:
@@ -140,9 +176,14 @@ def parse_rust_source(filename: str, upper_case: bool) -> list[tuple[str, list[s
                     name = line.split("pub struct ")[1].split("(")[0].strip()
                     rust_args = line.split("(")[1].split(");")[0]
                     args = []
+                    # the python constructor of a streamable tuple struct
+                    # names its parameters field_0, field_1 etc.
+                    prefix = "field_" if was_streamable else "a"
                     for idx, rust_type in enumerate(rust_args.split(",")):
                         py_type = rust_type_to_python(rust_type)
-                        args.append(f"a{idx}: {py_type}")
+                        args.append(f"{prefix}{idx}: {py_type}")
+                    if was_streamable:
+                        tuple_structs.add(name)
                     ret.append((name, args))
                     continue
                 else:
@@ -176,7 +217,41 @@ def parse_rust_source(filename: str, upper_case: bool) -> list[tuple[str, list[s
     return ret
 
 
+def exported_constants(filename: str) -> list[str]:
+    # integer constants are registered with m.add("NAME", value). Exception
+    # types are added the same way, via py.get_type::<T>(), so skip those
+    ret: list[str] = []
+    with open(filename) as f:
+        for name, value in re.findall(
+            r'm\.add\(\s*"([A-Za-z0-9_]+)",\s*([^;]*?)\s*,?\s*\)\?;', f.read()
+        ):
+            if "get_type" in value:
+                continue
+            ret.append(name)
+    return ret
+
+
+def exported_functions(directory: Path) -> list[str]:
+    # the python name of every #[pyfunction], taking #[pyo3(name = "...")]
+    # into account
+    ret: list[str] = []
+    for filepath in sorted(glob(str(directory / "*.rs"))):
+        with open(filepath) as f:
+            for attrs, name in re.findall(
+                r"#\[pyfunction\]((?:\s*#\[[^\n]*\])*)\s*(?:pub )?fn (\w+)", f.read()
+            ):
+                rename = re.search(r'name\s*=\s*"(\w+)"', attrs)
+                ret.append(rename.group(1) if rename else name)
+    return ret
+
+
 extra_members = {
+    "FeeRate": [
+        "def fee_for_cost(self, cost: int) -> Optional[int]: ...",
+        "@staticmethod\n    def from_fee_rounding_down(fee: int, cost: int) -> Optional[FeeRate]: ...",
+        "@staticmethod\n    def from_fee_rounding_up(fee: int, cost: int) -> Optional[FeeRate]: ...",
+        "def is_met_by(self, fee: int, cost: int) -> bool: ...",
+    ],
     "Coin": [
         "def name(self) -> bytes32: ...",
     ],
@@ -184,12 +259,17 @@ extra_members = {
         "@staticmethod\n    def create(bytes) -> ClassgroupElement: ...",
         "@staticmethod\n    def get_default_element() -> ClassgroupElement: ...",
         "@staticmethod\n    def get_size() -> int: ...",
+        "def is_canonical(self) -> bool: ...",
+    ],
+    "VDFProof": [
+        "def validate_shape(self, constants: ConsensusConstants) -> None: ...",
     ],
     "UnfinishedBlock": [
         "prev_header_hash: bytes32",
         "partial_hash: bytes32",
         "def is_transaction_block(self) -> bool: ...",
         "total_iters: uint128",
+        "def complete(self, infusion: InfusionPoint) -> FullBlock: ...",
     ],
     "FullBlock": [
         "prev_header_hash: bytes32",
@@ -200,6 +280,7 @@ extra_members = {
         "weight: uint128",
         "def get_included_reward_coins(self) -> list[Coin]: ...",
         "def is_fully_compactified(self) -> bool: ...",
+        "def to_header_block(self, transactions_filter: bytes) -> HeaderBlock: ...",
     ],
     "HeaderBlock": [
         "prev_header_hash: bytes32",
@@ -219,6 +300,18 @@ extra_members = {
     ],
     "RewardChainBlock": [
         "def get_unfinished(self) -> RewardChainBlockUnfinished: ...",
+        "def validate_iters(self) -> None: ...",
+    ],
+    "RespondToPhUpdates": [
+        "def split_for_max_size(self, max_bytes: int) -> list[RespondToPhUpdates]: ...",
+        "def apply_min_height_filter(self, min_height: int) -> RespondToPhUpdates: ...",
+    ],
+    "RespondToCoinUpdates": [
+        "def split_for_max_size(self, max_bytes: int) -> list[RespondToCoinUpdates]: ...",
+        "def apply_min_height_filter(self, min_height: int) -> RespondToCoinUpdates: ...",
+    ],
+    "PoolTarget": [
+        "def is_valid_at(self, height: int) -> bool: ...",
     ],
     "SubSlotData": [
         "def is_end_of_slot(self) -> bool: ...",
@@ -235,12 +328,22 @@ extra_members = {
         "@staticmethod\n    def from_program(p: ChiaProgram) -> Program: ...",
         "def to_program(self) -> ChiaProgram: ...",
         "def uncurry(self) -> tuple[ChiaProgram, ChiaProgram]: ...",
+        "def curry(self, *args: object) -> Program: ...",
     ],
     "SpendBundle": [
         "@classmethod\n    def aggregate(cls, spend_bundles: list[SpendBundle]) -> Self: ...",
         "def name(self) -> bytes32: ...",
         "def removals(self) -> list[Coin]: ...",
         "def additions(self) -> list[Coin]: ...",
+        "def estimate_cost(self, per_byte_cost: int, condition_costs: list[tuple[int, int]]) -> CostEstimate: ...",
+        "def normalized(self) -> SpendBundle: ...",
+        "def signing_digest(self, genesis_challenge: bytes32) -> bytes32: ...",
+    ],
+    "CoinSpend": [
+        "def serialized_length(self) -> int: ...",
+    ],
+    "CostEstimate": [
+        "def min_fee(self, rate: FeeRate) -> Optional[int]: ...",
     ],
     "BlockRecord": [
         "is_transaction_block: bool",
@@ -256,7 +359,16 @@ extra_members = {
 
 classes = []
 for filepath in sorted(glob(str(input_dir / "*.rs"))):
-    if filepath.endswith("bytes.rs") or filepath.endswith("lazy_node.rs"):
+    if (
+        filepath.endswith("bytes.rs")
+        or filepath.endswith("lazy_node.rs")
+        or filepath.endswith("amount.rs")
+        or filepath.endswith("streamable_iter.rs")
+        or filepath.endswith("coin_state_delta.rs")
+        or filepath.endswith("codec.rs")
+        or filepath.endswith("streamable_registry.rs")
+        or filepath.endswith("streamable_since.rs")
+    ):
         continue
     classes.extend(parse_rust_source(filepath, upper_case=False))
 
@@ -274,10 +386,10 @@ with open(output_file, "w") as file:
 # this file is generated by generate_type_stubs.py
 #
 
-from typing import Optional, Sequence, Union, Any, ClassVar, final
+from typing import AbstractSet, Optional, Sequence, Union, Any, ClassVar, final
 from .sized_bytes import bytes32, bytes100
 from .sized_ints import uint8, uint16, uint32, uint64, uint128, int8, int16, int32, int64
-from typing_extensions import Self
+from typing_extensions import Self, deprecated
 from chia.types.blockchain_format.program import Program as ChiaProgram
 
 ReadableBuffer = Union[bytes, bytearray, memoryview]
@@ -290,21 +402,152 @@ def solution_generator_backrefs(spends: Sequence[tuple[Coin, bytes, bytes]]) ->
 
 def compute_merkle_set_root(values: Sequence[bytes]) -> bytes: ...
 
+def compute_transactions_filter(additions: Sequence[Coin], removals: Sequence[bytes32]) -> bytes: ...
+def verify_transactions_filter(filter: bytes, additions: Sequence[Coin], removals: Sequence[bytes32]) -> None: ...
+
+def is_overflow_block(constants: ConsensusConstants, signage_point_index: int) -> bool: ...
+def calculate_sp_interval_iters(constants: ConsensusConstants, sub_slot_iters: int) -> int: ...
+def calculate_sp_iters(constants: ConsensusConstants, sub_slot_iters: int, signage_point_index: int) -> int: ...
+def calculate_ip_iters(
+    constants: ConsensusConstants,
+    sub_slot_iters: int,
+    signage_point_index: int,
+    required_iters: int,
+) -> int: ...
+def calculate_deficit(
+    constants: ConsensusConstants,
+    height: int,
+    prev: Optional[BlockRecord],
+    overflow: bool,
+    num_finished_sub_slots: int,
+) -> int: ...
+def block_to_block_record(
+    constants: ConsensusConstants,
+    block: FullBlock,
+    required_iters: int,
+    sub_slot_iters: int,
+    prev: Optional[BlockRecord],
+    sub_epoch_summary: Optional[SubEpochSummary],
+) -> BlockRecord: ...
+def calculate_iterations_quality(
+    difficulty_constant_factor: int,
+    quality_string: bytes32,
+    size: int,
+    difficulty: int,
+    cc_sp_output_hash: bytes32,
+) -> int: ...
+def is_valid_partial(
+    constants: ConsensusConstants,
+    quality_string: bytes32,
+    size: int,
+    difficulty: int,
+    cc_sp_output_hash: bytes32,
+    sub_slot_iters: Optional[int] = None,
+) -> bool: ...
+def max_partial_difficulty(
+    constants: ConsensusConstants,
+    quality_string: bytes32,
+    size: int,
+    cc_sp_output_hash: bytes32,
+    sub_slot_iters: Optional[int] = None,
+) -> int: ...
+def calculate_prefix_bits(constants: ConsensusConstants, height: int) -> int: ...
+def passes_plot_filter(
+    constants: ConsensusConstants,
+    height: int,
+    plot_id: bytes32,
+    challenge_hash: bytes32,
+    signage_point: bytes32,
+) -> bool: ...
+def calculate_plot_id_pk(pool_pk: G1Element, plot_pk: G1Element) -> bytes32: ...
+def calculate_plot_id_ph(pool_contract_ph: bytes32, plot_pk: G1Element) -> bytes32: ...
+def generate_plot_public_key(
+    local_pk: G1Element, farmer_pk: G1Element, include_taproot: bool = False
+) -> G1Element: ...
+
+def calculate_pool_reward(height: int) -> int: ...
+def calculate_base_farmer_reward(height: int) -> int: ...
+def expected_reward_coins(
+    height: int,
+    pool_puzzle_hash: bytes32,
+    farmer_puzzle_hash: bytes32,
+    fees: int,
+    genesis_challenge: bytes32,
+) -> list[Coin]: ...
+def validate_reward_coins(claimed: list[Coin], expected: list[Coin]) -> None: ...
+def compute_block_fees(conds: SpendBundleConditions) -> int: ...
+def validate_block_fees(conds: SpendBundleConditions, claimed_fees: int) -> None: ...
+def satisfiable_at(conds: SpendBundleConditions, block_height: int, block_timestamp: int, coin_confirm_info: dict[bytes32, tuple[int, int]]) -> None: ...
+
+def calculate_synthetic_offset(public_key: G1Element, hidden_puzzle_hash: bytes32) -> int: ...
+def calculate_synthetic_public_key(public_key: G1Element, hidden_puzzle_hash: bytes32) -> G1Element: ...
+def calculate_synthetic_secret_key(secret_key: PrivateKey, hidden_puzzle_hash: bytes32) -> PrivateKey: ...
+def is_synthetic_of(synthetic_key: G1Element, original_key: G1Element, hidden_puzzle_hash: bytes32) -> bool: ...
+
+def compress_with_dict(data: bytes, dict_id: Optional[int] = None) -> bytes: ...
+def decompress_with_dict(data: bytes, max_decompressed_size: int) -> bytes: ...
+
 def supports_fast_forward(spend: CoinSpend) -> bool : ...
 def fast_forward_singleton(spend: CoinSpend, new_coin: Coin, new_parent: Coin) -> bytes: ...
 
+@deprecated("use run_block_generator_for_height() instead")
 def run_block_generator(
     program: ReadableBuffer, block_refs: list[ReadableBuffer], max_cost: int, flags: int, signature: G2Element, bls_cache: Optional[BLSCache], constants: ConsensusConstants
 ) -> tuple[Optional[int], Optional[SpendBundleConditions]]: ...
 
+@deprecated("use run_block_generator_for_height() instead")
 def run_block_generator2(
     program: ReadableBuffer, block_refs: list[ReadableBuffer], max_cost: int, flags: int, signature: G2Element, bls_cache: Optional[BLSCache], constants: ConsensusConstants
 ) -> tuple[Optional[int], Optional[SpendBundleConditions]]: ...
 
+def run_block_generator_for_height(
+    program: ReadableBuffer, block_refs: list[ReadableBuffer], max_cost: int, flags: int, height: int, signature: G2Element, bls_cache: Optional[BLSCache], constants: ConsensusConstants
+) -> tuple[Optional[int], Optional[SpendBundleConditions]]: ...
+
+def run_block_generator_for_height_with_metrics(
+    program: ReadableBuffer, block_refs: list[ReadableBuffer], max_cost: int, flags: int, height: int, signature: G2Element, bls_cache: Optional[BLSCache], constants: ConsensusConstants
+) -> tuple[Optional[int], Optional[SpendBundleConditions], Optional[AllocatorMetrics]]: ...
+
+def run_block_generator_for_height_cancellable(
+    program: ReadableBuffer, block_refs: list[ReadableBuffer], max_cost: int, flags: int, height: int, signature: G2Element, bls_cache: Optional[BLSCache], constants: ConsensusConstants, cancellation_token: CancellationToken
+) -> tuple[Optional[int], Optional[SpendBundleConditions]]: ...
+
+@final
+class CancellationToken:
+    def __init__(self) -> None: ...
+    def cancel(self) -> None: ...
+    def is_cancelled(self) -> bool: ...
+
+def run_block_generator_for_height_cached(
+    program: ReadableBuffer, block_refs: list[ReadableBuffer], max_cost: int, flags: int, height: int, signature: G2Element, bls_cache: Optional[BLSCache], conditions_cache: ConditionsCache, constants: ConsensusConstants
+) -> tuple[Optional[int], Optional[SpendBundleConditions]]: ...
+
+@final
+class ConditionsCache:
+    def __init__(self, max_entries: int = 1000, max_bytes: int = 100000000) -> None: ...
+    def len(self) -> int: ...
+    def size_bytes(self) -> int: ...
+    def stats(self) -> ConditionsCacheStats: ...
+    def clear(self) -> None: ...
+
+@final
+class ConditionsCacheStats:
+    hits: int
+    misses: int
+    evictions: int
+
 def additions_and_removals(
     program: ReadableBuffer, block_refs: list[ReadableBuffer], flags: int, constants: ConsensusConstants
 ) -> tuple[list[tuple[Coin, Optional[bytes]]], list[Coin]]: ...
 
+def additions_and_removals_filtered(
+    program: ReadableBuffer, block_refs: list[ReadableBuffer], flags: int, constants: ConsensusConstants, watch_puzzle_hashes: AbstractSet[bytes32], watch_hints: AbstractSet[bytes32]
+) -> tuple[list[tuple[Coin, Optional[bytes]]], list[Coin], int]: ...
+
+def block_coin_report(
+    program: ReadableBuffer, block_refs: list[ReadableBuffer], flags: int, height: int, farmer_puzzle_hash: bytes32, pool_puzzle_hash: bytes32, constants: ConsensusConstants
+) -> BlockCoinReport: ...
+
 def confirm_included_already_hashed(
     root: bytes32,
     item: bytes32,
@@ -317,6 +560,18 @@ def confirm_not_included_already_hashed(
     proof: bytes,
 ) -> bool: ...
 
+def verify_additions_proofs(
+    header: HeaderBlock,
+    coins: Sequence[tuple[bytes32, Sequence[Coin]]],
+    proofs: Optional[Sequence[tuple[bytes32, bytes, Optional[bytes]]]] = None,
+) -> None: ...
+
+def verify_removals_proofs(
+    header: HeaderBlock,
+    coins: Sequence[tuple[bytes32, Optional[Coin]]],
+    proofs: Optional[Sequence[tuple[bytes32, bytes]]] = None,
+) -> None: ...
+
 def validate_clvm_and_signature(
     new_spend: SpendBundle,
     max_cost: int,
@@ -324,6 +579,13 @@ def validate_clvm_and_signature(
     peak_height: int,
 ) -> tuple[SpendBundleConditions, list[tuple[bytes32, GTElement]], float]: ...
 
+def validate_serialized_clvm_and_signature(
+    new_spend: ReadableBuffer,
+    max_cost: int,
+    constants: ConsensusConstants,
+    peak_height: int,
+) -> tuple[SpendBundleConditions, list[tuple[bytes32, GTElement]], float]: ...
+
 def get_conditions_from_spendbundle(
     spend_bundle: SpendBundle,
     max_cost: int,
@@ -331,25 +593,31 @@ def get_conditions_from_spendbundle(
     height: int,
 ) -> SpendBundleConditions: ...
 
+def conflicts_with(a: SpendBundleConditions, b: SpendBundleConditions) -> Conflict: ...
+def select_spends(items: Sequence[MempoolCandidate], max_cost: int) -> MempoolSelection: ...
+
+def diff_coin_states(old: Sequence[CoinState], new: Sequence[CoinState]) -> CoinStateDelta: ...
+def diff_json(a: object, b: object) -> str: ...
+
 def get_flags_for_height_and_constants(
     height: int,
     constants: ConsensusConstants
 ) -> int: ...
 
+def enable_tracing(level: str = "debug") -> None: ...
+
+class ValidationError(ValueError, TypeError):
+    code: int
+    message: str
 
-NO_UNKNOWN_CONDS: int = ...
-STRICT_ARGS_COUNT: int = ...
-LIMIT_HEAP: int = ...
-ENABLE_KECCAK: int = ...
-ENABLE_KECCAK_OPS_OUTSIDE_GUARD: int = ...
-MEMPOOL_MODE: int = ...
-ALLOW_BACKREFS: int = ...
-DONT_VALIDATE_SIGNATURE: int = ...
+class CostError(ValidationError): ...
+class SignatureError(ValidationError): ...
 
-ELIGIBLE_FOR_DEDUP: int = ...
-ELIGIBLE_FOR_FF: int = ...
+class StreamableError(ValueError): ...
+class TruncatedError(StreamableError): ...
+class InvalidEnumError(StreamableError): ...
+class TooLargeError(StreamableError): ...
 
-NO_UNKNOWN_OPS: int = ...
 
 def run_chia_program(
     program: bytes, args: bytes, max_cost: int, flags: int
@@ -367,12 +635,29 @@ def get_puzzle_and_solution_for_coin2(generator: Program, block_refs: list[Reada
 
 @final
 class BLSCache:
-    def __init__(self, cache_size: Optional[int] = 50000) -> None: ...
+    def __init__(self, cache_size: Optional[int] = 50000, *, size_bytes: Optional[int] = None, ttl: Optional[float] = None) -> None: ...
     def len(self) -> int: ...
     def aggregate_verify(self, pks: list[G1Element], msgs: list[bytes], sig: G2Element) -> bool: ...
     def items(self) -> list[tuple[bytes, GTElement]]: ...
     def update(self, other: Sequence[tuple[bytes, GTElement]]) -> None: ...
     def evict(self, pks: list[G1Element], msgs: list[bytes]) -> None: ...
+    def evict_hashes(self, hashes: Sequence[bytes]) -> None: ...
+    def stats(self) -> BLSCacheStats: ...
+    def serialize(self) -> bytes: ...
+    @staticmethod
+    def deserialize(data: bytes, size: Optional[int] = None, *, size_bytes: Optional[int] = None) -> BLSCache: ...
+
+@final
+class BLSCacheStats:
+    hits: int
+    misses: int
+    evictions: int
+
+def generate_mnemonic() -> str: ...
+def validate_mnemonic(phrase: str) -> None: ...
+def mnemonic_to_entropy(phrase: str) -> bytes: ...
+def mnemonic_from_entropy(entropy: bytes) -> str: ...
+def mnemonic_to_seed(phrase: str, passphrase: str = "") -> bytes: ...
 
 @final
 class AugSchemeMPL:
@@ -395,6 +680,62 @@ class AugSchemeMPL:
     @staticmethod
     def derive_child_pk_unhardened(pk: G1Element, index: int) -> G1Element: ...
 
+@final
+class PopSchemeMPL:
+    @staticmethod
+    def sign(sk: PrivateKey, msg: bytes) -> G2Element: ...
+    @staticmethod
+    def aggregate(sigs: Sequence[G2Element]) -> G2Element: ...
+    @staticmethod
+    def verify(pk: G1Element, msg: bytes, sig: G2Element) -> bool: ...
+    @staticmethod
+    def pop_prove(sk: PrivateKey) -> G2Element: ...
+    @staticmethod
+    def pop_verify(pk: G1Element, proof: G2Element) -> bool: ...
+    @staticmethod
+    def fast_aggregate_verify(pks: Sequence[G1Element], msg: bytes, sig: G2Element) -> bool: ...
+
+@final
+class AllocatorMetrics:
+    atoms: int
+    small_atoms: int
+    pairs: int
+    heap_size: int
+
+@final
+class CoinStateDelta:
+    created: list[CoinState]
+    spent: list[CoinState]
+    reorged: list[CoinState]
+    updated: list[CoinState]
+    unchanged: list[CoinState]
+    def is_empty(self) -> bool: ...
+    def max_height(self) -> Optional[int]: ...
+
+@final
+class Conflict:
+    shared_spends: list[bytes32]
+    shared_additions: list[bytes32]
+    a_depends_on_b: list[bytes32]
+    b_depends_on_a: list[bytes32]
+    def is_empty(self) -> bool: ...
+
+@final
+class MempoolCandidate:
+    id: bytes32
+    fee: int
+    cost: int
+    conditions: SpendBundleConditions
+    def __init__(
+        self, id: bytes32, fee: int, cost: int, conditions: SpendBundleConditions
+    ) -> None: ...
+
+@final
+class MempoolSelection:
+    ids: list[bytes32]
+    fee: int
+    cost: int
+
 @final
 class MerkleSet:
     def get_root(self) -> bytes32: ...
@@ -403,9 +744,40 @@ class MerkleSet:
         self,
         leafs: list[bytes32],
     ) -> None: ...
+
+class MerkleTree:
+    def __init__(self, leaves: list[bytes32]) -> None: ...
+    @property
+    def leaves(self) -> list[bytes32]: ...
+    def root(self) -> bytes32: ...
+    def proof_for(self, index: int) -> MerkleProof: ...
+
+class MerkleProof:
+    path: int
+    hashes: list[bytes32]
+    def __init__(self, path: int, hashes: list[bytes32]) -> None: ...
+    def verify(self, root: bytes32, leaf: bytes32) -> bool: ...
+    def to_program(self) -> Program: ...
+
+class Simulator:
+    def __init__(self, constants: ConsensusConstants) -> None: ...
+    @property
+    def height(self) -> int: ...
+    @property
+    def timestamp(self) -> int: ...
+    def pass_time(self, seconds: uint64) -> None: ...
+    def farm_block(self, puzzle_hash: bytes32) -> list[Coin]: ...
+    def push_bundle(self, spend_bundle: SpendBundle) -> int: ...
+    def coin_state(self, coin_id: bytes32) -> Optional[CoinState]: ...
+    def unspent_coins(self, puzzle_hash: bytes32) -> list[Coin]: ...
+    def rewind(self, height: uint32) -> None: ...
 """
     )
 
+    file.write("\n")
+    for name in exported_constants(str(wheel_src_dir / "api.rs")):
+        file.write(f"{name}: int = ...\n")
+
     print_class(
         file,
         "G1Element",
@@ -425,6 +797,7 @@ class MerkleSet:
         ],
         martial_for_json_hint="str",
         unmartial_from_json_hint="Union[str, bytes]",
+        has_getters=False,
     )
     print_class(
         file,
@@ -442,6 +815,7 @@ class MerkleSet:
         ],
         martial_for_json_hint="str",
         unmartial_from_json_hint="Union[str, bytes]",
+        has_getters=False,
     )
     print_class(
         file,
@@ -452,8 +826,12 @@ class MerkleSet:
             "def __str__(self) -> str: ...",
             "def __mul__(self, rhs: GTElement) -> GTElement: ...",
             "def __imul__(self, rhs: GTElement) -> GTElement : ...",
+            "def to_canonical_bytes(self) -> bytes: ...",
+            "@staticmethod",
+            "def from_canonical_bytes(data: bytes) -> GTElement: ...",
         ],
         martial_for_json_hint="str",
+        has_getters=False,
     )
     print_class(
         file,
@@ -469,8 +847,11 @@ class MerkleSet:
             "def derive_unhardened(self, idx: int) -> PrivateKey: ...",
             "@staticmethod",
             "def from_seed(seed: bytes) -> PrivateKey: ...",
+            "@staticmethod",
+            "def from_mnemonic(phrase: str) -> PrivateKey: ...",
         ],
         martial_for_json_hint="str",
+        has_getters=False,
     )
 
     print_class(
@@ -497,6 +878,35 @@ class MerkleSet:
             "agg_sig_parent_puzzle: list[tuple[G1Element, bytes]]",
             "flags: int",
         ],
+        extra=[
+            "eligible_for_dedup: bool",
+            "eligible_for_fast_forward: bool",
+        ],
+        has_getters=False,
+    )
+
+    print_class(
+        file,
+        "CoinAddition",
+        [
+            "coin: Coin",
+            "hint: Optional[bytes]",
+            "memos: list[bytes]",
+        ],
+        has_getters=False,
+    )
+
+    print_class(
+        file,
+        "BlockCoinReport",
+        [
+            "height: int",
+            "additions: list[CoinAddition]",
+            "removals: list[Coin]",
+            "reward_coins: list[Coin]",
+            "fees: int",
+        ],
+        has_getters=False,
     )
 
     print_class(
@@ -515,6 +925,7 @@ class MerkleSet:
             "addition_amount: int",
             "validated_signature: bool",
         ],
+        has_getters=False,
     )
 
     for item in classes:
@@ -522,6 +933,8 @@ class MerkleSet:
         martial_for_json_hint = None
         if item[0] == "Program":
             martial_for_json_hint = "str"
+        elif item[0] in tuple_structs:
+            martial_for_json_hint = "list[Any]"
 
         print_class(
             file,
@@ -529,4 +942,18 @@ class MerkleSet:
             item[1],
             extra_members.get(item[0]),
             martial_for_json_hint=martial_for_json_hint,
+            # Program is a tuple struct and has no getters
+            has_getters=item[0] != "Program",
+            tuple_struct=item[0] in tuple_structs,
         )
+
+# every function exported by the module must have a (hand-written) stub
+with open(output_file) as f:
+    stubs = f.read()
+missing = [
+    name
+    for name in exported_functions(wheel_src_dir)
+    if re.search(rf"^def {name}\(", stubs, re.MULTILINE) is None
+]
+if missing:
+    raise SystemExit(f"missing type stubs for functions: {', '.join(missing)}")
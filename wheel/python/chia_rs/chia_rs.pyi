@@ -3,10 +3,10 @@
 # this file is generated by generate_type_stubs.py
 #
 
-from typing import Optional, Sequence, Union, Any, ClassVar, final
+from typing import AbstractSet, Optional, Sequence, Union, Any, ClassVar, final
 from .sized_bytes import bytes32, bytes100
 from .sized_ints import uint8, uint16, uint32, uint64, uint128, int8, int16, int32, int64
-from typing_extensions import Self
+from typing_extensions import Self, deprecated
 from chia.types.blockchain_format.program import Program as ChiaProgram
 
 ReadableBuffer = Union[bytes, bytearray, memoryview]
@@ -19,21 +19,152 @@ def solution_generator_backrefs(spends: Sequence[tuple[Coin, bytes, bytes]]) ->
 
 def compute_merkle_set_root(values: Sequence[bytes]) -> bytes: ...
 
+def compute_transactions_filter(additions: Sequence[Coin], removals: Sequence[bytes32]) -> bytes: ...
+def verify_transactions_filter(filter: bytes, additions: Sequence[Coin], removals: Sequence[bytes32]) -> None: ...
+
+def is_overflow_block(constants: ConsensusConstants, signage_point_index: int) -> bool: ...
+def calculate_sp_interval_iters(constants: ConsensusConstants, sub_slot_iters: int) -> int: ...
+def calculate_sp_iters(constants: ConsensusConstants, sub_slot_iters: int, signage_point_index: int) -> int: ...
+def calculate_ip_iters(
+    constants: ConsensusConstants,
+    sub_slot_iters: int,
+    signage_point_index: int,
+    required_iters: int,
+) -> int: ...
+def calculate_deficit(
+    constants: ConsensusConstants,
+    height: int,
+    prev: Optional[BlockRecord],
+    overflow: bool,
+    num_finished_sub_slots: int,
+) -> int: ...
+def block_to_block_record(
+    constants: ConsensusConstants,
+    block: FullBlock,
+    required_iters: int,
+    sub_slot_iters: int,
+    prev: Optional[BlockRecord],
+    sub_epoch_summary: Optional[SubEpochSummary],
+) -> BlockRecord: ...
+def calculate_iterations_quality(
+    difficulty_constant_factor: int,
+    quality_string: bytes32,
+    size: int,
+    difficulty: int,
+    cc_sp_output_hash: bytes32,
+) -> int: ...
+def is_valid_partial(
+    constants: ConsensusConstants,
+    quality_string: bytes32,
+    size: int,
+    difficulty: int,
+    cc_sp_output_hash: bytes32,
+    sub_slot_iters: Optional[int] = None,
+) -> bool: ...
+def max_partial_difficulty(
+    constants: ConsensusConstants,
+    quality_string: bytes32,
+    size: int,
+    cc_sp_output_hash: bytes32,
+    sub_slot_iters: Optional[int] = None,
+) -> int: ...
+def calculate_prefix_bits(constants: ConsensusConstants, height: int) -> int: ...
+def passes_plot_filter(
+    constants: ConsensusConstants,
+    height: int,
+    plot_id: bytes32,
+    challenge_hash: bytes32,
+    signage_point: bytes32,
+) -> bool: ...
+def calculate_plot_id_pk(pool_pk: G1Element, plot_pk: G1Element) -> bytes32: ...
+def calculate_plot_id_ph(pool_contract_ph: bytes32, plot_pk: G1Element) -> bytes32: ...
+def generate_plot_public_key(
+    local_pk: G1Element, farmer_pk: G1Element, include_taproot: bool = False
+) -> G1Element: ...
+
+def calculate_pool_reward(height: int) -> int: ...
+def calculate_base_farmer_reward(height: int) -> int: ...
+def expected_reward_coins(
+    height: int,
+    pool_puzzle_hash: bytes32,
+    farmer_puzzle_hash: bytes32,
+    fees: int,
+    genesis_challenge: bytes32,
+) -> list[Coin]: ...
+def validate_reward_coins(claimed: list[Coin], expected: list[Coin]) -> None: ...
+def compute_block_fees(conds: SpendBundleConditions) -> int: ...
+def validate_block_fees(conds: SpendBundleConditions, claimed_fees: int) -> None: ...
+def satisfiable_at(conds: SpendBundleConditions, block_height: int, block_timestamp: int, coin_confirm_info: dict[bytes32, tuple[int, int]]) -> None: ...
+
+def calculate_synthetic_offset(public_key: G1Element, hidden_puzzle_hash: bytes32) -> int: ...
+def calculate_synthetic_public_key(public_key: G1Element, hidden_puzzle_hash: bytes32) -> G1Element: ...
+def calculate_synthetic_secret_key(secret_key: PrivateKey, hidden_puzzle_hash: bytes32) -> PrivateKey: ...
+def is_synthetic_of(synthetic_key: G1Element, original_key: G1Element, hidden_puzzle_hash: bytes32) -> bool: ...
+
+def compress_with_dict(data: bytes, dict_id: Optional[int] = None) -> bytes: ...
+def decompress_with_dict(data: bytes, max_decompressed_size: int) -> bytes: ...
+
 def supports_fast_forward(spend: CoinSpend) -> bool : ...
 def fast_forward_singleton(spend: CoinSpend, new_coin: Coin, new_parent: Coin) -> bytes: ...
 
+@deprecated("use run_block_generator_for_height() instead")
 def run_block_generator(
     program: ReadableBuffer, block_refs: list[ReadableBuffer], max_cost: int, flags: int, signature: G2Element, bls_cache: Optional[BLSCache], constants: ConsensusConstants
 ) -> tuple[Optional[int], Optional[SpendBundleConditions]]: ...
 
+@deprecated("use run_block_generator_for_height() instead")
 def run_block_generator2(
     program: ReadableBuffer, block_refs: list[ReadableBuffer], max_cost: int, flags: int, signature: G2Element, bls_cache: Optional[BLSCache], constants: ConsensusConstants
 ) -> tuple[Optional[int], Optional[SpendBundleConditions]]: ...
 
+def run_block_generator_for_height(
+    program: ReadableBuffer, block_refs: list[ReadableBuffer], max_cost: int, flags: int, height: int, signature: G2Element, bls_cache: Optional[BLSCache], constants: ConsensusConstants
+) -> tuple[Optional[int], Optional[SpendBundleConditions]]: ...
+
+def run_block_generator_for_height_with_metrics(
+    program: ReadableBuffer, block_refs: list[ReadableBuffer], max_cost: int, flags: int, height: int, signature: G2Element, bls_cache: Optional[BLSCache], constants: ConsensusConstants
+) -> tuple[Optional[int], Optional[SpendBundleConditions], Optional[AllocatorMetrics]]: ...
+
+def run_block_generator_for_height_cancellable(
+    program: ReadableBuffer, block_refs: list[ReadableBuffer], max_cost: int, flags: int, height: int, signature: G2Element, bls_cache: Optional[BLSCache], constants: ConsensusConstants, cancellation_token: CancellationToken
+) -> tuple[Optional[int], Optional[SpendBundleConditions]]: ...
+
+@final
+class CancellationToken:
+    def __init__(self) -> None: ...
+    def cancel(self) -> None: ...
+    def is_cancelled(self) -> bool: ...
+
+def run_block_generator_for_height_cached(
+    program: ReadableBuffer, block_refs: list[ReadableBuffer], max_cost: int, flags: int, height: int, signature: G2Element, bls_cache: Optional[BLSCache], conditions_cache: ConditionsCache, constants: ConsensusConstants
+) -> tuple[Optional[int], Optional[SpendBundleConditions]]: ...
+
+@final
+class ConditionsCache:
+    def __init__(self, max_entries: int = 1000, max_bytes: int = 100000000) -> None: ...
+    def len(self) -> int: ...
+    def size_bytes(self) -> int: ...
+    def stats(self) -> ConditionsCacheStats: ...
+    def clear(self) -> None: ...
+
+@final
+class ConditionsCacheStats:
+    hits: int
+    misses: int
+    evictions: int
+
 def additions_and_removals(
     program: ReadableBuffer, block_refs: list[ReadableBuffer], flags: int, constants: ConsensusConstants
 ) -> tuple[list[tuple[Coin, Optional[bytes]]], list[Coin]]: ...
 
+def additions_and_removals_filtered(
+    program: ReadableBuffer, block_refs: list[ReadableBuffer], flags: int, constants: ConsensusConstants, watch_puzzle_hashes: AbstractSet[bytes32], watch_hints: AbstractSet[bytes32]
+) -> tuple[list[tuple[Coin, Optional[bytes]]], list[Coin], int]: ...
+
+def block_coin_report(
+    program: ReadableBuffer, block_refs: list[ReadableBuffer], flags: int, height: int, farmer_puzzle_hash: bytes32, pool_puzzle_hash: bytes32, constants: ConsensusConstants
+) -> BlockCoinReport: ...
+
 def confirm_included_already_hashed(
     root: bytes32,
     item: bytes32,
@@ -46,6 +177,18 @@ def confirm_not_included_already_hashed(
     proof: bytes,
 ) -> bool: ...
 
+def verify_additions_proofs(
+    header: HeaderBlock,
+    coins: Sequence[tuple[bytes32, Sequence[Coin]]],
+    proofs: Optional[Sequence[tuple[bytes32, bytes, Optional[bytes]]]] = None,
+) -> None: ...
+
+def verify_removals_proofs(
+    header: HeaderBlock,
+    coins: Sequence[tuple[bytes32, Optional[Coin]]],
+    proofs: Optional[Sequence[tuple[bytes32, bytes]]] = None,
+) -> None: ...
+
 def validate_clvm_and_signature(
     new_spend: SpendBundle,
     max_cost: int,
@@ -53,6 +196,13 @@ def validate_clvm_and_signature(
     peak_height: int,
 ) -> tuple[SpendBundleConditions, list[tuple[bytes32, GTElement]], float]: ...
 
+def validate_serialized_clvm_and_signature(
+    new_spend: ReadableBuffer,
+    max_cost: int,
+    constants: ConsensusConstants,
+    peak_height: int,
+) -> tuple[SpendBundleConditions, list[tuple[bytes32, GTElement]], float]: ...
+
 def get_conditions_from_spendbundle(
     spend_bundle: SpendBundle,
     max_cost: int,
@@ -60,25 +210,31 @@ def get_conditions_from_spendbundle(
     height: int,
 ) -> SpendBundleConditions: ...
 
+def conflicts_with(a: SpendBundleConditions, b: SpendBundleConditions) -> Conflict: ...
+def select_spends(items: Sequence[MempoolCandidate], max_cost: int) -> MempoolSelection: ...
+
+def diff_coin_states(old: Sequence[CoinState], new: Sequence[CoinState]) -> CoinStateDelta: ...
+def diff_json(a: object, b: object) -> str: ...
+
 def get_flags_for_height_and_constants(
     height: int,
     constants: ConsensusConstants
 ) -> int: ...
 
+def enable_tracing(level: str = "debug") -> None: ...
 
-NO_UNKNOWN_CONDS: int = ...
-STRICT_ARGS_COUNT: int = ...
-LIMIT_HEAP: int = ...
-ENABLE_KECCAK: int = ...
-ENABLE_KECCAK_OPS_OUTSIDE_GUARD: int = ...
-MEMPOOL_MODE: int = ...
-ALLOW_BACKREFS: int = ...
-DONT_VALIDATE_SIGNATURE: int = ...
+class ValidationError(ValueError, TypeError):
+    code: int
+    message: str
 
-ELIGIBLE_FOR_DEDUP: int = ...
-ELIGIBLE_FOR_FF: int = ...
+class CostError(ValidationError): ...
+class SignatureError(ValidationError): ...
+
+class StreamableError(ValueError): ...
+class TruncatedError(StreamableError): ...
+class InvalidEnumError(StreamableError): ...
+class TooLargeError(StreamableError): ...
 
-NO_UNKNOWN_OPS: int = ...
 
 def run_chia_program(
     program: bytes, args: bytes, max_cost: int, flags: int
@@ -96,12 +252,29 @@ def get_puzzle_and_solution_for_coin2(generator: Program, block_refs: list[Reada
 
 @final
 class BLSCache:
-    def __init__(self, cache_size: Optional[int] = 50000) -> None: ...
+    def __init__(self, cache_size: Optional[int] = 50000, *, size_bytes: Optional[int] = None, ttl: Optional[float] = None) -> None: ...
     def len(self) -> int: ...
     def aggregate_verify(self, pks: list[G1Element], msgs: list[bytes], sig: G2Element) -> bool: ...
     def items(self) -> list[tuple[bytes, GTElement]]: ...
     def update(self, other: Sequence[tuple[bytes, GTElement]]) -> None: ...
     def evict(self, pks: list[G1Element], msgs: list[bytes]) -> None: ...
+    def evict_hashes(self, hashes: Sequence[bytes]) -> None: ...
+    def stats(self) -> BLSCacheStats: ...
+    def serialize(self) -> bytes: ...
+    @staticmethod
+    def deserialize(data: bytes, size: Optional[int] = None, *, size_bytes: Optional[int] = None) -> BLSCache: ...
+
+@final
+class BLSCacheStats:
+    hits: int
+    misses: int
+    evictions: int
+
+def generate_mnemonic() -> str: ...
+def validate_mnemonic(phrase: str) -> None: ...
+def mnemonic_to_entropy(phrase: str) -> bytes: ...
+def mnemonic_from_entropy(entropy: bytes) -> str: ...
+def mnemonic_to_seed(phrase: str, passphrase: str = "") -> bytes: ...
 
 @final
 class AugSchemeMPL:
@@ -124,6 +297,62 @@ class AugSchemeMPL:
     @staticmethod
     def derive_child_pk_unhardened(pk: G1Element, index: int) -> G1Element: ...
 
+@final
+class PopSchemeMPL:
+    @staticmethod
+    def sign(sk: PrivateKey, msg: bytes) -> G2Element: ...
+    @staticmethod
+    def aggregate(sigs: Sequence[G2Element]) -> G2Element: ...
+    @staticmethod
+    def verify(pk: G1Element, msg: bytes, sig: G2Element) -> bool: ...
+    @staticmethod
+    def pop_prove(sk: PrivateKey) -> G2Element: ...
+    @staticmethod
+    def pop_verify(pk: G1Element, proof: G2Element) -> bool: ...
+    @staticmethod
+    def fast_aggregate_verify(pks: Sequence[G1Element], msg: bytes, sig: G2Element) -> bool: ...
+
+@final
+class AllocatorMetrics:
+    atoms: int
+    small_atoms: int
+    pairs: int
+    heap_size: int
+
+@final
+class CoinStateDelta:
+    created: list[CoinState]
+    spent: list[CoinState]
+    reorged: list[CoinState]
+    updated: list[CoinState]
+    unchanged: list[CoinState]
+    def is_empty(self) -> bool: ...
+    def max_height(self) -> Optional[int]: ...
+
+@final
+class Conflict:
+    shared_spends: list[bytes32]
+    shared_additions: list[bytes32]
+    a_depends_on_b: list[bytes32]
+    b_depends_on_a: list[bytes32]
+    def is_empty(self) -> bool: ...
+
+@final
+class MempoolCandidate:
+    id: bytes32
+    fee: int
+    cost: int
+    conditions: SpendBundleConditions
+    def __init__(
+        self, id: bytes32, fee: int, cost: int, conditions: SpendBundleConditions
+    ) -> None: ...
+
+@final
+class MempoolSelection:
+    ids: list[bytes32]
+    fee: int
+    cost: int
+
 @final
 class MerkleSet:
     def get_root(self) -> bytes32: ...
@@ -133,6 +362,48 @@ class MerkleSet:
         leafs: list[bytes32],
     ) -> None: ...
 
+class MerkleTree:
+    def __init__(self, leaves: list[bytes32]) -> None: ...
+    @property
+    def leaves(self) -> list[bytes32]: ...
+    def root(self) -> bytes32: ...
+    def proof_for(self, index: int) -> MerkleProof: ...
+
+class MerkleProof:
+    path: int
+    hashes: list[bytes32]
+    def __init__(self, path: int, hashes: list[bytes32]) -> None: ...
+    def verify(self, root: bytes32, leaf: bytes32) -> bool: ...
+    def to_program(self) -> Program: ...
+
+class Simulator:
+    def __init__(self, constants: ConsensusConstants) -> None: ...
+    @property
+    def height(self) -> int: ...
+    @property
+    def timestamp(self) -> int: ...
+    def pass_time(self, seconds: uint64) -> None: ...
+    def farm_block(self, puzzle_hash: bytes32) -> list[Coin]: ...
+    def push_bundle(self, spend_bundle: SpendBundle) -> int: ...
+    def coin_state(self, coin_id: bytes32) -> Optional[CoinState]: ...
+    def unspent_coins(self, puzzle_hash: bytes32) -> list[Coin]: ...
+    def rewind(self, height: uint32) -> None: ...
+
+ELIGIBLE_FOR_DEDUP: int = ...
+ELIGIBLE_FOR_FF: int = ...
+HAS_RELATIVE_CONDITION: int = ...
+KNOWN_DICT_STANDARD_PUZZLES: int = ...
+NO_UNKNOWN_CONDS: int = ...
+STRICT_ARGS_COUNT: int = ...
+MEMPOOL_MODE: int = ...
+ALLOW_BACKREFS: int = ...
+DONT_VALIDATE_SIGNATURE: int = ...
+CHECK_DUPLICATE_COIN_IDS: int = ...
+NO_UNKNOWN_OPS: int = ...
+LIMIT_HEAP: int = ...
+ENABLE_KECCAK: int = ...
+ENABLE_KECCAK_OPS_OUTSIDE_GUARD: int = ...
+
 @final
 class G1Element:
     SIZE: ClassVar[int] = ...
@@ -163,9 +434,12 @@ class G1Element:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: G1Element) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> str: ...
     @classmethod
     def from_json_dict(cls, json_dict: Union[str, bytes]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
 
 @final
 class G2Element:
@@ -194,9 +468,12 @@ class G2Element:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: G2Element) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> str: ...
     @classmethod
     def from_json_dict(cls, json_dict: Union[str, bytes]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
 
 @final
 class GTElement:
@@ -204,6 +481,9 @@ class GTElement:
     def __str__(self) -> str: ...
     def __mul__(self, rhs: GTElement) -> GTElement: ...
     def __imul__(self, rhs: GTElement) -> GTElement : ...
+    def to_canonical_bytes(self) -> bytes: ...
+    @staticmethod
+    def from_canonical_bytes(data: bytes) -> GTElement: ...
     def __init__(
         self
     ) -> None: ...
@@ -221,9 +501,12 @@ class GTElement:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: GTElement) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> str: ...
     @classmethod
     def from_json_dict(cls, json_dict: str) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
 
 @final
 class PrivateKey:
@@ -236,6 +519,8 @@ class PrivateKey:
     def derive_unhardened(self, idx: int) -> PrivateKey: ...
     @staticmethod
     def from_seed(seed: bytes) -> PrivateKey: ...
+    @staticmethod
+    def from_mnemonic(phrase: str) -> PrivateKey: ...
     def __init__(
         self
     ) -> None: ...
@@ -253,9 +538,12 @@ class PrivateKey:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: PrivateKey) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> str: ...
     @classmethod
     def from_json_dict(cls, json_dict: str) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
 
 @final
 class SpendConditions:
@@ -278,6 +566,8 @@ class SpendConditions:
     agg_sig_parent_amount: list[tuple[G1Element, bytes]]
     agg_sig_parent_puzzle: list[tuple[G1Element, bytes]]
     flags: int
+    eligible_for_dedup: bool
+    eligible_for_fast_forward: bool
     def __init__(
         self,
         coin_id: bytes,
@@ -314,9 +604,12 @@ class SpendConditions:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: SpendConditions) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
     def replace(self, *, coin_id: Union[ bytes, _Unspec] = _Unspec(),
         parent_id: Union[ bytes, _Unspec] = _Unspec(),
         puzzle_hash: Union[ bytes, _Unspec] = _Unspec(),
@@ -337,6 +630,82 @@ class SpendConditions:
         agg_sig_parent_puzzle: Union[ list[tuple[G1Element, bytes]], _Unspec] = _Unspec(),
         flags: Union[ int, _Unspec] = _Unspec()) -> SpendConditions: ...
 
+@final
+class CoinAddition:
+    coin: Coin
+    hint: Optional[bytes]
+    memos: list[bytes]
+    def __init__(
+        self,
+        coin: Coin,
+        hint: Optional[bytes],
+        memos: Sequence[bytes]
+    ) -> None: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __deepcopy__(self, memo: object) -> CoinAddition: ...
+    def __copy__(self) -> CoinAddition: ...
+    @classmethod
+    def from_bytes(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def from_bytes_unchecked(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def parse_rust(cls, blob: ReadableBuffer, trusted: bool = False) -> tuple[Self, int]: ...
+    def to_bytes(self) -> bytes: ...
+    def __bytes__(self) -> bytes: ...
+    def stream_to_bytes(self) -> bytes: ...
+    def get_hash(self) -> bytes32: ...
+    def diff(self, other: CoinAddition) -> list[tuple[str, str, str]]: ...
+    def to_json_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    def replace(self, *, coin: Union[ Coin, _Unspec] = _Unspec(),
+        hint: Union[ Optional[bytes], _Unspec] = _Unspec(),
+        memos: Union[ list[bytes], _Unspec] = _Unspec()) -> CoinAddition: ...
+
+@final
+class BlockCoinReport:
+    height: int
+    additions: list[CoinAddition]
+    removals: list[Coin]
+    reward_coins: list[Coin]
+    fees: int
+    def __init__(
+        self,
+        height: int,
+        additions: Sequence[CoinAddition],
+        removals: Sequence[Coin],
+        reward_coins: Sequence[Coin],
+        fees: int
+    ) -> None: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __deepcopy__(self, memo: object) -> BlockCoinReport: ...
+    def __copy__(self) -> BlockCoinReport: ...
+    @classmethod
+    def from_bytes(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def from_bytes_unchecked(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def parse_rust(cls, blob: ReadableBuffer, trusted: bool = False) -> tuple[Self, int]: ...
+    def to_bytes(self) -> bytes: ...
+    def __bytes__(self) -> bytes: ...
+    def stream_to_bytes(self) -> bytes: ...
+    def get_hash(self) -> bytes32: ...
+    def diff(self, other: BlockCoinReport) -> list[tuple[str, str, str]]: ...
+    def to_json_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    def replace(self, *, height: Union[ int, _Unspec] = _Unspec(),
+        additions: Union[ list[CoinAddition], _Unspec] = _Unspec(),
+        removals: Union[ list[Coin], _Unspec] = _Unspec(),
+        reward_coins: Union[ list[Coin], _Unspec] = _Unspec(),
+        fees: Union[ int, _Unspec] = _Unspec()) -> BlockCoinReport: ...
+
 @final
 class SpendBundleConditions:
     spends: list[SpendConditions]
@@ -378,9 +747,12 @@ class SpendBundleConditions:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: SpendBundleConditions) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
     def replace(self, *, spends: Union[ list[SpendConditions], _Unspec] = _Unspec(),
         reserve_fee: Union[ int, _Unspec] = _Unspec(),
         height_absolute: Union[ int, _Unspec] = _Unspec(),
@@ -470,9 +842,14 @@ class BlockRecord:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: BlockRecord) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, header_hash: Union[ bytes32, _Unspec] = _Unspec(),
         prev_hash: Union[ bytes32, _Unspec] = _Unspec(),
         height: Union[ uint32, _Unspec] = _Unspec(),
@@ -524,9 +901,14 @@ class Message:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: Message) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, msg_type: Union[ int, _Unspec] = _Unspec(),
         id: Union[ Optional[uint16], _Unspec] = _Unspec(),
         data: Union[ bytes, _Unspec] = _Unspec()) -> Message: ...
@@ -562,9 +944,14 @@ class Handshake:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: Handshake) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, network_id: Union[ str, _Unspec] = _Unspec(),
         protocol_version: Union[ str, _Unspec] = _Unspec(),
         software_version: Union[ str, _Unspec] = _Unspec(),
@@ -572,6 +959,43 @@ class Handshake:
         node_type: Union[ int, _Unspec] = _Unspec(),
         capabilities: Union[ list[tuple[uint16, str]], _Unspec] = _Unspec()) -> Handshake: ...
 
+@final
+class ProtocolError:
+    code: int16
+    message: str
+    data: Optional[bytes]
+    def __init__(
+        self,
+        code: int16,
+        message: str,
+        data: Optional[bytes]
+    ) -> None: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __deepcopy__(self, memo: object) -> ProtocolError: ...
+    def __copy__(self) -> ProtocolError: ...
+    @classmethod
+    def from_bytes(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def from_bytes_unchecked(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def parse_rust(cls, blob: ReadableBuffer, trusted: bool = False) -> tuple[Self, int]: ...
+    def to_bytes(self) -> bytes: ...
+    def __bytes__(self) -> bytes: ...
+    def stream_to_bytes(self) -> bytes: ...
+    def get_hash(self) -> bytes32: ...
+    def diff(self, other: ProtocolError) -> list[tuple[str, str, str]]: ...
+    def to_json_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
+    def replace(self, *, code: Union[ int16, _Unspec] = _Unspec(),
+        message: Union[ str, _Unspec] = _Unspec(),
+        data: Union[ Optional[bytes], _Unspec] = _Unspec()) -> ProtocolError: ...
+
 @final
 class ClassgroupElement:
     data: bytes100
@@ -581,6 +1005,7 @@ class ClassgroupElement:
     def get_default_element() -> ClassgroupElement: ...
     @staticmethod
     def get_size() -> int: ...
+    def is_canonical(self) -> bool: ...
     def __init__(
         self,
         data: bytes100
@@ -599,9 +1024,14 @@ class ClassgroupElement:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: ClassgroupElement) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, data: Union[ bytes100, _Unspec] = _Unspec()) -> ClassgroupElement: ...
 
 @final
@@ -630,9 +1060,14 @@ class Coin:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: Coin) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, parent_coin_info: Union[ bytes32, _Unspec] = _Unspec(),
         puzzle_hash: Union[ bytes32, _Unspec] = _Unspec(),
         amount: Union[ uint64, _Unspec] = _Unspec()) -> Coin: ...
@@ -642,6 +1077,7 @@ class CoinSpend:
     coin: Coin
     puzzle_reveal: Program
     solution: Program
+    def serialized_length(self) -> int: ...
     def __init__(
         self,
         coin: Coin,
@@ -662,9 +1098,14 @@ class CoinSpend:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: CoinSpend) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin: Union[ Coin, _Unspec] = _Unspec(),
         puzzle_reveal: Union[ Program, _Unspec] = _Unspec(),
         solution: Union[ Program, _Unspec] = _Unspec()) -> CoinSpend: ...
@@ -694,9 +1135,14 @@ class CoinState:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: CoinState) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin: Union[ Coin, _Unspec] = _Unspec(),
         spent_height: Union[ Optional[uint32], _Unspec] = _Unspec(),
         created_height: Union[ Optional[uint32], _Unspec] = _Unspec()) -> CoinState: ...
@@ -728,9 +1174,14 @@ class EndOfSubSlotBundle:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: EndOfSubSlotBundle) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, challenge_chain: Union[ ChallengeChainSubSlot, _Unspec] = _Unspec(),
         infused_challenge_chain: Union[ Optional[InfusedChallengeChainSubSlot], _Unspec] = _Unspec(),
         reward_chain: Union[ RewardChainSubSlot, _Unspec] = _Unspec(),
@@ -739,6 +1190,12 @@ class EndOfSubSlotBundle:
 @final
 class FeeRate:
     mojos_per_clvm_cost: uint64
+    def fee_for_cost(self, cost: int) -> Optional[int]: ...
+    @staticmethod
+    def from_fee_rounding_down(fee: int, cost: int) -> Optional[FeeRate]: ...
+    @staticmethod
+    def from_fee_rounding_up(fee: int, cost: int) -> Optional[FeeRate]: ...
+    def is_met_by(self, fee: int, cost: int) -> bool: ...
     def __init__(
         self,
         mojos_per_clvm_cost: uint64
@@ -757,9 +1214,14 @@ class FeeRate:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: FeeRate) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, mojos_per_clvm_cost: Union[ uint64, _Unspec] = _Unspec()) -> FeeRate: ...
 
 @final
@@ -787,9 +1249,14 @@ class FeeEstimate:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: FeeEstimate) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, error: Union[ Optional[str], _Unspec] = _Unspec(),
         time_target: Union[ uint64, _Unspec] = _Unspec(),
         estimated_fee_rate: Union[ FeeRate, _Unspec] = _Unspec()) -> FeeEstimate: ...
@@ -817,9 +1284,14 @@ class FeeEstimateGroup:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: FeeEstimateGroup) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, error: Union[ Optional[str], _Unspec] = _Unspec(),
         estimates: Union[ list[FeeEstimate], _Unspec] = _Unspec()) -> FeeEstimateGroup: ...
 
@@ -854,9 +1326,14 @@ class TransactionsInfo:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: TransactionsInfo) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, generator_root: Union[ bytes32, _Unspec] = _Unspec(),
         generator_refs_root: Union[ bytes32, _Unspec] = _Unspec(),
         aggregated_signature: Union[ G2Element, _Unspec] = _Unspec(),
@@ -895,9 +1372,14 @@ class FoliageTransactionBlock:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: FoliageTransactionBlock) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, prev_transaction_block_hash: Union[ bytes32, _Unspec] = _Unspec(),
         timestamp: Union[ uint64, _Unspec] = _Unspec(),
         filter_hash: Union[ bytes32, _Unspec] = _Unspec(),
@@ -934,9 +1416,14 @@ class FoliageBlockData:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: FoliageBlockData) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, unfinished_reward_block_hash: Union[ bytes32, _Unspec] = _Unspec(),
         pool_target: Union[ PoolTarget, _Unspec] = _Unspec(),
         pool_signature: Union[ Optional[G2Element], _Unspec] = _Unspec(),
@@ -974,9 +1461,14 @@ class Foliage:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: Foliage) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, prev_block_hash: Union[ bytes32, _Unspec] = _Unspec(),
         reward_block_hash: Union[ bytes32, _Unspec] = _Unspec(),
         foliage_block_data: Union[ FoliageBlockData, _Unspec] = _Unspec(),
@@ -1013,9 +1505,14 @@ class NewPeak:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: NewPeak) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, header_hash: Union[ bytes32, _Unspec] = _Unspec(),
         height: Union[ uint32, _Unspec] = _Unspec(),
         weight: Union[ uint128, _Unspec] = _Unspec(),
@@ -1047,9 +1544,14 @@ class NewTransaction:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: NewTransaction) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, transaction_id: Union[ bytes32, _Unspec] = _Unspec(),
         cost: Union[ uint64, _Unspec] = _Unspec(),
         fees: Union[ uint64, _Unspec] = _Unspec()) -> NewTransaction: ...
@@ -1075,9 +1577,14 @@ class RequestTransaction:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestTransaction) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, transaction_id: Union[ bytes32, _Unspec] = _Unspec()) -> RequestTransaction: ...
 
 @final
@@ -1101,9 +1608,14 @@ class RespondTransaction:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondTransaction) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, transaction: Union[ SpendBundle, _Unspec] = _Unspec()) -> RespondTransaction: ...
 
 @final
@@ -1129,9 +1641,14 @@ class RequestProofOfWeight:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestProofOfWeight) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, total_number_of_blocks: Union[ uint32, _Unspec] = _Unspec(),
         tip: Union[ bytes32, _Unspec] = _Unspec()) -> RequestProofOfWeight: ...
 
@@ -1158,9 +1675,14 @@ class RespondProofOfWeight:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondProofOfWeight) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, wp: Union[ WeightProof, _Unspec] = _Unspec(),
         tip: Union[ bytes32, _Unspec] = _Unspec()) -> RespondProofOfWeight: ...
 
@@ -1187,9 +1709,14 @@ class RequestBlock:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestBlock) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec(),
         include_transaction_block: Union[ bool, _Unspec] = _Unspec()) -> RequestBlock: ...
 
@@ -1214,9 +1741,14 @@ class RejectBlock:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RejectBlock) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec()) -> RejectBlock: ...
 
 @final
@@ -1244,9 +1776,14 @@ class RequestBlocks:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestBlocks) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, start_height: Union[ uint32, _Unspec] = _Unspec(),
         end_height: Union[ uint32, _Unspec] = _Unspec(),
         include_transaction_block: Union[ bool, _Unspec] = _Unspec()) -> RequestBlocks: ...
@@ -1276,9 +1813,14 @@ class RespondBlocks:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondBlocks) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, start_height: Union[ uint32, _Unspec] = _Unspec(),
         end_height: Union[ uint32, _Unspec] = _Unspec(),
         blocks: Union[ list[FullBlock], _Unspec] = _Unspec()) -> RespondBlocks: ...
@@ -1306,9 +1848,14 @@ class RejectBlocks:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RejectBlocks) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, start_height: Union[ uint32, _Unspec] = _Unspec(),
         end_height: Union[ uint32, _Unspec] = _Unspec()) -> RejectBlocks: ...
 
@@ -1333,9 +1880,14 @@ class RespondBlock:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondBlock) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, block: Union[ FullBlock, _Unspec] = _Unspec()) -> RespondBlock: ...
 
 @final
@@ -1359,9 +1911,14 @@ class NewUnfinishedBlock:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: NewUnfinishedBlock) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, unfinished_reward_hash: Union[ bytes32, _Unspec] = _Unspec()) -> NewUnfinishedBlock: ...
 
 @final
@@ -1385,9 +1942,14 @@ class RequestUnfinishedBlock:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestUnfinishedBlock) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, unfinished_reward_hash: Union[ bytes32, _Unspec] = _Unspec()) -> RequestUnfinishedBlock: ...
 
 @final
@@ -1411,9 +1973,14 @@ class RespondUnfinishedBlock:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondUnfinishedBlock) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, unfinished_block: Union[ UnfinishedBlock, _Unspec] = _Unspec()) -> RespondUnfinishedBlock: ...
 
 @final
@@ -1443,9 +2010,14 @@ class NewSignagePointOrEndOfSubSlot:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: NewSignagePointOrEndOfSubSlot) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, prev_challenge_hash: Union[ Optional[bytes32], _Unspec] = _Unspec(),
         challenge_hash: Union[ bytes32, _Unspec] = _Unspec(),
         index_from_challenge: Union[ uint8, _Unspec] = _Unspec(),
@@ -1476,9 +2048,14 @@ class RequestSignagePointOrEndOfSubSlot:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestSignagePointOrEndOfSubSlot) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, challenge_hash: Union[ bytes32, _Unspec] = _Unspec(),
         index_from_challenge: Union[ uint8, _Unspec] = _Unspec(),
         last_rc_infusion: Union[ bytes32, _Unspec] = _Unspec()) -> RequestSignagePointOrEndOfSubSlot: ...
@@ -1512,9 +2089,14 @@ class RespondSignagePoint:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondSignagePoint) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, index_from_challenge: Union[ uint8, _Unspec] = _Unspec(),
         challenge_chain_vdf: Union[ VDFInfo, _Unspec] = _Unspec(),
         challenge_chain_proof: Union[ VDFProof, _Unspec] = _Unspec(),
@@ -1542,9 +2124,14 @@ class RespondEndOfSubSlot:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondEndOfSubSlot) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, end_of_slot_bundle: Union[ EndOfSubSlotBundle, _Unspec] = _Unspec()) -> RespondEndOfSubSlot: ...
 
 @final
@@ -1568,9 +2155,14 @@ class RequestMempoolTransactions:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestMempoolTransactions) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, filter: Union[ bytes, _Unspec] = _Unspec()) -> RequestMempoolTransactions: ...
 
 @final
@@ -1600,9 +2192,14 @@ class NewCompactVDF:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: NewCompactVDF) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec(),
         header_hash: Union[ bytes32, _Unspec] = _Unspec(),
         field_vdf: Union[ uint8, _Unspec] = _Unspec(),
@@ -1635,9 +2232,14 @@ class RequestCompactVDF:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestCompactVDF) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec(),
         header_hash: Union[ bytes32, _Unspec] = _Unspec(),
         field_vdf: Union[ uint8, _Unspec] = _Unspec(),
@@ -1672,9 +2274,14 @@ class RespondCompactVDF:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondCompactVDF) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec(),
         header_hash: Union[ bytes32, _Unspec] = _Unspec(),
         field_vdf: Union[ uint8, _Unspec] = _Unspec(),
@@ -1700,9 +2307,14 @@ class RequestPeers:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestPeers) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
 
 @final
 class RespondPeers:
@@ -1725,9 +2337,14 @@ class RespondPeers:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondPeers) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, peer_list: Union[ list[TimestampedPeerInfo], _Unspec] = _Unspec()) -> RespondPeers: ...
 
 @final
@@ -1753,9 +2370,14 @@ class NewUnfinishedBlock2:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: NewUnfinishedBlock2) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, unfinished_reward_hash: Union[ bytes32, _Unspec] = _Unspec(),
         foliage_hash: Union[ Optional[bytes32], _Unspec] = _Unspec()) -> NewUnfinishedBlock2: ...
 
@@ -1782,9 +2404,14 @@ class RequestUnfinishedBlock2:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestUnfinishedBlock2) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, unfinished_reward_hash: Union[ bytes32, _Unspec] = _Unspec(),
         foliage_hash: Union[ Optional[bytes32], _Unspec] = _Unspec()) -> RequestUnfinishedBlock2: ...
 
@@ -1810,6 +2437,7 @@ class FullBlock:
     weight: uint128
     def get_included_reward_coins(self) -> list[Coin]: ...
     def is_fully_compactified(self) -> bool: ...
+    def to_header_block(self, transactions_filter: bytes) -> HeaderBlock: ...
     def __init__(
         self,
         finished_sub_slots: Sequence[EndOfSubSlotBundle],
@@ -1839,9 +2467,14 @@ class FullBlock:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: FullBlock) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, finished_sub_slots: Union[ list[EndOfSubSlotBundle], _Unspec] = _Unspec(),
         reward_chain_block: Union[ RewardChainBlock, _Unspec] = _Unspec(),
         challenge_chain_sp_proof: Union[ Optional[VDFProof], _Unspec] = _Unspec(),
@@ -1905,9 +2538,14 @@ class HeaderBlock:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: HeaderBlock) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, finished_sub_slots: Union[ list[EndOfSubSlotBundle], _Unspec] = _Unspec(),
         reward_chain_block: Union[ RewardChainBlock, _Unspec] = _Unspec(),
         challenge_chain_sp_proof: Union[ Optional[VDFProof], _Unspec] = _Unspec(),
@@ -1920,6 +2558,99 @@ class HeaderBlock:
         transactions_filter: Union[ bytes, _Unspec] = _Unspec(),
         transactions_info: Union[ Optional[TransactionsInfo], _Unspec] = _Unspec()) -> HeaderBlock: ...
 
+@final
+class RequestPeersIntroducer:
+    def __init__(
+        self
+    ) -> None: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __deepcopy__(self, memo: object) -> RequestPeersIntroducer: ...
+    def __copy__(self) -> RequestPeersIntroducer: ...
+    @classmethod
+    def from_bytes(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def from_bytes_unchecked(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def parse_rust(cls, blob: ReadableBuffer, trusted: bool = False) -> tuple[Self, int]: ...
+    def to_bytes(self) -> bytes: ...
+    def __bytes__(self) -> bytes: ...
+    def stream_to_bytes(self) -> bytes: ...
+    def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestPeersIntroducer) -> list[tuple[str, str, str]]: ...
+    def to_json_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
+
+@final
+class RespondPeersIntroducer:
+    peer_list: list[TimestampedPeerInfo]
+    def __init__(
+        self,
+        peer_list: Sequence[TimestampedPeerInfo]
+    ) -> None: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __deepcopy__(self, memo: object) -> RespondPeersIntroducer: ...
+    def __copy__(self) -> RespondPeersIntroducer: ...
+    @classmethod
+    def from_bytes(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def from_bytes_unchecked(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def parse_rust(cls, blob: ReadableBuffer, trusted: bool = False) -> tuple[Self, int]: ...
+    def to_bytes(self) -> bytes: ...
+    def __bytes__(self) -> bytes: ...
+    def stream_to_bytes(self) -> bytes: ...
+    def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondPeersIntroducer) -> list[tuple[str, str, str]]: ...
+    def to_json_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
+    def replace(self, *, peer_list: Union[ list[TimestampedPeerInfo], _Unspec] = _Unspec()) -> RespondPeersIntroducer: ...
+
+@final
+class PeerInfo:
+    host: str
+    port: uint16
+    def __init__(
+        self,
+        host: str,
+        port: uint16
+    ) -> None: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __deepcopy__(self, memo: object) -> PeerInfo: ...
+    def __copy__(self) -> PeerInfo: ...
+    @classmethod
+    def from_bytes(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def from_bytes_unchecked(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def parse_rust(cls, blob: ReadableBuffer, trusted: bool = False) -> tuple[Self, int]: ...
+    def to_bytes(self) -> bytes: ...
+    def __bytes__(self) -> bytes: ...
+    def stream_to_bytes(self) -> bytes: ...
+    def get_hash(self) -> bytes32: ...
+    def diff(self, other: PeerInfo) -> list[tuple[str, str, str]]: ...
+    def to_json_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
+    def replace(self, *, host: Union[ str, _Unspec] = _Unspec(),
+        port: Union[ uint16, _Unspec] = _Unspec()) -> PeerInfo: ...
+
 @final
 class TimestampedPeerInfo:
     host: str
@@ -1945,9 +2676,14 @@ class TimestampedPeerInfo:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: TimestampedPeerInfo) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, host: Union[ str, _Unspec] = _Unspec(),
         port: Union[ uint16, _Unspec] = _Unspec(),
         timestamp: Union[ uint64, _Unspec] = _Unspec()) -> TimestampedPeerInfo: ...
@@ -1956,6 +2692,7 @@ class TimestampedPeerInfo:
 class PoolTarget:
     puzzle_hash: bytes32
     max_height: uint32
+    def is_valid_at(self, height: int) -> bool: ...
     def __init__(
         self,
         puzzle_hash: bytes,
@@ -1975,9 +2712,14 @@ class PoolTarget:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: PoolTarget) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
-    def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, puzzle_hash: Union[ bytes32, _Unspec] = _Unspec(),
         max_height: Union[ uint32, _Unspec] = _Unspec()) -> PoolTarget: ...
 
@@ -1998,6 +2740,7 @@ class Program:
     def from_program(p: ChiaProgram) -> Program: ...
     def to_program(self) -> ChiaProgram: ...
     def uncurry(self) -> tuple[ChiaProgram, ChiaProgram]: ...
+    def curry(self, *args: object) -> Program: ...
     def __init__(
         self,
         a0: bytes
@@ -2016,9 +2759,12 @@ class Program:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: Program) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> str: ...
     @classmethod
     def from_json_dict(cls, json_dict: str) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
 
 @final
 class ProofOfSpace:
@@ -2051,9 +2797,14 @@ class ProofOfSpace:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: ProofOfSpace) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, challenge: Union[ bytes32, _Unspec] = _Unspec(),
         pool_public_key: Union[ Optional[G1Element], _Unspec] = _Unspec(),
         pool_contract_puzzle_hash: Union[ Optional[bytes32], _Unspec] = _Unspec(),
@@ -2096,9 +2847,14 @@ class RewardChainBlockUnfinished:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RewardChainBlockUnfinished) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, total_iters: Union[ uint128, _Unspec] = _Unspec(),
         signage_point_index: Union[ uint8, _Unspec] = _Unspec(),
         pos_ss_cc_challenge_hash: Union[ bytes32, _Unspec] = _Unspec(),
@@ -2125,6 +2881,7 @@ class RewardChainBlock:
     infused_challenge_chain_ip_vdf: Optional[VDFInfo]
     is_transaction_block: bool
     def get_unfinished(self) -> RewardChainBlockUnfinished: ...
+    def validate_iters(self) -> None: ...
     def __init__(
         self,
         weight: uint128,
@@ -2156,9 +2913,14 @@ class RewardChainBlock:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RewardChainBlock) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, weight: Union[ uint128, _Unspec] = _Unspec(),
         height: Union[ uint32, _Unspec] = _Unspec(),
         total_iters: Union[ uint128, _Unspec] = _Unspec(),
@@ -2201,9 +2963,14 @@ class ChallengeBlockInfo:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: ChallengeBlockInfo) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, proof_of_space: Union[ ProofOfSpace, _Unspec] = _Unspec(),
         challenge_chain_sp_vdf: Union[ Optional[VDFInfo], _Unspec] = _Unspec(),
         challenge_chain_sp_signature: Union[ G2Element, _Unspec] = _Unspec(),
@@ -2238,9 +3005,14 @@ class ChallengeChainSubSlot:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: ChallengeChainSubSlot) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, challenge_chain_end_of_slot_vdf: Union[ VDFInfo, _Unspec] = _Unspec(),
         infused_challenge_chain_sub_slot_hash: Union[ Optional[bytes32], _Unspec] = _Unspec(),
         subepoch_summary_hash: Union[ Optional[bytes32], _Unspec] = _Unspec(),
@@ -2268,9 +3040,14 @@ class InfusedChallengeChainSubSlot:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: InfusedChallengeChainSubSlot) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, infused_challenge_chain_end_of_slot_vdf: Union[ VDFInfo, _Unspec] = _Unspec()) -> InfusedChallengeChainSubSlot: ...
 
 @final
@@ -2300,9 +3077,14 @@ class RewardChainSubSlot:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RewardChainSubSlot) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, end_of_slot_vdf: Union[ VDFInfo, _Unspec] = _Unspec(),
         challenge_chain_sub_slot_hash: Union[ bytes32, _Unspec] = _Unspec(),
         infused_challenge_chain_sub_slot_hash: Union[ Optional[bytes32], _Unspec] = _Unspec(),
@@ -2333,9 +3115,14 @@ class SubSlotProofs:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: SubSlotProofs) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, challenge_chain_slot_proof: Union[ VDFProof, _Unspec] = _Unspec(),
         infused_challenge_chain_slot_proof: Union[ Optional[VDFProof], _Unspec] = _Unspec(),
         reward_chain_slot_proof: Union[ VDFProof, _Unspec] = _Unspec()) -> SubSlotProofs: ...
@@ -2349,6 +3136,9 @@ class SpendBundle:
     def name(self) -> bytes32: ...
     def removals(self) -> list[Coin]: ...
     def additions(self) -> list[Coin]: ...
+    def estimate_cost(self, per_byte_cost: int, condition_costs: list[tuple[int, int]]) -> CostEstimate: ...
+    def normalized(self) -> SpendBundle: ...
+    def signing_digest(self, genesis_challenge: bytes32) -> bytes32: ...
     def __init__(
         self,
         coin_spends: Sequence[CoinSpend],
@@ -2368,12 +3158,129 @@ class SpendBundle:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: SpendBundle) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin_spends: Union[ list[CoinSpend], _Unspec] = _Unspec(),
         aggregated_signature: Union[ G2Element, _Unspec] = _Unspec()) -> SpendBundle: ...
 
+@final
+class CostEstimate:
+    serialized_size: uint64
+    byte_cost: uint64
+    execution_cost: uint64
+    condition_cost: uint64
+    cost: uint64
+    def min_fee(self, rate: FeeRate) -> Optional[int]: ...
+    def __init__(
+        self,
+        serialized_size: uint64,
+        byte_cost: uint64,
+        execution_cost: uint64,
+        condition_cost: uint64,
+        cost: uint64
+    ) -> None: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __deepcopy__(self, memo: object) -> CostEstimate: ...
+    def __copy__(self) -> CostEstimate: ...
+    @classmethod
+    def from_bytes(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def from_bytes_unchecked(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def parse_rust(cls, blob: ReadableBuffer, trusted: bool = False) -> tuple[Self, int]: ...
+    def to_bytes(self) -> bytes: ...
+    def __bytes__(self) -> bytes: ...
+    def stream_to_bytes(self) -> bytes: ...
+    def get_hash(self) -> bytes32: ...
+    def diff(self, other: CostEstimate) -> list[tuple[str, str, str]]: ...
+    def to_json_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
+    def replace(self, *, serialized_size: Union[ uint64, _Unspec] = _Unspec(),
+        byte_cost: Union[ uint64, _Unspec] = _Unspec(),
+        execution_cost: Union[ uint64, _Unspec] = _Unspec(),
+        condition_cost: Union[ uint64, _Unspec] = _Unspec(),
+        cost: Union[ uint64, _Unspec] = _Unspec()) -> CostEstimate: ...
+
+@final
+class JsonMapStreamableTest:
+    amounts: list[tuple[bytes32, uint64]]
+    proofs: list[tuple[bytes32, bytes]]
+    def __init__(
+        self,
+        amounts: Sequence[tuple[bytes32, uint64]],
+        proofs: Sequence[tuple[bytes32, bytes]]
+    ) -> None: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __deepcopy__(self, memo: object) -> JsonMapStreamableTest: ...
+    def __copy__(self) -> JsonMapStreamableTest: ...
+    @classmethod
+    def from_bytes(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def from_bytes_unchecked(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def parse_rust(cls, blob: ReadableBuffer, trusted: bool = False) -> tuple[Self, int]: ...
+    def to_bytes(self) -> bytes: ...
+    def __bytes__(self) -> bytes: ...
+    def stream_to_bytes(self) -> bytes: ...
+    def get_hash(self) -> bytes32: ...
+    def diff(self, other: JsonMapStreamableTest) -> list[tuple[str, str, str]]: ...
+    def to_json_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
+    def replace(self, *, amounts: Union[ list[tuple[bytes32, uint64]], _Unspec] = _Unspec(),
+        proofs: Union[ list[tuple[bytes32, bytes]], _Unspec] = _Unspec()) -> JsonMapStreamableTest: ...
+
+@final
+class TupleStreamableTest:
+    def __len__(self) -> int: ...
+    def __getitem__(self, index: int) -> Any: ...
+    def __init__(
+        self,
+        field_0: bytes,
+        field_1: uint64,
+        field_2: Optional[str],
+        field_3: Sequence[uint32]
+    ) -> None: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __deepcopy__(self, memo: object) -> TupleStreamableTest: ...
+    def __copy__(self) -> TupleStreamableTest: ...
+    @classmethod
+    def from_bytes(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def from_bytes_unchecked(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def parse_rust(cls, blob: ReadableBuffer, trusted: bool = False) -> tuple[Self, int]: ...
+    def to_bytes(self) -> bytes: ...
+    def __bytes__(self) -> bytes: ...
+    def stream_to_bytes(self) -> bytes: ...
+    def get_hash(self) -> bytes32: ...
+    def diff(self, other: TupleStreamableTest) -> list[tuple[str, str, str]]: ...
+    def to_json_dict(self) -> list[Any]: ...
+    @classmethod
+    def from_json_dict(cls, json_dict: list[Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
+
 @final
 class SubEpochSummary:
     prev_subepoch_summary_hash: bytes32
@@ -2403,9 +3310,14 @@ class SubEpochSummary:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: SubEpochSummary) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, prev_subepoch_summary_hash: Union[ bytes32, _Unspec] = _Unspec(),
         reward_chain_hash: Union[ bytes32, _Unspec] = _Unspec(),
         num_blocks_overflow: Union[ uint8, _Unspec] = _Unspec(),
@@ -2427,6 +3339,7 @@ class UnfinishedBlock:
     partial_hash: bytes32
     def is_transaction_block(self) -> bool: ...
     total_iters: uint128
+    def complete(self, infusion: InfusionPoint) -> FullBlock: ...
     def __init__(
         self,
         finished_sub_slots: Sequence[EndOfSubSlotBundle],
@@ -2453,9 +3366,14 @@ class UnfinishedBlock:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: UnfinishedBlock) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, finished_sub_slots: Union[ list[EndOfSubSlotBundle], _Unspec] = _Unspec(),
         reward_chain_block: Union[ RewardChainBlockUnfinished, _Unspec] = _Unspec(),
         challenge_chain_sp_proof: Union[ Optional[VDFProof], _Unspec] = _Unspec(),
@@ -2466,6 +3384,61 @@ class UnfinishedBlock:
         transactions_generator: Union[ Optional[Program], _Unspec] = _Unspec(),
         transactions_generator_ref_list: Union[ list[uint32], _Unspec] = _Unspec()) -> UnfinishedBlock: ...
 
+@final
+class InfusionPoint:
+    challenge_chain_ip_vdf: VDFInfo
+    challenge_chain_ip_proof: VDFProof
+    reward_chain_ip_vdf: VDFInfo
+    reward_chain_ip_proof: VDFProof
+    infused_challenge_chain_ip_vdf: Optional[VDFInfo]
+    infused_challenge_chain_ip_proof: Optional[VDFProof]
+    weight: uint128
+    height: uint32
+    is_transaction_block: bool
+    def __init__(
+        self,
+        challenge_chain_ip_vdf: VDFInfo,
+        challenge_chain_ip_proof: VDFProof,
+        reward_chain_ip_vdf: VDFInfo,
+        reward_chain_ip_proof: VDFProof,
+        infused_challenge_chain_ip_vdf: Optional[VDFInfo],
+        infused_challenge_chain_ip_proof: Optional[VDFProof],
+        weight: uint128,
+        height: uint32,
+        is_transaction_block: bool
+    ) -> None: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __deepcopy__(self, memo: object) -> InfusionPoint: ...
+    def __copy__(self) -> InfusionPoint: ...
+    @classmethod
+    def from_bytes(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def from_bytes_unchecked(cls, blob: bytes) -> Self: ...
+    @classmethod
+    def parse_rust(cls, blob: ReadableBuffer, trusted: bool = False) -> tuple[Self, int]: ...
+    def to_bytes(self) -> bytes: ...
+    def __bytes__(self) -> bytes: ...
+    def stream_to_bytes(self) -> bytes: ...
+    def get_hash(self) -> bytes32: ...
+    def diff(self, other: InfusionPoint) -> list[tuple[str, str, str]]: ...
+    def to_json_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
+    def replace(self, *, challenge_chain_ip_vdf: Union[ VDFInfo, _Unspec] = _Unspec(),
+        challenge_chain_ip_proof: Union[ VDFProof, _Unspec] = _Unspec(),
+        reward_chain_ip_vdf: Union[ VDFInfo, _Unspec] = _Unspec(),
+        reward_chain_ip_proof: Union[ VDFProof, _Unspec] = _Unspec(),
+        infused_challenge_chain_ip_vdf: Union[ Optional[VDFInfo], _Unspec] = _Unspec(),
+        infused_challenge_chain_ip_proof: Union[ Optional[VDFProof], _Unspec] = _Unspec(),
+        weight: Union[ uint128, _Unspec] = _Unspec(),
+        height: Union[ uint32, _Unspec] = _Unspec(),
+        is_transaction_block: Union[ bool, _Unspec] = _Unspec()) -> InfusionPoint: ...
+
 @final
 class UnfinishedHeaderBlock:
     finished_sub_slots: list[EndOfSubSlotBundle]
@@ -2502,9 +3475,14 @@ class UnfinishedHeaderBlock:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: UnfinishedHeaderBlock) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, finished_sub_slots: Union[ list[EndOfSubSlotBundle], _Unspec] = _Unspec(),
         reward_chain_block: Union[ RewardChainBlockUnfinished, _Unspec] = _Unspec(),
         challenge_chain_sp_proof: Union[ Optional[VDFProof], _Unspec] = _Unspec(),
@@ -2538,9 +3516,14 @@ class VDFInfo:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: VDFInfo) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, challenge: Union[ bytes32, _Unspec] = _Unspec(),
         number_of_iterations: Union[ uint64, _Unspec] = _Unspec(),
         output: Union[ ClassgroupElement, _Unspec] = _Unspec()) -> VDFInfo: ...
@@ -2550,6 +3533,7 @@ class VDFProof:
     witness_type: uint8
     witness: bytes
     normalized_to_identity: bool
+    def validate_shape(self, constants: ConsensusConstants) -> None: ...
     def __init__(
         self,
         witness_type: uint8,
@@ -2570,9 +3554,14 @@ class VDFProof:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: VDFProof) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, witness_type: Union[ uint8, _Unspec] = _Unspec(),
         witness: Union[ bytes, _Unspec] = _Unspec(),
         normalized_to_identity: Union[ bool, _Unspec] = _Unspec()) -> VDFProof: ...
@@ -2600,9 +3589,14 @@ class RequestPuzzleSolution:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestPuzzleSolution) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin_name: Union[ bytes32, _Unspec] = _Unspec(),
         height: Union[ uint32, _Unspec] = _Unspec()) -> RequestPuzzleSolution: ...
 
@@ -2633,9 +3627,14 @@ class PuzzleSolutionResponse:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: PuzzleSolutionResponse) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin_name: Union[ bytes32, _Unspec] = _Unspec(),
         height: Union[ uint32, _Unspec] = _Unspec(),
         puzzle: Union[ Program, _Unspec] = _Unspec(),
@@ -2662,9 +3661,14 @@ class RespondPuzzleSolution:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondPuzzleSolution) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, response: Union[ PuzzleSolutionResponse, _Unspec] = _Unspec()) -> RespondPuzzleSolution: ...
 
 @final
@@ -2690,9 +3694,14 @@ class RejectPuzzleSolution:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RejectPuzzleSolution) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin_name: Union[ bytes32, _Unspec] = _Unspec(),
         height: Union[ uint32, _Unspec] = _Unspec()) -> RejectPuzzleSolution: ...
 
@@ -2717,9 +3726,14 @@ class SendTransaction:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: SendTransaction) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, transaction: Union[ SpendBundle, _Unspec] = _Unspec()) -> SendTransaction: ...
 
 @final
@@ -2747,9 +3761,14 @@ class TransactionAck:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: TransactionAck) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, txid: Union[ bytes32, _Unspec] = _Unspec(),
         status: Union[ uint8, _Unspec] = _Unspec(),
         error: Union[ Optional[str], _Unspec] = _Unspec()) -> TransactionAck: ...
@@ -2781,9 +3800,14 @@ class NewPeakWallet:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: NewPeakWallet) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, header_hash: Union[ bytes32, _Unspec] = _Unspec(),
         height: Union[ uint32, _Unspec] = _Unspec(),
         weight: Union[ uint128, _Unspec] = _Unspec(),
@@ -2810,9 +3834,14 @@ class RequestBlockHeader:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestBlockHeader) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec()) -> RequestBlockHeader: ...
 
 @final
@@ -2836,9 +3865,14 @@ class RespondBlockHeader:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondBlockHeader) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, header_block: Union[ HeaderBlock, _Unspec] = _Unspec()) -> RespondBlockHeader: ...
 
 @final
@@ -2862,9 +3896,14 @@ class RejectHeaderRequest:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RejectHeaderRequest) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec()) -> RejectHeaderRequest: ...
 
 @final
@@ -2892,9 +3931,14 @@ class RequestRemovals:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestRemovals) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec(),
         header_hash: Union[ bytes32, _Unspec] = _Unspec(),
         coin_names: Union[ Optional[list[bytes32]], _Unspec] = _Unspec()) -> RequestRemovals: ...
@@ -2926,9 +3970,14 @@ class RespondRemovals:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondRemovals) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec(),
         header_hash: Union[ bytes32, _Unspec] = _Unspec(),
         coins: Union[ list[tuple[bytes32, Optional[Coin]]], _Unspec] = _Unspec(),
@@ -2957,9 +4006,14 @@ class RejectRemovalsRequest:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RejectRemovalsRequest) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec(),
         header_hash: Union[ bytes32, _Unspec] = _Unspec()) -> RejectRemovalsRequest: ...
 
@@ -2988,9 +4042,14 @@ class RequestAdditions:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestAdditions) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec(),
         header_hash: Union[ Optional[bytes32], _Unspec] = _Unspec(),
         puzzle_hashes: Union[ Optional[list[bytes32]], _Unspec] = _Unspec()) -> RequestAdditions: ...
@@ -3022,9 +4081,14 @@ class RespondAdditions:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondAdditions) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec(),
         header_hash: Union[ bytes32, _Unspec] = _Unspec(),
         coins: Union[ list[tuple[bytes32, list[Coin]]], _Unspec] = _Unspec(),
@@ -3053,9 +4117,14 @@ class RejectAdditionsRequest:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RejectAdditionsRequest) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec(),
         header_hash: Union[ bytes32, _Unspec] = _Unspec()) -> RejectAdditionsRequest: ...
 
@@ -3084,9 +4153,14 @@ class RespondBlockHeaders:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondBlockHeaders) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, start_height: Union[ uint32, _Unspec] = _Unspec(),
         end_height: Union[ uint32, _Unspec] = _Unspec(),
         header_blocks: Union[ list[HeaderBlock], _Unspec] = _Unspec()) -> RespondBlockHeaders: ...
@@ -3114,9 +4188,14 @@ class RejectBlockHeaders:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RejectBlockHeaders) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, start_height: Union[ uint32, _Unspec] = _Unspec(),
         end_height: Union[ uint32, _Unspec] = _Unspec()) -> RejectBlockHeaders: ...
 
@@ -3145,9 +4224,14 @@ class RequestBlockHeaders:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestBlockHeaders) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, start_height: Union[ uint32, _Unspec] = _Unspec(),
         end_height: Union[ uint32, _Unspec] = _Unspec(),
         return_filter: Union[ bool, _Unspec] = _Unspec()) -> RequestBlockHeaders: ...
@@ -3175,9 +4259,14 @@ class RequestHeaderBlocks:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestHeaderBlocks) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, start_height: Union[ uint32, _Unspec] = _Unspec(),
         end_height: Union[ uint32, _Unspec] = _Unspec()) -> RequestHeaderBlocks: ...
 
@@ -3204,9 +4293,14 @@ class RejectHeaderBlocks:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RejectHeaderBlocks) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, start_height: Union[ uint32, _Unspec] = _Unspec(),
         end_height: Union[ uint32, _Unspec] = _Unspec()) -> RejectHeaderBlocks: ...
 
@@ -3235,9 +4329,14 @@ class RespondHeaderBlocks:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondHeaderBlocks) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, start_height: Union[ uint32, _Unspec] = _Unspec(),
         end_height: Union[ uint32, _Unspec] = _Unspec(),
         header_blocks: Union[ list[HeaderBlock], _Unspec] = _Unspec()) -> RespondHeaderBlocks: ...
@@ -3265,9 +4364,14 @@ class RegisterForPhUpdates:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RegisterForPhUpdates) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, puzzle_hashes: Union[ list[bytes32], _Unspec] = _Unspec(),
         min_height: Union[ uint32, _Unspec] = _Unspec()) -> RegisterForPhUpdates: ...
 
@@ -3276,6 +4380,8 @@ class RespondToPhUpdates:
     puzzle_hashes: list[bytes32]
     min_height: uint32
     coin_states: list[CoinState]
+    def split_for_max_size(self, max_bytes: int) -> list[RespondToPhUpdates]: ...
+    def apply_min_height_filter(self, min_height: int) -> RespondToPhUpdates: ...
     def __init__(
         self,
         puzzle_hashes: Sequence[bytes32],
@@ -3296,9 +4402,14 @@ class RespondToPhUpdates:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondToPhUpdates) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, puzzle_hashes: Union[ list[bytes32], _Unspec] = _Unspec(),
         min_height: Union[ uint32, _Unspec] = _Unspec(),
         coin_states: Union[ list[CoinState], _Unspec] = _Unspec()) -> RespondToPhUpdates: ...
@@ -3326,9 +4437,14 @@ class RegisterForCoinUpdates:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RegisterForCoinUpdates) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin_ids: Union[ list[bytes32], _Unspec] = _Unspec(),
         min_height: Union[ uint32, _Unspec] = _Unspec()) -> RegisterForCoinUpdates: ...
 
@@ -3337,6 +4453,8 @@ class RespondToCoinUpdates:
     coin_ids: list[bytes32]
     min_height: uint32
     coin_states: list[CoinState]
+    def split_for_max_size(self, max_bytes: int) -> list[RespondToCoinUpdates]: ...
+    def apply_min_height_filter(self, min_height: int) -> RespondToCoinUpdates: ...
     def __init__(
         self,
         coin_ids: Sequence[bytes32],
@@ -3357,9 +4475,14 @@ class RespondToCoinUpdates:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondToCoinUpdates) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin_ids: Union[ list[bytes32], _Unspec] = _Unspec(),
         min_height: Union[ uint32, _Unspec] = _Unspec(),
         coin_states: Union[ list[CoinState], _Unspec] = _Unspec()) -> RespondToCoinUpdates: ...
@@ -3391,9 +4514,14 @@ class CoinStateUpdate:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: CoinStateUpdate) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, height: Union[ uint32, _Unspec] = _Unspec(),
         fork_height: Union[ uint32, _Unspec] = _Unspec(),
         peak_hash: Union[ bytes32, _Unspec] = _Unspec(),
@@ -3420,9 +4548,14 @@ class RequestChildren:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestChildren) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin_name: Union[ bytes32, _Unspec] = _Unspec()) -> RequestChildren: ...
 
 @final
@@ -3446,9 +4579,14 @@ class RespondChildren:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondChildren) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin_states: Union[ list[CoinState], _Unspec] = _Unspec()) -> RespondChildren: ...
 
 @final
@@ -3474,9 +4612,14 @@ class RequestSesInfo:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestSesInfo) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, start_height: Union[ uint32, _Unspec] = _Unspec(),
         end_height: Union[ uint32, _Unspec] = _Unspec()) -> RequestSesInfo: ...
 
@@ -3503,9 +4646,14 @@ class RespondSesInfo:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondSesInfo) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, reward_chain_hash: Union[ list[bytes32], _Unspec] = _Unspec(),
         heights: Union[ list[list[uint32]], _Unspec] = _Unspec()) -> RespondSesInfo: ...
 
@@ -3530,9 +4678,14 @@ class RequestFeeEstimates:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestFeeEstimates) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, time_targets: Union[ list[uint64], _Unspec] = _Unspec()) -> RequestFeeEstimates: ...
 
 @final
@@ -3556,9 +4709,14 @@ class RespondFeeEstimates:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondFeeEstimates) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, estimates: Union[ FeeEstimateGroup, _Unspec] = _Unspec()) -> RespondFeeEstimates: ...
 
 @final
@@ -3582,9 +4740,14 @@ class RequestRemovePuzzleSubscriptions:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestRemovePuzzleSubscriptions) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, puzzle_hashes: Union[ Optional[list[bytes32]], _Unspec] = _Unspec()) -> RequestRemovePuzzleSubscriptions: ...
 
 @final
@@ -3608,9 +4771,14 @@ class RespondRemovePuzzleSubscriptions:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondRemovePuzzleSubscriptions) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, puzzle_hashes: Union[ list[bytes32], _Unspec] = _Unspec()) -> RespondRemovePuzzleSubscriptions: ...
 
 @final
@@ -3634,9 +4802,14 @@ class RequestRemoveCoinSubscriptions:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestRemoveCoinSubscriptions) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin_ids: Union[ Optional[list[bytes32]], _Unspec] = _Unspec()) -> RequestRemoveCoinSubscriptions: ...
 
 @final
@@ -3660,9 +4833,14 @@ class RespondRemoveCoinSubscriptions:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondRemoveCoinSubscriptions) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin_ids: Union[ list[bytes32], _Unspec] = _Unspec()) -> RespondRemoveCoinSubscriptions: ...
 
 @final
@@ -3692,9 +4870,14 @@ class CoinStateFilters:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: CoinStateFilters) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, include_spent: Union[ bool, _Unspec] = _Unspec(),
         include_unspent: Union[ bool, _Unspec] = _Unspec(),
         include_hinted: Union[ bool, _Unspec] = _Unspec(),
@@ -3729,9 +4912,14 @@ class RequestPuzzleState:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestPuzzleState) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, puzzle_hashes: Union[ list[bytes32], _Unspec] = _Unspec(),
         previous_height: Union[ Optional[uint32], _Unspec] = _Unspec(),
         header_hash: Union[ bytes32, _Unspec] = _Unspec(),
@@ -3767,9 +4955,14 @@ class RespondPuzzleState:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondPuzzleState) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, puzzle_hashes: Union[ list[bytes32], _Unspec] = _Unspec(),
         height: Union[ uint32, _Unspec] = _Unspec(),
         header_hash: Union[ bytes32, _Unspec] = _Unspec(),
@@ -3797,9 +4990,14 @@ class RejectPuzzleState:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RejectPuzzleState) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, reason: Union[ int, _Unspec] = _Unspec()) -> RejectPuzzleState: ...
 
 @final
@@ -3829,9 +5027,14 @@ class RequestCoinState:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestCoinState) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin_ids: Union[ list[bytes32], _Unspec] = _Unspec(),
         previous_height: Union[ Optional[uint32], _Unspec] = _Unspec(),
         header_hash: Union[ bytes32, _Unspec] = _Unspec(),
@@ -3860,9 +5063,14 @@ class RespondCoinState:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondCoinState) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, coin_ids: Union[ list[bytes32], _Unspec] = _Unspec(),
         coin_states: Union[ list[CoinState], _Unspec] = _Unspec()) -> RespondCoinState: ...
 
@@ -3887,9 +5095,14 @@ class RejectCoinState:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RejectCoinState) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, reason: Union[ int, _Unspec] = _Unspec()) -> RejectCoinState: ...
 
 @final
@@ -3915,9 +5128,14 @@ class RemovedMempoolItem:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RemovedMempoolItem) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, transaction_id: Union[ bytes32, _Unspec] = _Unspec(),
         reason: Union[ int, _Unspec] = _Unspec()) -> RemovedMempoolItem: ...
 
@@ -3942,9 +5160,14 @@ class MempoolItemsAdded:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: MempoolItemsAdded) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, transaction_ids: Union[ list[bytes32], _Unspec] = _Unspec()) -> MempoolItemsAdded: ...
 
 @final
@@ -3968,9 +5191,14 @@ class MempoolItemsRemoved:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: MempoolItemsRemoved) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, removed_items: Union[ list[RemovedMempoolItem], _Unspec] = _Unspec()) -> MempoolItemsRemoved: ...
 
 @final
@@ -3992,9 +5220,14 @@ class RequestCostInfo:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RequestCostInfo) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
 
 @final
 class RespondCostInfo:
@@ -4027,9 +5260,14 @@ class RespondCostInfo:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RespondCostInfo) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, max_transaction_cost: Union[ uint64, _Unspec] = _Unspec(),
         max_block_cost: Union[ uint64, _Unspec] = _Unspec(),
         max_mempool_cost: Union[ uint64, _Unspec] = _Unspec(),
@@ -4064,9 +5302,14 @@ class SubEpochData:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: SubEpochData) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, reward_chain_hash: Union[ bytes32, _Unspec] = _Unspec(),
         num_blocks_overflow: Union[ uint8, _Unspec] = _Unspec(),
         new_sub_slot_iters: Union[ Optional[uint64], _Unspec] = _Unspec(),
@@ -4119,9 +5362,14 @@ class SubSlotData:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: SubSlotData) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, proof_of_space: Union[ Optional[ProofOfSpace], _Unspec] = _Unspec(),
         cc_signage_point: Union[ Optional[VDFProof], _Unspec] = _Unspec(),
         cc_infusion_point: Union[ Optional[VDFProof], _Unspec] = _Unspec(),
@@ -4161,9 +5409,14 @@ class SubEpochChallengeSegment:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: SubEpochChallengeSegment) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, sub_epoch_n: Union[ uint32, _Unspec] = _Unspec(),
         sub_slots: Union[ list[SubSlotData], _Unspec] = _Unspec(),
         rc_slot_end_info: Union[ Optional[VDFInfo], _Unspec] = _Unspec()) -> SubEpochChallengeSegment: ...
@@ -4189,9 +5442,14 @@ class SubEpochSegments:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: SubEpochSegments) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, challenge_segments: Union[ list[SubEpochChallengeSegment], _Unspec] = _Unspec()) -> SubEpochSegments: ...
 
 @final
@@ -4215,9 +5473,14 @@ class RecentChainData:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: RecentChainData) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, recent_chain_data: Union[ list[HeaderBlock], _Unspec] = _Unspec()) -> RecentChainData: ...
 
 @final
@@ -4243,9 +5506,14 @@ class ProofBlockHeader:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: ProofBlockHeader) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, finished_sub_slots: Union[ list[EndOfSubSlotBundle], _Unspec] = _Unspec(),
         reward_chain_block: Union[ RewardChainBlock, _Unspec] = _Unspec()) -> ProofBlockHeader: ...
 
@@ -4274,9 +5542,14 @@ class WeightProof:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: WeightProof) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, sub_epochs: Union[ list[SubEpochData], _Unspec] = _Unspec(),
         sub_epoch_segments: Union[ list[SubEpochChallengeSegment], _Unspec] = _Unspec(),
         recent_chain_data: Union[ list[HeaderBlock], _Unspec] = _Unspec()) -> WeightProof: ...
@@ -4392,9 +5665,14 @@ class ConsensusConstants:
     def __bytes__(self) -> bytes: ...
     def stream_to_bytes(self) -> bytes: ...
     def get_hash(self) -> bytes32: ...
+    def diff(self, other: ConsensusConstants) -> list[tuple[str, str, str]]: ...
     def to_json_dict(self) -> dict[str, Any]: ...
     @classmethod
     def from_json_dict(cls, json_dict: dict[str, Any]) -> Self: ...
+    @classmethod
+    def from_json_bytes(cls, data: bytes) -> Self: ...
+    @classmethod
+    def fields(cls) -> list[tuple[str, str]]: ...
     def replace(self, *, SLOT_BLOCKS_TARGET: Union[ uint32, _Unspec] = _Unspec(),
         MIN_BLOCKS_PER_CHALLENGE_BLOCK: Union[ uint8, _Unspec] = _Unspec(),
         MAX_SUB_SLOT_BLOCKS: Union[ uint32, _Unspec] = _Unspec(),
@@ -1,69 +1,111 @@
 use crate::run_generator::{
-    additions_and_removals, py_to_slice, run_block_generator, run_block_generator2,
+    additions_and_removals, additions_and_removals_filtered, block_coin_report, py_to_slice,
+    run_block_generator, run_block_generator2, run_block_generator_for_height,
+    run_block_generator_for_height_cached, run_block_generator_for_height_cancellable,
+    run_block_generator_for_height_with_metrics,
 };
-use chia_consensus::allocator::make_allocator;
+use chia_consensus::allocator::{make_allocator, AllocatorMetrics};
+use chia_consensus::block_record::{block_to_block_record, calculate_deficit};
+use chia_consensus::block_rewards::{
+    calculate_base_farmer_reward, calculate_pool_reward, compute_block_fees, expected_reward_coins,
+    validate_block_fees, validate_reward_coins,
+};
+use chia_consensus::coin_proofs::{verify_additions_proofs, verify_removals_proofs};
 use chia_consensus::consensus_constants::ConsensusConstants;
+use chia_consensus::gen::additions_and_removals::{BlockCoinReport, CoinAddition};
+use chia_consensus::gen::cancellation::CancellationToken;
+use chia_consensus::gen::conditions_cache::{ConditionsCache, ConditionsCacheStats};
+use chia_consensus::gen::conflicts::{conflicts_with, Conflict};
 use chia_consensus::gen::flags::{
-    ALLOW_BACKREFS, DONT_VALIDATE_SIGNATURE, MEMPOOL_MODE, NO_UNKNOWN_CONDS, STRICT_ARGS_COUNT,
+    ALLOW_BACKREFS, CHECK_DUPLICATE_COIN_IDS, DONT_VALIDATE_SIGNATURE, MEMPOOL_MODE,
+    NO_UNKNOWN_CONDS, STRICT_ARGS_COUNT,
 };
+use chia_consensus::gen::mempool_selection::{select_spends, MempoolCandidate, Selection};
 use chia_consensus::gen::owned_conditions::{OwnedSpendBundleConditions, OwnedSpendConditions};
 use chia_consensus::gen::run_block_generator::setup_generator_args;
 use chia_consensus::gen::solution_generator::solution_generator as native_solution_generator;
 use chia_consensus::gen::solution_generator::solution_generator_backrefs as native_solution_generator_backrefs;
 use chia_consensus::merkle_set::compute_merkle_set_root as compute_merkle_root_impl;
-use chia_consensus::merkle_tree::{validate_merkle_proof, MerkleSet};
+use chia_consensus::merkle_tree::{validate_merkle_proof, MerkleProof, MerkleSet, MerkleTree};
+use chia_consensus::pot_iterations::{
+    calculate_ip_iters, calculate_iterations_quality, calculate_prefix_bits,
+    calculate_sp_interval_iters, calculate_sp_iters, is_overflow_block, is_valid_partial,
+    max_partial_difficulty, passes_plot_filter,
+};
+use chia_consensus::proof_of_space::{
+    calculate_plot_id_ph, calculate_plot_id_pk, generate_plot_public_key,
+};
+use chia_consensus::simulator::Simulator;
 use chia_consensus::spendbundle_conditions::get_conditions_from_spendbundle;
 use chia_consensus::spendbundle_validation::{
     get_flags_for_height_and_constants, validate_clvm_and_signature,
+    validate_serialized_clvm_and_signature,
+};
+use chia_consensus::time_locks::satisfiable_at;
+use chia_consensus::transactions_filter::{
+    compute_transactions_filter, verify_transactions_filter,
 };
+use chia_protocol::diff_coin_states;
 use chia_protocol::{
-    BlockRecord, Bytes32, ChallengeBlockInfo, ChallengeChainSubSlot, ClassgroupElement, Coin,
-    CoinSpend, CoinState, CoinStateFilters, CoinStateUpdate, EndOfSubSlotBundle, FeeEstimate,
-    FeeEstimateGroup, FeeRate, Foliage, FoliageBlockData, FoliageTransactionBlock, FullBlock,
-    Handshake, HeaderBlock, InfusedChallengeChainSubSlot, LazyNode, MempoolItemsAdded,
-    MempoolItemsRemoved, Message, NewCompactVDF, NewPeak, NewPeakWallet,
-    NewSignagePointOrEndOfSubSlot, NewTransaction, NewUnfinishedBlock, NewUnfinishedBlock2,
-    PoolTarget, Program, ProofBlockHeader, ProofOfSpace, PuzzleSolutionResponse, RecentChainData,
-    RegisterForCoinUpdates, RegisterForPhUpdates, RejectAdditionsRequest, RejectBlock,
-    RejectBlockHeaders, RejectBlocks, RejectCoinState, RejectHeaderBlocks, RejectHeaderRequest,
-    RejectPuzzleSolution, RejectPuzzleState, RejectRemovalsRequest, RemovedMempoolItem,
-    RequestAdditions, RequestBlock, RequestBlockHeader, RequestBlockHeaders, RequestBlocks,
-    RequestChildren, RequestCoinState, RequestCompactVDF, RequestCostInfo, RequestFeeEstimates,
-    RequestHeaderBlocks, RequestMempoolTransactions, RequestPeers, RequestProofOfWeight,
+    BlockRecord, Bytes, Bytes32, ChallengeBlockInfo, ChallengeChainSubSlot, ClassgroupElement,
+    Coin, CoinSpend, CoinState, CoinStateDelta, CoinStateFilters, CoinStateUpdate, CostEstimate,
+    EndOfSubSlotBundle, FeeEstimate, FeeEstimateGroup, FeeRate, Foliage, FoliageBlockData,
+    FoliageTransactionBlock, FullBlock, Handshake, HeaderBlock, InfusedChallengeChainSubSlot,
+    InfusionPoint, JsonMapStreamableTest, LazyNode, MempoolItemsAdded, MempoolItemsRemoved,
+    Message, NewCompactVDF, NewPeak, NewPeakWallet, NewSignagePointOrEndOfSubSlot, NewTransaction,
+    NewUnfinishedBlock, NewUnfinishedBlock2, PeerInfo, PoolTarget, Program, ProofBlockHeader,
+    ProofOfSpace, ProtocolError, PuzzleSolutionResponse, RecentChainData, RegisterForCoinUpdates,
+    RegisterForPhUpdates, RejectAdditionsRequest, RejectBlock, RejectBlockHeaders, RejectBlocks,
+    RejectCoinState, RejectHeaderBlocks, RejectHeaderRequest, RejectPuzzleSolution,
+    RejectPuzzleState, RejectRemovalsRequest, RemovedMempoolItem, RequestAdditions, RequestBlock,
+    RequestBlockHeader, RequestBlockHeaders, RequestBlocks, RequestChildren, RequestCoinState,
+    RequestCompactVDF, RequestCostInfo, RequestFeeEstimates, RequestHeaderBlocks,
+    RequestMempoolTransactions, RequestPeers, RequestPeersIntroducer, RequestProofOfWeight,
     RequestPuzzleSolution, RequestPuzzleState, RequestRemovals, RequestRemoveCoinSubscriptions,
     RequestRemovePuzzleSubscriptions, RequestSesInfo, RequestSignagePointOrEndOfSubSlot,
     RequestTransaction, RequestUnfinishedBlock, RequestUnfinishedBlock2, RespondAdditions,
     RespondBlock, RespondBlockHeader, RespondBlockHeaders, RespondBlocks, RespondChildren,
     RespondCoinState, RespondCompactVDF, RespondCostInfo, RespondEndOfSubSlot, RespondFeeEstimates,
-    RespondHeaderBlocks, RespondPeers, RespondProofOfWeight, RespondPuzzleSolution,
-    RespondPuzzleState, RespondRemovals, RespondRemoveCoinSubscriptions,
+    RespondHeaderBlocks, RespondPeers, RespondPeersIntroducer, RespondProofOfWeight,
+    RespondPuzzleSolution, RespondPuzzleState, RespondRemovals, RespondRemoveCoinSubscriptions,
     RespondRemovePuzzleSubscriptions, RespondSesInfo, RespondSignagePoint, RespondToCoinUpdates,
     RespondToPhUpdates, RespondTransaction, RespondUnfinishedBlock, RewardChainBlock,
     RewardChainBlockUnfinished, RewardChainSubSlot, SendTransaction, SpendBundle,
     SubEpochChallengeSegment, SubEpochData, SubEpochSegments, SubEpochSummary, SubSlotData,
-    SubSlotProofs, TimestampedPeerInfo, TransactionAck, TransactionsInfo, UnfinishedBlock,
-    UnfinishedHeaderBlock, VDFInfo, VDFProof, WeightProof,
+    SubSlotProofs, TimestampedPeerInfo, TransactionAck, TransactionsInfo, TupleStreamableTest,
+    UnfinishedBlock, UnfinishedHeaderBlock, VDFInfo, VDFProof, WeightProof,
 };
-use chia_traits::ChiaToPython;
+use chia_puzzles::{
+    calculate_synthetic_offset, calculate_synthetic_secret_key, compress_with_dict,
+    decompress_with_dict, is_synthetic_of, DeriveSynthetic, KnownDict,
+};
+use chia_traits::chia_error::{InvalidEnumError, StreamableError, TooLargeError, TruncatedError};
+use chia_traits::{register_hash_type, ChiaToPython};
 use clvm_utils::tree_hash_from_bytes;
 use clvmr::chia_dialect::{ENABLE_KECCAK, ENABLE_KECCAK_OPS_OUTSIDE_GUARD};
 use clvmr::{LIMIT_HEAP, NO_UNKNOWN_OPS};
 use pyo3::buffer::PyBuffer;
-use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::pybacked::PyBackedBytes;
 use pyo3::types::PyBytes;
+use pyo3::types::PyDict;
+use pyo3::types::PyInt;
 use pyo3::types::PyList;
 use pyo3::types::PyTuple;
+use pyo3::types::PyType;
 use pyo3::wrap_pyfunction;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter::zip;
 
 use crate::run_program::{run_chia_program, serialized_length};
 
 use chia_consensus::fast_forward::fast_forward_singleton as native_ff;
+use chia_consensus::fast_forward::supports_fast_forward as native_supports_ff;
 use chia_consensus::gen::get_puzzle_and_solution::get_puzzle_and_solution_for_coin as parse_puzzle_solution;
-use chia_consensus::gen::validation_error::ValidationErr;
+use chia_consensus::gen::validation_error::{
+    cost_error_type, signature_error_type, validation_error_type, ValidationErr,
+};
 use clvmr::allocator::NodePtr;
 use clvmr::cost::Cost;
 use clvmr::reduction::EvalErr;
@@ -74,10 +116,28 @@ use clvmr::serde::{node_from_bytes, node_from_bytes_backrefs, node_from_bytes_ba
 use clvmr::ChiaDialect;
 
 use chia_bls::{
-    hash_to_g2 as native_hash_to_g2, BlsCache, DerivableKey, GTElement, PublicKey, SecretKey,
-    Signature,
+    hash_to_g2 as native_hash_to_g2, BlsCache, BlsCacheStats, DerivableKey, GTElement, PublicKey,
+    SecretKey, Signature,
 };
 
+/// Installs a subscriber that writes the validation spans and events of the
+/// given level and above to stderr. This can only be done once per process.
+#[pyfunction]
+#[pyo3(signature = (level="debug"))]
+pub fn enable_tracing(level: &str) -> PyResult<()> {
+    use std::io::IsTerminal;
+
+    let level: tracing::Level = level
+        .parse()
+        .map_err(|_| PyValueError::new_err(format!("invalid tracing level: {level}")))?;
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .try_init()
+        .map_err(|err| PyRuntimeError::new_err(err.to_string()))
+}
+
 #[pyfunction]
 pub fn compute_merkle_set_root<'p>(
     py: Python<'p>,
@@ -112,6 +172,26 @@ pub fn confirm_not_included_already_hashed(
         .map(|r| !r)
 }
 
+#[pyfunction]
+#[pyo3(name = "verify_additions_proofs", signature = (header, coins, proofs=None))]
+pub fn py_verify_additions_proofs(
+    header: &HeaderBlock,
+    coins: Vec<(Bytes32, Vec<Coin>)>,
+    proofs: Option<Vec<(Bytes32, Bytes, Option<Bytes>)>>,
+) -> PyResult<()> {
+    Ok(verify_additions_proofs(header, &coins, proofs.as_deref())?)
+}
+
+#[pyfunction]
+#[pyo3(name = "verify_removals_proofs", signature = (header, coins, proofs=None))]
+pub fn py_verify_removals_proofs(
+    header: &HeaderBlock,
+    coins: Vec<(Bytes32, Option<Coin>)>,
+    proofs: Option<Vec<(Bytes32, Bytes)>>,
+) -> PyResult<()> {
+    Ok(verify_removals_proofs(header, &coins, proofs.as_deref())?)
+}
+
 #[pyfunction]
 pub fn tree_hash<'a>(py: Python<'a>, blob: PyBuffer<u8>) -> PyResult<Bound<'a, PyAny>> {
     let slice = py_to_slice::<'a>(blob);
@@ -154,7 +234,7 @@ pub fn get_puzzle_and_solution_for_coin<'a>(
                 &allocator,
                 result,
                 &HashSet::new(),
-                &Coin::new(find_parent, find_ph, find_amount),
+                &Coin::new(find_parent, find_ph, find_amount.into()),
             ) {
                 Err(ValidationErr(n, _)) => Err(EvalErr(n, "coin not found".to_string())),
                 Ok(pair) => Ok(pair),
@@ -352,20 +432,84 @@ impl AugSchemeMPL {
     }
 }
 
+#[pyclass]
+struct PopSchemeMPL {}
+
+#[pymethods]
+impl PopSchemeMPL {
+    #[staticmethod]
+    pub fn sign(sk: &SecretKey, msg: &[u8]) -> Signature {
+        chia_bls::pop_sign(sk, msg)
+    }
+
+    #[staticmethod]
+    pub fn aggregate(sigs: &Bound<'_, PyList>) -> PyResult<Signature> {
+        let mut ret = Signature::default();
+        for p2 in sigs {
+            ret += &p2.extract::<Signature>()?;
+        }
+        Ok(ret)
+    }
+
+    #[staticmethod]
+    pub fn verify(py: Python<'_>, pk: &PublicKey, msg: &[u8], sig: &Signature) -> bool {
+        py.allow_threads(|| chia_bls::pop_fast_aggregate_verify([pk], msg, sig))
+    }
+
+    #[staticmethod]
+    pub fn pop_prove(sk: &SecretKey) -> Signature {
+        chia_bls::pop_prove(sk)
+    }
+
+    #[staticmethod]
+    pub fn pop_verify(py: Python<'_>, pk: &PublicKey, proof: &Signature) -> bool {
+        py.allow_threads(|| chia_bls::pop_verify(pk, proof))
+    }
+
+    #[staticmethod]
+    pub fn fast_aggregate_verify(
+        py: Python<'_>,
+        pks: &Bound<'_, PyList>,
+        msg: &[u8],
+        sig: &Signature,
+    ) -> PyResult<bool> {
+        let pks = pks
+            .iter()
+            .map(|pk| pk.extract::<PublicKey>())
+            .collect::<PyResult<Vec<_>>>()?;
+
+        py.allow_threads(|| Ok(chia_bls::pop_fast_aggregate_verify(&pks, msg, sig)))
+    }
+}
+
 #[pyfunction]
-fn supports_fast_forward(spend: &CoinSpend) -> bool {
-    // the test function just attempts the rebase onto a dummy parent coin
-    let new_parent = Coin {
-        parent_coin_info: [0_u8; 32].into(),
-        puzzle_hash: spend.coin.puzzle_hash,
-        amount: spend.coin.amount,
-    };
-    let new_coin = Coin {
-        parent_coin_info: new_parent.coin_id(),
-        puzzle_hash: spend.coin.puzzle_hash,
-        amount: spend.coin.amount,
-    };
+fn generate_mnemonic() -> String {
+    chia_bls::generate_mnemonic(&mut rand::thread_rng())
+}
 
+#[pyfunction]
+fn validate_mnemonic(phrase: &str) -> PyResult<()> {
+    Ok(chia_bls::validate_mnemonic(phrase)?)
+}
+
+#[pyfunction]
+fn mnemonic_to_entropy<'p>(py: Python<'p>, phrase: &str) -> PyResult<Bound<'p, PyBytes>> {
+    Ok(PyBytes::new(py, &chia_bls::mnemonic_to_entropy(phrase)?))
+}
+
+#[pyfunction]
+fn mnemonic_from_entropy(entropy: &[u8]) -> PyResult<String> {
+    Ok(chia_bls::mnemonic_from_entropy(entropy)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (phrase, passphrase=""))]
+fn mnemonic_to_seed<'p>(py: Python<'p>, phrase: &str, passphrase: &str) -> Bound<'p, PyBytes> {
+    PyBytes::new(py, &chia_bls::mnemonic_to_seed(phrase, passphrase))
+}
+
+#[pyfunction]
+fn supports_fast_forward(spend: &CoinSpend) -> bool {
     let mut a = make_allocator(LIMIT_HEAP);
     let Ok(puzzle) = node_from_bytes(&mut a, spend.puzzle_reveal.as_slice()) else {
         return false;
@@ -374,15 +518,7 @@ fn supports_fast_forward(spend: &CoinSpend) -> bool {
         return false;
     };
 
-    native_ff(
-        &mut a,
-        puzzle,
-        solution,
-        &spend.coin,
-        &new_coin,
-        &new_parent,
-    )
-    .is_ok()
+    native_supports_ff(&mut a, puzzle, solution, &spend.coin)
 }
 
 #[pyfunction]
@@ -416,9 +552,42 @@ pub fn py_validate_clvm_and_signature(
     let (owned_conditions, additions, duration) = py
         .allow_threads(|| validate_clvm_and_signature(new_spend, max_cost, constants, peak_height))
         .map_err(|e| {
-            // cast validation error to int
-            let error_code: u32 = e.into();
-            PyErr::new::<PyTypeError, _>(error_code)
+            // the error code is the only argument, like the TypeError this
+            // used to raise
+            e.to_py_err((u32::from(e),))
+        })?;
+    Ok((owned_conditions, additions, duration.as_secs_f32()))
+}
+
+#[pyfunction]
+#[pyo3(name = "validate_serialized_clvm_and_signature")]
+#[allow(clippy::type_complexity)]
+pub fn py_validate_serialized_clvm_and_signature(
+    py: Python<'_>,
+    new_spend: PyBuffer<u8>,
+    max_cost: u64,
+    constants: &ConsensusConstants,
+    peak_height: u32,
+) -> PyResult<(OwnedSpendBundleConditions, Vec<([u8; 32], GTElement)>, f32)> {
+    // borrow the buffer for the duration of the call, unless it isn't
+    // contiguous, in which case we have to copy it
+    let copy: Vec<u8>;
+    let buf: &[u8] = if new_spend.is_c_contiguous() {
+        unsafe {
+            std::slice::from_raw_parts(new_spend.buf_ptr() as *const u8, new_spend.len_bytes())
+        }
+    } else {
+        copy = new_spend.to_vec(py)?;
+        &copy
+    };
+    let (owned_conditions, additions, duration) = py
+        .allow_threads(|| {
+            validate_serialized_clvm_and_signature(buf, max_cost, constants, peak_height)
+        })
+        .map_err(|e| {
+            // the error code is the only argument, like the TypeError this
+            // used to raise
+            e.to_py_err((u32::from(e),))
         })?;
     Ok((owned_conditions, additions, duration.as_secs_f32()))
 }
@@ -436,25 +605,439 @@ pub fn py_get_conditions_from_spendbundle(
     let mut a = make_allocator(LIMIT_HEAP);
     let conditions =
         get_conditions_from_spendbundle(&mut a, spend_bundle, max_cost, height, constants)
-            .map_err(|e| {
-                let error_code: u32 = e.1.into();
-                PyErr::new::<PyTypeError, _>(error_code)
-            })?;
+            .map_err(|e| e.1.to_py_err((u32::from(e.1),)))?;
     Ok(OwnedSpendBundleConditions::from(&a, conditions))
 }
 
+#[pyfunction]
+#[pyo3(name = "conflicts_with")]
+pub fn py_conflicts_with(
+    a: &OwnedSpendBundleConditions,
+    b: &OwnedSpendBundleConditions,
+) -> Conflict {
+    conflicts_with(a, b)
+}
+
+#[pyfunction]
+#[pyo3(name = "select_spends")]
+pub fn py_select_spends(items: Vec<MempoolCandidate>, max_cost: u64) -> Selection {
+    select_spends(&items, max_cost)
+}
+
+#[pyfunction]
+#[pyo3(name = "diff_coin_states")]
+pub fn py_diff_coin_states(old: Vec<CoinState>, new: Vec<CoinState>) -> CoinStateDelta {
+    diff_coin_states(&old, &new)
+}
+
+/// The fields that differ between two Streamable values, as a JSON list of
+/// `{"path": ..., "left": ..., "right": ...}` objects, e.g. for logging why
+/// two blocks that were expected to be identical aren't.
+#[pyfunction]
+pub fn diff_json(py: Python<'_>, a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>) -> PyResult<String> {
+    // the diff() method of a's type checks that b is the same type
+    let diffs: Vec<(String, String, String)> = a.call_method1("diff", (b,))?.extract()?;
+    let list = PyList::empty(py);
+    for (path, left, right) in diffs {
+        let entry = PyDict::new(py);
+        entry.set_item("path", path)?;
+        entry.set_item("left", left)?;
+        entry.set_item("right", right)?;
+        list.append(entry)?;
+    }
+    py.import("json")?.call_method1("dumps", (list,))?.extract()
+}
+
+#[pyfunction]
+#[pyo3(name = "compute_transactions_filter")]
+pub fn py_compute_transactions_filter(
+    py: Python<'_>,
+    additions: Vec<Coin>,
+    removals: Vec<Bytes32>,
+) -> Bound<'_, PyBytes> {
+    PyBytes::new(py, &compute_transactions_filter(&additions, &removals))
+}
+
+#[pyfunction]
+#[pyo3(name = "verify_transactions_filter")]
+pub fn py_verify_transactions_filter(
+    filter: &[u8],
+    additions: Vec<Coin>,
+    removals: Vec<Bytes32>,
+) -> PyResult<()> {
+    Ok(verify_transactions_filter(filter, &additions, &removals)?)
+}
+
+#[pyfunction]
+#[pyo3(name = "is_overflow_block")]
+pub fn py_is_overflow_block(
+    constants: &ConsensusConstants,
+    signage_point_index: u8,
+) -> PyResult<bool> {
+    Ok(is_overflow_block(constants, signage_point_index)?)
+}
+
+#[pyfunction]
+#[pyo3(name = "calculate_sp_interval_iters")]
+pub fn py_calculate_sp_interval_iters(
+    constants: &ConsensusConstants,
+    sub_slot_iters: u64,
+) -> PyResult<u64> {
+    Ok(calculate_sp_interval_iters(constants, sub_slot_iters)?)
+}
+
+#[pyfunction]
+#[pyo3(name = "calculate_sp_iters")]
+pub fn py_calculate_sp_iters(
+    constants: &ConsensusConstants,
+    sub_slot_iters: u64,
+    signage_point_index: u8,
+) -> PyResult<u64> {
+    Ok(calculate_sp_iters(
+        constants,
+        sub_slot_iters,
+        signage_point_index,
+    )?)
+}
+
+#[pyfunction]
+#[pyo3(name = "calculate_ip_iters")]
+pub fn py_calculate_ip_iters(
+    constants: &ConsensusConstants,
+    sub_slot_iters: u64,
+    signage_point_index: u8,
+    required_iters: u64,
+) -> PyResult<u64> {
+    Ok(calculate_ip_iters(
+        constants,
+        sub_slot_iters,
+        signage_point_index,
+        required_iters,
+    )?)
+}
+
+#[pyfunction]
+#[pyo3(name = "calculate_deficit")]
+#[pyo3(signature = (constants, height, prev, overflow, num_finished_sub_slots))]
+pub fn py_calculate_deficit(
+    constants: &ConsensusConstants,
+    height: u32,
+    prev: Option<BlockRecord>,
+    overflow: bool,
+    num_finished_sub_slots: usize,
+) -> PyResult<u8> {
+    Ok(calculate_deficit(
+        constants,
+        height,
+        prev.as_ref(),
+        overflow,
+        num_finished_sub_slots,
+    )?)
+}
+
+#[pyfunction]
+#[pyo3(name = "block_to_block_record")]
+#[pyo3(signature = (constants, block, required_iters, sub_slot_iters, prev, sub_epoch_summary))]
+pub fn py_block_to_block_record(
+    constants: &ConsensusConstants,
+    block: &FullBlock,
+    required_iters: u64,
+    sub_slot_iters: u64,
+    prev: Option<BlockRecord>,
+    sub_epoch_summary: Option<SubEpochSummary>,
+) -> PyResult<BlockRecord> {
+    Ok(block_to_block_record(
+        constants,
+        block,
+        required_iters,
+        sub_slot_iters,
+        prev.as_ref(),
+        sub_epoch_summary,
+    )?)
+}
+
+#[pyfunction]
+#[pyo3(name = "calculate_iterations_quality")]
+pub fn py_calculate_iterations_quality(
+    difficulty_constant_factor: u128,
+    quality_string: Bytes32,
+    size: u8,
+    difficulty: u64,
+    cc_sp_output_hash: Bytes32,
+) -> PyResult<u64> {
+    Ok(calculate_iterations_quality(
+        difficulty_constant_factor,
+        &quality_string,
+        size,
+        difficulty,
+        &cc_sp_output_hash,
+    )?)
+}
+
+#[pyfunction]
+#[pyo3(name = "is_valid_partial", signature = (constants, quality_string, size, difficulty, cc_sp_output_hash, sub_slot_iters=None))]
+pub fn py_is_valid_partial(
+    constants: &ConsensusConstants,
+    quality_string: Bytes32,
+    size: u8,
+    difficulty: u64,
+    cc_sp_output_hash: Bytes32,
+    sub_slot_iters: Option<u64>,
+) -> PyResult<bool> {
+    Ok(is_valid_partial(
+        constants,
+        &quality_string,
+        size,
+        difficulty,
+        &cc_sp_output_hash,
+        sub_slot_iters.unwrap_or(constants.pool_sub_slot_iters),
+    )?)
+}
+
+#[pyfunction]
+#[pyo3(name = "max_partial_difficulty", signature = (constants, quality_string, size, cc_sp_output_hash, sub_slot_iters=None))]
+pub fn py_max_partial_difficulty(
+    constants: &ConsensusConstants,
+    quality_string: Bytes32,
+    size: u8,
+    cc_sp_output_hash: Bytes32,
+    sub_slot_iters: Option<u64>,
+) -> PyResult<u64> {
+    Ok(max_partial_difficulty(
+        constants,
+        &quality_string,
+        size,
+        &cc_sp_output_hash,
+        sub_slot_iters.unwrap_or(constants.pool_sub_slot_iters),
+    )?)
+}
+
+#[pyfunction]
+#[pyo3(name = "calculate_prefix_bits")]
+pub fn py_calculate_prefix_bits(constants: &ConsensusConstants, height: u32) -> u8 {
+    calculate_prefix_bits(constants, height)
+}
+
+#[pyfunction]
+#[pyo3(name = "passes_plot_filter")]
+pub fn py_passes_plot_filter(
+    constants: &ConsensusConstants,
+    height: u32,
+    plot_id: Bytes32,
+    challenge_hash: Bytes32,
+    signage_point: Bytes32,
+) -> bool {
+    passes_plot_filter(constants, height, &plot_id, &challenge_hash, &signage_point)
+}
+
+#[pyfunction]
+#[pyo3(name = "calculate_plot_id_pk")]
+pub fn py_calculate_plot_id_pk(pool_pk: &PublicKey, plot_pk: &PublicKey) -> Bytes32 {
+    calculate_plot_id_pk(pool_pk, plot_pk)
+}
+
+#[pyfunction]
+#[pyo3(name = "calculate_plot_id_ph")]
+pub fn py_calculate_plot_id_ph(pool_contract_ph: Bytes32, plot_pk: &PublicKey) -> Bytes32 {
+    calculate_plot_id_ph(&pool_contract_ph, plot_pk)
+}
+
+#[pyfunction]
+#[pyo3(name = "generate_plot_public_key", signature = (local_pk, farmer_pk, include_taproot=false))]
+pub fn py_generate_plot_public_key(
+    local_pk: &PublicKey,
+    farmer_pk: &PublicKey,
+    include_taproot: bool,
+) -> PublicKey {
+    generate_plot_public_key(local_pk, farmer_pk, include_taproot)
+}
+
 #[pyfunction]
 #[pyo3(name = "get_flags_for_height_and_constants")]
 pub fn py_get_flags_for_height_and_constants(height: u32, constants: &ConsensusConstants) -> u32 {
     get_flags_for_height_and_constants(height, constants)
 }
 
+#[pyfunction]
+#[pyo3(name = "calculate_pool_reward")]
+pub fn py_calculate_pool_reward(height: u32) -> u64 {
+    calculate_pool_reward(height)
+}
+
+#[pyfunction]
+#[pyo3(name = "calculate_base_farmer_reward")]
+pub fn py_calculate_base_farmer_reward(height: u32) -> u64 {
+    calculate_base_farmer_reward(height)
+}
+
+#[pyfunction]
+#[pyo3(name = "expected_reward_coins")]
+pub fn py_expected_reward_coins(
+    height: u32,
+    pool_puzzle_hash: Bytes32,
+    farmer_puzzle_hash: Bytes32,
+    fees: u64,
+    genesis_challenge: Bytes32,
+) -> Vec<Coin> {
+    expected_reward_coins(
+        height,
+        pool_puzzle_hash,
+        farmer_puzzle_hash,
+        fees,
+        &genesis_challenge,
+    )
+}
+
+#[pyfunction]
+#[pyo3(name = "validate_reward_coins")]
+pub fn py_validate_reward_coins(claimed: Vec<Coin>, expected: Vec<Coin>) -> PyResult<()> {
+    Ok(validate_reward_coins(&claimed, &expected)?)
+}
+
+#[pyfunction]
+#[pyo3(name = "compute_block_fees")]
+pub fn py_compute_block_fees(conds: &OwnedSpendBundleConditions) -> PyResult<u64> {
+    Ok(compute_block_fees(
+        conds.removal_amount,
+        conds.addition_amount,
+        conds.reserve_fee,
+    )?)
+}
+
+#[pyfunction]
+#[pyo3(name = "validate_block_fees")]
+pub fn py_validate_block_fees(
+    conds: &OwnedSpendBundleConditions,
+    claimed_fees: u64,
+) -> PyResult<()> {
+    Ok(validate_block_fees(
+        conds.removal_amount,
+        conds.addition_amount,
+        conds.reserve_fee,
+        claimed_fees,
+    )?)
+}
+
+#[pyfunction]
+#[pyo3(name = "satisfiable_at")]
+pub fn py_satisfiable_at(
+    conds: &OwnedSpendBundleConditions,
+    block_height: u32,
+    block_timestamp: u64,
+    coin_confirm_info: HashMap<Bytes32, (u32, u64)>,
+) -> PyResult<()> {
+    Ok(satisfiable_at(
+        conds,
+        block_height,
+        block_timestamp,
+        &coin_confirm_info,
+    )?)
+}
+
+#[pyfunction]
+#[pyo3(name = "compress_with_dict", signature = (data, dict_id=None))]
+pub fn py_compress_with_dict<'p>(
+    py: Python<'p>,
+    data: &[u8],
+    dict_id: Option<u8>,
+) -> PyResult<Bound<'p, PyBytes>> {
+    let dict = dict_id
+        .map(|id| {
+            KnownDict::from_id(id).ok_or_else(|| {
+                PyValueError::new_err(format!("unknown compression dictionary {id}"))
+            })
+        })
+        .transpose()?;
+    let compressed = py.allow_threads(|| compress_with_dict(data, dict));
+    Ok(PyBytes::new(py, &compressed))
+}
+
+#[pyfunction]
+#[pyo3(name = "decompress_with_dict")]
+pub fn py_decompress_with_dict<'p>(
+    py: Python<'p>,
+    data: &[u8],
+    max_decompressed_size: usize,
+) -> PyResult<Bound<'p, PyBytes>> {
+    let decompressed = py
+        .allow_threads(|| decompress_with_dict(data, max_decompressed_size))
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(PyBytes::new(py, &decompressed))
+}
+
+#[pyfunction]
+#[pyo3(name = "calculate_synthetic_offset")]
+pub fn py_calculate_synthetic_offset<'p>(
+    py: Python<'p>,
+    public_key: &PublicKey,
+    hidden_puzzle_hash: Bytes32,
+) -> PyResult<Bound<'p, PyAny>> {
+    // the offset is returned as an int, like chia-blockchain does
+    let offset = calculate_synthetic_offset(public_key, &hidden_puzzle_hash.to_bytes());
+    py.get_type::<PyInt>()
+        .call_method1("from_bytes", (PyBytes::new(py, &offset.to_bytes()), "big"))
+}
+
+#[pyfunction]
+#[pyo3(name = "calculate_synthetic_public_key")]
+pub fn py_calculate_synthetic_public_key(
+    public_key: &PublicKey,
+    hidden_puzzle_hash: Bytes32,
+) -> PublicKey {
+    public_key.derive_synthetic_hidden(&hidden_puzzle_hash.to_bytes())
+}
+
+#[pyfunction]
+#[pyo3(name = "calculate_synthetic_secret_key")]
+pub fn py_calculate_synthetic_secret_key(
+    secret_key: &SecretKey,
+    hidden_puzzle_hash: Bytes32,
+) -> SecretKey {
+    calculate_synthetic_secret_key(secret_key, &hidden_puzzle_hash.to_bytes())
+}
+
+#[pyfunction]
+#[pyo3(name = "is_synthetic_of")]
+pub fn py_is_synthetic_of(
+    synthetic_key: &PublicKey,
+    original_key: &PublicKey,
+    hidden_puzzle_hash: Bytes32,
+) -> bool {
+    is_synthetic_of(synthetic_key, original_key, &hidden_puzzle_hash.to_bytes())
+}
+
 #[pymodule]
-pub fn chia_rs(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+pub fn chia_rs(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // get_hash() returns hashes as bytes32. When this extension module is
+    // loaded without the chia_rs package around it, that type isn't available
+    // and they are returned as bytes instead
+    if let Ok(bytes32) = PyModule::import(py, "chia_rs.sized_bytes")
+        .and_then(|sized_bytes| sized_bytes.getattr("bytes32"))
+    {
+        register_hash_type(py, bytes32.downcast::<PyType>()?);
+    }
+
     // generator functions
     m.add_function(wrap_pyfunction!(run_block_generator, m)?)?;
     m.add_function(wrap_pyfunction!(run_block_generator2, m)?)?;
+    m.add_function(wrap_pyfunction!(run_block_generator_for_height, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        run_block_generator_for_height_with_metrics,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(run_block_generator_for_height_cached, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        run_block_generator_for_height_cancellable,
+        m
+    )?)?;
+    m.add_class::<CancellationToken>()?;
+    m.add_class::<ConditionsCache>()?;
+    m.add_class::<ConditionsCacheStats>()?;
     m.add_function(wrap_pyfunction!(additions_and_removals, m)?)?;
+    m.add_function(wrap_pyfunction!(additions_and_removals_filtered, m)?)?;
+    m.add_function(wrap_pyfunction!(block_coin_report, m)?)?;
+    m.add_class::<BlockCoinReport>()?;
+    m.add_class::<CoinAddition>()?;
     m.add_function(wrap_pyfunction!(solution_generator, m)?)?;
     m.add_function(wrap_pyfunction!(solution_generator_backrefs, m)?)?;
     m.add_function(wrap_pyfunction!(supports_fast_forward, m)?)?;
@@ -468,27 +1051,85 @@ pub fn chia_rs(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
         "ELIGIBLE_FOR_FF",
         chia_consensus::gen::conditions::ELIGIBLE_FOR_FF,
     )?;
+    m.add(
+        "HAS_RELATIVE_CONDITION",
+        chia_consensus::gen::conditions::HAS_RELATIVE_CONDITION,
+    )?;
     m.add_class::<OwnedSpendConditions>()?;
+    m.add_class::<Conflict>()?;
+    m.add_class::<AllocatorMetrics>()?;
+    m.add_class::<MempoolCandidate>()?;
+    m.add_class::<Selection>()?;
+    m.add_function(wrap_pyfunction!(py_conflicts_with, m)?)?;
+    m.add_function(wrap_pyfunction!(py_select_spends, m)?)?;
+    m.add_function(wrap_pyfunction!(py_diff_coin_states, m)?)?;
+    m.add_function(wrap_pyfunction!(diff_json, m)?)?;
 
     // constants
     m.add_class::<ConsensusConstants>()?;
 
+    // simulator
+    m.add_class::<Simulator>()?;
+
     // merkle tree
     m.add_class::<MerkleSet>()?;
+    m.add_class::<MerkleTree>()?;
+    m.add_class::<MerkleProof>()?;
     m.add_function(wrap_pyfunction!(confirm_included_already_hashed, m)?)?;
     m.add_function(wrap_pyfunction!(confirm_not_included_already_hashed, m)?)?;
+    m.add_function(wrap_pyfunction!(py_verify_additions_proofs, m)?)?;
+    m.add_function(wrap_pyfunction!(py_verify_removals_proofs, m)?)?;
 
     // spendbundle validation
     m.add_function(wrap_pyfunction!(py_validate_clvm_and_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        py_validate_serialized_clvm_and_signature,
+        m
+    )?)?;
     m.add_function(wrap_pyfunction!(py_get_conditions_from_spendbundle, m)?)?;
     m.add_function(wrap_pyfunction!(py_get_flags_for_height_and_constants, m)?)?;
 
+    // block rewards and fees
+    m.add_function(wrap_pyfunction!(py_calculate_pool_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(py_calculate_base_farmer_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(py_expected_reward_coins, m)?)?;
+    m.add_function(wrap_pyfunction!(py_validate_reward_coins, m)?)?;
+    m.add_function(wrap_pyfunction!(py_compute_block_fees, m)?)?;
+    m.add_function(wrap_pyfunction!(py_validate_block_fees, m)?)?;
+    m.add_function(wrap_pyfunction!(py_satisfiable_at, m)?)?;
+
+    // synthetic keys of the standard puzzle
+    m.add_function(wrap_pyfunction!(py_calculate_synthetic_offset, m)?)?;
+    m.add_function(wrap_pyfunction!(py_calculate_synthetic_public_key, m)?)?;
+    m.add_function(wrap_pyfunction!(py_calculate_synthetic_secret_key, m)?)?;
+    m.add_function(wrap_pyfunction!(py_is_synthetic_of, m)?)?;
+
+    // wire compression
+    m.add_function(wrap_pyfunction!(py_compress_with_dict, m)?)?;
+    m.add_function(wrap_pyfunction!(py_decompress_with_dict, m)?)?;
+    m.add(
+        "KNOWN_DICT_STANDARD_PUZZLES",
+        KnownDict::StandardPuzzles.id(),
+    )?;
+
+    // validation errors
+    m.add("ValidationError", validation_error_type(py))?;
+    m.add("CostError", cost_error_type(py))?;
+    m.add("SignatureError", signature_error_type(py))?;
+
+    // streamable errors
+    m.add("StreamableError", py.get_type::<StreamableError>())?;
+    m.add("TruncatedError", py.get_type::<TruncatedError>())?;
+    m.add("InvalidEnumError", py.get_type::<InvalidEnumError>())?;
+    m.add("TooLargeError", py.get_type::<TooLargeError>())?;
+
     // clvm functions
     m.add("NO_UNKNOWN_CONDS", NO_UNKNOWN_CONDS)?;
     m.add("STRICT_ARGS_COUNT", STRICT_ARGS_COUNT)?;
     m.add("MEMPOOL_MODE", MEMPOOL_MODE)?;
     m.add("ALLOW_BACKREFS", ALLOW_BACKREFS)?;
     m.add("DONT_VALIDATE_SIGNATURE", DONT_VALIDATE_SIGNATURE)?;
+    m.add("CHECK_DUPLICATE_COIN_IDS", CHECK_DUPLICATE_COIN_IDS)?;
 
     // Chia classes
     m.add_class::<Coin>()?;
@@ -508,6 +1149,7 @@ pub fn chia_rs(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<RewardChainSubSlot>()?;
     m.add_class::<SubSlotProofs>()?;
     m.add_class::<SpendBundle>()?;
+    m.add_class::<CostEstimate>()?;
     m.add_class::<Program>()?;
     m.add_class::<CoinSpend>()?;
     m.add_class::<VDFInfo>()?;
@@ -518,11 +1160,13 @@ pub fn chia_rs(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<SubEpochSegments>()?;
     m.add_class::<SubEpochSummary>()?;
     m.add_class::<UnfinishedBlock>()?;
+    m.add_class::<InfusionPoint>()?;
     m.add_class::<FullBlock>()?;
     m.add_class::<BlockRecord>()?;
     m.add_class::<WeightProof>()?;
     m.add_class::<RecentChainData>()?;
     m.add_class::<ProofBlockHeader>()?;
+    m.add_class::<PeerInfo>()?;
     m.add_class::<TimestampedPeerInfo>()?;
 
     // wallet protocol
@@ -551,6 +1195,7 @@ pub fn chia_rs(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<HeaderBlock>()?;
     m.add_class::<UnfinishedHeaderBlock>()?;
     m.add_class::<CoinState>()?;
+    m.add_class::<CoinStateDelta>()?;
     m.add_class::<RegisterForPhUpdates>()?;
     m.add_class::<RespondToPhUpdates>()?;
     m.add_class::<RegisterForCoinUpdates>()?;
@@ -608,11 +1253,20 @@ pub fn chia_rs(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<NewUnfinishedBlock2>()?;
     m.add_class::<RequestUnfinishedBlock2>()?;
     m.add_class::<Handshake>()?;
+    m.add_class::<ProtocolError>()?;
     m.add_class::<FeeEstimate>()?;
     m.add_class::<FeeEstimateGroup>()?;
     m.add_class::<FeeRate>()?;
     m.add_class::<LazyNode>()?;
     m.add_class::<Message>()?;
+    // only used to test the bindings of streamable tuple structs
+    m.add_class::<TupleStreamableTest>()?;
+    // only used to test the JSON conversion of #[streamable(json_map)] fields
+    m.add_class::<JsonMapStreamableTest>()?;
+
+    // introducer protocol
+    m.add_class::<RequestPeersIntroducer>()?;
+    m.add_class::<RespondPeersIntroducer>()?;
 
     // facilities from clvm_rs
 
@@ -627,9 +1281,28 @@ pub fn chia_rs(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     m.add_function(wrap_pyfunction!(serialized_length, m)?)?;
     m.add_function(wrap_pyfunction!(compute_merkle_set_root, m)?)?;
+    m.add_function(wrap_pyfunction!(py_compute_transactions_filter, m)?)?;
+    m.add_function(wrap_pyfunction!(py_verify_transactions_filter, m)?)?;
+
+    // pot iterations and the plot filter
+    m.add_function(wrap_pyfunction!(py_is_overflow_block, m)?)?;
+    m.add_function(wrap_pyfunction!(py_calculate_sp_interval_iters, m)?)?;
+    m.add_function(wrap_pyfunction!(py_calculate_sp_iters, m)?)?;
+    m.add_function(wrap_pyfunction!(py_calculate_ip_iters, m)?)?;
+    m.add_function(wrap_pyfunction!(py_calculate_iterations_quality, m)?)?;
+    m.add_function(wrap_pyfunction!(py_calculate_deficit, m)?)?;
+    m.add_function(wrap_pyfunction!(py_block_to_block_record, m)?)?;
+    m.add_function(wrap_pyfunction!(py_is_valid_partial, m)?)?;
+    m.add_function(wrap_pyfunction!(py_max_partial_difficulty, m)?)?;
+    m.add_function(wrap_pyfunction!(py_calculate_prefix_bits, m)?)?;
+    m.add_function(wrap_pyfunction!(py_passes_plot_filter, m)?)?;
+    m.add_function(wrap_pyfunction!(py_calculate_plot_id_pk, m)?)?;
+    m.add_function(wrap_pyfunction!(py_calculate_plot_id_ph, m)?)?;
+    m.add_function(wrap_pyfunction!(py_generate_plot_public_key, m)?)?;
     m.add_function(wrap_pyfunction!(tree_hash, m)?)?;
     m.add_function(wrap_pyfunction!(get_puzzle_and_solution_for_coin, m)?)?;
     m.add_function(wrap_pyfunction!(get_puzzle_and_solution_for_coin2, m)?)?;
+    m.add_function(wrap_pyfunction!(enable_tracing, m)?)?;
 
     // facilities from chia-bls
 
@@ -638,7 +1311,14 @@ pub fn chia_rs(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<GTElement>()?;
     m.add_class::<SecretKey>()?;
     m.add_class::<AugSchemeMPL>()?;
+    m.add_class::<PopSchemeMPL>()?;
     m.add_class::<BlsCache>()?;
+    m.add_class::<BlsCacheStats>()?;
+    m.add_function(wrap_pyfunction!(generate_mnemonic, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_mnemonic, m)?)?;
+    m.add_function(wrap_pyfunction!(mnemonic_to_entropy, m)?)?;
+    m.add_function(wrap_pyfunction!(mnemonic_from_entropy, m)?)?;
+    m.add_function(wrap_pyfunction!(mnemonic_to_seed, m)?)?;
 
     Ok(())
 }
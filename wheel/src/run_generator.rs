@@ -1,15 +1,25 @@
 use chia_bls::{BlsCache, Signature};
-use chia_consensus::allocator::make_allocator;
+use chia_consensus::allocator::{make_allocator, AllocatorMetrics};
 use chia_consensus::consensus_constants::ConsensusConstants;
 use chia_consensus::gen::additions_and_removals::additions_and_removals as native_additions_and_removals;
+use chia_consensus::gen::additions_and_removals::additions_and_removals_filtered as native_additions_and_removals_filtered;
+use chia_consensus::gen::additions_and_removals::block_coin_report as native_block_coin_report;
+use chia_consensus::gen::additions_and_removals::BlockCoinReport;
+use chia_consensus::gen::cancellation::CancellationToken;
+use chia_consensus::gen::conditions_cache::{get_or_run, ConditionsCache};
 use chia_consensus::gen::owned_conditions::OwnedSpendBundleConditions;
 use chia_consensus::gen::run_block_generator::run_block_generator as native_run_block_generator;
 use chia_consensus::gen::run_block_generator::run_block_generator2 as native_run_block_generator2;
+use chia_consensus::gen::run_block_generator::run_block_generator_for_height as native_run_block_generator_for_height;
+use chia_consensus::gen::run_block_generator::run_block_generator_for_height_cancellable as native_run_block_generator_for_height_cancellable;
+use chia_consensus::gen::run_block_generator::run_block_generator_for_height_with_metrics as native_run_block_generator_for_height_with_metrics;
 use chia_consensus::gen::validation_error::ValidationErr;
 use chia_protocol::Bytes;
+use chia_protocol::Bytes32;
 use chia_protocol::Coin;
 
 use clvmr::cost::Cost;
+use std::collections::HashSet;
 
 use pyo3::buffer::PyBuffer;
 use pyo3::prelude::*;
@@ -126,6 +136,233 @@ pub fn run_block_generator2<'a>(
     })
 }
 
+#[pyfunction]
+#[pyo3(signature = (program, block_refs, max_cost, flags, height, signature, bls_cache, constants))]
+#[allow(clippy::too_many_arguments)]
+pub fn run_block_generator_for_height<'a>(
+    py: Python<'a>,
+    program: PyBuffer<u8>,
+    block_refs: &Bound<'_, PyList>,
+    max_cost: Cost,
+    flags: u32,
+    height: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+) -> (Option<u32>, Option<OwnedSpendBundleConditions>) {
+    let mut allocator = make_allocator(flags);
+
+    let refs = block_refs
+        .into_iter()
+        .map(|b| {
+            let buf = b
+                .extract::<PyBuffer<u8>>()
+                .expect("block_refs must be list of buffers");
+            py_to_slice::<'a>(buf)
+        })
+        .collect::<Vec<&'a [u8]>>();
+
+    let program = py_to_slice::<'a>(program);
+
+    py.allow_threads(|| {
+        match native_run_block_generator_for_height(
+            &mut allocator,
+            program,
+            refs,
+            max_cost,
+            flags,
+            height,
+            signature,
+            bls_cache,
+            constants,
+        ) {
+            Ok(spend_bundle_conds) => (
+                None,
+                Some(OwnedSpendBundleConditions::from(
+                    &allocator,
+                    spend_bundle_conds,
+                )),
+            ),
+            Err(ValidationErr(_, error_code)) => {
+                // a validation error occurred
+                (Some(error_code.into()), None)
+            }
+        }
+    })
+}
+
+// Like run_block_generator_for_height(), but calling cancel() on the token
+// (from another thread) aborts the run, which then fails with the Cancelled
+// error code. The GIL is released while the generator runs.
+#[pyfunction]
+#[pyo3(signature = (program, block_refs, max_cost, flags, height, signature, bls_cache, constants, cancellation_token))]
+#[allow(clippy::too_many_arguments)]
+pub fn run_block_generator_for_height_cancellable<'a>(
+    py: Python<'a>,
+    program: PyBuffer<u8>,
+    block_refs: &Bound<'_, PyList>,
+    max_cost: Cost,
+    flags: u32,
+    height: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+    cancellation_token: &CancellationToken,
+) -> (Option<u32>, Option<OwnedSpendBundleConditions>) {
+    let mut allocator = make_allocator(flags);
+
+    let refs = block_refs
+        .into_iter()
+        .map(|b| {
+            let buf = b
+                .extract::<PyBuffer<u8>>()
+                .expect("block_refs must be list of buffers");
+            py_to_slice::<'a>(buf)
+        })
+        .collect::<Vec<&'a [u8]>>();
+
+    let program = py_to_slice::<'a>(program);
+    let token = cancellation_token.clone();
+
+    py.allow_threads(|| {
+        match native_run_block_generator_for_height_cancellable(
+            &mut allocator,
+            program,
+            refs,
+            max_cost,
+            flags,
+            height,
+            signature,
+            bls_cache,
+            constants,
+            &token,
+        ) {
+            Ok(spend_bundle_conds) => (
+                None,
+                Some(OwnedSpendBundleConditions::from(
+                    &allocator,
+                    spend_bundle_conds,
+                )),
+            ),
+            Err(ValidationErr(_, error_code)) => {
+                // a validation error occurred
+                (Some(error_code.into()), None)
+            }
+        }
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (program, block_refs, max_cost, flags, height, signature, bls_cache, constants))]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn run_block_generator_for_height_with_metrics<'a>(
+    py: Python<'a>,
+    program: PyBuffer<u8>,
+    block_refs: &Bound<'_, PyList>,
+    max_cost: Cost,
+    flags: u32,
+    height: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    constants: &ConsensusConstants,
+) -> (
+    Option<u32>,
+    Option<OwnedSpendBundleConditions>,
+    Option<AllocatorMetrics>,
+) {
+    let mut allocator = make_allocator(flags);
+    let refs = block_refs
+        .into_iter()
+        .map(|b| {
+            let buf = b
+                .extract::<PyBuffer<u8>>()
+                .expect("block_refs must be list of buffers");
+            py_to_slice::<'a>(buf)
+        })
+        .collect::<Vec<&'a [u8]>>();
+
+    let program = py_to_slice::<'a>(program);
+
+    py.allow_threads(|| {
+        match native_run_block_generator_for_height_with_metrics(
+            &mut allocator,
+            program,
+            refs,
+            max_cost,
+            flags,
+            height,
+            signature,
+            bls_cache,
+            constants,
+        ) {
+            Ok((spend_bundle_conds, metrics)) => (
+                None,
+                Some(OwnedSpendBundleConditions::from(
+                    &allocator,
+                    spend_bundle_conds,
+                )),
+                Some(metrics),
+            ),
+            Err(ValidationErr(_, error_code)) => {
+                // a validation error occurred
+                (Some(error_code.into()), None, None)
+            }
+        }
+    })
+}
+
+// Like run_block_generator_for_height(), but the result is looked up in, and
+// added to, the conditions cache
+#[pyfunction]
+#[pyo3(signature = (program, block_refs, max_cost, flags, height, signature, bls_cache, conditions_cache, constants))]
+#[allow(clippy::too_many_arguments)]
+pub fn run_block_generator_for_height_cached<'a>(
+    py: Python<'a>,
+    program: PyBuffer<u8>,
+    block_refs: &Bound<'_, PyList>,
+    max_cost: Cost,
+    flags: u32,
+    height: u32,
+    signature: &Signature,
+    bls_cache: Option<&BlsCache>,
+    conditions_cache: &ConditionsCache,
+    constants: &ConsensusConstants,
+) -> (Option<u32>, Option<OwnedSpendBundleConditions>) {
+    let mut allocator = make_allocator(flags);
+    let refs = block_refs
+        .into_iter()
+        .map(|b| {
+            let buf = b
+                .extract::<PyBuffer<u8>>()
+                .expect("block_refs must be list of buffers");
+            py_to_slice::<'a>(buf)
+        })
+        .collect::<Vec<&'a [u8]>>();
+
+    let program = py_to_slice::<'a>(program);
+
+    py.allow_threads(|| {
+        match get_or_run(
+            conditions_cache,
+            &mut allocator,
+            program,
+            &refs,
+            max_cost,
+            flags,
+            height,
+            signature,
+            bls_cache,
+            constants,
+        ) {
+            Ok(conditions) => (None, Some(conditions)),
+            Err(ValidationErr(_, error_code)) => {
+                // a validation error occurred
+                (Some(error_code.into()), None)
+            }
+        }
+    })
+}
+
 #[pyfunction]
 #[allow(clippy::type_complexity)]
 pub fn additions_and_removals<'a>(
@@ -147,13 +384,84 @@ pub fn additions_and_removals<'a>(
 
     let program = py_to_slice::<'a>(program);
 
-    py.allow_threads(|| {
-        native_additions_and_removals(program, refs, flags, constants).map_err(|e| {
-            // a validation error occurred
-            pyo3::exceptions::PyValueError::new_err(format!(
-                "additions_and_removals() failed: {}",
-                e.1 as u16
-            ))
+    py.allow_threads(|| native_additions_and_removals(program, refs, flags, constants))
+        .map_err(|e| {
+            // a validation error occurred. The message is the same as the
+            // ValueError this used to raise
+            e.1.to_py_err(format!("additions_and_removals() failed: {}", e.1 as u16))
         })
-    })
+}
+
+#[pyfunction]
+#[allow(clippy::type_complexity)]
+pub fn additions_and_removals_filtered<'a>(
+    py: Python<'a>,
+    program: PyBuffer<u8>,
+    block_refs: &Bound<'_, PyList>,
+    flags: u32,
+    constants: &ConsensusConstants,
+    watch_puzzle_hashes: HashSet<Bytes32>,
+    watch_hints: HashSet<Bytes32>,
+) -> PyResult<(Vec<(Coin, Option<Bytes>)>, Vec<Coin>, u64)> {
+    let refs = block_refs
+        .into_iter()
+        .map(|b| {
+            let buf = b
+                .extract::<PyBuffer<u8>>()
+                .expect("block_refs must be list of buffers");
+            py_to_slice::<'a>(buf)
+        })
+        .collect::<Vec<&'a [u8]>>();
+
+    let program = py_to_slice::<'a>(program);
+
+    // a validation error is raised as a ValidationError exception
+    Ok(py.allow_threads(|| {
+        native_additions_and_removals_filtered(
+            program,
+            refs,
+            flags,
+            constants,
+            &watch_puzzle_hashes,
+            &watch_hints,
+        )
+    })?)
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+pub fn block_coin_report<'a>(
+    py: Python<'a>,
+    program: PyBuffer<u8>,
+    block_refs: &Bound<'_, PyList>,
+    flags: u32,
+    height: u32,
+    farmer_puzzle_hash: Bytes32,
+    pool_puzzle_hash: Bytes32,
+    constants: &ConsensusConstants,
+) -> PyResult<BlockCoinReport> {
+    let refs = block_refs
+        .into_iter()
+        .map(|b| {
+            let buf = b
+                .extract::<PyBuffer<u8>>()
+                .expect("block_refs must be list of buffers");
+            py_to_slice::<'a>(buf)
+        })
+        .collect::<Vec<&'a [u8]>>();
+
+    let program = py_to_slice::<'a>(program);
+
+    // a validation error is raised as a ValidationError exception
+    Ok(py.allow_threads(|| {
+        native_block_coin_report(
+            program,
+            refs,
+            flags,
+            height,
+            farmer_puzzle_hash,
+            pool_puzzle_hash,
+            constants,
+        )
+    })?)
 }